@@ -0,0 +1,515 @@
+//! Analog Comparator peripheral.
+//!
+//! The BL808 has two analog comparators placed in the always-on domain. Each comparator
+//! compares a positive input channel with either another channel or an internal reference
+//! level, and its output can raise a hibernate interrupt on crossing. Hibernate interrupts
+//! are wakeup sources, so a comparator can bring the chip out of HBN mode, e.g. when the
+//! battery voltage falls below a threshold.
+
+use crate::hbn;
+use core::ops::Deref;
+use volatile_register::RW;
+
+/// Analog comparator registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Comparator configuration registers.
+    pub config: [RW<AcompConfig>; 2],
+    /// Comparator control and output state register.
+    pub control: RW<AcompControl>,
+}
+
+/// Analog comparator configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct AcompConfig(u32);
+
+impl AcompConfig {
+    const ENABLE: u32 = 1 << 0;
+    const HYSTERESIS_NEGATIVE: u32 = 0x7 << 4;
+    const HYSTERESIS_POSITIVE: u32 = 0x7 << 7;
+    const BIAS: u32 = 0x3 << 10;
+    const LEVEL: u32 = 0x3f << 12;
+    const NEGATIVE_INPUT: u32 = 0xf << 18;
+    const POSITIVE_INPUT: u32 = 0xf << 22;
+    const MUX: u32 = 1 << 26;
+
+    /// Enable comparator.
+    #[inline]
+    pub const fn enable_comparator(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable comparator.
+    #[inline]
+    pub const fn disable_comparator(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if comparator is enabled.
+    #[inline]
+    pub const fn is_comparator_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Set hysteresis level on negative input.
+    #[inline]
+    pub const fn set_negative_hysteresis(self, val: Hysteresis) -> Self {
+        Self((self.0 & !Self::HYSTERESIS_NEGATIVE) | ((val as u32) << 4))
+    }
+    /// Get hysteresis level on negative input.
+    #[inline]
+    pub const fn negative_hysteresis(self) -> Hysteresis {
+        Hysteresis::from_bits((self.0 & Self::HYSTERESIS_NEGATIVE) >> 4)
+    }
+    /// Set hysteresis level on positive input.
+    #[inline]
+    pub const fn set_positive_hysteresis(self, val: Hysteresis) -> Self {
+        Self((self.0 & !Self::HYSTERESIS_POSITIVE) | ((val as u32) << 7))
+    }
+    /// Get hysteresis level on positive input.
+    #[inline]
+    pub const fn positive_hysteresis(self) -> Hysteresis {
+        Hysteresis::from_bits((self.0 & Self::HYSTERESIS_POSITIVE) >> 7)
+    }
+    /// Set bias current.
+    #[inline]
+    pub const fn set_bias(self, val: Bias) -> Self {
+        Self((self.0 & !Self::BIAS) | ((val as u32) << 10))
+    }
+    /// Get bias current.
+    #[inline]
+    pub const fn bias(self) -> Bias {
+        match (self.0 & Self::BIAS) >> 10 {
+            0 => Bias::Low,
+            1 => Bias::Medium,
+            2 => Bias::High,
+            3 => Bias::Highest,
+            _ => unreachable!(),
+        }
+    }
+    /// Set internal reference level.
+    ///
+    /// The reference voltage is `level / 64` of the selected reference source.
+    #[inline]
+    pub const fn set_level(self, val: u8) -> Self {
+        Self((self.0 & !Self::LEVEL) | (((val as u32) << 12) & Self::LEVEL))
+    }
+    /// Get internal reference level.
+    #[inline]
+    pub const fn level(self) -> u8 {
+        ((self.0 & Self::LEVEL) >> 12) as u8
+    }
+    /// Set negative input channel.
+    #[inline]
+    pub const fn set_negative_input(self, val: Channel) -> Self {
+        Self((self.0 & !Self::NEGATIVE_INPUT) | ((val as u32) << 18))
+    }
+    /// Get negative input channel.
+    #[inline]
+    pub const fn negative_input(self) -> Channel {
+        Channel::from_bits((self.0 & Self::NEGATIVE_INPUT) >> 18)
+    }
+    /// Set positive input channel.
+    #[inline]
+    pub const fn set_positive_input(self, val: Channel) -> Self {
+        Self((self.0 & !Self::POSITIVE_INPUT) | ((val as u32) << 22))
+    }
+    /// Get positive input channel.
+    #[inline]
+    pub const fn positive_input(self) -> Channel {
+        Channel::from_bits((self.0 & Self::POSITIVE_INPUT) >> 22)
+    }
+    /// Enable input multiplexer.
+    #[inline]
+    pub const fn enable_mux(self) -> Self {
+        Self(self.0 | Self::MUX)
+    }
+    /// Disable input multiplexer.
+    #[inline]
+    pub const fn disable_mux(self) -> Self {
+        Self(self.0 & !Self::MUX)
+    }
+    /// Check if input multiplexer is enabled.
+    #[inline]
+    pub const fn is_mux_enabled(self) -> bool {
+        self.0 & Self::MUX != 0
+    }
+}
+
+/// Analog comparator control and output state register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct AcompControl(u32);
+
+impl AcompControl {
+    const RESET_N: [u32; 2] = [1 << 1, 1 << 0];
+    const OUTPUT: [u32; 2] = [1 << 19, 1 << 17];
+    const REFERENCE: u32 = 0x3f << 24;
+
+    /// Release analog reset of comparator `I`.
+    #[inline]
+    pub const fn release_reset<const I: usize>(self) -> Self {
+        Self(self.0 | Self::RESET_N[I])
+    }
+    /// Hold comparator `I` in analog reset.
+    #[inline]
+    pub const fn assert_reset<const I: usize>(self) -> Self {
+        Self(self.0 & !Self::RESET_N[I])
+    }
+    /// Check if comparator `I` is held in analog reset.
+    #[inline]
+    pub const fn is_reset_asserted<const I: usize>(self) -> bool {
+        self.0 & Self::RESET_N[I] == 0
+    }
+    /// Get raw output of comparator `I`.
+    #[inline]
+    pub const fn output<const I: usize>(self) -> bool {
+        self.0 & Self::OUTPUT[I] != 0
+    }
+    /// Set reference voltage selection.
+    #[inline]
+    pub const fn set_reference(self, val: u8) -> Self {
+        Self((self.0 & !Self::REFERENCE) | (((val as u32) << 24) & Self::REFERENCE))
+    }
+    /// Get reference voltage selection.
+    #[inline]
+    pub const fn reference(self) -> u8 {
+        ((self.0 & Self::REFERENCE) >> 24) as u8
+    }
+}
+
+/// Comparator hysteresis level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Hysteresis {
+    /// No hysteresis.
+    None = 0,
+    /// About 10 millivolts.
+    Mv10 = 1,
+    /// About 20 millivolts.
+    Mv20 = 2,
+    /// About 30 millivolts.
+    Mv30 = 3,
+    /// About 40 millivolts.
+    Mv40 = 4,
+    /// About 50 millivolts.
+    Mv50 = 5,
+    /// About 60 millivolts.
+    Mv60 = 6,
+    /// About 70 millivolts.
+    Mv70 = 7,
+}
+
+impl Hysteresis {
+    #[inline]
+    const fn from_bits(bits: u32) -> Self {
+        match bits {
+            0 => Hysteresis::None,
+            1 => Hysteresis::Mv10,
+            2 => Hysteresis::Mv20,
+            3 => Hysteresis::Mv30,
+            4 => Hysteresis::Mv40,
+            5 => Hysteresis::Mv50,
+            6 => Hysteresis::Mv60,
+            7 => Hysteresis::Mv70,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Comparator bias current, higher bias gives faster response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Bias {
+    /// Lowest power consumption.
+    Low = 0,
+    /// Medium bias current.
+    Medium = 1,
+    /// High bias current.
+    High = 2,
+    /// Fastest response.
+    Highest = 3,
+}
+
+/// Comparator input channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Channel {
+    /// Analog channel 0.
+    Analog0 = 0,
+    /// Analog channel 1.
+    Analog1 = 1,
+    /// Analog channel 2.
+    Analog2 = 2,
+    /// Analog channel 3.
+    Analog3 = 3,
+    /// Analog channel 4.
+    Analog4 = 4,
+    /// Analog channel 5.
+    Analog5 = 5,
+    /// Analog channel 6.
+    Analog6 = 6,
+    /// Analog channel 7.
+    Analog7 = 7,
+    /// Digital-to-Analog Converter output A.
+    DacA = 8,
+    /// Digital-to-Analog Converter output B.
+    DacB = 9,
+    /// Internal reference level set by comparator level.
+    Level = 10,
+    /// 1.2 volt bandgap reference.
+    Reference1v2 = 11,
+    /// Power supply divided by four.
+    VbatDiv4 = 12,
+    /// Ground.
+    Ground = 13,
+}
+
+impl Channel {
+    #[inline]
+    const fn from_bits(bits: u32) -> Self {
+        match bits {
+            0 => Channel::Analog0,
+            1 => Channel::Analog1,
+            2 => Channel::Analog2,
+            3 => Channel::Analog3,
+            4 => Channel::Analog4,
+            5 => Channel::Analog5,
+            6 => Channel::Analog6,
+            7 => Channel::Analog7,
+            8 => Channel::DacA,
+            9 => Channel::DacB,
+            10 => Channel::Level,
+            11 => Channel::Reference1v2,
+            12 => Channel::VbatDiv4,
+            _ => Channel::Ground,
+        }
+    }
+}
+
+/// Comparator output edge that raises an interrupt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Edge {
+    /// Output changes from low to high, positive input rises above negative input.
+    Rising = 1,
+    /// Output changes from high to low, positive input falls below negative input.
+    Falling = 2,
+    /// Any output change.
+    Both = 3,
+}
+
+/// Analog comparator configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Config {
+    /// Positive input channel.
+    pub positive_input: Channel,
+    /// Negative input channel.
+    pub negative_input: Channel,
+    /// Internal reference level used when an input is [`Channel::Level`].
+    pub level: u8,
+    /// Hysteresis on positive input.
+    pub positive_hysteresis: Hysteresis,
+    /// Hysteresis on negative input.
+    pub negative_hysteresis: Hysteresis,
+    /// Bias current.
+    pub bias: Bias,
+}
+
+impl Default for Config {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            positive_input: Channel::Analog0,
+            negative_input: Channel::Level,
+            level: 32,
+            positive_hysteresis: Hysteresis::None,
+            negative_hysteresis: Hysteresis::None,
+            bias: Bias::Low,
+        }
+    }
+}
+
+impl Config {
+    /// Set comparator inputs.
+    #[inline]
+    pub const fn set_inputs(mut self, positive: Channel, negative: Channel) -> Self {
+        self.positive_input = positive;
+        self.negative_input = negative;
+        self
+    }
+    /// Compare positive input against internal reference level.
+    #[inline]
+    pub const fn set_threshold(mut self, level: u8) -> Self {
+        self.negative_input = Channel::Level;
+        self.level = level;
+        self
+    }
+    /// Set hysteresis on both inputs.
+    #[inline]
+    pub const fn set_hysteresis(mut self, positive: Hysteresis, negative: Hysteresis) -> Self {
+        self.positive_hysteresis = positive;
+        self.negative_hysteresis = negative;
+        self
+    }
+    /// Set bias current.
+    #[inline]
+    pub const fn set_bias(mut self, bias: Bias) -> Self {
+        self.bias = bias;
+        self
+    }
+}
+
+/// Managed analog comparator `I`.
+pub struct Acomp<ACOMP, const I: usize> {
+    acomp: ACOMP,
+}
+
+impl<ACOMP: Deref<Target = RegisterBlock>, const I: usize> Acomp<ACOMP, I> {
+    /// Create and enable analog comparator with configuration.
+    #[inline]
+    pub fn new(acomp: ACOMP, config: Config) -> Self {
+        let val = AcompConfig(0)
+            .set_positive_input(config.positive_input)
+            .set_negative_input(config.negative_input)
+            .set_level(config.level)
+            .set_positive_hysteresis(config.positive_hysteresis)
+            .set_negative_hysteresis(config.negative_hysteresis)
+            .set_bias(config.bias)
+            .enable_mux()
+            .enable_comparator();
+        unsafe {
+            acomp.control.modify(|v| v.assert_reset::<I>());
+            acomp.config[I].write(val);
+            acomp.control.modify(|v| v.release_reset::<I>());
+        }
+        Self { acomp }
+    }
+    /// Check if positive input is above negative input.
+    #[inline]
+    pub fn is_high(&self) -> bool {
+        self.acomp.control.read().output::<I>()
+    }
+    /// Enable crossing interrupt on given edge.
+    ///
+    /// The comparator interrupt is a hibernate interrupt; once enabled it also
+    /// wakes the chip from HBN mode.
+    #[inline]
+    pub fn enable_interrupt(&self, hbn: &hbn::RegisterBlock, edge: Edge) {
+        unsafe {
+            hbn.interrupt_mode
+                .modify(|v| v.set_acomp_edge(I, edge as u8))
+        };
+    }
+    /// Disable crossing interrupt.
+    #[inline]
+    pub fn disable_interrupt(&self, hbn: &hbn::RegisterBlock) {
+        unsafe { hbn.interrupt_mode.modify(|v| v.set_acomp_edge(I, 0)) };
+    }
+    /// Check if crossing interrupt is pending.
+    #[inline]
+    pub fn has_interrupt(&self, hbn: &hbn::RegisterBlock) -> bool {
+        hbn.interrupt_state.read() & (0x3 << HBN_ACOMP_SHIFT[I]) != 0
+    }
+    /// Clear crossing interrupt.
+    #[inline]
+    pub fn clear_interrupt(&self, hbn: &hbn::RegisterBlock) {
+        unsafe { hbn.interrupt_clear.write(0x3 << HBN_ACOMP_SHIFT[I]) };
+    }
+    /// Disable comparator and release its peripheral.
+    #[inline]
+    pub fn free(self) -> ACOMP {
+        unsafe {
+            self.acomp.config[I].modify(|v| v.disable_comparator());
+            self.acomp.control.modify(|v| v.assert_reset::<I>());
+        }
+        self.acomp
+    }
+}
+
+/// Bit offsets of comparator interrupts in hibernate interrupt registers.
+const HBN_ACOMP_SHIFT: [u32; 2] = [20, 22];
+
+#[cfg(test)]
+mod tests {
+    use super::{AcompConfig, AcompControl, Bias, Channel, Hysteresis, RegisterBlock};
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, control), 0x08);
+    }
+
+    #[test]
+    fn struct_acomp_config_functions() {
+        let mut val = AcompConfig(0x0);
+
+        val = val.enable_comparator();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_comparator_enabled());
+        val = val.disable_comparator();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_comparator_enabled());
+
+        val = val.set_negative_hysteresis(Hysteresis::Mv70);
+        assert_eq!(val.0, 0x0000_0070);
+        assert_eq!(val.negative_hysteresis(), Hysteresis::Mv70);
+        val = val.set_negative_hysteresis(Hysteresis::None);
+
+        val = val.set_positive_hysteresis(Hysteresis::Mv30);
+        assert_eq!(val.0, 0x0000_0180);
+        assert_eq!(val.positive_hysteresis(), Hysteresis::Mv30);
+        val = val.set_positive_hysteresis(Hysteresis::None);
+
+        val = val.set_bias(Bias::Highest);
+        assert_eq!(val.0, 0x0000_0c00);
+        assert_eq!(val.bias(), Bias::Highest);
+        val = val.set_bias(Bias::Low);
+
+        val = val.set_level(0x3f);
+        assert_eq!(val.0, 0x0003_f000);
+        assert_eq!(val.level(), 0x3f);
+        val = val.set_level(0);
+
+        val = val.set_negative_input(Channel::Ground);
+        assert_eq!(val.0, 0x0034_0000);
+        assert_eq!(val.negative_input(), Channel::Ground);
+        val = val.set_negative_input(Channel::Analog0);
+
+        val = val.set_positive_input(Channel::VbatDiv4);
+        assert_eq!(val.0, 0x0300_0000);
+        assert_eq!(val.positive_input(), Channel::VbatDiv4);
+        val = val.set_positive_input(Channel::Analog0);
+
+        val = val.enable_mux();
+        assert_eq!(val.0, 0x0400_0000);
+        assert!(val.is_mux_enabled());
+        val = val.disable_mux();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_mux_enabled());
+    }
+
+    #[test]
+    fn struct_acomp_control_functions() {
+        let mut val = AcompControl(0x0);
+
+        assert!(val.is_reset_asserted::<0>());
+        val = val.release_reset::<0>();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(!val.is_reset_asserted::<0>());
+        val = val.release_reset::<1>();
+        assert_eq!(val.0, 0x0000_0003);
+        val = val.assert_reset::<0>().assert_reset::<1>();
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = AcompControl(0x0008_0000);
+        assert!(val.output::<0>());
+        assert!(!val.output::<1>());
+        val = AcompControl(0x0002_0000);
+        assert!(!val.output::<0>());
+        assert!(val.output::<1>());
+
+        val = AcompControl(0x0).set_reference(0x3f);
+        assert_eq!(val.0, 0x3f00_0000);
+        assert_eq!(val.reference(), 0x3f);
+    }
+}
@@ -0,0 +1,3 @@
+//! Processor architecture extensions of bouffalolab cores.
+
+pub mod xtheadcsr;
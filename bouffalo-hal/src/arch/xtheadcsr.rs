@@ -0,0 +1,504 @@
+//! T-Head extended control registers and cache operations.
+//!
+//! C906 and E907 cores of BL808 extend RISC-V with cache control registers and custom
+//! cache maintenance instructions. Instructions by address require `MAEE` and
+//! `THEADISAEE` in `mxstatus`, which ROM bootloader sets before entering firmware.
+
+/// Smallest data cache line of BL808 cores, used as cache maintenance step.
+pub const CACHE_LINE: usize = 32;
+
+/// Machine extended status register (`mxstatus`, 0x7c0).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Mxstatus(usize);
+
+impl Mxstatus {
+    const MAEE: usize = 1 << 21;
+    const THEADISAEE: usize = 1 << 22;
+
+    /// Enable extended memory attributes in page table entries.
+    #[inline]
+    pub const fn enable_extended_memory_attributes(self) -> Self {
+        Self(self.0 | Self::MAEE)
+    }
+    /// Disable extended memory attributes in page table entries.
+    #[inline]
+    pub const fn disable_extended_memory_attributes(self) -> Self {
+        Self(self.0 & !Self::MAEE)
+    }
+    /// Check if extended memory attributes are enabled.
+    #[inline]
+    pub const fn is_extended_memory_attributes_enabled(self) -> bool {
+        self.0 & Self::MAEE != 0
+    }
+    /// Enable T-Head extended instructions.
+    #[inline]
+    pub const fn enable_thead_instructions(self) -> Self {
+        Self(self.0 | Self::THEADISAEE)
+    }
+    /// Disable T-Head extended instructions.
+    #[inline]
+    pub const fn disable_thead_instructions(self) -> Self {
+        Self(self.0 & !Self::THEADISAEE)
+    }
+    /// Check if T-Head extended instructions are enabled.
+    #[inline]
+    pub const fn is_thead_instructions_enabled(self) -> bool {
+        self.0 & Self::THEADISAEE != 0
+    }
+}
+
+/// Machine hardware configuration register (`mhcr`, 0x7c1).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Mhcr(usize);
+
+impl Mhcr {
+    const ICACHE: usize = 1 << 0;
+    const DCACHE: usize = 1 << 1;
+    const WRITE_ALLOCATE: usize = 1 << 2;
+    const WRITE_BACK: usize = 1 << 3;
+    const RETURN_STACK: usize = 1 << 4;
+    const BRANCH_PREDICTION: usize = 1 << 5;
+    const BRANCH_TARGET_BUFFER: usize = 1 << 6;
+
+    /// Enable instruction cache.
+    #[inline]
+    pub const fn enable_icache(self) -> Self {
+        Self(self.0 | Self::ICACHE)
+    }
+    /// Disable instruction cache.
+    #[inline]
+    pub const fn disable_icache(self) -> Self {
+        Self(self.0 & !Self::ICACHE)
+    }
+    /// Check if instruction cache is enabled.
+    #[inline]
+    pub const fn is_icache_enabled(self) -> bool {
+        self.0 & Self::ICACHE != 0
+    }
+    /// Enable data cache.
+    #[inline]
+    pub const fn enable_dcache(self) -> Self {
+        Self(self.0 | Self::DCACHE)
+    }
+    /// Disable data cache.
+    #[inline]
+    pub const fn disable_dcache(self) -> Self {
+        Self(self.0 & !Self::DCACHE)
+    }
+    /// Check if data cache is enabled.
+    #[inline]
+    pub const fn is_dcache_enabled(self) -> bool {
+        self.0 & Self::DCACHE != 0
+    }
+    /// Enable write allocate on data cache misses.
+    #[inline]
+    pub const fn enable_write_allocate(self) -> Self {
+        Self(self.0 | Self::WRITE_ALLOCATE)
+    }
+    /// Disable write allocate on data cache misses.
+    #[inline]
+    pub const fn disable_write_allocate(self) -> Self {
+        Self(self.0 & !Self::WRITE_ALLOCATE)
+    }
+    /// Check if write allocate is enabled.
+    #[inline]
+    pub const fn is_write_allocate_enabled(self) -> bool {
+        self.0 & Self::WRITE_ALLOCATE != 0
+    }
+    /// Enable write back, data cache is write through otherwise.
+    #[inline]
+    pub const fn enable_write_back(self) -> Self {
+        Self(self.0 | Self::WRITE_BACK)
+    }
+    /// Disable write back.
+    #[inline]
+    pub const fn disable_write_back(self) -> Self {
+        Self(self.0 & !Self::WRITE_BACK)
+    }
+    /// Check if write back is enabled.
+    #[inline]
+    pub const fn is_write_back_enabled(self) -> bool {
+        self.0 & Self::WRITE_BACK != 0
+    }
+    /// Enable return address stack.
+    #[inline]
+    pub const fn enable_return_stack(self) -> Self {
+        Self(self.0 | Self::RETURN_STACK)
+    }
+    /// Disable return address stack.
+    #[inline]
+    pub const fn disable_return_stack(self) -> Self {
+        Self(self.0 & !Self::RETURN_STACK)
+    }
+    /// Check if return address stack is enabled.
+    #[inline]
+    pub const fn is_return_stack_enabled(self) -> bool {
+        self.0 & Self::RETURN_STACK != 0
+    }
+    /// Enable branch prediction.
+    #[inline]
+    pub const fn enable_branch_prediction(self) -> Self {
+        Self(self.0 | Self::BRANCH_PREDICTION)
+    }
+    /// Disable branch prediction.
+    #[inline]
+    pub const fn disable_branch_prediction(self) -> Self {
+        Self(self.0 & !Self::BRANCH_PREDICTION)
+    }
+    /// Check if branch prediction is enabled.
+    #[inline]
+    pub const fn is_branch_prediction_enabled(self) -> bool {
+        self.0 & Self::BRANCH_PREDICTION != 0
+    }
+    /// Enable branch target buffer.
+    #[inline]
+    pub const fn enable_branch_target_buffer(self) -> Self {
+        Self(self.0 | Self::BRANCH_TARGET_BUFFER)
+    }
+    /// Disable branch target buffer.
+    #[inline]
+    pub const fn disable_branch_target_buffer(self) -> Self {
+        Self(self.0 & !Self::BRANCH_TARGET_BUFFER)
+    }
+    /// Check if branch target buffer is enabled.
+    #[inline]
+    pub const fn is_branch_target_buffer_enabled(self) -> bool {
+        self.0 & Self::BRANCH_TARGET_BUFFER != 0
+    }
+}
+
+/// Cache selection of cache operation register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum CacheSelect {
+    /// Instruction cache.
+    Instruction = 1,
+    /// Data cache.
+    Data = 2,
+    /// Both instruction and data cache.
+    Both = 3,
+}
+
+/// Machine cache operation register (`mcor`, 0x7c2).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Mcor(usize);
+
+impl Mcor {
+    const CACHE_SELECT: usize = 0x3;
+    const INVALIDATE: usize = 1 << 4;
+    const CLEAN: usize = 1 << 5;
+    const BRANCH_HISTORY_INVALIDATE: usize = 1 << 16;
+    const BRANCH_TARGET_INVALIDATE: usize = 1 << 17;
+
+    /// Select caches to operate on.
+    #[inline]
+    pub const fn set_cache_select(self, val: CacheSelect) -> Self {
+        Self((self.0 & !Self::CACHE_SELECT) | val as usize)
+    }
+    /// Invalidate selected caches.
+    #[inline]
+    pub const fn invalidate(self) -> Self {
+        Self(self.0 | Self::INVALIDATE)
+    }
+    /// Write back dirty lines of selected caches.
+    #[inline]
+    pub const fn clean(self) -> Self {
+        Self(self.0 | Self::CLEAN)
+    }
+    /// Invalidate branch history table.
+    #[inline]
+    pub const fn invalidate_branch_history(self) -> Self {
+        Self(self.0 | Self::BRANCH_HISTORY_INVALIDATE)
+    }
+    /// Invalidate branch target buffer.
+    #[inline]
+    pub const fn invalidate_branch_target(self) -> Self {
+        Self(self.0 | Self::BRANCH_TARGET_INVALIDATE)
+    }
+    /// Check if a previously started operation is still running.
+    #[inline]
+    pub const fn is_busy(self) -> bool {
+        self.0 & (Self::INVALIDATE | Self::CLEAN) != 0
+    }
+}
+
+/// Machine hint register (`mhint`, 0x7c5).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Mhint(usize);
+
+impl Mhint {
+    const DCACHE_PREFETCH: usize = 1 << 2;
+    const ICACHE_PREFETCH: usize = 1 << 8;
+    const PREFETCH_DISTANCE: usize = 0x3 << 13;
+
+    /// Enable data cache prefetch.
+    #[inline]
+    pub const fn enable_dcache_prefetch(self) -> Self {
+        Self(self.0 | Self::DCACHE_PREFETCH)
+    }
+    /// Disable data cache prefetch.
+    #[inline]
+    pub const fn disable_dcache_prefetch(self) -> Self {
+        Self(self.0 & !Self::DCACHE_PREFETCH)
+    }
+    /// Check if data cache prefetch is enabled.
+    #[inline]
+    pub const fn is_dcache_prefetch_enabled(self) -> bool {
+        self.0 & Self::DCACHE_PREFETCH != 0
+    }
+    /// Enable instruction cache prefetch.
+    #[inline]
+    pub const fn enable_icache_prefetch(self) -> Self {
+        Self(self.0 | Self::ICACHE_PREFETCH)
+    }
+    /// Disable instruction cache prefetch.
+    #[inline]
+    pub const fn disable_icache_prefetch(self) -> Self {
+        Self(self.0 & !Self::ICACHE_PREFETCH)
+    }
+    /// Check if instruction cache prefetch is enabled.
+    #[inline]
+    pub const fn is_icache_prefetch_enabled(self) -> bool {
+        self.0 & Self::ICACHE_PREFETCH != 0
+    }
+    /// Set data prefetch distance in cache lines, from 0 (2 lines) to 3 (16 lines).
+    #[inline]
+    pub const fn set_prefetch_distance(self, val: u8) -> Self {
+        Self((self.0 & !Self::PREFETCH_DISTANCE) | ((val as usize & 0x3) << 13))
+    }
+    /// Get data prefetch distance.
+    #[inline]
+    pub const fn prefetch_distance(self) -> u8 {
+        ((self.0 & Self::PREFETCH_DISTANCE) >> 13) as u8
+    }
+}
+
+macro_rules! csr_access {
+    ($($Ty: ident: $csr: literal,)+) => {
+        $(
+            impl $Ty {
+                /// Read this register of the current core.
+                #[inline]
+                pub fn read() -> Self {
+                    #[cfg(all(
+                        feature = "bl808",
+                        any(target_arch = "riscv32", target_arch = "riscv64")
+                    ))]
+                    {
+                        let bits: usize;
+                        unsafe { core::arch::asm!(concat!("csrr {0}, ", $csr), out(reg) bits) };
+                        Self(bits)
+                    }
+                    #[cfg(not(all(
+                        feature = "bl808",
+                        any(target_arch = "riscv32", target_arch = "riscv64")
+                    )))]
+                    Self(0)
+                }
+                /// Write this register of the current core.
+                ///
+                /// # Safety
+                ///
+                /// Changing cache or core configuration affects all code on this core.
+                #[inline]
+                pub unsafe fn write(self) {
+                    #[cfg(all(
+                        feature = "bl808",
+                        any(target_arch = "riscv32", target_arch = "riscv64")
+                    ))]
+                    unsafe {
+                        core::arch::asm!(concat!("csrw ", $csr, ", {0}"), in(reg) self.0)
+                    };
+                }
+            }
+        )+
+    };
+}
+
+csr_access! {
+    Mxstatus: "0x7c0",
+    Mhcr: "0x7c1",
+    Mcor: "0x7c2",
+    Mhint: "0x7c5",
+}
+
+macro_rules! cache_instruction {
+    ($(#[$doc: meta])* $name: ident, $funct: literal) => {
+        $(#[$doc])*
+        #[inline]
+        pub fn $name() {
+            #[cfg(all(
+                feature = "bl808",
+                any(target_arch = "riscv32", target_arch = "riscv64")
+            ))]
+            unsafe {
+                core::arch::asm!(concat!(".insn i 0x0b, 0, x0, x0, ", $funct))
+            };
+        }
+    };
+    ($(#[$doc: meta])* $name: ident(addr), $funct: literal) => {
+        $(#[$doc])*
+        #[inline]
+        pub fn $name(_addr: usize) {
+            #[cfg(all(
+                feature = "bl808",
+                any(target_arch = "riscv32", target_arch = "riscv64")
+            ))]
+            unsafe {
+                core::arch::asm!(concat!(".insn i 0x0b, 0, x0, {0}, ", $funct), in(reg) _addr)
+            };
+        }
+    };
+}
+
+cache_instruction!(
+    /// Write back all dirty data cache lines (`dcache.call`).
+    dcache_clean_all, "0x001"
+);
+cache_instruction!(
+    /// Discard all data cache lines (`dcache.iall`).
+    dcache_invalidate_all, "0x002"
+);
+cache_instruction!(
+    /// Write back and discard all data cache lines (`dcache.ciall`).
+    dcache_flush_all, "0x003"
+);
+cache_instruction!(
+    /// Discard all instruction cache lines (`icache.iall`).
+    icache_invalidate_all, "0x010"
+);
+cache_instruction!(
+    /// Wait until previous instructions and cache operations complete (`sync.s`).
+    sync, "0x019"
+);
+cache_instruction!(
+    /// Write back data cache line containing virtual address (`dcache.cva`).
+    dcache_clean_line(addr), "0x025"
+);
+cache_instruction!(
+    /// Discard data cache line containing virtual address (`dcache.iva`).
+    dcache_invalidate_line(addr), "0x026"
+);
+cache_instruction!(
+    /// Write back and discard data cache line containing virtual address (`dcache.civa`).
+    dcache_flush_line(addr), "0x027"
+);
+cache_instruction!(
+    /// Discard instruction cache line containing virtual address (`icache.iva`).
+    icache_invalidate_line(addr), "0x030"
+);
+
+/// Write back data cache lines covering `len` bytes from `addr` to memory.
+#[inline]
+pub fn clean_dcache(addr: usize, len: usize) {
+    for_each_line(addr, len, dcache_clean_line);
+    sync();
+}
+
+/// Discard data cache lines covering `len` bytes from `addr`.
+///
+/// Dirty data in these lines is lost, so the region should cover whole cache lines.
+#[inline]
+pub fn invalidate_dcache(addr: usize, len: usize) {
+    for_each_line(addr, len, dcache_invalidate_line);
+    sync();
+}
+
+/// Write back and discard data cache lines covering `len` bytes from `addr`.
+#[inline]
+pub fn flush_dcache(addr: usize, len: usize) {
+    for_each_line(addr, len, dcache_flush_line);
+    sync();
+}
+
+/// Discard instruction cache lines covering `len` bytes from `addr`.
+///
+/// Call after writing code into memory, once it is cleaned from data cache.
+#[inline]
+pub fn invalidate_icache(addr: usize, len: usize) {
+    for_each_line(addr, len, icache_invalidate_line);
+    sync();
+}
+
+#[inline]
+fn for_each_line(addr: usize, len: usize, f: impl FnMut(usize)) {
+    let start = addr & !(CACHE_LINE - 1);
+    (start..addr + len).step_by(CACHE_LINE).for_each(f);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CacheSelect, Mcor, Mhcr, Mhint, Mxstatus, for_each_line};
+
+    #[test]
+    fn struct_mhcr_functions() {
+        let mut val = Mhcr(0);
+        val = val.enable_icache().enable_dcache().enable_write_back();
+        assert_eq!(val.0, 0x0000_000b);
+        assert!(val.is_icache_enabled());
+        assert!(val.is_dcache_enabled());
+        assert!(!val.is_write_allocate_enabled());
+
+        val = val.enable_branch_prediction().enable_branch_target_buffer();
+        assert_eq!(val.0, 0x0000_006b);
+        val = val.disable_icache().disable_branch_prediction();
+        assert_eq!(val.0, 0x0000_004a);
+        assert!(val.is_branch_target_buffer_enabled());
+        assert!(!val.is_return_stack_enabled());
+    }
+
+    #[test]
+    fn struct_mcor_functions() {
+        let val = Mcor(0)
+            .set_cache_select(CacheSelect::Data)
+            .clean()
+            .invalidate();
+        assert_eq!(val.0, 0x0000_0032);
+        assert!(val.is_busy());
+        let val = val.set_cache_select(CacheSelect::Instruction);
+        assert_eq!(val.0, 0x0000_0031);
+        let val = Mcor(0)
+            .invalidate_branch_history()
+            .invalidate_branch_target();
+        assert_eq!(val.0, 0x0003_0000);
+        assert!(!val.is_busy());
+    }
+
+    #[test]
+    fn struct_mhint_functions() {
+        let mut val = Mhint(0).enable_dcache_prefetch().enable_icache_prefetch();
+        assert_eq!(val.0, 0x0000_0104);
+        val = val.set_prefetch_distance(3);
+        assert_eq!(val.0, 0x0000_6104);
+        assert_eq!(val.prefetch_distance(), 3);
+        val = val.disable_dcache_prefetch();
+        assert!(!val.is_dcache_prefetch_enabled());
+        assert!(val.is_icache_prefetch_enabled());
+    }
+
+    #[test]
+    fn struct_mxstatus_functions() {
+        let val = Mxstatus(0)
+            .enable_extended_memory_attributes()
+            .enable_thead_instructions();
+        assert_eq!(val.0, 0x0060_0000);
+        let val = val.disable_extended_memory_attributes();
+        assert!(!val.is_extended_memory_attributes_enabled());
+        assert!(val.is_thead_instructions_enabled());
+    }
+
+    #[test]
+    fn function_for_each_line() {
+        let mut lines = [0usize; 4];
+        let mut count = 0;
+        for_each_line(0x1010, 0x40, |line| {
+            lines[count] = line;
+            count += 1;
+        });
+        assert_eq!(&lines[..count], &[0x1000, 0x1020, 0x1040]);
+    }
+}
@@ -0,0 +1,384 @@
+//! AT command client over serial.
+//!
+//! [`AtClient`] sends a command line, collects information response lines and waits for
+//! its final result code, so cellular and Wi-Fi co-processors can be driven over any
+//! `embedded-io` serial. Lines starting with `+` which do not answer the pending command,
+//! and every line received between commands, are unsolicited result codes (URCs) and are
+//! passed to a handler, e.g. `+CREG: 1` or `+IPD,4:ping`.
+//!
+//! Responses are read line by line; prompts without line ending such as `> ` for
+//! `AT+CMGS` are not supported.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let serial = p.uart1.freerun(config, pads, &c).unwrap();
+//! let mut modem = AtClient::<_, _, 128>::new(serial, |urc: &[u8]| {
+//!     if urc.starts_with(b"+CREG:") {
+//!         REGISTERED.store(urc.ends_with(b" 1"), Ordering::Relaxed);
+//!     }
+//! });
+//! let mut response = [0u8; 64];
+//! let len = modem.command(b"AT+CSQ", &mut response, &clock, 300.milliseconds())?;
+//! // response[..len] is b"+CSQ: 21,99".
+//! loop {
+//!     modem.poll()?;
+//! }
+//! ```
+
+use crate::uart::{LineError, LineReader};
+use embedded_io::{Read, ReadReady, Write};
+use embedded_time::{Clock, duration::Duration, fixed_point::FixedPoint};
+
+/// AT command error.
+#[derive(Debug)]
+pub enum AtError<E> {
+    /// Error from underlying serial.
+    Serial(E),
+    /// Command failed with `ERROR`, or an error result in verbose text.
+    Error,
+    /// Equipment error with numeric `+CME ERROR` code.
+    Cme(u16),
+    /// Message service error with numeric `+CMS ERROR` code.
+    Cms(u16),
+    /// Response or a received line does not fit in its buffer.
+    Overflow,
+    /// Serial reached end of stream.
+    EndOfStream,
+    /// No final result code before deadline.
+    Timeout,
+    /// Timer source unavailable or deadline out of its range.
+    Clock,
+}
+
+impl<E> From<LineError<E>> for AtError<E> {
+    #[inline]
+    fn from(value: LineError<E>) -> Self {
+        match value {
+            LineError::Read(e) => AtError::Serial(e),
+            LineError::Overflow => AtError::Overflow,
+            LineError::EndOfStream => AtError::EndOfStream,
+        }
+    }
+}
+
+/// AT command client over serial `S` with an `N` byte line buffer.
+///
+/// URC handler `U` is called with every unsolicited line, without line ending.
+pub struct AtClient<S, U, const N: usize> {
+    lines: LineReader<S, N>,
+    urc: U,
+}
+
+impl<S, U, const N: usize> AtClient<S, U, N>
+where
+    S: Read + ReadReady + Write,
+    U: FnMut(&[u8]),
+{
+    /// Create AT client over `serial`, passing unsolicited result codes to `urc`.
+    #[inline]
+    pub const fn new(serial: S, urc: U) -> Self {
+        Self {
+            lines: LineReader::new(serial),
+            urc,
+        }
+    }
+    /// Send `command` and wait for its final result code.
+    ///
+    /// `command` is the line without terminating `\r`, e.g. `b"AT+CGATT?"`. Information
+    /// response lines are copied into `response` separated by `\n`, and their total length
+    /// is returned; echo of the command is skipped. If they do not fit, the final result
+    /// is still awaited and [`AtError::Overflow`] returned.
+    #[inline]
+    pub fn command<C: Clock, D: Duration + FixedPoint>(
+        &mut self,
+        command: &[u8],
+        response: &mut [u8],
+        clock: &C,
+        timeout: D,
+    ) -> Result<usize, AtError<S::Error>>
+    where
+        C::T: TryFrom<D::T>,
+    {
+        self.poll()?;
+        let now = clock.try_now().map_err(|_| AtError::Clock)?;
+        let deadline = now.checked_add(timeout).ok_or(AtError::Clock)?;
+        self.exchange(command, response, || {
+            clock.try_now().map_or(true, |now| now >= deadline)
+        })
+    }
+    /// Pass lines received so far to URC handler without waiting.
+    #[inline]
+    pub fn poll(&mut self) -> Result<(), AtError<S::Error>> {
+        loop {
+            match self.lines.poll_line() {
+                Ok([]) => {}
+                Ok(line) => (self.urc)(line),
+                Err(nb::Error::WouldBlock) => return Ok(()),
+                Err(nb::Error::Other(e)) => return Err(e.into()),
+            }
+        }
+    }
+    /// Get a mutable reference to underlying serial.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut S {
+        self.lines.inner_mut()
+    }
+    /// Release AT client and return its serial; buffered bytes are dropped.
+    #[inline]
+    pub fn free(self) -> S {
+        self.lines.free()
+    }
+    // Send command line and collect response until final result code or `expired`.
+    #[inline]
+    fn exchange(
+        &mut self,
+        command: &[u8],
+        response: &mut [u8],
+        mut expired: impl FnMut() -> bool,
+    ) -> Result<usize, AtError<S::Error>> {
+        let serial = self.lines.inner_mut();
+        serial.write_all(command).map_err(AtError::Serial)?;
+        serial.write_all(b"\r").map_err(AtError::Serial)?;
+        serial.flush().map_err(AtError::Serial)?;
+        let prefix = response_prefix(command);
+        let mut len = 0;
+        let mut overflow = false;
+        loop {
+            let line = match self.lines.poll_line() {
+                Ok(line) => line,
+                Err(nb::Error::WouldBlock) => {
+                    if expired() {
+                        return Err(AtError::Timeout);
+                    }
+                    core::hint::spin_loop();
+                    continue;
+                }
+                // Rest of the long line is skipped, wait for final result.
+                Err(nb::Error::Other(LineError::Overflow)) => {
+                    overflow = true;
+                    continue;
+                }
+                Err(nb::Error::Other(e)) => return Err(e.into()),
+            };
+            // Echo ends with another `\r` after the command line.
+            let line = line.trim_ascii_end();
+            match classify(line, command, prefix) {
+                Kind::Empty | Kind::Echo => {}
+                Kind::Ok if overflow => return Err(AtError::Overflow),
+                Kind::Ok => return Ok(len),
+                Kind::Error => return Err(AtError::Error),
+                Kind::Cme(code) => return Err(AtError::Cme(code)),
+                Kind::Cms(code) => return Err(AtError::Cms(code)),
+                Kind::Urc => (self.urc)(line),
+                Kind::Response => {
+                    let separator = if len == 0 { 0 } else { 1 };
+                    let end = len + separator + line.len();
+                    if overflow || end > response.len() {
+                        overflow = true;
+                        continue;
+                    }
+                    if separator != 0 {
+                        response[len] = b'\n';
+                    }
+                    response[len + separator..end].copy_from_slice(line);
+                    len = end;
+                }
+            }
+        }
+    }
+}
+
+// Kind of a line received while a command is pending.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Empty,
+    Echo,
+    Ok,
+    Error,
+    Cme(u16),
+    Cms(u16),
+    Response,
+    Urc,
+}
+
+#[inline]
+fn classify(line: &[u8], command: &[u8], prefix: &[u8]) -> Kind {
+    if line.is_empty() {
+        return Kind::Empty;
+    }
+    if line == command {
+        return Kind::Echo;
+    }
+    match line {
+        b"OK" => return Kind::Ok,
+        b"ERROR" => return Kind::Error,
+        _ => {}
+    }
+    if let Some(code) = line.strip_prefix(b"+CME ERROR:") {
+        return parse_code(code).map_or(Kind::Error, Kind::Cme);
+    }
+    if let Some(code) = line.strip_prefix(b"+CMS ERROR:") {
+        return parse_code(code).map_or(Kind::Error, Kind::Cms);
+    }
+    if line[0] != b'+' {
+        return Kind::Response;
+    }
+    match line.strip_prefix(prefix) {
+        Some([b':', ..]) if !prefix.is_empty() => Kind::Response,
+        _ => Kind::Urc,
+    }
+}
+
+// Information response prefix of an extended command, e.g. `+CSQ` of `AT+CSQ?`.
+#[inline]
+fn response_prefix(command: &[u8]) -> &[u8] {
+    match command {
+        [b'A' | b'a', b'T' | b't', rest @ ..] if rest.first() == Some(&b'+') => {
+            let end = rest
+                .iter()
+                .position(|&b| matches!(b, b'=' | b'?' | b';'))
+                .unwrap_or(rest.len());
+            &rest[..end]
+        }
+        _ => &[],
+    }
+}
+
+#[inline]
+fn parse_code(text: &[u8]) -> Option<u16> {
+    let digits = text.trim_ascii();
+    if digits.is_empty() {
+        return None;
+    }
+    let mut code = 0u16;
+    for &b in digits {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        code = code.checked_mul(10)?.checked_add((b - b'0') as u16)?;
+    }
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::{AtClient, AtError, Kind, classify, parse_code, response_prefix};
+    use std::vec::Vec;
+
+    // Serial replaying `input` and recording written bytes.
+    struct Modem<'a> {
+        input: &'a [u8],
+        output: Vec<u8>,
+    }
+
+    impl embedded_io::ErrorType for Modem<'_> {
+        type Error = embedded_io::ErrorKind;
+    }
+
+    impl embedded_io::Read for Modem<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            let n = buf.len().min(self.input.len()).min(5);
+            buf[..n].copy_from_slice(&self.input[..n]);
+            self.input = &self.input[n..];
+            Ok(n)
+        }
+    }
+
+    impl embedded_io::ReadReady for Modem<'_> {
+        fn read_ready(&mut self) -> Result<bool, Self::Error> {
+            Ok(!self.input.is_empty())
+        }
+    }
+
+    impl embedded_io::Write for Modem<'_> {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            self.output.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn function_response_prefix() {
+        assert_eq!(response_prefix(b"AT+CSQ"), b"+CSQ");
+        assert_eq!(response_prefix(b"AT+CGATT?"), b"+CGATT");
+        assert_eq!(response_prefix(b"at+cops=0,2"), b"+cops");
+        assert_eq!(response_prefix(b"ATI"), b"");
+        assert_eq!(response_prefix(b"AT"), b"");
+    }
+
+    #[test]
+    fn function_classify() {
+        let command = b"AT+CSQ";
+        let prefix = b"+CSQ";
+        assert_eq!(classify(b"", command, prefix), Kind::Empty);
+        assert_eq!(classify(b"AT+CSQ", command, prefix), Kind::Echo);
+        assert_eq!(classify(b"OK", command, prefix), Kind::Ok);
+        assert_eq!(classify(b"ERROR", command, prefix), Kind::Error);
+        assert_eq!(classify(b"+CME ERROR: 10", command, prefix), Kind::Cme(10));
+        assert_eq!(
+            classify(b"+CMS ERROR: 500", command, prefix),
+            Kind::Cms(500)
+        );
+        assert_eq!(
+            classify(b"+CME ERROR: SIM not inserted", command, prefix),
+            Kind::Error
+        );
+        assert_eq!(classify(b"+CSQ: 21,99", command, prefix), Kind::Response);
+        assert_eq!(classify(b"+CSQN: 1", command, prefix), Kind::Urc);
+        assert_eq!(classify(b"+CREG: 1", command, prefix), Kind::Urc);
+        assert_eq!(classify(b"Quectel", b"AT+CGMI", b"+CGMI"), Kind::Response);
+        assert_eq!(classify(b"+CREG: 1", b"ATI", b""), Kind::Urc);
+    }
+
+    #[test]
+    fn function_parse_code() {
+        assert_eq!(parse_code(b" 3"), Some(3));
+        assert_eq!(parse_code(b"65535"), Some(65535));
+        assert_eq!(parse_code(b"65536"), None);
+        assert_eq!(parse_code(b" "), None);
+    }
+
+    #[test]
+    fn struct_at_client_exchange() {
+        let modem = Modem {
+            input: b"+CREG: 2\r\nAT+CSQ\r\r\n+CSQ: 21,99\r\n+CREG: 1\r\n\r\nOK\r\n+IPD,4:ping\r\n",
+            output: Vec::new(),
+        };
+        let mut urcs = Vec::new();
+        let mut client = AtClient::<_, _, 32>::new(modem, |line: &[u8]| urcs.push(line.to_vec()));
+        let mut response = [0u8; 16];
+        let len = client.exchange(b"AT+CSQ", &mut response, || false).unwrap();
+        assert_eq!(&response[..len], b"+CSQ: 21,99");
+        client.poll().unwrap();
+        let modem = client.free();
+        assert_eq!(modem.output, b"AT+CSQ\r");
+        assert_eq!(urcs, [&b"+CREG: 2"[..], b"+CREG: 1", b"+IPD,4:ping"]);
+    }
+
+    #[test]
+    fn struct_at_client_errors() {
+        let modem = Modem {
+            input: b"Quectel\r\nEC25\r\nOK\r\n+CME ERROR: 10\r\n",
+            output: Vec::new(),
+        };
+        let mut client = AtClient::<_, _, 32>::new(modem, |_: &[u8]| {});
+        let mut response = [0u8; 8];
+        assert!(matches!(
+            client.exchange(b"ATI", &mut response, || false),
+            Err(AtError::Overflow)
+        ));
+        assert!(matches!(
+            client.exchange(b"AT+CPIN?", &mut response, || false),
+            Err(AtError::Cme(10))
+        ));
+        assert!(matches!(
+            client.exchange(b"AT", &mut response, || true),
+            Err(AtError::Timeout)
+        ));
+    }
+}
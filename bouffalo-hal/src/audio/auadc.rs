@@ -1,7 +1,481 @@
 //! Audio Analog-Digital Converter peripheral.
 
+use super::{
+    ConfigError, FifoConfig, FifoStatus, Interrupt, SampleRate, VolumeConfig, check_clock,
+};
+use crate::clocks::Clocks;
+use core::ops::Deref;
+use volatile_register::{RO, RW};
+
 /// Audio Analog-Digital Converter peripheral registers.
 #[repr(C)]
 pub struct RegisterBlock {
-    // todo fields
+    /// Converter enable and sample rate register.
+    pub config: RW<AdcConfig>,
+    _reserved0: [u8; 0x4],
+    /// Digital volume and mute register.
+    pub volume: RW<VolumeConfig>,
+    _reserved1: [u8; 0x54],
+    /// Analog input and amplifier register.
+    pub input: RW<InputConfig>,
+    _reserved2: [u8; 0x24],
+    /// Receive FIFO configuration register.
+    pub fifo_config: RW<FifoConfig>,
+    /// Receive FIFO status register.
+    pub fifo_status: RW<FifoStatus>,
+    /// Receive FIFO data register.
+    pub fifo_read: RO<u32>,
+}
+
+/// Converter enable and sample rate register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct AdcConfig(u32);
+
+impl AdcConfig {
+    const ENABLE: u32 = 1 << 0;
+    const INTERFACE: u32 = 1 << 1;
+    const HIGH_PASS: u32 = 1 << 2;
+    const CLOCK: u32 = 1 << 27;
+    const SAMPLE_RATE: u32 = 0x7 << 28;
+
+    /// Enable converter.
+    #[inline]
+    pub const fn enable_adc(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable converter.
+    #[inline]
+    pub const fn disable_adc(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if converter is enabled.
+    #[inline]
+    pub const fn is_adc_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Enable sample interface to FIFO.
+    #[inline]
+    pub const fn enable_interface(self) -> Self {
+        Self(self.0 | Self::INTERFACE)
+    }
+    /// Disable sample interface to FIFO.
+    #[inline]
+    pub const fn disable_interface(self) -> Self {
+        Self(self.0 & !Self::INTERFACE)
+    }
+    /// Check if sample interface is enabled.
+    #[inline]
+    pub const fn is_interface_enabled(self) -> bool {
+        self.0 & Self::INTERFACE != 0
+    }
+    /// Enable high-pass filter removing direct current offset.
+    #[inline]
+    pub const fn enable_high_pass(self) -> Self {
+        Self(self.0 | Self::HIGH_PASS)
+    }
+    /// Disable high-pass filter.
+    #[inline]
+    pub const fn disable_high_pass(self) -> Self {
+        Self(self.0 & !Self::HIGH_PASS)
+    }
+    /// Check if high-pass filter is enabled.
+    #[inline]
+    pub const fn is_high_pass_enabled(self) -> bool {
+        self.0 & Self::HIGH_PASS != 0
+    }
+    /// Enable converter clock gate.
+    #[inline]
+    pub const fn enable_clock(self) -> Self {
+        Self(self.0 | Self::CLOCK)
+    }
+    /// Disable converter clock gate.
+    #[inline]
+    pub const fn disable_clock(self) -> Self {
+        Self(self.0 & !Self::CLOCK)
+    }
+    /// Check if converter clock gate is enabled.
+    #[inline]
+    pub const fn is_clock_enabled(self) -> bool {
+        self.0 & Self::CLOCK != 0
+    }
+    /// Set sample rate.
+    #[inline]
+    pub const fn set_sample_rate(self, val: SampleRate) -> Self {
+        Self((self.0 & !Self::SAMPLE_RATE) | ((val as u32) << 28))
+    }
+    /// Get sample rate.
+    #[inline]
+    pub const fn sample_rate(self) -> SampleRate {
+        SampleRate::from_bits((self.0 & Self::SAMPLE_RATE) >> 28)
+    }
+}
+
+/// Analog input and amplifier register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct InputConfig(u32);
+
+impl InputConfig {
+    const GAIN: u32 = 0xf;
+    const INPUT: u32 = 1 << 4;
+    const MIC_BIAS: u32 = 1 << 8;
+    const POWER: u32 = 1 << 9;
+
+    /// Set amplifier gain in decibels, rounded down to 3 dB steps up to [`MAX_GAIN`].
+    #[inline]
+    pub const fn set_gain(self, val: u8) -> Self {
+        let val = if val > MAX_GAIN { MAX_GAIN } else { val };
+        Self((self.0 & !Self::GAIN) | (val / 3) as u32)
+    }
+    /// Get amplifier gain in decibels.
+    #[inline]
+    pub const fn gain(self) -> u8 {
+        (self.0 & Self::GAIN) as u8 * 3
+    }
+    /// Set analog input source.
+    #[inline]
+    pub const fn set_input(self, val: Input) -> Self {
+        Self((self.0 & !Self::INPUT) | ((val as u32) << 4))
+    }
+    /// Get analog input source.
+    #[inline]
+    pub const fn input(self) -> Input {
+        match (self.0 & Self::INPUT) >> 4 {
+            0 => Input::Microphone,
+            _ => Input::LineIn,
+        }
+    }
+    /// Enable microphone bias supply.
+    #[inline]
+    pub const fn enable_mic_bias(self) -> Self {
+        Self(self.0 | Self::MIC_BIAS)
+    }
+    /// Disable microphone bias supply.
+    #[inline]
+    pub const fn disable_mic_bias(self) -> Self {
+        Self(self.0 & !Self::MIC_BIAS)
+    }
+    /// Check if microphone bias supply is enabled.
+    #[inline]
+    pub const fn is_mic_bias_enabled(self) -> bool {
+        self.0 & Self::MIC_BIAS != 0
+    }
+    /// Power up analog front end.
+    #[inline]
+    pub const fn enable_power(self) -> Self {
+        Self(self.0 | Self::POWER)
+    }
+    /// Power down analog front end.
+    #[inline]
+    pub const fn disable_power(self) -> Self {
+        Self(self.0 & !Self::POWER)
+    }
+    /// Check if analog front end is powered.
+    #[inline]
+    pub const fn is_power_enabled(self) -> bool {
+        self.0 & Self::POWER != 0
+    }
+}
+
+/// Highest amplifier gain in decibels.
+pub const MAX_GAIN: u8 = 42;
+
+/// Analog input source of the converter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Input {
+    /// Differential microphone input.
+    Microphone = 0,
+    /// Single-ended line input.
+    LineIn = 1,
+}
+
+/// Audio ADC configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Config {
+    /// Sample rate.
+    pub sample_rate: SampleRate,
+    /// Analog input source.
+    pub input: Input,
+    /// Amplifier gain in decibels.
+    pub gain: u8,
+    /// Digital volume in half decibel steps.
+    pub volume: i16,
+    /// Supply microphone bias.
+    pub mic_bias: bool,
+    /// Remove direct current offset.
+    pub high_pass: bool,
+}
+
+impl Default for Config {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            sample_rate: SampleRate::Hz16000,
+            input: Input::Microphone,
+            gain: 24,
+            volume: 0,
+            mic_bias: true,
+            high_pass: true,
+        }
+    }
+}
+
+impl Config {
+    /// Set sample rate.
+    #[inline]
+    pub const fn set_sample_rate(mut self, sample_rate: SampleRate) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+    /// Set analog input source, microphone bias is supplied for microphone input only.
+    #[inline]
+    pub const fn set_input(mut self, input: Input) -> Self {
+        self.input = input;
+        self.mic_bias = matches!(input, Input::Microphone);
+        self
+    }
+    /// Set amplifier gain in decibels.
+    #[inline]
+    pub const fn set_gain(mut self, gain: u8) -> Self {
+        self.gain = gain;
+        self
+    }
+    /// Set digital volume in half decibel steps.
+    #[inline]
+    pub const fn set_volume(mut self, volume: i16) -> Self {
+        self.volume = volume;
+        self
+    }
+}
+
+/// Managed audio Analog-Digital Converter.
+pub struct Auadc<ADC> {
+    adc: ADC,
+}
+
+impl<ADC: Deref<Target = RegisterBlock>> Auadc<ADC> {
+    /// Create and enable audio ADC with configuration.
+    ///
+    /// Audio PLL must run at a multiple of sample rate.
+    #[inline]
+    pub fn new(adc: ADC, config: Config, clocks: &Clocks) -> Result<Self, ConfigError> {
+        check_clock(config.sample_rate, clocks)?;
+        let mut input = InputConfig(0)
+            .set_input(config.input)
+            .set_gain(config.gain)
+            .enable_power();
+        if config.mic_bias {
+            input = input.enable_mic_bias();
+        }
+        let mut val = AdcConfig(0)
+            .set_sample_rate(config.sample_rate)
+            .enable_clock();
+        if config.high_pass {
+            val = val.enable_high_pass();
+        }
+        unsafe {
+            adc.config.write(val);
+            adc.input.write(input);
+            adc.volume.write(
+                VolumeConfig(0)
+                    .set_volume(config.volume)
+                    .enable_zero_cross()
+                    .update_volume(),
+            );
+            adc.fifo_config.write(FifoConfig(0).clear_fifo());
+            adc.config.modify(|v| v.enable_adc().enable_interface());
+        }
+        Ok(Self { adc })
+    }
+    /// Set amplifier gain in decibels.
+    #[inline]
+    pub fn set_gain(&mut self, gain: u8) {
+        unsafe { self.adc.input.modify(|v| v.set_gain(gain)) };
+    }
+    /// Get amplifier gain in decibels.
+    #[inline]
+    pub fn gain(&self) -> u8 {
+        self.adc.input.read().gain()
+    }
+    /// Set analog input source.
+    #[inline]
+    pub fn set_input(&mut self, input: Input) {
+        unsafe { self.adc.input.modify(|v| v.set_input(input)) };
+    }
+    /// Set digital volume in half decibel steps.
+    ///
+    /// Values are clamped to [`MIN_VOLUME`](super::MIN_VOLUME)..=[`MAX_VOLUME`](super::MAX_VOLUME).
+    #[inline]
+    pub fn set_volume(&mut self, volume: i16) {
+        unsafe {
+            self.adc
+                .volume
+                .modify(|v| v.set_volume(volume).update_volume())
+        };
+    }
+    /// Get digital volume in half decibel steps.
+    #[inline]
+    pub fn volume(&self) -> i16 {
+        self.adc.volume.read().volume()
+    }
+    /// Mute or unmute recorded samples.
+    #[inline]
+    pub fn set_mute(&mut self, mute: bool) {
+        unsafe {
+            self.adc.volume.modify(|v| {
+                if mute {
+                    v.enable_mute()
+                } else {
+                    v.disable_mute()
+                }
+            })
+        };
+    }
+    /// Check if recorded samples are muted.
+    #[inline]
+    pub fn is_muted(&self) -> bool {
+        self.adc.volume.read().is_mute_enabled()
+    }
+    /// Enable receive DMA requests.
+    #[inline]
+    pub fn enable_dma(&mut self, threshold: u8) {
+        unsafe {
+            self.adc
+                .fifo_config
+                .modify(|v| v.set_threshold(threshold).enable_dma())
+        };
+    }
+    /// Read samples from receive FIFO, blocking until the buffer is filled.
+    #[inline]
+    pub fn read_samples(&mut self, buf: &mut [u32]) {
+        for sample in buf {
+            while self.adc.fifo_status.read().available() == 0 {
+                core::hint::spin_loop();
+            }
+            *sample = self.adc.fifo_read.read();
+        }
+    }
+    /// Enable interrupt.
+    #[inline]
+    pub fn enable_interrupt(&mut self, val: Interrupt) {
+        unsafe { self.adc.fifo_config.modify(|v| v.enable_interrupt(val)) };
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub fn disable_interrupt(&mut self, val: Interrupt) {
+        unsafe { self.adc.fifo_config.modify(|v| v.disable_interrupt(val)) };
+    }
+    /// Check if interrupt flag is set.
+    ///
+    /// Converter interrupts are raised on the `audio` interrupt line.
+    #[inline]
+    pub fn has_interrupt(&self, val: Interrupt) -> bool {
+        self.adc.fifo_status.read().has_interrupt(val)
+    }
+    /// Clear interrupt flag.
+    #[inline]
+    pub fn clear_interrupt(&mut self, val: Interrupt) {
+        unsafe {
+            self.adc
+                .fifo_status
+                .write(FifoStatus::default().clear_interrupt(val))
+        };
+    }
+    /// Power down input, disable converter and release its peripheral.
+    #[inline]
+    pub fn free(self) -> ADC {
+        unsafe {
+            self.adc
+                .config
+                .modify(|v| v.disable_interface().disable_adc().disable_clock());
+            self.adc
+                .input
+                .modify(|v| v.disable_mic_bias().disable_power());
+        }
+        self.adc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AdcConfig, Input, InputConfig, RegisterBlock, SampleRate};
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, volume), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, input), 0x60);
+        assert_eq!(offset_of!(RegisterBlock, fifo_config), 0x88);
+        assert_eq!(offset_of!(RegisterBlock, fifo_status), 0x8c);
+        assert_eq!(offset_of!(RegisterBlock, fifo_read), 0x90);
+    }
+
+    #[test]
+    fn struct_adc_config_functions() {
+        let mut val = AdcConfig(0x0);
+
+        val = val.enable_adc();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_adc_enabled());
+        val = val.disable_adc();
+        assert!(!val.is_adc_enabled());
+
+        val = val.enable_interface();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_interface_enabled());
+        val = val.disable_interface();
+        assert!(!val.is_interface_enabled());
+
+        val = val.enable_high_pass();
+        assert_eq!(val.0, 0x0000_0004);
+        assert!(val.is_high_pass_enabled());
+        val = val.disable_high_pass();
+        assert!(!val.is_high_pass_enabled());
+
+        val = val.enable_clock();
+        assert_eq!(val.0, 0x0800_0000);
+        assert!(val.is_clock_enabled());
+        val = val.disable_clock();
+        assert!(!val.is_clock_enabled());
+
+        val = val.set_sample_rate(SampleRate::Hz16000);
+        assert_eq!(val.0, 0x1000_0000);
+        assert_eq!(val.sample_rate(), SampleRate::Hz16000);
+    }
+
+    #[test]
+    fn struct_input_config_functions() {
+        let mut val = InputConfig(0x0);
+
+        val = val.set_gain(42);
+        assert_eq!(val.0, 0x0000_000e);
+        assert_eq!(val.gain(), 42);
+        val = val.set_gain(100);
+        assert_eq!(val.gain(), 42);
+        val = val.set_gain(10);
+        assert_eq!(val.gain(), 9);
+        val = val.set_gain(0);
+
+        val = val.set_input(Input::LineIn);
+        assert_eq!(val.0, 0x0000_0010);
+        assert_eq!(val.input(), Input::LineIn);
+        val = val.set_input(Input::Microphone);
+        assert_eq!(val.input(), Input::Microphone);
+
+        val = val.enable_mic_bias();
+        assert_eq!(val.0, 0x0000_0100);
+        assert!(val.is_mic_bias_enabled());
+        val = val.disable_mic_bias();
+        assert!(!val.is_mic_bias_enabled());
+
+        val = val.enable_power();
+        assert_eq!(val.0, 0x0000_0200);
+        assert!(val.is_power_enabled());
+        val = val.disable_power();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_power_enabled());
+    }
 }
@@ -1,7 +1,418 @@
 //! Audio Digital-Analog Converter peripheral.
 
+use super::{
+    ConfigError, FifoConfig, FifoStatus, Interrupt, SampleRate, VolumeConfig, check_clock,
+};
+use crate::clocks::Clocks;
+use core::ops::Deref;
+use volatile_register::{RW, WO};
+
 /// Audio Digital-Analog Converter peripheral registers.
 #[repr(C)]
 pub struct RegisterBlock {
-    // todo fields
+    /// Converter enable and sample rate register.
+    pub config: RW<DacConfig>,
+    _reserved0: [u8; 0x4],
+    /// Digital volume and mute register.
+    pub volume: RW<VolumeConfig>,
+    _reserved1: [u8; 0x8],
+    /// Analog output routing register.
+    pub output: RW<OutputConfig>,
+    _reserved2: [u8; 0x74],
+    /// Transmit FIFO configuration register.
+    pub fifo_config: RW<FifoConfig>,
+    /// Transmit FIFO status register.
+    pub fifo_status: RW<FifoStatus>,
+    /// Transmit FIFO data register.
+    pub fifo_write: WO<u32>,
+}
+
+/// Converter enable and sample rate register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct DacConfig(u32);
+
+impl DacConfig {
+    const ENABLE: u32 = 1 << 0;
+    const INTERFACE: u32 = 1 << 1;
+    const CLOCK: u32 = 1 << 27;
+    const SAMPLE_RATE: u32 = 0x7 << 28;
+
+    /// Enable converter.
+    #[inline]
+    pub const fn enable_dac(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable converter.
+    #[inline]
+    pub const fn disable_dac(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if converter is enabled.
+    #[inline]
+    pub const fn is_dac_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Enable sample interface from FIFO.
+    #[inline]
+    pub const fn enable_interface(self) -> Self {
+        Self(self.0 | Self::INTERFACE)
+    }
+    /// Disable sample interface from FIFO.
+    #[inline]
+    pub const fn disable_interface(self) -> Self {
+        Self(self.0 & !Self::INTERFACE)
+    }
+    /// Check if sample interface is enabled.
+    #[inline]
+    pub const fn is_interface_enabled(self) -> bool {
+        self.0 & Self::INTERFACE != 0
+    }
+    /// Enable converter clock gate.
+    #[inline]
+    pub const fn enable_clock(self) -> Self {
+        Self(self.0 | Self::CLOCK)
+    }
+    /// Disable converter clock gate.
+    #[inline]
+    pub const fn disable_clock(self) -> Self {
+        Self(self.0 & !Self::CLOCK)
+    }
+    /// Check if converter clock gate is enabled.
+    #[inline]
+    pub const fn is_clock_enabled(self) -> bool {
+        self.0 & Self::CLOCK != 0
+    }
+    /// Set sample rate.
+    #[inline]
+    pub const fn set_sample_rate(self, val: SampleRate) -> Self {
+        Self((self.0 & !Self::SAMPLE_RATE) | ((val as u32) << 28))
+    }
+    /// Get sample rate.
+    #[inline]
+    pub const fn sample_rate(self) -> SampleRate {
+        SampleRate::from_bits((self.0 & Self::SAMPLE_RATE) >> 28)
+    }
+}
+
+/// Analog output routing register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct OutputConfig(u32);
+
+impl OutputConfig {
+    const ROUTE: u32 = 0x3;
+    const HEADPHONE_GAIN: u32 = 0x3 << 4;
+
+    /// Set analog outputs driven by converter.
+    #[inline]
+    pub const fn set_route(self, val: Route) -> Self {
+        Self((self.0 & !Self::ROUTE) | (val as u32))
+    }
+    /// Get analog outputs driven by converter.
+    #[inline]
+    pub const fn route(self) -> Route {
+        match self.0 & Self::ROUTE {
+            0 => Route::None,
+            1 => Route::Headphone,
+            2 => Route::LineOut,
+            3 => Route::Both,
+            _ => unreachable!(),
+        }
+    }
+    /// Set headphone amplifier gain.
+    #[inline]
+    pub const fn set_headphone_gain(self, val: HeadphoneGain) -> Self {
+        Self((self.0 & !Self::HEADPHONE_GAIN) | ((val as u32) << 4))
+    }
+    /// Get headphone amplifier gain.
+    #[inline]
+    pub const fn headphone_gain(self) -> HeadphoneGain {
+        match (self.0 & Self::HEADPHONE_GAIN) >> 4 {
+            0 => HeadphoneGain::Db0,
+            1 => HeadphoneGain::Db3,
+            2 => HeadphoneGain::Db6,
+            3 => HeadphoneGain::Db9,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Analog outputs driven by the converter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Route {
+    /// All outputs powered down.
+    None = 0,
+    /// Headphone amplifier only.
+    Headphone = 1,
+    /// Line output only.
+    LineOut = 2,
+    /// Headphone amplifier and line output.
+    Both = 3,
+}
+
+/// Headphone amplifier gain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum HeadphoneGain {
+    /// 0 dB.
+    Db0 = 0,
+    /// +3 dB.
+    Db3 = 1,
+    /// +6 dB.
+    Db6 = 2,
+    /// +9 dB.
+    Db9 = 3,
+}
+
+/// Audio DAC configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Config {
+    /// Sample rate.
+    pub sample_rate: SampleRate,
+    /// Digital volume in half decibel steps.
+    pub volume: i16,
+    /// Start muted.
+    pub mute: bool,
+    /// Analog outputs driven.
+    pub route: Route,
+    /// Headphone amplifier gain.
+    pub headphone_gain: HeadphoneGain,
+}
+
+impl Default for Config {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            sample_rate: SampleRate::Hz48000,
+            volume: 0,
+            mute: false,
+            route: Route::Headphone,
+            headphone_gain: HeadphoneGain::Db0,
+        }
+    }
+}
+
+impl Config {
+    /// Set sample rate.
+    #[inline]
+    pub const fn set_sample_rate(mut self, sample_rate: SampleRate) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+    /// Set digital volume in half decibel steps.
+    #[inline]
+    pub const fn set_volume(mut self, volume: i16) -> Self {
+        self.volume = volume;
+        self
+    }
+    /// Set analog outputs driven.
+    #[inline]
+    pub const fn set_route(mut self, route: Route) -> Self {
+        self.route = route;
+        self
+    }
+}
+
+/// Managed audio Digital-Analog Converter.
+pub struct Audac<DAC> {
+    dac: DAC,
+}
+
+impl<DAC: Deref<Target = RegisterBlock>> Audac<DAC> {
+    /// Create and enable audio DAC with configuration.
+    ///
+    /// Audio PLL must run at a multiple of sample rate.
+    #[inline]
+    pub fn new(dac: DAC, config: Config, clocks: &Clocks) -> Result<Self, ConfigError> {
+        check_clock(config.sample_rate, clocks)?;
+        let mut volume = VolumeConfig(0)
+            .set_volume(config.volume)
+            .enable_ramp()
+            .enable_zero_cross();
+        if config.mute {
+            volume = volume.enable_mute();
+        }
+        unsafe {
+            dac.config.write(
+                DacConfig(0)
+                    .set_sample_rate(config.sample_rate)
+                    .enable_clock(),
+            );
+            dac.fifo_config.write(FifoConfig(0).clear_fifo());
+            dac.volume.write(volume.update_volume());
+            dac.output.write(
+                OutputConfig(0)
+                    .set_route(config.route)
+                    .set_headphone_gain(config.headphone_gain),
+            );
+            dac.config.modify(|v| v.enable_dac().enable_interface());
+        }
+        Ok(Self { dac })
+    }
+    /// Set digital volume in half decibel steps.
+    ///
+    /// Values are clamped to [`MIN_VOLUME`](super::MIN_VOLUME)..=[`MAX_VOLUME`](super::MAX_VOLUME).
+    #[inline]
+    pub fn set_volume(&mut self, volume: i16) {
+        unsafe {
+            self.dac
+                .volume
+                .modify(|v| v.set_volume(volume).update_volume())
+        };
+    }
+    /// Get digital volume in half decibel steps.
+    #[inline]
+    pub fn volume(&self) -> i16 {
+        self.dac.volume.read().volume()
+    }
+    /// Mute or unmute output, ramping volume to avoid clicks.
+    #[inline]
+    pub fn set_mute(&mut self, mute: bool) {
+        unsafe {
+            self.dac.volume.modify(|v| {
+                if mute {
+                    v.enable_mute()
+                } else {
+                    v.disable_mute()
+                }
+            })
+        };
+    }
+    /// Check if output is muted.
+    #[inline]
+    pub fn is_muted(&self) -> bool {
+        self.dac.volume.read().is_mute_enabled()
+    }
+    /// Set analog outputs driven.
+    #[inline]
+    pub fn set_route(&mut self, route: Route) {
+        unsafe { self.dac.output.modify(|v| v.set_route(route)) };
+    }
+    /// Set headphone amplifier gain.
+    #[inline]
+    pub fn set_headphone_gain(&mut self, gain: HeadphoneGain) {
+        unsafe { self.dac.output.modify(|v| v.set_headphone_gain(gain)) };
+    }
+    /// Enable transmit DMA requests.
+    #[inline]
+    pub fn enable_dma(&mut self, threshold: u8) {
+        unsafe {
+            self.dac
+                .fifo_config
+                .modify(|v| v.set_threshold(threshold).enable_dma())
+        };
+    }
+    /// Write samples into transmit FIFO, blocking until all samples are queued.
+    #[inline]
+    pub fn write_samples(&mut self, samples: &[u32]) {
+        for &sample in samples {
+            while self.dac.fifo_status.read().available() == 0 {
+                core::hint::spin_loop();
+            }
+            unsafe { self.dac.fifo_write.write(sample) };
+        }
+    }
+    /// Enable interrupt.
+    #[inline]
+    pub fn enable_interrupt(&mut self, val: Interrupt) {
+        unsafe { self.dac.fifo_config.modify(|v| v.enable_interrupt(val)) };
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub fn disable_interrupt(&mut self, val: Interrupt) {
+        unsafe { self.dac.fifo_config.modify(|v| v.disable_interrupt(val)) };
+    }
+    /// Check if interrupt flag is set.
+    ///
+    /// Converter interrupts are raised on the `audio` interrupt line.
+    #[inline]
+    pub fn has_interrupt(&self, val: Interrupt) -> bool {
+        self.dac.fifo_status.read().has_interrupt(val)
+    }
+    /// Clear interrupt flag.
+    #[inline]
+    pub fn clear_interrupt(&mut self, val: Interrupt) {
+        unsafe {
+            self.dac
+                .fifo_status
+                .write(FifoStatus::default().clear_interrupt(val))
+        };
+    }
+    /// Power down outputs, disable converter and release its peripheral.
+    #[inline]
+    pub fn free(self) -> DAC {
+        unsafe {
+            self.dac.output.modify(|v| v.set_route(Route::None));
+            self.dac
+                .config
+                .modify(|v| v.disable_interface().disable_dac().disable_clock());
+        }
+        self.dac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DacConfig, HeadphoneGain, OutputConfig, RegisterBlock, Route, SampleRate};
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, volume), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, output), 0x14);
+        assert_eq!(offset_of!(RegisterBlock, fifo_config), 0x8c);
+        assert_eq!(offset_of!(RegisterBlock, fifo_status), 0x90);
+        assert_eq!(offset_of!(RegisterBlock, fifo_write), 0x94);
+    }
+
+    #[test]
+    fn struct_dac_config_functions() {
+        let mut val = DacConfig(0x0);
+
+        val = val.enable_dac();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_dac_enabled());
+        val = val.disable_dac();
+        assert!(!val.is_dac_enabled());
+
+        val = val.enable_interface();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_interface_enabled());
+        val = val.disable_interface();
+        assert!(!val.is_interface_enabled());
+
+        val = val.enable_clock();
+        assert_eq!(val.0, 0x0800_0000);
+        assert!(val.is_clock_enabled());
+        val = val.disable_clock();
+        assert!(!val.is_clock_enabled());
+
+        val = val.set_sample_rate(SampleRate::Hz48000);
+        assert_eq!(val.0, 0x6000_0000);
+        assert_eq!(val.sample_rate(), SampleRate::Hz48000);
+        val = val.set_sample_rate(SampleRate::Hz8000);
+        assert_eq!(val.0, 0x0000_0000);
+        assert_eq!(val.sample_rate(), SampleRate::Hz8000);
+    }
+
+    #[test]
+    fn struct_output_config_functions() {
+        let mut val = OutputConfig(0x0);
+        assert_eq!(val.route(), Route::None);
+
+        val = val.set_route(Route::Both);
+        assert_eq!(val.0, 0x0000_0003);
+        assert_eq!(val.route(), Route::Both);
+        val = val.set_route(Route::LineOut);
+        assert_eq!(val.0, 0x0000_0002);
+        assert_eq!(val.route(), Route::LineOut);
+        val = val.set_route(Route::None);
+
+        val = val.set_headphone_gain(HeadphoneGain::Db9);
+        assert_eq!(val.0, 0x0000_0030);
+        assert_eq!(val.headphone_gain(), HeadphoneGain::Db9);
+    }
 }
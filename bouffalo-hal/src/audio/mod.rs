@@ -1,4 +1,365 @@
 //! Audio processing peripherals.
+//!
+//! The internal audio codec consists of an Analog-Digital Converter for microphone and
+//! line inputs ([`auadc`]) and a Digital-Analog Converter driving headphone and line
+//! outputs ([`audac`]). Both converters are clocked from audio PLL, share the same
+//! digital volume and FIFO register layout, and raise the `audio` interrupt.
 
 pub mod auadc;
 pub mod audac;
+
+use crate::clocks::Clocks;
+use embedded_time::rate::Hertz;
+
+/// Converter sample rate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum SampleRate {
+    /// 8 kHz.
+    Hz8000 = 0,
+    /// 16 kHz.
+    Hz16000 = 1,
+    /// 22.05 kHz.
+    Hz22050 = 2,
+    /// 24 kHz.
+    Hz24000 = 3,
+    /// 32 kHz.
+    Hz32000 = 4,
+    /// 44.1 kHz.
+    Hz44100 = 5,
+    /// 48 kHz.
+    Hz48000 = 6,
+}
+
+impl SampleRate {
+    #[inline]
+    const fn from_bits(bits: u32) -> Self {
+        match bits {
+            0 => SampleRate::Hz8000,
+            1 => SampleRate::Hz16000,
+            2 => SampleRate::Hz22050,
+            3 => SampleRate::Hz24000,
+            4 => SampleRate::Hz32000,
+            5 => SampleRate::Hz44100,
+            _ => SampleRate::Hz48000,
+        }
+    }
+    /// Sample rate in hertz.
+    #[inline]
+    pub const fn hertz(self) -> Hertz {
+        Hertz(match self {
+            SampleRate::Hz8000 => 8_000,
+            SampleRate::Hz16000 => 16_000,
+            SampleRate::Hz22050 => 22_050,
+            SampleRate::Hz24000 => 24_000,
+            SampleRate::Hz32000 => 32_000,
+            SampleRate::Hz44100 => 44_100,
+            SampleRate::Hz48000 => 48_000,
+        })
+    }
+}
+
+/// Digital volume and mute register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct VolumeConfig(u32);
+
+impl VolumeConfig {
+    const VOLUME: u32 = 0x1ff;
+    const UPDATE: u32 = 1 << 9;
+    const MUTE: u32 = 1 << 10;
+    const RAMP: u32 = 1 << 11;
+    const ZERO_CROSS: u32 = 1 << 12;
+
+    /// Set volume in half decibel steps, clamped to [`MIN_VOLUME`]..=[`MAX_VOLUME`].
+    #[inline]
+    pub const fn set_volume(self, val: i16) -> Self {
+        let val = if val < MIN_VOLUME {
+            MIN_VOLUME
+        } else if val > MAX_VOLUME {
+            MAX_VOLUME
+        } else {
+            val
+        };
+        Self((self.0 & !Self::VOLUME) | (val as u32 & Self::VOLUME))
+    }
+    /// Get volume in half decibel steps.
+    #[inline]
+    pub const fn volume(self) -> i16 {
+        // Sign extend 9-bit two's complement field.
+        (((self.0 & Self::VOLUME) << 7) as u16 as i16) >> 7
+    }
+    /// Apply volume value on next sample.
+    #[inline]
+    pub const fn update_volume(self) -> Self {
+        Self(self.0 | Self::UPDATE)
+    }
+    /// Check if a volume update is in progress.
+    #[inline]
+    pub const fn is_volume_updating(self) -> bool {
+        self.0 & Self::UPDATE != 0
+    }
+    /// Mute converter.
+    #[inline]
+    pub const fn enable_mute(self) -> Self {
+        Self(self.0 | Self::MUTE)
+    }
+    /// Unmute converter.
+    #[inline]
+    pub const fn disable_mute(self) -> Self {
+        Self(self.0 & !Self::MUTE)
+    }
+    /// Check if converter is muted.
+    #[inline]
+    pub const fn is_mute_enabled(self) -> bool {
+        self.0 & Self::MUTE != 0
+    }
+    /// Ramp volume and mute changes gradually.
+    #[inline]
+    pub const fn enable_ramp(self) -> Self {
+        Self(self.0 | Self::RAMP)
+    }
+    /// Apply volume and mute changes at once.
+    #[inline]
+    pub const fn disable_ramp(self) -> Self {
+        Self(self.0 & !Self::RAMP)
+    }
+    /// Check if volume ramp is enabled.
+    #[inline]
+    pub const fn is_ramp_enabled(self) -> bool {
+        self.0 & Self::RAMP != 0
+    }
+    /// Apply volume changes on signal zero crossing.
+    #[inline]
+    pub const fn enable_zero_cross(self) -> Self {
+        Self(self.0 | Self::ZERO_CROSS)
+    }
+    /// Apply volume changes regardless of signal level.
+    #[inline]
+    pub const fn disable_zero_cross(self) -> Self {
+        Self(self.0 & !Self::ZERO_CROSS)
+    }
+    /// Check if zero crossing detection is enabled.
+    #[inline]
+    pub const fn is_zero_cross_enabled(self) -> bool {
+        self.0 & Self::ZERO_CROSS != 0
+    }
+}
+
+/// Lowest digital volume, -95.5 dB in half decibel steps.
+pub const MIN_VOLUME: i16 = -191;
+/// Highest digital volume, +18 dB in half decibel steps.
+pub const MAX_VOLUME: i16 = 36;
+
+/// Sample FIFO configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct FifoConfig(u32);
+
+impl FifoConfig {
+    const DMA: u32 = 1 << 0;
+    const CLEAR: u32 = 1 << 1;
+    const INTERRUPT: u32 = 0x7 << 8;
+    const THRESHOLD: u32 = 0x1f << 16;
+
+    /// Enable DMA requests.
+    #[inline]
+    pub const fn enable_dma(self) -> Self {
+        Self(self.0 | Self::DMA)
+    }
+    /// Disable DMA requests.
+    #[inline]
+    pub const fn disable_dma(self) -> Self {
+        Self(self.0 & !Self::DMA)
+    }
+    /// Check if DMA requests are enabled.
+    #[inline]
+    pub const fn is_dma_enabled(self) -> bool {
+        self.0 & Self::DMA != 0
+    }
+    /// Clear FIFO.
+    #[inline]
+    pub const fn clear_fifo(self) -> Self {
+        Self(self.0 | Self::CLEAR)
+    }
+    /// Enable interrupt.
+    #[inline]
+    pub const fn enable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32 + 8)))
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub const fn disable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 & !(1 << (val as u32 + 8)))
+    }
+    /// Check if interrupt is enabled.
+    #[inline]
+    pub const fn is_interrupt_enabled(self, val: Interrupt) -> bool {
+        self.0 & Self::INTERRUPT & (1 << (val as u32 + 8)) != 0
+    }
+    /// Set FIFO level in samples that raises requests.
+    #[inline]
+    pub const fn set_threshold(self, val: u8) -> Self {
+        Self((self.0 & !Self::THRESHOLD) | (((val as u32) << 16) & Self::THRESHOLD))
+    }
+    /// Get FIFO level in samples that raises requests.
+    #[inline]
+    pub const fn threshold(self) -> u8 {
+        ((self.0 & Self::THRESHOLD) >> 16) as u8
+    }
+}
+
+/// Sample FIFO status register.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct FifoStatus(u32);
+
+impl FifoStatus {
+    const COUNT: u32 = 0x3f << 16;
+
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub const fn has_interrupt(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32)) != 0
+    }
+    /// Clear interrupt flag, write-one-to-clear.
+    #[inline]
+    pub const fn clear_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32)))
+    }
+    /// Free slots in transmit FIFO or filled slots in receive FIFO, in samples.
+    #[inline]
+    pub const fn available(self) -> u8 {
+        ((self.0 & Self::COUNT) >> 16) as u8
+    }
+}
+
+/// Audio converter interrupt event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Interrupt {
+    /// FIFO level reached threshold.
+    FifoRequest = 0,
+    /// Transmit FIFO ran empty or receive FIFO was read empty.
+    Underrun = 1,
+    /// Transmit FIFO was written full or receive FIFO overflowed.
+    Overrun = 2,
+}
+
+/// Errors on audio codec configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConfigError {
+    /// Audio PLL is not running.
+    ClockSource,
+    /// Sample rate cannot be derived from audio PLL frequency.
+    SampleRate,
+}
+
+/// Check that audio PLL frequency is a multiple of sample rate.
+#[inline]
+fn check_clock(rate: SampleRate, clocks: &Clocks) -> Result<(), ConfigError> {
+    let Some(pll) = clocks.audio_pll() else {
+        return Err(ConfigError::ClockSource);
+    };
+    if !pll.0.is_multiple_of(rate.hertz().0) {
+        return Err(ConfigError::SampleRate);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FifoConfig, FifoStatus, Interrupt, SampleRate, VolumeConfig};
+
+    #[test]
+    fn struct_volume_config_functions() {
+        let mut val = VolumeConfig(0x0);
+
+        val = val.set_volume(-1);
+        assert_eq!(val.0, 0x0000_01ff);
+        assert_eq!(val.volume(), -1);
+        val = val.set_volume(-500);
+        assert_eq!(val.volume(), -191);
+        val = val.set_volume(100);
+        assert_eq!(val.0, 0x0000_0024);
+        assert_eq!(val.volume(), 36);
+        val = val.set_volume(0);
+
+        val = val.update_volume();
+        assert_eq!(val.0, 0x0000_0200);
+        assert!(val.is_volume_updating());
+        val = VolumeConfig(0x0);
+
+        val = val.enable_mute();
+        assert_eq!(val.0, 0x0000_0400);
+        assert!(val.is_mute_enabled());
+        val = val.disable_mute();
+        assert!(!val.is_mute_enabled());
+
+        val = val.enable_ramp();
+        assert_eq!(val.0, 0x0000_0800);
+        assert!(val.is_ramp_enabled());
+        val = val.disable_ramp();
+        assert!(!val.is_ramp_enabled());
+
+        val = val.enable_zero_cross();
+        assert_eq!(val.0, 0x0000_1000);
+        assert!(val.is_zero_cross_enabled());
+        val = val.disable_zero_cross();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_zero_cross_enabled());
+    }
+
+    #[test]
+    fn struct_fifo_config_functions() {
+        let mut val = FifoConfig(0x0);
+
+        val = val.enable_dma();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_dma_enabled());
+        val = val.disable_dma();
+        assert!(!val.is_dma_enabled());
+
+        val = val.clear_fifo();
+        assert_eq!(val.0, 0x0000_0002);
+        val = FifoConfig(0x0);
+
+        val = val.enable_interrupt(Interrupt::Overrun);
+        assert_eq!(val.0, 0x0000_0400);
+        assert!(val.is_interrupt_enabled(Interrupt::Overrun));
+        assert!(!val.is_interrupt_enabled(Interrupt::FifoRequest));
+        val = val.disable_interrupt(Interrupt::Overrun);
+        assert!(!val.is_interrupt_enabled(Interrupt::Overrun));
+
+        val = val.set_threshold(0x1f);
+        assert_eq!(val.0, 0x001f_0000);
+        assert_eq!(val.threshold(), 0x1f);
+    }
+
+    #[test]
+    fn struct_fifo_status_functions() {
+        let val = FifoStatus(0x0020_0002);
+        assert!(val.has_interrupt(Interrupt::Underrun));
+        assert!(!val.has_interrupt(Interrupt::FifoRequest));
+        assert_eq!(val.available(), 0x20);
+
+        let val = FifoStatus::default().clear_interrupt(Interrupt::FifoRequest);
+        assert_eq!(val.0, 0x0000_0001);
+    }
+
+    #[test]
+    fn enum_sample_rate_bits() {
+        for rate in [
+            SampleRate::Hz8000,
+            SampleRate::Hz16000,
+            SampleRate::Hz22050,
+            SampleRate::Hz24000,
+            SampleRate::Hz32000,
+            SampleRate::Hz44100,
+            SampleRate::Hz48000,
+        ] {
+            assert_eq!(SampleRate::from_bits(rate as u32), rate);
+        }
+        assert_eq!(SampleRate::Hz44100.hertz().0, 44_100);
+    }
+}
@@ -0,0 +1,468 @@
+//! Bluetooth Low Energy controller HCI transport.
+//!
+//! The link layer runs in the vendor BLE controller library, which exchanges HCI commands,
+//! events and ACL data through its on-chip interface rather than a serial line. [`Hci`]
+//! converts this interface into an H4 byte stream, where every packet starts with its
+//! [`PacketType`] indicator, so host stacks written for UART transports (`bleps`, or
+//! `trouble` through `bt-hci` serial transport) can run on top of it unchanged.
+//!
+//! The controller library and the runtime it depends on must be linked into the
+//! application; this module only declares its symbols.
+
+use atomic_waker::AtomicWaker;
+use core::{
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    task::Poll,
+};
+
+/// H4 packet type indicator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum PacketType {
+    /// Command from host to controller.
+    Command = 0x01,
+    /// Asynchronous connection-oriented data in both directions.
+    AclData = 0x02,
+    /// Event from controller to host.
+    Event = 0x04,
+}
+
+impl PacketType {
+    #[inline]
+    const fn from_indicator(val: u8) -> Option<Self> {
+        match val {
+            0x01 => Some(PacketType::Command),
+            0x02 => Some(PacketType::AclData),
+            0x04 => Some(PacketType::Event),
+            _ => None,
+        }
+    }
+    /// Length of packet header following the indicator.
+    #[inline]
+    const fn header_len(self) -> usize {
+        match self {
+            PacketType::Command | PacketType::Event => 3,
+            PacketType::AclData => 4,
+        }
+    }
+    /// Length of payload following the header.
+    #[inline]
+    const fn payload_len(self, header: &[u8]) -> usize {
+        match self {
+            PacketType::Command => header[2] as usize,
+            PacketType::Event => header[1] as usize,
+            PacketType::AclData => u16::from_le_bytes([header[2], header[3]]) as usize,
+        }
+    }
+}
+
+/// Errors on HCI transport.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// Packet starts with an unknown or host-bound type indicator.
+    PacketType,
+    /// Packet does not fit controller interface.
+    TooLong,
+    /// Controller rejected the packet with status code.
+    Controller(i8),
+}
+
+impl embedded_io::Error for Error {
+    #[inline]
+    fn kind(&self) -> embedded_io::ErrorKind {
+        match self {
+            Error::PacketType | Error::TooLong => embedded_io::ErrorKind::InvalidData,
+            Error::Controller(_) => embedded_io::ErrorKind::Other,
+        }
+    }
+}
+
+/// Largest H4 packet accepted from host, a command with 255 parameter bytes.
+const TRANSMIT_BUFFER: usize = 1 + 3 + 255;
+/// Size of the controller to host byte ring, a power of two.
+const RECEIVE_BUFFER: usize = 1024;
+
+/// Bluetooth controller as an H4 byte stream.
+pub struct Hci {
+    transmit: [u8; TRANSMIT_BUFFER],
+    transmit_len: usize,
+}
+
+impl Hci {
+    /// Start the vendor controller and attach its on-chip HCI interface.
+    ///
+    /// `task_priority` is passed to the controller runtime. Returns `None` if the
+    /// controller is already in use.
+    #[inline]
+    pub fn new(task_priority: u8) -> Option<Self> {
+        if TAKEN.swap(true, Ordering::AcqRel) {
+            return None;
+        }
+        RECEIVE.reset();
+        unsafe {
+            ffi::ble_controller_init(task_priority);
+            ffi::bt_onchiphci_interface_init(on_controller_packet);
+        }
+        Some(Self {
+            transmit: [0; TRANSMIT_BUFFER],
+            transmit_len: 0,
+        })
+    }
+    /// Check if packets from controller are waiting to be read.
+    #[inline]
+    pub fn has_received(&self) -> bool {
+        !RECEIVE.is_empty()
+    }
+    /// Check if controller packets were dropped because host did not read in time.
+    ///
+    /// Clears the flag.
+    #[inline]
+    pub fn take_overflow(&mut self) -> bool {
+        RECEIVE.overflow.swap(false, Ordering::Relaxed)
+    }
+    /// Stop the controller.
+    #[inline]
+    pub fn free(self) {
+        unsafe { ffi::ble_controller_deinit() };
+        TAKEN.store(false, Ordering::Release);
+    }
+    /// Queue bytes of H4 packets, sending each packet once it is complete.
+    #[inline]
+    fn push(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        for &byte in buf {
+            if self.transmit_len == 0
+                && !matches!(
+                    PacketType::from_indicator(byte),
+                    Some(PacketType::Command | PacketType::AclData)
+                )
+            {
+                return Err(Error::PacketType);
+            }
+            self.transmit[self.transmit_len] = byte;
+            self.transmit_len += 1;
+            // Indicator was checked on packet start.
+            let ty = match self.transmit[0] {
+                0x01 => PacketType::Command,
+                _ => PacketType::AclData,
+            };
+            let header_end = 1 + ty.header_len();
+            if self.transmit_len < header_end {
+                continue;
+            }
+            let total = header_end + ty.payload_len(&self.transmit[1..header_end]);
+            if total > TRANSMIT_BUFFER {
+                self.transmit_len = 0;
+                return Err(Error::TooLong);
+            }
+            if self.transmit_len == total {
+                self.transmit_len = 0;
+                send(ty, &self.transmit[1..total])?;
+            }
+        }
+        Ok(buf.len())
+    }
+}
+
+/// Send one complete host packet, `packet` excludes the indicator.
+#[inline]
+fn send(ty: PacketType, packet: &[u8]) -> Result<(), Error> {
+    let mut raw = match ty {
+        PacketType::Command => ffi::HciPacket {
+            command: ffi::HciCommand {
+                opcode: u16::from_le_bytes([packet[0], packet[1]]),
+                param_len: packet[2],
+                params: packet[3..].as_ptr(),
+            },
+        },
+        PacketType::AclData => {
+            let handle = u16::from_le_bytes([packet[0], packet[1]]);
+            ffi::HciPacket {
+                acl_data: ffi::HciAclData {
+                    handle: handle & 0x0fff,
+                    pb_bc_flag: (handle >> 12) as u8,
+                    len: u16::from_le_bytes([packet[2], packet[3]]),
+                    buffer: packet[4..].as_ptr(),
+                },
+            }
+        }
+        PacketType::Event => return Err(Error::PacketType),
+    };
+    let vendor_type = match ty {
+        PacketType::Command => ffi::BT_HCI_CMD,
+        _ => ffi::BT_HCI_ACL_DATA,
+    };
+    match unsafe { ffi::bt_onchiphci_send(vendor_type, 0, &mut raw) } {
+        0 => Ok(()),
+        code => Err(Error::Controller(code)),
+    }
+}
+
+static TAKEN: AtomicBool = AtomicBool::new(false);
+static RECEIVE: ReceiveRing = ReceiveRing::new();
+
+/// Single producer, single consumer ring of H4 bytes from controller to host.
+struct ReceiveRing {
+    buffer: [core::cell::UnsafeCell<u8>; RECEIVE_BUFFER],
+    // Free running indices, masked on access.
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    overflow: AtomicBool,
+    waker: AtomicWaker,
+}
+
+// Bytes between tail and head are owned by the consumer, others by the producer.
+unsafe impl Sync for ReceiveRing {}
+
+impl ReceiveRing {
+    #[inline]
+    const fn new() -> Self {
+        Self {
+            buffer: [const { core::cell::UnsafeCell::new(0) }; RECEIVE_BUFFER],
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            overflow: AtomicBool::new(false),
+            waker: AtomicWaker::new(),
+        }
+    }
+    #[inline]
+    fn reset(&self) {
+        self.tail
+            .store(self.head.load(Ordering::Acquire), Ordering::Release);
+        self.overflow.store(false, Ordering::Relaxed);
+    }
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire) == self.tail.load(Ordering::Relaxed)
+    }
+    /// Append a whole packet made of `parts`, or drop it if it does not fit.
+    #[inline]
+    fn push_packet(&self, parts: &[&[u8]]) {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        let len: usize = parts.iter().map(|p| p.len()).sum();
+        if RECEIVE_BUFFER - head.wrapping_sub(tail) < len {
+            self.overflow.store(true, Ordering::Relaxed);
+            return;
+        }
+        let mut index = head;
+        for &byte in parts.iter().flat_map(|p| p.iter()) {
+            unsafe { *self.buffer[index % RECEIVE_BUFFER].get() = byte };
+            index = index.wrapping_add(1);
+        }
+        self.head.store(index, Ordering::Release);
+        self.waker.wake();
+    }
+    #[inline]
+    fn pop(&self, buf: &mut [u8]) -> usize {
+        let head = self.head.load(Ordering::Acquire);
+        let mut tail = self.tail.load(Ordering::Relaxed);
+        let mut n = 0;
+        while n < buf.len() && tail != head {
+            buf[n] = unsafe { *self.buffer[tail % RECEIVE_BUFFER].get() };
+            tail = tail.wrapping_add(1);
+            n += 1;
+        }
+        self.tail.store(tail, Ordering::Release);
+        n
+    }
+}
+
+/// Rebuild H4 packets from vendor on-chip interface callbacks.
+extern "C" fn on_controller_packet(pkt_type: u8, src_id: u16, param: *const u8, param_len: u8) {
+    let params = if param.is_null() {
+        &[][..]
+    } else {
+        unsafe { core::slice::from_raw_parts(param, param_len as usize) }
+    };
+    let [id_low, id_high] = src_id.to_le_bytes();
+    let event = PacketType::Event as u8;
+    match pkt_type {
+        ffi::BT_HCI_CMD_CMP_EVT => RECEIVE.push_packet(&[
+            &[event, 0x0e, param_len.saturating_add(3), 1, id_low, id_high],
+            params,
+        ]),
+        ffi::BT_HCI_CMD_STAT_EVT => {
+            let status = params.first().copied().unwrap_or(0);
+            RECEIVE.push_packet(&[&[event, 0x0f, 4, status, 1, id_low, id_high]])
+        }
+        ffi::BT_HCI_LE_EVT => RECEIVE.push_packet(&[&[event, 0x3e, param_len], params]),
+        ffi::BT_HCI_EVT => RECEIVE.push_packet(&[&[event, id_low, param_len], params]),
+        ffi::BT_HCI_ACL_DATA => {
+            // Controller reports whole packets; mark them as first flushable fragments.
+            let handle = if src_id & 0xf000 == 0 {
+                src_id | 0x2000
+            } else {
+                src_id
+            };
+            let [handle_low, handle_high] = handle.to_le_bytes();
+            RECEIVE.push_packet(&[
+                &[
+                    PacketType::AclData as u8,
+                    handle_low,
+                    handle_high,
+                    param_len,
+                    0,
+                ],
+                params,
+            ])
+        }
+        _ => {}
+    }
+}
+
+impl embedded_io::ErrorType for Hci {
+    type Error = Error;
+}
+
+impl embedded_io::Read for Hci {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let n = RECEIVE.pop(buf);
+            if n > 0 {
+                return Ok(n);
+            }
+            core::hint::spin_loop();
+        }
+    }
+}
+
+impl embedded_io::ReadReady for Hci {
+    #[inline]
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.has_received())
+    }
+}
+
+impl embedded_io::Write for Hci {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.push(buf)
+    }
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl embedded_io_async::Read for Hci {
+    #[inline]
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        core::future::poll_fn(|cx| {
+            RECEIVE.waker.register(cx.waker());
+            match RECEIVE.pop(buf) {
+                0 => Poll::Pending,
+                n => Poll::Ready(Ok(n)),
+            }
+        })
+        .await
+    }
+}
+
+impl embedded_io_async::Write for Hci {
+    #[inline]
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.push(buf)
+    }
+}
+
+mod ffi {
+    //! Symbols of the vendor BLE controller library.
+
+    // Vendor on-chip packet types.
+    pub const BT_HCI_CMD: u8 = 0;
+    pub const BT_HCI_ACL_DATA: u8 = 1;
+    pub const BT_HCI_CMD_CMP_EVT: u8 = 2;
+    pub const BT_HCI_CMD_STAT_EVT: u8 = 3;
+    pub const BT_HCI_LE_EVT: u8 = 4;
+    pub const BT_HCI_EVT: u8 = 5;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct HciCommand {
+        pub opcode: u16,
+        pub param_len: u8,
+        pub params: *const u8,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct HciAclData {
+        pub handle: u16,
+        pub pb_bc_flag: u8,
+        pub len: u16,
+        pub buffer: *const u8,
+    }
+
+    #[repr(C)]
+    pub union HciPacket {
+        pub command: HciCommand,
+        pub acl_data: HciAclData,
+    }
+
+    pub type RecvCallback = extern "C" fn(u8, u16, *const u8, u8);
+
+    unsafe extern "C" {
+        pub fn ble_controller_init(task_priority: u8);
+        pub fn ble_controller_deinit();
+        pub fn bt_onchiphci_interface_init(cb: RecvCallback) -> u8;
+        pub fn bt_onchiphci_send(pkt_type: u8, dest_id: u16, pkt: *mut HciPacket) -> i8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PacketType, RECEIVE, ReceiveRing, ffi, on_controller_packet};
+
+    #[test]
+    fn enum_packet_type_lengths() {
+        assert_eq!(PacketType::from_indicator(0x01), Some(PacketType::Command));
+        assert_eq!(PacketType::from_indicator(0x03), None);
+        assert_eq!(PacketType::Command.payload_len(&[0x03, 0x0c, 0x05]), 5);
+        assert_eq!(PacketType::Event.payload_len(&[0x0e, 0x04, 0x01]), 4);
+        assert_eq!(
+            PacketType::AclData.payload_len(&[0x40, 0x20, 0x1b, 0x00]),
+            27
+        );
+    }
+
+    #[test]
+    fn struct_receive_ring_functions() {
+        let ring = ReceiveRing::new();
+        assert!(ring.is_empty());
+        ring.push_packet(&[&[1, 2], &[3]]);
+        let mut buf = [0; 2];
+        assert_eq!(ring.pop(&mut buf), 2);
+        assert_eq!(buf, [1, 2]);
+        assert_eq!(ring.pop(&mut buf), 1);
+        assert!(ring.is_empty());
+
+        ring.push_packet(&[&[0; super::RECEIVE_BUFFER + 1]]);
+        assert!(ring.overflow.load(core::sync::atomic::Ordering::Relaxed));
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn function_on_controller_packet() {
+        let params = [0x00, 0x01];
+        on_controller_packet(ffi::BT_HCI_CMD_CMP_EVT, 0x0c03, params.as_ptr(), 2);
+        on_controller_packet(ffi::BT_HCI_CMD_STAT_EVT, 0x200d, params.as_ptr(), 1);
+        on_controller_packet(ffi::BT_HCI_ACL_DATA, 0x0040, params.as_ptr(), 2);
+        let mut buf = [0; 32];
+        let n = RECEIVE.pop(&mut buf);
+        assert_eq!(
+            buf[..n],
+            [
+                0x04, 0x0e, 0x05, 0x01, 0x03, 0x0c, 0x00, 0x01, // command complete
+                0x04, 0x0f, 0x04, 0x00, 0x01, 0x0d, 0x20, // command status
+                0x02, 0x40, 0x20, 0x02, 0x00, 0x00, 0x01, // ACL data
+            ]
+        );
+    }
+}
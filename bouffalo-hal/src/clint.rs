@@ -0,0 +1,176 @@
+//! Core-local interruptor and machine timer.
+//!
+//! Every core has a 64-bit `mtime` counter and an `mtimecmp` compare register; the
+//! machine timer interrupt is pending while `mtime` is not below `mtimecmp`. E907 and E24
+//! cores map `mtime` into the interruptor region, while C906 (D0 core of BL808) only
+//! exposes it through the `time` CSR.
+
+use core::ops::Deref;
+use embedded_time::{Clock, Instant, clock, fraction::Fraction};
+use volatile_register::RW;
+
+/// Core-local interruptor registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Machine software interrupt pending.
+    pub msip: RW<u32>,
+    _reserved0: [u8; 0x3ffc],
+    /// Machine timer compare value, low 32 bits.
+    pub mtimecmp_lo: RW<u32>,
+    /// Machine timer compare value, high 32 bits.
+    pub mtimecmp_hi: RW<u32>,
+    _reserved1: [u8; 0x7ff0],
+    /// Machine timer counter, low 32 bits.
+    pub mtime_lo: RW<u32>,
+    /// Machine timer counter, high 32 bits.
+    pub mtime_hi: RW<u32>,
+}
+
+/// Where the machine timer counter is read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TimeSource {
+    /// Memory-mapped `mtime` registers, used on E907 and E24 cores.
+    Register,
+    /// The `time` CSR, used on C906 cores.
+    Csr,
+}
+
+/// Machine timer of the current core.
+///
+/// `HZ` is the counter frequency, which is 1 MHz after ROM bootloader configures the
+/// machine timer divider.
+pub struct MachineTimer<CLINT, const HZ: u32 = 1_000_000> {
+    clint: CLINT,
+    source: TimeSource,
+}
+
+impl<CLINT: Deref<Target = RegisterBlock>, const HZ: u32> MachineTimer<CLINT, HZ> {
+    /// Create machine timer driver reading counter from `source`.
+    #[inline]
+    pub const fn new(clint: CLINT, source: TimeSource) -> Self {
+        Self { clint, source }
+    }
+    /// Get current counter value.
+    #[inline]
+    pub fn current_time(&self) -> u64 {
+        match self.source {
+            TimeSource::Register => loop {
+                // Re-read if the low half wrapped between two reads of the high half.
+                let hi = self.clint.mtime_hi.read();
+                let lo = self.clint.mtime_lo.read();
+                if self.clint.mtime_hi.read() == hi {
+                    break ((hi as u64) << 32) | lo as u64;
+                }
+            },
+            TimeSource::Csr => read_time_csr(),
+        }
+    }
+    /// Set compare value, the timer interrupt is pending once counter reaches it.
+    #[inline]
+    pub fn set_timecmp(&self, val: u64) {
+        // Raise high half first so no spurious match happens between the two writes.
+        unsafe {
+            self.clint.mtimecmp_hi.write(u32::MAX);
+            self.clint.mtimecmp_lo.write(val as u32);
+            self.clint.mtimecmp_hi.write((val >> 32) as u32);
+        }
+    }
+    /// Get compare value.
+    #[inline]
+    pub fn timecmp(&self) -> u64 {
+        ((self.clint.mtimecmp_hi.read() as u64) << 32) | self.clint.mtimecmp_lo.read() as u64
+    }
+    /// Trigger machine software interrupt of this core.
+    #[inline]
+    pub fn set_software_interrupt(&self) {
+        unsafe { self.clint.msip.write(1) };
+    }
+    /// Clear machine software interrupt of this core.
+    #[inline]
+    pub fn clear_software_interrupt(&self) {
+        unsafe { self.clint.msip.write(0) };
+    }
+    /// Release the interruptor peripheral.
+    #[inline]
+    pub fn free(self) -> CLINT {
+        self.clint
+    }
+}
+
+impl<CLINT: Deref<Target = RegisterBlock>, const HZ: u32> Clock for MachineTimer<CLINT, HZ> {
+    type T = u64;
+
+    const SCALING_FACTOR: Fraction = Fraction::new(1, HZ);
+
+    #[inline]
+    fn try_now(&self) -> Result<Instant<Self>, clock::Error> {
+        Ok(Instant::new(self.current_time()))
+    }
+}
+
+#[inline]
+fn read_time_csr() -> u64 {
+    cfg_if::cfg_if! {
+        if #[cfg(target_arch = "riscv64")] {
+            let time: u64;
+            unsafe { core::arch::asm!("csrr {0}, time", out(reg) time) };
+            time
+        } else if #[cfg(target_arch = "riscv32")] {
+            loop {
+                let (hi, lo, hi2): (u32, u32, u32);
+                unsafe {
+                    core::arch::asm!(
+                        "csrr {0}, timeh",
+                        "csrr {1}, time",
+                        "csrr {2}, timeh",
+                        out(reg) hi, out(reg) lo, out(reg) hi2,
+                    )
+                };
+                if hi == hi2 {
+                    break ((hi as u64) << 32) | lo as u64;
+                }
+            }
+        } else {
+            unimplemented!()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MachineTimer, RegisterBlock, TimeSource};
+    use core::mem::offset_of;
+    use embedded_time::{Clock, fraction::Fraction};
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, msip), 0x0);
+        assert_eq!(offset_of!(RegisterBlock, mtimecmp_lo), 0x4000);
+        assert_eq!(offset_of!(RegisterBlock, mtimecmp_hi), 0x4004);
+        assert_eq!(offset_of!(RegisterBlock, mtime_lo), 0xBFF8);
+        assert_eq!(offset_of!(RegisterBlock, mtime_hi), 0xBFFC);
+    }
+
+    #[test]
+    fn struct_machine_timer_functions() {
+        let block: RegisterBlock = unsafe { core::mem::zeroed() };
+        unsafe {
+            block.mtime_lo.write(0x9abc_def0);
+            block.mtime_hi.write(0x1234_5678);
+        }
+        let timer: MachineTimer<_> = MachineTimer::new(&block, TimeSource::Register);
+        assert_eq!(timer.current_time(), 0x1234_5678_9abc_def0);
+        timer.set_timecmp(0x0000_0001_ffff_0000);
+        assert_eq!(block.mtimecmp_lo.read(), 0xffff_0000);
+        assert_eq!(block.mtimecmp_hi.read(), 0x0000_0001);
+        assert_eq!(timer.timecmp(), 0x0000_0001_ffff_0000);
+        timer.set_software_interrupt();
+        assert_eq!(block.msip.read(), 1);
+        timer.clear_software_interrupt();
+        assert_eq!(block.msip.read(), 0);
+        assert_eq!(
+            <MachineTimer<&RegisterBlock> as Clock>::SCALING_FACTOR,
+            Fraction::new(1, 1_000_000)
+        );
+    }
+}
@@ -1,5 +1,9 @@
 //! System-on-Chip clock configuration.
 
+use crate::{
+    glb::v2::{self, ClockOutSource},
+    gpio::{Alternate, ClockOut},
+};
 use embedded_time::rate::Hertz;
 
 /// Clock settings for current chip.
@@ -25,4 +29,69 @@ impl Clocks {
             _ => unreachable!(),
         }
     }
+    /// Audio Phase-Locked Loop output frequency, source of Inter-IC Sound clocks.
+    #[inline]
+    pub const fn audio_pll(&self) -> Option<Hertz> {
+        // todo: calculate from Clocks structure fields
+        Some(Hertz(442_368_000))
+    }
+    /// Ultra-high speed PSRAM data rate clock frequency.
+    #[inline]
+    pub const fn psram_clock(&self) -> Option<Hertz> {
+        // todo: calculate from Clocks structure fields
+        Some(Hertz(2_000_000_000))
+    }
+}
+
+/// Internal clock routed to a GPIO pad for measurement.
+///
+/// BL808 and BL616 have four clock outputs; pad `N` carries clock output `N % 4`, so
+/// pads sharing an output also share its source.
+pub struct ClockOutput<'a, const N: usize> {
+    pad: Alternate<'a, N, ClockOut>,
+}
+
+impl<'a, const N: usize> ClockOutput<'a, N> {
+    /// Clock output carried by this pad.
+    pub const OUTPUT: usize = N % 4;
+
+    /// Route `source` to `pad`.
+    ///
+    /// Returns the pad back if `source` cannot be routed to its clock output.
+    #[inline]
+    pub fn new(
+        pad: Alternate<'a, N, ClockOut>,
+        source: ClockOutSource,
+        glb: &v2::RegisterBlock,
+    ) -> Result<Self, Alternate<'a, N, ClockOut>> {
+        let Some(bits) = source.bits(Self::OUTPUT) else {
+            return Err(pad);
+        };
+        unsafe {
+            glb.clock_out_config
+                .modify(|v| v.set_source(Self::OUTPUT, bits).enable_output(Self::OUTPUT))
+        };
+        Ok(Self { pad })
+    }
+    /// Change routed clock, returns `false` if `source` cannot be routed to this pad.
+    #[inline]
+    pub fn set_source(&mut self, source: ClockOutSource, glb: &v2::RegisterBlock) -> bool {
+        let Some(bits) = source.bits(Self::OUTPUT) else {
+            return false;
+        };
+        unsafe {
+            glb.clock_out_config
+                .modify(|v| v.set_source(Self::OUTPUT, bits))
+        };
+        true
+    }
+    /// Stop clock output and release the pad.
+    #[inline]
+    pub fn free(self, glb: &v2::RegisterBlock) -> Alternate<'a, N, ClockOut> {
+        unsafe {
+            glb.clock_out_config
+                .modify(|v| v.disable_output(Self::OUTPUT))
+        };
+        self.pad
+    }
 }
@@ -1,17 +1,47 @@
 //! Display bus interface.
+//!
+//! The display bus interface drives LCD panels over a type B (Intel 8080 parallel) or
+//! type C (3-wire or 4-wire SPI) bus. Each transfer sends a command byte, then either
+//! up to four normal data bytes through a data register, or a stream of pixels through
+//! the transmit queue, which can be fed by DMA.
 
-use volatile_register::RW;
+use crate::dma::{
+    BurstSize, DmaChannelConfig, DmaMode, LliPool, LliTransfer, Periph4Dma2, TransferWidth,
+    TypedChannel, Word, clean_dcache,
+};
+use core::ops::Deref;
+use volatile_register::{RO, RW, WO};
+
+#[cfg(feature = "embedded-graphics")]
+mod graphics;
+#[cfg(feature = "embedded-graphics")]
+pub use graphics::*;
 
 /// Display bus interface registers.
 #[repr(C)]
 pub struct RegisterBlock {
     /// Function configuration register.
     pub config: RW<Config>,
-    _reserved: [u8; 0x7b],
+    _reserved0: [u8; 0x4],
+    /// Pixel count and format of pixel data transfers.
+    pub pixel_config: RW<PixelConfig>,
+    /// Duration of data phases and conditions in source clock.
+    pub period_signal: RW<PeriodSignal>,
+    /// Normal data to write.
+    pub data_write: RW<u32>,
+    /// Normal data read back.
+    pub data_read: RO<u32>,
+    /// Interrupt configuration and state register.
+    pub interrupt_config: RW<InterruptConfig>,
+    /// Bus busy state indication register.
+    pub bus_busy: RO<BusBusy>,
+    _reserved1: [u8; 0x60],
     /// First-in first-out queue configuration 0.
     pub fifo_config_0: RW<FifoConfig0>,
     /// First-in first-out queue configuration 1.
     pub fifo_config_1: RW<FifoConfig1>,
+    /// First-in first-out queue write data register.
+    pub fifo_write: WO<u32>,
 }
 
 /// Function configuration register.
@@ -277,6 +307,183 @@ impl Config {
     }
 }
 
+/// Pixel count and format register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct PixelConfig(u32);
+
+impl PixelConfig {
+    const PIXEL_COUNT: u32 = 0xff_ffff;
+    const PIXEL_FORMAT: u32 = 1 << 31;
+
+    /// Set number of pixels in a pixel data transfer.
+    #[inline]
+    pub const fn set_pixel_count(self, val: u32) -> Self {
+        Self((self.0 & !Self::PIXEL_COUNT) | (val & Self::PIXEL_COUNT))
+    }
+    /// Get number of pixels in a pixel data transfer.
+    #[inline]
+    pub const fn pixel_count(self) -> u32 {
+        self.0 & Self::PIXEL_COUNT
+    }
+    /// Set pixel format.
+    #[inline]
+    pub const fn set_pixel_format(self, val: PixelFormat) -> Self {
+        match val {
+            PixelFormat::Rgb888 => Self(self.0 & !Self::PIXEL_FORMAT),
+            PixelFormat::Rgb565 => Self(self.0 | Self::PIXEL_FORMAT),
+        }
+    }
+    /// Get pixel format.
+    #[inline]
+    pub const fn pixel_format(self) -> PixelFormat {
+        if self.0 & Self::PIXEL_FORMAT != 0 {
+            PixelFormat::Rgb565
+        } else {
+            PixelFormat::Rgb888
+        }
+    }
+}
+
+/// Pixel format on the display bus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PixelFormat {
+    /// 24-bit pixels, one pixel in each queue word with the highest byte ignored.
+    Rgb888,
+    /// 16-bit pixels, two pixels in each queue word with the first in lower half.
+    Rgb565,
+}
+
+/// Duration of data phases and conditions in source clock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct PeriodSignal(u32);
+
+impl PeriodSignal {
+    const START_CONDITION: u32 = 0xff;
+    const STOP_CONDITION: u32 = 0xff << 8;
+    const DATA_PHASE_0: u32 = 0xff << 16;
+    const DATA_PHASE_1: u32 = 0xff << 24;
+
+    /// Set start condition clock length.
+    #[inline]
+    pub const fn set_start_condition(self, val: u8) -> Self {
+        Self((self.0 & !Self::START_CONDITION) | (val as u32))
+    }
+    /// Get start condition clock length.
+    #[inline]
+    pub const fn start_condition(self) -> u8 {
+        (self.0 & Self::START_CONDITION) as u8
+    }
+    /// Set stop condition clock length.
+    #[inline]
+    pub const fn set_stop_condition(self, val: u8) -> Self {
+        Self((self.0 & !Self::STOP_CONDITION) | ((val as u32) << 8))
+    }
+    /// Get stop condition clock length.
+    #[inline]
+    pub const fn stop_condition(self) -> u8 {
+        ((self.0 & Self::STOP_CONDITION) >> 8) as u8
+    }
+    /// Set data phase 0 clock length.
+    #[inline]
+    pub const fn set_data_phase_0(self, val: u8) -> Self {
+        Self((self.0 & !Self::DATA_PHASE_0) | ((val as u32) << 16))
+    }
+    /// Get data phase 0 clock length.
+    #[inline]
+    pub const fn data_phase_0(self) -> u8 {
+        ((self.0 & Self::DATA_PHASE_0) >> 16) as u8
+    }
+    /// Set data phase 1 clock length.
+    #[inline]
+    pub const fn set_data_phase_1(self, val: u8) -> Self {
+        Self((self.0 & !Self::DATA_PHASE_1) | ((val as u32) << 24))
+    }
+    /// Get data phase 1 clock length.
+    #[inline]
+    pub const fn data_phase_1(self) -> u8 {
+        ((self.0 & Self::DATA_PHASE_1) >> 24) as u8
+    }
+}
+
+/// Interrupt configuration and state register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct InterruptConfig(u32);
+
+impl InterruptConfig {
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub const fn has_interrupt(self, val: Interrupt) -> bool {
+        (self.0 & (1 << (val as u32))) != 0
+    }
+    /// Set interrupt mask.
+    #[inline]
+    pub const fn mask_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32 + 8)))
+    }
+    /// Clear interrupt mask.
+    #[inline]
+    pub const fn unmask_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 & !(1 << (val as u32 + 8)))
+    }
+    /// Check if interrupt is masked.
+    #[inline]
+    pub const fn is_interrupt_masked(self, val: Interrupt) -> bool {
+        (self.0 & (1 << (val as u32 + 8))) != 0
+    }
+    /// Clear interrupt flag.
+    ///
+    /// Only `TransferEnd` is cleared this way; queue interrupts follow queue state.
+    #[inline]
+    pub const fn clear_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32 + 16)))
+    }
+    /// Enable interrupt.
+    #[inline]
+    pub const fn enable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32 + 24)))
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub const fn disable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 & !(1 << (val as u32 + 24)))
+    }
+    /// Check if interrupt is enabled.
+    #[inline]
+    pub const fn is_interrupt_enabled(self, val: Interrupt) -> bool {
+        (self.0 & (1 << (val as u32 + 24))) != 0
+    }
+}
+
+/// Interrupt event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Interrupt {
+    /// Command, normal data or all pixels of a transfer are sent.
+    TransferEnd = 0,
+    /// Transmit first-in first-out queue reaches its threshold.
+    TransmitFifoReady = 1,
+    /// Transmit first-in first-out queue overflow or underflow.
+    FifoError = 2,
+}
+
+/// Bus busy state indication register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct BusBusy(u32);
+
+impl BusBusy {
+    const BUS_BUSY: u32 = 1 << 0;
+
+    /// Check if the bus is busy.
+    #[inline]
+    pub const fn is_bus_busy(self) -> bool {
+        self.0 & Self::BUS_BUSY != 0
+    }
+}
+
 /// First-in first-out queue configuration 0.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -346,15 +553,333 @@ impl FifoConfig1 {
     }
 }
 
+/// Display bus type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Type B, Intel 8080 parallel bus.
+    TypeB,
+    /// Type C 3-wire SPI, with data/command bit sent before each byte.
+    TypeC3Wire,
+    /// Type C 4-wire SPI, with a separate data/command signal.
+    TypeC4Wire,
+}
+
+/// Display bus interface error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Read buffer is longer than four bytes.
+    TooLong,
+    /// DMA pixel data does not fill whole queue words.
+    NotAligned,
+}
+
+/// Pixel type sent through the transmit queue.
+pub trait Pixel: Word {
+    /// Pixel format on the display bus.
+    const FORMAT: PixelFormat;
+    /// DMA transfer width of the pixel.
+    const TRANSFER_WIDTH: TransferWidth;
+}
+
+impl Pixel for u16 {
+    const FORMAT: PixelFormat = PixelFormat::Rgb565;
+    const TRANSFER_WIDTH: TransferWidth = TransferWidth::HalfWord;
+}
+
+impl Pixel for u32 {
+    const FORMAT: PixelFormat = PixelFormat::Rgb888;
+    const TRANSFER_WIDTH: TransferWidth = TransferWidth::Word;
+}
+
+/// Managed display bus interface peripheral.
+pub struct Dbi<DBI, PADS> {
+    dbi: DBI,
+    pads: PADS,
+}
+
+impl<DBI: Deref<Target = RegisterBlock>, PADS> Dbi<DBI, PADS> {
+    /// Create a new display bus interface instance.
+    ///
+    /// Pads must be configured to type B or type C display bus function matching `mode`.
+    #[inline]
+    pub fn new(dbi: DBI, pads: PADS, mode: Mode) -> Self {
+        let config = Config(0).disable_master().disable_continuous_transfer();
+        let config = match mode {
+            Mode::TypeB => config.set_type_b(),
+            Mode::TypeC3Wire => config.set_type_c().set_type_c_3_wire_mode(),
+            Mode::TypeC4Wire => config.set_type_c().set_type_c_4_wire_mode(),
+        };
+        unsafe {
+            dbi.config.write(config);
+            dbi.period_signal.write(
+                PeriodSignal(0)
+                    .set_start_condition(4)
+                    .set_stop_condition(4)
+                    .set_data_phase_0(4)
+                    .set_data_phase_1(4),
+            );
+            dbi.fifo_config_0
+                .modify(|val| val.disable_dma_transmit().clear_transmit_fifo());
+            dbi.fifo_config_1
+                .write(FifoConfig1(0).set_transmit_threshold(0));
+            dbi.interrupt_config
+                .modify(|val| val.clear_interrupt(Interrupt::TransferEnd));
+        }
+        Self { dbi, pads }
+    }
+    /// Send a command with normal data parameters.
+    ///
+    /// Parameters longer than four bytes are sent in following transfers of four bytes.
+    #[inline]
+    pub fn command(&mut self, command: u8, params: &[u8]) {
+        let config = self.dbi.config.read().set_command(command);
+        if params.is_empty() {
+            self.run(config.enable_command().disable_data());
+            return;
+        }
+        for (i, chunk) in params.chunks(4).enumerate() {
+            let mut word = [0u8; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            unsafe { self.dbi.data_write.write(u32::from_le_bytes(word)) };
+            let config = if i == 0 {
+                config.enable_command()
+            } else {
+                config.disable_command()
+            };
+            self.run(
+                config
+                    .enable_data()
+                    .set_data_write()
+                    .set_data_normal()
+                    .set_data_byte_count(chunk.len() as u8 - 1),
+            );
+        }
+    }
+    /// Send a command and read at most four bytes of normal data.
+    #[inline]
+    pub fn read(&mut self, command: u8, buf: &mut [u8]) -> Result<(), Error> {
+        if buf.len() > 4 {
+            return Err(Error::TooLong);
+        }
+        if buf.is_empty() {
+            self.command(command, &[]);
+            return Ok(());
+        }
+        let config = self
+            .dbi
+            .config
+            .read()
+            .set_command(command)
+            .enable_command()
+            .enable_data()
+            .set_data_read()
+            .set_data_normal()
+            .set_data_byte_count(buf.len() as u8 - 1);
+        self.run(config);
+        let word = self.dbi.data_read.read().to_le_bytes();
+        buf.copy_from_slice(&word[..buf.len()]);
+        Ok(())
+    }
+    /// Send a command followed by pixel data, e.g. a memory write command.
+    #[inline]
+    pub fn write_pixels<P: Pixel>(&mut self, command: u8, pixels: &[P]) {
+        let config = self.start_pixels::<P>(command, pixels.len());
+        let bytes = unsafe {
+            core::slice::from_raw_parts(pixels.as_ptr().cast::<u8>(), size_of_val(pixels))
+        };
+        for chunk in bytes.chunks(4) {
+            let mut word = [0u8; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            while self.dbi.fifo_config_1.read().transmit_available_bytes() == 0 {
+                core::hint::spin_loop();
+            }
+            unsafe { self.dbi.fifo_write.write(u32::from_le_bytes(word)) };
+        }
+        self.wait(config);
+    }
+    /// Send a command followed by pixel data streamed by DMA.
+    ///
+    /// Pixels must fill whole queue words, i.e. an even number of `u16` pixels.
+    #[inline]
+    pub fn write_pixels_dma<P: Pixel>(
+        &mut self,
+        command: u8,
+        channel: &mut TypedChannel<'_, Periph4Dma2>,
+        pixels: &[P],
+    ) -> Result<(), Error> {
+        if !size_of_val(pixels).is_multiple_of(4) {
+            return Err(Error::NotAligned);
+        }
+        channel.configure(DmaChannelConfig {
+            direction: DmaMode::Mem2Periph,
+            src_req: None,
+            dst_req: Some(Periph4Dma2::DbiTx),
+            src_addr_inc: true,
+            dst_addr_inc: false,
+            src_burst_size: BurstSize::INCR1,
+            dst_burst_size: BurstSize::INCR1,
+            src_transfer_width: P::TRANSFER_WIDTH,
+            dst_transfer_width: TransferWidth::Word,
+        });
+        clean_dcache(pixels.as_ptr() as usize, size_of_val(pixels));
+        unsafe {
+            self.dbi
+                .fifo_config_0
+                .modify(|val| val.enable_dma_transmit())
+        };
+        let config = self.start_pixels::<P>(command, pixels.len());
+        let fifo = &self.dbi.fifo_write as *const _ as u32;
+        for chunk in pixels.chunks(DMA_CHUNK_PIXELS) {
+            let transfer = &mut [LliTransfer {
+                src_addr: chunk.as_ptr() as u32,
+                dst_addr: fifo,
+                nbytes: size_of_val(chunk) as u32,
+            }];
+            // A chunk fits in one linked list item, which is loaded into channel registers.
+            channel.lli_reload(&mut [LliPool::new(); 1], 1, transfer, 1);
+            channel.start();
+            while channel.is_busy() {
+                core::hint::spin_loop();
+            }
+            channel.stop();
+        }
+        self.wait(config);
+        unsafe {
+            self.dbi
+                .fifo_config_0
+                .modify(|val| val.disable_dma_transmit())
+        };
+        Ok(())
+    }
+    /// Release the display bus interface instance and return the pads.
+    #[inline]
+    pub fn free(self) -> (DBI, PADS) {
+        (self.dbi, self.pads)
+    }
+    /// Start a command and pixel transfer, pixels are then pushed into transmit queue.
+    #[inline]
+    fn start_pixels<P: Pixel>(&mut self, command: u8, count: usize) -> Config {
+        unsafe {
+            self.dbi.pixel_config.write(
+                PixelConfig(0)
+                    .set_pixel_count(count as u32)
+                    .set_pixel_format(P::FORMAT),
+            );
+            self.dbi
+                .fifo_config_0
+                .modify(|val| val.clear_transmit_fifo());
+        }
+        let config = self
+            .dbi
+            .config
+            .read()
+            .set_command(command)
+            .enable_command()
+            .enable_data()
+            .set_data_write()
+            .set_data_pixel();
+        unsafe {
+            self.dbi
+                .interrupt_config
+                .modify(|val| val.clear_interrupt(Interrupt::TransferEnd));
+            self.dbi.config.write(config.enable_master());
+        }
+        config
+    }
+    /// Run a transfer and wait until it ends.
+    #[inline]
+    fn run(&mut self, config: Config) {
+        unsafe {
+            self.dbi
+                .interrupt_config
+                .modify(|val| val.clear_interrupt(Interrupt::TransferEnd));
+            self.dbi.config.write(config.enable_master());
+        }
+        self.wait(config);
+    }
+    /// Wait until the running transfer ends, then stop the master.
+    #[inline]
+    fn wait(&mut self, config: Config) {
+        while !self
+            .dbi
+            .interrupt_config
+            .read()
+            .has_interrupt(Interrupt::TransferEnd)
+        {
+            core::hint::spin_loop();
+        }
+        unsafe {
+            self.dbi
+                .interrupt_config
+                .modify(|val| val.clear_interrupt(Interrupt::TransferEnd));
+            self.dbi.config.write(config.disable_master());
+        }
+    }
+}
+
+// Pixels per DMA chunk, within the largest transfer size of DMA linked list items.
+const DMA_CHUNK_PIXELS: usize = 4064;
+
 #[cfg(test)]
 mod tests {
-    use super::RegisterBlock;
+    use super::{
+        BusBusy, Interrupt, InterruptConfig, PeriodSignal, PixelConfig, PixelFormat, RegisterBlock,
+    };
     use core::mem::offset_of;
 
     #[test]
     fn struct_register_block_offset() {
         assert_eq!(offset_of!(RegisterBlock, config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, pixel_config), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, period_signal), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, data_write), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, data_read), 0x14);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_config), 0x18);
+        assert_eq!(offset_of!(RegisterBlock, bus_busy), 0x1c);
         assert_eq!(offset_of!(RegisterBlock, fifo_config_0), 0x80);
         assert_eq!(offset_of!(RegisterBlock, fifo_config_1), 0x84);
+        assert_eq!(offset_of!(RegisterBlock, fifo_write), 0x88);
+    }
+
+    #[test]
+    fn struct_pixel_config_functions() {
+        let val = PixelConfig(0).set_pixel_count(240 * 320);
+        assert_eq!(val.0, 0x0001_2c00);
+        assert_eq!(val.pixel_count(), 76800);
+        assert_eq!(val.pixel_format(), PixelFormat::Rgb888);
+        let val = val.set_pixel_format(PixelFormat::Rgb565);
+        assert_eq!(val.0, 0x8001_2c00);
+        assert_eq!(val.pixel_format(), PixelFormat::Rgb565);
+        let val = val.set_pixel_count(0x0100_0001);
+        assert_eq!(val.pixel_count(), 1);
+    }
+
+    #[test]
+    fn struct_period_signal_functions() {
+        let val = PeriodSignal(0)
+            .set_start_condition(1)
+            .set_stop_condition(2)
+            .set_data_phase_0(3)
+            .set_data_phase_1(4);
+        assert_eq!(val.0, 0x0403_0201);
+        assert_eq!(val.start_condition(), 1);
+        assert_eq!(val.stop_condition(), 2);
+        assert_eq!(val.data_phase_0(), 3);
+        assert_eq!(val.data_phase_1(), 4);
+    }
+
+    #[test]
+    fn struct_interrupt_config_functions() {
+        let val = InterruptConfig(0)
+            .mask_interrupt(Interrupt::FifoError)
+            .clear_interrupt(Interrupt::TransferEnd)
+            .enable_interrupt(Interrupt::TransmitFifoReady);
+        assert_eq!(val.0, 0x0201_0400);
+        assert!(val.is_interrupt_masked(Interrupt::FifoError));
+        assert!(val.is_interrupt_enabled(Interrupt::TransmitFifoReady));
+        assert!(!val.has_interrupt(Interrupt::TransferEnd));
+        assert!(InterruptConfig(0x1).has_interrupt(Interrupt::TransferEnd));
+        assert!(BusBusy(0x1).is_bus_busy());
     }
 }
@@ -0,0 +1,143 @@
+//! `embedded-graphics` drawing on MIPI DCS compatible panels.
+
+use super::{Dbi, Error, RegisterBlock};
+use core::ops::Deref;
+use embedded_graphics_core::{
+    Pixel,
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions, Size},
+    pixelcolor::{IntoStorage, Rgb565},
+    primitives::Rectangle,
+};
+
+const CMD_COLUMN_ADDRESS_SET: u8 = 0x2a;
+const CMD_PAGE_ADDRESS_SET: u8 = 0x2b;
+const CMD_MEMORY_WRITE: u8 = 0x2c;
+const CMD_MEMORY_WRITE_CONTINUE: u8 = 0x3c;
+
+// Pixels buffered before each memory write.
+const BATCH_PIXELS: usize = 64;
+
+/// Panel drawn with `embedded-graphics` over a display bus interface.
+///
+/// The panel must accept MIPI DCS address set and memory write commands, and be
+/// initialized to RGB565 pixel format by the caller.
+pub struct DbiDisplay<DBI, PADS> {
+    dbi: Dbi<DBI, PADS>,
+    size: Size,
+}
+
+impl<DBI: Deref<Target = RegisterBlock>, PADS> DbiDisplay<DBI, PADS> {
+    /// Create a display of `width` by `height` pixels.
+    #[inline]
+    pub fn new(dbi: Dbi<DBI, PADS>, width: u32, height: u32) -> Self {
+        Self {
+            dbi,
+            size: Size::new(width, height),
+        }
+    }
+    /// Release the display and return the display bus interface.
+    #[inline]
+    pub fn free(self) -> Dbi<DBI, PADS> {
+        self.dbi
+    }
+    /// Set panel memory window to a non-empty area inside the display.
+    #[inline]
+    fn set_window(&mut self, area: &Rectangle) {
+        let start = area.top_left;
+        let end = area.bottom_right().unwrap_or(start);
+        let (x0, x1) = (start.x as u16, end.x as u16);
+        let (y0, y1) = (start.y as u16, end.y as u16);
+        let [x0h, x0l] = x0.to_be_bytes();
+        let [x1h, x1l] = x1.to_be_bytes();
+        let [y0h, y0l] = y0.to_be_bytes();
+        let [y1h, y1l] = y1.to_be_bytes();
+        self.dbi
+            .command(CMD_COLUMN_ADDRESS_SET, &[x0h, x0l, x1h, x1l]);
+        self.dbi
+            .command(CMD_PAGE_ADDRESS_SET, &[y0h, y0l, y1h, y1l]);
+    }
+    /// Write colors into the current window in batches.
+    #[inline]
+    fn write_colors(&mut self, colors: impl IntoIterator<Item = Rgb565>) {
+        let mut buf = [0u16; BATCH_PIXELS];
+        let mut len = 0;
+        let mut command = CMD_MEMORY_WRITE;
+        for color in colors {
+            buf[len] = color.into_storage();
+            len += 1;
+            if len == BATCH_PIXELS {
+                self.dbi.write_pixels(command, &buf);
+                command = CMD_MEMORY_WRITE_CONTINUE;
+                len = 0;
+            }
+        }
+        if len != 0 {
+            self.dbi.write_pixels(command, &buf[..len]);
+        }
+    }
+}
+
+impl<DBI: Deref<Target = RegisterBlock>, PADS> OriginDimensions for DbiDisplay<DBI, PADS> {
+    #[inline]
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<DBI: Deref<Target = RegisterBlock>, PADS> DrawTarget for DbiDisplay<DBI, PADS> {
+    type Color = Rgb565;
+    type Error = Error;
+
+    #[inline]
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let bounds = self.bounding_box();
+        for Pixel(point, color) in pixels {
+            if bounds.contains(point) {
+                self.set_window(&Rectangle::new(point, Size::new(1, 1)));
+                self.dbi
+                    .write_pixels(CMD_MEMORY_WRITE, &[color.into_storage()]);
+            }
+        }
+        Ok(())
+    }
+    #[inline]
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let drawable = area.intersection(&self.bounding_box());
+        if drawable.is_zero_sized() {
+            return Ok(());
+        }
+        if drawable != *area {
+            // Clipped areas fall back to pixels, skipping those outside the display.
+            let (top_left, size) = (area.top_left, area.size);
+            let points = (0..size.height)
+                .flat_map(move |y| (0..size.width).map(move |x| top_left + Size::new(x, y)));
+            let pixels = points.zip(colors).map(|(point, color)| Pixel(point, color));
+            return self.draw_iter(pixels);
+        }
+        self.set_window(area);
+        self.write_colors(
+            colors
+                .into_iter()
+                .take(area.size.width as usize * area.size.height as usize),
+        );
+        Ok(())
+    }
+    #[inline]
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let drawable = area.intersection(&self.bounding_box());
+        if drawable.is_zero_sized() {
+            return Ok(());
+        }
+        let count = drawable.size.width as usize * drawable.size.height as usize;
+        self.set_window(&drawable);
+        self.write_colors(core::iter::repeat_n(color, count));
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+//! Register block snapshots for debugging.
+//!
+//! [`dump`] copies every 32-bit word of a register block into a [`RegisterDump`], which
+//! prints as one `offset: value` line per word. Comparing dumps before and after a driver
+//! call, or attaching one to a bug report, helps bring-up without a debug probe.
+//!
+//! Reading some registers has side effects, such as popping a byte from a receive FIFO
+//! or clearing a status flag. Pass their offsets as `skip`; skipped words read as zero.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use core::mem::{offset_of, size_of};
+//! use bouffalo_hal::uart::RegisterBlock;
+//! const N: usize = size_of::<RegisterBlock>() / 4;
+//! let snapshot = bouffalo_hal::debug::dump::<_, N>(&*p.uart0, &[offset_of!(RegisterBlock, fifo_read)]);
+//! println!("{}", snapshot);
+//! ```
+
+use core::fmt;
+
+/// Snapshot of `N` 32-bit registers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegisterDump<const N: usize> {
+    base: usize,
+    words: [u32; N],
+}
+
+impl<const N: usize> RegisterDump<N> {
+    /// Address of the register block.
+    #[inline]
+    pub const fn base(&self) -> usize {
+        self.base
+    }
+    /// Register values, the word at index `i` is at offset `i * 4`.
+    #[inline]
+    pub const fn words(&self) -> &[u32; N] {
+        &self.words
+    }
+    /// Get the register value at byte `offset`.
+    #[inline]
+    pub const fn get(&self, offset: usize) -> Option<u32> {
+        if !offset.is_multiple_of(4) || offset / 4 >= N {
+            return None;
+        }
+        Some(self.words[offset / 4])
+    }
+    /// Iterate over offsets and values of registers which differ from `other`.
+    #[inline]
+    pub fn changes<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = (usize, u32, u32)> + 'a {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(i, (&a, &b))| (i * 4, a, b))
+    }
+}
+
+impl<const N: usize> fmt::Display for RegisterDump<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, word) in self.words.iter().enumerate() {
+            writeln!(f, "{:#010x}: {:#010x}", self.base + i * 4, word)?;
+        }
+        Ok(())
+    }
+}
+
+/// Read register block `rb` word by word into a snapshot.
+///
+/// `N` must be the size of `RB` in 32-bit words. Words at byte offsets in `skip` are not
+/// read and stored as zero.
+#[inline]
+pub fn dump<RB, const N: usize>(rb: &RB, skip: &[usize]) -> RegisterDump<N> {
+    const {
+        assert!(
+            N * 4 == size_of::<RB>(),
+            "N must be register block size in words"
+        )
+    };
+    let base = rb as *const RB as usize;
+    let mut words = [0u32; N];
+    for (i, word) in words.iter_mut().enumerate() {
+        if !skip.contains(&(i * 4)) {
+            *word = unsafe { (base as *const u32).add(i).read_volatile() };
+        }
+    }
+    RegisterDump { base, words }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dump;
+    use core::fmt::{self, Write};
+
+    #[repr(C)]
+    struct Block {
+        a: u32,
+        fifo: u32,
+        c: u32,
+    }
+
+    struct Buffer {
+        data: [u8; 128],
+        len: usize,
+    }
+
+    impl Write for Buffer {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.data[self.len..self.len + s.len()].copy_from_slice(s.as_bytes());
+            self.len += s.len();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn function_dump() {
+        let mut block = Block {
+            a: 0x1234_5678,
+            fifo: 0xffff_ffff,
+            c: 0x8000_0001,
+        };
+        let before = dump::<_, 3>(&block, &[4]);
+        assert_eq!(before.words(), &[0x1234_5678, 0, 0x8000_0001]);
+        assert_eq!(before.get(8), Some(0x8000_0001));
+        assert_eq!(before.get(2), None);
+        assert_eq!(before.get(12), None);
+
+        block.c = 0;
+        let after = dump::<_, 3>(&block, &[4]);
+        let mut changes = before.changes(&after);
+        assert_eq!(changes.next(), Some((8, 0x8000_0001, 0)));
+        assert_eq!(changes.next(), None);
+        assert_eq!(block.fifo, 0xffff_ffff);
+
+        let mut buf = Buffer {
+            data: [0; 128],
+            len: 0,
+        };
+        write!(buf, "{}", after).unwrap();
+        let text = core::str::from_utf8(&buf.data[..buf.len]).unwrap();
+        assert_eq!(text.lines().count(), 3);
+        assert!(text.lines().next().unwrap().ends_with(": 0x12345678"));
+    }
+}
@@ -0,0 +1,804 @@
+//! Display controller with on-screen display layers.
+//!
+//! The display controller scans a framebuffer in memory, blends on-screen display (OSD)
+//! layers on top of it and drives the result to a parallel (DPI), MIPI DSI or DBI panel.
+//! DSI panels are initialized in command mode through the [`dsi`] module before the
+//! controller starts streaming video.
+
+pub mod dsi;
+
+#[cfg(feature = "embedded-graphics")]
+mod graphics;
+#[cfg(feature = "embedded-graphics")]
+pub use graphics::*;
+
+use core::ops::Deref;
+use volatile_register::{RW, WO};
+
+/// Display controller registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Display configuration register.
+    pub config: RW<DisplayConfig>,
+    /// Active area in pixels and lines.
+    pub resolution: RW<Resolution>,
+    /// Horizontal timing in pixel clocks.
+    pub horizontal_timing: RW<Timing>,
+    /// Vertical timing in lines.
+    pub vertical_timing: RW<Timing>,
+    /// Start address of background framebuffer.
+    pub framebuffer_address: RW<u32>,
+    /// Bytes between start of two framebuffer lines.
+    pub framebuffer_stride: RW<u32>,
+    /// Color outside framebuffer and layers, in RGB888.
+    pub background_color: RW<u32>,
+    _reserved0: [u8; 0x4],
+    /// Interrupt enable and state register.
+    pub interrupt: RW<InterruptConfig>,
+    /// Interrupt clear register.
+    pub interrupt_clear: WO<InterruptClear>,
+    _reserved1: [u8; 0x18],
+    /// On-screen display layers, blended in increasing index order.
+    pub layers: [Layer; 4],
+}
+
+/// On-screen display layer registers.
+#[repr(C)]
+pub struct Layer {
+    /// Layer configuration register.
+    pub config: RW<LayerConfig>,
+    /// Start address of layer buffer.
+    pub address: RW<u32>,
+    /// Top left corner of layer on screen.
+    pub position: RW<Position>,
+    /// Layer size in pixels and lines.
+    pub size: RW<Resolution>,
+}
+
+/// Display configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct DisplayConfig(u32);
+
+impl DisplayConfig {
+    const ENABLE: u32 = 1 << 0;
+    const OUTPUT: u32 = 0x3 << 1;
+    const FORMAT: u32 = 0x7 << 4;
+
+    /// Enable display output.
+    #[inline]
+    pub const fn enable_display(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable display output.
+    #[inline]
+    pub const fn disable_display(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if display output is enabled.
+    #[inline]
+    pub const fn is_display_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Set output interface.
+    #[inline]
+    pub const fn set_output(self, val: Output) -> Self {
+        Self((self.0 & !Self::OUTPUT) | ((val as u32) << 1))
+    }
+    /// Get output interface.
+    #[inline]
+    pub const fn output(self) -> Output {
+        match (self.0 & Self::OUTPUT) >> 1 {
+            0 => Output::Dpi,
+            1 => Output::Dsi,
+            _ => Output::Dbi,
+        }
+    }
+    /// Set background framebuffer pixel format.
+    #[inline]
+    pub const fn set_format(self, val: Format) -> Self {
+        Self((self.0 & !Self::FORMAT) | ((val as u32) << 4))
+    }
+    /// Get background framebuffer pixel format.
+    #[inline]
+    pub const fn format(self) -> Format {
+        Format::from_bits((self.0 & Self::FORMAT) >> 4)
+    }
+}
+
+/// Display output interface.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Output {
+    /// Parallel RGB interface.
+    Dpi = 0,
+    /// MIPI Display Serial Interface.
+    Dsi = 1,
+    /// Display Bus Interface.
+    Dbi = 2,
+}
+
+/// Pixel format in memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Format {
+    /// 16-bit RGB565.
+    Rgb565 = 0,
+    /// 24-bit RGB888.
+    Rgb888 = 1,
+    /// 32-bit ARGB8888.
+    Argb8888 = 2,
+    /// 16-bit YUV422 interleaved.
+    Yuv422 = 3,
+}
+
+impl Format {
+    #[inline]
+    const fn from_bits(bits: u32) -> Self {
+        match bits {
+            0 => Format::Rgb565,
+            1 => Format::Rgb888,
+            2 => Format::Argb8888,
+            _ => Format::Yuv422,
+        }
+    }
+    /// Bytes per pixel.
+    #[inline]
+    pub const fn bytes(self) -> u32 {
+        match self {
+            Format::Rgb565 | Format::Yuv422 => 2,
+            Format::Rgb888 => 3,
+            Format::Argb8888 => 4,
+        }
+    }
+}
+
+/// Size register in pixels and lines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Resolution(u32);
+
+impl Resolution {
+    const WIDTH: u32 = 0xfff;
+    const HEIGHT: u32 = 0xfff << 16;
+
+    /// Set width in pixels.
+    #[inline]
+    pub const fn set_width(self, val: u16) -> Self {
+        Self((self.0 & !Self::WIDTH) | (val as u32 & Self::WIDTH))
+    }
+    /// Get width in pixels.
+    #[inline]
+    pub const fn width(self) -> u16 {
+        (self.0 & Self::WIDTH) as u16
+    }
+    /// Set height in lines.
+    #[inline]
+    pub const fn set_height(self, val: u16) -> Self {
+        Self((self.0 & !Self::HEIGHT) | (((val as u32) << 16) & Self::HEIGHT))
+    }
+    /// Get height in lines.
+    #[inline]
+    pub const fn height(self) -> u16 {
+        ((self.0 & Self::HEIGHT) >> 16) as u16
+    }
+}
+
+/// Position register in pixels and lines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Position(u32);
+
+impl Position {
+    const X: u32 = 0xfff;
+    const Y: u32 = 0xfff << 16;
+
+    /// Set horizontal position.
+    #[inline]
+    pub const fn set_x(self, val: u16) -> Self {
+        Self((self.0 & !Self::X) | (val as u32 & Self::X))
+    }
+    /// Get horizontal position.
+    #[inline]
+    pub const fn x(self) -> u16 {
+        (self.0 & Self::X) as u16
+    }
+    /// Set vertical position.
+    #[inline]
+    pub const fn set_y(self, val: u16) -> Self {
+        Self((self.0 & !Self::Y) | (((val as u32) << 16) & Self::Y))
+    }
+    /// Get vertical position.
+    #[inline]
+    pub const fn y(self) -> u16 {
+        ((self.0 & Self::Y) >> 16) as u16
+    }
+}
+
+/// Synchronization timing register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Timing(u32);
+
+impl Timing {
+    const SYNC_WIDTH: u32 = 0xff;
+    const BACK_PORCH: u32 = 0xff << 8;
+    const FRONT_PORCH: u32 = 0xff << 16;
+
+    /// Set synchronization pulse width.
+    #[inline]
+    pub const fn set_sync_width(self, val: u8) -> Self {
+        Self((self.0 & !Self::SYNC_WIDTH) | val as u32)
+    }
+    /// Get synchronization pulse width.
+    #[inline]
+    pub const fn sync_width(self) -> u8 {
+        (self.0 & Self::SYNC_WIDTH) as u8
+    }
+    /// Set back porch.
+    #[inline]
+    pub const fn set_back_porch(self, val: u8) -> Self {
+        Self((self.0 & !Self::BACK_PORCH) | ((val as u32) << 8))
+    }
+    /// Get back porch.
+    #[inline]
+    pub const fn back_porch(self) -> u8 {
+        ((self.0 & Self::BACK_PORCH) >> 8) as u8
+    }
+    /// Set front porch.
+    #[inline]
+    pub const fn set_front_porch(self, val: u8) -> Self {
+        Self((self.0 & !Self::FRONT_PORCH) | ((val as u32) << 16))
+    }
+    /// Get front porch.
+    #[inline]
+    pub const fn front_porch(self) -> u8 {
+        ((self.0 & Self::FRONT_PORCH) >> 16) as u8
+    }
+}
+
+/// Interrupt enable and state register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct InterruptConfig(u32);
+
+impl InterruptConfig {
+    const ENABLE: u32 = 0x7;
+
+    /// Enable interrupt.
+    #[inline]
+    pub const fn enable_interrupt(self, val: Interrupt) -> Self {
+        Self((self.0 & Self::ENABLE) | (1 << (val as u32)))
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub const fn disable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 & Self::ENABLE & !(1 << (val as u32)))
+    }
+    /// Check if interrupt is enabled.
+    #[inline]
+    pub const fn is_interrupt_enabled(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32)) != 0
+    }
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub const fn has_interrupt(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32 + 8)) != 0
+    }
+}
+
+/// Interrupt clear register.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct InterruptClear(u32);
+
+impl InterruptClear {
+    /// Clear interrupt flag.
+    #[inline]
+    pub const fn clear_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32)))
+    }
+}
+
+/// Display interrupt event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Interrupt {
+    /// Vertical synchronization, a new frame starts scanning.
+    Vsync = 0,
+    /// Last line of a frame has been sent.
+    FrameDone = 1,
+    /// Memory bandwidth could not keep up with pixel output.
+    Underflow = 2,
+}
+
+/// On-screen display layer configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct LayerConfig(u32);
+
+impl LayerConfig {
+    const ENABLE: u32 = 1 << 0;
+    const PIXEL_ALPHA: u32 = 1 << 1;
+    const FORMAT: u32 = 0x7 << 4;
+    const GLOBAL_ALPHA: u32 = 0xff << 8;
+
+    /// Enable layer.
+    #[inline]
+    pub const fn enable_layer(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable layer.
+    #[inline]
+    pub const fn disable_layer(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if layer is enabled.
+    #[inline]
+    pub const fn is_layer_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Blend using alpha channel of each pixel.
+    #[inline]
+    pub const fn enable_pixel_alpha(self) -> Self {
+        Self(self.0 | Self::PIXEL_ALPHA)
+    }
+    /// Blend using global alpha of layer.
+    #[inline]
+    pub const fn disable_pixel_alpha(self) -> Self {
+        Self(self.0 & !Self::PIXEL_ALPHA)
+    }
+    /// Check if alpha channel of each pixel is used.
+    #[inline]
+    pub const fn is_pixel_alpha_enabled(self) -> bool {
+        self.0 & Self::PIXEL_ALPHA != 0
+    }
+    /// Set layer pixel format.
+    #[inline]
+    pub const fn set_format(self, val: Format) -> Self {
+        Self((self.0 & !Self::FORMAT) | ((val as u32) << 4))
+    }
+    /// Get layer pixel format.
+    #[inline]
+    pub const fn format(self) -> Format {
+        Format::from_bits((self.0 & Self::FORMAT) >> 4)
+    }
+    /// Set global alpha, 255 is opaque.
+    #[inline]
+    pub const fn set_global_alpha(self, val: u8) -> Self {
+        Self((self.0 & !Self::GLOBAL_ALPHA) | ((val as u32) << 8))
+    }
+    /// Get global alpha.
+    #[inline]
+    pub const fn global_alpha(self) -> u8 {
+        ((self.0 & Self::GLOBAL_ALPHA) >> 8) as u8
+    }
+}
+
+/// Synchronization pulse and porch widths.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Porch {
+    /// Synchronization pulse width.
+    pub sync_width: u8,
+    /// Back porch.
+    pub back_porch: u8,
+    /// Front porch.
+    pub front_porch: u8,
+}
+
+/// Layer blending mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Blend {
+    /// Blend the whole layer with a fixed alpha, 255 is opaque.
+    Global(u8),
+    /// Blend using alpha channel of each pixel.
+    PerPixel,
+}
+
+/// On-screen display layer settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Osd {
+    /// Top left corner on screen.
+    pub position: (u16, u16),
+    /// Layer size in pixels and lines.
+    pub size: (u16, u16),
+    /// Layer pixel format.
+    pub format: Format,
+    /// Blending mode.
+    pub blend: Blend,
+    /// Layer pixels.
+    pub buffer: &'static [u8],
+}
+
+/// Display controller configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Config {
+    /// Output interface.
+    pub output: Output,
+    /// Background framebuffer pixel format.
+    pub format: Format,
+    /// Active area in pixels and lines.
+    pub resolution: (u16, u16),
+    /// Horizontal timing in pixel clocks.
+    pub horizontal: Porch,
+    /// Vertical timing in lines.
+    pub vertical: Porch,
+}
+
+impl Default for Config {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            output: Output::Dpi,
+            format: Format::Rgb565,
+            resolution: (480, 272),
+            horizontal: Porch {
+                sync_width: 4,
+                back_porch: 43,
+                front_porch: 8,
+            },
+            vertical: Porch {
+                sync_width: 4,
+                back_porch: 12,
+                front_porch: 8,
+            },
+        }
+    }
+}
+
+impl Config {
+    /// Set output interface.
+    #[inline]
+    pub const fn set_output(mut self, output: Output) -> Self {
+        self.output = output;
+        self
+    }
+    /// Set background framebuffer pixel format.
+    #[inline]
+    pub const fn set_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+    /// Set active area.
+    #[inline]
+    pub const fn set_resolution(mut self, width: u16, height: u16) -> Self {
+        self.resolution = (width, height);
+        self
+    }
+    /// Set horizontal and vertical timing.
+    #[inline]
+    pub const fn set_timing(mut self, horizontal: Porch, vertical: Porch) -> Self {
+        self.horizontal = horizontal;
+        self.vertical = vertical;
+        self
+    }
+    /// Size of one framebuffer in bytes.
+    #[inline]
+    pub const fn frame_bytes(&self) -> u32 {
+        self.resolution.0 as u32 * self.resolution.1 as u32 * self.format.bytes()
+    }
+}
+
+/// Errors on display configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConfigError {
+    /// Buffer is not aligned to 4 bytes.
+    BufferAlignment,
+    /// Buffer cannot hold a whole frame or layer.
+    BufferTooSmall,
+    /// Layer exceeds the active area.
+    LayerOutOfRange,
+    /// Pixel format is not supported by the operation.
+    UnsupportedFormat,
+}
+
+/// Managed display controller.
+pub struct Display<DISP> {
+    display: DISP,
+    framebuffer: &'static mut [u8],
+    config: Config,
+}
+
+impl<DISP: Deref<Target = RegisterBlock>> Display<DISP> {
+    /// Create a display controller scanning `framebuffer`.
+    #[inline]
+    pub fn new(
+        display: DISP,
+        config: Config,
+        framebuffer: &'static mut [u8],
+    ) -> Result<Self, ConfigError> {
+        check_buffer(framebuffer, config.frame_bytes())?;
+        let (width, height) = config.resolution;
+        let timing = |p: Porch| {
+            Timing(0)
+                .set_sync_width(p.sync_width)
+                .set_back_porch(p.back_porch)
+                .set_front_porch(p.front_porch)
+        };
+        let val = DisplayConfig(0)
+            .set_output(config.output)
+            .set_format(config.format);
+        unsafe {
+            display.config.write(val.disable_display());
+            display
+                .resolution
+                .write(Resolution(0).set_width(width).set_height(height));
+            display.horizontal_timing.write(timing(config.horizontal));
+            display.vertical_timing.write(timing(config.vertical));
+            display
+                .framebuffer_address
+                .write(framebuffer.as_ptr() as u32);
+            display
+                .framebuffer_stride
+                .write(width as u32 * config.format.bytes());
+            for layer in display.layers.iter() {
+                layer.config.write(LayerConfig(0));
+            }
+        }
+        Ok(Self {
+            display,
+            framebuffer,
+            config,
+        })
+    }
+    /// Start scanning out frames.
+    #[inline]
+    pub fn start(&mut self) {
+        unsafe { self.display.config.modify(|v| v.enable_display()) };
+    }
+    /// Stop scanning out frames.
+    #[inline]
+    pub fn stop(&mut self) {
+        unsafe { self.display.config.modify(|v| v.disable_display()) };
+    }
+    /// Get background framebuffer for drawing.
+    #[inline]
+    pub fn framebuffer(&mut self) -> &mut [u8] {
+        self.framebuffer
+    }
+    /// Replace background framebuffer, returning the previous one.
+    ///
+    /// New address is latched on next vertical sync; wait for [`Interrupt::Vsync`]
+    /// before drawing into the returned buffer.
+    #[inline]
+    pub fn swap_framebuffer(
+        &mut self,
+        framebuffer: &'static mut [u8],
+    ) -> Result<&'static mut [u8], ConfigError> {
+        check_buffer(framebuffer, self.config.frame_bytes())?;
+        unsafe {
+            self.display
+                .framebuffer_address
+                .write(framebuffer.as_ptr() as u32)
+        };
+        Ok(core::mem::replace(&mut self.framebuffer, framebuffer))
+    }
+    /// Set color outside framebuffer and layers, in RGB888.
+    #[inline]
+    pub fn set_background_color(&mut self, rgb: u32) {
+        unsafe { self.display.background_color.write(rgb & 0xff_ffff) };
+    }
+    /// Show on-screen display layer `L` on top of framebuffer.
+    #[inline]
+    pub fn enable_layer<const L: usize>(&mut self, osd: Osd) -> Result<(), ConfigError> {
+        let (x, y) = osd.position;
+        let (width, height) = osd.size;
+        let (max_width, max_height) = self.config.resolution;
+        if x as u32 + width as u32 > max_width as u32
+            || y as u32 + height as u32 > max_height as u32
+        {
+            return Err(ConfigError::LayerOutOfRange);
+        }
+        check_buffer(
+            osd.buffer,
+            width as u32 * height as u32 * osd.format.bytes(),
+        )?;
+        let mut val = LayerConfig(0).set_format(osd.format);
+        val = match osd.blend {
+            Blend::Global(alpha) => val.disable_pixel_alpha().set_global_alpha(alpha),
+            Blend::PerPixel => val.enable_pixel_alpha().set_global_alpha(0xff),
+        };
+        let layer = &self.display.layers[L];
+        unsafe {
+            layer.config.write(val.disable_layer());
+            layer.address.write(osd.buffer.as_ptr() as u32);
+            layer.position.write(Position(0).set_x(x).set_y(y));
+            layer
+                .size
+                .write(Resolution(0).set_width(width).set_height(height));
+            layer.config.write(val.enable_layer());
+        }
+        Ok(())
+    }
+    /// Hide on-screen display layer `L`.
+    #[inline]
+    pub fn disable_layer<const L: usize>(&mut self) {
+        unsafe { self.display.layers[L].config.modify(|v| v.disable_layer()) };
+    }
+    /// Set global alpha of on-screen display layer `L`.
+    #[inline]
+    pub fn set_layer_alpha<const L: usize>(&mut self, alpha: u8) {
+        unsafe {
+            self.display.layers[L]
+                .config
+                .modify(|v| v.set_global_alpha(alpha))
+        };
+    }
+    /// Enable interrupt.
+    #[inline]
+    pub fn enable_interrupt(&mut self, val: Interrupt) {
+        unsafe { self.display.interrupt.modify(|v| v.enable_interrupt(val)) };
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub fn disable_interrupt(&mut self, val: Interrupt) {
+        unsafe { self.display.interrupt.modify(|v| v.disable_interrupt(val)) };
+    }
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub fn has_interrupt(&self, val: Interrupt) -> bool {
+        self.display.interrupt.read().has_interrupt(val)
+    }
+    /// Clear interrupt flag.
+    #[inline]
+    pub fn clear_interrupt(&mut self, val: Interrupt) {
+        unsafe {
+            self.display
+                .interrupt_clear
+                .write(InterruptClear::default().clear_interrupt(val))
+        };
+    }
+    /// Handle vertical sync interrupt, calling `f` if a new frame has started.
+    ///
+    /// Call this function in the `display` interrupt handler.
+    #[inline]
+    pub fn on_vsync(&mut self, f: impl FnOnce(&mut Self)) {
+        if self.has_interrupt(Interrupt::Vsync) {
+            self.clear_interrupt(Interrupt::Vsync);
+            f(self);
+        }
+    }
+    /// Stop display and release peripheral and framebuffer.
+    #[inline]
+    pub fn free(self) -> (DISP, &'static mut [u8]) {
+        unsafe { self.display.config.modify(|v| v.disable_display()) };
+        (self.display, self.framebuffer)
+    }
+}
+
+#[inline]
+fn check_buffer(buffer: &[u8], bytes: u32) -> Result<(), ConfigError> {
+    if !(buffer.as_ptr() as usize).is_multiple_of(4) {
+        return Err(ConfigError::BufferAlignment);
+    }
+    if bytes == 0 || buffer.len() < bytes as usize {
+        return Err(ConfigError::BufferTooSmall);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DisplayConfig, Format, Interrupt, InterruptClear, InterruptConfig, Layer, LayerConfig,
+        Output, Position, RegisterBlock, Resolution, Timing,
+    };
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, resolution), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, horizontal_timing), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, vertical_timing), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, framebuffer_address), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, framebuffer_stride), 0x14);
+        assert_eq!(offset_of!(RegisterBlock, background_color), 0x18);
+        assert_eq!(offset_of!(RegisterBlock, interrupt), 0x20);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_clear), 0x24);
+        assert_eq!(offset_of!(RegisterBlock, layers), 0x40);
+        assert_eq!(size_of::<Layer>(), 0x10);
+        assert_eq!(offset_of!(Layer, address), 0x04);
+        assert_eq!(offset_of!(Layer, position), 0x08);
+        assert_eq!(offset_of!(Layer, size), 0x0c);
+    }
+
+    #[test]
+    fn struct_display_config_functions() {
+        let mut val = DisplayConfig(0x0);
+
+        val = val.enable_display();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_display_enabled());
+        val = val.disable_display();
+        assert!(!val.is_display_enabled());
+
+        val = val.set_output(Output::Dbi);
+        assert_eq!(val.0, 0x0000_0004);
+        assert_eq!(val.output(), Output::Dbi);
+        val = val.set_output(Output::Dsi);
+        assert_eq!(val.output(), Output::Dsi);
+
+        val = DisplayConfig(0x0).set_format(Format::Yuv422);
+        assert_eq!(val.0, 0x0000_0030);
+        assert_eq!(val.format(), Format::Yuv422);
+        val = val.set_format(Format::Argb8888);
+        assert_eq!(val.format(), Format::Argb8888);
+    }
+
+    #[test]
+    fn struct_resolution_position_functions() {
+        let val = Resolution(0x0).set_width(800).set_height(480);
+        assert_eq!(val.0, 0x01e0_0320);
+        assert_eq!(val.width(), 800);
+        assert_eq!(val.height(), 480);
+
+        let val = Position(0x0).set_x(0xfff).set_y(0x10);
+        assert_eq!(val.0, 0x0010_0fff);
+        assert_eq!(val.x(), 0xfff);
+        assert_eq!(val.y(), 0x10);
+    }
+
+    #[test]
+    fn struct_timing_functions() {
+        let val = Timing(0x0)
+            .set_sync_width(0x04)
+            .set_back_porch(0x2b)
+            .set_front_porch(0x08);
+        assert_eq!(val.0, 0x0008_2b04);
+        assert_eq!(val.sync_width(), 0x04);
+        assert_eq!(val.back_porch(), 0x2b);
+        assert_eq!(val.front_porch(), 0x08);
+    }
+
+    #[test]
+    fn struct_interrupt_functions() {
+        let mut val = InterruptConfig(0x0);
+
+        val = val.enable_interrupt(Interrupt::Vsync);
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_interrupt_enabled(Interrupt::Vsync));
+        val = val.enable_interrupt(Interrupt::Underflow);
+        assert_eq!(val.0, 0x0000_0005);
+        val = val.disable_interrupt(Interrupt::Vsync);
+        assert!(!val.is_interrupt_enabled(Interrupt::Vsync));
+
+        // Writing back interrupt enables must not write interrupt state bits.
+        val = InterruptConfig(0x0000_0300).enable_interrupt(Interrupt::FrameDone);
+        assert_eq!(val.0, 0x0000_0002);
+
+        let val = InterruptConfig(0x0000_0100);
+        assert!(val.has_interrupt(Interrupt::Vsync));
+        assert!(!val.has_interrupt(Interrupt::FrameDone));
+
+        let val = InterruptClear::default()
+            .clear_interrupt(Interrupt::Vsync)
+            .clear_interrupt(Interrupt::Underflow);
+        assert_eq!(val.0, 0x0000_0005);
+    }
+
+    #[test]
+    fn struct_layer_config_functions() {
+        let mut val = LayerConfig(0x0);
+
+        val = val.enable_layer();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_layer_enabled());
+        val = val.disable_layer();
+        assert!(!val.is_layer_enabled());
+
+        val = val.enable_pixel_alpha();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_pixel_alpha_enabled());
+        val = val.disable_pixel_alpha();
+        assert!(!val.is_pixel_alpha_enabled());
+
+        val = val.set_format(Format::Argb8888);
+        assert_eq!(val.0, 0x0000_0020);
+        assert_eq!(val.format(), Format::Argb8888);
+
+        val = val.set_global_alpha(0x80);
+        assert_eq!(val.0, 0x0000_8020);
+        assert_eq!(val.global_alpha(), 0x80);
+    }
+}
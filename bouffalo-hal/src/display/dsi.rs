@@ -0,0 +1,459 @@
+//! MIPI Display Serial Interface host.
+//!
+//! Most DSI panels need a sequence of Display Command Set (DCS) commands after reset.
+//! [`Dsi`] sends them in command mode, then switches to video mode where pixels come
+//! from the display controller.
+
+use core::ops::Deref;
+use volatile_register::{RO, RW, WO};
+
+/// MIPI Display Serial Interface host registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Host configuration register.
+    pub config: RW<DsiConfig>,
+    /// Command packet header, writing this register sends the packet.
+    pub command_header: WO<PacketHeader>,
+    /// Command packet payload FIFO.
+    pub command_payload: WO<u32>,
+    /// Command transfer state register.
+    pub command_state: RO<CommandState>,
+    _reserved0: [u8; 0x70],
+    /// D-PHY lane configuration register.
+    pub dphy_config: RW<DphyConfig>,
+    /// D-PHY timing configuration registers.
+    pub dphy_timing: [RW<u32>; 4],
+}
+
+/// Host configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct DsiConfig(u32);
+
+impl DsiConfig {
+    const ENABLE: u32 = 1 << 0;
+    const LANES: u32 = 0x3 << 1;
+    const VIDEO_MODE: u32 = 1 << 3;
+    const VIRTUAL_CHANNEL: u32 = 0x3 << 4;
+    const LOW_POWER_COMMAND: u32 = 1 << 6;
+
+    /// Enable host.
+    #[inline]
+    pub const fn enable_host(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable host.
+    #[inline]
+    pub const fn disable_host(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if host is enabled.
+    #[inline]
+    pub const fn is_host_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Set number of data lanes.
+    #[inline]
+    pub const fn set_lanes(self, val: Lanes) -> Self {
+        Self((self.0 & !Self::LANES) | ((val as u32) << 1))
+    }
+    /// Get number of data lanes.
+    #[inline]
+    pub const fn lanes(self) -> Lanes {
+        match (self.0 & Self::LANES) >> 1 {
+            0 => Lanes::One,
+            1 => Lanes::Two,
+            2 => Lanes::Three,
+            _ => Lanes::Four,
+        }
+    }
+    /// Stream pixels from display controller.
+    #[inline]
+    pub const fn enable_video_mode(self) -> Self {
+        Self(self.0 | Self::VIDEO_MODE)
+    }
+    /// Send command packets only.
+    #[inline]
+    pub const fn disable_video_mode(self) -> Self {
+        Self(self.0 & !Self::VIDEO_MODE)
+    }
+    /// Check if video mode is enabled.
+    #[inline]
+    pub const fn is_video_mode_enabled(self) -> bool {
+        self.0 & Self::VIDEO_MODE != 0
+    }
+    /// Set virtual channel of video stream.
+    #[inline]
+    pub const fn set_virtual_channel(self, val: u8) -> Self {
+        Self((self.0 & !Self::VIRTUAL_CHANNEL) | (((val as u32) << 4) & Self::VIRTUAL_CHANNEL))
+    }
+    /// Get virtual channel of video stream.
+    #[inline]
+    pub const fn virtual_channel(self) -> u8 {
+        ((self.0 & Self::VIRTUAL_CHANNEL) >> 4) as u8
+    }
+    /// Send command packets in low power mode.
+    #[inline]
+    pub const fn enable_low_power_command(self) -> Self {
+        Self(self.0 | Self::LOW_POWER_COMMAND)
+    }
+    /// Send command packets in high speed mode.
+    #[inline]
+    pub const fn disable_low_power_command(self) -> Self {
+        Self(self.0 & !Self::LOW_POWER_COMMAND)
+    }
+    /// Check if command packets are sent in low power mode.
+    #[inline]
+    pub const fn is_low_power_command_enabled(self) -> bool {
+        self.0 & Self::LOW_POWER_COMMAND != 0
+    }
+}
+
+/// Number of D-PHY data lanes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Lanes {
+    /// Data lane 0 only.
+    One = 0,
+    /// Data lane 0 to 1.
+    Two = 1,
+    /// Data lane 0 to 2.
+    Three = 2,
+    /// Data lane 0 to 3.
+    Four = 3,
+}
+
+/// Command packet header register.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct PacketHeader(u32);
+
+impl PacketHeader {
+    const DATA_TYPE: u32 = 0x3f;
+    const VIRTUAL_CHANNEL: u32 = 0x3 << 6;
+    const DATA: u32 = 0xffff << 8;
+
+    /// Set packet data type.
+    #[inline]
+    pub const fn set_data_type(self, val: u8) -> Self {
+        Self((self.0 & !Self::DATA_TYPE) | (val as u32 & Self::DATA_TYPE))
+    }
+    /// Get packet data type.
+    #[inline]
+    pub const fn data_type(self) -> u8 {
+        (self.0 & Self::DATA_TYPE) as u8
+    }
+    /// Set packet virtual channel.
+    #[inline]
+    pub const fn set_virtual_channel(self, val: u8) -> Self {
+        Self((self.0 & !Self::VIRTUAL_CHANNEL) | (((val as u32) << 6) & Self::VIRTUAL_CHANNEL))
+    }
+    /// Get packet virtual channel.
+    #[inline]
+    pub const fn virtual_channel(self) -> u8 {
+        ((self.0 & Self::VIRTUAL_CHANNEL) >> 6) as u8
+    }
+    /// Set short packet parameters or long packet word count.
+    #[inline]
+    pub const fn set_data(self, val: u16) -> Self {
+        Self((self.0 & !Self::DATA) | ((val as u32) << 8))
+    }
+    /// Get short packet parameters or long packet word count.
+    #[inline]
+    pub const fn data(self) -> u16 {
+        ((self.0 & Self::DATA) >> 8) as u16
+    }
+}
+
+/// Command transfer state register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct CommandState(u32);
+
+impl CommandState {
+    const BUSY: u32 = 1 << 0;
+    const PAYLOAD_FULL: u32 = 1 << 1;
+    const ERROR: u32 = 1 << 2;
+
+    /// Check if a command packet is being sent.
+    #[inline]
+    pub const fn is_busy(self) -> bool {
+        self.0 & Self::BUSY != 0
+    }
+    /// Check if payload FIFO is full.
+    #[inline]
+    pub const fn is_payload_full(self) -> bool {
+        self.0 & Self::PAYLOAD_FULL != 0
+    }
+    /// Check if panel reported an error in acknowledge.
+    #[inline]
+    pub const fn has_error(self) -> bool {
+        self.0 & Self::ERROR != 0
+    }
+}
+
+/// D-PHY lane configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct DphyConfig(u32);
+
+impl DphyConfig {
+    const DATA_LANES: u32 = 0xf;
+    const CLOCK_LANE: u32 = 1 << 4;
+    const CONTINUOUS_CLOCK: u32 = 1 << 5;
+
+    /// Enable data lanes starting from lane 0.
+    #[inline]
+    pub const fn set_data_lanes(self, val: Lanes) -> Self {
+        let bits = (1 << (val as u32 + 1)) - 1;
+        Self((self.0 & !Self::DATA_LANES) | bits)
+    }
+    /// Get number of enabled data lanes.
+    #[inline]
+    pub const fn data_lanes(self) -> u8 {
+        (self.0 & Self::DATA_LANES).count_ones() as u8
+    }
+    /// Enable clock lane.
+    #[inline]
+    pub const fn enable_clock_lane(self) -> Self {
+        Self(self.0 | Self::CLOCK_LANE)
+    }
+    /// Disable clock lane.
+    #[inline]
+    pub const fn disable_clock_lane(self) -> Self {
+        Self(self.0 & !Self::CLOCK_LANE)
+    }
+    /// Check if clock lane is enabled.
+    #[inline]
+    pub const fn is_clock_lane_enabled(self) -> bool {
+        self.0 & Self::CLOCK_LANE != 0
+    }
+    /// Keep clock lane in high speed mode between packets.
+    #[inline]
+    pub const fn enable_continuous_clock(self) -> Self {
+        Self(self.0 | Self::CONTINUOUS_CLOCK)
+    }
+    /// Return clock lane to low power mode between packets.
+    #[inline]
+    pub const fn disable_continuous_clock(self) -> Self {
+        Self(self.0 & !Self::CONTINUOUS_CLOCK)
+    }
+    /// Check if continuous clock is enabled.
+    #[inline]
+    pub const fn is_continuous_clock_enabled(self) -> bool {
+        self.0 & Self::CONTINUOUS_CLOCK != 0
+    }
+}
+
+/// DCS short write without parameter.
+const DCS_SHORT_WRITE: u8 = 0x05;
+/// DCS short write with one parameter.
+const DCS_SHORT_WRITE_PARAM: u8 = 0x15;
+/// DCS long write.
+const DCS_LONG_WRITE: u8 = 0x39;
+
+/// Errors on command transfer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// Command parameters exceed maximum long packet size.
+    PayloadTooLong,
+    /// Panel reported an error.
+    Acknowledge,
+}
+
+/// Managed MIPI Display Serial Interface host.
+pub struct Dsi<DSI> {
+    dsi: DSI,
+}
+
+impl<DSI: Deref<Target = RegisterBlock>> Dsi<DSI> {
+    /// Create a host in low power command mode.
+    #[inline]
+    pub fn new(dsi: DSI, lanes: Lanes) -> Self {
+        let val = DsiConfig(0)
+            .set_lanes(lanes)
+            .set_virtual_channel(0)
+            .enable_low_power_command();
+        unsafe {
+            dsi.config.write(val.disable_host());
+            dsi.dphy_config
+                .write(DphyConfig(0).set_data_lanes(lanes).enable_clock_lane());
+            dsi.config.write(val.enable_host());
+        }
+        Self { dsi }
+    }
+    /// Send a DCS command with parameters and wait until it is transferred.
+    pub fn write_command(&mut self, command: u8, params: &[u8]) -> Result<(), Error> {
+        self.wait_idle();
+        let header = PacketHeader::default().set_virtual_channel(0);
+        let header = match params {
+            [] => header
+                .set_data_type(DCS_SHORT_WRITE)
+                .set_data(command as u16),
+            [param] => header
+                .set_data_type(DCS_SHORT_WRITE_PARAM)
+                .set_data(command as u16 | (*param as u16) << 8),
+            _ => {
+                let count = params.len() + 1;
+                if count > u16::MAX as usize {
+                    return Err(Error::PayloadTooLong);
+                }
+                let mut bytes = core::iter::once(&command).chain(params.iter());
+                for _ in 0..count.div_ceil(4) {
+                    let mut word = 0u32;
+                    for shift in 0..4 {
+                        if let Some(b) = bytes.next() {
+                            word |= (*b as u32) << (shift * 8);
+                        }
+                    }
+                    while self.dsi.command_state.read().is_payload_full() {
+                        core::hint::spin_loop();
+                    }
+                    unsafe { self.dsi.command_payload.write(word) };
+                }
+                header.set_data_type(DCS_LONG_WRITE).set_data(count as u16)
+            }
+        };
+        unsafe { self.dsi.command_header.write(header) };
+        self.wait_idle();
+        if self.dsi.command_state.read().has_error() {
+            return Err(Error::Acknowledge);
+        }
+        Ok(())
+    }
+    /// Send a panel initialization sequence of DCS commands and parameters.
+    #[inline]
+    pub fn write_commands(&mut self, sequence: &[(u8, &[u8])]) -> Result<(), Error> {
+        for (command, params) in sequence {
+            self.write_command(*command, params)?;
+        }
+        Ok(())
+    }
+    /// Switch to video mode, streaming pixels from display controller.
+    #[inline]
+    pub fn enter_video_mode(&mut self) {
+        self.wait_idle();
+        unsafe {
+            self.dsi.dphy_config.modify(|v| v.enable_continuous_clock());
+            self.dsi.config.modify(|v| v.enable_video_mode());
+        }
+    }
+    /// Switch back to command mode.
+    #[inline]
+    pub fn enter_command_mode(&mut self) {
+        unsafe {
+            self.dsi.config.modify(|v| v.disable_video_mode());
+            self.dsi
+                .dphy_config
+                .modify(|v| v.disable_continuous_clock());
+        }
+    }
+    /// Disable host and release the peripheral.
+    #[inline]
+    pub fn free(self) -> DSI {
+        unsafe {
+            self.dsi.config.modify(|v| v.disable_host());
+            self.dsi.dphy_config.write(DphyConfig(0));
+        }
+        self.dsi
+    }
+    #[inline]
+    fn wait_idle(&self) {
+        while self.dsi.command_state.read().is_busy() {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommandState, DphyConfig, DsiConfig, Lanes, PacketHeader, RegisterBlock};
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, command_header), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, command_payload), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, command_state), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, dphy_config), 0x80);
+        assert_eq!(offset_of!(RegisterBlock, dphy_timing), 0x84);
+    }
+
+    #[test]
+    fn struct_dsi_config_functions() {
+        let mut val = DsiConfig(0x0);
+
+        val = val.enable_host();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_host_enabled());
+        val = val.disable_host();
+        assert!(!val.is_host_enabled());
+
+        val = val.set_lanes(Lanes::Four);
+        assert_eq!(val.0, 0x0000_0006);
+        assert_eq!(val.lanes(), Lanes::Four);
+        val = val.set_lanes(Lanes::Two);
+        assert_eq!(val.lanes(), Lanes::Two);
+
+        val = DsiConfig(0x0).enable_video_mode();
+        assert_eq!(val.0, 0x0000_0008);
+        assert!(val.is_video_mode_enabled());
+        val = val.disable_video_mode();
+        assert!(!val.is_video_mode_enabled());
+
+        val = val.set_virtual_channel(3);
+        assert_eq!(val.0, 0x0000_0030);
+        assert_eq!(val.virtual_channel(), 3);
+
+        val = DsiConfig(0x0).enable_low_power_command();
+        assert_eq!(val.0, 0x0000_0040);
+        assert!(val.is_low_power_command_enabled());
+        val = val.disable_low_power_command();
+        assert!(!val.is_low_power_command_enabled());
+    }
+
+    #[test]
+    fn struct_packet_header_functions() {
+        let val = PacketHeader::default()
+            .set_data_type(0x39)
+            .set_virtual_channel(1)
+            .set_data(0x1234);
+        assert_eq!(val.0, 0x0012_3479);
+        assert_eq!(val.data_type(), 0x39);
+        assert_eq!(val.virtual_channel(), 1);
+        assert_eq!(val.data(), 0x1234);
+    }
+
+    #[test]
+    fn struct_command_state_functions() {
+        let val = CommandState(0x0000_0005);
+        assert!(val.is_busy());
+        assert!(!val.is_payload_full());
+        assert!(val.has_error());
+    }
+
+    #[test]
+    fn struct_dphy_config_functions() {
+        let mut val = DphyConfig(0x0);
+
+        val = val.set_data_lanes(Lanes::Two);
+        assert_eq!(val.0, 0x0000_0003);
+        assert_eq!(val.data_lanes(), 2);
+        val = val.set_data_lanes(Lanes::Four);
+        assert_eq!(val.0, 0x0000_000f);
+        assert_eq!(val.data_lanes(), 4);
+
+        val = DphyConfig(0x0).enable_clock_lane();
+        assert_eq!(val.0, 0x0000_0010);
+        assert!(val.is_clock_lane_enabled());
+        val = val.disable_clock_lane();
+        assert!(!val.is_clock_lane_enabled());
+
+        val = val.enable_continuous_clock();
+        assert_eq!(val.0, 0x0000_0020);
+        assert!(val.is_continuous_clock_enabled());
+        val = val.disable_continuous_clock();
+        assert!(!val.is_continuous_clock_enabled());
+    }
+}
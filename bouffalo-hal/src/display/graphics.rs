@@ -0,0 +1,202 @@
+//! `embedded-graphics` drawing into display framebuffers.
+
+use super::{ConfigError, Display, Format, Interrupt, RegisterBlock, check_buffer};
+use crate::arch::xtheadcsr::clean_dcache;
+use core::ops::Deref;
+use embedded_graphics_core::{
+    Pixel,
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions, Size},
+    pixelcolor::{IntoStorage, Rgb565},
+    primitives::Rectangle,
+};
+
+/// RGB565 pixel buffer drawn with `embedded-graphics`.
+///
+/// Works on the background framebuffer as well as on-screen display layer buffers.
+/// Drawing stays in data cache; call [`flush`](Self::flush) before the display
+/// controller scans the buffer.
+pub struct Framebuffer<'a> {
+    buffer: &'a mut [u8],
+    size: Size,
+}
+
+impl<'a> Framebuffer<'a> {
+    /// Wrap a buffer of `width` by `height` RGB565 pixels.
+    #[inline]
+    pub fn new(buffer: &'a mut [u8], width: u16, height: u16) -> Result<Self, ConfigError> {
+        check_buffer(
+            buffer,
+            width as u32 * height as u32 * Format::Rgb565.bytes(),
+        )?;
+        Ok(Self {
+            buffer,
+            size: Size::new(width as u32, height as u32),
+        })
+    }
+    /// Write drawn pixels back to memory for the display controller.
+    #[inline]
+    pub fn flush(&self) {
+        clean_dcache(self.buffer.as_ptr() as usize, self.frame_bytes());
+    }
+    #[inline]
+    fn frame_bytes(&self) -> usize {
+        self.size.width as usize * self.size.height as usize * 2
+    }
+    #[inline]
+    fn set_pixel(&mut self, x: u32, y: u32, color: Rgb565) {
+        let offset = (y * self.size.width + x) as usize * 2;
+        self.buffer[offset..offset + 2].copy_from_slice(&color.into_storage().to_le_bytes());
+    }
+}
+
+impl OriginDimensions for Framebuffer<'_> {
+    #[inline]
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl DrawTarget for Framebuffer<'_> {
+    type Color = Rgb565;
+    type Error = core::convert::Infallible;
+
+    #[inline]
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let bounds = self.bounding_box();
+        for Pixel(point, color) in pixels {
+            if bounds.contains(point) {
+                self.set_pixel(point.x as u32, point.y as u32, color);
+            }
+        }
+        Ok(())
+    }
+    #[inline]
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box());
+        if area.is_zero_sized() {
+            return Ok(());
+        }
+        let bytes = color.into_storage().to_le_bytes();
+        let (x, width) = (area.top_left.x as usize, area.size.width as usize);
+        for y in area.top_left.y as usize..area.top_left.y as usize + area.size.height as usize {
+            let start = (y * self.size.width as usize + x) * 2;
+            for pixel in self.buffer[start..start + width * 2].chunks_exact_mut(2) {
+                pixel.copy_from_slice(&bytes);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Double-buffered background framebuffer drawn with `embedded-graphics`.
+///
+/// Drawing goes into the back buffer while the display controller scans the front
+/// buffer. [`present`](Self::present) swaps them; the new back buffer is only drawn
+/// after the swap is latched on next vertical sync, so frames never tear. The display
+/// must use RGB565 background format.
+pub struct DoubleBuffer<DISP> {
+    display: Display<DISP>,
+    back: &'static mut [u8],
+    swap_pending: bool,
+}
+
+impl<DISP: Deref<Target = RegisterBlock>> DoubleBuffer<DISP> {
+    /// Create a double buffer from a display and its second framebuffer.
+    #[inline]
+    pub fn new(display: Display<DISP>, back: &'static mut [u8]) -> Result<Self, ConfigError> {
+        if display.config.format != Format::Rgb565 {
+            return Err(ConfigError::UnsupportedFormat);
+        }
+        check_buffer(back, display.config.frame_bytes())?;
+        Ok(Self {
+            display,
+            back,
+            swap_pending: false,
+        })
+    }
+    /// Show the back buffer from next vertical sync on.
+    #[inline]
+    pub fn present(&mut self) {
+        self.wait_swap();
+        let (width, height) = self.display.config.resolution;
+        let bytes = width as usize * height as usize * 2;
+        clean_dcache(self.back.as_ptr() as usize, bytes);
+        let back = core::mem::take(&mut self.back);
+        // Buffer was checked on creation or returned by the display.
+        self.back = self.display.swap_framebuffer(back).unwrap();
+        self.display.clear_interrupt(Interrupt::Vsync);
+        self.swap_pending = true;
+    }
+    /// Handle vertical sync interrupt, completing a pending swap.
+    ///
+    /// Call this function in the `display` interrupt handler, or leave vertical sync
+    /// to be polled when drawing next frame.
+    #[inline]
+    pub fn on_vsync(&mut self) {
+        if self.display.has_interrupt(Interrupt::Vsync) {
+            self.display.clear_interrupt(Interrupt::Vsync);
+            self.swap_pending = false;
+        }
+    }
+    /// Check if a swap waits for vertical sync.
+    #[inline]
+    pub fn is_swap_pending(&self) -> bool {
+        self.swap_pending
+    }
+    /// Get the display controller, e.g. to configure on-screen display layers.
+    #[inline]
+    pub fn display(&mut self) -> &mut Display<DISP> {
+        &mut self.display
+    }
+    /// Release display controller and back buffer.
+    #[inline]
+    pub fn free(mut self) -> (Display<DISP>, &'static mut [u8]) {
+        self.wait_swap();
+        (self.display, self.back)
+    }
+    #[inline]
+    fn wait_swap(&mut self) {
+        while self.swap_pending {
+            self.on_vsync();
+            core::hint::spin_loop();
+        }
+    }
+    #[inline]
+    fn back_buffer(&mut self) -> Framebuffer<'_> {
+        self.wait_swap();
+        let (width, height) = self.display.config.resolution;
+        Framebuffer {
+            buffer: &mut *self.back,
+            size: Size::new(width as u32, height as u32),
+        }
+    }
+}
+
+impl<DISP: Deref<Target = RegisterBlock>> OriginDimensions for DoubleBuffer<DISP> {
+    #[inline]
+    fn size(&self) -> Size {
+        let (width, height) = self.display.config.resolution;
+        Size::new(width as u32, height as u32)
+    }
+}
+
+impl<DISP: Deref<Target = RegisterBlock>> DrawTarget for DoubleBuffer<DISP> {
+    type Color = Rgb565;
+    type Error = core::convert::Infallible;
+
+    #[inline]
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.back_buffer().draw_iter(pixels)
+    }
+    #[inline]
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.back_buffer().fill_solid(area, color)
+    }
+}
@@ -0,0 +1,161 @@
+//! DMA buffers and data cache maintenance.
+//!
+//! DMA controllers access memory directly, bypassing data caches of BL808 cores. Buffers
+//! handed to DMA must be written back to memory before the transfer, and stale cache
+//! lines must be discarded before the core reads data that DMA wrote.
+
+use super::{DmaAddr, LliTransfer};
+
+pub use crate::arch::xtheadcsr::{clean_dcache, flush_dcache, invalidate_dcache};
+
+/// Word type that DMA transfers.
+///
+/// # Safety
+///
+/// The type must accept any bit pattern and have no padding.
+pub unsafe trait Word: Copy + 'static {}
+
+unsafe impl Word for u8 {}
+unsafe impl Word for u16 {}
+unsafe impl Word for u32 {}
+unsafe impl Word for u64 {}
+
+/// Buffer that DMA reads from.
+///
+/// # Safety
+///
+/// `read_buffer` must return the same valid region each time it is called while `self`
+/// is not moved or mutated, and the region must stay valid as long as `self` lives.
+pub unsafe trait ReadBuffer {
+    /// Word type of the buffer.
+    type Word: Word;
+    /// Get start address and number of words of the buffer.
+    ///
+    /// # Safety
+    ///
+    /// Caller must not let DMA access the region after `self` is dropped.
+    unsafe fn read_buffer(&self) -> (*const Self::Word, usize);
+}
+
+/// Buffer that DMA writes into.
+///
+/// # Safety
+///
+/// `write_buffer` must return the same valid region each time it is called while `self`
+/// is not moved, and the region must stay valid as long as `self` lives.
+pub unsafe trait WriteBuffer {
+    /// Word type of the buffer.
+    type Word: Word;
+    /// Get start address and number of words of the buffer.
+    ///
+    /// # Safety
+    ///
+    /// Caller must not let DMA access the region after `self` is dropped.
+    unsafe fn write_buffer(&mut self) -> (*mut Self::Word, usize);
+}
+
+unsafe impl<W: Word> ReadBuffer for &'static [W] {
+    type Word = W;
+    #[inline]
+    unsafe fn read_buffer(&self) -> (*const W, usize) {
+        (self.as_ptr(), self.len())
+    }
+}
+
+unsafe impl<W: Word> ReadBuffer for &'static mut [W] {
+    type Word = W;
+    #[inline]
+    unsafe fn read_buffer(&self) -> (*const W, usize) {
+        (self.as_ptr(), self.len())
+    }
+}
+
+unsafe impl<W: Word, const N: usize> ReadBuffer for &'static [W; N] {
+    type Word = W;
+    #[inline]
+    unsafe fn read_buffer(&self) -> (*const W, usize) {
+        (self.as_ptr(), N)
+    }
+}
+
+unsafe impl<W: Word, const N: usize> ReadBuffer for &'static mut [W; N] {
+    type Word = W;
+    #[inline]
+    unsafe fn read_buffer(&self) -> (*const W, usize) {
+        (self.as_ptr(), N)
+    }
+}
+
+unsafe impl<W: Word> WriteBuffer for &'static mut [W] {
+    type Word = W;
+    #[inline]
+    unsafe fn write_buffer(&mut self) -> (*mut W, usize) {
+        (self.as_mut_ptr(), self.len())
+    }
+}
+
+unsafe impl<W: Word, const N: usize> WriteBuffer for &'static mut [W; N] {
+    type Word = W;
+    #[inline]
+    unsafe fn write_buffer(&mut self) -> (*mut W, usize) {
+        (self.as_mut_ptr(), N)
+    }
+}
+
+impl LliTransfer {
+    /// Transfer from buffer to peripheral data register.
+    ///
+    /// Data cache lines of the buffer are written back to memory before returning.
+    #[inline]
+    pub fn memory_to_peripheral<B: ReadBuffer>(buf: &B, dst: DmaAddr) -> Self {
+        let (ptr, len) = unsafe { buf.read_buffer() };
+        let nbytes = len * size_of::<B::Word>();
+        clean_dcache(ptr as usize, nbytes);
+        Self {
+            src_addr: ptr as u32,
+            dst_addr: dst as u32,
+            nbytes: nbytes as u32,
+        }
+    }
+    /// Transfer from peripheral data register into buffer.
+    ///
+    /// Data cache lines of the buffer are written back and discarded so no dirty line
+    /// overwrites incoming data; call [`invalidate_dcache`] again after the transfer
+    /// completes and before reading the buffer.
+    #[inline]
+    pub fn peripheral_to_memory<B: WriteBuffer>(src: DmaAddr, buf: &mut B) -> Self {
+        let (ptr, len) = unsafe { buf.write_buffer() };
+        let nbytes = len * size_of::<B::Word>();
+        flush_dcache(ptr as usize, nbytes);
+        Self {
+            src_addr: src as u32,
+            dst_addr: ptr as u32,
+            nbytes: nbytes as u32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DmaAddr, LliTransfer, ReadBuffer, WriteBuffer};
+
+    #[test]
+    fn struct_lli_transfer_from_buffer() {
+        static TX: [u16; 3] = [1, 2, 3];
+        let tx = &TX;
+        let transfer = LliTransfer::memory_to_peripheral(&tx, DmaAddr::Uart0Tx);
+        assert_eq!(transfer.src_addr, TX.as_ptr() as u32);
+        assert_eq!(transfer.dst_addr, 0x2000A088);
+        assert_eq!(transfer.nbytes, 6);
+        assert_eq!(unsafe { tx.read_buffer() }.1, 3);
+
+        static mut RX: [u32; 5] = [0; 5];
+        let mut rx: &'static mut [u32] =
+            unsafe { core::slice::from_raw_parts_mut((&raw mut RX).cast(), 5) };
+        let ptr = unsafe { rx.write_buffer() }.0;
+        let transfer = LliTransfer::peripheral_to_memory(DmaAddr::Uart1Rx, &mut rx);
+        assert_eq!(transfer.src_addr, 0x2000A18C);
+        assert_eq!(transfer.dst_addr, ptr as u32);
+        assert_eq!(transfer.nbytes, 20);
+    }
+}
@@ -0,0 +1,142 @@
+//! Linked list item chains for scatter-gather transfers.
+
+use super::{LliPool, LliTransfer, UntypedChannel};
+
+/// Chain of transfers run one after another by a DMA channel.
+///
+/// A chain holds up to `N` transfers; in cyclic mode the last transfer links back
+/// to the first, so the channel keeps running without CPU intervention.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TransferChain<const N: usize> {
+    transfers: [LliTransfer; N],
+    len: usize,
+    cyclic: bool,
+}
+
+impl<const N: usize> TransferChain<N> {
+    /// Creates an empty transfer chain.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            transfers: [LliTransfer {
+                src_addr: 0,
+                dst_addr: 0,
+                nbytes: 0,
+            }; N],
+            len: 0,
+            cyclic: false,
+        }
+    }
+    /// Append a transfer to the end of this chain.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the chain already holds `N` transfers.
+    #[inline]
+    pub const fn then(mut self, transfer: LliTransfer) -> Self {
+        assert!(self.len < N, "transfer chain capacity exceeded");
+        self.transfers[self.len] = transfer;
+        self.len += 1;
+        self
+    }
+    /// Link the last transfer back to the first one.
+    #[inline]
+    pub const fn cyclic(self) -> Self {
+        Self {
+            cyclic: true,
+            ..self
+        }
+    }
+    /// Check if this chain is cyclic.
+    #[inline]
+    pub const fn is_cyclic(&self) -> bool {
+        self.cyclic
+    }
+    /// Get transfers in this chain.
+    #[inline]
+    pub fn transfers(&self) -> &[LliTransfer] {
+        &self.transfers[..self.len]
+    }
+}
+
+impl<const N: usize> Default for TransferChain<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Error on loading a transfer chain into a DMA channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChainError {
+    /// Transfer chain holds no transfer.
+    Empty,
+    /// Linked list item pool is too small for the chain.
+    PoolExhausted,
+}
+
+impl UntypedChannel<'_> {
+    /// Load a transfer chain into linked list items and channel registers.
+    ///
+    /// Returns number of linked list items used; `lli_pool` must outlive the transfer.
+    #[inline]
+    pub fn load_chain<const N: usize>(
+        &self,
+        chain: &TransferChain<N>,
+        lli_pool: &mut [LliPool],
+    ) -> Result<usize, ChainError> {
+        if chain.len == 0 {
+            return Err(ChainError::Empty);
+        }
+        let mut transfers = chain.transfers;
+        let used = self.lli_reload(
+            lli_pool,
+            lli_pool.len() as u32,
+            &mut transfers[..chain.len],
+            chain.len as u32,
+        );
+        if used < 0 {
+            return Err(ChainError::PoolExhausted);
+        }
+        if chain.cyclic {
+            self.lli_link_head(lli_pool, used as usize);
+        }
+        Ok(used as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LliTransfer, TransferChain};
+
+    #[test]
+    fn struct_transfer_chain_builder() {
+        let first = LliTransfer {
+            src_addr: 0x6200_0000,
+            dst_addr: 0x2000_a088,
+            nbytes: 64,
+        };
+        let second = LliTransfer {
+            src_addr: 0x6200_1000,
+            dst_addr: 0x2000_a088,
+            nbytes: 128,
+        };
+        let chain = TransferChain::<4>::new().then(first).then(second);
+        assert_eq!(chain.transfers(), &[first, second]);
+        assert!(!chain.is_cyclic());
+        let chain = chain.cyclic();
+        assert!(chain.is_cyclic());
+        assert_eq!(TransferChain::<4>::new().transfers(), &[]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn struct_transfer_chain_capacity() {
+        let transfer = LliTransfer {
+            src_addr: 0,
+            dst_addr: 0,
+            nbytes: 4,
+        };
+        let _ = TransferChain::<1>::new().then(transfer).then(transfer);
+    }
+}
@@ -117,8 +117,8 @@ impl<'a, T> DerefMut for TypedChannel<'a, T> {
 
 /// Channel without a dedicated peripheral type.
 pub struct UntypedChannel<'a> {
-    dma: &'a RegisterBlock,
-    channel_id: usize,
+    pub(crate) dma: &'a RegisterBlock,
+    pub(crate) channel_id: usize,
 }
 
 impl<'a> UntypedChannel<'a> {
@@ -0,0 +1,201 @@
+//! Channel interrupts, completion callbacks and futures.
+
+use super::{ErrorClear, RegisterBlock, TransferCompleteClear, UntypedChannel};
+use core::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicPtr, AtomicU8, AtomicUsize, Ordering},
+    task::{Context, Poll},
+};
+
+/// Event on a DMA channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChannelEvent {
+    /// Transfer has completed.
+    TransferComplete,
+    /// Transfer has failed on a bus error.
+    Error,
+}
+
+/// Channel interrupt source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChannelInterrupt {
+    /// Transfer complete interrupt.
+    TransferComplete,
+    /// Error interrupt.
+    Error,
+}
+
+/// DMA transfer error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TransferError {
+    /// Bus error during transfer.
+    Bus,
+}
+
+/// Callback invoked from interrupt handler with channel number and event.
+pub type ChannelCallback = fn(u8, ChannelEvent);
+
+const EVENT_COMPLETE: u8 = 1 << 0;
+const EVENT_ERROR: u8 = 1 << 1;
+
+/// Set of wakers and callbacks as the state for an interrupt driven DMA controller.
+#[derive(Debug)]
+pub struct DmaState {
+    wakers: [atomic_waker::AtomicWaker; 8],
+    callbacks: [AtomicPtr<()>; 8],
+    events: [AtomicU8; 8],
+    ref_to_dma: AtomicUsize,
+}
+
+impl DmaState {
+    /// Creates the state for a DMA controller.
+    #[inline]
+    pub const fn new() -> DmaState {
+        DmaState {
+            wakers: [const { atomic_waker::AtomicWaker::new() }; 8],
+            callbacks: [const { AtomicPtr::new(core::ptr::null_mut()) }; 8],
+            events: [const { AtomicU8::new(0) }; 8],
+            ref_to_dma: AtomicUsize::new(0),
+        }
+    }
+    /// Register a callback invoked on interrupts of the channel.
+    #[inline]
+    pub fn set_callback(&self, channel: &UntypedChannel<'_>, callback: ChannelCallback) {
+        self.ref_to_dma
+            .store(channel.dma as *const _ as usize, Ordering::Release);
+        self.callbacks[channel.channel_id].store(callback as *mut (), Ordering::Release);
+    }
+    /// Remove callback of the channel.
+    #[inline]
+    pub fn clear_callback(&self, channel: &UntypedChannel<'_>) {
+        self.callbacks[channel.channel_id].store(core::ptr::null_mut(), Ordering::Release);
+    }
+    /// Use this state to handle interrupt.
+    #[inline]
+    pub fn on_interrupt(&self) {
+        let dma = self.ref_to_dma.load(Ordering::Acquire);
+        if dma == 0 {
+            return;
+        }
+        let dma = unsafe { &*(dma as *const RegisterBlock) };
+        let complete = dma.interrupts.transfer_complete_state.read();
+        let error = dma.interrupts.error_state.read();
+        for ch in 0..8u8 {
+            let mut events = 0;
+            if complete.if_cplt_int_occurs(ch) {
+                unsafe {
+                    dma.interrupts
+                        .transfer_complete_clear
+                        .write(TransferCompleteClear::default().clear_cplt_int(ch))
+                };
+                events |= EVENT_COMPLETE;
+            }
+            if error.if_err_int_occurs(ch) {
+                unsafe {
+                    dma.interrupts
+                        .error_clear
+                        .write(ErrorClear::default().clear_err_int(ch))
+                };
+                events |= EVENT_ERROR;
+            }
+            if events == 0 {
+                continue;
+            }
+            self.events[ch as usize].fetch_or(events, Ordering::AcqRel);
+            let callback = self.callbacks[ch as usize].load(Ordering::Acquire);
+            if !callback.is_null() {
+                let callback: ChannelCallback = unsafe { core::mem::transmute(callback) };
+                if events & EVENT_ERROR != 0 {
+                    callback(ch, ChannelEvent::Error);
+                }
+                if events & EVENT_COMPLETE != 0 {
+                    callback(ch, ChannelEvent::TransferComplete);
+                }
+            }
+            self.wakers[ch as usize].wake();
+        }
+    }
+}
+
+impl Default for DmaState {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UntypedChannel<'_> {
+    /// Enable channel interrupt.
+    #[inline]
+    pub fn enable_interrupt(&self, interrupt: ChannelInterrupt) {
+        unsafe {
+            self.dma.channels[self.channel_id]
+                .config
+                .modify(|val| match interrupt {
+                    ChannelInterrupt::TransferComplete => val.enable_cplt_int(),
+                    ChannelInterrupt::Error => val.enable_err_int(),
+                })
+        };
+    }
+    /// Disable channel interrupt.
+    #[inline]
+    pub fn disable_interrupt(&self, interrupt: ChannelInterrupt) {
+        unsafe {
+            self.dma.channels[self.channel_id]
+                .config
+                .modify(|val| match interrupt {
+                    ChannelInterrupt::TransferComplete => val.disable_cplt_int(),
+                    ChannelInterrupt::Error => val.disable_err_int(),
+                })
+        };
+    }
+    /// Check if channel interrupt is enabled.
+    #[inline]
+    pub fn is_interrupt_enabled(&self, interrupt: ChannelInterrupt) -> bool {
+        let config = self.dma.channels[self.channel_id].config.read();
+        match interrupt {
+            ChannelInterrupt::TransferComplete => config.is_cplt_int_enabled(),
+            ChannelInterrupt::Error => config.is_err_int_enabled(),
+        }
+    }
+    /// Wait for current transfer on this channel to complete.
+    ///
+    /// Interrupts of the DMA controller must be routed to [`DmaState::on_interrupt`].
+    #[inline]
+    pub fn wait_complete<'s>(&self, state: &'s DmaState) -> TransferFuture<'s> {
+        state
+            .ref_to_dma
+            .store(self.dma as *const _ as usize, Ordering::Release);
+        self.enable_interrupt(ChannelInterrupt::TransferComplete);
+        self.enable_interrupt(ChannelInterrupt::Error);
+        TransferFuture {
+            state,
+            channel_id: self.channel_id,
+        }
+    }
+}
+
+/// Future that resolves when a DMA channel transfer completes.
+#[derive(Debug)]
+pub struct TransferFuture<'s> {
+    state: &'s DmaState,
+    channel_id: usize,
+}
+
+impl Future for TransferFuture<'_> {
+    type Output = Result<(), TransferError>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let events = &self.state.events[self.channel_id];
+        if events.load(Ordering::Acquire) == 0 {
+            self.state.wakers[self.channel_id].register(cx.waker());
+        }
+        match events.swap(0, Ordering::AcqRel) {
+            0 => Poll::Pending,
+            val if val & EVENT_ERROR != 0 => Poll::Ready(Err(TransferError::Bus)),
+            _ => Poll::Ready(Ok(())),
+        }
+    }
+}
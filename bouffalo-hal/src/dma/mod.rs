@@ -1,11 +1,17 @@
 //! Direct Memory Access peripheral.
 
+mod buffer;
+mod chain;
 mod channel;
 mod config;
+mod interrupt;
 mod register;
 
+pub use buffer::*;
+pub use chain::*;
 pub use channel::*;
 pub use config::*;
+pub use interrupt::*;
 pub use register::*;
 
 use crate::glb;
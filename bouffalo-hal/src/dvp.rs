@@ -0,0 +1,780 @@
+//! Digital Video Port camera capture peripheral.
+//!
+//! Each DVP2BUS unit on the multi-media subsystem receives pixels from a parallel camera
+//! interface and writes frames into a ring buffer in memory. Start addresses of completed
+//! frames are queued in a small hardware FIFO, so a buffer that holds two frames or more
+//! gives double buffering without software copy.
+
+use core::ops::Deref;
+use volatile_register::{RO, RW, WO};
+
+/// Digital Video Port capture registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Capture configuration register.
+    pub config: RW<DvpConfig>,
+    /// Start address of frame ring buffer.
+    pub buffer_address: RW<u32>,
+    /// Size of frame ring buffer in bus bursts.
+    pub buffer_bursts: RW<u32>,
+    /// Interrupt state, enable and frame count register.
+    pub status: RW<Status>,
+    /// Size of one frame in bytes.
+    pub frame_bytes: RW<u32>,
+    /// Frame FIFO pop and interrupt clear register.
+    pub frame_pop: WO<FramePop>,
+    /// Frame valid period configuration.
+    pub frame_valid: RW<u32>,
+    /// Frame period configuration.
+    pub frame_period: RW<u32>,
+    /// Miscellaneous configuration register.
+    pub misc: RW<u32>,
+    _reserved0: [u8; 0xc],
+    /// Horizontal cropping window in pixel clocks.
+    pub hsync_crop: RW<Crop>,
+    /// Vertical cropping window in lines.
+    pub vsync_crop: RW<Crop>,
+    /// Expected total pixel clocks per line and lines per frame.
+    pub frame_size: RW<FrameSize>,
+    _reserved1: [u8; 0x4],
+    /// Start addresses of completed frames, index 0 is the oldest frame.
+    pub frame_start_address: [RO<u32>; 4],
+}
+
+/// Capture configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct DvpConfig(u32);
+
+impl DvpConfig {
+    const ENABLE: u32 = 1 << 0;
+    const SOFTWARE_MODE: u32 = 1 << 1;
+    const FRAME_VALID_POLARITY: u32 = 1 << 2;
+    const LINE_VALID_POLARITY: u32 = 1 << 3;
+    const BURST_LENGTH: u32 = 0x7 << 4;
+    const PIXEL_MODE: u32 = 0x7 << 8;
+    const DROP: u32 = 1 << 12;
+    const DROP_EVEN: u32 = 1 << 13;
+
+    /// Enable capture.
+    #[inline]
+    pub const fn enable_capture(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable capture.
+    #[inline]
+    pub const fn disable_capture(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if capture is enabled.
+    #[inline]
+    pub const fn is_capture_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Enable software mode, capture stops after buffer is filled once.
+    #[inline]
+    pub const fn enable_software_mode(self) -> Self {
+        Self(self.0 | Self::SOFTWARE_MODE)
+    }
+    /// Disable software mode, frames are written into ring buffer continuously.
+    #[inline]
+    pub const fn disable_software_mode(self) -> Self {
+        Self(self.0 & !Self::SOFTWARE_MODE)
+    }
+    /// Check if software mode is enabled.
+    #[inline]
+    pub const fn is_software_mode_enabled(self) -> bool {
+        self.0 & Self::SOFTWARE_MODE != 0
+    }
+    /// Set active level of frame valid (vertical sync) signal.
+    #[inline]
+    pub const fn set_frame_valid_polarity(self, val: Polarity) -> Self {
+        Self((self.0 & !Self::FRAME_VALID_POLARITY) | ((val as u32) << 2))
+    }
+    /// Get active level of frame valid (vertical sync) signal.
+    #[inline]
+    pub const fn frame_valid_polarity(self) -> Polarity {
+        Polarity::from_bit(self.0 & Self::FRAME_VALID_POLARITY != 0)
+    }
+    /// Set active level of line valid (horizontal sync) signal.
+    #[inline]
+    pub const fn set_line_valid_polarity(self, val: Polarity) -> Self {
+        Self((self.0 & !Self::LINE_VALID_POLARITY) | ((val as u32) << 3))
+    }
+    /// Get active level of line valid (horizontal sync) signal.
+    #[inline]
+    pub const fn line_valid_polarity(self) -> Polarity {
+        Polarity::from_bit(self.0 & Self::LINE_VALID_POLARITY != 0)
+    }
+    /// Set bus burst length.
+    #[inline]
+    pub const fn set_burst_length(self, val: BurstLength) -> Self {
+        Self((self.0 & !Self::BURST_LENGTH) | ((val as u32) << 4))
+    }
+    /// Get bus burst length.
+    #[inline]
+    pub const fn burst_length(self) -> BurstLength {
+        match (self.0 & Self::BURST_LENGTH) >> 4 {
+            0 => BurstLength::Single,
+            1 => BurstLength::Incr4,
+            2 => BurstLength::Incr8,
+            3 => BurstLength::Incr16,
+            5 => BurstLength::Incr32,
+            _ => BurstLength::Incr64,
+        }
+    }
+    /// Set pixel data mode.
+    #[inline]
+    pub const fn set_pixel_mode(self, val: PixelMode) -> Self {
+        Self((self.0 & !Self::PIXEL_MODE) | ((val as u32) << 8))
+    }
+    /// Get pixel data mode.
+    #[inline]
+    pub const fn pixel_mode(self) -> PixelMode {
+        match (self.0 & Self::PIXEL_MODE) >> 8 {
+            0 => PixelMode::OneByte,
+            1 => PixelMode::TwoBytes,
+            2 => PixelMode::ThreeBytes,
+            _ => PixelMode::FourBytes,
+        }
+    }
+    /// Enable dropping every other pixel.
+    #[inline]
+    pub const fn enable_drop(self) -> Self {
+        Self(self.0 | Self::DROP)
+    }
+    /// Disable dropping pixels.
+    #[inline]
+    pub const fn disable_drop(self) -> Self {
+        Self(self.0 & !Self::DROP)
+    }
+    /// Check if pixel dropping is enabled.
+    #[inline]
+    pub const fn is_drop_enabled(self) -> bool {
+        self.0 & Self::DROP != 0
+    }
+    /// Drop even pixels instead of odd pixels.
+    #[inline]
+    pub const fn enable_drop_even(self) -> Self {
+        Self(self.0 | Self::DROP_EVEN)
+    }
+    /// Drop odd pixels instead of even pixels.
+    #[inline]
+    pub const fn disable_drop_even(self) -> Self {
+        Self(self.0 & !Self::DROP_EVEN)
+    }
+    /// Check if even pixels are dropped.
+    #[inline]
+    pub const fn is_drop_even_enabled(self) -> bool {
+        self.0 & Self::DROP_EVEN != 0
+    }
+}
+
+/// Active level of a synchronization signal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Polarity {
+    /// Signal is active when low.
+    ActiveLow = 0,
+    /// Signal is active when high.
+    ActiveHigh = 1,
+}
+
+impl Polarity {
+    #[inline]
+    const fn from_bit(bit: bool) -> Self {
+        if bit {
+            Polarity::ActiveHigh
+        } else {
+            Polarity::ActiveLow
+        }
+    }
+}
+
+/// Bus burst length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum BurstLength {
+    /// Single transfer.
+    Single = 0,
+    /// Incrementing burst of 4.
+    Incr4 = 1,
+    /// Incrementing burst of 8.
+    Incr8 = 2,
+    /// Incrementing burst of 16.
+    Incr16 = 3,
+    /// Incrementing burst of 32.
+    Incr32 = 5,
+    /// Incrementing burst of 64.
+    Incr64 = 6,
+}
+
+impl BurstLength {
+    /// Number of bytes in one burst on the 64-bit bus.
+    #[inline]
+    pub const fn bytes(self) -> u32 {
+        let beats = match self {
+            BurstLength::Single => 1,
+            BurstLength::Incr4 => 4,
+            BurstLength::Incr8 => 8,
+            BurstLength::Incr16 => 16,
+            BurstLength::Incr32 => 32,
+            BurstLength::Incr64 => 64,
+        };
+        beats * 8
+    }
+}
+
+/// Pixel data mode, number of bytes sampled per pixel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum PixelMode {
+    /// One byte per pixel, e.g. raw or grayscale.
+    OneByte = 0,
+    /// Two bytes per pixel, e.g. RGB565 or YUV422.
+    TwoBytes = 1,
+    /// Three bytes per pixel, e.g. RGB888.
+    ThreeBytes = 2,
+    /// Four bytes per pixel.
+    FourBytes = 3,
+}
+
+impl PixelMode {
+    /// Number of bytes per pixel.
+    #[inline]
+    pub const fn bytes(self) -> u32 {
+        self as u32 + 1
+    }
+}
+
+/// Interrupt state, enable and frame count register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Status(u32);
+
+impl Status {
+    const INTERRUPT_STATE: u32 = 0x3f;
+    const INTERRUPT_ENABLE: u32 = 0x3f << 8;
+    const FRAME_COUNT: u32 = 0x1f << 24;
+
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub const fn has_interrupt(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32)) & Self::INTERRUPT_STATE != 0
+    }
+    /// Enable interrupt.
+    #[inline]
+    pub const fn enable_interrupt(self, val: Interrupt) -> Self {
+        Self((self.0 & !Self::INTERRUPT_STATE) | (1 << (val as u32 + 8)))
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub const fn disable_interrupt(self, val: Interrupt) -> Self {
+        Self((self.0 & !Self::INTERRUPT_STATE) & !(1 << (val as u32 + 8)))
+    }
+    /// Check if interrupt is enabled.
+    #[inline]
+    pub const fn is_interrupt_enabled(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32 + 8)) & Self::INTERRUPT_ENABLE != 0
+    }
+    /// Get number of completed frames in frame FIFO.
+    #[inline]
+    pub const fn frame_count(self) -> u8 {
+        ((self.0 & Self::FRAME_COUNT) >> 24) as u8
+    }
+}
+
+/// Capture interrupt event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Interrupt {
+    /// A frame is completed and queued in frame FIFO.
+    FrameComplete = 0,
+    /// Ring buffer is filled once.
+    BufferFull = 1,
+    /// Frame FIFO overflowed and a frame is dropped.
+    FrameOverflow = 2,
+    /// Pixel FIFO overflowed.
+    FifoOverflow = 3,
+    /// Pixel count of a line differs from expected value.
+    LineSizeError = 4,
+    /// Line count of a frame differs from expected value.
+    FrameSizeError = 5,
+}
+
+/// Frame FIFO pop and interrupt clear register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct FramePop(u32);
+
+impl FramePop {
+    const POP: u32 = 1 << 0;
+
+    /// Pop the oldest frame from frame FIFO.
+    #[inline]
+    pub const fn pop_frame(self) -> Self {
+        Self(self.0 | Self::POP)
+    }
+    /// Clear interrupt flag.
+    #[inline]
+    pub const fn clear_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32 + 4)))
+    }
+}
+
+/// Cropping window register, start and end are both inclusive counters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Crop(u32);
+
+impl Crop {
+    const END: u32 = 0xffff;
+    const START: u32 = 0xffff << 16;
+
+    /// Set window end.
+    #[inline]
+    pub const fn set_end(self, val: u16) -> Self {
+        Self((self.0 & !Self::END) | val as u32)
+    }
+    /// Get window end.
+    #[inline]
+    pub const fn end(self) -> u16 {
+        (self.0 & Self::END) as u16
+    }
+    /// Set window start.
+    #[inline]
+    pub const fn set_start(self, val: u16) -> Self {
+        Self((self.0 & !Self::START) | ((val as u32) << 16))
+    }
+    /// Get window start.
+    #[inline]
+    pub const fn start(self) -> u16 {
+        ((self.0 & Self::START) >> 16) as u16
+    }
+}
+
+/// Expected frame size register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct FrameSize(u32);
+
+impl FrameSize {
+    const PIXELS: u32 = 0xffff;
+    const LINES: u32 = 0xffff << 16;
+
+    /// Set pixel clocks per line.
+    #[inline]
+    pub const fn set_pixels(self, val: u16) -> Self {
+        Self((self.0 & !Self::PIXELS) | val as u32)
+    }
+    /// Get pixel clocks per line.
+    #[inline]
+    pub const fn pixels(self) -> u16 {
+        (self.0 & Self::PIXELS) as u16
+    }
+    /// Set lines per frame.
+    #[inline]
+    pub const fn set_lines(self, val: u16) -> Self {
+        Self((self.0 & !Self::LINES) | ((val as u32) << 16))
+    }
+    /// Get lines per frame.
+    #[inline]
+    pub const fn lines(self) -> u16 {
+        ((self.0 & Self::LINES) >> 16) as u16
+    }
+}
+
+/// Capture window inside the sensor output, in pixels and lines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Window {
+    /// First captured pixel of a line.
+    pub x: u16,
+    /// First captured line of a frame.
+    pub y: u16,
+    /// Captured pixels per line.
+    pub width: u16,
+    /// Captured lines per frame.
+    pub height: u16,
+}
+
+/// Digital Video Port capture configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Config {
+    /// Active level of frame valid signal.
+    pub frame_valid_polarity: Polarity,
+    /// Active level of line valid signal.
+    pub line_valid_polarity: Polarity,
+    /// Bytes sampled per pixel.
+    pub pixel_mode: PixelMode,
+    /// Bus burst length.
+    pub burst_length: BurstLength,
+    /// Sensor output resolution in pixels and lines.
+    pub resolution: (u16, u16),
+    /// Cropping window, the full resolution is captured if `None`.
+    pub crop: Option<Window>,
+}
+
+impl Default for Config {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            frame_valid_polarity: Polarity::ActiveHigh,
+            line_valid_polarity: Polarity::ActiveHigh,
+            pixel_mode: PixelMode::TwoBytes,
+            burst_length: BurstLength::Incr16,
+            resolution: (640, 480),
+            crop: None,
+        }
+    }
+}
+
+impl Config {
+    /// Set sensor output resolution.
+    #[inline]
+    pub const fn set_resolution(mut self, width: u16, height: u16) -> Self {
+        self.resolution = (width, height);
+        self
+    }
+    /// Set cropping window.
+    #[inline]
+    pub const fn set_crop(mut self, window: Window) -> Self {
+        self.crop = Some(window);
+        self
+    }
+    /// Set synchronization signal polarities.
+    #[inline]
+    pub const fn set_polarity(mut self, frame_valid: Polarity, line_valid: Polarity) -> Self {
+        self.frame_valid_polarity = frame_valid;
+        self.line_valid_polarity = line_valid;
+        self
+    }
+    /// Set bytes sampled per pixel.
+    #[inline]
+    pub const fn set_pixel_mode(mut self, pixel_mode: PixelMode) -> Self {
+        self.pixel_mode = pixel_mode;
+        self
+    }
+    /// Size of one captured frame in bytes.
+    #[inline]
+    pub const fn frame_bytes(&self) -> u32 {
+        let (width, height) = match self.crop {
+            Some(window) => (window.width, window.height),
+            None => self.resolution,
+        };
+        width as u32 * height as u32 * self.pixel_mode.bytes()
+    }
+}
+
+/// Errors on capture configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConfigError {
+    /// Buffer is not aligned to bus burst size.
+    BufferAlignment,
+    /// Buffer cannot hold two frames for double buffering.
+    BufferTooSmall,
+    /// Cropping window exceeds sensor resolution.
+    CropOutOfRange,
+    /// Line length in bytes does not fit in capture registers.
+    LineTooLong,
+}
+
+/// Managed Digital Video Port capture unit.
+pub struct Dvp<DVP> {
+    dvp: DVP,
+    buffer: &'static mut [u8],
+    frame_bytes: usize,
+}
+
+impl<DVP: Deref<Target = RegisterBlock>> Dvp<DVP> {
+    /// Create a capture unit writing frames into `buffer`.
+    ///
+    /// The buffer must hold at least two frames; its length is rounded down to a
+    /// whole number of frames.
+    #[inline]
+    pub fn new(dvp: DVP, config: Config, buffer: &'static mut [u8]) -> Result<Self, ConfigError> {
+        let burst = config.burst_length.bytes() as usize;
+        if !(buffer.as_ptr() as usize).is_multiple_of(burst) {
+            return Err(ConfigError::BufferAlignment);
+        }
+        let frame_bytes = config.frame_bytes() as usize;
+        if frame_bytes == 0 || buffer.len() < frame_bytes * 2 {
+            return Err(ConfigError::BufferTooSmall);
+        }
+        let buffer_bytes = buffer.len() / frame_bytes * frame_bytes;
+        let (width, height) = config.resolution;
+        let bytes = config.pixel_mode.bytes();
+        let line_bytes =
+            u16::try_from(width as u32 * bytes).map_err(|_| ConfigError::LineTooLong)?;
+        let (hsync, vsync) = match config.crop {
+            Some(w) => {
+                if w.x as u32 + w.width as u32 > width as u32
+                    || w.y as u32 + w.height as u32 > height as u32
+                {
+                    return Err(ConfigError::CropOutOfRange);
+                }
+                (
+                    crop(w.x, w.width, bytes).ok_or(ConfigError::CropOutOfRange)?,
+                    crop(w.y, w.height, 1).ok_or(ConfigError::CropOutOfRange)?,
+                )
+            }
+            None => (
+                crop(0, width, bytes).ok_or(ConfigError::LineTooLong)?,
+                crop(0, height, 1).ok_or(ConfigError::CropOutOfRange)?,
+            ),
+        };
+        let val = DvpConfig(0)
+            .disable_software_mode()
+            .set_frame_valid_polarity(config.frame_valid_polarity)
+            .set_line_valid_polarity(config.line_valid_polarity)
+            .set_burst_length(config.burst_length)
+            .set_pixel_mode(config.pixel_mode);
+        unsafe {
+            dvp.config.write(val.disable_capture());
+            dvp.buffer_address.write(buffer.as_ptr() as u32);
+            dvp.buffer_bursts.write((buffer_bytes / burst) as u32);
+            dvp.frame_bytes.write(frame_bytes as u32);
+            dvp.hsync_crop.write(hsync);
+            dvp.vsync_crop.write(vsync);
+            dvp.frame_size
+                .write(FrameSize(0).set_pixels(line_bytes).set_lines(height));
+        }
+        Ok(Self {
+            dvp,
+            buffer,
+            frame_bytes,
+        })
+    }
+    /// Start capturing frames.
+    #[inline]
+    pub fn start(&mut self) {
+        unsafe { self.dvp.config.modify(|val| val.enable_capture()) };
+    }
+    /// Stop capturing frames.
+    #[inline]
+    pub fn stop(&mut self) {
+        unsafe { self.dvp.config.modify(|val| val.disable_capture()) };
+    }
+    /// Enable interrupt.
+    #[inline]
+    pub fn enable_interrupt(&mut self, val: Interrupt) {
+        unsafe { self.dvp.status.modify(|v| v.enable_interrupt(val)) };
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub fn disable_interrupt(&mut self, val: Interrupt) {
+        unsafe { self.dvp.status.modify(|v| v.disable_interrupt(val)) };
+    }
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub fn has_interrupt(&self, val: Interrupt) -> bool {
+        self.dvp.status.read().has_interrupt(val)
+    }
+    /// Clear interrupt flag.
+    #[inline]
+    pub fn clear_interrupt(&mut self, val: Interrupt) {
+        unsafe {
+            self.dvp
+                .frame_pop
+                .write(FramePop::default().clear_interrupt(val))
+        };
+    }
+    /// Get number of completed frames waiting to be released.
+    #[inline]
+    pub fn frame_count(&self) -> usize {
+        self.dvp.status.read().frame_count() as usize
+    }
+    /// Get the oldest completed frame, if any.
+    ///
+    /// The frame stays valid until it is released by [`Dvp::release_frame`].
+    #[inline]
+    pub fn frame(&self) -> Option<&[u8]> {
+        if self.frame_count() == 0 {
+            return None;
+        }
+        let offset = (self.dvp.frame_start_address[0].read() as usize)
+            .checked_sub(self.buffer.as_ptr() as usize)?;
+        self.buffer
+            .get(offset..offset.checked_add(self.frame_bytes)?)
+    }
+    /// Release the oldest completed frame to hardware.
+    #[inline]
+    pub fn release_frame(&mut self) {
+        unsafe { self.dvp.frame_pop.write(FramePop::default().pop_frame()) };
+    }
+    /// Handle frame complete interrupt, calling `f` on every completed frame.
+    ///
+    /// Call this function in the `dvp2_bus_int*` interrupt handler.
+    #[inline]
+    pub fn on_frame_complete(&mut self, mut f: impl FnMut(&[u8])) {
+        self.clear_interrupt(Interrupt::FrameComplete);
+        while let Some(frame) = self.frame() {
+            f(frame);
+            self.release_frame();
+        }
+    }
+    /// Stop capture and release peripheral and buffer.
+    #[inline]
+    pub fn free(self) -> (DVP, &'static mut [u8]) {
+        unsafe { self.dvp.config.modify(|val| val.disable_capture()) };
+        (self.dvp, self.buffer)
+    }
+}
+
+/// Synchronization crop register covering `len` units from `start`, `bytes` each.
+#[inline]
+fn crop(start: u16, len: u16, bytes: u32) -> Option<Crop> {
+    let first = start as u32 * bytes;
+    let last = (first + len as u32 * bytes).checked_sub(1)?;
+    Some(
+        Crop(0)
+            .set_start(u16::try_from(first).ok()?)
+            .set_end(u16::try_from(last).ok()?),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BurstLength, Config, Crop, DvpConfig, FramePop, FrameSize, Interrupt, PixelMode, Polarity,
+        RegisterBlock, Status, Window, crop,
+    };
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, buffer_address), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, buffer_bursts), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, status), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, frame_bytes), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, frame_pop), 0x14);
+        assert_eq!(offset_of!(RegisterBlock, frame_valid), 0x18);
+        assert_eq!(offset_of!(RegisterBlock, frame_period), 0x1c);
+        assert_eq!(offset_of!(RegisterBlock, misc), 0x20);
+        assert_eq!(offset_of!(RegisterBlock, hsync_crop), 0x30);
+        assert_eq!(offset_of!(RegisterBlock, vsync_crop), 0x34);
+        assert_eq!(offset_of!(RegisterBlock, frame_size), 0x38);
+        assert_eq!(offset_of!(RegisterBlock, frame_start_address), 0x40);
+    }
+
+    #[test]
+    fn struct_dvp_config_functions() {
+        let mut val = DvpConfig(0x0);
+
+        val = val.enable_capture();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_capture_enabled());
+        val = val.disable_capture();
+        assert!(!val.is_capture_enabled());
+
+        val = val.enable_software_mode();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_software_mode_enabled());
+        val = val.disable_software_mode();
+        assert!(!val.is_software_mode_enabled());
+
+        val = val.set_frame_valid_polarity(Polarity::ActiveHigh);
+        assert_eq!(val.0, 0x0000_0004);
+        assert_eq!(val.frame_valid_polarity(), Polarity::ActiveHigh);
+        val = val.set_frame_valid_polarity(Polarity::ActiveLow);
+        assert_eq!(val.frame_valid_polarity(), Polarity::ActiveLow);
+
+        val = val.set_line_valid_polarity(Polarity::ActiveHigh);
+        assert_eq!(val.0, 0x0000_0008);
+        assert_eq!(val.line_valid_polarity(), Polarity::ActiveHigh);
+        val = val.set_line_valid_polarity(Polarity::ActiveLow);
+
+        val = val.set_burst_length(BurstLength::Incr64);
+        assert_eq!(val.0, 0x0000_0060);
+        assert_eq!(val.burst_length(), BurstLength::Incr64);
+        val = val.set_burst_length(BurstLength::Single);
+
+        val = val.set_pixel_mode(PixelMode::FourBytes);
+        assert_eq!(val.0, 0x0000_0300);
+        assert_eq!(val.pixel_mode(), PixelMode::FourBytes);
+        val = val.set_pixel_mode(PixelMode::OneByte);
+
+        val = val.enable_drop();
+        assert_eq!(val.0, 0x0000_1000);
+        assert!(val.is_drop_enabled());
+        val = val.disable_drop();
+        assert!(!val.is_drop_enabled());
+
+        val = val.enable_drop_even();
+        assert_eq!(val.0, 0x0000_2000);
+        assert!(val.is_drop_even_enabled());
+        val = val.disable_drop_even();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_drop_even_enabled());
+    }
+
+    #[test]
+    fn struct_status_functions() {
+        let mut val = Status(0x0);
+
+        val = val.enable_interrupt(Interrupt::FrameComplete);
+        assert_eq!(val.0, 0x0000_0100);
+        assert!(val.is_interrupt_enabled(Interrupt::FrameComplete));
+        val = val.enable_interrupt(Interrupt::FrameSizeError);
+        assert_eq!(val.0, 0x0000_2100);
+        val = val.disable_interrupt(Interrupt::FrameComplete);
+        assert_eq!(val.0, 0x0000_2000);
+        assert!(!val.is_interrupt_enabled(Interrupt::FrameComplete));
+
+        // Writing back interrupt enables must not write interrupt state bits.
+        val = Status(0x0000_0003).enable_interrupt(Interrupt::BufferFull);
+        assert_eq!(val.0, 0x0000_0200);
+
+        val = Status(0x0300_0005);
+        assert!(val.has_interrupt(Interrupt::FrameComplete));
+        assert!(!val.has_interrupt(Interrupt::BufferFull));
+        assert!(val.has_interrupt(Interrupt::FrameOverflow));
+        assert_eq!(val.frame_count(), 3);
+    }
+
+    #[test]
+    fn struct_frame_pop_functions() {
+        let val = FramePop::default().pop_frame();
+        assert_eq!(val.0, 0x0000_0001);
+        let val = FramePop::default().clear_interrupt(Interrupt::FrameComplete);
+        assert_eq!(val.0, 0x0000_0010);
+        let val = FramePop::default().clear_interrupt(Interrupt::FrameSizeError);
+        assert_eq!(val.0, 0x0000_0200);
+    }
+
+    #[test]
+    fn struct_crop_and_frame_size_functions() {
+        let val = Crop(0x0).set_start(0x1234).set_end(0x5678);
+        assert_eq!(val.0, 0x1234_5678);
+        assert_eq!(val.start(), 0x1234);
+        assert_eq!(val.end(), 0x5678);
+
+        let val = FrameSize(0x0).set_pixels(1280).set_lines(480);
+        assert_eq!(val.0, 0x01e0_0500);
+        assert_eq!(val.pixels(), 1280);
+        assert_eq!(val.lines(), 480);
+    }
+
+    #[test]
+    fn struct_config_frame_bytes() {
+        let config = Config::default();
+        assert_eq!(config.frame_bytes(), 640 * 480 * 2);
+        let config = config.set_pixel_mode(PixelMode::OneByte).set_crop(Window {
+            x: 16,
+            y: 8,
+            width: 320,
+            height: 240,
+        });
+        assert_eq!(config.frame_bytes(), 320 * 240);
+    }
+
+    #[test]
+    fn function_crop() {
+        let val = crop(16, 320, 2).unwrap();
+        assert_eq!(val.start(), 32);
+        assert_eq!(val.end(), 671);
+        assert!(crop(0, 0, 1).is_none());
+        assert!(crop(0, 40000, 2).is_none());
+        assert!(crop(65535, 1, 2).is_none());
+    }
+}
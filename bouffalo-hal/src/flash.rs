@@ -0,0 +1,858 @@
+//! Serial flash controller.
+//!
+//! Commands are sent through the software interface of the controller. While a command
+//! runs, the flash cannot serve execute-in-place fetches; command routines are placed in
+//! RAM, and interrupt handlers that run from flash must be disabled by the caller during
+//! erase and program operations.
+use core::ops::Deref;
+use volatile_register::RW;
+
+mod settings;
+pub use settings::*;
+
+/// Serial flash controller registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Controller configuration 0.
+    pub config_0: RW<u32>,
+    /// Controller configuration 1.
+    pub config_1: RW<Config1>,
+    /// Software interface command configuration.
+    pub software_command: RW<SoftwareCommand>,
+    /// Software interface command and address buffer 0.
+    pub command_buffer_0: RW<u32>,
+    /// Software interface command and address buffer 1.
+    pub command_buffer_1: RW<u32>,
+    _reserved0: [u8; 0x5c],
+    /// Controller configuration 2.
+    pub config_2: RW<Config2>,
+    _reserved1: [u8; 0x58c],
+    /// Software interface data buffer.
+    pub data_buffer: [RW<u32>; 64],
+}
+
+/// Controller configuration register 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct Config1(u32);
+
+impl Config1 {
+    const FUNCTION_SELECT: u32 = 1 << 28;
+    const AHB_TO_INTERFACE: u32 = 1 << 30;
+
+    /// Hand the flash interface to software commands.
+    #[inline]
+    pub const fn select_software(self) -> Self {
+        Self(self.0 & !(Self::FUNCTION_SELECT | Self::AHB_TO_INTERFACE))
+    }
+    /// Hand the flash interface to execute-in-place bus accesses.
+    #[inline]
+    pub const fn select_bus(self) -> Self {
+        Self(self.0 | Self::FUNCTION_SELECT | Self::AHB_TO_INTERFACE)
+    }
+    /// Check if the flash interface is owned by execute-in-place bus accesses.
+    #[inline]
+    pub const fn is_bus_selected(self) -> bool {
+        self.0 & Self::FUNCTION_SELECT != 0
+    }
+}
+
+/// Controller configuration register 2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct Config2(u32);
+
+impl Config2 {
+    const BANK2_ENABLE: u32 = 1 << 30;
+    const BANK_SELECT: u32 = 1 << 31;
+
+    /// Enable the second memory bank.
+    #[inline]
+    pub const fn enable_bank2(self) -> Self {
+        Self(self.0 | Self::BANK2_ENABLE)
+    }
+    /// Disable the second memory bank.
+    #[inline]
+    pub const fn disable_bank2(self) -> Self {
+        Self(self.0 & !Self::BANK2_ENABLE)
+    }
+    /// Check if the second memory bank is enabled.
+    #[inline]
+    pub const fn is_bank2_enabled(self) -> bool {
+        self.0 & Self::BANK2_ENABLE != 0
+    }
+    /// Select memory bank addressed by software interface commands.
+    #[inline]
+    pub const fn select_bank(self, val: Bank) -> Self {
+        match val {
+            Bank::One => Self(self.0 & !Self::BANK_SELECT),
+            Bank::Two => Self(self.0 | Self::BANK_SELECT),
+        }
+    }
+    /// Get memory bank addressed by software interface commands.
+    #[inline]
+    pub const fn bank(self) -> Bank {
+        if self.0 & Self::BANK_SELECT != 0 {
+            Bank::Two
+        } else {
+            Bank::One
+        }
+    }
+}
+
+/// Memory bank of the flash controller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Bank {
+    /// Boot flash, also serving execute-in-place accesses.
+    One,
+    /// Second flash or external PSRAM on its own chip select.
+    Two,
+}
+
+/// Data line usage of a command.
+///
+/// Command opcode is always sent on one line; the mode decides how many lines carry
+/// address and data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IoMode {
+    /// Address and data on one line.
+    Single,
+    /// Address on one line, data on two lines.
+    DualOutput,
+    /// Address on one line, data on four lines.
+    QuadOutput,
+    /// Address and data on two lines.
+    DualIo,
+    /// Address and data on four lines.
+    QuadIo,
+}
+
+/// Software interface command configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct SoftwareCommand(u32);
+
+impl SoftwareCommand {
+    const BUSY: u32 = 1 << 0;
+    const TRIGGER: u32 = 1 << 1;
+    const DATA_BYTES: u32 = 0x3ff << 2;
+    const DUMMY_BYTES: u32 = 0x1f << 12;
+    const ADDRESS_BYTES: u32 = 0x7 << 17;
+    const COMMAND_BYTES: u32 = 0x7 << 20;
+    const DATA_WRITE: u32 = 1 << 23;
+    const DATA_ENABLE: u32 = 1 << 24;
+    const DUMMY_ENABLE: u32 = 1 << 25;
+    const ADDRESS_ENABLE: u32 = 1 << 26;
+    const COMMAND_ENABLE: u32 = 1 << 27;
+    const IO_MODE: u32 = 0x7 << 28;
+
+    /// Check if software interface is busy.
+    #[inline]
+    pub const fn is_busy(self) -> bool {
+        self.0 & Self::BUSY != 0
+    }
+    /// Set trigger bit; command starts on its rising edge.
+    #[inline]
+    pub const fn set_trigger(self, val: bool) -> Self {
+        Self(self.0 & !Self::TRIGGER | (val as u32) << 1)
+    }
+    /// Set command phase length in bytes, 0 to disable.
+    #[inline]
+    pub const fn set_command_bytes(self, val: u8) -> Self {
+        match val {
+            0 => Self(self.0 & !(Self::COMMAND_ENABLE | Self::COMMAND_BYTES)),
+            n => Self(
+                self.0 & !Self::COMMAND_BYTES
+                    | Self::COMMAND_ENABLE
+                    | ((n as u32 - 1) << 20) & Self::COMMAND_BYTES,
+            ),
+        }
+    }
+    /// Set address phase length in bytes, 0 to disable.
+    #[inline]
+    pub const fn set_address_bytes(self, val: u8) -> Self {
+        match val {
+            0 => Self(self.0 & !(Self::ADDRESS_ENABLE | Self::ADDRESS_BYTES)),
+            n => Self(
+                self.0 & !Self::ADDRESS_BYTES
+                    | Self::ADDRESS_ENABLE
+                    | ((n as u32 - 1) << 17) & Self::ADDRESS_BYTES,
+            ),
+        }
+    }
+    /// Set dummy phase length in bytes, 0 to disable.
+    #[inline]
+    pub const fn set_dummy_bytes(self, val: u8) -> Self {
+        match val {
+            0 => Self(self.0 & !(Self::DUMMY_ENABLE | Self::DUMMY_BYTES)),
+            n => Self(
+                self.0 & !Self::DUMMY_BYTES
+                    | Self::DUMMY_ENABLE
+                    | ((n as u32 - 1) << 12) & Self::DUMMY_BYTES,
+            ),
+        }
+    }
+    /// Set data line usage of address and data phases.
+    #[inline]
+    pub const fn set_io_mode(self, val: IoMode) -> Self {
+        let val = match val {
+            IoMode::Single => 0,
+            IoMode::DualOutput => 1,
+            IoMode::QuadOutput => 2,
+            IoMode::DualIo => 3,
+            IoMode::QuadIo => 4,
+        };
+        Self(self.0 & !Self::IO_MODE | val << 28)
+    }
+    /// Get data line usage of address and data phases.
+    #[inline]
+    pub const fn io_mode(self) -> IoMode {
+        match (self.0 & Self::IO_MODE) >> 28 {
+            1 => IoMode::DualOutput,
+            2 => IoMode::QuadOutput,
+            3 => IoMode::DualIo,
+            4 => IoMode::QuadIo,
+            _ => IoMode::Single,
+        }
+    }
+    /// Set data phase length in bytes and direction, 0 to disable.
+    #[inline]
+    pub const fn set_data(self, len: u16, write: bool) -> Self {
+        match len {
+            0 => Self(self.0 & !(Self::DATA_ENABLE | Self::DATA_BYTES | Self::DATA_WRITE)),
+            n => Self(
+                self.0 & !(Self::DATA_BYTES | Self::DATA_WRITE)
+                    | Self::DATA_ENABLE
+                    | ((n as u32 - 1) << 2) & Self::DATA_BYTES
+                    | (write as u32) << 23,
+            ),
+        }
+    }
+}
+
+/// Size of a flash page in bytes.
+pub const PAGE_SIZE: usize = 256;
+/// Size of a flash sector in bytes.
+pub const SECTOR_SIZE: usize = 4096;
+/// Size of a flash block in bytes.
+pub const BLOCK_SIZE: usize = 65536;
+
+pub(crate) const CMD_WRITE_ENABLE: u8 = 0x06;
+pub(crate) const CMD_READ_STATUS: u8 = 0x05;
+pub(crate) const CMD_READ_DATA: u8 = 0x03;
+pub(crate) const CMD_PAGE_PROGRAM: u8 = 0x02;
+pub(crate) const CMD_SECTOR_ERASE: u8 = 0x20;
+const CMD_BLOCK_ERASE: u8 = 0xd8;
+pub(crate) const CMD_READ_JEDEC_ID: u8 = 0x9f;
+const CMD_FAST_READ: u8 = 0x0b;
+const CMD_FAST_READ_DUAL_OUTPUT: u8 = 0x3b;
+const CMD_FAST_READ_QUAD_OUTPUT: u8 = 0x6b;
+const CMD_FAST_READ_DUAL_IO: u8 = 0xbb;
+const CMD_FAST_READ_QUAD_IO: u8 = 0xeb;
+const CMD_QUAD_PAGE_PROGRAM: u8 = 0x32;
+pub(crate) const STATUS_BUSY: u8 = 1 << 0;
+
+/// Execute-in-place address window mapping flash offsets into system bus addresses.
+///
+/// Boot ROM maps flash offset `image_offset` to bus address `base`, where
+/// `image_offset` is the image address offset in processor core configuration of
+/// boot header plus the offset of code after the header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct XipWindow {
+    base: usize,
+    image_offset: u32,
+    len: usize,
+}
+
+impl XipWindow {
+    /// Creates an execute-in-place window of `len` bytes at bus address `base`
+    /// mapping flash from `image_offset`.
+    #[inline]
+    pub const fn new(base: usize, image_offset: u32, len: usize) -> Self {
+        Self {
+            base,
+            image_offset,
+            len,
+        }
+    }
+    /// Bus address of the window start.
+    #[inline]
+    pub const fn base(&self) -> usize {
+        self.base
+    }
+    /// Flash offset mapped to the window start.
+    #[inline]
+    pub const fn image_offset(&self) -> u32 {
+        self.image_offset
+    }
+    /// Translate flash offset into bus address, or `None` if it is not mapped.
+    #[inline]
+    pub const fn to_virtual(self, flash_offset: u32) -> Option<usize> {
+        if flash_offset < self.image_offset {
+            return None;
+        }
+        let offset = (flash_offset - self.image_offset) as usize;
+        if offset >= self.len {
+            return None;
+        }
+        Some(self.base + offset)
+    }
+    /// Translate bus address into flash offset, or `None` if it is outside the window.
+    #[inline]
+    pub const fn to_physical(self, address: usize) -> Option<u32> {
+        if address < self.base || address - self.base >= self.len {
+            return None;
+        }
+        Some(self.image_offset + (address - self.base) as u32)
+    }
+    /// Check if bus address is inside the window.
+    #[inline]
+    pub const fn contains(&self, address: usize) -> bool {
+        self.to_physical(address).is_some()
+    }
+}
+
+/// Flash operation error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Address or length is not aligned to erase or program unit.
+    NotAligned,
+    /// Address range is outside of flash.
+    OutOfBounds,
+    /// Data does not fit into software interface buffer.
+    TooLong,
+}
+
+/// Command sent through software interface.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Command {
+    /// Command opcode.
+    pub opcode: u8,
+    /// 24-bit address, `None` if the command has no address phase.
+    pub address: Option<u32>,
+    /// Dummy phase length in bytes at the width of address phase.
+    pub dummy_bytes: u8,
+    /// Data line usage of address and data phases.
+    pub io_mode: IoMode,
+}
+
+impl Command {
+    /// Create a single line command without dummy phase.
+    #[inline]
+    pub const fn new(opcode: u8, address: Option<u32>) -> Self {
+        Self {
+            opcode,
+            address,
+            dummy_bytes: 0,
+            io_mode: IoMode::Single,
+        }
+    }
+    /// Standard fast read command of flash for `io_mode`.
+    #[inline]
+    pub const fn fast_read(io_mode: IoMode, address: u32) -> Self {
+        let (opcode, dummy_bytes) = match io_mode {
+            IoMode::Single => (CMD_FAST_READ, 1),
+            IoMode::DualOutput => (CMD_FAST_READ_DUAL_OUTPUT, 1),
+            IoMode::QuadOutput => (CMD_FAST_READ_QUAD_OUTPUT, 1),
+            // Mode byte is sent as a dummy byte.
+            IoMode::DualIo => (CMD_FAST_READ_DUAL_IO, 1),
+            IoMode::QuadIo => (CMD_FAST_READ_QUAD_IO, 3),
+        };
+        Self {
+            opcode,
+            address: Some(address),
+            dummy_bytes,
+            io_mode,
+        }
+    }
+    /// Standard page program command of flash for `io_mode`.
+    ///
+    /// Quad modes program over four data lines; other modes fall back to one line, as
+    /// flash has no dual program command.
+    #[inline]
+    pub const fn page_program(io_mode: IoMode, address: u32) -> Self {
+        match io_mode {
+            IoMode::QuadOutput | IoMode::QuadIo => Self {
+                opcode: CMD_QUAD_PAGE_PROGRAM,
+                address: Some(address),
+                dummy_bytes: 0,
+                io_mode: IoMode::QuadOutput,
+            },
+            _ => Self::new(CMD_PAGE_PROGRAM, Some(address)),
+        }
+    }
+}
+
+/// Managed serial flash peripheral.
+pub struct Flash<SF> {
+    sf: SF,
+    capacity: usize,
+}
+
+impl<SF: Deref<Target = RegisterBlock>> Flash<SF> {
+    /// Creates a serial flash instance with given flash capacity in bytes.
+    #[inline]
+    pub fn new(sf: SF, capacity: usize) -> Self {
+        Self { sf, capacity }
+    }
+    /// Read manufacturer and device identifier.
+    #[inline]
+    pub fn jedec_id(&mut self) -> [u8; 3] {
+        let mut id = [0u8; 3];
+        // Three bytes always fit in the data buffer.
+        let _ = unsafe {
+            command(
+                &self.sf,
+                Bank::One,
+                Command::new(CMD_READ_JEDEC_ID, None),
+                &mut id,
+                false,
+            )
+        };
+        id
+    }
+    /// Read data from flash at `address`.
+    #[inline]
+    pub fn read(&mut self, address: u32, buf: &mut [u8]) -> Result<(), Error> {
+        self.check_bounds(address, buf.len())?;
+        for (i, chunk) in buf.chunks_mut(PAGE_SIZE).enumerate() {
+            let address = address + (i * PAGE_SIZE) as u32;
+            unsafe {
+                command(
+                    &self.sf,
+                    Bank::One,
+                    Command::new(CMD_READ_DATA, Some(address)),
+                    chunk,
+                    false,
+                )?
+            };
+        }
+        Ok(())
+    }
+    /// Erase the 4-KiB sector at `address`.
+    #[inline]
+    pub fn erase_sector(&mut self, address: u32) -> Result<(), Error> {
+        self.erase(CMD_SECTOR_ERASE, address, SECTOR_SIZE)
+    }
+    /// Erase the 64-KiB block at `address`.
+    #[inline]
+    pub fn erase_block(&mut self, address: u32) -> Result<(), Error> {
+        self.erase(CMD_BLOCK_ERASE, address, BLOCK_SIZE)
+    }
+    /// Program data into flash; data must not cross a page boundary.
+    #[inline]
+    pub fn program_page(&mut self, address: u32, data: &[u8]) -> Result<(), Error> {
+        self.check_bounds(address, data.len())?;
+        if address as usize % PAGE_SIZE + data.len() > PAGE_SIZE {
+            return Err(Error::NotAligned);
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+        let mut page = [0u8; PAGE_SIZE];
+        page[..data.len()].copy_from_slice(data);
+        unsafe {
+            command(
+                &self.sf,
+                Bank::One,
+                Command::new(CMD_WRITE_ENABLE, None),
+                &mut [],
+                false,
+            )?;
+            command(
+                &self.sf,
+                Bank::One,
+                Command::new(CMD_PAGE_PROGRAM, Some(address)),
+                &mut page[..data.len()],
+                true,
+            )?;
+            wait_ready(&self.sf, Bank::One);
+        }
+        Ok(())
+    }
+    /// Release serial flash instance and return its peripheral.
+    #[inline]
+    pub fn free(self) -> SF {
+        self.sf
+    }
+    #[inline]
+    fn erase(&mut self, opcode: u8, address: u32, size: usize) -> Result<(), Error> {
+        self.check_bounds(address, size)?;
+        if !(address as usize).is_multiple_of(size) {
+            return Err(Error::NotAligned);
+        }
+        unsafe {
+            command(
+                &self.sf,
+                Bank::One,
+                Command::new(CMD_WRITE_ENABLE, None),
+                &mut [],
+                false,
+            )?;
+            command(
+                &self.sf,
+                Bank::One,
+                Command::new(opcode, Some(address)),
+                &mut [],
+                false,
+            )?;
+            wait_ready(&self.sf, Bank::One);
+        }
+        Ok(())
+    }
+    #[inline]
+    fn check_bounds(&self, address: u32, len: usize) -> Result<(), Error> {
+        match (address as usize).checked_add(len) {
+            Some(end) if end <= self.capacity => Ok(()),
+            _ => Err(Error::OutOfBounds),
+        }
+    }
+}
+
+/// Second flash or external PSRAM on bank 2 of serial flash controller.
+///
+/// Commands use the data line usage set by [`set_io_mode`](Self::set_io_mode), so data
+/// logging can use two or four lines while bank 1 keeps serving execute-in-place. Pads
+/// of bank 2 must be configured before creating this instance. As with [`Flash`],
+/// interrupt handlers that run from flash must be disabled during commands.
+pub struct Bank2<SF> {
+    sf: SF,
+    capacity: usize,
+    io_mode: IoMode,
+}
+
+impl<SF: Deref<Target = RegisterBlock>> Bank2<SF> {
+    /// Enable bank 2 with given memory capacity in bytes, using single line commands.
+    #[inline]
+    pub fn new(sf: SF, capacity: usize) -> Self {
+        unsafe { sf.config_2.modify(|val| val.enable_bank2()) };
+        Self {
+            sf,
+            capacity,
+            io_mode: IoMode::Single,
+        }
+    }
+    /// Get data line usage of read and program commands.
+    #[inline]
+    pub fn io_mode(&self) -> IoMode {
+        self.io_mode
+    }
+    /// Set data line usage of read and program commands.
+    ///
+    /// Quad modes require the memory to have its quad enable bit set, e.g. with
+    /// [`command`](Self::command) writing its status register; otherwise reads return
+    /// garbage, but the memory is not harmed.
+    #[inline]
+    pub fn set_io_mode(&mut self, io_mode: IoMode) {
+        self.io_mode = io_mode;
+    }
+    /// Read data at `address` with fast read command of current data line usage.
+    #[inline]
+    pub fn read(&mut self, address: u32, buf: &mut [u8]) -> Result<(), Error> {
+        self.check_bounds(address, buf.len())?;
+        for (i, chunk) in buf.chunks_mut(PAGE_SIZE).enumerate() {
+            let address = address + (i * PAGE_SIZE) as u32;
+            let cmd = Command::fast_read(self.io_mode, address);
+            unsafe { command(&self.sf, Bank::Two, cmd, chunk, false)? };
+        }
+        Ok(())
+    }
+    /// Program flash page with page program command of current data line usage; data
+    /// must not cross a page boundary.
+    #[inline]
+    pub fn program_page(&mut self, address: u32, data: &[u8]) -> Result<(), Error> {
+        self.check_bounds(address, data.len())?;
+        if address as usize % PAGE_SIZE + data.len() > PAGE_SIZE {
+            return Err(Error::NotAligned);
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+        let mut page = [0u8; PAGE_SIZE];
+        page[..data.len()].copy_from_slice(data);
+        let cmd = Command::page_program(self.io_mode, address);
+        unsafe {
+            command(
+                &self.sf,
+                Bank::Two,
+                Command::new(CMD_WRITE_ENABLE, None),
+                &mut [],
+                false,
+            )?;
+            command(&self.sf, Bank::Two, cmd, &mut page[..data.len()], true)?;
+            wait_ready(&self.sf, Bank::Two);
+        }
+        Ok(())
+    }
+    /// Send an arbitrary command, e.g. PSRAM quad write or flash status register access.
+    ///
+    /// Data of at most 256 bytes is written from or read into `data` depending on `write`.
+    #[inline]
+    pub fn command(&mut self, cmd: Command, data: &mut [u8], write: bool) -> Result<(), Error> {
+        unsafe { command(&self.sf, Bank::Two, cmd, data, write) }
+    }
+    /// Disable bank 2 and return the peripheral.
+    #[inline]
+    pub fn free(self) -> SF {
+        unsafe { self.sf.config_2.modify(|val| val.disable_bank2()) };
+        self.sf
+    }
+    #[inline]
+    fn check_bounds(&self, address: u32, len: usize) -> Result<(), Error> {
+        match (address as usize).checked_add(len) {
+            Some(end) if end <= self.capacity => Ok(()),
+            _ => Err(Error::OutOfBounds),
+        }
+    }
+}
+
+/// Send a command to `bank` through software interface, and switch back to execute-in-place.
+///
+/// Data of at most 256 bytes is written from or read into `data` depending on `write`.
+///
+/// While the software interface is selected, code and constants in flash cannot be
+/// fetched. This function and everything it calls must stay in RAM: data is moved with
+/// volatile byte loops and unchecked indexing, so neither `memcpy` nor panic paths in
+/// flash are referenced.
+#[inline(never)]
+#[cfg_attr(target_os = "none", unsafe(link_section = ".data.flash"))]
+unsafe fn command(
+    sf: &RegisterBlock,
+    bank: Bank,
+    cmd: Command,
+    data: &mut [u8],
+    write: bool,
+) -> Result<(), Error> {
+    let len = data.len();
+    if len > PAGE_SIZE {
+        return Err(Error::TooLong);
+    }
+    let data = data.as_mut_ptr();
+    unsafe {
+        sf.config_1.modify(|val| val.select_software());
+        sf.config_2.modify(|val| val.select_bank(bank));
+        let address_bytes = match cmd.address {
+            Some(address) => {
+                sf.command_buffer_0
+                    .write((cmd.opcode as u32) << 24 | (address & 0x00ff_ffff));
+                3
+            }
+            None => {
+                sf.command_buffer_0.write((cmd.opcode as u32) << 24);
+                0
+            }
+        };
+        if write {
+            let mut i = 0;
+            while i < len {
+                let mut word = 0u32;
+                let mut j = 0;
+                while j < 4 && i + j < len {
+                    word |= (data.add(i + j).read_volatile() as u32) << (j * 8);
+                    j += 1;
+                }
+                sf.data_buffer.get_unchecked(i / 4).write(word);
+                i += 4;
+            }
+        }
+        let config = SoftwareCommand::default()
+            .set_command_bytes(1)
+            .set_address_bytes(address_bytes)
+            .set_dummy_bytes(cmd.dummy_bytes)
+            .set_data(len as u16, write)
+            .set_io_mode(cmd.io_mode);
+        sf.software_command.write(config.set_trigger(false));
+        sf.software_command.write(config.set_trigger(true));
+        while sf.software_command.read().is_busy() {
+            core::hint::spin_loop();
+        }
+        if !write {
+            let mut i = 0;
+            while i < len {
+                let word = sf.data_buffer.get_unchecked(i / 4).read();
+                let mut j = 0;
+                while j < 4 && i + j < len {
+                    data.add(i + j).write_volatile((word >> (j * 8)) as u8);
+                    j += 1;
+                }
+                i += 4;
+            }
+        }
+        sf.config_2.modify(|val| val.select_bank(Bank::One));
+        sf.config_1.modify(|val| val.select_bus());
+    }
+    Ok(())
+}
+
+#[inline(never)]
+#[cfg_attr(target_os = "none", unsafe(link_section = ".data.flash"))]
+unsafe fn wait_ready(sf: &RegisterBlock, bank: Bank) {
+    loop {
+        let mut status = 0u8;
+        let cmd = Command::new(CMD_READ_STATUS, None);
+        // A single status byte always fits in the data buffer.
+        let _ = unsafe { command(sf, bank, cmd, core::slice::from_mut(&mut status), false) };
+        if status & STATUS_BUSY == 0 {
+            break;
+        }
+    }
+}
+
+impl embedded_storage::nor_flash::NorFlashError for Error {
+    #[inline]
+    fn kind(&self) -> embedded_storage::nor_flash::NorFlashErrorKind {
+        match self {
+            Error::NotAligned => embedded_storage::nor_flash::NorFlashErrorKind::NotAligned,
+            Error::OutOfBounds => embedded_storage::nor_flash::NorFlashErrorKind::OutOfBounds,
+            Error::TooLong => embedded_storage::nor_flash::NorFlashErrorKind::Other,
+        }
+    }
+}
+
+impl<SF> embedded_storage::nor_flash::ErrorType for Flash<SF> {
+    type Error = Error;
+}
+
+impl<SF: Deref<Target = RegisterBlock>> embedded_storage::nor_flash::ReadNorFlash for Flash<SF> {
+    const READ_SIZE: usize = 1;
+
+    #[inline]
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        Flash::read(self, offset, bytes)
+    }
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl<SF: Deref<Target = RegisterBlock>> embedded_storage::nor_flash::NorFlash for Flash<SF> {
+    const WRITE_SIZE: usize = 1;
+    const ERASE_SIZE: usize = SECTOR_SIZE;
+
+    #[inline]
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        if !(from as usize).is_multiple_of(SECTOR_SIZE)
+            || !(to as usize).is_multiple_of(SECTOR_SIZE)
+            || from > to
+        {
+            return Err(Error::NotAligned);
+        }
+        for address in (from..to).step_by(SECTOR_SIZE) {
+            self.erase_sector(address)?;
+        }
+        Ok(())
+    }
+    #[inline]
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.check_bounds(offset, bytes.len())?;
+        let mut address = offset;
+        let mut bytes = bytes;
+        while !bytes.is_empty() {
+            let len = core::cmp::min(PAGE_SIZE - address as usize % PAGE_SIZE, bytes.len());
+            self.program_page(address, &bytes[..len])?;
+            address += len as u32;
+            bytes = &bytes[len..];
+        }
+        Ok(())
+    }
+}
+
+impl<SF: Deref<Target = RegisterBlock>> embedded_storage::nor_flash::MultiwriteNorFlash
+    for Flash<SF>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Bank, Command, Config1, Config2, IoMode, RegisterBlock, SoftwareCommand, XipWindow,
+    };
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, config_0), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, config_1), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, software_command), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, command_buffer_0), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, command_buffer_1), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, config_2), 0x70);
+        assert_eq!(offset_of!(RegisterBlock, data_buffer), 0x600);
+    }
+
+    #[test]
+    fn struct_config1_functions() {
+        let val = Config1(0x0).select_bus();
+        assert_eq!(val.0, 0x5000_0000);
+        assert!(val.is_bus_selected());
+        let val = val.select_software();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_bus_selected());
+    }
+
+    #[test]
+    fn struct_config2_functions() {
+        let val = Config2(0x0).enable_bank2();
+        assert_eq!(val.0, 0x4000_0000);
+        assert!(val.is_bank2_enabled());
+        let val = val.select_bank(Bank::Two);
+        assert_eq!(val.0, 0xc000_0000);
+        assert_eq!(val.bank(), Bank::Two);
+        let val = val.select_bank(Bank::One).disable_bank2();
+        assert_eq!(val.0, 0x0000_0000);
+        assert_eq!(val.bank(), Bank::One);
+        assert!(!val.is_bank2_enabled());
+    }
+
+    #[test]
+    fn struct_software_command_functions() {
+        let val = SoftwareCommand(0x0)
+            .set_command_bytes(1)
+            .set_address_bytes(3)
+            .set_data(256, true);
+        assert_eq!(val.0, 0x0d84_03fc);
+        let val = val.set_trigger(true);
+        assert_eq!(val.0, 0x0d84_03fe);
+        let val = val
+            .set_trigger(false)
+            .set_address_bytes(0)
+            .set_data(0, false)
+            .set_dummy_bytes(1);
+        assert_eq!(val.0, 0x0a00_0000);
+        assert!(!val.is_busy());
+        let val = val.set_io_mode(IoMode::QuadIo);
+        assert_eq!(val.0, 0x4a00_0000);
+        assert_eq!(val.io_mode(), IoMode::QuadIo);
+        let val = val.set_io_mode(IoMode::DualOutput);
+        assert_eq!(val.0, 0x1a00_0000);
+        assert_eq!(val.io_mode(), IoMode::DualOutput);
+    }
+
+    #[test]
+    fn struct_command_functions() {
+        let cmd = Command::fast_read(IoMode::QuadIo, 0x1000);
+        assert_eq!(cmd.opcode, 0xeb);
+        assert_eq!(cmd.address, Some(0x1000));
+        assert_eq!(cmd.dummy_bytes, 3);
+        let cmd = Command::fast_read(IoMode::Single, 0x1000);
+        assert_eq!((cmd.opcode, cmd.dummy_bytes), (0x0b, 1));
+        let cmd = Command::page_program(IoMode::QuadIo, 0x100);
+        assert_eq!((cmd.opcode, cmd.io_mode), (0x32, IoMode::QuadOutput));
+        let cmd = Command::page_program(IoMode::DualIo, 0x100);
+        assert_eq!(cmd, Command::new(0x02, Some(0x100)));
+    }
+
+    #[test]
+    fn struct_xip_window_functions() {
+        let window = XipWindow::new(0x5800_0000, 0x2000, 0x10_0000);
+        assert_eq!(window.to_virtual(0x2000), Some(0x5800_0000));
+        assert_eq!(window.to_virtual(0x3456), Some(0x5800_1456));
+        assert_eq!(window.to_virtual(0x1fff), None);
+        assert_eq!(window.to_virtual(0x10_2000), None);
+        assert_eq!(window.to_physical(0x5800_1456), Some(0x3456));
+        assert_eq!(window.to_physical(0x57ff_ffff), None);
+        assert_eq!(window.to_physical(0x5810_0000), None);
+        assert!(window.contains(0x580f_ffff));
+    }
+}
@@ -0,0 +1,438 @@
+//! Wear-leveled key-value settings storage on NOR flash.
+//!
+//! The storage region is split into sectors used as an append-only log. Each
+//! sector starts with a header holding a sequence number; the valid sector with the
+//! largest sequence number is active. When the active sector is full, live records are
+//! copied into the next sector in turn, so erase cycles are spread over the region.
+use embedded_storage::nor_flash::MultiwriteNorFlash;
+
+/// Maximum length of a settings key in bytes.
+pub const MAX_KEY_LEN: usize = 32;
+/// Maximum length of a settings value in bytes.
+pub const MAX_VALUE_LEN: usize = 0xfffe;
+
+const SECTOR_MAGIC: [u8; 4] = *b"BLKV";
+const SECTOR_HEADER_LEN: u32 = 8;
+const RECORD_HEADER_LEN: u32 = 4;
+const STATE_EMPTY: u8 = 0xff;
+const STATE_PENDING: u8 = 0xfe;
+const STATE_VALID: u8 = 0xfc;
+const TOMBSTONE: u16 = 0xffff;
+
+/// Settings storage error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettingsError<E> {
+    /// Underlying flash error.
+    Flash(E),
+    /// Region is not sector aligned or has fewer than two sectors.
+    InvalidRegion,
+    /// Flash does not support byte-granular writes.
+    UnsupportedWriteSize,
+    /// Key is empty or longer than [`MAX_KEY_LEN`].
+    InvalidKey,
+    /// Value is longer than [`MAX_VALUE_LEN`].
+    ValueTooLong,
+    /// Buffer is too small for stored value; contains length of the value.
+    BufferTooSmall(usize),
+    /// Live records do not fit into one sector.
+    Full,
+}
+
+/// Key-value settings storage on a region of NOR flash.
+pub struct Settings<F> {
+    flash: F,
+    start: u32,
+    sectors: u32,
+    active: u32,
+    sequence: u32,
+    write_offset: u32,
+}
+
+#[derive(Clone, Copy)]
+struct Record {
+    offset: u32,
+    state: u8,
+    key_len: u8,
+    value_len: u16,
+}
+
+impl Record {
+    #[inline]
+    const fn len(&self) -> u32 {
+        RECORD_HEADER_LEN + self.key_len as u32 + self.data_len() as u32
+    }
+    #[inline]
+    const fn data_len(&self) -> u16 {
+        if self.value_len == TOMBSTONE {
+            0
+        } else {
+            self.value_len
+        }
+    }
+    #[inline]
+    const fn key_offset(&self) -> u32 {
+        self.offset + RECORD_HEADER_LEN
+    }
+    #[inline]
+    const fn value_offset(&self) -> u32 {
+        self.key_offset() + self.key_len as u32
+    }
+}
+
+impl<F: MultiwriteNorFlash> Settings<F> {
+    /// Mount settings storage on `sectors` erase sectors starting at `start`.
+    ///
+    /// The region is formatted if it holds no valid sector.
+    pub fn new(flash: F, start: u32, sectors: u32) -> Result<Self, SettingsError<F::Error>> {
+        if F::WRITE_SIZE != 1 {
+            return Err(SettingsError::UnsupportedWriteSize);
+        }
+        let end = (sectors as usize)
+            .checked_mul(F::ERASE_SIZE)
+            .and_then(|len| len.checked_add(start as usize));
+        if sectors < 2
+            || !(start as usize).is_multiple_of(F::ERASE_SIZE)
+            || !matches!(end, Some(end) if end <= flash.capacity())
+        {
+            return Err(SettingsError::InvalidRegion);
+        }
+        let mut ans = Self {
+            flash,
+            start,
+            sectors,
+            active: 0,
+            sequence: 0,
+            write_offset: SECTOR_HEADER_LEN,
+        };
+        let mut found = None;
+        for sector in 0..sectors {
+            if let Some(sequence) = ans.sector_sequence(sector)? {
+                match found {
+                    Some((_, current)) if sequence.wrapping_sub(current) as i32 <= 0 => {}
+                    _ => found = Some((sector, sequence)),
+                }
+            }
+        }
+        match found {
+            Some((sector, sequence)) => {
+                ans.active = sector;
+                ans.sequence = sequence;
+                ans.write_offset = ans.log_end(sector)?;
+            }
+            None => ans.format()?,
+        }
+        Ok(ans)
+    }
+    /// Read value of `key` into `buf`, returns value length if key exists.
+    pub fn get(
+        &mut self,
+        key: &[u8],
+        buf: &mut [u8],
+    ) -> Result<Option<usize>, SettingsError<F::Error>> {
+        check_key(key)?;
+        let Some(record) = self.find(self.active, key, 0)? else {
+            return Ok(None);
+        };
+        if record.value_len == TOMBSTONE {
+            return Ok(None);
+        }
+        let len = record.value_len as usize;
+        if buf.len() < len {
+            return Err(SettingsError::BufferTooSmall(len));
+        }
+        let address = self.address(self.active, record.value_offset());
+        self.flash
+            .read(address, &mut buf[..len])
+            .map_err(SettingsError::Flash)?;
+        Ok(Some(len))
+    }
+    /// Store `value` under `key`, replacing any previous value.
+    pub fn set(&mut self, key: &[u8], value: &[u8]) -> Result<(), SettingsError<F::Error>> {
+        check_key(key)?;
+        if value.len() > MAX_VALUE_LEN {
+            return Err(SettingsError::ValueTooLong);
+        }
+        self.append(key, value.len() as u16, value)
+    }
+    /// Remove `key` from storage.
+    pub fn remove(&mut self, key: &[u8]) -> Result<(), SettingsError<F::Error>> {
+        check_key(key)?;
+        match self.find(self.active, key, 0)? {
+            Some(record) if record.value_len != TOMBSTONE => self.append(key, TOMBSTONE, &[]),
+            _ => Ok(()),
+        }
+    }
+    /// Erase all settings.
+    pub fn format(&mut self) -> Result<(), SettingsError<F::Error>> {
+        let end = self.start + self.sectors * F::ERASE_SIZE as u32;
+        self.flash
+            .erase(self.start, end)
+            .map_err(SettingsError::Flash)?;
+        self.active = 0;
+        self.sequence = 0;
+        self.write_sector_header(0, 0)?;
+        self.write_offset = SECTOR_HEADER_LEN;
+        Ok(())
+    }
+    /// Release settings storage and return its flash.
+    #[inline]
+    pub fn free(self) -> F {
+        self.flash
+    }
+
+    fn append(
+        &mut self,
+        key: &[u8],
+        value_len: u16,
+        value: &[u8],
+    ) -> Result<(), SettingsError<F::Error>> {
+        let len = RECORD_HEADER_LEN + key.len() as u32 + value.len() as u32;
+        if self.write_offset + len > F::ERASE_SIZE as u32 {
+            self.compact()?;
+            if self.write_offset + len > F::ERASE_SIZE as u32 {
+                return Err(SettingsError::Full);
+            }
+        }
+        let [lo, hi] = value_len.to_le_bytes();
+        let header = [STATE_PENDING, key.len() as u8, lo, hi];
+        let offset = self.write_offset;
+        self.write(self.active, offset, &header)?;
+        self.write(self.active, offset + RECORD_HEADER_LEN, key)?;
+        self.write(
+            self.active,
+            offset + RECORD_HEADER_LEN + key.len() as u32,
+            value,
+        )?;
+        self.write(self.active, offset, &[STATE_VALID])?;
+        self.write_offset += len;
+        Ok(())
+    }
+    /// Copy live records into next sector and make it active.
+    fn compact(&mut self) -> Result<(), SettingsError<F::Error>> {
+        let source = self.active;
+        let target = (source + 1) % self.sectors;
+        let base = self.address(target, 0);
+        self.flash
+            .erase(base, base + F::ERASE_SIZE as u32)
+            .map_err(SettingsError::Flash)?;
+        let mut write_offset = SECTOR_HEADER_LEN;
+        let mut offset = SECTOR_HEADER_LEN;
+        let mut key = [0u8; MAX_KEY_LEN];
+        while let Some(record) = self.record_at(source, offset)? {
+            offset += record.len();
+            if record.state != STATE_VALID || record.value_len == TOMBSTONE {
+                continue;
+            }
+            let key = &mut key[..record.key_len as usize];
+            self.read(source, record.key_offset(), key)?;
+            if self.find(source, key, offset)?.is_some() {
+                continue;
+            }
+            let mut copied = 0;
+            let mut chunk = [0u8; 32];
+            while copied < record.len() {
+                let len = (record.len() - copied).min(chunk.len() as u32);
+                let chunk = &mut chunk[..len as usize];
+                self.read(source, record.offset + copied, chunk)?;
+                self.write(target, write_offset + copied, chunk)?;
+                copied += len;
+            }
+            write_offset += record.len();
+        }
+        let sequence = self.sequence.wrapping_add(1);
+        self.write_sector_header(target, sequence)?;
+        self.active = target;
+        self.sequence = sequence;
+        self.write_offset = write_offset;
+        Ok(())
+    }
+    /// Find latest valid record of `key` starting from `offset` in `sector`.
+    fn find(
+        &mut self,
+        sector: u32,
+        key: &[u8],
+        offset: u32,
+    ) -> Result<Option<Record>, SettingsError<F::Error>> {
+        let mut offset = offset.max(SECTOR_HEADER_LEN);
+        let mut found = None;
+        let mut buf = [0u8; MAX_KEY_LEN];
+        while let Some(record) = self.record_at(sector, offset)? {
+            offset += record.len();
+            if record.state != STATE_VALID || record.key_len as usize != key.len() {
+                continue;
+            }
+            let buf = &mut buf[..key.len()];
+            self.read(sector, record.key_offset(), buf)?;
+            if *buf == *key {
+                found = Some(record);
+            }
+        }
+        Ok(found)
+    }
+    fn record_at(
+        &mut self,
+        sector: u32,
+        offset: u32,
+    ) -> Result<Option<Record>, SettingsError<F::Error>> {
+        if offset + RECORD_HEADER_LEN > F::ERASE_SIZE as u32 {
+            return Ok(None);
+        }
+        let mut header = [0u8; 4];
+        self.read(sector, offset, &mut header)?;
+        if header[0] == STATE_EMPTY {
+            return Ok(None);
+        }
+        let record = Record {
+            offset,
+            state: header[0],
+            key_len: header[1],
+            value_len: u16::from_le_bytes([header[2], header[3]]),
+        };
+        if record.key_len as usize > MAX_KEY_LEN || offset + record.len() > F::ERASE_SIZE as u32 {
+            return Ok(None);
+        }
+        Ok(Some(record))
+    }
+    /// Offset of the first free byte in `sector`.
+    fn log_end(&mut self, sector: u32) -> Result<u32, SettingsError<F::Error>> {
+        let mut offset = SECTOR_HEADER_LEN;
+        while let Some(record) = self.record_at(sector, offset)? {
+            offset += record.len();
+        }
+        // A torn or corrupted record makes the remainder unusable until next compaction.
+        if offset + RECORD_HEADER_LEN <= F::ERASE_SIZE as u32 {
+            let mut state = [0u8];
+            self.read(sector, offset, &mut state)?;
+            if state[0] != STATE_EMPTY {
+                return Ok(F::ERASE_SIZE as u32);
+            }
+        }
+        Ok(offset)
+    }
+    fn sector_sequence(&mut self, sector: u32) -> Result<Option<u32>, SettingsError<F::Error>> {
+        let mut header = [0u8; SECTOR_HEADER_LEN as usize];
+        self.read(sector, 0, &mut header)?;
+        if header[..4] != SECTOR_MAGIC {
+            return Ok(None);
+        }
+        Ok(Some(u32::from_le_bytes([
+            header[4], header[5], header[6], header[7],
+        ])))
+    }
+    fn write_sector_header(
+        &mut self,
+        sector: u32,
+        sequence: u32,
+    ) -> Result<(), SettingsError<F::Error>> {
+        self.write(sector, 4, &sequence.to_le_bytes())?;
+        self.write(sector, 0, &SECTOR_MAGIC)
+    }
+    #[inline]
+    fn address(&self, sector: u32, offset: u32) -> u32 {
+        self.start + sector * F::ERASE_SIZE as u32 + offset
+    }
+    #[inline]
+    fn read(
+        &mut self,
+        sector: u32,
+        offset: u32,
+        buf: &mut [u8],
+    ) -> Result<(), SettingsError<F::Error>> {
+        let address = self.address(sector, offset);
+        self.flash.read(address, buf).map_err(SettingsError::Flash)
+    }
+    #[inline]
+    fn write(
+        &mut self,
+        sector: u32,
+        offset: u32,
+        buf: &[u8],
+    ) -> Result<(), SettingsError<F::Error>> {
+        let address = self.address(sector, offset);
+        self.flash.write(address, buf).map_err(SettingsError::Flash)
+    }
+}
+
+#[inline]
+fn check_key<E>(key: &[u8]) -> Result<(), SettingsError<E>> {
+    if key.is_empty() || key.len() > MAX_KEY_LEN {
+        return Err(SettingsError::InvalidKey);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Settings, SettingsError};
+    use embedded_storage::nor_flash::{
+        ErrorType, MultiwriteNorFlash, NorFlash, NorFlashErrorKind, ReadNorFlash,
+    };
+
+    struct MockFlash([u8; 3 * 256]);
+
+    impl ErrorType for MockFlash {
+        type Error = NorFlashErrorKind;
+    }
+
+    impl ReadNorFlash for MockFlash {
+        const READ_SIZE: usize = 1;
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            bytes.copy_from_slice(&self.0[offset..offset + bytes.len()]);
+            Ok(())
+        }
+        fn capacity(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    impl NorFlash for MockFlash {
+        const WRITE_SIZE: usize = 1;
+        const ERASE_SIZE: usize = 256;
+        fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+            self.0[from as usize..to as usize].fill(0xff);
+            Ok(())
+        }
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            for (dst, src) in self.0[offset..offset + bytes.len()].iter_mut().zip(bytes) {
+                *dst &= *src;
+            }
+            Ok(())
+        }
+    }
+
+    impl MultiwriteNorFlash for MockFlash {}
+
+    #[test]
+    fn settings_set_get_remove() {
+        let mut settings = Settings::new(MockFlash([0xff; 3 * 256]), 0, 3).unwrap();
+        let mut buf = [0u8; 16];
+        assert_eq!(settings.get(b"ssid", &mut buf), Ok(None));
+        settings.set(b"ssid", b"bouffalo").unwrap();
+        assert_eq!(settings.get(b"ssid", &mut buf), Ok(Some(8)));
+        assert_eq!(&buf[..8], b"bouffalo");
+        assert_eq!(
+            settings.get(b"ssid", &mut [0u8; 4]),
+            Err(SettingsError::BufferTooSmall(8))
+        );
+        settings.remove(b"ssid").unwrap();
+        assert_eq!(settings.get(b"ssid", &mut buf), Ok(None));
+    }
+
+    #[test]
+    fn settings_compact_and_remount() {
+        let mut settings = Settings::new(MockFlash([0xff; 3 * 256]), 0, 3).unwrap();
+        settings.set(b"calibration", b"\x01\x02\x03\x04").unwrap();
+        for i in 0..100u8 {
+            settings.set(b"counter", &[i]).unwrap();
+        }
+        let flash = settings.free();
+        let mut settings = Settings::new(flash, 0, 3).unwrap();
+        let mut buf = [0u8; 4];
+        assert_eq!(settings.get(b"counter", &mut buf), Ok(Some(1)));
+        assert_eq!(buf[0], 99);
+        assert_eq!(settings.get(b"calibration", &mut buf), Ok(Some(4)));
+        assert_eq!(&buf, b"\x01\x02\x03\x04");
+    }
+}
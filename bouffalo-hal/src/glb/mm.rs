@@ -9,6 +9,17 @@ pub struct RegisterBlock {
     pub cpu_config_0: RW<CpuConfig0>,
     /// CPU clock configuration register 1.
     pub cpu_config_1: RW<CpuConfig1>,
+    _reserved0: [u8; 0x38],
+    /// Software reset of the multi-media subsystem and its processor.
+    pub software_reset: RW<SoftwareReset>,
+}
+
+/// Multi-media subsystem miscellaneous registers.
+#[repr(C)]
+pub struct MiscRegisterBlock {
+    _reserved0: [u8; 0x208],
+    /// Reset vector of multi-media processor (D0).
+    pub cpu_boot_address: RW<u32>,
 }
 
 /// CPU clock source.
@@ -38,35 +49,31 @@ pub enum CpuRootClockSource {
 #[repr(transparent)]
 pub struct CpuConfig0(u32);
 
-impl CpuConfig0 {
-    const CPU_CLOCK_ENABLE: u32 = 0x1 << 1;
-    const CPU_CLOCK_SELECT: u32 = 0x3 << 8;
-    const CPU_ROOT_CLOCK_SELECT: u32 = 0x1 << 11;
+mod cpu_config_0 {
+    use crate::BitField;
 
-    /// Enable clock for CPU.
-    #[inline]
-    pub const fn enable_cpu_clock(self) -> Self {
-        Self(self.0 | Self::CPU_CLOCK_ENABLE)
-    }
-    /// Disable clock for CPU.
-    #[inline]
-    pub const fn disable_cpu_clock(self) -> Self {
-        Self(self.0 & !Self::CPU_CLOCK_ENABLE)
-    }
-    /// Check if clock for CPU is enabled.
-    #[inline]
-    pub const fn is_cpu_clock_enabled(self) -> bool {
-        self.0 & Self::CPU_CLOCK_ENABLE != 0
-    }
+    pub(crate) type CpuClockEnable = BitField<1, 1, u32>;
+    pub(crate) type CpuClockSelect = BitField<2, 8, u32>;
+    pub(crate) type CpuRootClockSelect = BitField<1, 11, u32>;
+}
+
+impl CpuConfig0 {
+    register_flag!(
+        enable_cpu_clock,
+        disable_cpu_clock,
+        is_cpu_clock_enabled,
+        cpu_config_0::CpuClockEnable,
+        "clock for CPU"
+    );
     /// Set clock source for CPU.
     #[inline]
     pub const fn set_cpu_clock_source(self, val: CpuClockSource) -> Self {
-        Self((self.0 & !Self::CPU_CLOCK_SELECT) | ((val as u32) << 8))
+        Self(cpu_config_0::CpuClockSelect::from(self.0).set(val as usize))
     }
     /// Get clock source for CPU.
     #[inline]
     pub const fn cpu_clock_source(self) -> CpuClockSource {
-        match (self.0 & Self::CPU_CLOCK_SELECT) >> 25 {
+        match cpu_config_0::CpuClockSelect::from(self.0).get() {
             0 => CpuClockSource::MuxPll240M,
             1 => CpuClockSource::MuxPll320M,
             _ => CpuClockSource::CpuPll400M,
@@ -75,12 +82,12 @@ impl CpuConfig0 {
     /// Set source for CPU root clock.
     #[inline]
     pub const fn set_cpu_root_clock_source(self, val: CpuRootClockSource) -> Self {
-        Self((self.0 & !Self::CPU_ROOT_CLOCK_SELECT) | ((val as u32) << 8))
+        Self(cpu_config_0::CpuRootClockSelect::from(self.0).set(val as usize))
     }
     /// Get source for CPU root clock.
     #[inline]
     pub const fn cpu_root_clock_source(self) -> CpuRootClockSource {
-        match (self.0 & Self::CPU_ROOT_CLOCK_SELECT) >> 8 {
+        match cpu_config_0::CpuRootClockSelect::from(self.0).get() {
             0 => CpuRootClockSource::Xclk,
             1 => CpuRootClockSource::Pll,
             _ => unreachable!(),
@@ -93,26 +100,113 @@ impl CpuConfig0 {
 #[repr(transparent)]
 pub struct CpuConfig1(u32);
 
+mod cpu_config_1 {
+    use crate::BitField;
+
+    pub(crate) type CpuClockDivide = BitField<8, 0, u32>;
+}
+
 impl CpuConfig1 {
-    const CPU_CLOCK_DIVIDE: u32 = 0xff << 0;
+    register_field!(
+        set_cpu_clock_divide,
+        cpu_clock_divide,
+        u8,
+        cpu_config_1::CpuClockDivide,
+        "CPU clock divide factor"
+    );
+}
 
-    /// Set CPU clock divide factor.
-    #[inline]
-    pub const fn set_cpu_clock_divide(self, val: u8) -> Self {
-        Self((self.0 & !Self::CPU_CLOCK_DIVIDE) | ((val as u32) << 0))
+/// Multi-media subsystem software reset register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct SoftwareReset(u32);
+
+mod software_reset {
+    use crate::BitField;
+
+    pub(crate) type System = BitField<1, 0, u32>;
+    pub(crate) type PowerOn = BitField<1, 2, u32>;
+    pub(crate) type Cpu = BitField<1, 8, u32>;
+}
+
+impl SoftwareReset {
+    register_flag! {
+        /// Hold multi-media subsystem in reset.
+        enable_system_reset,
+        /// Release multi-media subsystem from reset.
+        disable_system_reset,
+        /// Check if multi-media subsystem is held in reset.
+        is_system_reset_enabled,
+        software_reset::System
     }
-    /// Get CPU clock divide factor.
-    #[inline]
-    pub const fn cpu_clock_divide(self) -> u8 {
-        ((self.0 & Self::CPU_CLOCK_DIVIDE) >> 0) as u8
+    register_flag! {
+        /// Hold multi-media subsystem in power-on reset.
+        enable_power_on_reset,
+        /// Release multi-media subsystem from power-on reset.
+        disable_power_on_reset,
+        /// Check if multi-media subsystem is held in power-on reset.
+        is_power_on_reset_enabled,
+        software_reset::PowerOn
+    }
+    register_flag! {
+        /// Hold multi-media processor (D0) in reset.
+        enable_cpu_reset,
+        /// Release multi-media processor (D0) from reset.
+        disable_cpu_reset,
+        /// Check if multi-media processor (D0) is held in reset.
+        is_cpu_reset_enabled,
+        software_reset::Cpu
+    }
+}
+
+/// Hold multi-media processor (D0) in reset.
+///
+/// Call from the MCU core (M0), e.g. before loading a new D0 firmware image.
+#[inline]
+pub fn halt_dsp(mm_glb: &RegisterBlock) {
+    unsafe { mm_glb.software_reset.modify(|val| val.enable_cpu_reset()) };
+}
+
+/// Set reset vector of multi-media processor (D0).
+///
+/// Takes effect on next release from reset; the image at `entry` must be written and
+/// cleaned from data cache of the calling core before that.
+#[inline]
+pub fn set_dsp_entry(mm_misc: &MiscRegisterBlock, entry: u32) {
+    unsafe { mm_misc.cpu_boot_address.write(entry) };
+}
+
+/// Get reset vector of multi-media processor (D0).
+#[inline]
+pub fn dsp_entry(mm_misc: &MiscRegisterBlock) -> u32 {
+    mm_misc.cpu_boot_address.read()
+}
+
+/// Enable clock of multi-media processor (D0) and release it from reset.
+#[inline]
+pub fn release_dsp(mm_glb: &RegisterBlock) {
+    unsafe {
+        mm_glb.cpu_config_0.modify(|val| val.enable_cpu_clock());
+        mm_glb.software_reset.modify(|val| val.disable_cpu_reset());
     }
 }
 
+/// Restart multi-media processor (D0) at `entry`.
+///
+/// Halts the core, sets its reset vector and releases it again.
+#[inline]
+pub fn boot_dsp(mm_glb: &RegisterBlock, mm_misc: &MiscRegisterBlock, entry: u32) {
+    halt_dsp(mm_glb);
+    set_dsp_entry(mm_misc, entry);
+    release_dsp(mm_glb);
+}
+
 #[cfg(test)]
 mod tests {
     use crate::glb::mm::{CpuClockSource, CpuRootClockSource};
 
-    use super::{CpuConfig0, CpuConfig1};
+    use super::{CpuConfig0, CpuConfig1, MiscRegisterBlock, RegisterBlock, SoftwareReset};
+    use core::mem::offset_of;
 
     #[test]
     fn struct_cpu_config0_functions() {
@@ -133,12 +227,12 @@ mod tests {
         config = CpuConfig0(0x0);
         config = config.set_cpu_clock_source(CpuClockSource::MuxPll320M);
         assert_eq!(config.0, 0x00000100);
-        assert_eq!(config.cpu_clock_source(), CpuClockSource::MuxPll240M);
+        assert_eq!(config.cpu_clock_source(), CpuClockSource::MuxPll320M);
 
         config = CpuConfig0(0x0);
         config = config.set_cpu_clock_source(CpuClockSource::CpuPll400M);
         assert_eq!(config.0, 0x00000200);
-        assert_eq!(config.cpu_clock_source(), CpuClockSource::MuxPll240M);
+        assert_eq!(config.cpu_clock_source(), CpuClockSource::CpuPll400M);
 
         config = CpuConfig0(0x0);
         config = config.set_cpu_root_clock_source(CpuRootClockSource::Xclk);
@@ -147,8 +241,8 @@ mod tests {
 
         config = CpuConfig0(0x0);
         config = config.set_cpu_root_clock_source(CpuRootClockSource::Pll);
-        assert_eq!(config.0, 0x00000100);
-        assert_eq!(config.cpu_root_clock_source(), CpuRootClockSource::Xclk);
+        assert_eq!(config.0, 0x00000800);
+        assert_eq!(config.cpu_root_clock_source(), CpuRootClockSource::Pll);
     }
 
     #[test]
@@ -158,4 +252,29 @@ mod tests {
         assert_eq!(config.0, 0x00000001);
         assert_eq!(config.cpu_clock_divide(), 0x01);
     }
+
+    #[test]
+    fn struct_software_reset_functions() {
+        let mut val = SoftwareReset(0x0);
+        val = val.enable_system_reset();
+        assert_eq!(val.0, 0x00000001);
+        assert!(val.is_system_reset_enabled());
+        val = val.disable_system_reset().enable_power_on_reset();
+        assert_eq!(val.0, 0x00000004);
+        assert!(val.is_power_on_reset_enabled());
+        val = val.disable_power_on_reset().enable_cpu_reset();
+        assert_eq!(val.0, 0x00000100);
+        assert!(val.is_cpu_reset_enabled());
+        val = val.disable_cpu_reset();
+        assert_eq!(val.0, 0x00000000);
+        assert!(!val.is_cpu_reset_enabled());
+    }
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, cpu_config_0), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, cpu_config_1), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, software_reset), 0x40);
+        assert_eq!(offset_of!(MiscRegisterBlock, cpu_boot_address), 0x208);
+    }
 }
@@ -4,6 +4,8 @@ pub mod mm;
 pub mod v1;
 pub mod v2;
 
+use volatile_register::RW;
+
 /// Pin pull direction.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
@@ -40,3 +42,37 @@ cfg_if::cfg_if! {
         pub struct RegisterBlock {}
     }
 }
+
+/// Read-modify-write a register shared by several pins or signals.
+///
+/// Interrupts of the current core are masked meanwhile, so pin and multiplexer drivers
+/// may change modes from interrupt handlers without losing updates of one another.
+#[inline]
+pub(crate) fn modify_shared<T: Copy>(reg: &RW<T>, f: impl FnOnce(T) -> T) {
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    let mstatus: usize;
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    unsafe {
+        core::arch::asm!("csrrci {}, mstatus, 8", out(reg) mstatus)
+    };
+    unsafe { reg.write(f(reg.read())) };
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    unsafe {
+        core::arch::asm!("csrs mstatus, {}", in(reg) mstatus & 8)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::modify_shared;
+    use volatile_register::RW;
+
+    #[test]
+    fn function_modify_shared() {
+        let reg: RW<u32> = unsafe { core::mem::zeroed() };
+        unsafe { reg.write(0x0000_00f0) };
+        modify_shared(&reg, |val| val | 0x1);
+        modify_shared(&reg, |val| val & !0x10);
+        assert_eq!(reg.read(), 0x0000_00e1);
+    }
+}
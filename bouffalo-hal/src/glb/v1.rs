@@ -35,47 +35,60 @@ pub struct RegisterBlock {
 #[repr(transparent)]
 pub struct GpioConfig(u32);
 
-impl GpioConfig {
-    const INPUT_ENABLE: u32 = 1 << 0;
-    const SCHMITT: u32 = 1 << 1;
-    const DRIVE: u32 = 0x3 << 2;
-    const PULL: u32 = 0x3 << 4;
-    const FUNCTION: u32 = 0x1f << 8;
+// Each register configures two pins, one in each half-word.
+mod gpio_config {
+    use crate::BitField;
+
+    pub(crate) type InputEnable = BitField<1, 0, u16>;
+    pub(crate) type Schmitt = BitField<1, 1, u16>;
+    pub(crate) type Drive = BitField<2, 2, u16>;
+    pub(crate) type Pull = BitField<2, 4, u16>;
+    pub(crate) type Function = BitField<5, 8, u16>;
+}
 
+impl GpioConfig {
+    #[inline]
+    const fn pin(self, idx: usize) -> u16 {
+        (self.0 >> (idx * 16)) as u16
+    }
+    #[inline]
+    const fn with_pin(self, idx: usize, val: u16) -> Self {
+        Self((self.0 & !(0xffff << (idx * 16))) | ((val as u32) << (idx * 16)))
+    }
     /// Enable input function of current pin.
     #[inline]
     pub const fn enable_input(self, idx: usize) -> Self {
-        Self(self.0 | (Self::INPUT_ENABLE << (idx * 16)))
+        self.with_pin(idx, gpio_config::InputEnable::from(self.pin(idx)).enable())
     }
     /// Disable input function of current pin.
     #[inline]
     pub const fn disable_input(self, idx: usize) -> Self {
-        Self(self.0 & !(Self::INPUT_ENABLE << (idx * 16)))
+        self.with_pin(idx, gpio_config::InputEnable::from(self.pin(idx)).disable())
     }
     /// Check if input function of current pin is enabled.
     #[inline]
     pub const fn is_input_enabled(self, idx: usize) -> bool {
-        self.0 & (Self::INPUT_ENABLE << (idx * 16)) != 0
+        gpio_config::InputEnable::from(self.pin(idx)).is_enabled()
     }
     /// Enable Schmitt trigger function of current pin.
     #[inline]
     pub const fn enable_schmitt(self, idx: usize) -> Self {
-        Self(self.0 | (Self::SCHMITT << (idx * 16)))
+        self.with_pin(idx, gpio_config::Schmitt::from(self.pin(idx)).enable())
     }
     /// Disable Schmitt trigger function of current pin.
     #[inline]
     pub const fn disable_schmitt(self, idx: usize) -> Self {
-        Self(self.0 & !(Self::SCHMITT << (idx * 16)))
+        self.with_pin(idx, gpio_config::Schmitt::from(self.pin(idx)).disable())
     }
     /// Check if Schmitt trigger function of current pin is enabled.
     #[inline]
     pub const fn is_schmitt_enabled(self, idx: usize) -> bool {
-        self.0 & (Self::SCHMITT << (idx * 16)) != 0
+        gpio_config::Schmitt::from(self.pin(idx)).is_enabled()
     }
     /// Get drive strength of current pin.
     #[inline]
     pub const fn drive(self, idx: usize) -> Drive {
-        match ((self.0 >> (idx * 16)) & Self::DRIVE) >> 2 {
+        match gpio_config::Drive::from(self.pin(idx)).get() {
             0 => Drive::Drive0,
             1 => Drive::Drive1,
             2 => Drive::Drive2,
@@ -86,11 +99,14 @@ impl GpioConfig {
     /// Set drive strength of current pin.
     #[inline]
     pub const fn set_drive(self, idx: usize, val: Drive) -> Self {
-        Self((self.0 & !(Self::DRIVE << (idx * 16))) | ((val as u32) << (2 + (idx * 16))))
+        self.with_pin(
+            idx,
+            gpio_config::Drive::from(self.pin(idx)).set(val as usize),
+        )
     }
     /// Get pull direction of current pin.
     pub const fn pull(self, idx: usize) -> Pull {
-        match ((self.0 >> (idx * 16)) & Self::PULL) >> 4 {
+        match gpio_config::Pull::from(self.pin(idx)).get() {
             0 => Pull::None,
             1 => Pull::Up,
             2 => Pull::Down,
@@ -100,17 +116,23 @@ impl GpioConfig {
     /// Set pull direction of current pin.
     #[inline]
     pub const fn set_pull(self, idx: usize, val: Pull) -> Self {
-        Self((self.0 & !(Self::PULL << (idx * 16))) | ((val as u32) << (4 + (idx * 16))))
+        self.with_pin(
+            idx,
+            gpio_config::Pull::from(self.pin(idx)).set(val as usize),
+        )
     }
     /// Set function of current pin.
     #[inline]
     pub const fn set_function(self, idx: usize, val: Function) -> Self {
-        Self((self.0 & !(Self::FUNCTION << (idx * 16))) | ((val as u32) << (8 + (idx * 16))))
+        self.with_pin(
+            idx,
+            gpio_config::Function::from(self.pin(idx)).set(val as usize),
+        )
     }
     /// Get function of current pin.
     #[inline]
     pub const fn function(self, idx: usize) -> Function {
-        match ((self.0 >> (idx * 16)) & Self::FUNCTION) >> 8 {
+        match gpio_config::Function::from(self.pin(idx)).get() {
             0 => Function::ClkOut,
             1 => Function::BtCoexist,
             2 => Function::Flash,
@@ -144,18 +166,25 @@ impl GpioConfig {
 #[repr(transparent)]
 pub struct GpioInterruptMode(u32);
 
-impl GpioInterruptMode {
-    const INTERRUPT_MODE: u32 = 0x7;
+// Field of pin `idx` is shifted by `idx * 3`.
+mod gpio_interrupt_mode {
+    use crate::BitField;
 
+    pub(crate) type InterruptMode = BitField<3, 0, u32>;
+}
+
+impl GpioInterruptMode {
     /// Set interrupt mode of current pin.
     #[inline]
     pub const fn set_interrupt_mode(self, idx: usize, val: InterruptMode) -> Self {
-        Self((self.0 & !(Self::INTERRUPT_MODE << (idx * 3))) | ((val as u32) << (idx * 3)))
+        let field = gpio_interrupt_mode::InterruptMode::from(self.0 >> (idx * 3));
+        let mask = (field.get_mask() as u32) << (idx * 3);
+        Self((self.0 & !mask) | ((field.set(val as usize) << (idx * 3)) & mask))
     }
     /// Get interrupt mode of current pin.
     #[inline]
     pub const fn interrupt_mode(self, idx: usize) -> InterruptMode {
-        match (self.0 >> (idx * 3)) & Self::INTERRUPT_MODE {
+        match gpio_interrupt_mode::InterruptMode::from(self.0 >> (idx * 3)).get() {
             0 => InterruptMode::SyncFallingEdge,
             1 => InterruptMode::SyncRisingEdge,
             2 => InterruptMode::SyncLowLevel,
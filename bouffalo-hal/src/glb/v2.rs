@@ -13,18 +13,29 @@ pub struct RegisterBlock {
     _reserved1: [u8; 0x24],
     /// Inter-Integrated Circuit configuration register.
     pub i2c_config: RW<I2cConfig>,
-    _reserved2: [u8; 0x2c],
+    _reserved2: [u8; 0xc],
+    /// Inter-IC Sound configuration register.
+    pub i2s_config: RW<I2sConfig>,
+    _reserved3: [u8; 0x1c],
     /// Serial Peripheral Interface configuration register.
     pub spi_config: RW<SpiConfig>,
-    _reserved3: [u8; 0x1c],
+    _reserved4: [u8; 0x1c],
     /// Pulse Width Modulation configuration register.
     pub pwm_config: RW<PwmConfig>,
-    _reserved4: [u8; 0x25c],
+    _reserved5: [u8; 0x254],
+    /// Chip clock output configuration register.
+    pub clock_out_config: RW<ClockOutConfig>,
+    _reserved6: [u8; 0x4],
     /// SDH configuration register.
     pub sdh_config: RW<SdhConfig>,
-    _reserved5: [u8; 0xdd],
+    _reserved7: [u8; 0xdd],
     pub param_config: RW<ParamConfig>,
-    _reserved6: [u8; 0x6c],
+    _reserved8: [u8; 0x2c],
+    /// Software reset of peripherals, one bit per [`Peripheral`].
+    pub software_reset: [RW<u32>; 2],
+    /// Software reset of processors and the whole chip.
+    pub reset_control: RW<ResetControl>,
+    _reserved9: [u8; 0x34],
     /// Clock generation configuration 0.
     pub clock_config_0: RW<ClockConfig0>,
     /// Clock generation configuration 1.
@@ -33,16 +44,16 @@ pub struct RegisterBlock {
     pub clock_config_2: RW<ClockConfig2>,
     /// Clock generation configuration 3.
     pub clock_config_3: RW<ClockConfig3>,
-    _reserved7: [u8; 0x140],
+    _reserved10: [u8; 0x140],
     /// LDO12UHS config.
     pub ldo12uhs_config: RW<Ldo12uhsConfig>,
-    _reserved8: [u8; 0x1f0],
+    _reserved11: [u8; 0x1f0],
     /// Generic Purpose Input/Output config.
     pub gpio_config: [RW<GpioConfig>; 46],
-    _reserved9: [u8; 0x148],
+    _reserved12: [u8; 0x148],
     /// Read value from Generic Purpose Input/Output pads.
     pub gpio_input: [RO<u32>; 2],
-    _reserved10: [u8; 0x18],
+    _reserved13: [u8; 0x18],
     /// Write value to Generic Purpose Input/Output pads.
     pub gpio_output: [RW<u32>; 2],
     /// Set pin output value to high.
@@ -56,40 +67,33 @@ pub struct RegisterBlock {
 #[repr(transparent)]
 pub struct UartConfig(u32);
 
-impl UartConfig {
-    /// TODO: make divide factors a new type(enum), like UartSignal
-    const CLOCK_DIVIDE: u32 = 0x7 << 0;
-    const CLOCK_ENABLE: u32 = 0x1 << 4;
-    // const HBN_CLOCK_SEL: u32 = 0x1 << 7;
-    // const HBN_CLOCK_SEL2: u32 = 0x1 << 22;
-    // const UART2_IO_SEL: u32 = 0x1 << 24;
-
-    /// Set peripheral clock divide factor.
-    #[inline]
-    pub const fn set_clock_divide(self, val: u8) -> Self {
-        Self(self.0 & !Self::CLOCK_DIVIDE | ((val as u32) << 0) & Self::CLOCK_DIVIDE)
-    }
-    /// Get peripheral clock divide factor.
-    #[inline]
-    pub const fn clock_divide(self) -> u8 {
-        (self.0 & Self::CLOCK_DIVIDE) as u8
-    }
+mod uart_config {
+    use crate::BitField;
 
-    /// Enable peripheral level clock gate.
-    #[inline]
-    pub const fn enable_clock(self) -> Self {
-        Self(self.0 | Self::CLOCK_ENABLE)
-    }
-    /// Disable peripheral level clock gate.
-    #[inline]
-    pub const fn disable_clock(self) -> Self {
-        Self(self.0 & !Self::CLOCK_ENABLE)
-    }
-    /// Check if peripheral level clock gate is enabled.
-    #[inline]
-    pub const fn is_clock_enabled(self) -> bool {
-        self.0 & Self::CLOCK_ENABLE != 0
-    }
+    // TODO: make divide factors a new type(enum), like UartSignal
+    pub(crate) type ClockDivide = BitField<3, 0, u32>;
+    pub(crate) type ClockEnable = BitField<1, 4, u32>;
+    // pub(crate) type HbnClockSel = BitField<1, 7, u32>;
+    // pub(crate) type HbnClockSel2 = BitField<1, 22, u32>;
+    // pub(crate) type Uart2IoSel = BitField<1, 24, u32>;
+}
+
+impl UartConfig {
+    register_field!(
+        set_clock_divide,
+        clock_divide,
+        u8,
+        uart_config::ClockDivide,
+        "peripheral clock divide factor"
+    );
+
+    register_flag!(
+        enable_clock,
+        disable_clock,
+        is_clock_enabled,
+        uart_config::ClockEnable,
+        "peripheral level clock gate"
+    );
 }
 
 /// UART signal multiplexer group configuration register.
@@ -127,35 +131,52 @@ pub enum UartSignal {
     Rxd2 = 11,
 }
 
-impl UartMuxGroup {
-    const SIGNAL: u32 = 0xf;
+// Field of signal `idx` is shifted by `idx * 4`.
+mod uart_mux_group {
+    use crate::BitField;
 
+    pub(crate) type Signal = BitField<4, 0, u32>;
+}
+
+impl UartMuxGroup {
     /// Set signal for UART multiplexer.
     #[inline]
     pub const fn set_signal(self, idx: usize, val: UartSignal) -> Self {
         assert!(idx <= 7);
-        Self((self.0 & !(Self::SIGNAL << (idx * 4))) | ((val as u32) << (idx * 4)))
+        let field = uart_mux_group::Signal::from(self.0 >> (idx * 4));
+        let mask = (field.get_mask() as u32) << (idx * 4);
+        Self((self.0 & !mask) | ((field.set(val as usize) << (idx * 4)) & mask))
     }
     /// Get signal for UART multiplexer.
     #[inline]
     pub const fn signal(self, idx: usize) -> UartSignal {
-        assert!(idx <= 7);
-        match (self.0 & (Self::SIGNAL << (idx * 4))) >> (idx * 4) {
-            0 => UartSignal::Rts0,
-            1 => UartSignal::Cts0,
-            2 => UartSignal::Txd0,
-            3 => UartSignal::Rxd0,
-            4 => UartSignal::Rts1,
-            5 => UartSignal::Cts1,
-            6 => UartSignal::Txd1,
-            7 => UartSignal::Rxd1,
-            8 => UartSignal::Rts2,
-            9 => UartSignal::Cts2,
-            10 => UartSignal::Txd2,
-            11 => UartSignal::Rxd2,
-            _ => unreachable!(),
+        match self.try_signal(idx) {
+            Some(signal) => signal,
+            None => unreachable!(),
         }
     }
+    /// Get signal for UART multiplexer, or `None` if the field holds a reserved value.
+    #[inline]
+    pub const fn try_signal(self, idx: usize) -> Option<UartSignal> {
+        assert!(idx <= 7);
+        Some(
+            match uart_mux_group::Signal::from(self.0 >> (idx * 4)).get() {
+                0 => UartSignal::Rts0,
+                1 => UartSignal::Cts0,
+                2 => UartSignal::Txd0,
+                3 => UartSignal::Rxd0,
+                4 => UartSignal::Rts1,
+                5 => UartSignal::Cts1,
+                6 => UartSignal::Txd1,
+                7 => UartSignal::Rxd1,
+                8 => UartSignal::Rts2,
+                9 => UartSignal::Cts2,
+                10 => UartSignal::Txd2,
+                11 => UartSignal::Rxd2,
+                _ => return None,
+            },
+        )
+    }
 }
 
 /// Inter-Integrated Circuit clock source.
@@ -173,45 +194,38 @@ pub enum I2cClockSource {
 #[repr(transparent)]
 pub struct I2cConfig(u32);
 
-impl I2cConfig {
-    const CLOCK_DIVIDE: u32 = 0xff << 16;
-    const CLOCK_ENABLE: u32 = 1 << 24;
-    const CLOCK_SELECT: u32 = 1 << 25;
+mod i2c_config {
+    use crate::BitField;
 
-    /// Set peripheral clock divide factor.
-    #[inline]
-    pub const fn set_clock_divide(self, val: u8) -> Self {
-        Self((self.0 & !Self::CLOCK_DIVIDE) | ((val as u32) << 16))
-    }
-    /// Get peripheral clock divide factor.
-    #[inline]
-    pub const fn clock_divide(self) -> u8 {
-        ((self.0 & Self::CLOCK_DIVIDE) >> 16) as u8
-    }
-    /// Enable clock for Inter-Integrated Circuit peripheral.
-    #[inline]
-    pub const fn enable_clock(self) -> Self {
-        Self(self.0 | Self::CLOCK_ENABLE)
-    }
-    /// Disable clock for Inter-Integrated Circuit peripheral.
-    #[inline]
-    pub const fn disable_clock(self) -> Self {
-        Self(self.0 & !Self::CLOCK_ENABLE)
-    }
-    /// Check if clock for Inter-Integrated Circuit peripheral is enabled.
-    #[inline]
-    pub const fn is_clock_enabled(self) -> bool {
-        self.0 & Self::CLOCK_ENABLE != 0
-    }
+    pub(crate) type ClockDivide = BitField<8, 16, u32>;
+    pub(crate) type ClockEnable = BitField<1, 24, u32>;
+    pub(crate) type ClockSelect = BitField<1, 25, u32>;
+}
+
+impl I2cConfig {
+    register_field!(
+        set_clock_divide,
+        clock_divide,
+        u8,
+        i2c_config::ClockDivide,
+        "peripheral clock divide factor"
+    );
+    register_flag!(
+        enable_clock,
+        disable_clock,
+        is_clock_enabled,
+        i2c_config::ClockEnable,
+        "clock for Inter-Integrated Circuit peripheral"
+    );
     /// Set clock source for Inter-Integrated Circuit peripheral.
     #[inline]
     pub const fn set_clock_source(self, val: I2cClockSource) -> Self {
-        Self((self.0 & !Self::CLOCK_SELECT) | ((val as u32) << 25))
+        Self(i2c_config::ClockSelect::from(self.0).set(val as usize))
     }
     /// Get clock source for Inter-Integrated Circuit peripheral.
     #[inline]
     pub const fn clock_source(self) -> I2cClockSource {
-        match (self.0 & Self::CLOCK_SELECT) >> 25 {
+        match i2c_config::ClockSelect::from(self.0).get() {
             0 => I2cClockSource::Bclk,
             1 => I2cClockSource::Xclk,
             _ => unreachable!(),
@@ -219,6 +233,55 @@ impl I2cConfig {
     }
 }
 
+/// Inter-IC Sound configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct I2sConfig(u32);
+
+mod i2s_config {
+    use crate::BitField;
+
+    pub(crate) type ReferenceClockDivide = BitField<6, 0, u32>;
+    pub(crate) type DataInReferenceClock = BitField<1, 6, u32>;
+    pub(crate) type ReferenceClockEnable = BitField<1, 7, u32>;
+    pub(crate) type DataOutReferenceClock = BitField<1, 8, u32>;
+}
+
+impl I2sConfig {
+    register_field!(
+        set_reference_clock_divide,
+        reference_clock_divide,
+        u8,
+        i2s_config::ReferenceClockDivide,
+        "audio PLL divide factor for reference (master) clock"
+    );
+    register_flag!(
+        enable_reference_clock,
+        disable_reference_clock,
+        is_reference_clock_enabled,
+        i2s_config::ReferenceClockEnable,
+        "reference clock output"
+    );
+    register_flag! {
+        /// Route reference clock to data input pad.
+        enable_data_in_reference_clock,
+        /// Use data input pad as data input.
+        disable_data_in_reference_clock,
+        /// Check if reference clock is routed to data input pad.
+        is_data_in_reference_clock_enabled,
+        i2s_config::DataInReferenceClock
+    }
+    register_flag! {
+        /// Route reference clock to data output pad.
+        enable_data_out_reference_clock,
+        /// Use data output pad as data output.
+        disable_data_out_reference_clock,
+        /// Check if reference clock is routed to data output pad.
+        is_data_out_reference_clock_enabled,
+        i2s_config::DataOutReferenceClock
+    }
+}
+
 /// Serial Peripheral Interface clock source.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
@@ -234,46 +297,39 @@ pub enum SpiClockSource {
 #[repr(transparent)]
 pub struct SpiConfig(u32);
 
-impl SpiConfig {
-    const CLOCK_DIVIDE: u32 = 0xff << 0;
-    const CLOCK_ENABLE: u32 = 1 << 8;
-    const CLOCK_SELECT: u32 = 1 << 9;
-    // TODO const SWAP_SELECT: u32 = 1 << 16;
+mod spi_config {
+    use crate::BitField;
 
-    /// Set peripheral clock divide factor.
-    #[inline]
-    pub const fn set_clock_divide(self, val: u8) -> Self {
-        Self((self.0 & !Self::CLOCK_DIVIDE) | ((val as u32) << 0))
-    }
-    /// Get peripheral clock divide factor.
-    #[inline]
-    pub const fn clock_divide(self) -> u8 {
-        ((self.0 & Self::CLOCK_DIVIDE) >> 0) as u8
-    }
-    /// Enable clock for Serial Peripheral Interface peripheral.
-    #[inline]
-    pub const fn enable_clock(self) -> Self {
-        Self(self.0 | Self::CLOCK_ENABLE)
-    }
-    /// Disable clock for Serial Peripheral Interface peripheral.
-    #[inline]
-    pub const fn disable_clock(self) -> Self {
-        Self(self.0 & !Self::CLOCK_ENABLE)
-    }
-    /// Check if clock for Serial Peripheral Interface peripheral is enabled.
-    #[inline]
-    pub const fn is_clock_enabled(self) -> bool {
-        self.0 & Self::CLOCK_ENABLE != 0
-    }
+    pub(crate) type ClockDivide = BitField<8, 0, u32>;
+    pub(crate) type ClockEnable = BitField<1, 8, u32>;
+    pub(crate) type ClockSelect = BitField<1, 9, u32>;
+    // TODO pub(crate) type SwapSelect = BitField<1, 16, u32>;
+}
+
+impl SpiConfig {
+    register_field!(
+        set_clock_divide,
+        clock_divide,
+        u8,
+        spi_config::ClockDivide,
+        "peripheral clock divide factor"
+    );
+    register_flag!(
+        enable_clock,
+        disable_clock,
+        is_clock_enabled,
+        spi_config::ClockEnable,
+        "clock for Serial Peripheral Interface peripheral"
+    );
     /// Set clock source for Serial Peripheral Interface peripheral.
     #[inline]
     pub const fn set_clock_source(self, val: SpiClockSource) -> Self {
-        Self((self.0 & !Self::CLOCK_SELECT) | ((val as u32) << 9))
+        Self(spi_config::ClockSelect::from(self.0).set(val as usize))
     }
     /// Get clock source for Serial Peripheral Interface peripheral.
     #[inline]
     pub const fn clock_source(self) -> SpiClockSource {
-        match (self.0 & Self::CLOCK_SELECT) >> 9 {
+        match spi_config::ClockSelect::from(self.0).get() {
             0 => SpiClockSource::MuxPll160M,
             1 => SpiClockSource::Xclk,
             _ => unreachable!(),
@@ -286,19 +342,23 @@ impl SpiConfig {
 #[repr(transparent)]
 pub struct PwmConfig(u32);
 
-impl PwmConfig {
-    const SIGNAL_0_SELECT: u32 = 1 << 0;
-    const SIGNAL_1_SELECT: u32 = 1 << 1;
+mod pwm_config {
+    use crate::BitField;
 
+    pub(crate) type Signal0Select = BitField<1, 0, u32>;
+    pub(crate) type Signal1Select = BitField<1, 1, u32>;
+}
+
+impl PwmConfig {
     /// Set source for signal group 0.
     #[inline]
     pub const fn set_signal_0(self, val: PwmSignal0) -> Self {
-        Self((self.0 & !Self::SIGNAL_0_SELECT) | (val as u32))
+        Self(pwm_config::Signal0Select::from(self.0).set(val as usize))
     }
     /// Get source for signal group 0.
     #[inline]
     pub const fn signal_0(self) -> PwmSignal0 {
-        match self.0 & Self::SIGNAL_0_SELECT {
+        match pwm_config::Signal0Select::from(self.0).get() {
             0 => PwmSignal0::SingleEnd,
             1 => PwmSignal0::DifferentialEnd,
             _ => unreachable!(),
@@ -307,12 +367,12 @@ impl PwmConfig {
     /// Set source for signal group 1.
     #[inline]
     pub const fn set_signal_1(self, val: PwmSignal1) -> Self {
-        Self((self.0 & !Self::SIGNAL_1_SELECT) | ((val as u32) << 1))
+        Self(pwm_config::Signal1Select::from(self.0).set(val as usize))
     }
     /// Get source for signal group 1.
     #[inline]
     pub const fn signal_1(self) -> PwmSignal1 {
-        match (self.0 & Self::SIGNAL_1_SELECT) >> 1 {
+        match pwm_config::Signal1Select::from(self.0).get() {
             0 => PwmSignal1::SingleEnd,
             1 => PwmSignal1::BrushlessDcMotor,
             _ => unreachable!(),
@@ -345,22 +405,26 @@ pub enum PwmSignal1 {
 #[repr(transparent)]
 pub struct ParamConfig(u32);
 
-impl ParamConfig {
-    const SPI_0_MASTER_MODE: u32 = 0x1 << 12;
-    const SPI_1_MASTER_MODE: u32 = 0x1 << 27;
+mod param_config {
+    use crate::BitField;
 
+    pub(crate) type Spi0MasterMode = BitField<1, 12, u32>;
+    pub(crate) type Spi1MasterMode = BitField<1, 27, u32>;
+}
+
+impl ParamConfig {
     /// Set mode for Serial Peripheral Interface.
     #[inline]
     pub const fn set_spi_mode<const I: usize>(self, mode: SpiMode) -> Self {
         match mode {
             SpiMode::Master => match I {
-                0 => Self(self.0 | Self::SPI_0_MASTER_MODE),
-                1 => Self(self.0 | Self::SPI_1_MASTER_MODE),
+                0 => Self(param_config::Spi0MasterMode::from(self.0).enable()),
+                1 => Self(param_config::Spi1MasterMode::from(self.0).enable()),
                 _ => unreachable!(),
             },
             SpiMode::Slave => match I {
-                0 => Self(self.0 & !Self::SPI_0_MASTER_MODE),
-                1 => Self(self.0 & !Self::SPI_1_MASTER_MODE),
+                0 => Self(param_config::Spi0MasterMode::from(self.0).disable()),
+                1 => Self(param_config::Spi1MasterMode::from(self.0).disable()),
                 _ => unreachable!(),
             },
         }
@@ -370,14 +434,14 @@ impl ParamConfig {
     pub const fn spi_mode<const I: usize>(self) -> SpiMode {
         match I {
             0 => {
-                if self.0 & Self::SPI_0_MASTER_MODE != 0 {
+                if param_config::Spi0MasterMode::from(self.0).is_enabled() {
                     SpiMode::Master
                 } else {
                     SpiMode::Slave
                 }
             }
             1 => {
-                if self.0 & Self::SPI_1_MASTER_MODE != 0 {
+                if param_config::Spi1MasterMode::from(self.0).is_enabled() {
                     SpiMode::Master
                 } else {
                     SpiMode::Slave
@@ -393,46 +457,38 @@ impl ParamConfig {
 #[repr(transparent)]
 pub struct SdhConfig(u16);
 
-impl SdhConfig {
-    const SDH_CLK_EN: u16 = 0x1 << 13;
-    const SDH_CLK_SEL: u16 = 0x1 << 12;
-    const SDH_CLK_DIV_LEN: u16 = 0x7 << 9;
+mod sdh_config {
+    use crate::BitField;
 
-    /// Enable SDH clock.
-    #[inline]
-    pub const fn enable_sdh_clk(self) -> Self {
-        Self((self.0 & !Self::SDH_CLK_EN) | (Self::SDH_CLK_EN & (1 << 13)))
-    }
-    /// Disable SDH clock.
-    #[inline]
-    pub const fn disable_sdh_clk(self) -> Self {
-        Self((self.0 & !Self::SDH_CLK_EN) | (Self::SDH_CLK_EN & (0 << 13)))
-    }
-    // If SDH clock is enabled.
-    #[inline]
-    pub const fn is_sdh_clk_enabled(self) -> bool {
-        (self.0 & Self::SDH_CLK_EN) >> 13 == 1
-    }
-    /// Set SDH clock select.
-    #[inline]
-    pub const fn set_sdh_clk_sel(self, val: u8) -> Self {
-        Self((self.0 & !Self::SDH_CLK_SEL) | (Self::SDH_CLK_SEL & ((val as u16) << 12)))
-    }
-    /// Get SDH clock select.
-    #[inline]
-    pub const fn sdh_clk_sel(self) -> u8 {
-        ((self.0 & Self::SDH_CLK_SEL) >> 12) as u8
-    }
-    /// Set SDH clock divider length.
-    #[inline]
-    pub const fn set_sdh_clk_div_len(self, val: u8) -> Self {
-        Self((self.0 & !Self::SDH_CLK_DIV_LEN) | (Self::SDH_CLK_DIV_LEN & ((val as u16) << 9)))
-    }
-    /// Get SDH clock divider length.
-    #[inline]
-    pub const fn sdh_clk_div_len(self) -> u8 {
-        ((self.0 & Self::SDH_CLK_DIV_LEN) >> 9) as u8
-    }
+    pub(crate) type SdhClkDivLen = BitField<3, 9, u16>;
+    pub(crate) type SdhClkSel = BitField<1, 12, u16>;
+    pub(crate) type SdhClkEn = BitField<1, 13, u16>;
+}
+
+impl SdhConfig {
+    register_flag! {
+        /// Enable SDH clock.
+        enable_sdh_clk,
+        /// Disable SDH clock.
+        disable_sdh_clk,
+        /// Check if SDH clock is enabled.
+        is_sdh_clk_enabled,
+        sdh_config::SdhClkEn
+    }
+    register_field!(
+        set_sdh_clk_sel,
+        sdh_clk_sel,
+        u8,
+        sdh_config::SdhClkSel,
+        "SDH clock select"
+    );
+    register_field!(
+        set_sdh_clk_div_len,
+        sdh_clk_div_len,
+        u8,
+        sdh_config::SdhClkDivLen,
+        "SDH clock divider length"
+    );
 }
 
 /// Mode for Serial Peripheral Interface Bus.
@@ -448,27 +504,25 @@ pub enum SpiMode {
 #[repr(transparent)]
 pub struct ClockConfig0(u32);
 
-impl ClockConfig0 {
-    // const CCI: u32 = 0x1 << 4;
-    const DMA: u32 = 0x1 << 3;
-    // const SEC: u32 = 0x1 << 2;
-    // const SDU: u32 = 0x1 << 1;
-    // const CPU: u32 = 0x1;
+mod clock_config_0 {
+    use crate::BitField;
 
-    /// Enable clock gate for Direct Memory Access controller.
-    #[inline]
-    pub const fn enable_dma(self) -> Self {
-        Self(self.0 | Self::DMA)
-    }
-    /// Disable clock gate for Direct Memory Access controller.
-    #[inline]
-    pub const fn disable_dma(self) -> Self {
-        Self(self.0 & !Self::DMA)
-    }
-    /// Check if clock gate for Direct Memory Access controller is enabled.
-    #[inline]
-    pub const fn is_dma_enabled(self) -> bool {
-        (self.0 & Self::DMA) != 0
+    // pub(crate) type Cci = BitField<1, 4, u32>;
+    pub(crate) type Dma = BitField<1, 3, u32>;
+    // pub(crate) type Sec = BitField<1, 2, u32>;
+    // pub(crate) type Sdu = BitField<1, 1, u32>;
+    // pub(crate) type Cpu = BitField<1, 0, u32>;
+}
+
+impl ClockConfig0 {
+    register_flag! {
+        /// Enable clock gate for Direct Memory Access controller.
+        enable_dma,
+        /// Disable clock gate for Direct Memory Access controller.
+        disable_dma,
+        /// Check if clock gate for Direct Memory Access controller is enabled.
+        is_dma_enabled,
+        clock_config_0::Dma
     }
     // TODO: implment left fields.
 }
@@ -478,23 +532,28 @@ impl ClockConfig0 {
 #[repr(transparent)]
 pub struct ClockConfig1(u32);
 
-impl ClockConfig1 {
-    const DMA0: u32 = 0x1 << 12;
-    const UART0: u32 = 0x1 << 16;
-    const UART1: u32 = 0x1 << 17;
-    const I2C: u32 = 0x1 << 19;
-    const PWM: u32 = 0x1 << 20;
-    const DMA2: u32 = 0x1 << 24;
-    const UART2: u32 = 0x1 << 26;
-    const LZ4D: u32 = 0x1 << 29;
+mod clock_config_1 {
+    use crate::BitField;
+
+    pub(crate) type Dma0 = BitField<1, 12, u32>;
+    pub(crate) type Uart0 = BitField<1, 16, u32>;
+    pub(crate) type Uart1 = BitField<1, 17, u32>;
+    pub(crate) type I2c = BitField<1, 19, u32>;
+    pub(crate) type Pwm = BitField<1, 20, u32>;
+    pub(crate) type Dma2 = BitField<1, 24, u32>;
+    pub(crate) type Uart2 = BitField<1, 26, u32>;
+    pub(crate) type I2s = BitField<1, 27, u32>;
+    pub(crate) type Lz4d = BitField<1, 29, u32>;
+}
 
+impl ClockConfig1 {
     /// Enable clock gate for Direct Memory Access controller.
     #[inline]
     pub const fn enable_dma<const I: usize>(self) -> Self {
         match I {
-            0 => Self(self.0 | Self::DMA0),
+            0 => Self(clock_config_1::Dma0::from(self.0).enable()),
             1 => self,
-            2 => Self(self.0 | Self::DMA2),
+            2 => Self(clock_config_1::Dma2::from(self.0).enable()),
             _ => unreachable!(),
         }
     }
@@ -502,9 +561,9 @@ impl ClockConfig1 {
     #[inline]
     pub const fn disable_dma<const I: usize>(self) -> Self {
         match I {
-            0 => Self(self.0 & !Self::DMA0),
+            0 => Self(clock_config_1::Dma0::from(self.0).disable()),
             1 => self,
-            2 => Self(self.0 & !Self::DMA2),
+            2 => Self(clock_config_1::Dma2::from(self.0).disable()),
             _ => unreachable!(),
         }
     }
@@ -512,89 +571,346 @@ impl ClockConfig1 {
     #[inline]
     pub const fn is_dma_enabled<const I: usize>(self) -> bool {
         match I {
-            0 => (self.0 & Self::DMA0) != 0,
+            0 => clock_config_1::Dma0::from(self.0).is_enabled(),
             1 => true,
-            2 => (self.0 & Self::DMA2) != 0,
+            2 => clock_config_1::Dma2::from(self.0).is_enabled(),
             _ => unreachable!(),
         }
     }
     /// Enable clock gate for Universal Asynchronous Receiver/Transmitter peripheral.
     #[inline]
     pub const fn enable_uart<const I: usize>(self) -> Self {
-        let val = match I {
-            0 => Self::UART0,
-            1 => Self::UART1,
-            2 => Self::UART2,
+        match I {
+            0 => Self(clock_config_1::Uart0::from(self.0).enable()),
+            1 => Self(clock_config_1::Uart1::from(self.0).enable()),
+            2 => Self(clock_config_1::Uart2::from(self.0).enable()),
             _ => unreachable!(),
-        };
-        Self(self.0 | val)
+        }
     }
     /// Disable clock gate for Universal Asynchronous Receiver/Transmitter peripheral.
     #[inline]
     pub const fn disable_uart<const I: usize>(self) -> Self {
-        let val = match I {
-            0 => Self::UART0,
-            1 => Self::UART1,
-            2 => Self::UART2,
+        match I {
+            0 => Self(clock_config_1::Uart0::from(self.0).disable()),
+            1 => Self(clock_config_1::Uart1::from(self.0).disable()),
+            2 => Self(clock_config_1::Uart2::from(self.0).disable()),
             _ => unreachable!(),
-        };
-        Self(self.0 & !val)
+        }
     }
     /// Check if clock gate for Universal Asynchronous Receiver/Transmitter is enabled.
     #[inline]
     pub const fn is_uart_enabled<const I: usize>(self) -> bool {
-        let val = match I {
-            0 => Self::UART0,
-            1 => Self::UART1,
-            2 => Self::UART2,
+        match I {
+            0 => clock_config_1::Uart0::from(self.0).is_enabled(),
+            1 => clock_config_1::Uart1::from(self.0).is_enabled(),
+            2 => clock_config_1::Uart2::from(self.0).is_enabled(),
             _ => unreachable!(),
-        };
-        self.0 & val != 0
+        }
     }
-    /// Enable clock gate for Inter-Integrated Circuit peripheral.
-    #[inline]
-    pub const fn enable_i2c(self) -> Self {
-        Self(self.0 | Self::I2C)
+    register_flag! {
+        /// Enable clock gate for Inter-Integrated Circuit peripheral.
+        enable_i2c,
+        /// Disable clock gate for Inter-Integrated Circuit peripheral.
+        disable_i2c,
+        /// Check if clock gate for Inter-Integrated Circuit is enabled.
+        is_i2c_enabled,
+        clock_config_1::I2c
+    }
+    register_flag! {
+        /// Enable clock gate for Pulse Width Modulation peripheral.
+        enable_pwm,
+        /// Disable clock gate for Pulse Width Modulation peripheral.
+        disable_pwm,
+        /// Check if clock gate for Pulse Width Modulation is enabled.
+        is_pwm_enabled,
+        clock_config_1::Pwm
+    }
+    register_flag!(
+        enable_i2s,
+        disable_i2s,
+        is_i2s_enabled,
+        clock_config_1::I2s,
+        "clock gate for Inter-IC Sound peripheral"
+    );
+    register_flag! {
+        /// Enable clock gate for hardware LZ4 decompressor peripheral.
+        enable_lz4d,
+        /// Disable clock gate for hardware LZ4 decompressor peripheral.
+        disable_lz4d,
+        /// Check if clock gate for hardware LZ4 decompressor is enabled.
+        is_lz4d_enabled,
+        clock_config_1::Lz4d
     }
-    /// Disable clock gate for Inter-Integrated Circuit peripheral.
+}
+
+/// Chip clock output configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct ClockOutConfig(u32);
+
+// Fields of clock output `idx` are shifted by `idx * 2` and `idx` respectively.
+mod clock_out_config {
+    use crate::BitField;
+
+    pub(crate) type Source = BitField<2, 8, u32>;
+    pub(crate) type Enable = BitField<1, 16, u32>;
+}
+
+impl ClockOutConfig {
+    /// Set source of clock output `idx` from raw selection bits.
     #[inline]
-    pub const fn disable_i2c(self) -> Self {
-        Self(self.0 & !Self::I2C)
+    pub const fn set_source(self, idx: usize, val: u8) -> Self {
+        let field = clock_out_config::Source::from(self.0 >> (idx * 2));
+        let mask = (field.get_mask() as u32) << (idx * 2);
+        Self((self.0 & !mask) | ((field.set(val as usize) << (idx * 2)) & mask))
     }
-    /// Check if clock gate for Inter-Integrated Circuit is enabled.
+    /// Get raw selection bits of clock output `idx`.
     #[inline]
-    pub const fn is_i2c_enabled(self) -> bool {
-        self.0 & Self::I2C != 0
+    pub const fn source(self, idx: usize) -> u8 {
+        clock_out_config::Source::from(self.0 >> (idx * 2)).get() as u8
     }
-    /// Enable clock gate for Pulse Width Modulation peripheral.
+    /// Enable clock output `idx`.
     #[inline]
-    pub const fn enable_pwm(self) -> Self {
-        Self(self.0 | Self::PWM)
+    pub const fn enable_output(self, idx: usize) -> Self {
+        let field = clock_out_config::Enable::from(0);
+        Self(self.0 | (field.enable() << idx))
     }
-    /// Disable clock gate for Pulse Width Modulation peripheral.
+    /// Disable clock output `idx`.
     #[inline]
-    pub const fn disable_pwm(self) -> Self {
-        Self(self.0 & !Self::PWM)
+    pub const fn disable_output(self, idx: usize) -> Self {
+        let field = clock_out_config::Enable::from(0);
+        Self(self.0 & !(field.enable() << idx))
     }
-    /// Check if clock gate for Pulse Width Modulation is enabled.
+    /// Check if clock output `idx` is enabled.
     #[inline]
-    pub const fn is_pwm_enabled(self) -> bool {
-        self.0 & Self::PWM != 0
+    pub const fn is_output_enabled(self, idx: usize) -> bool {
+        clock_out_config::Enable::from(self.0 >> idx).is_enabled()
     }
-    /// Enable clock gate for hardware LZ4 decompressor peripheral.
+}
+
+/// Internal clock routed to a clock output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ClockOutSource {
+    /// Camera reference clock, on all outputs.
+    CameraReference,
+    /// Inter-IC Sound reference clock, on all outputs.
+    I2sReference,
+    /// Audio ADC clock, on outputs 0 and 1.
+    AudioAdc,
+    /// Audio DAC clock, on outputs 0 and 1.
+    AudioDac,
+    /// Crystal oscillator clock, on output 2.
+    Xtal,
+    /// 48-MHz division of Wi-Fi PLL, on output 2.
+    WifiPll48m,
+    /// 32-MHz division of Wi-Fi PLL, on output 3.
+    WifiPll32m,
+    /// 80-MHz division of Wi-Fi PLL, on output 3.
+    WifiPll80m,
+}
+
+impl ClockOutSource {
+    /// Selection bits of this source on clock output `idx`, `None` if not routable.
+    #[inline]
+    pub const fn bits(self, idx: usize) -> Option<u8> {
+        match (self, idx) {
+            (ClockOutSource::CameraReference, _) => Some(0),
+            (ClockOutSource::I2sReference, _) => Some(1),
+            (ClockOutSource::AudioAdc, 0 | 1) => Some(2),
+            (ClockOutSource::AudioDac, 0 | 1) => Some(3),
+            (ClockOutSource::Xtal, 2) => Some(2),
+            (ClockOutSource::WifiPll48m, 2) => Some(3),
+            (ClockOutSource::WifiPll32m, 3) => Some(2),
+            (ClockOutSource::WifiPll80m, 3) => Some(3),
+            _ => None,
+        }
+    }
+}
+
+/// Peripheral with a software reset bit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Peripheral {
+    /// Pseudo static random-access memory controller 0.
+    Psram0 = 17,
+    /// Universal Serial Bus controller.
+    Usb = 19,
+    /// Audio codec.
+    Audio = 21,
+    /// Secure Digital host.
+    Sdh = 22,
+    /// Ethernet media access controller.
+    Emac = 23,
+    /// Direct Memory Access controller 2.
+    Dma2 = 24,
+    /// General purpose analog block.
+    Gpip = 34,
+    /// Security engine.
+    SecEng = 36,
+    /// Electronic fuse controller.
+    Efuse = 39,
+    /// Serial flash controller.
+    Flash = 43,
+    /// Direct Memory Access controller.
+    Dma = 44,
+    /// Universal Asynchronous Receiver/Transmitter 0.
+    Uart0 = 48,
+    /// Universal Asynchronous Receiver/Transmitter 1.
+    Uart1 = 49,
+    /// Serial Peripheral Interface.
+    Spi = 50,
+    /// Inter-Integrated Circuit 0.
+    I2c0 = 51,
+    /// Pulse Width Modulation.
+    Pwm = 52,
+    /// Timer and watchdog.
+    Timer = 53,
+    /// Infrared remote.
+    IrRemote = 54,
+    /// Checksum engine.
+    Checksum = 55,
+    /// Quadrature decoder.
+    Qdec = 56,
+    /// Keyscan.
+    Kys = 57,
+    /// Inter-Integrated Circuit 1.
+    I2c1 = 58,
+    /// Inter-processor communication.
+    Ipc = 59,
+}
+
+impl Peripheral {
+    /// Index of software reset register and bit mask of this peripheral.
     #[inline]
-    pub const fn enable_lz4d(self) -> Self {
-        Self(self.0 | Self::LZ4D)
+    pub const fn reset_bit(self) -> (usize, u32) {
+        let bit = self as u8;
+        ((bit / 32) as usize, 1 << (bit % 32))
     }
-    /// Disable clock gate for hardware LZ4 decompressor peripheral.
+}
+
+/// Processor and chip reset control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct ResetControl(u32);
+
+mod reset_control {
+    use crate::BitField;
+
+    pub(crate) type PowerOn = BitField<1, 0, u32>;
+    pub(crate) type Cpu = BitField<1, 1, u32>;
+    pub(crate) type System = BitField<1, 2, u32>;
+    pub(crate) type LpCpu = BitField<1, 3, u32>;
+}
+
+impl ResetControl {
+    register_flag! {
+        /// Assert power-on reset of the whole chip.
+        enable_power_on_reset,
+        /// Release power-on reset.
+        disable_power_on_reset,
+        /// Check if power-on reset is asserted.
+        is_power_on_reset_enabled,
+        reset_control::PowerOn
+    }
+    /// Assert reset of processor `cpu`.
+    #[inline]
+    pub const fn enable_cpu_reset(self, cpu: Cpu) -> Self {
+        match cpu {
+            Cpu::Mcu => Self(reset_control::Cpu::from(self.0).enable()),
+            Cpu::Lp => Self(reset_control::LpCpu::from(self.0).enable()),
+        }
+    }
+    /// Release reset of processor `cpu`.
     #[inline]
-    pub const fn disable_lz4d(self) -> Self {
-        Self(self.0 & !Self::LZ4D)
+    pub const fn disable_cpu_reset(self, cpu: Cpu) -> Self {
+        match cpu {
+            Cpu::Mcu => Self(reset_control::Cpu::from(self.0).disable()),
+            Cpu::Lp => Self(reset_control::LpCpu::from(self.0).disable()),
+        }
     }
-    /// Check if clock gate for hardware LZ4 decompressor is enabled.
+    /// Check if reset of processor `cpu` is asserted.
     #[inline]
-    pub const fn is_lz4d_enabled(self) -> bool {
-        self.0 & Self::LZ4D != 0
+    pub const fn is_cpu_reset_enabled(self, cpu: Cpu) -> bool {
+        match cpu {
+            Cpu::Mcu => reset_control::Cpu::from(self.0).is_enabled(),
+            Cpu::Lp => reset_control::LpCpu::from(self.0).is_enabled(),
+        }
+    }
+    register_flag! {
+        /// Assert system reset, resetting processors and peripherals.
+        enable_system_reset,
+        /// Release system reset.
+        disable_system_reset,
+        /// Check if system reset is asserted.
+        is_system_reset_enabled,
+        reset_control::System
+    }
+}
+
+/// Processor reset by [`reset_cpu`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Cpu {
+    /// Microcontroller core (M0).
+    Mcu,
+    /// Low power core (LP).
+    Lp,
+}
+
+/// Pulse software reset of a peripheral.
+///
+/// Registers of the peripheral return to reset values; drivers using it must be
+/// created again afterwards.
+#[inline]
+pub fn reset_peripheral(glb: &RegisterBlock, peripheral: Peripheral) {
+    let (idx, bit) = peripheral.reset_bit();
+    let reg = &glb.software_reset[idx];
+    super::modify_shared(reg, |val| val & !bit);
+    super::modify_shared(reg, |val| val | bit);
+    settle();
+    super::modify_shared(reg, |val| val & !bit);
+}
+
+/// Pulse reset of processor `cpu`.
+///
+/// Resetting a coprocessor restarts it from its boot address; resetting the processor
+/// this code runs on does not return.
+#[inline]
+pub fn reset_cpu(glb: &RegisterBlock, cpu: Cpu) {
+    super::modify_shared(&glb.reset_control, |val| val.disable_cpu_reset(cpu));
+    super::modify_shared(&glb.reset_control, |val| val.enable_cpu_reset(cpu));
+    settle();
+    super::modify_shared(&glb.reset_control, |val| val.disable_cpu_reset(cpu));
+}
+
+/// Reset processors and peripherals, restarting the chip from boot ROM.
+///
+/// Always-on and hibernate domains keep their state, so [`reset_reason`] on next boot
+/// reports a software reset.
+///
+/// [`reset_reason`]: crate::hbn::reset_reason
+#[inline]
+pub fn system_reset(glb: &RegisterBlock) -> ! {
+    // Reset bits act on rising edge, release all of them before asserting one.
+    let val = glb
+        .reset_control
+        .read()
+        .disable_power_on_reset()
+        .disable_cpu_reset(Cpu::Mcu)
+        .disable_system_reset();
+    unsafe {
+        glb.reset_control.write(val);
+        glb.reset_control.write(val.enable_system_reset());
+    }
+    loop {
+        core::hint::spin_loop();
+    }
+}
+
+// Hold reset for a few bus cycles.
+#[inline]
+fn settle() {
+    for _ in 0..8 {
+        core::hint::spin_loop();
     }
 }
 
@@ -621,117 +937,89 @@ impl ClockConfig3 {
 #[repr(transparent)]
 pub struct GpioConfig(u32);
 
+mod gpio_config {
+    use crate::BitField;
+
+    pub(crate) type InputEnable = BitField<1, 0, u32>;
+    pub(crate) type Schmitt = BitField<1, 1, u32>;
+    pub(crate) type Drive = BitField<2, 2, u32>;
+    pub(crate) type Pull = BitField<2, 4, u32>;
+    pub(crate) type OutputEnable = BitField<1, 6, u32>;
+    pub(crate) type Function = BitField<5, 8, u32>;
+    pub(crate) type InterruptMode = BitField<4, 16, u32>;
+    pub(crate) type ClearInterrupt = BitField<1, 20, u32>;
+    pub(crate) type HasInterrupt = BitField<1, 21, u32>;
+    pub(crate) type InterruptMask = BitField<1, 22, u32>;
+    pub(crate) type Output = BitField<1, 24, u32>;
+    pub(crate) type SetOutput = BitField<1, 25, u32>;
+    pub(crate) type ClearOutput = BitField<1, 26, u32>;
+    pub(crate) type Input = BitField<1, 28, u32>;
+    pub(crate) type Mode = BitField<2, 30, u32>;
+}
+
 impl GpioConfig {
-    const INPUT_ENABLE: u32 = 1 << 0;
-    const SCHMITT: u32 = 1 << 1;
-    const DRIVE: u32 = 0x3 << 2;
-    const PULL: u32 = 0x3 << 4;
-    const OUTPUT_ENABLE: u32 = 1 << 6;
-    const FUNCTION: u32 = 0x1f << 8;
-    const INTERRUPT_MODE: u32 = 0xf << 16;
-    const CLEAR_INTERRUPT: u32 = 1 << 20;
-    const HAS_INTERRUPT: u32 = 1 << 21;
-    const INTERRUPT_MASK: u32 = 1 << 22;
-    const OUTPUT: u32 = 1 << 24;
-    const SET: u32 = 1 << 25;
-    const CLEAR: u32 = 1 << 26;
-    const INPUT: u32 = 1 << 28;
-    const MODE: u32 = 0x3 << 30;
-
-    /// Enable input function of current pin.
-    #[inline]
-    pub const fn enable_input(self) -> Self {
-        Self(self.0 | Self::INPUT_ENABLE)
-    }
-    /// Disable input function of current pin.
-    #[inline]
-    pub const fn disable_input(self) -> Self {
-        Self(self.0 & !Self::INPUT_ENABLE)
-    }
-    /// Check if input function of current pin is enabled.
-    #[inline]
-    pub const fn is_input_enabled(self) -> bool {
-        self.0 & Self::INPUT_ENABLE != 0
-    }
-    /// Enable Schmitt trigger function of current pin.
-    #[inline]
-    pub const fn enable_schmitt(self) -> Self {
-        Self(self.0 | Self::SCHMITT)
-    }
-    /// Disable Schmitt trigger function of current pin.
-    #[inline]
-    pub const fn disable_schmitt(self) -> Self {
-        Self(self.0 & !Self::SCHMITT)
-    }
-    /// Check if Schmitt trigger function of current pin is enabled.
-    #[inline]
-    pub const fn is_schmitt_enabled(self) -> bool {
-        self.0 & Self::SCHMITT != 0
-    }
-    /// Enable output function of current pin.
-    #[inline]
-    pub const fn enable_output(self) -> Self {
-        Self(self.0 | Self::OUTPUT_ENABLE)
-    }
-    /// Disable output function of current pin.
-    #[inline]
-    pub const fn disable_output(self) -> Self {
-        Self(self.0 & !Self::OUTPUT_ENABLE)
-    }
-    /// Check if output function of current pin is enabled.
-    #[inline]
-    pub const fn is_output_enabled(self) -> bool {
-        self.0 & Self::OUTPUT_ENABLE != 0
-    }
-    /// Enable interrupt function of current pin.
-    #[inline]
-    pub const fn mask_interrupt(self) -> Self {
-        Self(self.0 | Self::INTERRUPT_MASK)
-    }
-    /// Disable interrupt function of current pin.
-    #[inline]
-    pub const fn unmask_interrupt(self) -> Self {
-        Self(self.0 & !Self::INTERRUPT_MASK)
-    }
-    /// Check if interrupt function of current pin is enabled.
-    #[inline]
-    pub const fn is_interrupt_masked(self) -> bool {
-        self.0 & Self::INTERRUPT_MASK != 0
-    }
+    register_flag!(
+        enable_input,
+        disable_input,
+        is_input_enabled,
+        gpio_config::InputEnable,
+        "input function of current pin"
+    );
+    register_flag!(
+        enable_schmitt,
+        disable_schmitt,
+        is_schmitt_enabled,
+        gpio_config::Schmitt,
+        "Schmitt trigger function of current pin"
+    );
+    register_flag!(
+        enable_output,
+        disable_output,
+        is_output_enabled,
+        gpio_config::OutputEnable,
+        "output function of current pin"
+    );
+    register_flag!(
+        mask_interrupt,
+        unmask_interrupt,
+        is_interrupt_masked,
+        gpio_config::InterruptMask,
+        "interrupt function of current pin"
+    );
     /// Get output of current pin.
     #[inline]
     pub const fn output(self) -> bool {
-        self.0 & Self::OUTPUT != 0
+        gpio_config::Output::from(self.0).is_enabled()
     }
     /// Get intput of current pin.
     #[inline]
     pub const fn input(self) -> bool {
-        self.0 & Self::INPUT != 0
+        gpio_config::Input::from(self.0).is_enabled()
     }
     /// Check if current pin has interrupt function.
     #[inline]
     pub const fn has_interrupt(self) -> bool {
-        self.0 & Self::HAS_INTERRUPT != 0
+        gpio_config::HasInterrupt::from(self.0).is_enabled()
     }
     /// Set pin output value to high.
     #[inline]
     pub const fn set(self) -> Self {
-        Self(self.0 | Self::SET)
+        Self(gpio_config::SetOutput::from(self.0).enable())
     }
     /// Clear pin output value to low.
     #[inline]
     pub const fn clear(self) -> Self {
-        Self(self.0 | Self::CLEAR)
+        Self(gpio_config::ClearOutput::from(self.0).enable())
     }
     /// Clear interrupt pin output flag.
     #[inline]
     pub const fn clear_interrupt(self) -> Self {
-        Self(self.0 | Self::CLEAR_INTERRUPT)
+        Self(gpio_config::ClearInterrupt::from(self.0).enable())
     }
     /// Get drive strength of current pin.
     #[inline]
     pub const fn drive(self) -> Drive {
-        match (self.0 & Self::DRIVE) >> 2 {
+        match gpio_config::Drive::from(self.0).get() {
             0 => Drive::Drive0,
             1 => Drive::Drive1,
             2 => Drive::Drive2,
@@ -742,12 +1030,12 @@ impl GpioConfig {
     /// Set drive strength of current pin.
     #[inline]
     pub const fn set_drive(self, val: Drive) -> Self {
-        Self((self.0 & !Self::DRIVE) | ((val as u32) << 2))
+        Self(gpio_config::Drive::from(self.0).set(val as usize))
     }
     /// Get function of current pin.
     #[inline]
     pub const fn function(self) -> Function {
-        match (self.0 & Self::FUNCTION) >> 8 {
+        match gpio_config::Function::from(self.0).get() {
             0 => Function::Sdh,
             1 => Function::Spi0,
             2 => Function::Flash,
@@ -779,11 +1067,11 @@ impl GpioConfig {
     /// Set function of current pin.
     #[inline]
     pub const fn set_function(self, val: Function) -> Self {
-        Self((self.0 & !Self::FUNCTION) | ((val as u32) << 8))
+        Self(gpio_config::Function::from(self.0).set(val as usize))
     }
     /// Get interrupt mode of current pin.
     pub const fn interrupt_mode(self) -> InterruptMode {
-        match (self.0 & Self::INTERRUPT_MODE) >> 16 {
+        match gpio_config::InterruptMode::from(self.0).get() {
             0 => InterruptMode::SyncFallingEdge,
             1 => InterruptMode::SyncRisingEdge,
             2 => InterruptMode::SyncLowLevel,
@@ -799,11 +1087,11 @@ impl GpioConfig {
     /// Set interrupt mode of current pin.
     #[inline]
     pub const fn set_interrupt_mode(self, val: InterruptMode) -> Self {
-        Self((self.0 & !Self::INTERRUPT_MODE) | ((val as u32) << 16))
+        Self(gpio_config::InterruptMode::from(self.0).set(val as usize))
     }
     /// Get mode of current pin.
     pub const fn mode(self) -> Mode {
-        match (self.0 & Self::MODE) >> 30 {
+        match gpio_config::Mode::from(self.0).get() {
             0 => Mode::Normal,
             1 => Mode::SetClear,
             2 => Mode::Programmable,
@@ -814,11 +1102,11 @@ impl GpioConfig {
     /// Set mode of current pin.
     #[inline]
     pub const fn set_mode(self, val: Mode) -> Self {
-        Self((self.0 & !Self::MODE) | ((val as u32) << 30))
+        Self(gpio_config::Mode::from(self.0).set(val as usize))
     }
     /// Get pull direction of current pin.
     pub const fn pull(self) -> Pull {
-        match (self.0 & Self::PULL) >> 4 {
+        match gpio_config::Pull::from(self.0).get() {
             0 => Pull::None,
             1 => Pull::Up,
             2 => Pull::Down,
@@ -828,7 +1116,7 @@ impl GpioConfig {
     /// Set pull direction of current pin.
     #[inline]
     pub const fn set_pull(self, val: Pull) -> Self {
-        Self((self.0 & !Self::PULL) | ((val as u32) << 4))
+        Self(gpio_config::Pull::from(self.0).set(val as usize))
     }
     /// Reset value of GPIO_CONFIG register.
     #[allow(unused)]
@@ -896,35 +1184,30 @@ pub enum Mode {
 #[repr(transparent)]
 pub struct Ldo12uhsConfig(u32);
 
-impl Ldo12uhsConfig {
-    const POWER: u32 = 0x1 << 0;
-    const VOUT_SEL: u32 = 0xf << 20;
+mod ldo12uhs_config {
+    use crate::BitField;
 
-    /// Power up LDO12UHS.
-    #[inline]
-    pub const fn power_up(self) -> Self {
-        Self(self.0 | Self::POWER)
-    }
-    /// Power down LDO12UHS.
-    #[inline]
-    pub const fn power_down(self) -> Self {
-        Self(self.0 & !Self::POWER)
-    }
-    /// Check if LDO12UHS is powered up.
-    #[inline]
-    pub const fn is_powered_up(self) -> bool {
-        self.0 & Self::POWER != 0
-    }
-    /// Set output voltage of LDO12UHS.
-    #[inline]
-    pub const fn set_output_voltage(self, val: u8) -> Self {
-        Self((self.0 & !Self::VOUT_SEL) | ((val as u32) << 20))
-    }
-    /// Get output voltage of LDO12UHS.
-    #[inline]
-    pub const fn get_output_voltage(self) -> u8 {
-        ((self.0 & Self::VOUT_SEL) >> 20) as u8
-    }
+    pub(crate) type Power = BitField<1, 0, u32>;
+    pub(crate) type VoutSel = BitField<4, 20, u32>;
+}
+
+impl Ldo12uhsConfig {
+    register_flag! {
+        /// Power up LDO12UHS.
+        power_up,
+        /// Power down LDO12UHS.
+        power_down,
+        /// Check if LDO12UHS is powered up.
+        is_powered_up,
+        ldo12uhs_config::Power
+    }
+    register_field!(
+        set_output_voltage,
+        get_output_voltage,
+        u8,
+        ldo12uhs_config::VoutSel,
+        "output voltage of LDO12UHS"
+    );
 }
 
 #[cfg(test)]
@@ -933,21 +1216,55 @@ mod tests {
     use crate::glb::v2::SpiMode;
 
     use super::{
-        ClockConfig1, Drive, Function, GpioConfig, I2cClockSource, I2cConfig, InterruptMode, Mode,
-        ParamConfig, Pull, PwmConfig, PwmSignal0, PwmSignal1, RegisterBlock, SdhConfig, SpiConfig,
+        ClockConfig1, ClockOutConfig, ClockOutSource, Cpu, Drive, Function, GpioConfig,
+        I2cClockSource, I2cConfig, I2sConfig, InterruptMode, Mode, ParamConfig, Peripheral, Pull,
+        PwmConfig, PwmSignal0, PwmSignal1, RegisterBlock, ResetControl, SdhConfig, SpiConfig,
         UartConfig, UartMuxGroup, UartSignal,
     };
     use core::mem::offset_of;
 
+    #[test]
+    fn struct_reset_control_functions() {
+        let mut val = ResetControl(0x0);
+        val = val.enable_power_on_reset();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_power_on_reset_enabled());
+        val = val.disable_power_on_reset().enable_cpu_reset(Cpu::Mcu);
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_cpu_reset_enabled(Cpu::Mcu));
+        assert!(!val.is_cpu_reset_enabled(Cpu::Lp));
+        val = val.enable_cpu_reset(Cpu::Lp);
+        assert_eq!(val.0, 0x0000_000a);
+        val = val.disable_cpu_reset(Cpu::Mcu).disable_cpu_reset(Cpu::Lp);
+        assert_eq!(val.0, 0x0000_0000);
+        val = val.enable_system_reset();
+        assert_eq!(val.0, 0x0000_0004);
+        assert!(val.is_system_reset_enabled());
+        val = val.disable_system_reset();
+        assert!(!val.is_system_reset_enabled());
+    }
+
+    #[test]
+    fn struct_peripheral_reset_bit() {
+        assert_eq!(Peripheral::Psram0.reset_bit(), (0, 1 << 17));
+        assert_eq!(Peripheral::Gpip.reset_bit(), (1, 1 << 2));
+        assert_eq!(Peripheral::Uart0.reset_bit(), (1, 1 << 16));
+        assert_eq!(Peripheral::Ipc.reset_bit(), (1, 1 << 27));
+    }
+
     #[test]
     fn struct_register_block_offset() {
         assert_eq!(offset_of!(RegisterBlock, uart_config), 0x150);
         assert_eq!(offset_of!(RegisterBlock, uart_mux_group), 0x154);
         assert_eq!(offset_of!(RegisterBlock, i2c_config), 0x180);
+        assert_eq!(offset_of!(RegisterBlock, i2s_config), 0x190);
         assert_eq!(offset_of!(RegisterBlock, spi_config), 0x1b0);
         assert_eq!(offset_of!(RegisterBlock, pwm_config), 0x1d0);
+        assert_eq!(offset_of!(RegisterBlock, clock_out_config), 0x428);
         assert_eq!(offset_of!(RegisterBlock, sdh_config), 0x430);
         assert_eq!(offset_of!(RegisterBlock, param_config), 0x510);
+        assert_eq!(offset_of!(RegisterBlock, software_reset), 0x540);
+        assert_eq!(offset_of!(RegisterBlock, reset_control), 0x548);
         assert_eq!(offset_of!(RegisterBlock, clock_config_0), 0x580);
         assert_eq!(offset_of!(RegisterBlock, clock_config_1), 0x584);
         assert_eq!(offset_of!(RegisterBlock, clock_config_2), 0x588);
@@ -1104,6 +1421,38 @@ mod tests {
         val = val.set_signal(2, UartSignal::Txd2);
         assert_eq!(val.0, 0xFF | (UartSignal::Txd2 as u32) << 8);
         assert_eq!(val.signal(2), UartSignal::Txd2);
+
+        val = UartMuxGroup(0xF0);
+        assert_eq!(val.try_signal(0), Some(UartSignal::Rts0));
+        assert_eq!(val.try_signal(1), None);
+    }
+
+    #[test]
+    fn struct_i2s_config_functions() {
+        let mut config = I2sConfig(0x0);
+
+        config = config.set_reference_clock_divide(0x3f);
+        assert_eq!(config.0, 0x0000003f);
+        assert_eq!(config.reference_clock_divide(), 0x3f);
+
+        config = I2sConfig(0x0).enable_data_in_reference_clock();
+        assert_eq!(config.0, 0x00000040);
+        assert!(config.is_data_in_reference_clock_enabled());
+        config = config.disable_data_in_reference_clock();
+        assert!(!config.is_data_in_reference_clock_enabled());
+
+        config = config.enable_reference_clock();
+        assert_eq!(config.0, 0x00000080);
+        assert!(config.is_reference_clock_enabled());
+        config = config.disable_reference_clock();
+        assert!(!config.is_reference_clock_enabled());
+
+        config = config.enable_data_out_reference_clock();
+        assert_eq!(config.0, 0x00000100);
+        assert!(config.is_data_out_reference_clock_enabled());
+        config = config.disable_data_out_reference_clock();
+        assert_eq!(config.0, 0x00000000);
+        assert!(!config.is_data_out_reference_clock_enabled());
     }
 
     #[test]
@@ -1252,6 +1601,31 @@ mod tests {
         assert_eq!(val.0, 0x0E00);
     }
 
+    #[test]
+    fn struct_clock_out_config_functions() {
+        let mut val = ClockOutConfig(0x0);
+        val = val.set_source(0, 0x3);
+        assert_eq!(val.0, 0x0000_0300);
+        assert_eq!(val.source(0), 0x3);
+        val = val.set_source(3, 0x2);
+        assert_eq!(val.0, 0x0000_8300);
+        assert_eq!(val.source(3), 0x2);
+        val = val.set_source(0, 0x0);
+        val = val.enable_output(3);
+        assert_eq!(val.0, 0x0008_8000);
+        assert!(val.is_output_enabled(3));
+        assert!(!val.is_output_enabled(0));
+        val = val.disable_output(3);
+        assert_eq!(val.0, 0x0000_8000);
+
+        assert_eq!(ClockOutSource::I2sReference.bits(3), Some(1));
+        assert_eq!(ClockOutSource::AudioDac.bits(1), Some(3));
+        assert_eq!(ClockOutSource::AudioDac.bits(2), None);
+        assert_eq!(ClockOutSource::Xtal.bits(2), Some(2));
+        assert_eq!(ClockOutSource::Xtal.bits(0), None);
+        assert_eq!(ClockOutSource::WifiPll80m.bits(3), Some(3));
+    }
+
     #[test]
     fn struct_clock_config1_functions() {
         let mut config = ClockConfig1(0x0);
@@ -1296,6 +1670,14 @@ mod tests {
         assert_eq!(config.0, 0x000000);
         assert!(!config.is_pwm_enabled());
 
+        config = config.enable_i2s();
+        assert_eq!(config.0, 0x08000000);
+        assert!(config.is_i2s_enabled());
+
+        config = config.disable_i2s();
+        assert_eq!(config.0, 0x00000000);
+        assert!(!config.is_i2s_enabled());
+
         config = config.enable_lz4d();
         assert_eq!(config.0, 0x20000000);
         assert!(config.is_lz4d_enabled());
@@ -18,6 +18,13 @@
 //! alternate mode types, `Pad` structure would now match the demand of creating new
 //! peripheral structures, or include specific functions for developers to use.
 //!
+//! Alternate mode types, such as [`Uart`] or [`Spi`], are type states implementing the
+//! [`PinMode`] trait; there is one for each pad function of the GLB multiplexer. Mode
+//! conversion traits like [`IntoPad`], and traits peripherals use to check pads like
+//! [`HasUartSignal`](crate::uart::HasUartSignal) or [`uart::Pads`](crate::uart::Pads),
+//! are sealed: they are only implemented in this crate, for pad and signal combinations
+//! connected on hardware, so other crates cannot add impls that misconfigure the pads.
+//!
 //! # Examples
 //!
 //! A simple usage of GPIO pin is digital signal output.
@@ -117,7 +124,9 @@
 //! ```
 
 mod alternate;
+mod bus;
 mod convert;
+mod debug;
 mod disabled;
 mod gpio_group;
 mod input;
@@ -125,10 +134,14 @@ mod output;
 mod pad_dummy;
 mod pad_v1;
 mod pad_v2;
+mod port;
 mod typestate;
 
-pub use convert::{IntoPad, IntoPadv2};
+pub use bus::GpioBus;
+pub use convert::{IntoPad, IntoPadv1, IntoPadv2};
+pub use debug::DebugPad;
 pub use gpio_group::Pads;
+pub use port::{InputPort, OutputPort, Port, PortWriter};
 pub use typestate::*;
 pub use {alternate::Alternate, disabled::Disabled, input::Input, output::Output};
 pub use {pad_v1::Padv1, pad_v2::Padv2};
@@ -1,17 +1,23 @@
+#[cfg(feature = "glb-v1")]
+use super::convert::IntoPadv1;
+#[cfg(any(doc, feature = "glb-v2"))]
+use super::convert::IntoPadv2;
+#[cfg(any(doc, feature = "glb-v1", feature = "glb-v2"))]
+use super::typestate;
 use super::{
     convert::IntoPad,
     input::Input,
     output::Output,
     typestate::{Floating, PullDown, PullUp},
 };
-#[cfg(any(doc, feature = "glb-v2"))]
-use super::{convert::IntoPadv2, typestate};
 
 /// GPIO pad with alternate mode.
 pub struct Alternate<'a, const N: usize, M> {
     inner: super::Inner<'a, N, M>,
 }
 
+impl<'a, const N: usize, M> crate::sealed::Sealed for Alternate<'a, N, M> {}
+
 impl<'a, const N: usize, M> IntoPad<'a, N> for Alternate<'a, N, M> {
     #[inline]
     fn into_pull_up_output(self) -> Output<'a, N, PullUp> {
@@ -50,6 +56,10 @@ impl<'a, const N: usize, M> IntoPadv2<'a, N> for Alternate<'a, N, M> {
         self.inner.into_sdh().into()
     }
     #[inline]
+    fn into_i2s(self) -> Alternate<'a, N, typestate::I2s> {
+        self.inner.into_i2s().into()
+    }
+    #[inline]
     fn into_uart(self) -> Alternate<'a, N, typestate::Uart> {
         self.inner.into_uart().into()
     }
@@ -85,6 +95,46 @@ impl<'a, const N: usize, M> IntoPadv2<'a, N> for Alternate<'a, N, M> {
     fn into_jtag_lp(self) -> Alternate<'a, N, typestate::JtagLp> {
         self.inner.into_jtag_lp().into()
     }
+    #[inline]
+    fn into_clock_output(self) -> Alternate<'a, N, typestate::ClockOut> {
+        self.inner.into_clock_output().into()
+    }
+}
+
+#[cfg(feature = "glb-v1")]
+impl<'a, const N: usize, M> IntoPadv1<'a, N> for Alternate<'a, N, M> {
+    #[inline]
+    fn into_uart(self) -> Alternate<'a, N, typestate::Uart> {
+        self.inner.into_uart().into()
+    }
+    #[inline]
+    fn into_pull_up_pwm(self) -> Alternate<'a, N, typestate::Pwm<0>> {
+        self.inner.into_pull_up_pwm().into()
+    }
+    #[inline]
+    fn into_pull_down_pwm(self) -> Alternate<'a, N, typestate::Pwm<0>> {
+        self.inner.into_pull_down_pwm().into()
+    }
+    #[inline]
+    fn into_floating_pwm(self) -> Alternate<'a, N, typestate::Pwm<0>> {
+        self.inner.into_floating_pwm().into()
+    }
+    #[inline]
+    fn into_i2c(self) -> Alternate<'a, N, typestate::I2c<0>> {
+        self.inner.into_i2c().into()
+    }
+    #[inline]
+    fn into_spi(self) -> Alternate<'a, N, typestate::Spi<0>> {
+        self.inner.into_spi().into()
+    }
+    #[inline]
+    fn into_i2s(self) -> Alternate<'a, N, typestate::I2s> {
+        self.inner.into_i2s().into()
+    }
+    #[inline]
+    fn into_clock_output(self) -> Alternate<'a, N, typestate::ClockOut> {
+        self.inner.into_clock_output().into()
+    }
 }
 
 impl<'a, const N: usize, M> From<super::Inner<'a, N, M>> for Alternate<'a, N, M> {
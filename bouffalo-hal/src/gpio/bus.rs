@@ -0,0 +1,69 @@
+use super::port::{InputPort, OutputPort, Port};
+
+/// Parallel bus over `WIDTH` consecutive GPIO pads starting at pad `FIRST`.
+///
+/// Bit `i` of a bus value maps to pad `FIRST + i`. Writes update all bus pads with
+/// one write of the output value register, so data lines change together; this
+/// drives 8080 displays or parallel converters when dedicated peripherals do not fit.
+/// Pads `A` are owned as a tuple of [`Output`](super::Output) pads for writing, or
+/// [`Input`](super::Input) pads for reading, in any order.
+///
+/// # Examples
+///
+/// ```ignore
+/// let data = (
+///     p.gpio.io8.into_floating_output(),
+///     p.gpio.io9.into_floating_output(),
+///     p.gpio.io10.into_floating_output(),
+///     p.gpio.io11.into_floating_output(),
+/// );
+/// let mut bus: GpioBus<_, 8, 4> = GpioBus::new(data);
+/// bus.write(0b1010);
+/// ```
+pub struct GpioBus<A, const FIRST: usize, const WIDTH: usize> {
+    pads: A,
+}
+
+impl<A: Port, const FIRST: usize, const WIDTH: usize> GpioBus<A, FIRST, WIDTH> {
+    const SHIFT: u32 = (FIRST & 0x1F) as u32;
+    const MASK: u32 = (((1u64 << WIDTH) - 1) << Self::SHIFT) as u32;
+
+    /// Create a bus from owned pads.
+    ///
+    /// Pads must be exactly pads `FIRST..FIRST + WIDTH` within one port, or this
+    /// function fails to compile.
+    #[inline]
+    pub fn new(pads: A) -> Self {
+        const {
+            assert!(WIDTH >= 1, "bus must have at least one pad");
+            assert!((FIRST & 0x1F) + WIDTH <= 32, "bus pads must be in one port");
+            assert!(
+                A::SAME_PORT && A::PORT == FIRST >> 5,
+                "bus pads must be in one port"
+            );
+            assert!(A::MASK == Self::MASK, "bus pads must match FIRST and WIDTH");
+        };
+        Self { pads }
+    }
+    /// Release owned pads.
+    #[inline]
+    pub fn free(self) -> A {
+        self.pads
+    }
+}
+
+impl<A: OutputPort, const FIRST: usize, const WIDTH: usize> GpioBus<A, FIRST, WIDTH> {
+    /// Write a value to the bus, bits above `WIDTH` are ignored.
+    #[inline]
+    pub fn write(&mut self, value: u32) {
+        self.pads.__modify_port(Self::MASK, value << Self::SHIFT);
+    }
+}
+
+impl<A: InputPort, const FIRST: usize, const WIDTH: usize> GpioBus<A, FIRST, WIDTH> {
+    /// Read a value from the bus.
+    #[inline]
+    pub fn read(&self) -> u32 {
+        (self.pads.__read_port() & Self::MASK) >> Self::SHIFT
+    }
+}
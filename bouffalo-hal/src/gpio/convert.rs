@@ -4,9 +4,25 @@ use super::{
     output::Output,
     typestate::{self, Floating, PullDown, PullUp},
 };
+use crate::sealed::Sealed;
 
 /// Trait for pad mode conversations.
-pub trait IntoPad<'a, const N: usize> {
+///
+/// This trait is sealed and implemented for GPIO pad structures of this crate.
+///
+/// ```compile_fail
+/// use bouffalo_hal::gpio::{Floating, Input, IntoPad, Output, PullDown, PullUp};
+/// struct MyPad;
+/// impl<'a> IntoPad<'a, 0> for MyPad {
+///     fn into_pull_up_output(self) -> Output<'a, 0, PullUp> { todo!() }
+///     fn into_pull_down_output(self) -> Output<'a, 0, PullDown> { todo!() }
+///     fn into_floating_output(self) -> Output<'a, 0, Floating> { todo!() }
+///     fn into_pull_up_input(self) -> Input<'a, 0, PullUp> { todo!() }
+///     fn into_pull_down_input(self) -> Input<'a, 0, PullDown> { todo!() }
+///     fn into_floating_input(self) -> Input<'a, 0, Floating> { todo!() }
+/// }
+/// ```
+pub trait IntoPad<'a, const N: usize>: Sealed {
     /// Configures the pad to operate as a pull up output pad.
     fn into_pull_up_output(self) -> Output<'a, N, PullUp>;
     /// Configures the pad to operate as a pull down output pad.
@@ -22,11 +38,15 @@ pub trait IntoPad<'a, const N: usize> {
 }
 
 /// Trait for GLBv2 pad mode conversations.
-pub trait IntoPadv2<'a, const N: usize> {
+///
+/// This trait is sealed and implemented for GPIO pad structures of this crate.
+pub trait IntoPadv2<'a, const N: usize>: Sealed {
     /// Configures the pin to operate as a SPI pin.
     fn into_spi<const I: usize>(self) -> Alternate<'a, N, typestate::Spi<I>>;
     /// Configures the pin to operate as a SDH pin.
     fn into_sdh(self) -> Alternate<'a, N, typestate::Sdh>;
+    /// Configures the pin to operate as an Inter-IC Sound signal pin.
+    fn into_i2s(self) -> Alternate<'a, N, typestate::I2s>;
     /// Configures the pin to operate as UART signal.
     fn into_uart(self) -> Alternate<'a, N, typestate::Uart>;
     /// Configures the pin to operate as multi-media cluster UART signal.
@@ -45,4 +65,28 @@ pub trait IntoPadv2<'a, const N: usize> {
     fn into_jtag_m0(self) -> Alternate<'a, N, typestate::JtagM0>;
     /// Configures the pin to operate as LP core JTAG.
     fn into_jtag_lp(self) -> Alternate<'a, N, typestate::JtagLp>;
+    /// Configures the pin to output a clock selected in GLB clock output settings.
+    fn into_clock_output(self) -> Alternate<'a, N, typestate::ClockOut>;
+}
+
+/// Trait for GLBv1 pad mode conversations.
+///
+/// This trait is sealed and implemented for GPIO pad structures of this crate.
+pub trait IntoPadv1<'a, const N: usize>: Sealed {
+    /// Configures the pin to operate as UART signal.
+    fn into_uart(self) -> Alternate<'a, N, typestate::Uart>;
+    /// Configures the pin to operate as a pull up Pulse Width Modulation signal pin.
+    fn into_pull_up_pwm(self) -> Alternate<'a, N, typestate::Pwm<0>>;
+    /// Configures the pin to operate as a pull down Pulse Width Modulation signal pin.
+    fn into_pull_down_pwm(self) -> Alternate<'a, N, typestate::Pwm<0>>;
+    /// Configures the pin to operate as floating Pulse Width Modulation signal pin.
+    fn into_floating_pwm(self) -> Alternate<'a, N, typestate::Pwm<0>>;
+    /// Configures the pin to operate as an Inter-Integrated Circuit signal pin.
+    fn into_i2c(self) -> Alternate<'a, N, typestate::I2c<0>>;
+    /// Configures the pin to operate as a SPI pin.
+    fn into_spi(self) -> Alternate<'a, N, typestate::Spi<0>>;
+    /// Configures the pin to operate as an Inter-IC Sound signal pin.
+    fn into_i2s(self) -> Alternate<'a, N, typestate::I2s>;
+    /// Configures the pin to output a clock selected in GLB clock output settings.
+    fn into_clock_output(self) -> Alternate<'a, N, typestate::ClockOut>;
 }
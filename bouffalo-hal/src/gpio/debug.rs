@@ -0,0 +1,52 @@
+#[cfg(any(doc, feature = "glb-v2"))]
+use super::{alternate::Alternate, convert::IntoPadv2, typestate};
+use super::{disabled::Disabled, typestate::Disabled as DisabledState};
+
+/// GPIO pad reserved for the on-chip debugger.
+///
+/// GPIO0 to GPIO3 carry JTAG signals by default on BL616 and BL808. This wrapper only converts the pad into
+/// JTAG modes; any other use must go through [`release`](Self::release) first, so that
+/// the code giving up debugger access is explicit at the call site.
+pub struct DebugPad<'a, const N: usize> {
+    inner: Disabled<'a, N>,
+}
+
+impl<'a, const N: usize> DebugPad<'a, N> {
+    /// Give up debugger access on this pad and use it as an ordinary GPIO pad.
+    ///
+    /// Once all JTAG pads are reconfigured, the debugger can no longer halt this chip
+    /// until firmware without such reconfiguration is flashed, usually through the
+    /// bootloader UART.
+    #[inline]
+    pub fn release(self) -> Disabled<'a, N> {
+        self.inner
+    }
+}
+
+#[cfg(any(doc, feature = "glb-v2"))]
+impl<'a, const N: usize> DebugPad<'a, N> {
+    /// Configures the pad to operate as D0 core JTAG.
+    #[inline]
+    pub fn into_jtag_d0(self) -> Alternate<'a, N, typestate::JtagD0> {
+        self.inner.into_jtag_d0()
+    }
+    /// Configures the pad to operate as M0 core JTAG.
+    #[inline]
+    pub fn into_jtag_m0(self) -> Alternate<'a, N, typestate::JtagM0> {
+        self.inner.into_jtag_m0()
+    }
+    /// Configures the pad to operate as LP core JTAG.
+    #[inline]
+    pub fn into_jtag_lp(self) -> Alternate<'a, N, typestate::JtagLp> {
+        self.inner.into_jtag_lp()
+    }
+}
+
+impl<'a, const N: usize> From<super::Inner<'a, N, DisabledState>> for DebugPad<'a, N> {
+    #[inline]
+    fn from(inner: super::Inner<'a, N, DisabledState>) -> Self {
+        Self {
+            inner: inner.into(),
+        }
+    }
+}
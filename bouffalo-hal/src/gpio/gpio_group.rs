@@ -1,15 +1,23 @@
+#[cfg(any(feature = "bl616", feature = "bl808"))]
+use super::debug::DebugPad;
 use super::disabled::Disabled;
 
+// GPIO0 to GPIO3 carry JTAG signals by default only on BL616 and BL808.
+#[cfg(any(feature = "bl616", feature = "bl808"))]
+type JtagDefault<'a, const N: usize> = DebugPad<'a, N>;
+#[cfg(not(any(feature = "bl616", feature = "bl808")))]
+type JtagDefault<'a, const N: usize> = Disabled<'a, N>;
+
 /// Available GPIO pads.
 pub struct Pads<'a> {
-    /// GPIO I/O 0.
-    pub io0: Disabled<'a, 0>,
-    /// GPIO I/O 1.
-    pub io1: Disabled<'a, 1>,
-    /// GPIO I/O 2.
-    pub io2: Disabled<'a, 2>,
-    /// GPIO I/O 3.
-    pub io3: Disabled<'a, 3>,
+    /// GPIO I/O 0, JTAG signal by default on BL616 and BL808.
+    pub io0: JtagDefault<'a, 0>,
+    /// GPIO I/O 1, JTAG signal by default on BL616 and BL808.
+    pub io1: JtagDefault<'a, 1>,
+    /// GPIO I/O 2, JTAG signal by default on BL616 and BL808.
+    pub io2: JtagDefault<'a, 2>,
+    /// GPIO I/O 3, JTAG signal by default on BL616 and BL808.
+    pub io3: JtagDefault<'a, 3>,
     /// GPIO I/O 4.
     pub io4: Disabled<'a, 4>,
     /// GPIO I/O 5.
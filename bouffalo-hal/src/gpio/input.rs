@@ -1,5 +1,9 @@
+#[cfg(any(doc, feature = "glb-v1", feature = "glb-v2"))]
+use super::alternate::Alternate;
+#[cfg(feature = "glb-v1")]
+use super::convert::IntoPadv1;
 #[cfg(any(doc, feature = "glb-v2"))]
-use super::{alternate::Alternate, convert::IntoPadv2};
+use super::convert::IntoPadv2;
 use super::{
     convert::IntoPad,
     output::Output,
@@ -12,6 +16,8 @@ pub struct Input<'a, const N: usize, M> {
     inner: super::Inner<'a, N, typestate::Input<M>>,
 }
 
+impl<'a, const N: usize, M> crate::sealed::Sealed for Input<'a, N, M> {}
+
 impl<'a, const N: usize, M> Input<'a, N, M> {
     /// Enable schmitt trigger.
     #[inline]
@@ -43,6 +49,10 @@ impl<'a, const N: usize, M> Input<'a, N, M> {
     pub fn unmask_interrupt(&mut self) {
         self.inner.unmask_interrupt();
     }
+    #[inline]
+    pub(crate) fn inner(&self) -> &super::Inner<'a, N, typestate::Input<M>> {
+        &self.inner
+    }
 }
 
 impl<'a, const N: usize, M> IntoPad<'a, N> for Input<'a, N, M> {
@@ -83,6 +93,10 @@ impl<'a, const N: usize, M> IntoPadv2<'a, N> for Input<'a, N, M> {
         self.inner.into_sdh().into()
     }
     #[inline]
+    fn into_i2s(self) -> Alternate<'a, N, typestate::I2s> {
+        self.inner.into_i2s().into()
+    }
+    #[inline]
     fn into_uart(self) -> Alternate<'a, N, typestate::Uart> {
         self.inner.into_uart().into()
     }
@@ -118,6 +132,46 @@ impl<'a, const N: usize, M> IntoPadv2<'a, N> for Input<'a, N, M> {
     fn into_jtag_lp(self) -> Alternate<'a, N, typestate::JtagLp> {
         self.inner.into_jtag_lp().into()
     }
+    #[inline]
+    fn into_clock_output(self) -> Alternate<'a, N, typestate::ClockOut> {
+        self.inner.into_clock_output().into()
+    }
+}
+
+#[cfg(feature = "glb-v1")]
+impl<'a, const N: usize, M> IntoPadv1<'a, N> for Input<'a, N, M> {
+    #[inline]
+    fn into_uart(self) -> Alternate<'a, N, typestate::Uart> {
+        self.inner.into_uart().into()
+    }
+    #[inline]
+    fn into_pull_up_pwm(self) -> Alternate<'a, N, typestate::Pwm<0>> {
+        self.inner.into_pull_up_pwm().into()
+    }
+    #[inline]
+    fn into_pull_down_pwm(self) -> Alternate<'a, N, typestate::Pwm<0>> {
+        self.inner.into_pull_down_pwm().into()
+    }
+    #[inline]
+    fn into_floating_pwm(self) -> Alternate<'a, N, typestate::Pwm<0>> {
+        self.inner.into_floating_pwm().into()
+    }
+    #[inline]
+    fn into_i2c(self) -> Alternate<'a, N, typestate::I2c<0>> {
+        self.inner.into_i2c().into()
+    }
+    #[inline]
+    fn into_spi(self) -> Alternate<'a, N, typestate::Spi<0>> {
+        self.inner.into_spi().into()
+    }
+    #[inline]
+    fn into_i2s(self) -> Alternate<'a, N, typestate::I2s> {
+        self.inner.into_i2s().into()
+    }
+    #[inline]
+    fn into_clock_output(self) -> Alternate<'a, N, typestate::ClockOut> {
+        self.inner.into_clock_output().into()
+    }
 }
 
 impl<'a, const N: usize, M> ErrorType for Input<'a, N, M> {
@@ -1,18 +1,24 @@
+#[cfg(any(doc, feature = "glb-v1", feature = "glb-v2"))]
+use super::alternate::Alternate;
+#[cfg(feature = "glb-v1")]
+use super::convert::IntoPadv1;
 #[cfg(any(doc, feature = "glb-v2"))]
-use super::{alternate::Alternate, convert::IntoPadv2};
+use super::convert::IntoPadv2;
 use super::{
     convert::IntoPad,
     input::Input,
     typestate::{self, Floating, PullDown, PullUp},
 };
 use crate::glb::Drive;
-use embedded_hal::digital::{ErrorType, OutputPin};
+use embedded_hal::digital::{ErrorType, OutputPin, StatefulOutputPin};
 
 /// GPIO pad in output mode.
 pub struct Output<'a, const N: usize, M> {
     inner: super::Inner<'a, N, typestate::Output<M>>,
 }
 
+impl<'a, const N: usize, M> crate::sealed::Sealed for Output<'a, N, M> {}
+
 impl<'a, const N: usize, M> Output<'a, N, M> {
     /// Get drive strength of this pad.
     #[inline]
@@ -24,6 +30,10 @@ impl<'a, const N: usize, M> Output<'a, N, M> {
     pub fn set_drive(&mut self, val: Drive) {
         self.inner.set_drive(val)
     }
+    #[inline]
+    pub(crate) fn inner(&self) -> &super::Inner<'a, N, typestate::Output<M>> {
+        &self.inner
+    }
 }
 
 impl<'a, const N: usize, M> IntoPad<'a, N> for Output<'a, N, M> {
@@ -64,6 +74,10 @@ impl<'a, const N: usize, M> IntoPadv2<'a, N> for Output<'a, N, M> {
         self.inner.into_sdh().into()
     }
     #[inline]
+    fn into_i2s(self) -> Alternate<'a, N, typestate::I2s> {
+        self.inner.into_i2s().into()
+    }
+    #[inline]
     fn into_uart(self) -> Alternate<'a, N, typestate::Uart> {
         self.inner.into_uart().into()
     }
@@ -99,6 +113,46 @@ impl<'a, const N: usize, M> IntoPadv2<'a, N> for Output<'a, N, M> {
     fn into_jtag_lp(self) -> Alternate<'a, N, typestate::JtagLp> {
         self.inner.into_jtag_lp().into()
     }
+    #[inline]
+    fn into_clock_output(self) -> Alternate<'a, N, typestate::ClockOut> {
+        self.inner.into_clock_output().into()
+    }
+}
+
+#[cfg(feature = "glb-v1")]
+impl<'a, const N: usize, M> IntoPadv1<'a, N> for Output<'a, N, M> {
+    #[inline]
+    fn into_uart(self) -> Alternate<'a, N, typestate::Uart> {
+        self.inner.into_uart().into()
+    }
+    #[inline]
+    fn into_pull_up_pwm(self) -> Alternate<'a, N, typestate::Pwm<0>> {
+        self.inner.into_pull_up_pwm().into()
+    }
+    #[inline]
+    fn into_pull_down_pwm(self) -> Alternate<'a, N, typestate::Pwm<0>> {
+        self.inner.into_pull_down_pwm().into()
+    }
+    #[inline]
+    fn into_floating_pwm(self) -> Alternate<'a, N, typestate::Pwm<0>> {
+        self.inner.into_floating_pwm().into()
+    }
+    #[inline]
+    fn into_i2c(self) -> Alternate<'a, N, typestate::I2c<0>> {
+        self.inner.into_i2c().into()
+    }
+    #[inline]
+    fn into_spi(self) -> Alternate<'a, N, typestate::Spi<0>> {
+        self.inner.into_spi().into()
+    }
+    #[inline]
+    fn into_i2s(self) -> Alternate<'a, N, typestate::I2s> {
+        self.inner.into_i2s().into()
+    }
+    #[inline]
+    fn into_clock_output(self) -> Alternate<'a, N, typestate::ClockOut> {
+        self.inner.into_clock_output().into()
+    }
 }
 
 impl<'a, const N: usize, M> ErrorType for Output<'a, N, M> {
@@ -116,6 +170,21 @@ impl<'a, const N: usize, M> OutputPin for Output<'a, N, M> {
     }
 }
 
+impl<'a, const N: usize, M> StatefulOutputPin for Output<'a, N, M> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        self.inner.is_set_high()
+    }
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        self.inner.is_set_low()
+    }
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.inner.toggle()
+    }
+}
+
 // This part of implementation using `embedded_hal_027` is designed for backward compatibility of
 // ecosystem crates, as some of them depends on embedded-hal v0.2.7 traits.
 // We encourage ecosystem developers to use embedded-hal v1.0.0 traits; after that, this part of code
@@ -1,8 +1,9 @@
 #![allow(dead_code)]
+use super::port::PortAccess;
 use super::typestate::{Floating, Input, Output, PullDown, PullUp};
 use crate::glb::Drive;
 use core::marker::PhantomData;
-use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin, StatefulOutputPin};
 
 pub struct PadDummy<'a, const N: usize, M> {
     _unused: PhantomData<(&'a (), M)>,
@@ -46,6 +47,21 @@ impl<'a, const N: usize, M> PadDummy<'a, N, Output<M>> {
     }
 }
 
+impl<'a, const N: usize, M> PortAccess for PadDummy<'a, N, M> {
+    #[inline]
+    fn read_port(&self) -> u32 {
+        unimplemented!()
+    }
+    #[inline]
+    fn write_port(&self, _: u32, _: u32) {
+        unimplemented!()
+    }
+    #[inline]
+    fn modify_port(&self, _: u32, _: u32) {
+        unimplemented!()
+    }
+}
+
 impl<'a, const N: usize, M> PadDummy<'a, N, M> {
     #[inline]
     pub fn into_pull_up_output(self) -> PadDummy<'a, N, Output<PullUp>> {
@@ -103,6 +119,17 @@ impl<'a, const N: usize, M> OutputPin for PadDummy<'a, N, Output<M>> {
     }
 }
 
+impl<'a, const N: usize, M> StatefulOutputPin for PadDummy<'a, N, Output<M>> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        unimplemented!()
+    }
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        unimplemented!()
+    }
+}
+
 // Macro internal functions, do not use.
 impl<'a, const N: usize> PadDummy<'a, N, super::typestate::Disabled> {
     #[doc(hidden)]
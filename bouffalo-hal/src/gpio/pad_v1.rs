@@ -1,7 +1,10 @@
-use super::typestate::{Floating, Input, Output, PullDown, PullUp};
-use crate::glb::{Drive, Pull, v1};
+use super::port::PortAccess;
+use super::typestate::{
+    ClockOut, Floating, I2c, I2s, Input, Output, PullDown, PullUp, Pwm, Spi, Uart,
+};
+use crate::glb::{Drive, Pull, modify_shared, v1};
 use core::marker::PhantomData;
-use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin, StatefulOutputPin};
 
 /// Raw GPIO pad of BL602 and BL702.
 pub struct Padv1<'a, const N: usize, M> {
@@ -13,16 +16,16 @@ impl<'a, const N: usize, M> Padv1<'a, N, Input<M>> {
     /// Enable schmitt trigger.
     #[inline]
     pub fn enable_schmitt(&mut self) {
-        let config = self.base.gpio_config[N >> 1].read().enable_schmitt(N & 0x1);
-        unsafe { self.base.gpio_config[N >> 1].write(config) };
+        modify_shared(&self.base.gpio_config[N >> 1], |config| {
+            config.enable_schmitt(N & 0x1)
+        });
     }
     /// Disable schmitt trigger.
     #[inline]
     pub fn disable_schmitt(&mut self) {
-        let config = self.base.gpio_config[N >> 1]
-            .read()
-            .disable_schmitt(N & 0x1);
-        unsafe { self.base.gpio_config[N >> 1].write(config) };
+        modify_shared(&self.base.gpio_config[N >> 1], |config| {
+            config.disable_schmitt(N & 0x1)
+        });
     }
     /// Clear interrupt flag.
     #[inline]
@@ -37,14 +40,12 @@ impl<'a, const N: usize, M> Padv1<'a, N, Input<M>> {
     /// Mask interrupt.
     #[inline]
     pub fn mask_interrupt(&mut self) {
-        let config = self.base.gpio_interrupt_mask.read() | (1 << N);
-        unsafe { self.base.gpio_interrupt_mask.write(config) };
+        modify_shared(&self.base.gpio_interrupt_mask, |config| config | (1 << N));
     }
     /// Unmask interrupt.
     #[inline]
     pub fn unmask_interrupt(&mut self) {
-        let config = self.base.gpio_interrupt_mask.read() & !(1 << N);
-        unsafe { self.base.gpio_interrupt_mask.write(config) };
+        modify_shared(&self.base.gpio_interrupt_mask, |config| config & !(1 << N));
     }
 }
 
@@ -57,8 +58,28 @@ impl<'a, const N: usize, M> Padv1<'a, N, Output<M>> {
     /// Set drive strength of this pin.
     #[inline]
     pub fn set_drive(&mut self, val: Drive) {
-        let config = self.base.gpio_config[N >> 1].read().set_drive(N & 0x1, val);
-        unsafe { self.base.gpio_config[N >> 1].write(config) };
+        modify_shared(&self.base.gpio_config[N >> 1], |config| {
+            config.set_drive(N & 0x1, val)
+        });
+    }
+}
+
+impl<'a, const N: usize, M> PortAccess for Padv1<'a, N, M> {
+    #[inline]
+    fn read_port(&self) -> u32 {
+        self.base.gpio_input_value.read()
+    }
+    // BL602 and BL702 have no set and clear registers, so the output value register is
+    // updated with interrupts masked.
+    #[inline]
+    fn write_port(&self, set: u32, clear: u32) {
+        modify_shared(&self.base.gpio_output_value, |val| (val & !clear) | set);
+    }
+    #[inline]
+    fn modify_port(&self, mask: u32, value: u32) {
+        modify_shared(&self.base.gpio_output_value, |val| {
+            (val & !mask) | (value & mask)
+        });
     }
 }
 
@@ -73,10 +94,9 @@ impl<'a, const N: usize, M> Padv1<'a, N, Input<M>> {
     /// Set interrupt mode.
     #[inline]
     pub fn set_interrupt_mode(&mut self, val: v1::InterruptMode) {
-        let config = self.base.gpio_interrupt_mode[N >> 1]
-            .read()
-            .set_interrupt_mode(N & 0x1, val);
-        unsafe { self.base.gpio_interrupt_mode[N >> 1].write(config) };
+        modify_shared(&self.base.gpio_interrupt_mode[N >> 1], |config| {
+            config.set_interrupt_mode(N & 0x1, val)
+        });
     }
 }
 
@@ -84,14 +104,13 @@ impl<'a, const N: usize, M> Padv1<'a, N, M> {
     /// Configures the pin to operate as a pull up output pin.
     #[inline]
     pub fn into_pull_up_output(self) -> Padv1<'a, N, Output<PullUp>> {
-        let config = self.base.gpio_config[N >> 1]
-            .read()
-            .set_function(N & 0x1, v1::Function::Gpio)
-            .disable_input(N & 0x1)
-            .set_pull(N & 0x1, Pull::Up);
-        unsafe { self.base.gpio_config[N >> 1].write(config) };
-        let val = self.base.gpio_output_enable.read();
-        unsafe { self.base.gpio_output_enable.write(val | (1 << N)) };
+        modify_shared(&self.base.gpio_config[N >> 1], |config| {
+            config
+                .set_function(N & 0x1, v1::Function::Gpio)
+                .disable_input(N & 0x1)
+                .set_pull(N & 0x1, Pull::Up)
+        });
+        modify_shared(&self.base.gpio_output_enable, |val| val | (1 << N));
         Padv1 {
             base: self.base,
             _mode: PhantomData,
@@ -100,14 +119,13 @@ impl<'a, const N: usize, M> Padv1<'a, N, M> {
     /// Configures the pin to operate as a pull down output pin.
     #[inline]
     pub fn into_pull_down_output(self) -> Padv1<'a, N, Output<PullDown>> {
-        let config = self.base.gpio_config[N >> 1]
-            .read()
-            .set_function(N & 0x1, v1::Function::Gpio)
-            .disable_input(N & 0x1)
-            .set_pull(N & 0x1, Pull::Down);
-        unsafe { self.base.gpio_config[N >> 1].write(config) };
-        let val = self.base.gpio_output_enable.read();
-        unsafe { self.base.gpio_output_enable.write(val | (1 << N)) };
+        modify_shared(&self.base.gpio_config[N >> 1], |config| {
+            config
+                .set_function(N & 0x1, v1::Function::Gpio)
+                .disable_input(N & 0x1)
+                .set_pull(N & 0x1, Pull::Down)
+        });
+        modify_shared(&self.base.gpio_output_enable, |val| val | (1 << N));
         Padv1 {
             base: self.base,
             _mode: PhantomData,
@@ -116,14 +134,13 @@ impl<'a, const N: usize, M> Padv1<'a, N, M> {
     /// Configures the pin to operate as a floating output pin.
     #[inline]
     pub fn into_floating_output(self) -> Padv1<'a, N, Output<Floating>> {
-        let config = self.base.gpio_config[N >> 1]
-            .read()
-            .set_function(N & 0x1, v1::Function::Gpio)
-            .disable_input(N & 0x1)
-            .set_pull(N & 0x1, Pull::None);
-        unsafe { self.base.gpio_config[N >> 1].write(config) };
-        let val = self.base.gpio_output_enable.read();
-        unsafe { self.base.gpio_output_enable.write(val | (1 << N)) };
+        modify_shared(&self.base.gpio_config[N >> 1], |config| {
+            config
+                .set_function(N & 0x1, v1::Function::Gpio)
+                .disable_input(N & 0x1)
+                .set_pull(N & 0x1, Pull::None)
+        });
+        modify_shared(&self.base.gpio_output_enable, |val| val | (1 << N));
         Padv1 {
             base: self.base,
             _mode: PhantomData,
@@ -132,14 +149,13 @@ impl<'a, const N: usize, M> Padv1<'a, N, M> {
     /// Configures the pin to operate as a pull up input pin.
     #[inline]
     pub fn into_pull_up_input(self) -> Padv1<'a, N, Input<PullUp>> {
-        let config = self.base.gpio_config[N >> 1]
-            .read()
-            .set_function(N & 0x1, v1::Function::Gpio)
-            .enable_input(N & 0x1)
-            .set_pull(N & 0x1, Pull::Up);
-        unsafe { self.base.gpio_config[N >> 1].write(config) };
-        let val = self.base.gpio_output_enable.read();
-        unsafe { self.base.gpio_output_enable.write(val & !(1 << N)) };
+        modify_shared(&self.base.gpio_config[N >> 1], |config| {
+            config
+                .set_function(N & 0x1, v1::Function::Gpio)
+                .enable_input(N & 0x1)
+                .set_pull(N & 0x1, Pull::Up)
+        });
+        modify_shared(&self.base.gpio_output_enable, |val| val & !(1 << N));
         Padv1 {
             base: self.base,
             _mode: PhantomData,
@@ -148,14 +164,13 @@ impl<'a, const N: usize, M> Padv1<'a, N, M> {
     /// Configures the pin to operate as a pull down input pin.
     #[inline]
     pub fn into_pull_down_input(self) -> Padv1<'a, N, Input<PullDown>> {
-        let config = self.base.gpio_config[N >> 1]
-            .read()
-            .set_function(N & 0x1, v1::Function::Gpio)
-            .enable_input(N & 0x1)
-            .set_pull(N & 0x1, Pull::Down);
-        unsafe { self.base.gpio_config[N >> 1].write(config) };
-        let val = self.base.gpio_output_enable.read();
-        unsafe { self.base.gpio_output_enable.write(val & !(1 << N)) };
+        modify_shared(&self.base.gpio_config[N >> 1], |config| {
+            config
+                .set_function(N & 0x1, v1::Function::Gpio)
+                .enable_input(N & 0x1)
+                .set_pull(N & 0x1, Pull::Down)
+        });
+        modify_shared(&self.base.gpio_output_enable, |val| val & !(1 << N));
         Padv1 {
             base: self.base,
             _mode: PhantomData,
@@ -164,14 +179,76 @@ impl<'a, const N: usize, M> Padv1<'a, N, M> {
     /// Configures the pin to operate as a floating input pin.
     #[inline]
     pub fn into_floating_input(self) -> Padv1<'a, N, Input<Floating>> {
-        let config = self.base.gpio_config[N >> 1]
-            .read()
-            .set_function(N & 0x1, v1::Function::Gpio)
-            .enable_input(N & 0x1)
-            .set_pull(N & 0x1, Pull::None);
-        unsafe { self.base.gpio_config[N >> 1].write(config) };
-        let val = self.base.gpio_output_enable.read();
-        unsafe { self.base.gpio_output_enable.write(val & !(1 << N)) };
+        modify_shared(&self.base.gpio_config[N >> 1], |config| {
+            config
+                .set_function(N & 0x1, v1::Function::Gpio)
+                .enable_input(N & 0x1)
+                .set_pull(N & 0x1, Pull::None)
+        });
+        modify_shared(&self.base.gpio_output_enable, |val| val & !(1 << N));
+        Padv1 {
+            base: self.base,
+            _mode: PhantomData,
+        }
+    }
+}
+
+impl<'a, const N: usize, M> Padv1<'a, N, M> {
+    /// Configures the pin to operate as UART signal.
+    #[inline]
+    pub fn into_uart(self) -> Padv1<'a, N, Uart> {
+        self.into_function(v1::Function::Uart, true, Pull::Up)
+    }
+    /// Configures the pin to operate as a pull up Pulse Width Modulation signal pin.
+    #[inline]
+    pub fn into_pull_up_pwm(self) -> Padv1<'a, N, Pwm<0>> {
+        self.into_function(v1::Function::Pwm, false, Pull::Up)
+    }
+    /// Configures the pin to operate as a pull down Pulse Width Modulation signal pin.
+    #[inline]
+    pub fn into_pull_down_pwm(self) -> Padv1<'a, N, Pwm<0>> {
+        self.into_function(v1::Function::Pwm, false, Pull::Down)
+    }
+    /// Configures the pin to operate as floating Pulse Width Modulation signal pin.
+    #[inline]
+    pub fn into_floating_pwm(self) -> Padv1<'a, N, Pwm<0>> {
+        self.into_function(v1::Function::Pwm, false, Pull::None)
+    }
+    /// Configures the pin to operate as an Inter-Integrated Circuit signal pin.
+    #[inline]
+    pub fn into_i2c(self) -> Padv1<'a, N, I2c<0>> {
+        self.into_function(v1::Function::I2c, true, Pull::Up)
+    }
+    /// Configures the pin to operate as a SPI pin.
+    #[inline]
+    pub fn into_spi(self) -> Padv1<'a, N, Spi<0>> {
+        self.into_function(v1::Function::Spi, true, Pull::Up)
+    }
+    /// Configures the pin to operate as an Inter-IC Sound signal pin.
+    #[inline]
+    pub fn into_i2s(self) -> Padv1<'a, N, I2s> {
+        self.into_function(v1::Function::I2s, true, Pull::None)
+    }
+    /// Configures the pin to output a clock selected in GLB clock output settings.
+    #[inline]
+    pub fn into_clock_output(self) -> Padv1<'a, N, ClockOut> {
+        self.into_function(v1::Function::ClkOut, false, Pull::None)
+    }
+    #[inline]
+    fn into_function<T>(self, function: v1::Function, input: bool, pull: Pull) -> Padv1<'a, N, T> {
+        modify_shared(&self.base.gpio_config[N >> 1], |config| {
+            let config = if input {
+                config.enable_input(N & 0x1)
+            } else {
+                config.disable_input(N & 0x1)
+            };
+            config
+                .enable_schmitt(N & 0x1)
+                .set_drive(N & 0x1, Drive::Drive0)
+                .set_pull(N & 0x1, pull)
+                .set_function(N & 0x1, function)
+        });
+        modify_shared(&self.base.gpio_output_enable, |val| val | (1 << N));
         Padv1 {
             base: self.base,
             _mode: PhantomData,
@@ -201,14 +278,28 @@ impl<'a, const N: usize, M> InputPin for Padv1<'a, N, Input<M>> {
 impl<'a, const N: usize, M> OutputPin for Padv1<'a, N, Output<M>> {
     #[inline]
     fn set_low(&mut self) -> Result<(), Self::Error> {
-        let val = self.base.gpio_output_value.read();
-        unsafe { self.base.gpio_output_value.write(val & !(1 << N)) };
+        modify_shared(&self.base.gpio_output_value, |val| val & !(1 << N));
         Ok(())
     }
     #[inline]
     fn set_high(&mut self) -> Result<(), Self::Error> {
-        let val = self.base.gpio_output_value.read();
-        unsafe { self.base.gpio_output_value.write(val | (1 << N)) };
+        modify_shared(&self.base.gpio_output_value, |val| val | (1 << N));
+        Ok(())
+    }
+}
+
+impl<'a, const N: usize, M> StatefulOutputPin for Padv1<'a, N, Output<M>> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.base.gpio_output_value.read() & (1 << N) != 0)
+    }
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.base.gpio_output_value.read() & (1 << N) == 0)
+    }
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        modify_shared(&self.base.gpio_output_value, |val| val ^ (1 << N));
         Ok(())
     }
 }
@@ -1,13 +1,14 @@
+use super::port::PortAccess;
 use super::{
     Spi,
     typestate::{
-        Floating, I2c, Input, JtagD0, JtagLp, JtagM0, MmUart, Output, PullDown, PullUp, Pwm, Sdh,
-        Uart,
+        ClockOut, Floating, I2c, I2s, Input, JtagD0, JtagLp, JtagM0, MmUart, Output, PullDown,
+        PullUp, Pwm, Sdh, Uart,
     },
 };
-use crate::glb::{Drive, Pull, v2};
+use crate::glb::{Drive, Pull, modify_shared, v2};
 use core::marker::PhantomData;
-use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin, StatefulOutputPin};
 
 /// Raw GPIO pad of BL808 and BL616.
 pub struct Padv2<'a, const N: usize, M> {
@@ -67,6 +68,30 @@ impl<'a, const N: usize, M> Padv2<'a, N, Output<M>> {
     }
 }
 
+impl<'a, const N: usize, M> PortAccess for Padv2<'a, N, M> {
+    #[inline]
+    fn read_port(&self) -> u32 {
+        self.base.gpio_input[N >> 5].read()
+    }
+    #[inline]
+    fn write_port(&self, set: u32, clear: u32) {
+        unsafe {
+            if set != 0 {
+                self.base.gpio_set[N >> 5].write(set);
+            }
+            if clear != 0 {
+                self.base.gpio_clear[N >> 5].write(clear);
+            }
+        }
+    }
+    #[inline]
+    fn modify_port(&self, mask: u32, value: u32) {
+        modify_shared(&self.base.gpio_output[N >> 5], |val| {
+            (val & !mask) | (value & mask)
+        });
+    }
+}
+
 impl<'a, const N: usize, M> Padv2<'a, N, Input<M>> {
     /// Get interrupt mode.
     #[inline]
@@ -343,6 +368,41 @@ impl<'a, const N: usize, M> Padv2<'a, N, M> {
             self.base.gpio_config[N].write(config);
         }
 
+        Padv2 {
+            base: self.base,
+            _mode: PhantomData,
+        }
+    }
+    /// Configures the pin to output a clock selected in GLB clock output settings.
+    #[inline]
+    pub fn into_clock_output(self) -> Padv2<'a, N, ClockOut> {
+        let config = v2::GpioConfig::RESET_VALUE
+            .disable_input()
+            .enable_output()
+            .enable_schmitt()
+            .set_pull(Pull::None)
+            .set_drive(Drive::Drive0)
+            .set_function(v2::Function::ClockOut);
+        unsafe { self.base.gpio_config[N].write(config) };
+        Padv2 {
+            base: self.base,
+            _mode: PhantomData,
+        }
+    }
+    /// Configures the pin to operate as an I2S pin.
+    #[inline]
+    pub fn into_i2s(self) -> Padv2<'a, N, I2s> {
+        let config = v2::GpioConfig::RESET_VALUE
+            .enable_input()
+            .disable_output()
+            .enable_schmitt()
+            .set_pull(Pull::None)
+            .set_drive(Drive::Drive0)
+            .set_function(v2::Function::I2s);
+        unsafe {
+            self.base.gpio_config[N].write(config);
+        }
+
         Padv2 {
             base: self.base,
             _mode: PhantomData,
@@ -382,6 +442,27 @@ impl<'a, const N: usize, M> OutputPin for Padv2<'a, N, Output<M>> {
     }
 }
 
+impl<'a, const N: usize, M> StatefulOutputPin for Padv2<'a, N, Output<M>> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.base.gpio_output[N >> 5].read() & (1 << (N & 0x1F)) != 0)
+    }
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.base.gpio_output[N >> 5].read() & (1 << (N & 0x1F)) == 0)
+    }
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        // Output state of this pad is only changed through this pad, so writing
+        // set or clear register after reading it does not race with other pads.
+        if self.is_set_high()? {
+            self.set_low()
+        } else {
+            self.set_high()
+        }
+    }
+}
+
 // Macro internal functions, do not use.
 impl<'a, const N: usize> Padv2<'a, N, super::typestate::Disabled> {
     #[doc(hidden)]
@@ -0,0 +1,150 @@
+use super::{input::Input, output::Output};
+
+/// Pads of one GPIO port, owned as a tuple and accessed together.
+///
+/// Implemented for tuples of up to 16 [`Output`] or [`Input`] pads. A port covers 32
+/// pads, i.e. pads `0..=31` belong to port 0 and pads `32..=63` to port 1.
+pub trait Port {
+    /// Index of the port.
+    const PORT: usize;
+    /// Bits of owned pads in the port.
+    const MASK: u32;
+    #[doc(hidden)]
+    const SAME_PORT: bool;
+}
+
+/// Output pads of one GPIO port.
+pub trait OutputPort: Port {
+    #[doc(hidden)]
+    fn __write_port(&self, set: u32, clear: u32);
+    #[doc(hidden)]
+    fn __modify_port(&self, mask: u32, value: u32);
+}
+
+/// Input pads of one GPIO port.
+pub trait InputPort: Port {
+    #[doc(hidden)]
+    fn __read_port(&self) -> u32;
+}
+
+/// Register access of the port a raw pad belongs to.
+pub(crate) trait PortAccess {
+    /// Read input levels of all pads in the port.
+    fn read_port(&self) -> u32;
+    /// Set and clear output pads of the port; other pads of the port are untouched.
+    fn write_port(&self, set: u32, clear: u32);
+    /// Write pads in `mask` of the port in one register write.
+    fn modify_port(&self, mask: u32, value: u32);
+}
+
+macro_rules! impl_port {
+    ($(($n:ident $m:ident))+) => {
+        impl<'a, $(const $n: usize, $m),+> Port for ($(Output<'a, $n, $m>,)+) {
+            const PORT: usize = impl_port!(@first $($n)+) >> 5;
+            const MASK: u32 = 0 $(| (1 << ($n & 0x1F)))+;
+            const SAME_PORT: bool = true $(&& ($n >> 5) == Self::PORT)+;
+        }
+        impl<'a, $(const $n: usize, $m),+> OutputPort for ($(Output<'a, $n, $m>,)+) {
+            #[inline]
+            fn __write_port(&self, set: u32, clear: u32) {
+                self.0.inner().write_port(set, clear)
+            }
+            #[inline]
+            fn __modify_port(&self, mask: u32, value: u32) {
+                self.0.inner().modify_port(mask, value)
+            }
+        }
+        impl<'a, $(const $n: usize, $m),+> Port for ($(Input<'a, $n, $m>,)+) {
+            const PORT: usize = impl_port!(@first $($n)+) >> 5;
+            const MASK: u32 = 0 $(| (1 << ($n & 0x1F)))+;
+            const SAME_PORT: bool = true $(&& ($n >> 5) == Self::PORT)+;
+        }
+        impl<'a, $(const $n: usize, $m),+> InputPort for ($(Input<'a, $n, $m>,)+) {
+            #[inline]
+            fn __read_port(&self) -> u32 {
+                self.0.inner().read_port()
+            }
+        }
+    };
+    (@first $first:ident $($rest:ident)*) => {
+        $first
+    };
+}
+
+macro_rules! impl_port_all {
+    ([$($done:tt)*]) => {};
+    ([$($done:tt)*] $next:tt $($rest:tt)*) => {
+        impl_port!($($done)* $next);
+        impl_port_all!([$($done)* $next] $($rest)*);
+    };
+}
+
+impl_port_all!([]
+    (N0 M0) (N1 M1) (N2 M2) (N3 M3) (N4 M4) (N5 M5) (N6 M6) (N7 M7)
+    (N8 M8) (N9 M9) (N10 M10) (N11 M11) (N12 M12) (N13 M13) (N14 M14)
+    (N15 M15)
+);
+
+/// Bulk writer of output pads in one GPIO port.
+///
+/// Pads are changed with the set and clear registers where the chip has them, so
+/// several pads toggle in one or two register writes without disturbing other pads.
+/// This suits bit-banged protocols that need output rates in the MHz range.
+///
+/// # Examples
+///
+/// ```ignore
+/// let clock = p.gpio.io4.into_floating_output();
+/// let data = p.gpio.io5.into_floating_output();
+/// let mut port = PortWriter::new((clock, data));
+/// // Raise io5 and lower io4 at once.
+/// port.write_mask((1 << 4) | (1 << 5), 1 << 5);
+/// ```
+pub struct PortWriter<P> {
+    pads: P,
+}
+
+impl<P: OutputPort> PortWriter<P> {
+    /// Create a port writer from owned output pads.
+    ///
+    /// All pads must belong to the same port, or this function fails to compile.
+    #[inline]
+    pub fn new(pads: P) -> Self {
+        const {
+            assert!(
+                P::SAME_PORT,
+                "all pads of a port writer must be in one port"
+            )
+        };
+        Self { pads }
+    }
+    /// Write `value` to pads selected by `mask`.
+    ///
+    /// Bit `n` stands for pad `n` of the port; bits of pads not owned by this writer
+    /// are ignored.
+    #[inline]
+    pub fn write_mask(&mut self, mask: u32, value: u32) {
+        let mask = mask & P::MASK;
+        self.pads.__write_port(value & mask, !value & mask);
+    }
+    /// Set pads selected by `mask` to high level.
+    #[inline]
+    pub fn set_mask(&mut self, mask: u32) {
+        self.pads.__write_port(mask & P::MASK, 0);
+    }
+    /// Set pads selected by `mask` to low level.
+    #[inline]
+    pub fn clear_mask(&mut self, mask: u32) {
+        self.pads.__write_port(0, mask & P::MASK);
+    }
+    /// Get bits of pads owned by this writer.
+    #[inline]
+    pub const fn mask(&self) -> u32 {
+        P::MASK
+    }
+    /// Release owned output pads.
+    #[inline]
+    pub fn free(self) -> P {
+        self.pads
+    }
+}
@@ -1,6 +1,18 @@
 use crate::glb::v2;
+use crate::sealed::Sealed;
 use core::marker::PhantomData;
 
+/// Type state of a GPIO pad mode.
+///
+/// This trait is sealed and implemented for every mode in this module.
+///
+/// ```compile_fail
+/// use bouffalo_hal::gpio::PinMode;
+/// struct MyMode;
+/// impl PinMode for MyMode {}
+/// ```
+pub trait PinMode: Sealed {}
+
 /// Input mode (type state).
 pub struct Input<MODE> {
     _mode: PhantomData<MODE>,
@@ -53,6 +65,9 @@ impl<const F: usize> Spi<F> {
 /// SD Host mode (type state).
 pub struct Sdh;
 
+/// Inter-IC Sound mode (type state).
+pub struct I2s;
+
 /// Inter-Integrated Circuit mode (type state).
 pub struct I2c<const F: usize>;
 
@@ -67,6 +82,9 @@ impl<const F: usize> I2c<F> {
     };
 }
 
+/// Clock output mode (type state).
+pub struct ClockOut;
+
 /// Pulse Width Modulation signal mode (type state).
 pub struct Pwm<const F: usize>;
 
@@ -78,3 +96,78 @@ impl<const F: usize> Pwm<F> {
         _ => unreachable!(),
     };
 }
+
+/// Flash memory interface mode (type state).
+pub struct Flash;
+
+/// Pulse Density Modulation microphone mode (type state).
+pub struct Pdm;
+
+/// Ethernet media access controller mode (type state).
+pub struct Emac;
+
+/// Camera interface mode (type state).
+pub struct Cam;
+
+/// Analog signal mode (type state).
+pub struct Analog;
+
+/// Display bus interface type B mode (type state).
+pub struct DbiB;
+
+/// Display bus interface type C mode (type state).
+pub struct DbiC;
+
+/// Display pixel interface mode (type state).
+pub struct Dpi;
+
+impl<MODE> Sealed for Input<MODE> {}
+impl<MODE> PinMode for Input<MODE> {}
+impl<MODE> Sealed for Output<MODE> {}
+impl<MODE> PinMode for Output<MODE> {}
+impl Sealed for Disabled {}
+impl PinMode for Disabled {}
+impl Sealed for PullDown {}
+impl PinMode for PullDown {}
+impl Sealed for PullUp {}
+impl PinMode for PullUp {}
+impl Sealed for Floating {}
+impl PinMode for Floating {}
+impl Sealed for Uart {}
+impl PinMode for Uart {}
+impl Sealed for MmUart {}
+impl PinMode for MmUart {}
+impl Sealed for JtagD0 {}
+impl PinMode for JtagD0 {}
+impl Sealed for JtagM0 {}
+impl PinMode for JtagM0 {}
+impl Sealed for JtagLp {}
+impl PinMode for JtagLp {}
+impl Sealed for Sdh {}
+impl PinMode for Sdh {}
+impl Sealed for I2s {}
+impl PinMode for I2s {}
+impl Sealed for ClockOut {}
+impl PinMode for ClockOut {}
+impl Sealed for Flash {}
+impl PinMode for Flash {}
+impl Sealed for Pdm {}
+impl PinMode for Pdm {}
+impl Sealed for Emac {}
+impl PinMode for Emac {}
+impl Sealed for Cam {}
+impl PinMode for Cam {}
+impl Sealed for Analog {}
+impl PinMode for Analog {}
+impl Sealed for DbiB {}
+impl PinMode for DbiB {}
+impl Sealed for DbiC {}
+impl PinMode for DbiC {}
+impl Sealed for Dpi {}
+impl PinMode for Dpi {}
+impl<const F: usize> Sealed for Spi<F> {}
+impl<const F: usize> PinMode for Spi<F> {}
+impl<const F: usize> Sealed for I2c<F> {}
+impl<const F: usize> PinMode for I2c<F> {}
+impl<const F: usize> Sealed for Pwm<F> {}
+impl<const F: usize> PinMode for Pwm<F> {}
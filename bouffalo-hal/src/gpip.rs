@@ -42,6 +42,32 @@ pub struct RegisterBlock {
 #[repr(transparent)]
 pub struct GpadcConfig(u32);
 
+impl GpadcConfig {
+    const DMA_ENABLE: u32 = 1 << 0;
+    const FIFO_CLEAR: u32 = 1 << 1;
+
+    /// Enable DMA requests on conversion results.
+    #[inline]
+    pub const fn enable_dma(self) -> Self {
+        Self(self.0 | Self::DMA_ENABLE)
+    }
+    /// Disable DMA requests on conversion results.
+    #[inline]
+    pub const fn disable_dma(self) -> Self {
+        Self(self.0 & !Self::DMA_ENABLE)
+    }
+    /// Check if DMA requests on conversion results are enabled.
+    #[inline]
+    pub const fn is_dma_enabled(self) -> bool {
+        self.0 & Self::DMA_ENABLE != 0
+    }
+    /// Clear result FIFO.
+    #[inline]
+    pub const fn clear_fifo(self) -> Self {
+        Self(self.0 | Self::FIFO_CLEAR)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct GpadcDmaRdata(u32);
@@ -181,6 +207,24 @@ impl<ADC: Deref<Target = RegisterBlock>> Adc<ADC> {
         Self { adc }
     }
 
+    /// Enable DMA requests so that hardware triggered conversions run without CPU involvement.
+    #[inline]
+    pub fn enable_dma(&mut self) {
+        unsafe {
+            self.adc
+                .gpadc_config
+                .modify(|v| v.clear_fifo().enable_dma());
+        }
+    }
+
+    /// Disable DMA requests on conversion results.
+    #[inline]
+    pub fn disable_dma(&mut self) {
+        unsafe {
+            self.adc.gpadc_config.modify(|v| v.disable_dma());
+        }
+    }
+
     #[inline]
     pub fn free(self) -> ADC {
         unsafe {
@@ -192,9 +236,22 @@ impl<ADC: Deref<Target = RegisterBlock>> Adc<ADC> {
 
 #[cfg(test)]
 mod tests {
-    use super::RegisterBlock;
+    use super::{GpadcConfig, RegisterBlock};
     use core::mem::offset_of;
 
+    #[test]
+    fn struct_gpadc_config_bits() {
+        let mut val = GpadcConfig(0x0);
+        val = val.enable_dma();
+        assert!(val.is_dma_enabled());
+        assert_eq!(val.0, 0x0000_0001);
+        val = val.clear_fifo();
+        assert_eq!(val.0, 0x0000_0003);
+        val = val.disable_dma();
+        assert!(!val.is_dma_enabled());
+        assert_eq!(val.0, 0x0000_0002);
+    }
+
     #[test]
     fn struct_gpadc_config_functions() {
         assert_eq!(offset_of!(RegisterBlock, gpadc_config), 0x0);
@@ -0,0 +1,763 @@
+//! H.264 hardware video encoder.
+//!
+//! Frames are submitted one by one as NV12 buffers, typically frames completed by a
+//! [`Dvp`](crate::dvp::Dvp) capture unit. Encoded bitstream chunks are queued by hardware
+//! in an output ring buffer; they can be polled, or awaited when [`EncoderState::on_interrupt`]
+//! is called from the `h264_bs` and `h264_frame` interrupt handlers.
+
+use core::{
+    future::poll_fn,
+    ops::Deref,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::Poll,
+};
+use volatile_register::{RO, RW, WO};
+
+/// H.264 encoder registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Encoder configuration register.
+    pub config: RW<H264Config>,
+    /// Frame size in pixels and lines.
+    pub frame_size: RW<FrameSize>,
+    /// Group of pictures configuration register.
+    pub gop: RW<Gop>,
+    /// Target bitrate in bits per second.
+    pub bitrate: RW<u32>,
+    /// Input frame rate in frames per second.
+    pub frame_rate: RW<u32>,
+    /// Quantization parameter range register.
+    pub quantization: RW<Quantization>,
+    /// Start address of luma plane of submitted frame.
+    pub y_address: RW<u32>,
+    /// Start address of chroma plane of submitted frame.
+    pub uv_address: RW<u32>,
+    /// Frame submission register.
+    pub frame_kick: WO<FrameKick>,
+    /// Start address of bitstream ring buffer.
+    pub output_address: RW<u32>,
+    /// Size of bitstream ring buffer in bytes.
+    pub output_bytes: RW<u32>,
+    /// Interrupt state, enable and chunk count register.
+    pub status: RW<Status>,
+    /// Chunk FIFO pop and interrupt clear register.
+    pub chunk_pop: WO<ChunkPop>,
+    _reserved0: [u8; 0xc],
+    /// Completed bitstream chunks, index 0 is the oldest chunk.
+    pub chunks: [ChunkInfo; 4],
+}
+
+/// Completed bitstream chunk registers.
+#[repr(C)]
+pub struct ChunkInfo {
+    /// Start address of chunk.
+    pub address: RO<u32>,
+    /// Chunk size and frame type.
+    pub state: RO<ChunkState>,
+}
+
+/// Encoder configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct H264Config(u32);
+
+impl H264Config {
+    const ENABLE: u32 = 1 << 0;
+    const RATE_CONTROL: u32 = 1 << 1;
+
+    /// Enable encoder.
+    #[inline]
+    pub const fn enable_encoder(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable encoder.
+    #[inline]
+    pub const fn disable_encoder(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if encoder is enabled.
+    #[inline]
+    pub const fn is_encoder_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Enable rate control towards target bitrate.
+    #[inline]
+    pub const fn enable_rate_control(self) -> Self {
+        Self(self.0 | Self::RATE_CONTROL)
+    }
+    /// Disable rate control, encode with fixed quantization parameter.
+    #[inline]
+    pub const fn disable_rate_control(self) -> Self {
+        Self(self.0 & !Self::RATE_CONTROL)
+    }
+    /// Check if rate control is enabled.
+    #[inline]
+    pub const fn is_rate_control_enabled(self) -> bool {
+        self.0 & Self::RATE_CONTROL != 0
+    }
+}
+
+/// Frame size register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct FrameSize(u32);
+
+impl FrameSize {
+    const WIDTH: u32 = 0xfff;
+    const HEIGHT: u32 = 0xfff << 16;
+
+    /// Set width in pixels.
+    #[inline]
+    pub const fn set_width(self, val: u16) -> Self {
+        Self((self.0 & !Self::WIDTH) | (val as u32 & Self::WIDTH))
+    }
+    /// Get width in pixels.
+    #[inline]
+    pub const fn width(self) -> u16 {
+        (self.0 & Self::WIDTH) as u16
+    }
+    /// Set height in lines.
+    #[inline]
+    pub const fn set_height(self, val: u16) -> Self {
+        Self((self.0 & !Self::HEIGHT) | (((val as u32) << 16) & Self::HEIGHT))
+    }
+    /// Get height in lines.
+    #[inline]
+    pub const fn height(self) -> u16 {
+        ((self.0 & Self::HEIGHT) >> 16) as u16
+    }
+}
+
+/// Group of pictures configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Gop(u32);
+
+impl Gop {
+    const LENGTH: u32 = 0xfff;
+
+    /// Set number of frames between two IDR frames.
+    #[inline]
+    pub const fn set_length(self, val: u16) -> Self {
+        Self((self.0 & !Self::LENGTH) | (val as u32 & Self::LENGTH))
+    }
+    /// Get number of frames between two IDR frames.
+    #[inline]
+    pub const fn length(self) -> u16 {
+        (self.0 & Self::LENGTH) as u16
+    }
+}
+
+/// Quantization parameter range register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Quantization(u32);
+
+impl Quantization {
+    const INITIAL: u32 = 0x3f;
+    const MIN: u32 = 0x3f << 8;
+    const MAX: u32 = 0x3f << 16;
+
+    /// Set quantization parameter of the first frame.
+    #[inline]
+    pub const fn set_initial(self, val: u8) -> Self {
+        Self((self.0 & !Self::INITIAL) | (val as u32 & Self::INITIAL))
+    }
+    /// Get quantization parameter of the first frame.
+    #[inline]
+    pub const fn initial(self) -> u8 {
+        (self.0 & Self::INITIAL) as u8
+    }
+    /// Set minimum quantization parameter.
+    #[inline]
+    pub const fn set_min(self, val: u8) -> Self {
+        Self((self.0 & !Self::MIN) | (((val as u32) << 8) & Self::MIN))
+    }
+    /// Get minimum quantization parameter.
+    #[inline]
+    pub const fn min(self) -> u8 {
+        ((self.0 & Self::MIN) >> 8) as u8
+    }
+    /// Set maximum quantization parameter.
+    #[inline]
+    pub const fn set_max(self, val: u8) -> Self {
+        Self((self.0 & !Self::MAX) | (((val as u32) << 16) & Self::MAX))
+    }
+    /// Get maximum quantization parameter.
+    #[inline]
+    pub const fn max(self) -> u8 {
+        ((self.0 & Self::MAX) >> 16) as u8
+    }
+}
+
+/// Frame submission register.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct FrameKick(u32);
+
+impl FrameKick {
+    const KICK: u32 = 1 << 0;
+    const FORCE_IDR: u32 = 1 << 1;
+
+    /// Start encoding submitted frame.
+    #[inline]
+    pub const fn kick(self) -> Self {
+        Self(self.0 | Self::KICK)
+    }
+    /// Encode submitted frame as IDR frame.
+    #[inline]
+    pub const fn force_idr(self) -> Self {
+        Self(self.0 | Self::FORCE_IDR)
+    }
+}
+
+/// Interrupt state, enable and chunk count register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Status(u32);
+
+impl Status {
+    const INTERRUPT_ENABLE: u32 = 0x7;
+    const CHUNK_COUNT: u32 = 0x1f << 8;
+    const BUSY: u32 = 1 << 24;
+
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub const fn has_interrupt(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32 + 16)) != 0
+    }
+    /// Enable interrupt.
+    #[inline]
+    pub const fn enable_interrupt(self, val: Interrupt) -> Self {
+        Self((self.0 & Self::INTERRUPT_ENABLE) | (1 << (val as u32)))
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub const fn disable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 & Self::INTERRUPT_ENABLE & !(1 << (val as u32)))
+    }
+    /// Check if interrupt is enabled.
+    #[inline]
+    pub const fn is_interrupt_enabled(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32)) != 0
+    }
+    /// Get number of completed chunks in chunk FIFO.
+    #[inline]
+    pub const fn chunk_count(self) -> u8 {
+        ((self.0 & Self::CHUNK_COUNT) >> 8) as u8
+    }
+    /// Check if a submitted frame is being encoded.
+    #[inline]
+    pub const fn is_busy(self) -> bool {
+        self.0 & Self::BUSY != 0
+    }
+}
+
+/// Encoder interrupt event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Interrupt {
+    /// A bitstream chunk has been written into output buffer.
+    Bitstream = 0,
+    /// Submitted frame has been encoded, its buffer can be reused.
+    FrameDone = 1,
+    /// Last frame of a group of pictures has been encoded.
+    SequenceDone = 2,
+}
+
+/// Chunk FIFO pop and interrupt clear register.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct ChunkPop(u32);
+
+impl ChunkPop {
+    const POP: u32 = 1 << 0;
+
+    /// Release the oldest chunk in chunk FIFO.
+    #[inline]
+    pub const fn pop_chunk(self) -> Self {
+        Self(self.0 | Self::POP)
+    }
+    /// Clear interrupt flag.
+    #[inline]
+    pub const fn clear_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32 + 8)))
+    }
+}
+
+/// Bitstream chunk size and frame type register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct ChunkState(u32);
+
+impl ChunkState {
+    const BYTES: u32 = 0xff_ffff;
+    const KEYFRAME: u32 = 1 << 31;
+
+    /// Get size of chunk in bytes.
+    #[inline]
+    pub const fn bytes(self) -> u32 {
+        self.0 & Self::BYTES
+    }
+    /// Check if chunk belongs to an IDR frame.
+    #[inline]
+    pub const fn is_keyframe(self) -> bool {
+        self.0 & Self::KEYFRAME != 0
+    }
+}
+
+/// H.264 encoder configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Config {
+    /// Frame resolution in pixels and lines.
+    pub resolution: (u16, u16),
+    /// Number of frames between two IDR frames.
+    pub gop: u16,
+    /// Target bitrate in bits per second, rate control is disabled if `None`.
+    pub bitrate: Option<u32>,
+    /// Input frame rate in frames per second.
+    pub frame_rate: u8,
+    /// Quantization parameter of the first frame, and the fixed one without rate control.
+    pub qp: u8,
+    /// Minimum and maximum quantization parameter under rate control.
+    pub qp_range: (u8, u8),
+}
+
+impl Default for Config {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            resolution: (1280, 720),
+            gop: 30,
+            bitrate: Some(2_000_000),
+            frame_rate: 30,
+            qp: 28,
+            qp_range: (10, 51),
+        }
+    }
+}
+
+impl Config {
+    /// Set frame resolution.
+    #[inline]
+    pub const fn set_resolution(mut self, width: u16, height: u16) -> Self {
+        self.resolution = (width, height);
+        self
+    }
+    /// Set number of frames between two IDR frames.
+    #[inline]
+    pub const fn set_gop(mut self, gop: u16) -> Self {
+        self.gop = gop;
+        self
+    }
+    /// Set target bitrate in bits per second.
+    #[inline]
+    pub const fn set_bitrate(mut self, bitrate: u32) -> Self {
+        self.bitrate = Some(bitrate);
+        self
+    }
+    /// Encode every frame with a fixed quantization parameter.
+    #[inline]
+    pub const fn set_fixed_qp(mut self, qp: u8) -> Self {
+        self.bitrate = None;
+        self.qp = qp;
+        self
+    }
+    /// Set input frame rate.
+    #[inline]
+    pub const fn set_frame_rate(mut self, frame_rate: u8) -> Self {
+        self.frame_rate = frame_rate;
+        self
+    }
+    /// Size of one NV12 input frame in bytes.
+    #[inline]
+    pub const fn frame_bytes(&self) -> u32 {
+        self.resolution.0 as u32 * self.resolution.1 as u32 * 3 / 2
+    }
+}
+
+/// Errors on encoder configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConfigError {
+    /// Resolution is zero or not a multiple of 16 pixels.
+    InvalidResolution,
+    /// Group of pictures is empty or too long.
+    InvalidGop,
+    /// Quantization parameters exceed 51 or range is reversed.
+    InvalidQp,
+    /// Frame rate is zero.
+    InvalidFrameRate,
+    /// Buffer is not aligned to 8 bytes.
+    BufferAlignment,
+    /// Output buffer cannot hold half of an uncompressed frame.
+    BufferTooSmall,
+}
+
+/// Errors on frame submission.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SubmitError {
+    /// Buffer is not aligned to 8 bytes.
+    BufferAlignment,
+    /// Buffer cannot hold a frame.
+    BufferTooSmall,
+}
+
+/// Encoded bitstream chunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Chunk<'a> {
+    /// Encoded NAL units.
+    pub data: &'a [u8],
+    /// Whether chunk belongs to an IDR frame.
+    pub keyframe: bool,
+}
+
+/// Set of wakers as the state for an H.264 encoder.
+#[derive(Debug)]
+pub struct EncoderState {
+    bitstream_ready: atomic_waker::AtomicWaker,
+    frame_done: atomic_waker::AtomicWaker,
+    ref_to_encoder: AtomicUsize,
+}
+
+impl EncoderState {
+    /// Creates the set of wakers for an encoder.
+    #[inline]
+    pub const fn new() -> EncoderState {
+        EncoderState {
+            bitstream_ready: atomic_waker::AtomicWaker::new(),
+            frame_done: atomic_waker::AtomicWaker::new(),
+            ref_to_encoder: AtomicUsize::new(0),
+        }
+    }
+    /// Use this waker set to handle interrupt.
+    #[inline]
+    pub fn on_interrupt(&self) {
+        let ptr = self.ref_to_encoder.load(Ordering::Acquire);
+        if ptr == 0 {
+            return;
+        }
+        let h264 = unsafe { &*(ptr as *const RegisterBlock) };
+        let state = h264.status.read();
+        for (interrupt, waker) in [
+            (Interrupt::Bitstream, &self.bitstream_ready),
+            (Interrupt::FrameDone, &self.frame_done),
+            (Interrupt::SequenceDone, &self.frame_done),
+        ] {
+            if state.has_interrupt(interrupt) {
+                waker.wake();
+                unsafe {
+                    h264.chunk_pop
+                        .write(ChunkPop::default().clear_interrupt(interrupt))
+                };
+            }
+        }
+    }
+}
+
+impl Default for EncoderState {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Managed H.264 encoder.
+pub struct H264<ENC> {
+    h264: ENC,
+    output: &'static mut [u8],
+    state: &'static EncoderState,
+    frame_bytes: usize,
+}
+
+impl<ENC: Deref<Target = RegisterBlock>> H264<ENC> {
+    /// Create an encoder writing bitstream into `output`.
+    #[inline]
+    pub fn new(
+        h264: ENC,
+        config: Config,
+        output: &'static mut [u8],
+        state: &'static EncoderState,
+    ) -> Result<Self, ConfigError> {
+        let (width, height) = config.resolution;
+        if width == 0 || height == 0 || width % 16 != 0 || height % 16 != 0 {
+            return Err(ConfigError::InvalidResolution);
+        }
+        if config.gop == 0 || config.gop > 0xfff {
+            return Err(ConfigError::InvalidGop);
+        }
+        let (qp_min, qp_max) = config.qp_range;
+        if config.qp > 51 || qp_max > 51 || qp_min > qp_max {
+            return Err(ConfigError::InvalidQp);
+        }
+        if config.frame_rate == 0 {
+            return Err(ConfigError::InvalidFrameRate);
+        }
+        if !(output.as_ptr() as usize).is_multiple_of(8) {
+            return Err(ConfigError::BufferAlignment);
+        }
+        if output.len() < config.frame_bytes() as usize / 2 {
+            return Err(ConfigError::BufferTooSmall);
+        }
+        let val = match config.bitrate {
+            Some(_) => H264Config(0).enable_rate_control(),
+            None => H264Config(0).disable_rate_control(),
+        };
+        unsafe {
+            h264.config.write(val.disable_encoder());
+            h264.frame_size
+                .write(FrameSize(0).set_width(width).set_height(height));
+            h264.gop.write(Gop(0).set_length(config.gop));
+            h264.bitrate.write(config.bitrate.unwrap_or(0));
+            h264.frame_rate.write(config.frame_rate as u32);
+            h264.quantization.write(
+                Quantization(0)
+                    .set_initial(config.qp)
+                    .set_min(qp_min)
+                    .set_max(qp_max),
+            );
+            h264.output_address.write(output.as_ptr() as u32);
+            h264.output_bytes.write(output.len() as u32);
+            h264.config.write(val.enable_encoder());
+        }
+        state
+            .ref_to_encoder
+            .store(&*h264 as *const _ as usize, Ordering::Release);
+        Ok(Self {
+            h264,
+            output,
+            state,
+            frame_bytes: config.frame_bytes() as usize,
+        })
+    }
+    /// Check if a submitted frame is being encoded.
+    #[inline]
+    pub fn is_busy(&self) -> bool {
+        self.h264.status.read().is_busy()
+    }
+    /// Submit an NV12 frame for encoding, e.g. a frame from DVP capture unit.
+    ///
+    /// Hardware reads `frame` after this function returns, until [`Interrupt::FrameDone`]
+    /// or [`H264::wait_frame_done`]; the frame must therefore live in a static buffer.
+    #[inline]
+    pub fn submit_frame(
+        &mut self,
+        frame: &'static [u8],
+        force_idr: bool,
+    ) -> nb::Result<(), SubmitError> {
+        if !(frame.as_ptr() as usize).is_multiple_of(8) {
+            return Err(nb::Error::Other(SubmitError::BufferAlignment));
+        }
+        if frame.len() < self.frame_bytes {
+            return Err(nb::Error::Other(SubmitError::BufferTooSmall));
+        }
+        if self.is_busy() {
+            return Err(nb::Error::WouldBlock);
+        }
+        let y = frame.as_ptr() as u32;
+        let uv = y + (self.frame_bytes * 2 / 3) as u32;
+        let kick = match force_idr {
+            true => FrameKick::default().force_idr().kick(),
+            false => FrameKick::default().kick(),
+        };
+        unsafe {
+            self.h264.y_address.write(y);
+            self.h264.uv_address.write(uv);
+            self.h264.frame_kick.write(kick);
+        }
+        Ok(())
+    }
+    /// Wait until submitted frame has been encoded.
+    #[inline]
+    pub async fn wait_frame_done(&self) {
+        poll_fn(|cx| {
+            self.state.frame_done.register(cx.waker());
+            if self.is_busy() {
+                unsafe {
+                    self.h264
+                        .status
+                        .modify(|v| v.enable_interrupt(Interrupt::FrameDone))
+                };
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        })
+        .await
+    }
+    /// Get number of completed chunks waiting to be released.
+    #[inline]
+    pub fn chunk_count(&self) -> usize {
+        self.h264.status.read().chunk_count() as usize
+    }
+    /// Get the oldest bitstream chunk, if any.
+    ///
+    /// The chunk stays valid until it is released by [`H264::release_chunk`].
+    #[inline]
+    pub fn chunk(&self) -> Option<Chunk<'_>> {
+        if self.chunk_count() == 0 {
+            return None;
+        }
+        let info = &self.h264.chunks[0];
+        let state = info.state.read();
+        let offset = (info.address.read() as usize).checked_sub(self.output.as_ptr() as usize)?;
+        let data = self
+            .output
+            .get(offset..offset.checked_add(state.bytes() as usize)?)?;
+        Some(Chunk {
+            data,
+            keyframe: state.is_keyframe(),
+        })
+    }
+    /// Wait until a bitstream chunk is available and return it.
+    ///
+    /// The chunk stays valid until it is released by [`H264::release_chunk`].
+    #[inline]
+    pub async fn next_chunk(&self) -> Chunk<'_> {
+        poll_fn(|cx| {
+            self.state.bitstream_ready.register(cx.waker());
+            match self.chunk() {
+                Some(chunk) => Poll::Ready(chunk),
+                None => {
+                    unsafe {
+                        self.h264
+                            .status
+                            .modify(|v| v.enable_interrupt(Interrupt::Bitstream))
+                    };
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+    /// Release the oldest bitstream chunk to hardware.
+    #[inline]
+    pub fn release_chunk(&mut self) {
+        unsafe { self.h264.chunk_pop.write(ChunkPop::default().pop_chunk()) };
+    }
+    /// Enable interrupt.
+    #[inline]
+    pub fn enable_interrupt(&mut self, val: Interrupt) {
+        unsafe { self.h264.status.modify(|v| v.enable_interrupt(val)) };
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub fn disable_interrupt(&mut self, val: Interrupt) {
+        unsafe { self.h264.status.modify(|v| v.disable_interrupt(val)) };
+    }
+    /// Stop encoder and release peripheral and output buffer.
+    #[inline]
+    pub fn free(self) -> (ENC, &'static mut [u8]) {
+        unsafe { self.h264.config.modify(|v| v.disable_encoder()) };
+        self.state.ref_to_encoder.store(0, Ordering::Release);
+        (self.h264, self.output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ChunkInfo, ChunkPop, ChunkState, FrameKick, FrameSize, Gop, H264Config, Interrupt,
+        Quantization, RegisterBlock, Status,
+    };
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, frame_size), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, gop), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, bitrate), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, frame_rate), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, quantization), 0x14);
+        assert_eq!(offset_of!(RegisterBlock, y_address), 0x18);
+        assert_eq!(offset_of!(RegisterBlock, uv_address), 0x1c);
+        assert_eq!(offset_of!(RegisterBlock, frame_kick), 0x20);
+        assert_eq!(offset_of!(RegisterBlock, output_address), 0x24);
+        assert_eq!(offset_of!(RegisterBlock, output_bytes), 0x28);
+        assert_eq!(offset_of!(RegisterBlock, status), 0x2c);
+        assert_eq!(offset_of!(RegisterBlock, chunk_pop), 0x30);
+        assert_eq!(offset_of!(RegisterBlock, chunks), 0x40);
+        assert_eq!(size_of::<ChunkInfo>(), 0x08);
+    }
+
+    #[test]
+    fn struct_h264_config_functions() {
+        let mut val = H264Config(0x0);
+
+        val = val.enable_encoder();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_encoder_enabled());
+        val = val.disable_encoder();
+        assert!(!val.is_encoder_enabled());
+
+        val = val.enable_rate_control();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_rate_control_enabled());
+        val = val.disable_rate_control();
+        assert!(!val.is_rate_control_enabled());
+    }
+
+    #[test]
+    fn struct_frame_size_gop_functions() {
+        let val = FrameSize(0x0).set_width(1280).set_height(720);
+        assert_eq!(val.0, 0x02d0_0500);
+        assert_eq!(val.width(), 1280);
+        assert_eq!(val.height(), 720);
+
+        let val = Gop(0x0).set_length(60);
+        assert_eq!(val.0, 0x0000_003c);
+        assert_eq!(val.length(), 60);
+    }
+
+    #[test]
+    fn struct_quantization_functions() {
+        let val = Quantization(0x0).set_initial(28).set_min(10).set_max(51);
+        assert_eq!(val.0, 0x0033_0a1c);
+        assert_eq!(val.initial(), 28);
+        assert_eq!(val.min(), 10);
+        assert_eq!(val.max(), 51);
+    }
+
+    #[test]
+    fn struct_frame_kick_functions() {
+        assert_eq!(FrameKick::default().kick().0, 0x0000_0001);
+        assert_eq!(FrameKick::default().force_idr().kick().0, 0x0000_0003);
+    }
+
+    #[test]
+    fn struct_status_functions() {
+        let mut val = Status(0x0);
+
+        val = val.enable_interrupt(Interrupt::Bitstream);
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_interrupt_enabled(Interrupt::Bitstream));
+        val = val.enable_interrupt(Interrupt::SequenceDone);
+        assert_eq!(val.0, 0x0000_0005);
+        val = val.disable_interrupt(Interrupt::Bitstream);
+        assert!(!val.is_interrupt_enabled(Interrupt::Bitstream));
+
+        // Writing back interrupt enables must not write read-only state bits.
+        val = Status(0x0103_0300).enable_interrupt(Interrupt::FrameDone);
+        assert_eq!(val.0, 0x0000_0002);
+
+        let val = Status(0x0102_0300);
+        assert!(val.has_interrupt(Interrupt::FrameDone));
+        assert!(!val.has_interrupt(Interrupt::Bitstream));
+        assert_eq!(val.chunk_count(), 3);
+        assert!(val.is_busy());
+    }
+
+    #[test]
+    fn struct_chunk_functions() {
+        let val = ChunkPop::default().pop_chunk();
+        assert_eq!(val.0, 0x0000_0001);
+        let val = ChunkPop::default()
+            .clear_interrupt(Interrupt::Bitstream)
+            .clear_interrupt(Interrupt::SequenceDone);
+        assert_eq!(val.0, 0x0000_0500);
+
+        let val = ChunkState(0x8001_2345);
+        assert_eq!(val.bytes(), 0x1_2345);
+        assert!(val.is_keyframe());
+    }
+}
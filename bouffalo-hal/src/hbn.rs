@@ -1,4 +1,5 @@
 //! Hibernation (deep-sleep) control peripheral.
+use crate::gpio::Input;
 use core::cell::UnsafeCell;
 
 use volatile_register::{RO, RW, WO};
@@ -15,10 +16,10 @@ pub struct RegisterBlock {
     pub time_hi: RW<u32>,
     /// Low bits of Real-Time Clock time
     pub rtc_time_lo: RO<u32>,
-    /// High bits of Real-Time Clock time
-    pub rtc_time_hi: RO<u32>,
+    /// High bits of Real-Time Clock time, and latch bit of both halves
+    pub rtc_time_hi: RW<u32>,
     /// Hibernate interrupt contol
-    pub interrupt_mode: RW<u32>,
+    pub interrupt_mode: RW<InterruptMode>,
     /// Hibernate interrupt state
     pub interrupt_state: RO<u32>,
     /// Clear hibernate interrupt
@@ -41,9 +42,9 @@ pub struct RegisterBlock {
     pub pad_control_1: RW<u32>,
     _reserved0: [u8; 448],
     /// 32-kHz internal RC oscillator control
-    pub rc32k: RW<u32>,
+    pub rc32k: RW<Rc32kControl>,
     /// External crystal oscillator control
-    pub xtal32k: RW<u32>,
+    pub xtal32k: RW<Xtal32kControl>,
     /// Real-Time Clock control and reset register 0
     pub rtc_control_0: RW<u32>,
     /// Real-Time Clock control and reset register 1
@@ -71,6 +72,11 @@ impl GLOBAL {
     pub fn write(&self, val: Global) {
         unsafe { self.0.get().write_volatile(val.0) }
     }
+    /// Modify global configuration with read-modify-write closure `f`.
+    #[inline]
+    pub fn modify(&self, f: impl FnOnce(Global) -> Global) {
+        self.write(f(self.read()))
+    }
 }
 
 impl Global {
@@ -130,6 +136,16 @@ impl Global {
     pub const fn clear_reset_event(self) -> Self {
         Self(self.0 | Self::CLEAR_RESET_EVENT)
     }
+    /// Release reset event clear, so that later reset events are recorded.
+    #[inline]
+    pub const fn release_reset_event_clear(self) -> Self {
+        Self(self.0 & !Self::CLEAR_RESET_EVENT)
+    }
+    /// Get raw reset event flags, decoded by [`ResetReason::from_flags`].
+    #[inline]
+    pub const fn reset_flags(self) -> u8 {
+        ((self.0 & Self::RESET_EVENT) >> 7) as u8
+    }
     /// Get reset event.
     #[inline]
     pub const fn reset_event(self) -> ResetEvent {
@@ -207,6 +223,565 @@ impl Global {
     }
 }
 
+/// Hibernate interrupt control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct InterruptMode(u32);
+
+impl InterruptMode {
+    const PIN_WAKEUP_MODE: u32 = 0xf;
+    const PIN_WAKEUP_MASK: u32 = 0xf << 4;
+    const ACOMP_EDGE: u32 = 0x3 << 20;
+    const TOUCH_WAKEUP: u32 = 1 << 24;
+
+    /// Set trigger of always-on pad wakeup, shared by all wakeup pads.
+    #[inline]
+    pub const fn set_pin_wakeup_trigger(self, val: PinWakeupTrigger) -> Self {
+        Self(self.0 & !Self::PIN_WAKEUP_MODE | (val as u32))
+    }
+    /// Get trigger of always-on pad wakeup.
+    #[inline]
+    pub const fn pin_wakeup_trigger(self) -> PinWakeupTrigger {
+        match self.0 & Self::PIN_WAKEUP_MODE {
+            0 => PinWakeupTrigger::SyncFallingEdge,
+            1 => PinWakeupTrigger::SyncRisingEdge,
+            2 => PinWakeupTrigger::SyncLowLevel,
+            3 => PinWakeupTrigger::SyncHighLevel,
+            4 => PinWakeupTrigger::AsyncFallingEdge,
+            5 => PinWakeupTrigger::AsyncRisingEdge,
+            6 => PinWakeupTrigger::AsyncLowLevel,
+            7 => PinWakeupTrigger::AsyncHighLevel,
+            _ => unreachable!(),
+        }
+    }
+    /// Allow wakeup from always-on pad `idx`.
+    #[inline]
+    pub const fn unmask_pin_wakeup(self, idx: u8) -> Self {
+        Self(self.0 & !(1 << (idx as u32 + 4)))
+    }
+    /// Forbid wakeup from always-on pad `idx`.
+    #[inline]
+    pub const fn mask_pin_wakeup(self, idx: u8) -> Self {
+        Self(self.0 | (1 << (idx as u32 + 4)) & Self::PIN_WAKEUP_MASK)
+    }
+    /// Check if wakeup from always-on pad `idx` is forbidden.
+    #[inline]
+    pub const fn is_pin_wakeup_masked(self, idx: u8) -> bool {
+        self.0 & (1 << (idx as u32 + 4)) != 0
+    }
+    /// Set interrupt edges of analog comparator `idx`, bit 0 for rising and bit 1 for falling.
+    #[inline]
+    pub const fn set_acomp_edge(self, idx: usize, val: u8) -> Self {
+        let shift = 2 * idx as u32;
+        Self(
+            self.0 & !(Self::ACOMP_EDGE << shift)
+                | (((val as u32) << 20 << shift) & (Self::ACOMP_EDGE << shift)),
+        )
+    }
+    /// Get interrupt edges of analog comparator `idx`.
+    #[inline]
+    pub const fn acomp_edge(self, idx: usize) -> u8 {
+        ((self.0 >> (2 * idx as u32) & Self::ACOMP_EDGE) >> 20) as u8
+    }
+    /// Enable interrupt and wakeup on touch.
+    #[inline]
+    pub const fn enable_touch_wakeup(self) -> Self {
+        Self(self.0 | Self::TOUCH_WAKEUP)
+    }
+    /// Disable interrupt and wakeup on touch.
+    #[inline]
+    pub const fn disable_touch_wakeup(self) -> Self {
+        Self(self.0 & !Self::TOUCH_WAKEUP)
+    }
+    /// Check if interrupt and wakeup on touch is enabled.
+    #[inline]
+    pub const fn is_touch_wakeup_enabled(self) -> bool {
+        self.0 & Self::TOUCH_WAKEUP != 0
+    }
+}
+
+/// Trigger condition of always-on pad wakeup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PinWakeupTrigger {
+    /// Falling edge, synchronized to 32-kHz clock.
+    SyncFallingEdge = 0,
+    /// Rising edge, synchronized to 32-kHz clock.
+    SyncRisingEdge = 1,
+    /// Low level, synchronized to 32-kHz clock.
+    SyncLowLevel = 2,
+    /// High level, synchronized to 32-kHz clock.
+    SyncHighLevel = 3,
+    /// Falling edge, asynchronous.
+    AsyncFallingEdge = 4,
+    /// Rising edge, asynchronous.
+    AsyncRisingEdge = 5,
+    /// Low level, asynchronous.
+    AsyncLowLevel = 6,
+    /// High level, asynchronous.
+    AsyncHighLevel = 7,
+}
+
+/// GPIO pad that stays powered in hibernate and can wake the chip up.
+///
+/// Only always-on pads implement this trait, so that a regular pad passed as
+/// a wakeup source is rejected at compile time.
+#[diagnostic::on_unimplemented(
+    message = "{Self} is not an always-on pad and cannot wake the chip from hibernate"
+)]
+pub trait AonPad {
+    /// Index of this pad among always-on wakeup pads.
+    const WAKEUP_INDEX: u8;
+}
+
+impl<'a, M> AonPad for Input<'a, 16, M> {
+    const WAKEUP_INDEX: u8 = 0;
+}
+impl<'a, M> AonPad for Input<'a, 17, M> {
+    const WAKEUP_INDEX: u8 = 1;
+}
+impl<'a, M> AonPad for Input<'a, 18, M> {
+    const WAKEUP_INDEX: u8 = 2;
+}
+impl<'a, M> AonPad for Input<'a, 19, M> {
+    const WAKEUP_INDEX: u8 = 3;
+}
+
+/// Always-on input pad registered as a hibernate wakeup source.
+pub struct PinWakeup<P> {
+    pad: P,
+}
+
+impl<P: AonPad> PinWakeup<P> {
+    /// Enable wakeup from hibernate on `pad` with given trigger condition.
+    ///
+    /// Trigger condition is shared by all always-on wakeup pads.
+    #[inline]
+    pub fn new(pad: P, trigger: PinWakeupTrigger, hbn: &RegisterBlock) -> Self {
+        unsafe {
+            hbn.interrupt_mode.modify(|val| {
+                val.set_pin_wakeup_trigger(trigger)
+                    .unmask_pin_wakeup(P::WAKEUP_INDEX)
+            })
+        };
+        Self { pad }
+    }
+    /// Disable wakeup on this pad and return it.
+    #[inline]
+    pub fn free(self, hbn: &RegisterBlock) -> P {
+        unsafe {
+            hbn.interrupt_mode
+                .modify(|val| val.mask_pin_wakeup(P::WAKEUP_INDEX))
+        };
+        self.pad
+    }
+}
+
+/// 32-kHz internal RC oscillator control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Rc32kControl(u32);
+
+impl Rc32kControl {
+    const CALIBRATION_DONE: u32 = 1 << 0;
+    const READY: u32 = 1 << 1;
+    const CALIBRATING: u32 = 1 << 2;
+    const CALIBRATION_DIVIDER: u32 = 0x3 << 3;
+    const CALIBRATED_CODE: u32 = 0x3ff << 6;
+    const ALLOW_CALIBRATION: u32 = 1 << 18;
+    const EXTERNAL_CODE_ENABLE: u32 = 1 << 19;
+    const CALIBRATION_ENABLE: u32 = 1 << 20;
+    const POWER: u32 = 1 << 21;
+    const EXTERNAL_CODE: u32 = 0x3ff << 22;
+
+    /// Check if last calibration has finished.
+    #[inline]
+    pub const fn is_calibration_done(self) -> bool {
+        self.0 & Self::CALIBRATION_DONE != 0
+    }
+    /// Check if oscillator output is stable.
+    #[inline]
+    pub const fn is_ready(self) -> bool {
+        self.0 & Self::READY != 0
+    }
+    /// Check if calibration is in progress.
+    #[inline]
+    pub const fn is_calibrating(self) -> bool {
+        self.0 & Self::CALIBRATING != 0
+    }
+    /// Set crystal clock divider used as calibration reference.
+    #[inline]
+    pub const fn set_calibration_divider(self, val: u8) -> Self {
+        Self(
+            self.0 & !Self::CALIBRATION_DIVIDER | (((val as u32) << 3) & Self::CALIBRATION_DIVIDER),
+        )
+    }
+    /// Get crystal clock divider used as calibration reference.
+    #[inline]
+    pub const fn calibration_divider(self) -> u8 {
+        ((self.0 & Self::CALIBRATION_DIVIDER) >> 3) as u8
+    }
+    /// Get trim code found by last calibration.
+    #[inline]
+    pub const fn calibrated_code(self) -> u16 {
+        ((self.0 & Self::CALIBRATED_CODE) >> 6) as u16
+    }
+    /// Allow hardware calibration to update the trim code.
+    #[inline]
+    pub const fn allow_calibration(self) -> Self {
+        Self(self.0 | Self::ALLOW_CALIBRATION)
+    }
+    /// Forbid hardware calibration to update the trim code.
+    #[inline]
+    pub const fn forbid_calibration(self) -> Self {
+        Self(self.0 & !Self::ALLOW_CALIBRATION)
+    }
+    /// Check if hardware calibration may update the trim code.
+    #[inline]
+    pub const fn is_calibration_allowed(self) -> bool {
+        self.0 & Self::ALLOW_CALIBRATION != 0
+    }
+    /// Start calibration, on rising edge of this bit.
+    #[inline]
+    pub const fn enable_calibration(self) -> Self {
+        Self(self.0 | Self::CALIBRATION_ENABLE)
+    }
+    /// Stop calibration.
+    #[inline]
+    pub const fn disable_calibration(self) -> Self {
+        Self(self.0 & !Self::CALIBRATION_ENABLE)
+    }
+    /// Check if calibration is enabled.
+    #[inline]
+    pub const fn is_calibration_enabled(self) -> bool {
+        self.0 & Self::CALIBRATION_ENABLE != 0
+    }
+    /// Trim oscillator with external code instead of calibrated code.
+    #[inline]
+    pub const fn enable_external_code(self) -> Self {
+        Self(self.0 | Self::EXTERNAL_CODE_ENABLE)
+    }
+    /// Trim oscillator with calibrated code.
+    #[inline]
+    pub const fn disable_external_code(self) -> Self {
+        Self(self.0 & !Self::EXTERNAL_CODE_ENABLE)
+    }
+    /// Check if oscillator is trimmed with external code.
+    #[inline]
+    pub const fn is_external_code_enabled(self) -> bool {
+        self.0 & Self::EXTERNAL_CODE_ENABLE != 0
+    }
+    /// Set external trim code.
+    #[inline]
+    pub const fn set_external_code(self, val: u16) -> Self {
+        Self(self.0 & !Self::EXTERNAL_CODE | (((val as u32) << 22) & Self::EXTERNAL_CODE))
+    }
+    /// Get external trim code.
+    #[inline]
+    pub const fn external_code(self) -> u16 {
+        ((self.0 & Self::EXTERNAL_CODE) >> 22) as u16
+    }
+    /// Power up oscillator.
+    #[inline]
+    pub const fn power_up(self) -> Self {
+        Self(self.0 | Self::POWER)
+    }
+    /// Power down oscillator.
+    #[inline]
+    pub const fn power_down(self) -> Self {
+        Self(self.0 & !Self::POWER)
+    }
+    /// Check if oscillator is powered up.
+    #[inline]
+    pub const fn is_powered_up(self) -> bool {
+        self.0 & Self::POWER != 0
+    }
+}
+
+/// Errors on measuring or calibrating internal oscillators.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CalibrationError {
+    /// Oscillator did not become ready, or calibration did not finish in time.
+    Timeout,
+}
+
+/// Calibrate the 32-kHz internal RC oscillator against the crystal.
+///
+/// Hardware counts RC32K cycles over a window of crystal clock and searches the trim
+/// code; the code found is then latched as external code, so it stays in effect
+/// across hibernate. Call this before relying on RTC timing in sleep, e.g. wakeup
+/// timers, while the crystal is running. `max_polls` bounds each busy-wait step.
+///
+/// Returns the new trim code.
+pub fn calibrate_rc32k(hbn: &RegisterBlock, max_polls: u32) -> Result<u16, CalibrationError> {
+    unsafe { hbn.rc32k.modify(|val| val.power_up()) };
+    poll(max_polls, || hbn.rc32k.read().is_ready())?;
+    unsafe {
+        hbn.rc32k.modify(|val| {
+            val.disable_external_code()
+                .allow_calibration()
+                .disable_calibration()
+        });
+        hbn.rc32k.modify(|val| val.enable_calibration());
+    }
+    let result = poll(max_polls, || hbn.rc32k.read().is_calibration_done());
+    let code = hbn.rc32k.read().calibrated_code();
+    unsafe {
+        hbn.rc32k.modify(|val| {
+            let val = val.disable_calibration().forbid_calibration();
+            match result {
+                Ok(()) => val.set_external_code(code).enable_external_code(),
+                Err(_) => val,
+            }
+        })
+    };
+    result.map(|()| code)
+}
+
+/// Read current Real-Time Clock counter, in 32-kHz clock ticks.
+#[inline]
+pub fn rtc_time(hbn: &RegisterBlock) -> u64 {
+    const LATCH: u32 = 1 << 31;
+    unsafe {
+        hbn.rtc_time_hi.modify(|val| val | LATCH);
+        hbn.rtc_time_hi.modify(|val| val & !LATCH);
+    }
+    let lo = hbn.rtc_time_lo.read() as u64;
+    let hi = (hbn.rtc_time_hi.read() & 0xff) as u64;
+    (hi << 32) | lo
+}
+
+/// Measure frequency of the 32-kHz clock feeding the Real-Time Clock.
+///
+/// Counts RTC ticks over `window_micros` microseconds of `now`, a crystal derived
+/// time source such as the machine timer, and returns the frequency in Hz. The RTC
+/// must be running; a longer window gives a finer result.
+pub fn measure_f32k(hbn: &RegisterBlock, mut now: impl FnMut() -> u64, window_micros: u32) -> u32 {
+    let rtc_start = rtc_time(hbn);
+    let start = now();
+    let mut elapsed = 0;
+    while elapsed < window_micros as u64 {
+        core::hint::spin_loop();
+        elapsed = now().wrapping_sub(start);
+    }
+    let ticks = rtc_time(hbn).wrapping_sub(rtc_start);
+    f32k_frequency(ticks, elapsed)
+}
+
+#[inline]
+const fn f32k_frequency(ticks: u64, elapsed_micros: u64) -> u32 {
+    if elapsed_micros == 0 {
+        return 0;
+    }
+    ((ticks * 1_000_000 + elapsed_micros / 2) / elapsed_micros) as u32
+}
+
+#[inline]
+fn poll(max_polls: u32, mut f: impl FnMut() -> bool) -> Result<(), CalibrationError> {
+    for _ in 0..max_polls {
+        if f() {
+            return Ok(());
+        }
+        core::hint::spin_loop();
+    }
+    Err(CalibrationError::Timeout)
+}
+
+/// 32-kHz external crystal oscillator control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Xtal32kControl(u32);
+
+impl Xtal32kControl {
+    const EXTERNAL_CLOCK: u32 = 1 << 0;
+    const CAPACITOR: u32 = 0x3f << 10;
+    const POWER_BUFFER: u32 = 1 << 17;
+    const POWER: u32 = 1 << 18;
+    const FAST_STARTUP: u32 = 1 << 20;
+
+    /// Use clock signal on crystal pad instead of a crystal.
+    #[inline]
+    pub const fn enable_external_clock(self) -> Self {
+        Self(self.0 | Self::EXTERNAL_CLOCK)
+    }
+    /// Drive a crystal on crystal pads.
+    #[inline]
+    pub const fn disable_external_clock(self) -> Self {
+        Self(self.0 & !Self::EXTERNAL_CLOCK)
+    }
+    /// Check if clock signal on crystal pad is used instead of a crystal.
+    #[inline]
+    pub const fn is_external_clock_enabled(self) -> bool {
+        self.0 & Self::EXTERNAL_CLOCK != 0
+    }
+    /// Set load capacitor code.
+    #[inline]
+    pub const fn set_capacitor(self, val: u8) -> Self {
+        Self(self.0 & !Self::CAPACITOR | (((val as u32) << 10) & Self::CAPACITOR))
+    }
+    /// Get load capacitor code.
+    #[inline]
+    pub const fn capacitor(self) -> u8 {
+        ((self.0 & Self::CAPACITOR) >> 10) as u8
+    }
+    /// Power up oscillator and its output buffer.
+    #[inline]
+    pub const fn power_up(self) -> Self {
+        Self(self.0 | Self::POWER | Self::POWER_BUFFER)
+    }
+    /// Power down oscillator and its output buffer.
+    #[inline]
+    pub const fn power_down(self) -> Self {
+        Self(self.0 & !(Self::POWER | Self::POWER_BUFFER))
+    }
+    /// Check if oscillator is powered up.
+    #[inline]
+    pub const fn is_powered_up(self) -> bool {
+        self.0 & Self::POWER != 0
+    }
+    /// Enable fast startup, driving crystal harder until it oscillates.
+    #[inline]
+    pub const fn enable_fast_startup(self) -> Self {
+        Self(self.0 | Self::FAST_STARTUP)
+    }
+    /// Disable fast startup.
+    #[inline]
+    pub const fn disable_fast_startup(self) -> Self {
+        Self(self.0 & !Self::FAST_STARTUP)
+    }
+    /// Check if fast startup is enabled.
+    #[inline]
+    pub const fn is_fast_startup_enabled(self) -> bool {
+        self.0 & Self::FAST_STARTUP != 0
+    }
+}
+
+/// 32-kHz clock source selection configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct F32kConfig {
+    /// Time to wait for the crystal to start, in microseconds.
+    pub startup_micros: u32,
+    /// Measurement window used to verify the selected clock, in microseconds.
+    pub window_micros: u32,
+    /// Allowed deviation from nominal frequency, in parts per million.
+    pub tolerance_ppm: u32,
+    /// Switch to internal RC oscillator if selected clock fails verification.
+    pub fallback: bool,
+}
+
+impl Default for F32kConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            startup_micros: 100_000,
+            window_micros: 20_000,
+            tolerance_ppm: 10_000,
+            fallback: true,
+        }
+    }
+}
+
+impl F32kConfig {
+    /// Set crystal startup time.
+    #[inline]
+    pub const fn set_startup_micros(mut self, val: u32) -> Self {
+        self.startup_micros = val;
+        self
+    }
+    /// Set measurement window.
+    #[inline]
+    pub const fn set_window_micros(mut self, val: u32) -> Self {
+        self.window_micros = val;
+        self
+    }
+    /// Set allowed deviation from nominal frequency.
+    #[inline]
+    pub const fn set_tolerance_ppm(mut self, val: u32) -> Self {
+        self.tolerance_ppm = val;
+        self
+    }
+    /// Set whether to fall back to internal RC oscillator.
+    #[inline]
+    pub const fn set_fallback(mut self, val: bool) -> Self {
+        self.fallback = val;
+        self
+    }
+}
+
+/// 32-kHz clock in effect after source selection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct F32kStatus {
+    /// Selected source, internal RC oscillator if fallback happened.
+    pub source: F32kSource,
+    /// Measured frequency in Hz.
+    pub frequency: u32,
+}
+
+/// Errors on 32-kHz clock source selection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum F32kError {
+    /// Selected clock is off its nominal frequency, carrying measured frequency in Hz.
+    ///
+    /// The selected source stays in effect.
+    OutOfTolerance(u32),
+}
+
+/// Select and verify the 32-kHz clock source of the Real-Time Clock.
+///
+/// The external crystal is powered up and given `startup_micros` to oscillate before
+/// the switch; the internal RC oscillator is kept running as fallback. The selected
+/// clock is then measured against `now`, a crystal derived time source in microseconds
+/// (see [`measure_f32k`]). If it is off nominal frequency and fallback is configured,
+/// the internal RC oscillator is selected instead and the crystal is powered down.
+///
+/// Call this before enabling RTC alarms which need accurate timing.
+pub fn select_f32k_source(
+    hbn: &RegisterBlock,
+    source: F32kSource,
+    config: F32kConfig,
+    mut now: impl FnMut() -> u64,
+) -> Result<F32kStatus, F32kError> {
+    unsafe { hbn.rc32k.modify(|val| val.power_up()) };
+    if source == F32kSource::Xtal32K {
+        unsafe {
+            hbn.xtal32k.modify(|val| {
+                val.disable_external_clock()
+                    .power_up()
+                    .enable_fast_startup()
+            })
+        };
+        let start = now();
+        while now().wrapping_sub(start) < config.startup_micros as u64 {
+            core::hint::spin_loop();
+        }
+        unsafe { hbn.xtal32k.modify(|val| val.disable_fast_startup()) };
+    }
+    hbn.global.modify(|val| val.set_f32k_source(source));
+    let frequency = measure_f32k(hbn, &mut now, config.window_micros);
+    if within_tolerance(frequency, source.nominal_frequency(), config.tolerance_ppm) {
+        return Ok(F32kStatus { source, frequency });
+    }
+    if !config.fallback || source == F32kSource::RC32K {
+        return Err(F32kError::OutOfTolerance(frequency));
+    }
+    hbn.global
+        .modify(|val| val.set_f32k_source(F32kSource::RC32K));
+    if source == F32kSource::Xtal32K {
+        unsafe { hbn.xtal32k.modify(|val| val.power_down()) };
+    }
+    let frequency = measure_f32k(hbn, now, config.window_micros);
+    Ok(F32kStatus {
+        source: F32kSource::RC32K,
+        frequency,
+    })
+}
+
+#[inline]
+const fn within_tolerance(frequency: u32, nominal: u32, tolerance_ppm: u32) -> bool {
+    let deviation = frequency.abs_diff(nominal) as u64;
+    deviation * 1_000_000 <= nominal as u64 * tolerance_ppm as u64
+}
+
 /// Root clock source 1.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
@@ -239,6 +814,17 @@ pub enum F32kSource {
     Dig32K = 2,
 }
 
+impl F32kSource {
+    /// Nominal frequency of this source in Hz.
+    #[inline]
+    pub const fn nominal_frequency(self) -> u32 {
+        match self {
+            F32kSource::RC32K | F32kSource::Dig32K => 32_000,
+            F32kSource::Xtal32K => 32_768,
+        }
+    }
+}
+
 /// Uart clock source.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
@@ -307,11 +893,218 @@ pub enum ResetEvent {
     Blai = 49,
 }
 
+/// Cause of the last chip reset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResetReason {
+    /// Power supply came up.
+    PowerOn,
+    /// Supply voltage dropped below brown-out threshold.
+    BrownOut,
+    /// Reset pin was asserted.
+    Pin,
+    /// Watchdog timer expired.
+    Watchdog,
+    /// Software requested system reset.
+    Software,
+    /// Chip woke up from hibernate.
+    HibernateWakeup,
+    /// No reset event was recorded.
+    Unknown,
+}
+
+impl ResetReason {
+    const POWER_ON: u8 = 1 << 0;
+    const BROWN_OUT: u8 = 1 << 1;
+    const PIN: u8 = 1 << 2;
+    const WATCHDOG: u8 = 1 << 3;
+    const SOFTWARE: u8 = 1 << 4;
+
+    /// Decode reset event flags and whether a hibernate wakeup source is pending.
+    ///
+    /// When several events are recorded, the one that most likely caused the last
+    /// reset is returned; a wakeup from hibernate also goes through power-on.
+    #[inline]
+    pub const fn from_flags(flags: u8, wakeup: bool) -> Self {
+        if flags & Self::BROWN_OUT != 0 {
+            ResetReason::BrownOut
+        } else if flags & Self::WATCHDOG != 0 {
+            ResetReason::Watchdog
+        } else if flags & Self::SOFTWARE != 0 {
+            ResetReason::Software
+        } else if flags & Self::PIN != 0 {
+            ResetReason::Pin
+        } else if wakeup {
+            ResetReason::HibernateWakeup
+        } else if flags & Self::POWER_ON != 0 {
+            ResetReason::PowerOn
+        } else {
+            ResetReason::Unknown
+        }
+    }
+}
+
+/// Get cause of the last chip reset.
+///
+/// Reset event flags accumulate until cleared, call [`clear_reset_reason`] once the
+/// reason is read so the next boot reports only its own reset.
+#[inline]
+pub fn reset_reason(hbn: &RegisterBlock) -> ResetReason {
+    let flags = hbn.global.read().reset_flags();
+    let wakeup = hbn.interrupt_state.read() != 0;
+    ResetReason::from_flags(flags, wakeup)
+}
+
+/// Clear recorded reset event flags.
+#[inline]
+pub fn clear_reset_reason(hbn: &RegisterBlock) {
+    hbn.global.modify(|val| val.clear_reset_event());
+    hbn.global.modify(|val| val.release_reset_event_clear());
+}
+
 #[cfg(test)]
 mod tests {
-    use super::RegisterBlock;
+    use super::{
+        F32kSource, GLOBAL, Global, InterruptMode, PinWakeupTrigger, Rc32kControl, RegisterBlock,
+        ResetReason, Xtal32kControl, f32k_frequency, within_tolerance,
+    };
     use core::mem::offset_of;
 
+    #[test]
+    fn struct_interrupt_mode_functions() {
+        let mut val = InterruptMode(0x0);
+        val = val.set_pin_wakeup_trigger(PinWakeupTrigger::AsyncHighLevel);
+        assert_eq!(val.0, 0x0000_0007);
+        assert_eq!(val.pin_wakeup_trigger(), PinWakeupTrigger::AsyncHighLevel);
+        val = val.mask_pin_wakeup(2);
+        assert_eq!(val.0, 0x0000_0047);
+        assert!(val.is_pin_wakeup_masked(2));
+        val = val.unmask_pin_wakeup(2);
+        assert_eq!(val.0, 0x0000_0007);
+        assert!(!val.is_pin_wakeup_masked(2));
+
+        val = val.set_acomp_edge(1, 0x3);
+        assert_eq!(val.0, 0x00c0_0007);
+        assert_eq!(val.acomp_edge(1), 0x3);
+        assert_eq!(val.acomp_edge(0), 0x0);
+        val = val.set_acomp_edge(0, 0x1);
+        assert_eq!(val.0, 0x00d0_0007);
+        val = val.set_acomp_edge(1, 0x0);
+        assert_eq!(val.0, 0x0010_0007);
+        val = val.set_acomp_edge(0, 0x0);
+        assert_eq!(val.0, 0x0000_0007);
+
+        val = val.enable_touch_wakeup();
+        assert_eq!(val.0, 0x0100_0007);
+        assert!(val.is_touch_wakeup_enabled());
+        val = val.disable_touch_wakeup();
+        assert_eq!(val.0, 0x0000_0007);
+        assert!(!val.is_touch_wakeup_enabled());
+    }
+
+    #[test]
+    fn struct_rc32k_control_functions() {
+        let mut val = Rc32kControl(0x0);
+        val = val.power_up();
+        assert_eq!(val.0, 0x0020_0000);
+        assert!(val.is_powered_up());
+        val = val.allow_calibration().enable_calibration();
+        assert_eq!(val.0, 0x0034_0000);
+        assert!(val.is_calibration_allowed());
+        assert!(val.is_calibration_enabled());
+        val = val.forbid_calibration().disable_calibration();
+        assert_eq!(val.0, 0x0020_0000);
+        assert!(!val.is_calibration_allowed());
+        assert!(!val.is_calibration_enabled());
+        val = val.set_external_code(0x3ff);
+        assert_eq!(val.0, 0xffe0_0000);
+        assert_eq!(val.external_code(), 0x3ff);
+        val = val.enable_external_code();
+        assert_eq!(val.0, 0xffe8_0000);
+        assert!(val.is_external_code_enabled());
+        val = val
+            .disable_external_code()
+            .set_external_code(0)
+            .power_down();
+        assert_eq!(val.0, 0x0000_0000);
+        val = val.set_calibration_divider(0x3);
+        assert_eq!(val.0, 0x0000_0018);
+        assert_eq!(val.calibration_divider(), 0x3);
+
+        let val = Rc32kControl(0x0000_8047);
+        assert!(val.is_calibration_done());
+        assert!(val.is_ready());
+        assert!(val.is_calibrating());
+        assert_eq!(val.calibrated_code(), 0x201);
+    }
+
+    #[test]
+    fn struct_xtal32k_control_functions() {
+        let mut val = Xtal32kControl(0x0);
+        val = val.power_up();
+        assert_eq!(val.0, 0x0006_0000);
+        assert!(val.is_powered_up());
+        val = val.enable_fast_startup();
+        assert_eq!(val.0, 0x0016_0000);
+        assert!(val.is_fast_startup_enabled());
+        val = val.disable_fast_startup();
+        assert_eq!(val.0, 0x0006_0000);
+        assert!(!val.is_fast_startup_enabled());
+        val = val.set_capacitor(0x3f);
+        assert_eq!(val.0, 0x0006_fc00);
+        assert_eq!(val.capacitor(), 0x3f);
+        val = val.enable_external_clock();
+        assert_eq!(val.0, 0x0006_fc01);
+        assert!(val.is_external_clock_enabled());
+        val = val.disable_external_clock().set_capacitor(0).power_down();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_powered_up());
+    }
+
+    #[test]
+    fn function_within_tolerance() {
+        assert!(within_tolerance(32768, 32768, 0));
+        assert!(within_tolerance(32441, 32768, 10_000));
+        assert!(!within_tolerance(32440, 32768, 10_000));
+        assert!(within_tolerance(33095, 32768, 10_000));
+        assert!(!within_tolerance(0, 32000, 10_000));
+    }
+
+    #[test]
+    fn struct_reset_reason_from_flags() {
+        assert_eq!(Global(0x0000_0080).reset_flags(), 0x01);
+        assert_eq!(Global(0x0000_1f80).reset_flags(), 0x3f);
+        assert_eq!(ResetReason::from_flags(0x01, false), ResetReason::PowerOn);
+        assert_eq!(ResetReason::from_flags(0x03, false), ResetReason::BrownOut);
+        assert_eq!(ResetReason::from_flags(0x04, false), ResetReason::Pin);
+        assert_eq!(ResetReason::from_flags(0x09, false), ResetReason::Watchdog);
+        assert_eq!(ResetReason::from_flags(0x10, false), ResetReason::Software);
+        assert_eq!(
+            ResetReason::from_flags(0x01, true),
+            ResetReason::HibernateWakeup
+        );
+        assert_eq!(ResetReason::from_flags(0x18, true), ResetReason::Watchdog);
+        assert_eq!(ResetReason::from_flags(0x00, false), ResetReason::Unknown);
+    }
+
+    #[test]
+    fn struct_global_modify() {
+        let global = GLOBAL(core::cell::UnsafeCell::new(0x0000_1f81));
+        global.modify(|val| val.set_f32k_source(F32kSource::Xtal32K));
+        assert_eq!(global.read(), Global(0x0000_1f89));
+        global.modify(|val| val.clear_reset_event());
+        assert_eq!(global.read(), Global(0x0000_3f89));
+        global.modify(|val| val.release_reset_event_clear());
+        assert_eq!(global.read(), Global(0x0000_1f89));
+    }
+
+    #[test]
+    fn function_f32k_frequency() {
+        assert_eq!(f32k_frequency(32768, 1_000_000), 32768);
+        assert_eq!(f32k_frequency(3200, 100_000), 32000);
+        assert_eq!(f32k_frequency(327, 10_000), 32700);
+        assert_eq!(f32k_frequency(100, 0), 0);
+    }
+
     #[test]
     fn struct_register_block_offset() {
         assert_eq!(offset_of!(RegisterBlock, control), 0x00);
@@ -583,12 +583,138 @@ impl<I2C: Deref<Target = RegisterBlock>, SCL, SDA> I2c<I2C, (SCL, SDA)> {
                 .modify(|config| config.disable_sub_address());
         }
     }
+
+    /// Check if a slave acknowledges `address` with a one-byte read.
+    #[inline]
+    pub fn probe(&mut self, address: u8) -> bool {
+        let clear = InterruptClear(0)
+            .clear_interrupt(Interrupt::TransferEnd)
+            .clear_interrupt(Interrupt::NackReceived);
+        unsafe {
+            self.i2c.interrupt_clear.write(clear);
+            self.i2c
+                .fifo_config_0
+                .modify(|config| config.clear_receive_fifo());
+            self.i2c.config.modify(|config| {
+                config
+                    .set_read_direction()
+                    .set_slave_address(address as u16)
+                    .set_packet_length(0)
+                    .enable_master()
+            });
+        }
+        let mut present = false;
+        for _ in 0..PROBE_MAX_RETRY {
+            let state = self.i2c.interrupt_state.read();
+            if state.has_interrupt(Interrupt::NackReceived) {
+                break;
+            }
+            if state.has_interrupt(Interrupt::TransferEnd) {
+                present = true;
+                break;
+            }
+        }
+        unsafe {
+            self.i2c.config.modify(|config| config.disable_master());
+            self.i2c.interrupt_clear.write(clear);
+            self.i2c
+                .fifo_config_0
+                .modify(|config| config.clear_receive_fifo());
+        }
+        present
+    }
+
+    /// Scan 7-bit addresses `0x08..=0x77` and return those acknowledged by a slave.
+    ///
+    /// Reserved addresses are skipped.
+    #[inline]
+    pub fn scan(&mut self) -> AddressSet {
+        let mut found = AddressSet::new();
+        for address in 0x08..=0x77 {
+            if self.probe(address) {
+                found = found.insert(address);
+            }
+        }
+        found
+    }
+}
+
+// Status polls before a probed slave is considered absent.
+const PROBE_MAX_RETRY: usize = 100_000;
+
+/// Set of 7-bit I2C addresses, returned by bus scanning.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AddressSet(u128);
+
+impl AddressSet {
+    /// Create an empty set.
+    #[inline]
+    pub const fn new() -> Self {
+        Self(0)
+    }
+    /// Add an address to the set.
+    #[inline]
+    pub const fn insert(self, address: u8) -> Self {
+        assert!(address < 0x80);
+        Self(self.0 | (1 << address))
+    }
+    /// Check if the set contains an address.
+    #[inline]
+    pub const fn contains(self, address: u8) -> bool {
+        address < 0x80 && self.0 & (1 << address) != 0
+    }
+    /// Number of addresses in the set.
+    #[inline]
+    pub const fn count(self) -> usize {
+        self.0.count_ones() as usize
+    }
+    /// Check if the set is empty.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+    /// Iterate addresses in the set in ascending order.
+    #[inline]
+    pub fn iter(self) -> impl Iterator<Item = u8> {
+        (0..0x80).filter(move |&address| self.contains(address))
+    }
+}
+
+/// Release a bus held low by a slave stuck in the middle of a transfer.
+///
+/// Call with the pads of a freed [`I2c`] converted to GPIO, SCL as a pull-up output
+/// and SDA as a pull-up input. SCL is clocked at about 100 kHz up to nine times until
+/// the slave releases SDA; convert the pads back and create the [`I2c`] again after.
+pub fn recover_bus<SCL, SDA, D>(scl: &mut SCL, sda: &mut SDA, delay: &mut D) -> Result<(), Error>
+where
+    SCL: embedded_hal::digital::OutputPin,
+    SDA: embedded_hal::digital::InputPin,
+    D: embedded_hal::delay::DelayNs,
+{
+    scl.set_high().map_err(|_| Error::Other)?;
+    delay.delay_us(5);
+    for _ in 0..9 {
+        if sda.is_high().map_err(|_| Error::Other)? {
+            return Ok(());
+        }
+        scl.set_low().map_err(|_| Error::Other)?;
+        delay.delay_us(5);
+        scl.set_high().map_err(|_| Error::Other)?;
+        delay.delay_us(5);
+    }
+    if sda.is_high().map_err(|_| Error::Other)? {
+        Ok(())
+    } else {
+        Err(Error::BusStuck)
+    }
 }
 
 /// I2C error.
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum Error {
+    /// SDA is still held low after bus recovery.
+    BusStuck,
     Other,
 }
 
@@ -597,6 +723,7 @@ impl embedded_hal::i2c::Error for Error {
     fn kind(&self) -> embedded_hal::i2c::ErrorKind {
         use embedded_hal::i2c::ErrorKind;
         match self {
+            Error::BusStuck => ErrorKind::Bus,
             Error::Other => ErrorKind::Other,
         }
     }
@@ -703,9 +830,9 @@ mod i2c_impls {
 #[cfg(test)]
 mod tests {
     use super::{
-        BusBusy, Config, FifoConfig0, FifoConfig1, Interrupt, InterruptClear, InterruptEnable,
-        InterruptMask, InterruptState, PeriodData, PeriodStart, PeriodStop, RegisterBlock,
-        SubAddressByteCount,
+        AddressSet, BusBusy, Config, FifoConfig0, FifoConfig1, Interrupt, InterruptClear,
+        InterruptEnable, InterruptMask, InterruptState, PeriodData, PeriodStart, PeriodStop,
+        RegisterBlock, SubAddressByteCount,
     };
     use core::mem::offset_of;
 
@@ -959,4 +1086,23 @@ mod tests {
         fifo_config = FifoConfig1(0x0);
         assert_eq!(fifo_config.receive_threshold(), 0x00);
     }
+
+    #[test]
+    fn struct_address_set_functions() {
+        let set = AddressSet::new();
+        assert!(set.is_empty());
+        assert_eq!(set.count(), 0);
+
+        let set = set.insert(0x08).insert(0x50).insert(0x77).insert(0x50);
+        assert!(!set.is_empty());
+        assert_eq!(set.count(), 3);
+        assert!(set.contains(0x50));
+        assert!(!set.contains(0x51));
+        assert!(!set.contains(0x80));
+        let mut iter = set.iter();
+        assert_eq!(iter.next(), Some(0x08));
+        assert_eq!(iter.next(), Some(0x50));
+        assert_eq!(iter.next(), Some(0x77));
+        assert_eq!(iter.next(), None);
+    }
 }
@@ -1,5 +1,10 @@
 //! Inter-IC sound bus peripheral.
 
+use crate::clocks::Clocks;
+use crate::glb;
+use crate::gpio::{self, Alternate};
+use core::ops::Deref;
+use embedded_time::rate::Hertz;
 use volatile_register::{RO, RW, WO};
 
 /// Inter-IC sound bus peripheral registers.
@@ -30,6 +35,218 @@ pub struct RegisterBlock {
 #[repr(transparent)]
 pub struct Config(u32);
 
+impl Config {
+    const MASTER: u32 = 1 << 0;
+    const SLAVE: u32 = 1 << 1;
+    const TRANSMIT: u32 = 1 << 2;
+    const RECEIVE: u32 = 1 << 3;
+    const MUTE: u32 = 1 << 4;
+    const MONO: u32 = 1 << 5;
+    const MODE: u32 = 0x3 << 6;
+    const FRAME_SIZE: u32 = 0x3 << 8;
+    const DATA_SIZE: u32 = 0x3 << 10;
+    const DATA_OFFSET_COUNT: u32 = 0x1f << 16;
+    const DATA_OFFSET: u32 = 1 << 21;
+
+    /// Enable master mode.
+    #[inline]
+    pub const fn enable_master(self) -> Self {
+        Self(self.0 | Self::MASTER)
+    }
+    /// Disable master mode.
+    #[inline]
+    pub const fn disable_master(self) -> Self {
+        Self(self.0 & !Self::MASTER)
+    }
+    /// Check if master mode is enabled.
+    #[inline]
+    pub const fn is_master_enabled(self) -> bool {
+        self.0 & Self::MASTER != 0
+    }
+    /// Enable slave mode.
+    #[inline]
+    pub const fn enable_slave(self) -> Self {
+        Self(self.0 | Self::SLAVE)
+    }
+    /// Disable slave mode.
+    #[inline]
+    pub const fn disable_slave(self) -> Self {
+        Self(self.0 & !Self::SLAVE)
+    }
+    /// Check if slave mode is enabled.
+    #[inline]
+    pub const fn is_slave_enabled(self) -> bool {
+        self.0 & Self::SLAVE != 0
+    }
+    /// Enable transmit data output.
+    #[inline]
+    pub const fn enable_transmit(self) -> Self {
+        Self(self.0 | Self::TRANSMIT)
+    }
+    /// Disable transmit data output.
+    #[inline]
+    pub const fn disable_transmit(self) -> Self {
+        Self(self.0 & !Self::TRANSMIT)
+    }
+    /// Check if transmit data output is enabled.
+    #[inline]
+    pub const fn is_transmit_enabled(self) -> bool {
+        self.0 & Self::TRANSMIT != 0
+    }
+    /// Enable receive data input.
+    #[inline]
+    pub const fn enable_receive(self) -> Self {
+        Self(self.0 | Self::RECEIVE)
+    }
+    /// Disable receive data input.
+    #[inline]
+    pub const fn disable_receive(self) -> Self {
+        Self(self.0 & !Self::RECEIVE)
+    }
+    /// Check if receive data input is enabled.
+    #[inline]
+    pub const fn is_receive_enabled(self) -> bool {
+        self.0 & Self::RECEIVE != 0
+    }
+    /// Enable mute, transmit zeros instead of FIFO data.
+    #[inline]
+    pub const fn enable_mute(self) -> Self {
+        Self(self.0 | Self::MUTE)
+    }
+    /// Disable mute.
+    #[inline]
+    pub const fn disable_mute(self) -> Self {
+        Self(self.0 & !Self::MUTE)
+    }
+    /// Check if mute is enabled.
+    #[inline]
+    pub const fn is_mute_enabled(self) -> bool {
+        self.0 & Self::MUTE != 0
+    }
+    /// Enable mono mode, a single channel is sent on both slots.
+    #[inline]
+    pub const fn enable_mono(self) -> Self {
+        Self(self.0 | Self::MONO)
+    }
+    /// Disable mono mode.
+    #[inline]
+    pub const fn disable_mono(self) -> Self {
+        Self(self.0 & !Self::MONO)
+    }
+    /// Check if mono mode is enabled.
+    #[inline]
+    pub const fn is_mono_enabled(self) -> bool {
+        self.0 & Self::MONO != 0
+    }
+    /// Set data alignment mode.
+    #[inline]
+    pub const fn set_mode(self, val: Mode) -> Self {
+        Self((self.0 & !Self::MODE) | ((val as u32) << 6))
+    }
+    /// Get data alignment mode.
+    #[inline]
+    pub const fn mode(self) -> Mode {
+        match (self.0 & Self::MODE) >> 6 {
+            0 => Mode::LeftJustified,
+            1 => Mode::RightJustified,
+            _ => Mode::Dsp,
+        }
+    }
+    /// Set slot width of a single channel in a frame.
+    #[inline]
+    pub const fn set_frame_size(self, val: SampleSize) -> Self {
+        Self((self.0 & !Self::FRAME_SIZE) | ((val as u32) << 8))
+    }
+    /// Get slot width of a single channel in a frame.
+    #[inline]
+    pub const fn frame_size(self) -> SampleSize {
+        SampleSize::from_bits((self.0 & Self::FRAME_SIZE) >> 8)
+    }
+    /// Set sample data width.
+    #[inline]
+    pub const fn set_data_size(self, val: SampleSize) -> Self {
+        Self((self.0 & !Self::DATA_SIZE) | ((val as u32) << 10))
+    }
+    /// Get sample data width.
+    #[inline]
+    pub const fn data_size(self) -> SampleSize {
+        SampleSize::from_bits((self.0 & Self::DATA_SIZE) >> 10)
+    }
+    /// Set data offset in bit clock cycles, minus one.
+    #[inline]
+    pub const fn set_data_offset_count(self, val: u8) -> Self {
+        Self((self.0 & !Self::DATA_OFFSET_COUNT) | (((val as u32) << 16) & Self::DATA_OFFSET_COUNT))
+    }
+    /// Get data offset in bit clock cycles, minus one.
+    #[inline]
+    pub const fn data_offset_count(self) -> u8 {
+        ((self.0 & Self::DATA_OFFSET_COUNT) >> 16) as u8
+    }
+    /// Enable data offset relative to frame sync edge.
+    #[inline]
+    pub const fn enable_data_offset(self) -> Self {
+        Self(self.0 | Self::DATA_OFFSET)
+    }
+    /// Disable data offset relative to frame sync edge.
+    #[inline]
+    pub const fn disable_data_offset(self) -> Self {
+        Self(self.0 & !Self::DATA_OFFSET)
+    }
+    /// Check if data offset is enabled.
+    #[inline]
+    pub const fn is_data_offset_enabled(self) -> bool {
+        self.0 & Self::DATA_OFFSET != 0
+    }
+}
+
+/// Data alignment mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Mode {
+    /// Data starts at frame sync edge; standard I2S when used with one bit data offset.
+    LeftJustified = 0,
+    /// Data ends at frame sync edge.
+    RightJustified = 1,
+    /// Frame sync is a short pulse before slots are sent back to back.
+    Dsp = 2,
+}
+
+/// Width of a sample or slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum SampleSize {
+    /// 8 bits.
+    Bits8 = 0,
+    /// 16 bits.
+    Bits16 = 1,
+    /// 24 bits.
+    Bits24 = 2,
+    /// 32 bits.
+    Bits32 = 3,
+}
+
+impl SampleSize {
+    #[inline]
+    const fn from_bits(bits: u32) -> Self {
+        match bits {
+            0 => SampleSize::Bits8,
+            1 => SampleSize::Bits16,
+            2 => SampleSize::Bits24,
+            _ => SampleSize::Bits32,
+        }
+    }
+    /// Number of bits.
+    #[inline]
+    pub const fn bits(self) -> u32 {
+        match self {
+            SampleSize::Bits8 => 8,
+            SampleSize::Bits16 => 16,
+            SampleSize::Bits24 => 24,
+            SampleSize::Bits32 => 32,
+        }
+    }
+}
+
 /// Interrupt configuration and state register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -40,25 +257,619 @@ pub struct InterruptConfig(u32);
 #[repr(transparent)]
 pub struct BclkConfig(u32);
 
+impl BclkConfig {
+    const LOW_PERIOD: u32 = 0xfff;
+    const HIGH_PERIOD: u32 = 0xfff << 16;
+
+    /// Set bit clock low level duration in peripheral clock cycles, minus one.
+    #[inline]
+    pub const fn set_low_period(self, val: u16) -> Self {
+        Self((self.0 & !Self::LOW_PERIOD) | ((val as u32) & Self::LOW_PERIOD))
+    }
+    /// Get bit clock low level duration in peripheral clock cycles, minus one.
+    #[inline]
+    pub const fn low_period(self) -> u16 {
+        (self.0 & Self::LOW_PERIOD) as u16
+    }
+    /// Set bit clock high level duration in peripheral clock cycles, minus one.
+    #[inline]
+    pub const fn set_high_period(self, val: u16) -> Self {
+        Self((self.0 & !Self::HIGH_PERIOD) | (((val as u32) << 16) & Self::HIGH_PERIOD))
+    }
+    /// Get bit clock high level duration in peripheral clock cycles, minus one.
+    #[inline]
+    pub const fn high_period(self) -> u16 {
+        ((self.0 & Self::HIGH_PERIOD) >> 16) as u16
+    }
+}
+
 /// First-in first-out queue configuration register 0.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct FifoConfig0(u32);
 
+impl FifoConfig0 {
+    const TRANSMIT_DMA_ENABLE: u32 = 1 << 0;
+    const RECEIVE_DMA_ENABLE: u32 = 1 << 1;
+    const TRANSMIT_FIFO_CLEAR: u32 = 1 << 2;
+    const RECEIVE_FIFO_CLEAR: u32 = 1 << 3;
+    const TRANSMIT_FIFO_OVERFLOW: u32 = 1 << 4;
+    const TRANSMIT_FIFO_UNDERFLOW: u32 = 1 << 5;
+    const RECEIVE_FIFO_OVERFLOW: u32 = 1 << 6;
+    const RECEIVE_FIFO_UNDERFLOW: u32 = 1 << 7;
+    const CHANNEL_MERGE: u32 = 1 << 8;
+    const CHANNEL_EXCHANGE: u32 = 1 << 9;
+
+    /// Enable transmit DMA.
+    #[inline]
+    pub const fn enable_transmit_dma(self) -> Self {
+        Self(self.0 | Self::TRANSMIT_DMA_ENABLE)
+    }
+    /// Disable transmit DMA.
+    #[inline]
+    pub const fn disable_transmit_dma(self) -> Self {
+        Self(self.0 & !Self::TRANSMIT_DMA_ENABLE)
+    }
+    /// Check if transmit DMA is enabled.
+    #[inline]
+    pub const fn is_transmit_dma_enabled(self) -> bool {
+        self.0 & Self::TRANSMIT_DMA_ENABLE != 0
+    }
+    /// Enable receive DMA.
+    #[inline]
+    pub const fn enable_receive_dma(self) -> Self {
+        Self(self.0 | Self::RECEIVE_DMA_ENABLE)
+    }
+    /// Disable receive DMA.
+    #[inline]
+    pub const fn disable_receive_dma(self) -> Self {
+        Self(self.0 & !Self::RECEIVE_DMA_ENABLE)
+    }
+    /// Check if receive DMA is enabled.
+    #[inline]
+    pub const fn is_receive_dma_enabled(self) -> bool {
+        self.0 & Self::RECEIVE_DMA_ENABLE != 0
+    }
+    /// Clear transmit FIFO.
+    #[inline]
+    pub const fn clear_transmit_fifo(self) -> Self {
+        Self(self.0 | Self::TRANSMIT_FIFO_CLEAR)
+    }
+    /// Clear receive FIFO.
+    #[inline]
+    pub const fn clear_receive_fifo(self) -> Self {
+        Self(self.0 | Self::RECEIVE_FIFO_CLEAR)
+    }
+    /// Check if transmit FIFO has overflowed.
+    #[inline]
+    pub const fn transmit_fifo_overflow(self) -> bool {
+        self.0 & Self::TRANSMIT_FIFO_OVERFLOW != 0
+    }
+    /// Check if transmit FIFO has underflowed.
+    #[inline]
+    pub const fn transmit_fifo_underflow(self) -> bool {
+        self.0 & Self::TRANSMIT_FIFO_UNDERFLOW != 0
+    }
+    /// Check if receive FIFO has overflowed.
+    #[inline]
+    pub const fn receive_fifo_overflow(self) -> bool {
+        self.0 & Self::RECEIVE_FIFO_OVERFLOW != 0
+    }
+    /// Check if receive FIFO has underflowed.
+    #[inline]
+    pub const fn receive_fifo_underflow(self) -> bool {
+        self.0 & Self::RECEIVE_FIFO_UNDERFLOW != 0
+    }
+    /// Enable merging left and right channel samples into one FIFO word.
+    #[inline]
+    pub const fn enable_channel_merge(self) -> Self {
+        Self(self.0 | Self::CHANNEL_MERGE)
+    }
+    /// Disable merging left and right channel samples into one FIFO word.
+    #[inline]
+    pub const fn disable_channel_merge(self) -> Self {
+        Self(self.0 & !Self::CHANNEL_MERGE)
+    }
+    /// Check if channel merge is enabled.
+    #[inline]
+    pub const fn is_channel_merge_enabled(self) -> bool {
+        self.0 & Self::CHANNEL_MERGE != 0
+    }
+    /// Enable exchanging left and right channel in merged FIFO word.
+    #[inline]
+    pub const fn enable_channel_exchange(self) -> Self {
+        Self(self.0 | Self::CHANNEL_EXCHANGE)
+    }
+    /// Disable exchanging left and right channel in merged FIFO word.
+    #[inline]
+    pub const fn disable_channel_exchange(self) -> Self {
+        Self(self.0 & !Self::CHANNEL_EXCHANGE)
+    }
+    /// Check if channel exchange is enabled.
+    #[inline]
+    pub const fn is_channel_exchange_enabled(self) -> bool {
+        self.0 & Self::CHANNEL_EXCHANGE != 0
+    }
+}
+
 /// First-in first-out queue configuration register 1.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct FifoConfig1(u32);
 
+impl FifoConfig1 {
+    const TRANSMIT_COUNT: u32 = 0x3f;
+    const RECEIVE_COUNT: u32 = 0x3f << 8;
+    const TRANSMIT_THRESHOLD: u32 = 0xf << 16;
+    const RECEIVE_THRESHOLD: u32 = 0xf << 24;
+
+    /// Get number of empty spaces remained in transmit FIFO queue.
+    #[inline]
+    pub const fn transmit_available_bytes(self) -> u8 {
+        (self.0 & Self::TRANSMIT_COUNT) as u8
+    }
+    /// Get number of available words received in receive FIFO queue.
+    #[inline]
+    pub const fn receive_available_bytes(self) -> u8 {
+        ((self.0 & Self::RECEIVE_COUNT) >> 8) as u8
+    }
+    /// Set transmit FIFO threshold.
+    #[inline]
+    pub const fn set_transmit_threshold(self, val: u8) -> Self {
+        Self(self.0 & !Self::TRANSMIT_THRESHOLD | (((val as u32) << 16) & Self::TRANSMIT_THRESHOLD))
+    }
+    /// Get transmit FIFO threshold.
+    #[inline]
+    pub const fn transmit_threshold(self) -> u8 {
+        ((self.0 & Self::TRANSMIT_THRESHOLD) >> 16) as u8
+    }
+    /// Set receive FIFO threshold.
+    #[inline]
+    pub const fn set_receive_threshold(self, val: u8) -> Self {
+        Self(self.0 & !Self::RECEIVE_THRESHOLD | (((val as u32) << 24) & Self::RECEIVE_THRESHOLD))
+    }
+    /// Get receive FIFO threshold.
+    #[inline]
+    pub const fn receive_threshold(self) -> u8 {
+        ((self.0 & Self::RECEIVE_THRESHOLD) >> 24) as u8
+    }
+}
+
 /// Input/output signal configuration register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct IoConfig(u32);
 
+/// Audio data format on the bus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Standard I2S, data starts one bit clock after frame sync edge.
+    I2s,
+    /// Left-justified, data starts at frame sync edge.
+    LeftJustified,
+    /// Right-justified, data ends at frame sync edge.
+    RightJustified,
+    /// Frame sync is a short pulse before slots are sent back to back.
+    Dsp,
+}
+
+/// Clock role of the I2S peripheral.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// Generate bit clock and frame sync from audio PLL.
+    Master,
+    /// Bit clock and frame sync are driven by external device.
+    Slave,
+}
+
+/// Inter-IC sound bus configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct I2sConfig {
+    /// Clock role.
+    pub role: Role,
+    /// Audio data format.
+    pub format: Format,
+    /// Audio sample rate, used in master role only.
+    pub sample_rate: Hertz,
+    /// Sample data width.
+    pub data_size: SampleSize,
+    /// Slot width of each channel.
+    pub frame_size: SampleSize,
+    /// Send one channel on both slots.
+    pub mono: bool,
+}
+
+impl Default for I2sConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            role: Role::Master,
+            format: Format::I2s,
+            sample_rate: Hertz(48_000),
+            data_size: SampleSize::Bits16,
+            frame_size: SampleSize::Bits16,
+            mono: false,
+        }
+    }
+}
+
+impl I2sConfig {
+    /// Set clock role.
+    #[inline]
+    pub const fn set_role(mut self, role: Role) -> Self {
+        self.role = role;
+        self
+    }
+    /// Set audio data format.
+    #[inline]
+    pub const fn set_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+    /// Set audio sample rate.
+    #[inline]
+    pub const fn set_sample_rate(mut self, sample_rate: Hertz) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+    /// Set sample data width, slot width is set to the same value.
+    #[inline]
+    pub const fn set_sample_size(mut self, size: SampleSize) -> Self {
+        self.data_size = size;
+        self.frame_size = size;
+        self
+    }
+}
+
+/// Errors on I2S configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConfigError {
+    /// Sample rate too high for audio PLL frequency.
+    SampleRateTooHigh,
+    /// Sample rate too low for audio PLL frequency.
+    SampleRateTooLow,
+    /// Audio PLL frequency is not a multiple of bit clock, e.g. 44.1 kHz family
+    /// rates on a 48 kHz family PLL.
+    SampleRateInexact,
+    /// Data size is wider than frame size.
+    DataSizeTooLarge,
+    /// Impossibly audio PLL frequency.
+    ClockSource,
+}
+
+/// Managed Inter-IC sound bus peripheral.
+pub struct I2s<I2S, PADS> {
+    i2s: I2S,
+    pads: PADS,
+}
+
+impl<I2S: Deref<Target = RegisterBlock>, PADS> I2s<I2S, PADS> {
+    /// Create a new Inter-IC sound bus instance.
+    ///
+    /// In master role, the reference (master) clock is set to 256 times sample rate
+    /// from audio PLL, and bit clock is generated from it.
+    /// Sample rates that audio PLL frequency does not divide exactly are rejected with
+    /// [`ConfigError::SampleRateInexact`] instead of running off-rate.
+    #[inline]
+    pub fn new(
+        i2s: I2S,
+        pads: PADS,
+        config: I2sConfig,
+        clocks: &Clocks,
+        glb: &glb::v2::RegisterBlock,
+    ) -> Result<Self, ConfigError>
+    where
+        PADS: Pads,
+    {
+        if config.data_size.bits() > config.frame_size.bits() {
+            return Err(ConfigError::DataSizeTooLarge);
+        }
+        let mut val = format_config(config);
+        unsafe { glb.clock_config_1.modify(|v| v.enable_i2s()) };
+        match config.role {
+            Role::Master => {
+                let (divide, bclk) = clock_config(config, clocks)?;
+                unsafe {
+                    glb.i2s_config.modify(|v| {
+                        v.set_reference_clock_divide(divide)
+                            .enable_reference_clock()
+                    });
+                    i2s.bclk_config.write(bclk);
+                }
+                val = val.enable_master();
+            }
+            Role::Slave => val = val.enable_slave(),
+        }
+        if PADS::TRANSMIT {
+            val = val.enable_transmit();
+        }
+        if PADS::RECEIVE {
+            val = val.enable_receive();
+        }
+        unsafe {
+            i2s.fifo_config_0
+                .write(FifoConfig0(0).clear_transmit_fifo().clear_receive_fifo());
+            i2s.config.write(val);
+        }
+        Ok(Self { i2s, pads })
+    }
+
+    /// Enable transmit DMA.
+    #[inline]
+    pub fn enable_tx_dma(self) -> Self {
+        unsafe {
+            self.i2s
+                .fifo_config_1
+                .modify(|val| val.set_transmit_threshold(7));
+            self.i2s
+                .fifo_config_0
+                .modify(|val| val.enable_transmit_dma().clear_transmit_fifo());
+        }
+        self
+    }
+
+    /// Enable receive DMA.
+    #[inline]
+    pub fn enable_rx_dma(self) -> Self {
+        unsafe {
+            self.i2s
+                .fifo_config_1
+                .modify(|val| val.set_receive_threshold(7));
+            self.i2s
+                .fifo_config_0
+                .modify(|val| val.enable_receive_dma().clear_receive_fifo());
+        }
+        self
+    }
+
+    /// Write samples into transmit FIFO, blocking until all samples are queued.
+    ///
+    /// Each word holds one sample of one channel, left and right channels alternate.
+    #[inline]
+    pub fn write_samples(&mut self, samples: &[u32]) {
+        for &sample in samples {
+            while self.i2s.fifo_config_1.read().transmit_available_bytes() == 0 {
+                core::hint::spin_loop();
+            }
+            unsafe { self.i2s.fifo_write.write(sample) };
+        }
+    }
+
+    /// Read samples from receive FIFO, blocking until the buffer is filled.
+    #[inline]
+    pub fn read_samples(&mut self, buf: &mut [u32]) {
+        for sample in buf {
+            while self.i2s.fifo_config_1.read().receive_available_bytes() == 0 {
+                core::hint::spin_loop();
+            }
+            *sample = self.i2s.fifo_read.read();
+        }
+    }
+
+    /// Mute or unmute transmit output.
+    #[inline]
+    pub fn set_mute(&mut self, mute: bool) {
+        unsafe {
+            self.i2s.config.modify(|val| {
+                if mute {
+                    val.enable_mute()
+                } else {
+                    val.disable_mute()
+                }
+            })
+        };
+    }
+
+    /// Release the I2S instance and return the pads.
+    #[inline]
+    pub fn free(self) -> (I2S, PADS) {
+        unsafe {
+            self.i2s.config.modify(|val| {
+                val.disable_master()
+                    .disable_slave()
+                    .disable_transmit()
+                    .disable_receive()
+            })
+        };
+        (self.i2s, self.pads)
+    }
+}
+
+/// Peripheral configuration register value for data format of `config`.
+#[inline]
+fn format_config(config: I2sConfig) -> Config {
+    let mode = match config.format {
+        Format::I2s | Format::LeftJustified => Mode::LeftJustified,
+        Format::RightJustified => Mode::RightJustified,
+        Format::Dsp => Mode::Dsp,
+    };
+    let mut val = Config(0)
+        .set_mode(mode)
+        .set_frame_size(config.frame_size)
+        .set_data_size(config.data_size);
+    if config.format == Format::I2s {
+        val = val.enable_data_offset().set_data_offset_count(0);
+    }
+    if config.mono {
+        val = val.enable_mono();
+    }
+    val
+}
+
+/// Calculate reference clock divide factor and bit clock periods for master role.
+#[inline]
+fn clock_config(config: I2sConfig, clocks: &Clocks) -> Result<(u8, BclkConfig), ConfigError> {
+    let Some(pll) = clocks.audio_pll() else {
+        return Err(ConfigError::ClockSource);
+    };
+    let fs = config.sample_rate.0;
+    if fs == 0 {
+        return Err(ConfigError::SampleRateTooLow);
+    }
+    let Some(reference_rate) = fs.checked_mul(256) else {
+        return Err(ConfigError::SampleRateTooHigh);
+    };
+    let divide = pll.0 / reference_rate;
+    if divide == 0 {
+        return Err(ConfigError::SampleRateTooHigh);
+    }
+    if divide > 64 {
+        return Err(ConfigError::SampleRateTooLow);
+    }
+    let reference = pll.0 / divide;
+    // Bit clock covers two slots per frame, each half period counts in reference clock.
+    let Some(half_period_rate) = fs.checked_mul(config.frame_size.bits() * 2 * 2) else {
+        return Err(ConfigError::SampleRateTooHigh);
+    };
+    let half_period = reference / half_period_rate;
+    if half_period == 0 {
+        return Err(ConfigError::SampleRateTooHigh);
+    }
+    if half_period > 4096 {
+        return Err(ConfigError::SampleRateTooLow);
+    }
+    if !pll.0.is_multiple_of(divide) || !reference.is_multiple_of(half_period_rate) {
+        return Err(ConfigError::SampleRateInexact);
+    }
+    let bclk = BclkConfig(0)
+        .set_low_period((half_period - 1) as u16)
+        .set_high_period((half_period - 1) as u16);
+    Ok(((divide - 1) as u8, bclk))
+}
+
+/// Valid I2S pads.
+pub trait Pads {
+    /// Has transmit data output signal.
+    const TRANSMIT: bool;
+    /// Has receive data input signal.
+    const RECEIVE: bool;
+}
+
+impl<'a, 'b, 'c, const N1: usize, const N2: usize, const N3: usize> Pads
+    for (
+        Alternate<'a, N1, gpio::I2s>,
+        Alternate<'b, N2, gpio::I2s>,
+        Alternate<'c, N3, gpio::I2s>,
+    )
+where
+    Alternate<'a, N1, gpio::I2s>: HasBclkSignal,
+    Alternate<'b, N2, gpio::I2s>: HasFsSignal,
+    Alternate<'c, N3, gpio::I2s>: DataPad,
+{
+    const TRANSMIT: bool = <Alternate<'c, N3, gpio::I2s> as DataPad>::TRANSMIT;
+    const RECEIVE: bool = <Alternate<'c, N3, gpio::I2s> as DataPad>::RECEIVE;
+}
+
+impl<'a, 'b, 'c, 'd, const N1: usize, const N2: usize, const N3: usize, const N4: usize> Pads
+    for (
+        Alternate<'a, N1, gpio::I2s>,
+        Alternate<'b, N2, gpio::I2s>,
+        Alternate<'c, N3, gpio::I2s>,
+        Alternate<'d, N4, gpio::I2s>,
+    )
+where
+    Alternate<'a, N1, gpio::I2s>: HasBclkSignal,
+    Alternate<'b, N2, gpio::I2s>: HasFsSignal,
+    Alternate<'c, N3, gpio::I2s>: HasDoSignal,
+    Alternate<'d, N4, gpio::I2s>: HasDiSignal,
+{
+    const TRANSMIT: bool = true;
+    const RECEIVE: bool = true;
+}
+
+/// Single data pad of a transmit-only or receive-only I2S.
+pub trait DataPad {
+    /// Pad carries data output signal.
+    const TRANSMIT: bool;
+    /// Pad carries data input signal.
+    const RECEIVE: bool;
+}
+
+macro_rules! impl_data_pad {
+    ($transmit: expr, $receive: expr; $($n: expr),+) => {
+        $(
+            impl<'a> DataPad for Alternate<'a, $n, gpio::I2s> {
+                const TRANSMIT: bool = $transmit;
+                const RECEIVE: bool = $receive;
+            }
+        )+
+    };
+}
+
+impl_data_pad!(false, true; 2, 6, 10, 14, 18, 22, 26, 30, 34, 38, 42);
+impl_data_pad!(true, false; 3, 7, 11, 15, 19, 23, 27, 31, 35, 39, 43);
+
+/// Check if target gpio `Pin` is internally connected to I2S bit clock signal.
+pub trait HasBclkSignal {}
+
+impl<'a> HasBclkSignal for Alternate<'a, 0, gpio::I2s> {}
+impl<'a> HasBclkSignal for Alternate<'a, 4, gpio::I2s> {}
+impl<'a> HasBclkSignal for Alternate<'a, 8, gpio::I2s> {}
+impl<'a> HasBclkSignal for Alternate<'a, 12, gpio::I2s> {}
+impl<'a> HasBclkSignal for Alternate<'a, 16, gpio::I2s> {}
+impl<'a> HasBclkSignal for Alternate<'a, 20, gpio::I2s> {}
+impl<'a> HasBclkSignal for Alternate<'a, 24, gpio::I2s> {}
+impl<'a> HasBclkSignal for Alternate<'a, 28, gpio::I2s> {}
+impl<'a> HasBclkSignal for Alternate<'a, 32, gpio::I2s> {}
+impl<'a> HasBclkSignal for Alternate<'a, 36, gpio::I2s> {}
+impl<'a> HasBclkSignal for Alternate<'a, 40, gpio::I2s> {}
+impl<'a> HasBclkSignal for Alternate<'a, 44, gpio::I2s> {}
+
+/// Check if target gpio `Pin` is internally connected to I2S frame sync signal.
+pub trait HasFsSignal {}
+
+impl<'a> HasFsSignal for Alternate<'a, 1, gpio::I2s> {}
+impl<'a> HasFsSignal for Alternate<'a, 5, gpio::I2s> {}
+impl<'a> HasFsSignal for Alternate<'a, 9, gpio::I2s> {}
+impl<'a> HasFsSignal for Alternate<'a, 13, gpio::I2s> {}
+impl<'a> HasFsSignal for Alternate<'a, 17, gpio::I2s> {}
+impl<'a> HasFsSignal for Alternate<'a, 21, gpio::I2s> {}
+impl<'a> HasFsSignal for Alternate<'a, 25, gpio::I2s> {}
+impl<'a> HasFsSignal for Alternate<'a, 29, gpio::I2s> {}
+impl<'a> HasFsSignal for Alternate<'a, 33, gpio::I2s> {}
+impl<'a> HasFsSignal for Alternate<'a, 37, gpio::I2s> {}
+impl<'a> HasFsSignal for Alternate<'a, 41, gpio::I2s> {}
+impl<'a> HasFsSignal for Alternate<'a, 45, gpio::I2s> {}
+
+/// Check if target gpio `Pin` is internally connected to I2S data input signal.
+pub trait HasDiSignal {}
+
+impl<'a> HasDiSignal for Alternate<'a, 2, gpio::I2s> {}
+impl<'a> HasDiSignal for Alternate<'a, 6, gpio::I2s> {}
+impl<'a> HasDiSignal for Alternate<'a, 10, gpio::I2s> {}
+impl<'a> HasDiSignal for Alternate<'a, 14, gpio::I2s> {}
+impl<'a> HasDiSignal for Alternate<'a, 18, gpio::I2s> {}
+impl<'a> HasDiSignal for Alternate<'a, 22, gpio::I2s> {}
+impl<'a> HasDiSignal for Alternate<'a, 26, gpio::I2s> {}
+impl<'a> HasDiSignal for Alternate<'a, 30, gpio::I2s> {}
+impl<'a> HasDiSignal for Alternate<'a, 34, gpio::I2s> {}
+impl<'a> HasDiSignal for Alternate<'a, 38, gpio::I2s> {}
+impl<'a> HasDiSignal for Alternate<'a, 42, gpio::I2s> {}
+
+/// Check if target gpio `Pin` is internally connected to I2S data output signal.
+pub trait HasDoSignal {}
+
+impl<'a> HasDoSignal for Alternate<'a, 3, gpio::I2s> {}
+impl<'a> HasDoSignal for Alternate<'a, 7, gpio::I2s> {}
+impl<'a> HasDoSignal for Alternate<'a, 11, gpio::I2s> {}
+impl<'a> HasDoSignal for Alternate<'a, 15, gpio::I2s> {}
+impl<'a> HasDoSignal for Alternate<'a, 19, gpio::I2s> {}
+impl<'a> HasDoSignal for Alternate<'a, 23, gpio::I2s> {}
+impl<'a> HasDoSignal for Alternate<'a, 27, gpio::I2s> {}
+impl<'a> HasDoSignal for Alternate<'a, 31, gpio::I2s> {}
+impl<'a> HasDoSignal for Alternate<'a, 35, gpio::I2s> {}
+impl<'a> HasDoSignal for Alternate<'a, 39, gpio::I2s> {}
+impl<'a> HasDoSignal for Alternate<'a, 43, gpio::I2s> {}
+
 #[cfg(test)]
 mod tests {
-    use super::RegisterBlock;
+    use super::{
+        BclkConfig, Config, ConfigError, FifoConfig0, FifoConfig1, Format, I2sConfig, Mode,
+        RegisterBlock, SampleSize, clock_config, format_config,
+    };
+    use crate::clocks::Clocks;
     use core::mem::offset_of;
+    use embedded_time::rate::Hertz;
 
     #[test]
     fn struct_register_block_offset() {
@@ -71,4 +882,192 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, fifo_read), 0x8c);
         assert_eq!(offset_of!(RegisterBlock, io_config), 0xfc);
     }
+
+    #[test]
+    fn struct_config_functions() {
+        let mut val = Config(0x0);
+
+        val = val.enable_master();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_master_enabled());
+        val = val.disable_master();
+        assert!(!val.is_master_enabled());
+
+        val = val.enable_slave();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_slave_enabled());
+        val = val.disable_slave();
+        assert!(!val.is_slave_enabled());
+
+        val = val.enable_transmit();
+        assert_eq!(val.0, 0x0000_0004);
+        assert!(val.is_transmit_enabled());
+        val = val.disable_transmit();
+        assert!(!val.is_transmit_enabled());
+
+        val = val.enable_receive();
+        assert_eq!(val.0, 0x0000_0008);
+        assert!(val.is_receive_enabled());
+        val = val.disable_receive();
+        assert!(!val.is_receive_enabled());
+
+        val = val.enable_mute();
+        assert_eq!(val.0, 0x0000_0010);
+        assert!(val.is_mute_enabled());
+        val = val.disable_mute();
+        assert!(!val.is_mute_enabled());
+
+        val = val.enable_mono();
+        assert_eq!(val.0, 0x0000_0020);
+        assert!(val.is_mono_enabled());
+        val = val.disable_mono();
+        assert!(!val.is_mono_enabled());
+
+        val = val.set_mode(Mode::Dsp);
+        assert_eq!(val.0, 0x0000_0080);
+        assert_eq!(val.mode(), Mode::Dsp);
+        val = val.set_mode(Mode::LeftJustified);
+
+        val = val.set_frame_size(SampleSize::Bits32);
+        assert_eq!(val.0, 0x0000_0300);
+        assert_eq!(val.frame_size(), SampleSize::Bits32);
+        val = val.set_frame_size(SampleSize::Bits8);
+
+        val = val.set_data_size(SampleSize::Bits24);
+        assert_eq!(val.0, 0x0000_0800);
+        assert_eq!(val.data_size(), SampleSize::Bits24);
+        val = val.set_data_size(SampleSize::Bits8);
+
+        val = val.set_data_offset_count(0x1f);
+        assert_eq!(val.0, 0x001f_0000);
+        assert_eq!(val.data_offset_count(), 0x1f);
+        val = val.set_data_offset_count(0);
+
+        val = val.enable_data_offset();
+        assert_eq!(val.0, 0x0020_0000);
+        assert!(val.is_data_offset_enabled());
+        val = val.disable_data_offset();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_data_offset_enabled());
+    }
+
+    #[test]
+    fn struct_bclk_config_functions() {
+        let mut val = BclkConfig(0x0);
+
+        val = val.set_low_period(0xfff);
+        assert_eq!(val.0, 0x0000_0fff);
+        assert_eq!(val.low_period(), 0xfff);
+
+        val = val.set_high_period(0xfff);
+        assert_eq!(val.0, 0x0fff_0fff);
+        assert_eq!(val.high_period(), 0xfff);
+    }
+
+    #[test]
+    fn struct_fifo_config_0_functions() {
+        let mut val = FifoConfig0(0x0);
+
+        val = val.enable_transmit_dma();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_transmit_dma_enabled());
+        val = val.disable_transmit_dma();
+        assert!(!val.is_transmit_dma_enabled());
+
+        val = val.enable_receive_dma();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_receive_dma_enabled());
+        val = val.disable_receive_dma();
+        assert!(!val.is_receive_dma_enabled());
+
+        val = val.clear_transmit_fifo().clear_receive_fifo();
+        assert_eq!(val.0, 0x0000_000c);
+
+        val = FifoConfig0(0x0000_00f0);
+        assert!(val.transmit_fifo_overflow());
+        assert!(val.transmit_fifo_underflow());
+        assert!(val.receive_fifo_overflow());
+        assert!(val.receive_fifo_underflow());
+
+        val = FifoConfig0(0x0).enable_channel_merge();
+        assert_eq!(val.0, 0x0000_0100);
+        assert!(val.is_channel_merge_enabled());
+        val = val.disable_channel_merge();
+        assert!(!val.is_channel_merge_enabled());
+
+        val = val.enable_channel_exchange();
+        assert_eq!(val.0, 0x0000_0200);
+        assert!(val.is_channel_exchange_enabled());
+        val = val.disable_channel_exchange();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_channel_exchange_enabled());
+    }
+
+    #[test]
+    fn struct_fifo_config_1_functions() {
+        let mut val = FifoConfig1(0x0000_1010);
+        assert_eq!(val.transmit_available_bytes(), 0x10);
+        assert_eq!(val.receive_available_bytes(), 0x10);
+
+        val = FifoConfig1(0x0).set_transmit_threshold(0xf);
+        assert_eq!(val.0, 0x000f_0000);
+        assert_eq!(val.transmit_threshold(), 0xf);
+
+        val = val.set_receive_threshold(0xf);
+        assert_eq!(val.0, 0x0f0f_0000);
+        assert_eq!(val.receive_threshold(), 0xf);
+    }
+
+    #[test]
+    fn function_clock_config() {
+        let clocks = Clocks {
+            xtal: Hertz(40_000_000),
+        };
+        // 442.368 MHz / 36 = 12.288 MHz = 256 * 48 kHz.
+        let (divide, bclk) = clock_config(I2sConfig::default(), &clocks).unwrap();
+        assert_eq!(divide, 35);
+        assert_eq!(bclk.low_period(), 3);
+        assert_eq!(bclk.high_period(), 3);
+
+        let config = I2sConfig::default().set_sample_size(SampleSize::Bits32);
+        let (_, bclk) = clock_config(config, &clocks).unwrap();
+        assert_eq!(bclk.low_period(), 1);
+
+        let config = I2sConfig::default().set_sample_rate(Hertz(8_000_000));
+        assert_eq!(
+            clock_config(config, &clocks),
+            Err(ConfigError::SampleRateTooHigh)
+        );
+        let config = I2sConfig::default().set_sample_rate(Hertz(1_000));
+        assert_eq!(
+            clock_config(config, &clocks),
+            Err(ConfigError::SampleRateTooLow)
+        );
+        let config = I2sConfig::default().set_sample_rate(Hertz(u32::MAX));
+        assert_eq!(
+            clock_config(config, &clocks),
+            Err(ConfigError::SampleRateTooHigh)
+        );
+        let config = I2sConfig::default().set_sample_rate(Hertz(44_100));
+        assert_eq!(
+            clock_config(config, &clocks),
+            Err(ConfigError::SampleRateInexact)
+        );
+    }
+
+    #[test]
+    fn function_format_config() {
+        let val = format_config(I2sConfig::default());
+        assert_eq!(val.mode(), Mode::LeftJustified);
+        assert!(val.is_data_offset_enabled());
+        assert_eq!(val.data_offset_count(), 0);
+
+        let val = format_config(I2sConfig::default().set_format(Format::LeftJustified));
+        assert_eq!(val.mode(), Mode::LeftJustified);
+        assert!(!val.is_data_offset_enabled());
+
+        let val = format_config(I2sConfig::default().set_format(Format::Dsp));
+        assert_eq!(val.mode(), Mode::Dsp);
+        assert!(!val.is_data_offset_enabled());
+    }
 }
@@ -0,0 +1,191 @@
+//! Inter-processor communication mailbox.
+//!
+//! Each core owns one IPC block with 32 doorbell channels. A core signals another by
+//! setting channel bits in the block of the target core, which raises the target's IPC
+//! interrupt (`ipc_d0` on the DSP core) when the channel is unmasked. Doorbells carry no
+//! payload; larger messages are passed through shared memory [`queue`]s.
+
+pub mod queue;
+
+use core::ops::Deref;
+use volatile_register::{RO, RW, WO};
+
+/// Inter-processor communication registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Set channel doorbells, written by other cores.
+    pub set: WO<Channels>,
+    /// Raw doorbell state regardless of mask.
+    pub raw_state: RO<Channels>,
+    /// Clear channel doorbells.
+    pub clear: WO<Channels>,
+    /// Unmask channel interrupts.
+    pub unmask_set: WO<Channels>,
+    /// Mask channel interrupts.
+    pub unmask_clear: WO<Channels>,
+    /// Interrupt line select of channel 0 to 15.
+    pub line_select_low: RW<u32>,
+    /// Interrupt line select of channel 16 to 31.
+    pub line_select_high: RW<u32>,
+    /// Doorbell state of unmasked channels.
+    pub state: RO<Channels>,
+}
+
+/// Message channel number from 0 to 31.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Channel(u8);
+
+impl Channel {
+    /// Create a channel, panics if `number` is not below 32.
+    #[inline]
+    pub const fn new(number: u8) -> Self {
+        assert!(number < 32, "IPC channel number out of range");
+        Self(number)
+    }
+    /// Get channel number.
+    #[inline]
+    pub const fn number(self) -> u8 {
+        self.0
+    }
+}
+
+/// Set of message channels register.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Channels(u32);
+
+impl Channels {
+    /// Add channel to set.
+    #[inline]
+    pub const fn with(self, val: Channel) -> Self {
+        Self(self.0 | (1 << val.0))
+    }
+    /// Remove channel from set.
+    #[inline]
+    pub const fn without(self, val: Channel) -> Self {
+        Self(self.0 & !(1 << val.0))
+    }
+    /// Check if channel is in set.
+    #[inline]
+    pub const fn contains(self, val: Channel) -> bool {
+        self.0 & (1 << val.0) != 0
+    }
+    /// Check if set is empty.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+    /// Iterate channels in set in increasing order.
+    #[inline]
+    pub fn iter(self) -> impl Iterator<Item = Channel> {
+        (0..32u8)
+            .filter(move |i| self.0 & (1 << i) != 0)
+            .map(Channel)
+    }
+}
+
+/// Managed inter-processor communication mailbox.
+///
+/// Use the block of the current core to receive doorbells, and blocks of other cores
+/// to notify them.
+pub struct Ipc<IPC> {
+    ipc: IPC,
+}
+
+impl<IPC: Deref<Target = RegisterBlock>> Ipc<IPC> {
+    /// Create a mailbox from IPC block of any core.
+    ///
+    /// Channel masks are left untouched, as blocks of other cores are shared
+    /// with their owners.
+    #[inline]
+    pub fn new(ipc: IPC) -> Self {
+        Self { ipc }
+    }
+    /// Ring doorbell of channel on the core owning this block.
+    #[inline]
+    pub fn notify(&self, channel: Channel) {
+        unsafe { self.ipc.set.write(Channels::default().with(channel)) };
+    }
+    /// Unmask channel so its doorbell raises interrupt.
+    #[inline]
+    pub fn enable_channel(&mut self, channel: Channel) {
+        unsafe { self.ipc.unmask_set.write(Channels::default().with(channel)) };
+    }
+    /// Mask channel interrupt.
+    #[inline]
+    pub fn disable_channel(&mut self, channel: Channel) {
+        unsafe {
+            self.ipc
+                .unmask_clear
+                .write(Channels::default().with(channel))
+        };
+    }
+    /// Check if doorbell of channel is pending.
+    #[inline]
+    pub fn is_pending(&self, channel: Channel) -> bool {
+        self.ipc.raw_state.read().contains(channel)
+    }
+    /// Acknowledge doorbell of channel.
+    #[inline]
+    pub fn clear(&mut self, channel: Channel) {
+        unsafe { self.ipc.clear.write(Channels::default().with(channel)) };
+    }
+    /// Handle IPC interrupt, calling `f` on each pending unmasked channel.
+    ///
+    /// Doorbells are acknowledged before `f` is called, so a peer may ring again
+    /// while the callback runs.
+    #[inline]
+    pub fn on_interrupt(&mut self, mut f: impl FnMut(Channel)) {
+        let pending = self.ipc.state.read();
+        unsafe { self.ipc.clear.write(pending) };
+        pending.iter().for_each(&mut f);
+    }
+    /// Release the peripheral.
+    #[inline]
+    pub fn free(self) -> IPC {
+        self.ipc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Channel, Channels, RegisterBlock};
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, set), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, raw_state), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, clear), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, unmask_set), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, unmask_clear), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, line_select_low), 0x14);
+        assert_eq!(offset_of!(RegisterBlock, line_select_high), 0x18);
+        assert_eq!(offset_of!(RegisterBlock, state), 0x1c);
+    }
+
+    #[test]
+    fn struct_channels_functions() {
+        let mut val = Channels::default();
+        assert!(val.is_empty());
+
+        val = val.with(Channel::new(0)).with(Channel::new(31));
+        assert_eq!(val.0, 0x8000_0001);
+        assert!(val.contains(Channel::new(31)));
+        assert!(!val.contains(Channel::new(1)));
+
+        let mut iter = val.iter();
+        assert_eq!(iter.next(), Some(Channel::new(0)));
+        assert_eq!(iter.next(), Some(Channel::new(31)));
+        assert_eq!(iter.next(), None);
+
+        val = val.without(Channel::new(0));
+        assert_eq!(val.0, 0x8000_0000);
+    }
+
+    #[test]
+    #[should_panic]
+    fn struct_channel_out_of_range() {
+        let _ = Channel::new(32);
+    }
+}
@@ -0,0 +1,230 @@
+//! Shared-memory message queue between cores.
+//!
+//! A [`Queue`] is a single-producer single-consumer ring buffer placed at an address
+//! known to both cores, usually in on-chip shared RAM. Data caches of BL808 cores are
+//! not coherent with each other, so every access writes back or invalidates the touched
+//! cache lines. The producer rings an IPC doorbell after each message so the consumer
+//! can sleep until data arrives.
+
+use super::{Channel, Ipc, RegisterBlock};
+use crate::sync::{SharedWord, clean, invalidate};
+use core::{cell::UnsafeCell, mem::MaybeUninit, ops::Deref};
+
+pub use crate::sync::Pod;
+
+/// Cache line aligned value, keeps indices written by different cores apart.
+#[repr(C, align(64))]
+struct CacheLine<T>(T);
+
+/// Single-producer single-consumer message queue in shared memory.
+///
+/// `N` must be a power of two, so slot indices stay continuous when the free-running
+/// head and tail counters wrap around.
+#[repr(C)]
+pub struct Queue<T, const N: usize> {
+    head: SharedWord,
+    tail: SharedWord,
+    slots: CacheLine<UnsafeCell<[MaybeUninit<T>; N]>>,
+}
+
+unsafe impl<T: Pod, const N: usize> Sync for Queue<T, N> {}
+
+impl<T: Pod, const N: usize> Queue<T, N> {
+    /// Create an empty queue.
+    #[inline]
+    pub const fn new() -> Self {
+        const { assert!(N.is_power_of_two() && N <= 1 << 31) };
+        Self {
+            head: SharedWord::new(0),
+            tail: SharedWord::new(0),
+            slots: CacheLine(UnsafeCell::new([MaybeUninit::uninit(); N])),
+        }
+    }
+    /// Get the queue placed at a shared memory address.
+    ///
+    /// # Safety
+    ///
+    /// `address` must be aligned to 64 bytes, reserved for this queue on all cores,
+    /// and have the same `T` and `N` on both sides.
+    #[inline]
+    pub unsafe fn at(address: usize) -> &'static Self {
+        const { assert!(N.is_power_of_two() && N <= 1 << 31) };
+        unsafe { &*(address as *const Self) }
+    }
+    /// Empty the queue, called by one core before producer and consumer are created.
+    #[inline]
+    pub fn reset(&self) {
+        self.head.publish(0);
+        self.tail.publish(0);
+    }
+    /// Create the sending end, ringing `channel` on `ipc` after each message.
+    ///
+    /// # Safety
+    ///
+    /// At most one producer of this queue may exist across all cores.
+    #[inline]
+    pub unsafe fn producer<IPC>(&self, ipc: Ipc<IPC>, channel: Channel) -> Producer<'_, T, N, IPC> {
+        Producer {
+            queue: self,
+            ipc,
+            channel,
+        }
+    }
+    /// Create the receiving end.
+    ///
+    /// # Safety
+    ///
+    /// At most one consumer of this queue may exist across all cores.
+    #[inline]
+    pub unsafe fn consumer(&self) -> Consumer<'_, T, N> {
+        Consumer { queue: self }
+    }
+    #[inline]
+    fn slot(&self, index: u32) -> *mut MaybeUninit<T> {
+        let slots = self.slots.0.get() as *mut MaybeUninit<T>;
+        unsafe { slots.add(index as usize % N) }
+    }
+}
+
+impl<T: Pod, const N: usize> Default for Queue<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sending end of a shared-memory queue.
+pub struct Producer<'a, T, const N: usize, IPC> {
+    queue: &'a Queue<T, N>,
+    ipc: Ipc<IPC>,
+    channel: Channel,
+}
+
+impl<T: Pod, const N: usize, IPC: Deref<Target = RegisterBlock>> Producer<'_, T, N, IPC> {
+    /// Send a message and notify the consumer, returns it back if the queue is full.
+    #[inline]
+    pub fn send(&mut self, val: T) -> Result<(), T> {
+        let queue = self.queue;
+        let head = queue.head.consume();
+        let tail = queue.tail.consume();
+        if head.wrapping_sub(tail) as usize >= N {
+            return Err(val);
+        }
+        let slot = queue.slot(head);
+        unsafe {
+            slot.write(MaybeUninit::new(val));
+            clean(&*slot);
+        }
+        queue.head.publish(head.wrapping_add(1));
+        self.ipc.notify(self.channel);
+        Ok(())
+    }
+    /// Get number of free slots as last seen by this core.
+    #[inline]
+    pub fn free_slots(&self) -> usize {
+        let head = self.queue.head.consume();
+        let tail = self.queue.tail.consume();
+        N - head.wrapping_sub(tail) as usize
+    }
+    /// Release the doorbell mailbox.
+    #[inline]
+    pub fn free(self) -> Ipc<IPC> {
+        self.ipc
+    }
+}
+
+/// Receiving end of a shared-memory queue.
+pub struct Consumer<'a, T, const N: usize> {
+    queue: &'a Queue<T, N>,
+}
+
+impl<T: Pod, const N: usize> Consumer<'_, T, N> {
+    /// Receive the oldest message, if any.
+    ///
+    /// Call this function after the doorbell channel of the producer fires, and keep
+    /// calling until it returns `None` as several messages may share one doorbell.
+    #[inline]
+    pub fn recv(&mut self) -> Option<T> {
+        let queue = self.queue;
+        let tail = queue.tail.consume();
+        let head = queue.head.consume();
+        if head == tail {
+            return None;
+        }
+        let slot = queue.slot(tail);
+        let val = unsafe {
+            invalidate(&*slot);
+            (*slot).assume_init_read()
+        };
+        queue.tail.publish(tail.wrapping_add(1));
+        Some(val)
+    }
+    /// Check if there is no message as last seen by this core.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.queue.head.consume() == self.queue.tail.consume()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::Channel, super::Ipc, super::RegisterBlock, Queue};
+    use core::mem::{align_of, offset_of};
+
+    #[test]
+    fn struct_queue_layout() {
+        assert_eq!(align_of::<Queue<u32, 4>>(), 64);
+        assert_eq!(offset_of!(Queue<u32, 4>, tail), 64);
+        assert_eq!(offset_of!(Queue<u32, 4>, slots), 128);
+    }
+
+    #[test]
+    fn function_queue_send_recv() {
+        let mailbox: RegisterBlock = unsafe { core::mem::zeroed() };
+        let queue = Queue::<[u16; 3], 4>::new();
+        queue.reset();
+        let channel = Channel::new(5);
+        let mut producer = unsafe { queue.producer(Ipc::new(&mailbox), channel) };
+        let mut consumer = unsafe { queue.consumer() };
+
+        assert_eq!(consumer.recv(), None);
+        assert!(consumer.is_empty());
+        for i in 0..4 {
+            assert_eq!(producer.send([i, i + 1, i + 2]), Ok(()));
+        }
+        assert_eq!(producer.send([9, 9, 9]), Err([9, 9, 9]));
+        assert_eq!(producer.free_slots(), 0);
+        let doorbell = unsafe { (&mailbox.set as *const _ as *const u32).read_volatile() };
+        assert_eq!(doorbell, 1 << 5);
+
+        assert_eq!(consumer.recv(), Some([0, 1, 2]));
+        assert_eq!(producer.free_slots(), 1);
+        assert_eq!(producer.send([4, 5, 6]), Ok(()));
+        for i in 1..5 {
+            assert_eq!(consumer.recv(), Some([i, i + 1, i + 2]));
+        }
+        assert_eq!(consumer.recv(), None);
+    }
+
+    #[test]
+    fn function_queue_counter_wrap() {
+        let mailbox: RegisterBlock = unsafe { core::mem::zeroed() };
+        let queue = Queue::<u32, 4>::new();
+        queue.head.publish(u32::MAX - 1);
+        queue.tail.publish(u32::MAX - 1);
+        let mut producer = unsafe { queue.producer(Ipc::new(&mailbox), Channel::new(0)) };
+        let mut consumer = unsafe { queue.consumer() };
+
+        for i in 0..4 {
+            assert_eq!(producer.send(i), Ok(()));
+        }
+        assert_eq!(producer.send(9), Err(9));
+        assert_eq!(consumer.recv(), Some(0));
+        assert_eq!(producer.send(4), Ok(()));
+        for i in 1..5 {
+            assert_eq!(consumer.recv(), Some(i));
+        }
+        assert_eq!(consumer.recv(), None);
+        assert_eq!(queue.head.consume(), 3);
+    }
+}
@@ -1,5 +1,12 @@
 //! Infrared remote peripheral.
+//!
+//! The receiver measures pulses on its input and decodes NEC or RC-5 frames in
+//! hardware, raising the `ir_rx` interrupt when a frame ends. Other protocols are
+//! received in pulse width mode, where each pulse length is pushed into a FIFO for
+//! software decoding. The receive pin is selected in global configuration registers.
 
+use core::ops::Deref;
+use embedded_time::rate::Hertz;
 use volatile_register::{RO, RW};
 
 /// Infrared remote peripheral registers.
@@ -35,30 +42,499 @@ pub struct RegisterBlock {
 #[repr(transparent)]
 pub struct ReceiveConfig(u32);
 
+impl ReceiveConfig {
+    const ENABLE: u32 = 1 << 0;
+    const INVERT: u32 = 1 << 1;
+    const PROTOCOL: u32 = 0x3 << 2;
+    const DEGLITCH: u32 = 1 << 4;
+    const DEGLITCH_COUNT: u32 = 0xf << 8;
+
+    /// Enable receiver.
+    #[inline]
+    pub const fn enable_receive(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable receiver.
+    #[inline]
+    pub const fn disable_receive(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if receiver is enabled.
+    #[inline]
+    pub const fn is_receive_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Invert input signal, for receiver modules with active high output.
+    #[inline]
+    pub const fn enable_invert(self) -> Self {
+        Self(self.0 | Self::INVERT)
+    }
+    /// Use input signal as is.
+    #[inline]
+    pub const fn disable_invert(self) -> Self {
+        Self(self.0 & !Self::INVERT)
+    }
+    /// Check if input signal is inverted.
+    #[inline]
+    pub const fn is_invert_enabled(self) -> bool {
+        self.0 & Self::INVERT != 0
+    }
+    /// Set decoding protocol.
+    #[inline]
+    pub const fn set_protocol(self, val: Protocol) -> Self {
+        Self((self.0 & !Self::PROTOCOL) | ((val as u32) << 2))
+    }
+    /// Get decoding protocol.
+    #[inline]
+    pub const fn protocol(self) -> Protocol {
+        match (self.0 & Self::PROTOCOL) >> 2 {
+            0 => Protocol::Nec,
+            1 => Protocol::Rc5,
+            _ => Protocol::PulseWidth,
+        }
+    }
+    /// Enable input deglitch filter.
+    #[inline]
+    pub const fn enable_deglitch(self) -> Self {
+        Self(self.0 | Self::DEGLITCH)
+    }
+    /// Disable input deglitch filter.
+    #[inline]
+    pub const fn disable_deglitch(self) -> Self {
+        Self(self.0 & !Self::DEGLITCH)
+    }
+    /// Check if input deglitch filter is enabled.
+    #[inline]
+    pub const fn is_deglitch_enabled(self) -> bool {
+        self.0 & Self::DEGLITCH != 0
+    }
+    /// Set shortest accepted pulse in clock cycles for deglitch filter.
+    #[inline]
+    pub const fn set_deglitch_count(self, val: u8) -> Self {
+        Self((self.0 & !Self::DEGLITCH_COUNT) | (((val as u32) << 8) & Self::DEGLITCH_COUNT))
+    }
+    /// Get shortest accepted pulse in clock cycles for deglitch filter.
+    #[inline]
+    pub const fn deglitch_count(self) -> u8 {
+        ((self.0 & Self::DEGLITCH_COUNT) >> 8) as u8
+    }
+}
+
+/// Infrared decoding protocol.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Protocol {
+    /// NEC pulse distance protocol, 32 data bits.
+    Nec = 0,
+    /// Philips RC-5 Manchester protocol, 13 data bits after start bit.
+    Rc5 = 1,
+    /// Raw pulse widths pushed into FIFO.
+    PulseWidth = 2,
+}
+
 /// Receive interrupt state and configuration register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct ReceiveInterrupt(u32);
 
+impl ReceiveInterrupt {
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub const fn has_interrupt(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32)) != 0
+    }
+    /// Mask interrupt.
+    #[inline]
+    pub const fn mask_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32 + 8)))
+    }
+    /// Unmask interrupt.
+    #[inline]
+    pub const fn unmask_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 & !(1 << (val as u32 + 8)))
+    }
+    /// Check if interrupt is masked.
+    #[inline]
+    pub const fn is_interrupt_masked(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32 + 8)) != 0
+    }
+    /// Clear interrupt flag.
+    #[inline]
+    pub const fn clear_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32 + 16)))
+    }
+    /// Enable interrupt.
+    #[inline]
+    pub const fn enable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32 + 24)))
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub const fn disable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 & !(1 << (val as u32 + 24)))
+    }
+    /// Check if interrupt is enabled.
+    #[inline]
+    pub const fn is_interrupt_enabled(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32 + 24)) != 0
+    }
+}
+
+/// Infrared receive interrupt event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Interrupt {
+    /// Input stayed idle longer than end threshold, frame is complete.
+    ReceiveEnd = 0,
+    /// Pulse width FIFO count reached threshold.
+    Fifo = 1,
+}
+
 /// Receive pulse width threshold configuration.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct ReceiveThreshold(u32);
 
+impl ReceiveThreshold {
+    const DATA: u32 = 0xffff;
+    const END: u32 = 0xffff << 16;
+
+    /// Set pulse length in clock cycles separating logic 0 from logic 1.
+    #[inline]
+    pub const fn set_data_threshold(self, val: u16) -> Self {
+        Self((self.0 & !Self::DATA) | (val as u32))
+    }
+    /// Get pulse length in clock cycles separating logic 0 from logic 1.
+    #[inline]
+    pub const fn data_threshold(self) -> u16 {
+        (self.0 & Self::DATA) as u16
+    }
+    /// Set idle length in clock cycles ending a frame.
+    #[inline]
+    pub const fn set_end_threshold(self, val: u16) -> Self {
+        Self((self.0 & !Self::END) | ((val as u32) << 16))
+    }
+    /// Get idle length in clock cycles ending a frame.
+    #[inline]
+    pub const fn end_threshold(self) -> u16 {
+        ((self.0 & Self::END) >> 16) as u16
+    }
+}
+
 /// First-in first-out queue configuration register 0.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct FifoConfig0(u32);
 
+impl FifoConfig0 {
+    const CLEAR: u32 = 1 << 2;
+    const OVERFLOW: u32 = 1 << 4;
+    const UNDERFLOW: u32 = 1 << 5;
+
+    /// Clear pulse width FIFO.
+    #[inline]
+    pub const fn clear_fifo(self) -> Self {
+        Self(self.0 | Self::CLEAR)
+    }
+    /// Check if pulse width FIFO overflowed.
+    #[inline]
+    pub const fn is_overflow(self) -> bool {
+        self.0 & Self::OVERFLOW != 0
+    }
+    /// Check if pulse width FIFO was read empty.
+    #[inline]
+    pub const fn is_underflow(self) -> bool {
+        self.0 & Self::UNDERFLOW != 0
+    }
+}
+
 /// First-in first-out queue configuration register 1.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct FifoConfig1(u32);
 
+impl FifoConfig1 {
+    const COUNT: u32 = 0x7f;
+    const THRESHOLD: u32 = 0x3f << 16;
+
+    /// Number of pulse widths in FIFO.
+    #[inline]
+    pub const fn count(self) -> u8 {
+        (self.0 & Self::COUNT) as u8
+    }
+    /// Set FIFO count raising [`Interrupt::Fifo`].
+    #[inline]
+    pub const fn set_threshold(self, val: u8) -> Self {
+        Self((self.0 & !Self::THRESHOLD) | (((val as u32) << 16) & Self::THRESHOLD))
+    }
+    /// Get FIFO count raising [`Interrupt::Fifo`].
+    #[inline]
+    pub const fn threshold(self) -> u8 {
+        ((self.0 & Self::THRESHOLD) >> 16) as u8
+    }
+}
+
+/// Infrared receiver configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Config {
+    /// Decoding protocol.
+    pub protocol: Protocol,
+    /// Invert input signal.
+    pub invert: bool,
+    /// Shortest accepted pulse in clock cycles, `None` disables deglitch filter.
+    pub deglitch: Option<u8>,
+    /// Pulse length in clock cycles separating logic 0 from logic 1.
+    pub data_threshold: u16,
+    /// Idle length in clock cycles ending a frame.
+    pub end_threshold: u16,
+}
+
+impl Config {
+    /// NEC protocol thresholds for receiver clocked at `clock`.
+    #[inline]
+    pub const fn nec(clock: Hertz) -> Self {
+        Self {
+            protocol: Protocol::Nec,
+            invert: false,
+            deglitch: None,
+            data_threshold: cycles(clock, 1_725),
+            end_threshold: cycles(clock, 4_500),
+        }
+    }
+    /// RC-5 protocol thresholds for receiver clocked at `clock`.
+    #[inline]
+    pub const fn rc5(clock: Hertz) -> Self {
+        Self {
+            protocol: Protocol::Rc5,
+            invert: false,
+            deglitch: None,
+            data_threshold: cycles(clock, 1_333),
+            end_threshold: cycles(clock, 2_700),
+        }
+    }
+    /// Pulse width mode ending frames after `end_us` microseconds of idle input.
+    #[inline]
+    pub const fn pulse_width(clock: Hertz, end_us: u32) -> Self {
+        Self {
+            protocol: Protocol::PulseWidth,
+            invert: false,
+            deglitch: None,
+            data_threshold: 0,
+            end_threshold: cycles(clock, end_us),
+        }
+    }
+    /// Invert input signal.
+    #[inline]
+    pub const fn set_invert(mut self, invert: bool) -> Self {
+        self.invert = invert;
+        self
+    }
+    /// Ignore pulses shorter than `count` clock cycles.
+    #[inline]
+    pub const fn set_deglitch(mut self, count: u8) -> Self {
+        self.deglitch = Some(count);
+        self
+    }
+}
+
+/// Convert microseconds into receiver clock cycles, saturating at register width.
+#[inline]
+const fn cycles(clock: Hertz, us: u32) -> u16 {
+    let val = clock.0 as u64 * us as u64 / 1_000_000;
+    if val > u16::MAX as u64 {
+        u16::MAX
+    } else {
+        val as u16
+    }
+}
+
+/// Decoded infrared frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Frame {
+    /// Number of received bits.
+    pub bits: u8,
+    /// Received bits, first bit in bit 0 for NEC and in the highest bit for RC-5.
+    pub data: u64,
+}
+
+impl Frame {
+    /// Interpret as NEC frame, `None` if bit count or command check fails.
+    ///
+    /// Frames with address check failing are returned with 16-bit extended address.
+    #[inline]
+    pub const fn nec(self) -> Option<Nec> {
+        if self.bits != 32 {
+            return None;
+        }
+        let [address, address_inv, command, command_inv, ..] = self.data.to_le_bytes();
+        if command != !command_inv {
+            return None;
+        }
+        let address = if address == !address_inv {
+            address as u16
+        } else {
+            u16::from_le_bytes([address, address_inv])
+        };
+        Some(Nec { address, command })
+    }
+    /// Interpret as RC-5 frame, `None` if bit count does not match.
+    #[inline]
+    pub const fn rc5(self) -> Option<Rc5> {
+        if self.bits != 13 {
+            return None;
+        }
+        let data = self.data as u16;
+        // Inverted field bit extends command to 7 bits.
+        let field = (data >> 12) & 1;
+        Some(Rc5 {
+            toggle: (data >> 11) & 1 != 0,
+            address: ((data >> 6) & 0x1f) as u8,
+            command: ((data & 0x3f) | ((field ^ 1) << 6)) as u8,
+        })
+    }
+}
+
+/// NEC protocol message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Nec {
+    /// Device address, 8-bit or extended 16-bit.
+    pub address: u16,
+    /// Command code.
+    pub command: u8,
+}
+
+/// RC-5 protocol message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rc5 {
+    /// Toggles on each new key press.
+    pub toggle: bool,
+    /// Device address.
+    pub address: u8,
+    /// Command code, 7-bit extended.
+    pub command: u8,
+}
+
+/// Managed infrared receiver.
+pub struct IrReceiver<IR> {
+    ir: IR,
+}
+
+impl<IR: Deref<Target = RegisterBlock>> IrReceiver<IR> {
+    /// Create and enable infrared receiver with configuration.
+    #[inline]
+    pub fn new(ir: IR, config: Config) -> Self {
+        let mut val = ReceiveConfig(0).set_protocol(config.protocol);
+        if config.invert {
+            val = val.enable_invert();
+        }
+        if let Some(count) = config.deglitch {
+            val = val.enable_deglitch().set_deglitch_count(count);
+        }
+        unsafe {
+            ir.receive_config.write(val);
+            ir.receive_threshold.write(
+                ReceiveThreshold(0)
+                    .set_data_threshold(config.data_threshold)
+                    .set_end_threshold(config.end_threshold),
+            );
+            ir.fifo_config_0.write(FifoConfig0(0).clear_fifo());
+            ir.receive_interrupt.write(
+                ReceiveInterrupt(0)
+                    .mask_interrupt(Interrupt::ReceiveEnd)
+                    .mask_interrupt(Interrupt::Fifo)
+                    .enable_interrupt(Interrupt::ReceiveEnd)
+                    .clear_interrupt(Interrupt::ReceiveEnd),
+            );
+            ir.receive_config.modify(|v| v.enable_receive());
+        }
+        Self { ir }
+    }
+    /// Get a complete frame if one was received.
+    ///
+    /// In pulse width mode the frame has no data bits; read pulses with
+    /// [`read_pulses`](Self::read_pulses) instead.
+    #[inline]
+    pub fn try_receive(&mut self) -> Option<Frame> {
+        if !self.has_interrupt(Interrupt::ReceiveEnd) {
+            return None;
+        }
+        let bits = self.ir.receive_data_count.read() & 0x7f;
+        let data =
+            self.ir.receive_word_0.read() as u64 | (self.ir.receive_word_1.read() as u64) << 32;
+        self.clear_interrupt(Interrupt::ReceiveEnd);
+        Some(Frame { bits, data })
+    }
+    /// Wait until a complete frame is received.
+    #[inline]
+    pub fn receive(&mut self) -> Frame {
+        loop {
+            if let Some(frame) = self.try_receive() {
+                return frame;
+            }
+            core::hint::spin_loop();
+        }
+    }
+    /// Handle receive interrupt, returning the frame that just ended.
+    ///
+    /// Call this function in the `ir_rx` interrupt handler.
+    #[inline]
+    pub fn on_interrupt(&mut self) -> Option<Frame> {
+        self.try_receive()
+    }
+    /// Read pulse widths in clock cycles from FIFO, returns number of pulses read.
+    #[inline]
+    pub fn read_pulses(&mut self, buf: &mut [u16]) -> usize {
+        let count = (self.ir.fifo_config_1.read().count() as usize).min(buf.len());
+        for pulse in buf.iter_mut().take(count) {
+            *pulse = self.ir.fifo_read.read() as u16;
+        }
+        count
+    }
+    /// Set FIFO count raising [`Interrupt::Fifo`] in pulse width mode.
+    #[inline]
+    pub fn set_fifo_threshold(&mut self, threshold: u8) {
+        unsafe { self.ir.fifo_config_1.modify(|v| v.set_threshold(threshold)) };
+    }
+    /// Enable interrupt.
+    #[inline]
+    pub fn enable_interrupt(&mut self, val: Interrupt) {
+        unsafe {
+            self.ir
+                .receive_interrupt
+                .modify(|v| v.enable_interrupt(val).unmask_interrupt(val))
+        };
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub fn disable_interrupt(&mut self, val: Interrupt) {
+        unsafe { self.ir.receive_interrupt.modify(|v| v.mask_interrupt(val)) };
+    }
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub fn has_interrupt(&self, val: Interrupt) -> bool {
+        self.ir.receive_interrupt.read().has_interrupt(val)
+    }
+    /// Clear interrupt flag.
+    #[inline]
+    pub fn clear_interrupt(&mut self, val: Interrupt) {
+        unsafe { self.ir.receive_interrupt.modify(|v| v.clear_interrupt(val)) };
+    }
+    /// Disable receiver and release its peripheral.
+    #[inline]
+    pub fn free(self) -> IR {
+        unsafe { self.ir.receive_config.modify(|v| v.disable_receive()) };
+        self.ir
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::RegisterBlock;
+    use super::{
+        Config, FifoConfig0, FifoConfig1, Frame, Interrupt, Nec, Protocol, Rc5, ReceiveConfig,
+        ReceiveInterrupt, ReceiveThreshold, RegisterBlock,
+    };
     use core::mem::offset_of;
+    use embedded_time::rate::Hertz;
 
     #[test]
     fn struct_register_block_offset() {
@@ -72,4 +548,155 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, fifo_config_1), 0x84);
         assert_eq!(offset_of!(RegisterBlock, fifo_read), 0x8c);
     }
+
+    #[test]
+    fn struct_receive_config_functions() {
+        let mut val = ReceiveConfig(0x0);
+
+        val = val.enable_receive();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_receive_enabled());
+        val = val.disable_receive();
+        assert!(!val.is_receive_enabled());
+
+        val = val.enable_invert();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_invert_enabled());
+        val = val.disable_invert();
+        assert!(!val.is_invert_enabled());
+
+        val = val.set_protocol(Protocol::PulseWidth);
+        assert_eq!(val.0, 0x0000_0008);
+        assert_eq!(val.protocol(), Protocol::PulseWidth);
+        val = val.set_protocol(Protocol::Rc5);
+        assert_eq!(val.0, 0x0000_0004);
+        assert_eq!(val.protocol(), Protocol::Rc5);
+        val = val.set_protocol(Protocol::Nec);
+
+        val = val.enable_deglitch();
+        assert_eq!(val.0, 0x0000_0010);
+        assert!(val.is_deglitch_enabled());
+        val = val.disable_deglitch();
+        assert!(!val.is_deglitch_enabled());
+
+        val = val.set_deglitch_count(0xf);
+        assert_eq!(val.0, 0x0000_0f00);
+        assert_eq!(val.deglitch_count(), 0xf);
+    }
+
+    #[test]
+    fn struct_receive_interrupt_functions() {
+        let mut val = ReceiveInterrupt(0x0);
+
+        val = val.mask_interrupt(Interrupt::Fifo);
+        assert_eq!(val.0, 0x0000_0200);
+        assert!(val.is_interrupt_masked(Interrupt::Fifo));
+        val = val.unmask_interrupt(Interrupt::Fifo);
+        assert!(!val.is_interrupt_masked(Interrupt::Fifo));
+
+        val = val.clear_interrupt(Interrupt::ReceiveEnd);
+        assert_eq!(val.0, 0x0001_0000);
+        val = ReceiveInterrupt(0x0);
+
+        val = val.enable_interrupt(Interrupt::ReceiveEnd);
+        assert_eq!(val.0, 0x0100_0000);
+        assert!(val.is_interrupt_enabled(Interrupt::ReceiveEnd));
+        val = val.disable_interrupt(Interrupt::ReceiveEnd);
+        assert!(!val.is_interrupt_enabled(Interrupt::ReceiveEnd));
+
+        let val = ReceiveInterrupt(0x0000_0002);
+        assert!(val.has_interrupt(Interrupt::Fifo));
+        assert!(!val.has_interrupt(Interrupt::ReceiveEnd));
+    }
+
+    #[test]
+    fn struct_receive_threshold_functions() {
+        let mut val = ReceiveThreshold(0x0);
+
+        val = val.set_data_threshold(0x0d79);
+        assert_eq!(val.0, 0x0000_0d79);
+        assert_eq!(val.data_threshold(), 0x0d79);
+
+        val = val.set_end_threshold(0x2327);
+        assert_eq!(val.0, 0x2327_0d79);
+        assert_eq!(val.end_threshold(), 0x2327);
+    }
+
+    #[test]
+    fn struct_fifo_config_functions() {
+        let val = FifoConfig0(0x0).clear_fifo();
+        assert_eq!(val.0, 0x0000_0004);
+        assert!(FifoConfig0(0x10).is_overflow());
+        assert!(FifoConfig0(0x20).is_underflow());
+
+        let mut val = FifoConfig1(0x0000_0045);
+        assert_eq!(val.count(), 0x45);
+        val = val.set_threshold(0x3f);
+        assert_eq!(val.0, 0x003f_0045);
+        assert_eq!(val.threshold(), 0x3f);
+    }
+
+    #[test]
+    fn struct_config_thresholds() {
+        let config = Config::nec(Hertz(2_000_000));
+        assert_eq!(config.data_threshold, 3450);
+        assert_eq!(config.end_threshold, 9000);
+        let config = Config::rc5(Hertz(2_000_000));
+        assert_eq!(config.protocol, Protocol::Rc5);
+        assert_eq!(config.data_threshold, 2666);
+        let config = Config::pulse_width(Hertz(40_000_000), 10_000);
+        assert_eq!(config.end_threshold, u16::MAX);
+    }
+
+    #[test]
+    fn struct_frame_decode() {
+        let frame = Frame {
+            bits: 32,
+            data: 0xbf40_ff00,
+        };
+        assert_eq!(
+            frame.nec(),
+            Some(Nec {
+                address: 0x00,
+                command: 0x40
+            })
+        );
+        let frame = Frame {
+            bits: 32,
+            data: 0xbf40_7f00,
+        };
+        assert_eq!(
+            frame.nec(),
+            Some(Nec {
+                address: 0x7f00,
+                command: 0x40
+            })
+        );
+        let frame = Frame {
+            bits: 32,
+            data: 0xbe40_ff00,
+        };
+        assert_eq!(frame.nec(), None);
+        assert_eq!(frame.rc5(), None);
+
+        // Field, toggle, address 5 and command 12.
+        let frame = Frame {
+            bits: 13,
+            data: 0b1_1001_0100_1100,
+        };
+        assert_eq!(
+            frame.rc5(),
+            Some(Rc5 {
+                toggle: true,
+                address: 5,
+                command: 12
+            })
+        );
+        // Cleared field bit extends command to 76.
+        let frame = Frame {
+            bits: 13,
+            data: 0b0_0001_0100_1100,
+        };
+        assert_eq!(frame.rc5().unwrap().command, 76);
+    }
 }
@@ -0,0 +1,497 @@
+//! Keyboard matrix scan peripheral.
+//!
+//! The key scanner drives matrix rows in turn and samples columns, debouncing inputs
+//! in hardware. Pressed keys are pushed as key codes into an event FIFO, and the `kys`
+//! interrupt is raised on FIFO levels or when ghost keys are detected. A ghost key
+//! appears when three pressed keys on a rectangle's corners make the fourth corner
+//! look pressed; such scans are flagged instead of reported as events.
+
+use core::ops::Deref;
+use volatile_register::{RO, RW, WO};
+
+/// Key scan peripheral registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Scan control register.
+    pub control: RW<Control>,
+    _reserved0: [u8; 0xc],
+    /// Interrupt enable register.
+    pub interrupt_enable: RW<InterruptEnable>,
+    /// Interrupt state register.
+    pub interrupt_state: RO<InterruptState>,
+    /// Interrupt clear register.
+    pub interrupt_clear: WO<InterruptState>,
+    /// Key event FIFO index register.
+    pub fifo_index: RO<FifoIndex>,
+    /// Key event FIFO read register, each read pops one key code.
+    pub fifo_read: RO<u32>,
+}
+
+/// Scan control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Control(u32);
+
+impl Control {
+    const ENABLE: u32 = 1 << 0;
+    const FIFO_MODE: u32 = 1 << 1;
+    const GHOST: u32 = 1 << 2;
+    const DEBOUNCE: u32 = 1 << 3;
+    const DEBOUNCE_COUNT: u32 = 0xf << 4;
+    const SETTLE: u32 = 0x3 << 8;
+    const ROWS: u32 = 0x7 << 16;
+    const COLUMNS: u32 = 0x1f << 20;
+
+    /// Enable key scan.
+    #[inline]
+    pub const fn enable_scan(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable key scan.
+    #[inline]
+    pub const fn disable_scan(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if key scan is enabled.
+    #[inline]
+    pub const fn is_scan_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Push key codes into event FIFO.
+    #[inline]
+    pub const fn enable_fifo_mode(self) -> Self {
+        Self(self.0 | Self::FIFO_MODE)
+    }
+    /// Keep only latest key codes.
+    #[inline]
+    pub const fn disable_fifo_mode(self) -> Self {
+        Self(self.0 & !Self::FIFO_MODE)
+    }
+    /// Check if key codes are pushed into event FIFO.
+    #[inline]
+    pub const fn is_fifo_mode_enabled(self) -> bool {
+        self.0 & Self::FIFO_MODE != 0
+    }
+    /// Enable ghost key detection.
+    #[inline]
+    pub const fn enable_ghost_detect(self) -> Self {
+        Self(self.0 | Self::GHOST)
+    }
+    /// Disable ghost key detection.
+    #[inline]
+    pub const fn disable_ghost_detect(self) -> Self {
+        Self(self.0 & !Self::GHOST)
+    }
+    /// Check if ghost key detection is enabled.
+    #[inline]
+    pub const fn is_ghost_detect_enabled(self) -> bool {
+        self.0 & Self::GHOST != 0
+    }
+    /// Enable input debounce.
+    #[inline]
+    pub const fn enable_debounce(self) -> Self {
+        Self(self.0 | Self::DEBOUNCE)
+    }
+    /// Disable input debounce.
+    #[inline]
+    pub const fn disable_debounce(self) -> Self {
+        Self(self.0 & !Self::DEBOUNCE)
+    }
+    /// Check if input debounce is enabled.
+    #[inline]
+    pub const fn is_debounce_enabled(self) -> bool {
+        self.0 & Self::DEBOUNCE != 0
+    }
+    /// Set number of matching scans before a key state change is accepted.
+    #[inline]
+    pub const fn set_debounce_count(self, val: u8) -> Self {
+        Self((self.0 & !Self::DEBOUNCE_COUNT) | (((val as u32) << 4) & Self::DEBOUNCE_COUNT))
+    }
+    /// Get number of matching scans before a key state change is accepted.
+    #[inline]
+    pub const fn debounce_count(self) -> u8 {
+        ((self.0 & Self::DEBOUNCE_COUNT) >> 4) as u8
+    }
+    /// Set extra clock cycles a row is driven before columns are sampled.
+    #[inline]
+    pub const fn set_settle(self, val: u8) -> Self {
+        Self((self.0 & !Self::SETTLE) | (((val as u32) << 8) & Self::SETTLE))
+    }
+    /// Get extra clock cycles a row is driven before columns are sampled.
+    #[inline]
+    pub const fn settle(self) -> u8 {
+        ((self.0 & Self::SETTLE) >> 8) as u8
+    }
+    /// Set number of matrix rows, from 1 to [`MAX_ROWS`].
+    #[inline]
+    pub const fn set_rows(self, val: u8) -> Self {
+        Self((self.0 & !Self::ROWS) | ((((val - 1) as u32) << 16) & Self::ROWS))
+    }
+    /// Get number of matrix rows.
+    #[inline]
+    pub const fn rows(self) -> u8 {
+        ((self.0 & Self::ROWS) >> 16) as u8 + 1
+    }
+    /// Set number of matrix columns, from 1 to [`MAX_COLUMNS`].
+    #[inline]
+    pub const fn set_columns(self, val: u8) -> Self {
+        Self((self.0 & !Self::COLUMNS) | ((((val - 1) as u32) << 20) & Self::COLUMNS))
+    }
+    /// Get number of matrix columns.
+    #[inline]
+    pub const fn columns(self) -> u8 {
+        ((self.0 & Self::COLUMNS) >> 20) as u8 + 1
+    }
+}
+
+/// Largest number of matrix rows.
+pub const MAX_ROWS: u8 = 8;
+/// Largest number of matrix columns.
+pub const MAX_COLUMNS: u8 = 20;
+
+/// Interrupt enable register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct InterruptEnable(u32);
+
+impl InterruptEnable {
+    /// Enable interrupt.
+    #[inline]
+    pub const fn enable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32)))
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub const fn disable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 & !(1 << (val as u32)))
+    }
+    /// Check if interrupt is enabled.
+    #[inline]
+    pub const fn is_interrupt_enabled(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32)) != 0
+    }
+}
+
+/// Interrupt state register.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct InterruptState(u32);
+
+impl InterruptState {
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub const fn has_interrupt(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32)) != 0
+    }
+    /// Clear interrupt flag.
+    #[inline]
+    pub const fn clear_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32)))
+    }
+}
+
+/// Key scan interrupt event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Interrupt {
+    /// A full matrix scan finished.
+    ScanDone = 7,
+    /// Event FIFO is full, further keys are dropped.
+    FifoFull = 8,
+    /// Event FIFO is half full.
+    FifoHalf = 9,
+    /// Event FIFO is a quarter full.
+    FifoQuarter = 10,
+    /// Event FIFO holds at least one key.
+    FifoNotEmpty = 11,
+    /// Ghost key detected, events of the scan are discarded.
+    GhostDetected = 12,
+}
+
+/// Key event FIFO index register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct FifoIndex(u32);
+
+impl FifoIndex {
+    const HEAD: u32 = 0xf;
+    const TAIL: u32 = 0xf << 8;
+
+    /// Get read position.
+    #[inline]
+    pub const fn head(self) -> u8 {
+        (self.0 & Self::HEAD) as u8
+    }
+    /// Get write position.
+    #[inline]
+    pub const fn tail(self) -> u8 {
+        ((self.0 & Self::TAIL) >> 8) as u8
+    }
+}
+
+/// Key scan configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Config {
+    /// Number of matrix rows.
+    pub rows: u8,
+    /// Number of matrix columns.
+    pub columns: u8,
+    /// Matching scans before a key state change is accepted, `None` disables debounce.
+    pub debounce: Option<u8>,
+    /// Detect and discard ghost keys.
+    pub ghost_detect: bool,
+    /// Extra clock cycles a row settles before columns are sampled.
+    pub settle: u8,
+}
+
+impl Config {
+    /// Configuration of a matrix with `rows` by `columns` keys.
+    #[inline]
+    pub const fn new(rows: u8, columns: u8) -> Self {
+        Self {
+            rows,
+            columns,
+            debounce: Some(4),
+            ghost_detect: true,
+            settle: 0,
+        }
+    }
+    /// Set matching scans before a key state change is accepted.
+    #[inline]
+    pub const fn set_debounce(mut self, count: Option<u8>) -> Self {
+        self.debounce = count;
+        self
+    }
+    /// Enable or disable ghost key detection.
+    #[inline]
+    pub const fn set_ghost_detect(mut self, enable: bool) -> Self {
+        self.ghost_detect = enable;
+        self
+    }
+}
+
+/// Errors on key scan configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConfigError {
+    /// Number of rows is zero or above [`MAX_ROWS`].
+    Rows,
+    /// Number of columns is zero or above [`MAX_COLUMNS`].
+    Columns,
+}
+
+/// Pressed key position in matrix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Key {
+    /// Row index from 0.
+    pub row: u8,
+    /// Column index from 0.
+    pub column: u8,
+}
+
+impl Key {
+    /// Decode a key code, which counts keys row by row.
+    #[inline]
+    pub const fn from_code(code: u8, columns: u8) -> Self {
+        Self {
+            row: code / columns,
+            column: code % columns,
+        }
+    }
+}
+
+/// Managed keyboard matrix scanner.
+pub struct Keyscan<KYS> {
+    kys: KYS,
+    columns: u8,
+}
+
+impl<KYS: Deref<Target = RegisterBlock>> Keyscan<KYS> {
+    /// Create and start key scan with configuration.
+    #[inline]
+    pub fn new(kys: KYS, config: Config) -> Result<Self, ConfigError> {
+        if config.rows == 0 || config.rows > MAX_ROWS {
+            return Err(ConfigError::Rows);
+        }
+        if config.columns == 0 || config.columns > MAX_COLUMNS {
+            return Err(ConfigError::Columns);
+        }
+        let mut val = Control(0)
+            .set_rows(config.rows)
+            .set_columns(config.columns)
+            .set_settle(config.settle)
+            .enable_fifo_mode();
+        if let Some(count) = config.debounce {
+            val = val.enable_debounce().set_debounce_count(count);
+        }
+        if config.ghost_detect {
+            val = val.enable_ghost_detect();
+        }
+        unsafe {
+            kys.control.write(val);
+            kys.interrupt_clear.write(InterruptState(0xffff_ffff));
+            kys.control.modify(|v| v.enable_scan());
+        }
+        Ok(Self {
+            kys,
+            columns: config.columns,
+        })
+    }
+    /// Pop next pressed key from event FIFO.
+    #[inline]
+    pub fn read_key(&mut self) -> Option<Key> {
+        if !self.has_interrupt(Interrupt::FifoNotEmpty) {
+            return None;
+        }
+        let code = self.kys.fifo_read.read() as u8;
+        Some(Key::from_code(code, self.columns))
+    }
+    /// Check and clear ghost key flag.
+    ///
+    /// Keys of a scan with ghost key are not reported; applications may ask users to
+    /// release keys.
+    #[inline]
+    pub fn take_ghost_detected(&mut self) -> bool {
+        let ans = self.has_interrupt(Interrupt::GhostDetected);
+        if ans {
+            self.clear_interrupt(Interrupt::GhostDetected);
+        }
+        ans
+    }
+    /// Enable interrupt.
+    ///
+    /// Key scan interrupts are raised on the `kys` interrupt line.
+    #[inline]
+    pub fn enable_interrupt(&mut self, val: Interrupt) {
+        unsafe {
+            self.kys
+                .interrupt_enable
+                .modify(|v| v.enable_interrupt(val))
+        };
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub fn disable_interrupt(&mut self, val: Interrupt) {
+        unsafe {
+            self.kys
+                .interrupt_enable
+                .modify(|v| v.disable_interrupt(val))
+        };
+    }
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub fn has_interrupt(&self, val: Interrupt) -> bool {
+        self.kys.interrupt_state.read().has_interrupt(val)
+    }
+    /// Clear interrupt flag.
+    #[inline]
+    pub fn clear_interrupt(&mut self, val: Interrupt) {
+        unsafe {
+            self.kys
+                .interrupt_clear
+                .write(InterruptState::default().clear_interrupt(val))
+        };
+    }
+    /// Stop key scan and release its peripheral.
+    #[inline]
+    pub fn free(self) -> KYS {
+        unsafe { self.kys.control.modify(|v| v.disable_scan()) };
+        self.kys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Control, FifoIndex, Interrupt, InterruptEnable, InterruptState, Key, RegisterBlock,
+    };
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, control), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_enable), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_state), 0x14);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_clear), 0x18);
+        assert_eq!(offset_of!(RegisterBlock, fifo_index), 0x1c);
+        assert_eq!(offset_of!(RegisterBlock, fifo_read), 0x20);
+    }
+
+    #[test]
+    fn struct_control_functions() {
+        let mut val = Control(0x0);
+
+        val = val.enable_scan();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_scan_enabled());
+        val = val.disable_scan();
+        assert!(!val.is_scan_enabled());
+
+        val = val.enable_fifo_mode();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_fifo_mode_enabled());
+        val = val.disable_fifo_mode();
+        assert!(!val.is_fifo_mode_enabled());
+
+        val = val.enable_ghost_detect();
+        assert_eq!(val.0, 0x0000_0004);
+        assert!(val.is_ghost_detect_enabled());
+        val = val.disable_ghost_detect();
+        assert!(!val.is_ghost_detect_enabled());
+
+        val = val.enable_debounce();
+        assert_eq!(val.0, 0x0000_0008);
+        assert!(val.is_debounce_enabled());
+        val = val.disable_debounce();
+        assert!(!val.is_debounce_enabled());
+
+        val = val.set_debounce_count(0xf);
+        assert_eq!(val.0, 0x0000_00f0);
+        assert_eq!(val.debounce_count(), 0xf);
+        val = val.set_debounce_count(0);
+
+        val = val.set_settle(3);
+        assert_eq!(val.0, 0x0000_0300);
+        assert_eq!(val.settle(), 3);
+        val = val.set_settle(0);
+
+        val = val.set_rows(8);
+        assert_eq!(val.0, 0x0007_0000);
+        assert_eq!(val.rows(), 8);
+        val = val.set_rows(1);
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = val.set_columns(20);
+        assert_eq!(val.0, 0x0130_0000);
+        assert_eq!(val.columns(), 20);
+    }
+
+    #[test]
+    fn struct_interrupt_functions() {
+        let mut val = InterruptEnable(0x0);
+        val = val.enable_interrupt(Interrupt::GhostDetected);
+        assert_eq!(val.0, 0x0000_1000);
+        assert!(val.is_interrupt_enabled(Interrupt::GhostDetected));
+        val = val.disable_interrupt(Interrupt::GhostDetected);
+        assert!(!val.is_interrupt_enabled(Interrupt::GhostDetected));
+
+        let val = InterruptState(0x0000_0880);
+        assert!(val.has_interrupt(Interrupt::ScanDone));
+        assert!(val.has_interrupt(Interrupt::FifoNotEmpty));
+        assert!(!val.has_interrupt(Interrupt::FifoFull));
+        let val = InterruptState::default().clear_interrupt(Interrupt::FifoFull);
+        assert_eq!(val.0, 0x0000_0100);
+    }
+
+    #[test]
+    fn struct_fifo_index_functions() {
+        let val = FifoIndex(0x0000_0503);
+        assert_eq!(val.head(), 3);
+        assert_eq!(val.tail(), 5);
+    }
+
+    #[test]
+    fn struct_key_from_code() {
+        assert_eq!(Key::from_code(0, 4), Key { row: 0, column: 0 });
+        assert_eq!(Key::from_code(9, 4), Key { row: 2, column: 1 });
+    }
+}
@@ -8,36 +8,78 @@
 //! this package with `embedded-hal` ecosystem drivers to provide abundant amount of features.
 #![no_std]
 
+#[macro_use]
+mod macros;
+
+/// Private supertrait for traits users must not implement outside this crate.
+mod sealed {
+    pub trait Sealed {}
+
+    // Pads of a peripheral are passed as tuples of pads and signal multiplexers.
+    impl<A, B> Sealed for (A, B) {}
+    impl<A, B, C> Sealed for (A, B, C) {}
+    impl<A, B, C, D> Sealed for (A, B, C, D) {}
+}
+
+pub mod arch;
+pub mod clint;
 pub mod clocks;
 
+pub mod acomp;
+#[cfg(feature = "at")]
+pub mod at;
 pub mod audio;
+#[cfg(feature = "ble")]
+pub mod ble;
 pub mod dbi;
+pub mod debug;
+pub mod display;
 pub mod dma;
+pub mod dvp;
 pub mod emac;
+pub mod flash;
 pub mod glb;
 pub mod gpio;
 pub mod gpip;
+pub mod h264;
 pub mod hbn;
 pub mod i2c;
 pub mod i2s;
+pub mod ipc;
 pub mod ir;
+pub mod kys;
 pub mod lz4d;
+pub mod mipi_csi;
+pub mod mjpeg;
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(feature = "modbus")]
+pub mod modbus;
 pub mod psram;
 pub mod pwm;
+pub mod qdec;
+#[cfg(feature = "rom-api")]
+pub mod rom_api;
 pub mod sdio;
 pub mod sec;
 pub mod spi;
+pub mod sync;
 pub mod timer;
+pub mod touch;
 pub mod uart;
 pub mod usb;
+#[cfg(feature = "wifi")]
+pub mod wifi;
 
 #[doc(hidden)]
 pub mod prelude {
     pub use crate::dma::DmaExt as _;
-    pub use crate::gpio::{IntoPad as _, IntoPadv2 as _};
+    pub use crate::gpio::{IntoPad as _, IntoPadv1 as _, IntoPadv2 as _};
     pub use crate::lz4d::Lz4dExt as _;
     pub use crate::uart::UartExt as _;
-    pub use embedded_hal::digital::{InputPin as _, OutputPin as _, PinState};
+    pub use embedded_hal::digital::{
+        InputPin as _, OutputPin as _, PinState, StatefulOutputPin as _,
+    };
     pub use embedded_hal::i2c::I2c as _;
     pub use embedded_hal::pwm::SetDutyCycle as _;
     pub use embedded_io::{Read as _, Write as _};
@@ -0,0 +1,148 @@
+/// Generate enable, disable and check methods for a one-bit field of a register.
+///
+/// The register must be a tuple struct over an integer, and `$field` a one-bit
+/// [`BitField`](crate::BitField) over the same integer.
+/// With a trailing description, documents are generated as `Enable <description>.`,
+/// `Disable <description>.` and `Check if <description> is enabled.`; otherwise each
+/// method name is preceded by its own documents.
+macro_rules! register_flag {
+    ($enable:ident, $disable:ident, $is_enabled:ident, $field:ty, $what:literal) => {
+        register_flag! {
+            #[doc = concat!("Enable ", $what, ".")]
+            $enable,
+            #[doc = concat!("Disable ", $what, ".")]
+            $disable,
+            #[doc = concat!("Check if ", $what, " is enabled.")]
+            $is_enabled,
+            $field
+        }
+    };
+    (
+        $(#[$enable_attr:meta])* $enable:ident,
+        $(#[$disable_attr:meta])* $disable:ident,
+        $(#[$is_enabled_attr:meta])* $is_enabled:ident,
+        $field:ty
+    ) => {
+        $(#[$enable_attr])*
+        #[inline]
+        pub const fn $enable(self) -> Self {
+            Self(<$field>::from(self.0).enable())
+        }
+        $(#[$disable_attr])*
+        #[inline]
+        pub const fn $disable(self) -> Self {
+            Self(<$field>::from(self.0).disable())
+        }
+        $(#[$is_enabled_attr])*
+        #[inline]
+        pub const fn $is_enabled(self) -> bool {
+            <$field>::from(self.0).is_enabled()
+        }
+    };
+}
+
+/// Generate set and get methods for a numeric field of a register.
+///
+/// The register must be a tuple struct over an integer, and `$field` a
+/// [`BitField`](crate::BitField) over the same integer; bits of the value beyond the
+/// field are dropped.
+/// With a trailing description, documents are generated as `Set <description>.` and
+/// `Get <description>.`; otherwise each method name is preceded by its own documents.
+macro_rules! register_field {
+    ($set:ident, $get:ident, $ty:ty, $field:ty, $what:literal) => {
+        register_field! {
+            #[doc = concat!("Set ", $what, ".")]
+            $set,
+            #[doc = concat!("Get ", $what, ".")]
+            $get: $ty = $field
+        }
+    };
+    (
+        $(#[$set_attr:meta])* $set:ident,
+        $(#[$get_attr:meta])* $get:ident: $ty:ty = $field:ty
+    ) => {
+        $(#[$set_attr])*
+        #[inline]
+        pub const fn $set(self, val: $ty) -> Self {
+            Self(<$field>::from(self.0).set(val as usize))
+        }
+        $(#[$get_attr])*
+        #[inline]
+        pub const fn $get(self) -> $ty {
+            <$field>::from(self.0).get() as $ty
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct Register(u32);
+
+    mod register {
+        use crate::BitField;
+
+        pub(crate) type Flag = BitField<1, 5, u32>;
+        pub(crate) type Field = BitField<10, 12, u32>;
+        pub(crate) type Low = BitField<1, 0, u32>;
+        pub(crate) type Top = BitField<4, 28, u32>;
+    }
+
+    impl Register {
+        register_flag!(
+            enable_flag,
+            disable_flag,
+            is_flag_enabled,
+            register::Flag,
+            "test flag"
+        );
+        register_field!(set_field, field, u16, register::Field, "test field");
+        register_flag! {
+            /// Enable with own document.
+            enable_low,
+            /// Disable with own document.
+            disable_low,
+            /// Check with own document.
+            is_low_enabled,
+            register::Low
+        }
+        register_field! {
+            /// Set with own document.
+            set_top,
+            /// Get with own document.
+            top: u8 = register::Top
+        }
+    }
+
+    #[test]
+    fn macro_register_flag() {
+        let mut val = Register(0xffff_ffff);
+        val = val.disable_flag();
+        assert_eq!(val.0, 0xffff_ffdf);
+        assert!(!val.is_flag_enabled());
+        val = Register(0).enable_flag();
+        assert_eq!(val.0, 0x0000_0020);
+        assert!(val.is_flag_enabled());
+        val = val.enable_low();
+        assert_eq!(val.0, 0x0000_0021);
+        assert!(val.is_low_enabled());
+        assert_eq!(val.disable_low().0, 0x0000_0020);
+    }
+
+    #[test]
+    fn macro_register_field() {
+        let mut val = Register(0);
+        val = val.set_field(0x3ff);
+        assert_eq!(val.0, 0x003f_f000);
+        assert_eq!(val.field(), 0x3ff);
+        val = Register(0xffff_ffff).set_field(0x155);
+        assert_eq!(val.0, 0xffd5_5fff);
+        assert_eq!(val.field(), 0x155);
+        // Bits beyond the field are dropped.
+        val = Register(0).set_field(0x1401);
+        assert_eq!(val.0, 0x0000_1000);
+        val = Register(0).set_top(0xa);
+        assert_eq!(val.0, 0xa000_0000);
+        assert_eq!(val.top(), 0xa);
+    }
+}
@@ -0,0 +1,405 @@
+//! MIPI Camera Serial Interface receiver peripheral.
+//!
+//! The receiver decodes CSI-2 packets from up to two D-PHY data lanes and forwards
+//! pixel data of selected virtual channels to DVP capture units. Frames are then
+//! written into memory by [`Dvp`](crate::dvp::Dvp) as with parallel camera sensors.
+
+use core::ops::Deref;
+use volatile_register::{RO, RW};
+
+/// MIPI Camera Serial Interface receiver registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Receiver configuration register.
+    pub config: RW<CsiConfig>,
+    _reserved0: [u8; 0xc],
+    /// Interrupt state register.
+    pub interrupt_state: RO<u32>,
+    /// Interrupt mask register.
+    pub interrupt_mask: RW<u32>,
+    /// Interrupt clear register.
+    pub interrupt_clear: RW<u32>,
+    /// Interrupt enable register.
+    pub interrupt_enable: RW<u32>,
+    /// Generic short packet buffer state.
+    pub generic_buffer_state: RO<u32>,
+    /// Generic short packet buffer read data.
+    pub generic_buffer_read: RO<u32>,
+    _reserved1: [u8; 0x58],
+    /// D-PHY lane configuration register.
+    pub dphy_config: RW<DphyConfig>,
+    /// D-PHY timing configuration registers.
+    pub dphy_timing: [RW<u32>; 5],
+}
+
+/// Receiver configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct CsiConfig(u32);
+
+impl CsiConfig {
+    const ENABLE: u32 = 1 << 0;
+    const LANE_COUNT: u32 = 1 << 1;
+    const DATA_BIT_INVERSE: u32 = 1 << 3;
+    const SYNC_SHORT_PACKET: u32 = 1 << 4;
+    const UNPACK: u32 = 1 << 5;
+    const VIRTUAL_CHANNEL: [u32; 2] = [0x3 << 12, 0x3 << 14];
+
+    /// Enable receiver.
+    #[inline]
+    pub const fn enable_receiver(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable receiver.
+    #[inline]
+    pub const fn disable_receiver(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if receiver is enabled.
+    #[inline]
+    pub const fn is_receiver_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Set number of data lanes.
+    #[inline]
+    pub const fn set_lanes(self, val: Lanes) -> Self {
+        Self((self.0 & !Self::LANE_COUNT) | ((val as u32) << 1))
+    }
+    /// Get number of data lanes.
+    #[inline]
+    pub const fn lanes(self) -> Lanes {
+        if self.0 & Self::LANE_COUNT != 0 {
+            Lanes::Two
+        } else {
+            Lanes::One
+        }
+    }
+    /// Enable bit order inverse on each received byte.
+    #[inline]
+    pub const fn enable_data_bit_inverse(self) -> Self {
+        Self(self.0 | Self::DATA_BIT_INVERSE)
+    }
+    /// Disable bit order inverse on each received byte.
+    #[inline]
+    pub const fn disable_data_bit_inverse(self) -> Self {
+        Self(self.0 & !Self::DATA_BIT_INVERSE)
+    }
+    /// Check if bit order inverse is enabled.
+    #[inline]
+    pub const fn is_data_bit_inverse_enabled(self) -> bool {
+        self.0 & Self::DATA_BIT_INVERSE != 0
+    }
+    /// Enable generating sync signals from line start and end short packets.
+    #[inline]
+    pub const fn enable_sync_short_packet(self) -> Self {
+        Self(self.0 | Self::SYNC_SHORT_PACKET)
+    }
+    /// Disable generating sync signals from line start and end short packets.
+    #[inline]
+    pub const fn disable_sync_short_packet(self) -> Self {
+        Self(self.0 & !Self::SYNC_SHORT_PACKET)
+    }
+    /// Check if sync signals are generated from line short packets.
+    #[inline]
+    pub const fn is_sync_short_packet_enabled(self) -> bool {
+        self.0 & Self::SYNC_SHORT_PACKET != 0
+    }
+    /// Enable unpacking RAW10 and RAW12 pixels into 16-bit words.
+    #[inline]
+    pub const fn enable_unpack(self) -> Self {
+        Self(self.0 | Self::UNPACK)
+    }
+    /// Disable unpacking pixels.
+    #[inline]
+    pub const fn disable_unpack(self) -> Self {
+        Self(self.0 & !Self::UNPACK)
+    }
+    /// Check if pixel unpacking is enabled.
+    #[inline]
+    pub const fn is_unpack_enabled(self) -> bool {
+        self.0 & Self::UNPACK != 0
+    }
+    /// Set virtual channel forwarded to DVP output `D`.
+    #[inline]
+    pub const fn set_virtual_channel<const D: usize>(self, val: VirtualChannel) -> Self {
+        let shift = 12 + 2 * D;
+        Self((self.0 & !Self::VIRTUAL_CHANNEL[D]) | ((val as u32) << shift))
+    }
+    /// Get virtual channel forwarded to DVP output `D`.
+    #[inline]
+    pub const fn virtual_channel<const D: usize>(self) -> VirtualChannel {
+        let shift = 12 + 2 * D;
+        match (self.0 & Self::VIRTUAL_CHANNEL[D]) >> shift {
+            0 => VirtualChannel::Vc0,
+            1 => VirtualChannel::Vc1,
+            2 => VirtualChannel::Vc2,
+            _ => VirtualChannel::Vc3,
+        }
+    }
+}
+
+/// Number of D-PHY data lanes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Lanes {
+    /// Data lane 0 only.
+    One = 0,
+    /// Data lane 0 and 1.
+    Two = 1,
+}
+
+/// CSI-2 virtual channel identifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum VirtualChannel {
+    /// Virtual channel 0.
+    Vc0 = 0,
+    /// Virtual channel 1.
+    Vc1 = 1,
+    /// Virtual channel 2.
+    Vc2 = 2,
+    /// Virtual channel 3.
+    Vc3 = 3,
+}
+
+/// D-PHY lane configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct DphyConfig(u32);
+
+impl DphyConfig {
+    const DATA_LANE_ENABLE: [u32; 2] = [1 << 0, 1 << 1];
+    const CLOCK_LANE_ENABLE: u32 = 1 << 2;
+    const DATA_LANE_STOP: [u32; 2] = [1 << 4, 1 << 5];
+    const CLOCK_LANE_STOP: u32 = 1 << 6;
+
+    /// Enable data lane `L`.
+    #[inline]
+    pub const fn enable_data_lane<const L: usize>(self) -> Self {
+        Self(self.0 | Self::DATA_LANE_ENABLE[L])
+    }
+    /// Disable data lane `L`.
+    #[inline]
+    pub const fn disable_data_lane<const L: usize>(self) -> Self {
+        Self(self.0 & !Self::DATA_LANE_ENABLE[L])
+    }
+    /// Check if data lane `L` is enabled.
+    #[inline]
+    pub const fn is_data_lane_enabled<const L: usize>(self) -> bool {
+        self.0 & Self::DATA_LANE_ENABLE[L] != 0
+    }
+    /// Enable clock lane.
+    #[inline]
+    pub const fn enable_clock_lane(self) -> Self {
+        Self(self.0 | Self::CLOCK_LANE_ENABLE)
+    }
+    /// Disable clock lane.
+    #[inline]
+    pub const fn disable_clock_lane(self) -> Self {
+        Self(self.0 & !Self::CLOCK_LANE_ENABLE)
+    }
+    /// Check if clock lane is enabled.
+    #[inline]
+    pub const fn is_clock_lane_enabled(self) -> bool {
+        self.0 & Self::CLOCK_LANE_ENABLE != 0
+    }
+    /// Check if data lane `L` is in stop state.
+    #[inline]
+    pub const fn is_data_lane_stopped<const L: usize>(self) -> bool {
+        self.0 & Self::DATA_LANE_STOP[L] != 0
+    }
+    /// Check if clock lane is in stop state.
+    #[inline]
+    pub const fn is_clock_lane_stopped(self) -> bool {
+        self.0 & Self::CLOCK_LANE_STOP != 0
+    }
+}
+
+/// MIPI Camera Serial Interface receiver configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Config {
+    /// Number of data lanes.
+    pub lanes: Lanes,
+    /// Virtual channel forwarded to DVP output 0.
+    pub dvp0_channel: VirtualChannel,
+    /// Virtual channel forwarded to DVP output 1.
+    pub dvp1_channel: VirtualChannel,
+    /// Unpack RAW10 and RAW12 pixels into 16-bit words.
+    pub unpack: bool,
+}
+
+impl Default for Config {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            lanes: Lanes::Two,
+            dvp0_channel: VirtualChannel::Vc0,
+            dvp1_channel: VirtualChannel::Vc1,
+            unpack: false,
+        }
+    }
+}
+
+impl Config {
+    /// Set number of data lanes.
+    #[inline]
+    pub const fn set_lanes(mut self, lanes: Lanes) -> Self {
+        self.lanes = lanes;
+        self
+    }
+    /// Set virtual channels forwarded to DVP output 0 and 1.
+    #[inline]
+    pub const fn set_virtual_channels(
+        mut self,
+        dvp0: VirtualChannel,
+        dvp1: VirtualChannel,
+    ) -> Self {
+        self.dvp0_channel = dvp0;
+        self.dvp1_channel = dvp1;
+        self
+    }
+    /// Enable or disable pixel unpacking.
+    #[inline]
+    pub const fn set_unpack(mut self, unpack: bool) -> Self {
+        self.unpack = unpack;
+        self
+    }
+}
+
+/// Managed MIPI Camera Serial Interface receiver.
+pub struct MipiCsi<CSI> {
+    csi: CSI,
+}
+
+impl<CSI: Deref<Target = RegisterBlock>> MipiCsi<CSI> {
+    /// Create a receiver, enable D-PHY lanes and start receiving.
+    #[inline]
+    pub fn new(csi: CSI, config: Config) -> Self {
+        let mut dphy = DphyConfig(0).enable_clock_lane().enable_data_lane::<0>();
+        if config.lanes == Lanes::Two {
+            dphy = dphy.enable_data_lane::<1>();
+        }
+        let mut val = CsiConfig(0)
+            .set_lanes(config.lanes)
+            .enable_sync_short_packet()
+            .set_virtual_channel::<0>(config.dvp0_channel)
+            .set_virtual_channel::<1>(config.dvp1_channel);
+        if config.unpack {
+            val = val.enable_unpack();
+        }
+        unsafe {
+            csi.config.write(val.disable_receiver());
+            csi.dphy_config.write(dphy);
+            csi.config.write(val.enable_receiver());
+        }
+        Self { csi }
+    }
+    /// Forward virtual channel to DVP output `D`.
+    #[inline]
+    pub fn set_virtual_channel<const D: usize>(&mut self, val: VirtualChannel) {
+        unsafe { self.csi.config.modify(|v| v.set_virtual_channel::<D>(val)) };
+    }
+    /// Check if all enabled lanes are in stop state, i.e. the sensor is idle.
+    #[inline]
+    pub fn is_idle(&self) -> bool {
+        let dphy = self.csi.dphy_config.read();
+        let lanes = self.csi.config.read().lanes();
+        dphy.is_clock_lane_stopped()
+            && dphy.is_data_lane_stopped::<0>()
+            && (lanes == Lanes::One || dphy.is_data_lane_stopped::<1>())
+    }
+    /// Stop receiving and release the peripheral.
+    #[inline]
+    pub fn free(self) -> CSI {
+        unsafe {
+            self.csi.config.modify(|v| v.disable_receiver());
+            self.csi.dphy_config.write(DphyConfig(0));
+        }
+        self.csi
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CsiConfig, DphyConfig, Lanes, RegisterBlock, VirtualChannel};
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_state), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_mask), 0x14);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_clear), 0x18);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_enable), 0x1c);
+        assert_eq!(offset_of!(RegisterBlock, generic_buffer_state), 0x20);
+        assert_eq!(offset_of!(RegisterBlock, generic_buffer_read), 0x24);
+        assert_eq!(offset_of!(RegisterBlock, dphy_config), 0x80);
+        assert_eq!(offset_of!(RegisterBlock, dphy_timing), 0x84);
+    }
+
+    #[test]
+    fn struct_csi_config_functions() {
+        let mut val = CsiConfig(0x0);
+
+        val = val.enable_receiver();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_receiver_enabled());
+        val = val.disable_receiver();
+        assert!(!val.is_receiver_enabled());
+
+        val = val.set_lanes(Lanes::Two);
+        assert_eq!(val.0, 0x0000_0002);
+        assert_eq!(val.lanes(), Lanes::Two);
+        val = val.set_lanes(Lanes::One);
+        assert_eq!(val.lanes(), Lanes::One);
+
+        val = val.enable_data_bit_inverse();
+        assert_eq!(val.0, 0x0000_0008);
+        assert!(val.is_data_bit_inverse_enabled());
+        val = val.disable_data_bit_inverse();
+        assert!(!val.is_data_bit_inverse_enabled());
+
+        val = val.enable_sync_short_packet();
+        assert_eq!(val.0, 0x0000_0010);
+        assert!(val.is_sync_short_packet_enabled());
+        val = val.disable_sync_short_packet();
+        assert!(!val.is_sync_short_packet_enabled());
+
+        val = val.enable_unpack();
+        assert_eq!(val.0, 0x0000_0020);
+        assert!(val.is_unpack_enabled());
+        val = val.disable_unpack();
+        assert!(!val.is_unpack_enabled());
+
+        val = val.set_virtual_channel::<0>(VirtualChannel::Vc3);
+        assert_eq!(val.0, 0x0000_3000);
+        assert_eq!(val.virtual_channel::<0>(), VirtualChannel::Vc3);
+        val = val.set_virtual_channel::<1>(VirtualChannel::Vc2);
+        assert_eq!(val.0, 0x0000_b000);
+        assert_eq!(val.virtual_channel::<1>(), VirtualChannel::Vc2);
+    }
+
+    #[test]
+    fn struct_dphy_config_functions() {
+        let mut val = DphyConfig(0x0);
+
+        val = val.enable_data_lane::<0>();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_data_lane_enabled::<0>());
+        val = val.enable_data_lane::<1>();
+        assert_eq!(val.0, 0x0000_0003);
+        val = val.disable_data_lane::<0>().disable_data_lane::<1>();
+        assert!(!val.is_data_lane_enabled::<1>());
+
+        val = val.enable_clock_lane();
+        assert_eq!(val.0, 0x0000_0004);
+        assert!(val.is_clock_lane_enabled());
+        val = val.disable_clock_lane();
+        assert!(!val.is_clock_lane_enabled());
+
+        val = DphyConfig(0x0000_0070);
+        assert!(val.is_data_lane_stopped::<0>());
+        assert!(val.is_data_lane_stopped::<1>());
+        assert!(val.is_clock_lane_stopped());
+    }
+}
@@ -0,0 +1,641 @@
+//! Motion JPEG hardware encoder.
+//!
+//! The encoder reads YUV frames from memory, usually written by a [`Dvp`](crate::dvp::Dvp)
+//! capture unit, and writes JPEG images into an output ring buffer. Start address and
+//! size of each finished image are queued in a hardware FIFO, so a camera-to-JPEG
+//! pipeline runs without CPU copying any pixel.
+
+use core::ops::Deref;
+use volatile_register::{RO, RW, WO};
+
+/// Motion JPEG encoder registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Encoder configuration register.
+    pub config: RW<MjpegConfig>,
+    /// Software mode control register.
+    pub software: RW<Software>,
+    /// Start address of luma or packed YUV input.
+    pub y_address: RW<u32>,
+    /// Start address of chroma input.
+    pub uv_address: RW<u32>,
+    _reserved0: [u8; 0x4],
+    /// Start address of output ring buffer.
+    pub output_address: RW<u32>,
+    /// Size of output ring buffer in bytes.
+    pub output_bytes: RW<u32>,
+    /// Interrupt state, enable and frame count register.
+    pub status: RW<Status>,
+    /// Frame FIFO pop and interrupt clear register.
+    pub frame_pop: WO<FramePop>,
+    /// Input frame size in pixels and lines.
+    pub frame_size: RW<FrameSize>,
+    _reserved1: [u8; 0x58],
+    /// Completed images, index 0 is the oldest image.
+    pub frames: [FrameInfo; 4],
+    _reserved2: [u8; 0x360],
+    /// Luma quantization table, two 16-bit entries per word in zigzag order.
+    pub luma_quantization: [RW<u32>; 32],
+    _reserved3: [u8; 0x380],
+    /// Chroma quantization table, two 16-bit entries per word in zigzag order.
+    pub chroma_quantization: [RW<u32>; 32],
+}
+
+/// Completed image information registers.
+#[repr(C)]
+pub struct FrameInfo {
+    /// Start address of image.
+    pub address: RO<u32>,
+    /// Size of image in bytes.
+    pub bytes: RO<u32>,
+}
+
+/// Encoder configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct MjpegConfig(u32);
+
+impl MjpegConfig {
+    const ENABLE: u32 = 1 << 0;
+    const INPUT_FORMAT: u32 = 0x3 << 8;
+
+    /// Enable encoder.
+    #[inline]
+    pub const fn enable_encoder(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable encoder.
+    #[inline]
+    pub const fn disable_encoder(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if encoder is enabled.
+    #[inline]
+    pub const fn is_encoder_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Set input pixel format.
+    #[inline]
+    pub const fn set_input_format(self, val: InputFormat) -> Self {
+        Self((self.0 & !Self::INPUT_FORMAT) | ((val as u32) << 8))
+    }
+    /// Get input pixel format.
+    #[inline]
+    pub const fn input_format(self) -> InputFormat {
+        match (self.0 & Self::INPUT_FORMAT) >> 8 {
+            0 => InputFormat::Yuv420,
+            1 => InputFormat::Yuv400,
+            2 => InputFormat::Yuv422Planar,
+            _ => InputFormat::Yuv422Packed,
+        }
+    }
+}
+
+/// Encoder input pixel format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum InputFormat {
+    /// Luma plane followed by interleaved half height chroma plane.
+    Yuv420 = 0,
+    /// Luma plane only, encoded as grayscale image.
+    Yuv400 = 1,
+    /// Luma plane followed by interleaved full height chroma plane.
+    Yuv422Planar = 2,
+    /// Interleaved YUYV in a single plane.
+    Yuv422Packed = 3,
+}
+
+/// Software mode control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Software(u32);
+
+impl Software {
+    const FRAMES: u32 = 0x1f;
+    const KICK: u32 = 1 << 6;
+    const MODE: u32 = 1 << 8;
+
+    /// Set number of frames encoded on each kick.
+    #[inline]
+    pub const fn set_frames(self, val: u8) -> Self {
+        Self((self.0 & !Self::FRAMES) | (val as u32 & Self::FRAMES))
+    }
+    /// Get number of frames encoded on each kick.
+    #[inline]
+    pub const fn frames(self) -> u8 {
+        (self.0 & Self::FRAMES) as u8
+    }
+    /// Start encoding frames in software mode.
+    #[inline]
+    pub const fn kick(self) -> Self {
+        Self(self.0 | Self::KICK)
+    }
+    /// Enable software mode, frames are encoded only when kicked.
+    #[inline]
+    pub const fn enable_software_mode(self) -> Self {
+        Self(self.0 | Self::MODE)
+    }
+    /// Disable software mode, frames are encoded when capture unit finishes them.
+    #[inline]
+    pub const fn disable_software_mode(self) -> Self {
+        Self(self.0 & !Self::MODE)
+    }
+    /// Check if software mode is enabled.
+    #[inline]
+    pub const fn is_software_mode_enabled(self) -> bool {
+        self.0 & Self::MODE != 0
+    }
+}
+
+/// Interrupt state, enable and frame count register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Status(u32);
+
+impl Status {
+    const INTERRUPT_ENABLE: u32 = 0x7;
+    const FRAME_COUNT: u32 = 0x1f << 8;
+
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub const fn has_interrupt(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32 + 16)) != 0
+    }
+    /// Enable interrupt.
+    #[inline]
+    pub const fn enable_interrupt(self, val: Interrupt) -> Self {
+        Self((self.0 & Self::INTERRUPT_ENABLE) | (1 << (val as u32)))
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub const fn disable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 & Self::INTERRUPT_ENABLE & !(1 << (val as u32)))
+    }
+    /// Check if interrupt is enabled.
+    #[inline]
+    pub const fn is_interrupt_enabled(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32)) != 0
+    }
+    /// Get number of completed images in frame FIFO.
+    #[inline]
+    pub const fn frame_count(self) -> u8 {
+        ((self.0 & Self::FRAME_COUNT) >> 8) as u8
+    }
+}
+
+/// Encoder interrupt event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Interrupt {
+    /// An image has been written into output buffer.
+    FrameComplete = 0,
+    /// Output buffer has no room for the next image.
+    BufferOverflow = 1,
+    /// Input frame was overwritten before encoding finished.
+    InputOverwritten = 2,
+}
+
+/// Frame FIFO pop and interrupt clear register.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct FramePop(u32);
+
+impl FramePop {
+    const POP: u32 = 1 << 0;
+
+    /// Release the oldest image in frame FIFO.
+    #[inline]
+    pub const fn pop_frame(self) -> Self {
+        Self(self.0 | Self::POP)
+    }
+    /// Clear interrupt flag.
+    #[inline]
+    pub const fn clear_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32 + 8)))
+    }
+}
+
+/// Input frame size register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct FrameSize(u32);
+
+impl FrameSize {
+    const WIDTH: u32 = 0xfff;
+    const HEIGHT: u32 = 0xfff << 16;
+
+    /// Set width in pixels.
+    #[inline]
+    pub const fn set_width(self, val: u16) -> Self {
+        Self((self.0 & !Self::WIDTH) | (val as u32 & Self::WIDTH))
+    }
+    /// Get width in pixels.
+    #[inline]
+    pub const fn width(self) -> u16 {
+        (self.0 & Self::WIDTH) as u16
+    }
+    /// Set height in lines.
+    #[inline]
+    pub const fn set_height(self, val: u16) -> Self {
+        Self((self.0 & !Self::HEIGHT) | (((val as u32) << 16) & Self::HEIGHT))
+    }
+    /// Get height in lines.
+    #[inline]
+    pub const fn height(self) -> u16 {
+        ((self.0 & Self::HEIGHT) >> 16) as u16
+    }
+}
+
+/// Base luma quantization table at quality 50, in zigzag order.
+const LUMA_QUANTIZATION: [u8; 64] = [
+    16, 11, 12, 14, 12, 10, 16, 14, 13, 14, 18, 17, 16, 19, 24, 40, 26, 24, 22, 22, 24, 49, 35, 37,
+    29, 40, 58, 51, 61, 60, 57, 51, 56, 55, 64, 72, 92, 78, 64, 68, 87, 69, 55, 56, 80, 109, 81,
+    87, 95, 98, 103, 104, 103, 62, 77, 113, 121, 112, 100, 120, 92, 101, 103, 99,
+];
+
+/// Base chroma quantization table at quality 50, in zigzag order.
+const CHROMA_QUANTIZATION: [u8; 64] = [
+    17, 18, 18, 24, 21, 24, 47, 26, 26, 47, 99, 66, 56, 66, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99,
+    99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99,
+    99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99, 99,
+];
+
+/// Scale a base quantization table by JPEG quality factor from 1 to 100.
+#[inline]
+pub const fn quantization_table(base: &[u8; 64], quality: u8) -> [u16; 64] {
+    let scale = if quality < 50 {
+        5000 / quality as u32
+    } else {
+        200 - 2 * quality as u32
+    };
+    let mut ans = [0; 64];
+    let mut i = 0;
+    while i < 64 {
+        let val = (base[i] as u32 * scale + 50) / 100;
+        ans[i] = if val == 0 {
+            1
+        } else if val > 255 {
+            255
+        } else {
+            val as u16
+        };
+        i += 1;
+    }
+    ans
+}
+
+/// Motion JPEG encoder configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Config {
+    /// Input pixel format.
+    pub input_format: InputFormat,
+    /// Input resolution in pixels and lines.
+    pub resolution: (u16, u16),
+    /// JPEG quality factor from 1 to 100.
+    pub quality: u8,
+}
+
+impl Default for Config {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            input_format: InputFormat::Yuv422Packed,
+            resolution: (640, 480),
+            quality: 75,
+        }
+    }
+}
+
+impl Config {
+    /// Set input pixel format.
+    #[inline]
+    pub const fn set_input_format(mut self, input_format: InputFormat) -> Self {
+        self.input_format = input_format;
+        self
+    }
+    /// Set input resolution.
+    #[inline]
+    pub const fn set_resolution(mut self, width: u16, height: u16) -> Self {
+        self.resolution = (width, height);
+        self
+    }
+    /// Set JPEG quality factor.
+    #[inline]
+    pub const fn set_quality(mut self, quality: u8) -> Self {
+        self.quality = quality;
+        self
+    }
+    /// Size of luma or packed YUV input of one frame in bytes.
+    #[inline]
+    pub const fn y_bytes(&self) -> u32 {
+        let pixels = self.resolution.0 as u32 * self.resolution.1 as u32;
+        match self.input_format {
+            InputFormat::Yuv422Packed => pixels * 2,
+            _ => pixels,
+        }
+    }
+    /// Size of chroma input of one frame in bytes.
+    #[inline]
+    pub const fn uv_bytes(&self) -> u32 {
+        let pixels = self.resolution.0 as u32 * self.resolution.1 as u32;
+        match self.input_format {
+            InputFormat::Yuv420 => pixels / 2,
+            InputFormat::Yuv422Planar => pixels,
+            InputFormat::Yuv400 | InputFormat::Yuv422Packed => 0,
+        }
+    }
+}
+
+/// Errors on encoder configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConfigError {
+    /// Buffer is not aligned to 8 bytes.
+    BufferAlignment,
+    /// Input buffer cannot hold a frame.
+    BufferTooSmall,
+    /// Resolution is zero or not a multiple of 16 pixels.
+    InvalidResolution,
+    /// Quality factor is not in range 1 to 100.
+    InvalidQuality,
+}
+
+/// Input frame location of encoder.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Input {
+    /// Luma or packed YUV plane.
+    pub y: &'static [u8],
+    /// Chroma plane, unused for grayscale and packed formats.
+    pub uv: &'static [u8],
+}
+
+/// Managed Motion JPEG encoder.
+pub struct Mjpeg<MJPEG> {
+    mjpeg: MJPEG,
+    output: &'static mut [u8],
+}
+
+impl<MJPEG: Deref<Target = RegisterBlock>> Mjpeg<MJPEG> {
+    /// Create an encoder reading frames from `input` and writing images into `output`.
+    #[inline]
+    pub fn new(
+        mjpeg: MJPEG,
+        config: Config,
+        input: Input,
+        output: &'static mut [u8],
+    ) -> Result<Self, ConfigError> {
+        if !(1..=100).contains(&config.quality) {
+            return Err(ConfigError::InvalidQuality);
+        }
+        let (width, height) = config.resolution;
+        if width == 0 || height == 0 || width % 16 != 0 || height % 16 != 0 {
+            return Err(ConfigError::InvalidResolution);
+        }
+        let aligned = |ptr: *const u8| (ptr as usize).is_multiple_of(8);
+        if !aligned(input.y.as_ptr())
+            || !aligned(output.as_ptr())
+            || (config.uv_bytes() != 0 && !aligned(input.uv.as_ptr()))
+        {
+            return Err(ConfigError::BufferAlignment);
+        }
+        if input.y.len() < config.y_bytes() as usize || input.uv.len() < config.uv_bytes() as usize
+        {
+            return Err(ConfigError::BufferTooSmall);
+        }
+        let luma = quantization_table(&LUMA_QUANTIZATION, config.quality);
+        let chroma = quantization_table(&CHROMA_QUANTIZATION, config.quality);
+        let val = MjpegConfig(0).set_input_format(config.input_format);
+        unsafe {
+            mjpeg.config.write(val.disable_encoder());
+            mjpeg.y_address.write(input.y.as_ptr() as u32);
+            mjpeg.uv_address.write(input.uv.as_ptr() as u32);
+            mjpeg.output_address.write(output.as_ptr() as u32);
+            mjpeg.output_bytes.write(output.len() as u32);
+            mjpeg
+                .frame_size
+                .write(FrameSize(0).set_width(width).set_height(height));
+            for i in 0..32 {
+                mjpeg.luma_quantization[i]
+                    .write(luma[2 * i] as u32 | (luma[2 * i + 1] as u32) << 16);
+                mjpeg.chroma_quantization[i]
+                    .write(chroma[2 * i] as u32 | (chroma[2 * i + 1] as u32) << 16);
+            }
+        }
+        Ok(Self { mjpeg, output })
+    }
+    /// Encode every frame finished by the capture unit.
+    #[inline]
+    pub fn start(&mut self) {
+        unsafe {
+            self.mjpeg.software.modify(|v| v.disable_software_mode());
+            self.mjpeg.config.modify(|v| v.enable_encoder());
+        }
+    }
+    /// Encode `frames` frames from input buffer once.
+    #[inline]
+    pub fn encode(&mut self, frames: u8) {
+        unsafe {
+            self.mjpeg.config.modify(|v| v.enable_encoder());
+            self.mjpeg
+                .software
+                .write(Software(0).enable_software_mode().set_frames(frames).kick());
+        }
+    }
+    /// Stop encoding.
+    #[inline]
+    pub fn stop(&mut self) {
+        unsafe { self.mjpeg.config.modify(|v| v.disable_encoder()) };
+    }
+    /// Enable interrupt.
+    #[inline]
+    pub fn enable_interrupt(&mut self, val: Interrupt) {
+        unsafe { self.mjpeg.status.modify(|v| v.enable_interrupt(val)) };
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub fn disable_interrupt(&mut self, val: Interrupt) {
+        unsafe { self.mjpeg.status.modify(|v| v.disable_interrupt(val)) };
+    }
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub fn has_interrupt(&self, val: Interrupt) -> bool {
+        self.mjpeg.status.read().has_interrupt(val)
+    }
+    /// Clear interrupt flag.
+    #[inline]
+    pub fn clear_interrupt(&mut self, val: Interrupt) {
+        unsafe {
+            self.mjpeg
+                .frame_pop
+                .write(FramePop::default().clear_interrupt(val))
+        };
+    }
+    /// Get number of completed images waiting to be released.
+    #[inline]
+    pub fn frame_count(&self) -> usize {
+        self.mjpeg.status.read().frame_count() as usize
+    }
+    /// Get the oldest completed JPEG image, if any.
+    ///
+    /// The image stays valid until it is released by [`Mjpeg::release_frame`].
+    #[inline]
+    pub fn frame(&self) -> Option<&[u8]> {
+        if self.frame_count() == 0 {
+            return None;
+        }
+        let info = &self.mjpeg.frames[0];
+        let offset = (info.address.read() as usize).checked_sub(self.output.as_ptr() as usize)?;
+        self.output
+            .get(offset..offset.checked_add(info.bytes.read() as usize)?)
+    }
+    /// Release the oldest completed image to hardware.
+    #[inline]
+    pub fn release_frame(&mut self) {
+        unsafe { self.mjpeg.frame_pop.write(FramePop::default().pop_frame()) };
+    }
+    /// Handle frame complete interrupt, calling `f` on every completed image.
+    ///
+    /// Call this function in the `mjpeg` interrupt handler.
+    #[inline]
+    pub fn on_frame_complete(&mut self, mut f: impl FnMut(&[u8])) {
+        self.clear_interrupt(Interrupt::FrameComplete);
+        while let Some(frame) = self.frame() {
+            f(frame);
+            self.release_frame();
+        }
+    }
+    /// Stop encoding and release peripheral and output buffer.
+    #[inline]
+    pub fn free(self) -> (MJPEG, &'static mut [u8]) {
+        unsafe { self.mjpeg.config.modify(|v| v.disable_encoder()) };
+        (self.mjpeg, self.output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CHROMA_QUANTIZATION, Config, FrameInfo, FramePop, FrameSize, InputFormat, Interrupt,
+        LUMA_QUANTIZATION, MjpegConfig, RegisterBlock, Software, Status, quantization_table,
+    };
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, software), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, y_address), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, uv_address), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, output_address), 0x14);
+        assert_eq!(offset_of!(RegisterBlock, output_bytes), 0x18);
+        assert_eq!(offset_of!(RegisterBlock, status), 0x1c);
+        assert_eq!(offset_of!(RegisterBlock, frame_pop), 0x20);
+        assert_eq!(offset_of!(RegisterBlock, frame_size), 0x24);
+        assert_eq!(offset_of!(RegisterBlock, frames), 0x80);
+        assert_eq!(size_of::<FrameInfo>(), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, luma_quantization), 0x400);
+        assert_eq!(offset_of!(RegisterBlock, chroma_quantization), 0x800);
+    }
+
+    #[test]
+    fn struct_mjpeg_config_functions() {
+        let mut val = MjpegConfig(0x0);
+
+        val = val.enable_encoder();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_encoder_enabled());
+        val = val.disable_encoder();
+        assert!(!val.is_encoder_enabled());
+
+        val = val.set_input_format(InputFormat::Yuv422Packed);
+        assert_eq!(val.0, 0x0000_0300);
+        assert_eq!(val.input_format(), InputFormat::Yuv422Packed);
+        val = val.set_input_format(InputFormat::Yuv400);
+        assert_eq!(val.input_format(), InputFormat::Yuv400);
+    }
+
+    #[test]
+    fn struct_software_functions() {
+        let mut val = Software(0x0);
+
+        val = val.set_frames(0x1f);
+        assert_eq!(val.0, 0x0000_001f);
+        assert_eq!(val.frames(), 0x1f);
+
+        val = Software(0x0).kick();
+        assert_eq!(val.0, 0x0000_0040);
+
+        val = Software(0x0).enable_software_mode();
+        assert_eq!(val.0, 0x0000_0100);
+        assert!(val.is_software_mode_enabled());
+        val = val.disable_software_mode();
+        assert!(!val.is_software_mode_enabled());
+    }
+
+    #[test]
+    fn struct_status_functions() {
+        let mut val = Status(0x0);
+
+        val = val.enable_interrupt(Interrupt::FrameComplete);
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_interrupt_enabled(Interrupt::FrameComplete));
+        val = val.enable_interrupt(Interrupt::InputOverwritten);
+        assert_eq!(val.0, 0x0000_0005);
+        val = val.disable_interrupt(Interrupt::FrameComplete);
+        assert!(!val.is_interrupt_enabled(Interrupt::FrameComplete));
+
+        // Writing back interrupt enables must not write frame count or state bits.
+        val = Status(0x0003_0300).enable_interrupt(Interrupt::BufferOverflow);
+        assert_eq!(val.0, 0x0000_0002);
+
+        let val = Status(0x0002_0400);
+        assert!(val.has_interrupt(Interrupt::BufferOverflow));
+        assert!(!val.has_interrupt(Interrupt::FrameComplete));
+        assert_eq!(val.frame_count(), 4);
+    }
+
+    #[test]
+    fn struct_frame_pop_functions() {
+        let val = FramePop::default().pop_frame();
+        assert_eq!(val.0, 0x0000_0001);
+        let val = FramePop::default()
+            .clear_interrupt(Interrupt::FrameComplete)
+            .clear_interrupt(Interrupt::InputOverwritten);
+        assert_eq!(val.0, 0x0000_0500);
+    }
+
+    #[test]
+    fn struct_frame_size_functions() {
+        let val = FrameSize(0x0).set_width(1920).set_height(1080);
+        assert_eq!(val.0, 0x0438_0780);
+        assert_eq!(val.width(), 1920);
+        assert_eq!(val.height(), 1080);
+    }
+
+    #[test]
+    fn function_quantization_table() {
+        let table = quantization_table(&LUMA_QUANTIZATION, 50);
+        assert!(
+            table
+                .iter()
+                .zip(LUMA_QUANTIZATION)
+                .all(|(a, b)| *a == b as u16)
+        );
+        let table = quantization_table(&LUMA_QUANTIZATION, 100);
+        assert!(table.iter().all(|a| *a == 1));
+        let table = quantization_table(&CHROMA_QUANTIZATION, 1);
+        assert!(table.iter().all(|a| *a == 255));
+        let table = quantization_table(&LUMA_QUANTIZATION, 75);
+        assert_eq!(table[0], 8);
+        assert_eq!(table[63], 50);
+    }
+
+    #[test]
+    fn struct_config_input_bytes() {
+        let config = Config::default().set_resolution(640, 480);
+        assert_eq!(config.y_bytes(), 640 * 480 * 2);
+        assert_eq!(config.uv_bytes(), 0);
+        let config = config.set_input_format(InputFormat::Yuv420);
+        assert_eq!(config.y_bytes(), 640 * 480);
+        assert_eq!(config.uv_bytes(), 640 * 480 / 2);
+    }
+}
@@ -0,0 +1,383 @@
+//! In-memory register blocks for host unit tests.
+//!
+//! [`MockRegisters`] holds an image of a register block in memory and dereferences to the
+//! register block, so drivers taking `Deref<Target = RegisterBlock>` run on it unchanged;
+//! pass `&*registers` where a peripheral would be passed. Tests then check what the driver
+//! programmed without hardware.
+//!
+//! Drivers access the image as plain memory: a read returns what was last written, or what
+//! the test staged with [`set`](MockRegisters::set). Hardware side effects, such as
+//! write-one-to-clear flags or self-clearing start bits, are modeled by a write hook.
+//! [`settle`](MockRegisters::settle) compares the image with its state at the previous
+//! settle point and passes every changed register through the hook, which returns the
+//! value the hardware would hold afterwards.
+//!
+//! Status bits a driver busy-waits on are modeled by a read hook. While a driver call runs
+//! inside [`run`](MockRegisters::run), a background thread keeps passing every register
+//! through the hook, which returns the value the driver reads next. Like hardware, it
+//! changes registers concurrently with the driver, so it should only change fields the
+//! driver does not write.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use bouffalo_hal::{mock::MockRegisters, uart::RegisterBlock};
+//! use core::mem::offset_of;
+//!
+//! let uart = unsafe { MockRegisters::<RegisterBlock>::new() };
+//! let serial = BlockingSerial::freerun(&*uart, config, pads, &clocks).unwrap();
+//! assert_eq!(uart.get(offset_of!(RegisterBlock, bit_period)), 0x0027_0027);
+//!
+//! // Transmit queue drains by one byte on every pass of the read hook.
+//! uart.set_read_hook(|offset, val| match offset {
+//!     o if o == offset_of!(RegisterBlock, fifo_config_1) && val & 0x3f < 32 => val + 1,
+//!     _ => val,
+//! });
+//! uart.run(|| serial.flush()).unwrap();
+//! ```
+
+extern crate std;
+
+use core::{
+    cell::{Cell, UnsafeCell},
+    mem::MaybeUninit,
+    ops::Deref,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Write hook of a mocked register block.
+///
+/// Called with the byte offset, previous value and written value of a register; returns
+/// the value the register holds afterwards.
+pub type WriteHook = fn(offset: usize, old: u32, new: u32) -> u32;
+
+/// Read hook of a mocked register block.
+///
+/// Called with the byte offset and current value of a register; returns the value the
+/// driver reads next.
+pub type ReadHook = fn(offset: usize, val: u32) -> u32;
+
+/// Register block `RB` backed by memory.
+pub struct MockRegisters<RB> {
+    image: UnsafeCell<MaybeUninit<RB>>,
+    // Image at the previous settle point.
+    shadow: UnsafeCell<MaybeUninit<RB>>,
+    write_hook: Cell<Option<WriteHook>>,
+    read_hook: Cell<Option<ReadHook>>,
+}
+
+impl<RB> MockRegisters<RB> {
+    /// Create a register block with every register at zero.
+    ///
+    /// # Safety
+    ///
+    /// All-zero bytes must be a valid `RB`, which holds for register blocks made of
+    /// volatile integer registers.
+    #[inline]
+    pub const unsafe fn new() -> Self {
+        const {
+            assert!(
+                size_of::<RB>().is_multiple_of(4) && align_of::<RB>() >= 4,
+                "register block must consist of 32-bit words"
+            )
+        };
+        Self {
+            image: UnsafeCell::new(MaybeUninit::zeroed()),
+            shadow: UnsafeCell::new(MaybeUninit::zeroed()),
+            write_hook: Cell::new(None),
+            read_hook: Cell::new(None),
+        }
+    }
+    /// Set hook applied to registers changed by the driver on [`settle`](Self::settle).
+    #[inline]
+    pub fn set_write_hook(&self, hook: WriteHook) {
+        self.write_hook.set(Some(hook));
+    }
+    /// Set hook applied to every register while a driver call runs in [`run`](Self::run).
+    #[inline]
+    pub fn set_read_hook(&self, hook: ReadHook) {
+        self.read_hook.set(Some(hook));
+    }
+    /// Run `f` while a background thread applies the read hook to registers.
+    ///
+    /// Lets driver calls that poll status bits make progress; returns what `f` returns.
+    /// Without a read hook, `f` runs alone.
+    pub fn run<T>(&self, f: impl FnOnce() -> T) -> T {
+        let Some(hook) = self.read_hook.get() else {
+            return f();
+        };
+        let model = Model {
+            image: self.image.get() as *mut u32,
+            shadow: self.shadow.get() as *mut u32,
+            words: size_of::<RB>() / 4,
+        };
+        let done = AtomicBool::new(false);
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                while !done.load(Ordering::Acquire) {
+                    model.step(hook);
+                    std::thread::yield_now();
+                }
+            });
+            // Stop the model thread even if `f` panics, or the scope never joins.
+            let _stop = Stop(&done);
+            f()
+        })
+    }
+    /// Get register value at byte `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is not a word offset inside the register block.
+    #[inline]
+    pub fn get(&self, offset: usize) -> u32 {
+        unsafe { self.word(self.image.get(), offset).read_volatile() }
+    }
+    /// Stage register value at byte `offset`, as if hardware had set it.
+    ///
+    /// Staged values are not passed to the write hook.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is not a word offset inside the register block.
+    #[inline]
+    pub fn set(&self, offset: usize, val: u32) {
+        unsafe {
+            self.word(self.image.get(), offset).write_volatile(val);
+            self.word(self.shadow.get(), offset).write_volatile(val);
+        }
+    }
+    /// Apply write hook to registers changed since the previous settle point.
+    ///
+    /// Returns the number of changed registers.
+    #[inline]
+    pub fn settle(&self) -> usize {
+        let mut changed = 0;
+        for offset in (0..size_of::<RB>()).step_by(4) {
+            let (new, old) = unsafe {
+                (
+                    self.word(self.image.get(), offset).read_volatile(),
+                    self.word(self.shadow.get(), offset).read_volatile(),
+                )
+            };
+            if new == old {
+                continue;
+            }
+            changed += 1;
+            let val = match self.write_hook.get() {
+                Some(hook) => hook(offset, old, new),
+                None => new,
+            };
+            self.set(offset, val);
+        }
+        changed
+    }
+    #[inline]
+    fn word(&self, image: *mut MaybeUninit<RB>, offset: usize) -> *mut u32 {
+        assert!(
+            offset.is_multiple_of(4) && offset < size_of::<RB>(),
+            "offset is not a register of this block"
+        );
+        unsafe { (image as *mut u32).add(offset / 4) }
+    }
+}
+
+// Register image as seen by the read hook thread.
+struct Model {
+    image: *mut u32,
+    shadow: *mut u32,
+    words: usize,
+}
+
+// Image outlives the model thread, which is joined before `run` returns.
+unsafe impl Sync for Model {}
+
+impl Model {
+    #[inline]
+    fn step(&self, hook: ReadHook) {
+        for i in 0..self.words {
+            unsafe {
+                let old = self.image.add(i).read_volatile();
+                let val = hook(i * 4, old);
+                if val != old {
+                    // Not a driver write, keep it away from the write hook.
+                    self.image.add(i).write_volatile(val);
+                    self.shadow.add(i).write_volatile(val);
+                }
+            }
+        }
+    }
+}
+
+struct Stop<'a>(&'a AtomicBool);
+
+impl Drop for Stop<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Release);
+    }
+}
+
+impl<RB> Deref for MockRegisters<RB> {
+    type Target = RB;
+
+    #[inline]
+    fn deref(&self) -> &RB {
+        unsafe { (*self.image.get()).assume_init_ref() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MockRegisters;
+    use crate::dma::{
+        self, BurstSize, DmaMode, EightChannels, LliPool, Mem2MemChannelConfig, Periph4Dma01,
+        TransferWidth,
+    };
+    use crate::glb;
+    use volatile_register::RW;
+    #[cfg(feature = "glb-v2")]
+    use {
+        crate::clocks::Clocks,
+        crate::gpio::{IntoPadv2, Pads},
+        crate::uart::{self, BlockingSerial, Config, UartMuxes},
+        embedded_time::rate::{Extensions, Hertz},
+    };
+
+    #[repr(C)]
+    struct Block {
+        control: RW<u32>,
+        status: RW<u32>,
+    }
+
+    #[test]
+    fn struct_mock_registers() {
+        let block = unsafe { MockRegisters::<Block>::new() };
+        // Status flags are cleared by writing one.
+        block.set_write_hook(|offset, old, new| match offset {
+            4 => old & !new,
+            _ => new,
+        });
+        block.set(4, 0x0000_0005);
+        unsafe {
+            block.control.write(0x8000_0001);
+            block.status.write(0x0000_0001);
+        }
+        assert_eq!(block.settle(), 2);
+        assert_eq!(block.get(0), 0x8000_0001);
+        assert_eq!(block.status.read(), 0x0000_0004);
+        assert_eq!(block.settle(), 0);
+    }
+
+    #[test]
+    fn function_mock_registers_run() {
+        let block = unsafe { MockRegisters::<Block>::new() };
+        // Busy count in status register runs down by one on each pass.
+        block.set_read_hook(|offset, val| match offset {
+            4 if val != 0 => val - 1,
+            _ => val,
+        });
+        block.set(4, 1000);
+        let polls = block.run(|| {
+            let mut polls = 0;
+            while block.status.read() != 0 {
+                polls += 1;
+                core::hint::spin_loop();
+            }
+            unsafe { block.control.write(0x1) };
+            polls
+        });
+        assert!(polls > 0);
+        assert_eq!(block.get(0), 0x1);
+        assert_eq!(block.get(4), 0);
+        // Model thread changes are not driver writes.
+        assert_eq!(block.settle(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn struct_mock_registers_offset() {
+        let block = unsafe { MockRegisters::<Block>::new() };
+        block.get(8);
+    }
+
+    #[test]
+    fn function_dma_descriptor_setup() {
+        let glb = unsafe { MockRegisters::<glb::v2::RegisterBlock>::new() };
+        let dma = unsafe { MockRegisters::<dma::RegisterBlock>::new() };
+        let mut channels = EightChannels::<Periph4Dma01>::__new::<0>(&dma, &glb);
+        channels.ch0.memory_to_memory(Mem2MemChannelConfig {
+            direction: DmaMode::Mem2Mem,
+            src_addr_inc: true,
+            dst_addr_inc: false,
+            src_burst_size: BurstSize::INCR1,
+            dst_burst_size: BurstSize::INCR1,
+            src_transfer_width: TransferWidth::Byte,
+            dst_transfer_width: TransferWidth::Byte,
+        });
+        assert!(dma.global_config.read().is_dma_enabled());
+
+        let mut pool = [LliPool::new(); 3];
+        channels
+            .ch0
+            .lli_config(&mut pool, 3, 0x6200_0000, 0x2000_a088, 4064, 100);
+        assert_eq!(pool[0].src_addr, 0x6200_0000);
+        assert_eq!(pool[1].src_addr, 0x6200_0fe0);
+        assert_eq!(pool[2].dst_addr, 0x2000_a088);
+        assert_eq!(pool[0].next_lli, &pool[1] as *const LliPool as u32);
+        assert_eq!(pool[2].next_lli, 0);
+        assert_eq!(pool[0].control.transfer_size(), 4064);
+        assert!(!pool[0].control.is_cplt_int_enabled());
+        assert_eq!(pool[2].control.transfer_size(), 100);
+        assert!(pool[2].control.is_cplt_int_enabled());
+    }
+
+    #[cfg(feature = "glb-v2")]
+    #[test]
+    fn function_serial_freerun_programming() {
+        let glb = unsafe { MockRegisters::<glb::v2::RegisterBlock>::new() };
+        let uart = unsafe { MockRegisters::<uart::RegisterBlock>::new() };
+        let gpio = Pads::__pads_from_glb(&glb);
+        let muxes = UartMuxes::__uart_muxes_from_glb(&glb);
+        let tx = (gpio.io14.into_uart(), muxes.sig2.into_transmit::<0>());
+        let rx = (gpio.io15.into_uart(), muxes.sig3.into_receive::<0>());
+        let clocks = Clocks {
+            xtal: Hertz(40_000_000),
+        };
+        let config = Config::default().set_baudrate(2_000_000.Bd());
+        let _serial = BlockingSerial::freerun(&*uart, config, (tx, rx), &clocks).unwrap();
+        assert_eq!(uart.bit_period.read().transmit_time_interval(), 40);
+        assert_eq!(uart.bit_period.read().receive_time_interval(), 40);
+        assert!(uart.transmit_config.read().is_freerun_enabled());
+        assert!(uart.transmit_config.read().is_txd_enabled());
+        assert!(uart.receive_config.read().is_rxd_enabled());
+        assert_eq!(uart.receive_timeout.read().timeout(), 15);
+    }
+
+    #[cfg(feature = "glb-v2")]
+    #[test]
+    fn function_serial_flush_polling() {
+        use core::mem::offset_of;
+        use embedded_io::Write;
+
+        let glb = unsafe { MockRegisters::<glb::v2::RegisterBlock>::new() };
+        let uart = unsafe { MockRegisters::<uart::RegisterBlock>::new() };
+        let gpio = Pads::__pads_from_glb(&glb);
+        let muxes = UartMuxes::__uart_muxes_from_glb(&glb);
+        let tx = (gpio.io14.into_uart(), muxes.sig2.into_transmit::<0>());
+        let rx = (gpio.io15.into_uart(), muxes.sig3.into_receive::<0>());
+        let clocks = Clocks {
+            xtal: Hertz(40_000_000),
+        };
+        let config = Config::default().set_baudrate(2_000_000.Bd());
+        let mut serial = BlockingSerial::freerun(&*uart, config, (tx, rx), &clocks).unwrap();
+        // Transmit queue is full, then drains by one byte on each pass.
+        uart.set(offset_of!(uart::RegisterBlock, fifo_config_1), 0);
+        uart.set_read_hook(|offset, val| match offset {
+            o if o == offset_of!(uart::RegisterBlock, fifo_config_1) && val & 0x3f < 32 => val + 1,
+            _ => val,
+        });
+        uart.run(|| serial.flush()).unwrap();
+        assert_eq!(uart.fifo_config_1.read().transmit_available_bytes(), 32);
+    }
+}
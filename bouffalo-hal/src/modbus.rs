@@ -0,0 +1,311 @@
+//! Modbus RTU framing over serial.
+//!
+//! RTU frames are an address byte, a protocol data unit (PDU) starting with the function
+//! code, and a CRC-16 in little-endian order. Frames are delimited by at least 3.5
+//! characters of silence, which [`Rtu`] detects with the UART receive timeout, so no
+//! timer is needed to split incoming frames.
+//!
+//! This layer only moves PDUs; building and parsing function specific payloads is left
+//! to the application.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let serial = p.uart0.freerun(config, pads, &c).unwrap();
+//! let mut modbus = Rtu::new(serial, 19200);
+//! let mut response = [0u8; MAX_FRAME];
+//! // Read two holding registers from address 0 of device 1.
+//! let pdu = modbus.request(1, &[0x03, 0x00, 0x00, 0x00, 0x02], &mut response, &clock, 100.milliseconds())?;
+//! ```
+
+use crate::uart::{BlockingSerial, Error, RegisterBlock};
+use core::ops::Deref;
+use embedded_time::{Clock, duration::Duration, fixed_point::FixedPoint};
+
+/// Largest RTU frame in bytes, including address and CRC.
+pub const MAX_FRAME: usize = 256;
+
+/// Broadcast address, requests to it are executed by all devices without response.
+pub const BROADCAST: u8 = 0;
+
+/// Modbus RTU error.
+#[derive(Debug)]
+pub enum ModbusError {
+    /// Error from serial peripheral.
+    Serial(Error),
+    /// Received frame has a wrong CRC.
+    Crc,
+    /// Frame is too short or too long.
+    FrameLength,
+    /// Response came from another device.
+    Address(u8),
+    /// Device answered with exception code.
+    Exception(u8),
+    /// No response before deadline.
+    Timeout,
+    /// Timer source unavailable or deadline out of its range.
+    Clock,
+}
+
+impl From<Error> for ModbusError {
+    #[inline]
+    fn from(value: Error) -> Self {
+        ModbusError::Serial(value)
+    }
+}
+
+/// Compute Modbus CRC-16 of `data`.
+#[inline]
+pub const fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xffffu16;
+    let mut i = 0;
+    while i < data.len() {
+        crc ^= data[i] as u16;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xa001
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        i += 1;
+    }
+    crc
+}
+
+/// Silent interval between frames in bit periods at `baudrate`.
+///
+/// 3.5 characters of 11 bits each up to 19200 Bd; above that the specification fixes
+/// the interval to 1750 µs. Saturates at the largest receive timeout of 255 bits.
+#[inline]
+pub const fn silent_interval_bits(baudrate: u32) -> u8 {
+    let bits = if baudrate <= 19200 {
+        39
+    } else {
+        (baudrate as u64 * 1750).div_ceil(1_000_000)
+    };
+    if bits > 255 { 255 } else { bits as u8 }
+}
+
+/// Write RTU frame of `address` and `pdu` into `frame`, returning its length.
+#[inline]
+pub fn encode_frame(address: u8, pdu: &[u8], frame: &mut [u8]) -> Result<usize, ModbusError> {
+    let len = pdu.len() + 3;
+    if pdu.is_empty() || len > MAX_FRAME || len > frame.len() {
+        return Err(ModbusError::FrameLength);
+    }
+    frame[0] = address;
+    frame[1..=pdu.len()].copy_from_slice(pdu);
+    let crc = crc16(&frame[..len - 2]);
+    frame[len - 2..len].copy_from_slice(&crc.to_le_bytes());
+    Ok(len)
+}
+
+/// Check CRC of RTU `frame` and split it into address and PDU.
+#[inline]
+pub fn decode_frame(frame: &[u8]) -> Result<(u8, &[u8]), ModbusError> {
+    if frame.len() < 4 || frame.len() > MAX_FRAME {
+        return Err(ModbusError::FrameLength);
+    }
+    let (body, crc) = frame.split_at(frame.len() - 2);
+    if crc16(body) != u16::from_le_bytes([crc[0], crc[1]]) {
+        return Err(ModbusError::Crc);
+    }
+    Ok((body[0], &body[1..]))
+}
+
+/// Modbus RTU endpoint over a blocking serial.
+///
+/// Works as master with [`request`](Self::request) or as slave with
+/// [`receive_request`](Self::receive_request) and [`respond`](Self::respond).
+pub struct Rtu<UART, PADS> {
+    serial: BlockingSerial<UART, PADS>,
+    address: u8,
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> Rtu<UART, PADS> {
+    /// Create RTU endpoint, setting serial receive timeout to frame gap at `baudrate`.
+    ///
+    /// `baudrate` must match configuration of `serial`.
+    #[inline]
+    pub fn new(mut serial: BlockingSerial<UART, PADS>, baudrate: u32) -> Self {
+        serial.set_receive_timeout(silent_interval_bits(baudrate));
+        Self {
+            serial,
+            address: BROADCAST,
+        }
+    }
+    /// Send `pdu` to device `address` and wait for its response PDU.
+    ///
+    /// Broadcast requests return an empty PDU right after sending. Exception responses
+    /// are returned as [`ModbusError::Exception`].
+    #[inline]
+    pub fn request<'b, C: Clock, D: Duration + FixedPoint>(
+        &mut self,
+        address: u8,
+        pdu: &[u8],
+        response: &'b mut [u8; MAX_FRAME],
+        clock: &C,
+        timeout: D,
+    ) -> Result<&'b [u8], ModbusError>
+    where
+        C::T: TryFrom<D::T>,
+    {
+        self.send_frame(address, pdu)?;
+        if address == BROADCAST {
+            return Ok(&[]);
+        }
+        let now = clock.try_now().map_err(|_| ModbusError::Clock)?;
+        let deadline = now.checked_add(timeout).ok_or(ModbusError::Clock)?;
+        let len = self.receive_frame(response, || {
+            clock.try_now().map_or(true, |now| now >= deadline)
+        })?;
+        let (from, pdu) = decode_frame(&response[..len])?;
+        if from != address {
+            return Err(ModbusError::Address(from));
+        }
+        if pdu[0] & 0x80 != 0 {
+            return Err(ModbusError::Exception(pdu.get(1).copied().unwrap_or(0)));
+        }
+        Ok(pdu)
+    }
+    /// Wait for a request to device `address` or broadcast, returning its PDU.
+    ///
+    /// Frames to other devices and frames with wrong CRC are skipped.
+    #[inline]
+    pub fn receive_request<'b>(
+        &mut self,
+        address: u8,
+        request: &'b mut [u8; MAX_FRAME],
+    ) -> Result<&'b [u8], ModbusError> {
+        let len = loop {
+            let len = self.receive_frame(request, || false)?;
+            match decode_frame(&request[..len]) {
+                Ok((to, _)) if to == address || to == BROADCAST => break len,
+                _ => continue,
+            }
+        };
+        self.address = request[0];
+        Ok(&request[1..len - 2])
+    }
+    /// Send response `pdu` to the last received request.
+    ///
+    /// Nothing is sent for broadcast requests.
+    #[inline]
+    pub fn respond(&mut self, address: u8, pdu: &[u8]) -> Result<(), ModbusError> {
+        if self.address == BROADCAST {
+            return Ok(());
+        }
+        self.send_frame(address, pdu)
+    }
+    /// Send exception response with `code` to the last received request of `function`.
+    #[inline]
+    pub fn respond_exception(
+        &mut self,
+        address: u8,
+        function: u8,
+        code: u8,
+    ) -> Result<(), ModbusError> {
+        self.respond(address, &[function | 0x80, code])
+    }
+    /// Release RTU endpoint and return its serial.
+    #[inline]
+    pub fn free(self) -> BlockingSerial<UART, PADS> {
+        self.serial
+    }
+    #[inline]
+    fn send_frame(&mut self, address: u8, pdu: &[u8]) -> Result<(), ModbusError> {
+        let mut frame = [0u8; MAX_FRAME];
+        let len = encode_frame(address, pdu, &mut frame)?;
+        embedded_io::Write::write_all(&mut self.serial, &frame[..len])?;
+        embedded_io::Write::flush(&mut self.serial)?;
+        Ok(())
+    }
+    // Receive bytes until the line goes idle after at least one byte; `expired` is
+    // checked while waiting for the first byte.
+    #[inline]
+    fn receive_frame(
+        &mut self,
+        frame: &mut [u8; MAX_FRAME],
+        mut expired: impl FnMut() -> bool,
+    ) -> Result<usize, ModbusError> {
+        let mut len = 0;
+        self.serial.clear_receive_timeout();
+        loop {
+            match embedded_hal_nb::serial::Read::read(&mut self.serial) {
+                Ok(byte) => {
+                    // Excess bytes are counted but dropped, failing the length check.
+                    if len < MAX_FRAME {
+                        frame[len] = byte;
+                    }
+                    len += 1;
+                    self.serial.clear_receive_timeout();
+                    continue;
+                }
+                Err(nb::Error::Other(e)) => return Err(e.into()),
+                Err(nb::Error::WouldBlock) => {}
+            }
+            if len > 0 && self.serial.is_receive_timeout() {
+                break;
+            }
+            if len == 0 && expired() {
+                return Err(ModbusError::Timeout);
+            }
+            core::hint::spin_loop();
+        }
+        if len > MAX_FRAME {
+            return Err(ModbusError::FrameLength);
+        }
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ModbusError, crc16, decode_frame, encode_frame, silent_interval_bits};
+
+    #[test]
+    fn function_crc16() {
+        assert_eq!(crc16(&[0x01, 0x03, 0x00, 0x00, 0x00, 0x0a]), 0xcdc5);
+        assert_eq!(crc16(&[]), 0xffff);
+    }
+
+    #[test]
+    fn function_silent_interval_bits() {
+        assert_eq!(silent_interval_bits(9600), 39);
+        assert_eq!(silent_interval_bits(19200), 39);
+        assert_eq!(silent_interval_bits(38400), 68);
+        assert_eq!(silent_interval_bits(115200), 202);
+        assert_eq!(silent_interval_bits(2_000_000), 255);
+    }
+
+    #[test]
+    fn function_encode_decode_frame() {
+        let mut frame = [0u8; 16];
+        let len = encode_frame(0x01, &[0x03, 0x00, 0x00, 0x00, 0x0a], &mut frame).unwrap();
+        assert_eq!(
+            frame[..len],
+            [0x01, 0x03, 0x00, 0x00, 0x00, 0x0a, 0xc5, 0xcd]
+        );
+        let (address, pdu) = decode_frame(&frame[..len]).unwrap();
+        assert_eq!(address, 0x01);
+        assert_eq!(pdu, [0x03, 0x00, 0x00, 0x00, 0x0a]);
+
+        frame[3] ^= 0x01;
+        assert!(matches!(decode_frame(&frame[..len]), Err(ModbusError::Crc)));
+        assert!(matches!(
+            decode_frame(&frame[..3]),
+            Err(ModbusError::FrameLength)
+        ));
+        assert!(matches!(
+            encode_frame(0x01, &[], &mut frame),
+            Err(ModbusError::FrameLength)
+        ));
+        assert!(matches!(
+            encode_frame(0x01, &[0; 14], &mut frame),
+            Err(ModbusError::FrameLength)
+        ));
+    }
+}
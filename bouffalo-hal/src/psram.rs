@@ -1,10 +1,15 @@
 //! Pseudo Static Random Access Memory.
 
-use core::ptr;
+use core::{ops::Range, ptr};
 
-use crate::glb;
+use crate::{clocks::Clocks, glb};
 use volatile_register::RW;
 
+/// Start address of PSRAM in system memory map.
+pub const BASE_ADDRESS: usize = 0x5000_0000;
+/// Largest PSRAM size supported by the controller.
+pub const MAX_SIZE: usize = 64 * 1024 * 1024;
+
 /// Pseudo Static Random Access Memory registers.
 #[repr(C)]
 pub struct RegisterBlock {
@@ -55,6 +60,76 @@ pub fn init_psram(psram: &RegisterBlock, glb: &glb::v2::RegisterBlock) {
     }
 }
 
+/// PSRAM initialization error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// PSRAM clock is not configured.
+    ClockUnavailable,
+    /// Test pattern readback failed at given address.
+    TrainingFailed(usize),
+}
+
+/// Initializes the PSRAM, verifies its data path and returns the usable address range.
+///
+/// The returned range can be handed to a heap allocator.
+#[inline]
+pub fn init(
+    psram: &RegisterBlock,
+    glb: &glb::v2::RegisterBlock,
+    clocks: &Clocks,
+) -> Result<Range<usize>, Error> {
+    if clocks.psram_clock().is_none() {
+        return Err(Error::ClockUnavailable);
+    }
+    init_psram(psram, glb);
+    train(BASE_ADDRESS)?;
+    let size = detect_size(BASE_ADDRESS);
+    Ok(BASE_ADDRESS..BASE_ADDRESS + size)
+}
+
+/// Write and read back test patterns on data lines and address lines.
+#[inline]
+fn train(base: usize) -> Result<(), Error> {
+    const PATTERNS: [u32; 4] = [0x0000_0000, 0xFFFF_FFFF, 0x5555_5555, 0xAAAA_AAAA];
+    for (i, pattern) in PATTERNS
+        .into_iter()
+        .chain((0..32).map(|bit| 1 << bit))
+        .enumerate()
+    {
+        let address = base + i * 4;
+        unsafe { ptr::write_volatile(address as *mut u32, pattern) };
+        if unsafe { ptr::read_volatile(address as *const u32) } != pattern {
+            return Err(Error::TrainingFailed(address));
+        }
+    }
+    let mut offset = 4;
+    while offset < MAX_SIZE {
+        let address = base + offset;
+        unsafe { ptr::write_volatile(address as *mut u32, address as u32) };
+        if unsafe { ptr::read_volatile(address as *const u32) } != address as u32 {
+            return Err(Error::TrainingFailed(address));
+        }
+        offset <<= 1;
+    }
+    Ok(())
+}
+
+/// Detect PSRAM size by checking where addresses wrap around.
+#[inline]
+fn detect_size(base: usize) -> usize {
+    const MARKER: u32 = 0x5053_524D;
+    unsafe { ptr::write_volatile(base as *mut u32, MARKER) };
+    let mut size = 4 * 1024 * 1024;
+    while size < MAX_SIZE {
+        unsafe { ptr::write_volatile((base + size) as *mut u32, !MARKER) };
+        if unsafe { ptr::read_volatile(base as *const u32) } != MARKER {
+            break;
+        }
+        size <<= 1;
+    }
+    size
+}
+
 #[cfg(test)]
 mod tests {
     use super::RegisterBlock;
@@ -1,4 +1,7 @@
 //! Pulse Width Modulation peripheral.
+//!
+//! The PWM block only generates signals; to measure period and duty cycle of an
+//! external PWM signal, use input capture of the [timer](crate::timer) peripheral.
 use crate::clocks::Clocks;
 use crate::glb::{
     self,
@@ -123,6 +126,16 @@ impl GroupConfig {
     pub const fn set_adc_trigger_source(self, val: AdcTriggerSource) -> Self {
         Self((self.0 & !Self::ADC_TRIGGER_SOURCE) | ((val as u32) << 20) & Self::ADC_TRIGGER_SOURCE)
     }
+    /// Disable ADC trigger.
+    #[inline]
+    pub const fn disable_adc_trigger(self) -> Self {
+        Self(self.0 | Self::ADC_TRIGGER_SOURCE)
+    }
+    /// Check if ADC trigger is enabled.
+    #[inline]
+    pub const fn is_adc_trigger_enabled(self) -> bool {
+        (self.0 & Self::ADC_TRIGGER_SOURCE) >> 20 <= 8
+    }
     /// Get ADC trigger source.
     #[inline]
     pub const fn adc_trigger_source(self) -> AdcTriggerSource {
@@ -731,6 +744,27 @@ impl<PWM: Deref<Target = RegisterBlock>, S, const I: usize> Channels<PWM, S, I>
                 .modify(|val| val.set_period(duty))
         }
     }
+    /// Start a conversion on the general purpose ADC on every `source` event of this group.
+    ///
+    /// Conversions are triggered by hardware without CPU involvement; pair the ADC with DMA
+    /// to collect results.
+    #[inline]
+    pub fn enable_adc_trigger(&mut self, source: AdcTriggerSource) {
+        unsafe {
+            self.pwm.group[I]
+                .group_config
+                .modify(|val| val.set_adc_trigger_source(source))
+        }
+    }
+    /// Stop triggering ADC conversions from this group.
+    #[inline]
+    pub fn disable_adc_trigger(&mut self) {
+        unsafe {
+            self.pwm.group[I]
+                .group_config
+                .modify(|val| val.disable_adc_trigger())
+        }
+    }
     /// Start current PWM group.
     #[inline]
     pub fn start(&mut self) {
@@ -1300,6 +1334,10 @@ mod tests {
         val = val.set_adc_trigger_source(AdcTriggerSource::PeriodEnd);
         assert_eq!(val.adc_trigger_source(), AdcTriggerSource::PeriodEnd);
         assert_eq!(AdcTriggerSource::PeriodEnd as u32, 0x00000008);
+        assert!(val.is_adc_trigger_enabled());
+        val = val.disable_adc_trigger();
+        assert_eq!(val.0, 0x00f00000);
+        assert!(!val.is_adc_trigger_enabled());
 
         val = GroupConfig(0x0);
         val = val.enable_software_break();
@@ -0,0 +1,543 @@
+//! Quadrature decoder peripheral.
+//!
+//! Each decoder samples phase A and B signals of a rotary encoder, counts steps in
+//! hardware and reports the accumulated count once per report period, raising the
+//! `qdec` interrupt. Phase pads are selected in global configuration registers.
+//!
+//! Chips without decoder hardware use [`SoftwareDecoder`], fed from GPIO edge
+//! interrupts of both phase pads.
+
+use core::ops::Deref;
+use embedded_hal::digital::InputPin;
+use volatile_register::{RO, RW, WO};
+
+/// Quadrature decoder registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Decoder control register.
+    pub control: RW<Control>,
+    /// Sample and report configuration register.
+    pub config: RW<DecodeConfig>,
+    /// Accumulated and sampled value register.
+    pub value: RO<Value>,
+    _reserved0: [u8; 0x4],
+    /// Interrupt enable register.
+    pub interrupt_enable: RW<u32>,
+    /// Interrupt state register.
+    pub interrupt_state: RO<u32>,
+    /// Interrupt clear register.
+    pub interrupt_clear: WO<u32>,
+}
+
+/// Decoder control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Control(u32);
+
+impl Control {
+    const ENABLE: u32 = 1 << 0;
+    const SWAP: u32 = 1 << 1;
+
+    /// Enable decoder.
+    #[inline]
+    pub const fn enable_decoder(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable decoder.
+    #[inline]
+    pub const fn disable_decoder(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if decoder is enabled.
+    #[inline]
+    pub const fn is_decoder_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Swap phase A and B, reversing count direction.
+    #[inline]
+    pub const fn enable_swap(self) -> Self {
+        Self(self.0 | Self::SWAP)
+    }
+    /// Keep phase A and B.
+    #[inline]
+    pub const fn disable_swap(self) -> Self {
+        Self(self.0 & !Self::SWAP)
+    }
+    /// Check if phase A and B are swapped.
+    #[inline]
+    pub const fn is_swap_enabled(self) -> bool {
+        self.0 & Self::SWAP != 0
+    }
+}
+
+/// Sample and report configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct DecodeConfig(u32);
+
+impl DecodeConfig {
+    const SAMPLE_PERIOD: u32 = 0xf;
+    const DEGLITCH: u32 = 1 << 4;
+    const DEGLITCH_COUNT: u32 = 0xf << 8;
+    const REPORT_PERIOD: u32 = 0xffff << 16;
+
+    /// Set phase sample period, in `2^val` microseconds.
+    #[inline]
+    pub const fn set_sample_period(self, val: u8) -> Self {
+        Self((self.0 & !Self::SAMPLE_PERIOD) | ((val as u32) & Self::SAMPLE_PERIOD))
+    }
+    /// Get phase sample period, in `2^val` microseconds.
+    #[inline]
+    pub const fn sample_period(self) -> u8 {
+        (self.0 & Self::SAMPLE_PERIOD) as u8
+    }
+    /// Enable phase input deglitch.
+    #[inline]
+    pub const fn enable_deglitch(self) -> Self {
+        Self(self.0 | Self::DEGLITCH)
+    }
+    /// Disable phase input deglitch.
+    #[inline]
+    pub const fn disable_deglitch(self) -> Self {
+        Self(self.0 & !Self::DEGLITCH)
+    }
+    /// Check if phase input deglitch is enabled.
+    #[inline]
+    pub const fn is_deglitch_enabled(self) -> bool {
+        self.0 & Self::DEGLITCH != 0
+    }
+    /// Set deglitch length in clock cycles.
+    #[inline]
+    pub const fn set_deglitch_count(self, val: u8) -> Self {
+        Self((self.0 & !Self::DEGLITCH_COUNT) | (((val as u32) << 8) & Self::DEGLITCH_COUNT))
+    }
+    /// Get deglitch length in clock cycles.
+    #[inline]
+    pub const fn deglitch_count(self) -> u8 {
+        ((self.0 & Self::DEGLITCH_COUNT) >> 8) as u8
+    }
+    /// Set report period in samples.
+    #[inline]
+    pub const fn set_report_period(self, val: u16) -> Self {
+        Self((self.0 & !Self::REPORT_PERIOD) | ((val as u32) << 16))
+    }
+    /// Get report period in samples.
+    #[inline]
+    pub const fn report_period(self) -> u16 {
+        ((self.0 & Self::REPORT_PERIOD) >> 16) as u16
+    }
+}
+
+/// Accumulated and sampled value register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Value(u32);
+
+impl Value {
+    const ACCUMULATED: u32 = 0xffff;
+    const SAMPLE: u32 = 0x3 << 16;
+
+    /// Get steps accumulated in last report period, positive when phase A leads.
+    #[inline]
+    pub const fn accumulated(self) -> i16 {
+        (self.0 & Self::ACCUMULATED) as u16 as i16
+    }
+    /// Get direction of last sample.
+    #[inline]
+    pub const fn sample(self) -> Direction {
+        match (self.0 & Self::SAMPLE) >> 16 {
+            0 => Direction::None,
+            1 => Direction::Forward,
+            2 => Direction::Backward,
+            _ => Direction::Error,
+        }
+    }
+}
+
+/// Step direction of a sample.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// No step.
+    None,
+    /// One step with phase A leading.
+    Forward,
+    /// One step with phase B leading.
+    Backward,
+    /// Both phases changed, a step was missed.
+    Error,
+}
+
+/// Quadrature decoder interrupt event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Interrupt {
+    /// Report period elapsed, accumulated value is ready.
+    Report = 0,
+    /// A sample is taken.
+    Sample = 1,
+    /// Both phases changed within a sample period.
+    Error = 2,
+    /// Accumulated value overflowed.
+    Overflow = 3,
+}
+
+/// Quadrature decoder configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Config {
+    /// Phase sample period, in `2^sample_period` microseconds.
+    pub sample_period: u8,
+    /// Report period in samples.
+    pub report_period: u16,
+    /// Deglitch length in clock cycles, `None` disables deglitch.
+    pub deglitch: Option<u8>,
+    /// Swap phase A and B.
+    pub swap: bool,
+}
+
+impl Default for Config {
+    #[inline]
+    fn default() -> Self {
+        // Sample every 32 us and report every 1000 samples, i.e. 32 ms.
+        Self {
+            sample_period: 5,
+            report_period: 1000,
+            deglitch: Some(4),
+            swap: false,
+        }
+    }
+}
+
+impl Config {
+    /// Set sample and report periods.
+    #[inline]
+    pub const fn set_periods(mut self, sample_period: u8, report_period: u16) -> Self {
+        self.sample_period = sample_period;
+        self.report_period = report_period;
+        self
+    }
+    /// Swap phase A and B, reversing count direction.
+    #[inline]
+    pub const fn set_swap(mut self, swap: bool) -> Self {
+        self.swap = swap;
+        self
+    }
+    /// Length of one report period in microseconds.
+    #[inline]
+    pub const fn report_micros(&self) -> u32 {
+        (self.report_period as u32) << self.sample_period
+    }
+}
+
+/// Managed quadrature decoder.
+pub struct Qdec<QDEC> {
+    qdec: QDEC,
+    position: i32,
+    velocity: i32,
+    report_micros: u32,
+}
+
+impl<QDEC: Deref<Target = RegisterBlock>> Qdec<QDEC> {
+    /// Create and start decoder with configuration.
+    #[inline]
+    pub fn new(qdec: QDEC, config: Config) -> Self {
+        let mut val = DecodeConfig(0)
+            .set_sample_period(config.sample_period)
+            .set_report_period(config.report_period);
+        if let Some(count) = config.deglitch {
+            val = val.enable_deglitch().set_deglitch_count(count);
+        }
+        let control = if config.swap {
+            Control(0).enable_swap()
+        } else {
+            Control(0)
+        };
+        unsafe {
+            qdec.control.write(control);
+            qdec.config.write(val);
+            qdec.interrupt_clear.write(0xf);
+            qdec.control.write(control.enable_decoder());
+        }
+        Self {
+            qdec,
+            position: 0,
+            velocity: 0,
+            report_micros: config.report_micros(),
+        }
+    }
+    /// Accumulate a finished report period into position and velocity.
+    ///
+    /// Call from the `qdec` interrupt handler with [`Interrupt::Report`] enabled, or
+    /// poll it; returns `true` if a report was consumed.
+    #[inline]
+    pub fn update(&mut self) -> bool {
+        if self.qdec.interrupt_state.read() & (1 << Interrupt::Report as u32) == 0 {
+            return false;
+        }
+        let steps = self.qdec.value.read().accumulated() as i32;
+        unsafe {
+            self.qdec
+                .interrupt_clear
+                .write(1 << Interrupt::Report as u32)
+        };
+        self.position = self.position.wrapping_add(steps);
+        self.velocity = (steps as i64 * 1_000_000 / self.report_micros.max(1) as i64) as i32;
+        true
+    }
+    /// Get position in steps since creation or last [`set_position`](Self::set_position).
+    #[inline]
+    pub const fn position(&self) -> i32 {
+        self.position
+    }
+    /// Set current position.
+    #[inline]
+    pub fn set_position(&mut self, position: i32) {
+        self.position = position;
+    }
+    /// Get velocity in steps per second over the last report period.
+    #[inline]
+    pub const fn velocity(&self) -> i32 {
+        self.velocity
+    }
+    /// Enable interrupt.
+    #[inline]
+    pub fn enable_interrupt(&mut self, val: Interrupt) {
+        unsafe { self.qdec.interrupt_enable.modify(|v| v | (1 << val as u32)) };
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub fn disable_interrupt(&mut self, val: Interrupt) {
+        unsafe {
+            self.qdec
+                .interrupt_enable
+                .modify(|v| v & !(1 << val as u32))
+        };
+    }
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub fn has_interrupt(&self, val: Interrupt) -> bool {
+        self.qdec.interrupt_state.read() & (1 << val as u32) != 0
+    }
+    /// Clear interrupt flag.
+    #[inline]
+    pub fn clear_interrupt(&mut self, val: Interrupt) {
+        unsafe { self.qdec.interrupt_clear.write(1 << val as u32) };
+    }
+    /// Stop decoder and release its peripheral.
+    #[inline]
+    pub fn free(self) -> QDEC {
+        unsafe {
+            self.qdec.control.modify(|v| v.disable_decoder());
+            self.qdec.interrupt_enable.write(0);
+        }
+        self.qdec
+    }
+}
+
+/// Quadrature decoder in software over two input pads.
+///
+/// Call [`on_edge`](Self::on_edge) from GPIO interrupt handlers of both pads with
+/// interrupts on both edges; each phase change is counted as one step.
+pub struct SoftwareDecoder<A, B> {
+    a: A,
+    b: B,
+    state: u8,
+    position: i32,
+    last_position: i32,
+    errors: u32,
+}
+
+impl<A: InputPin, B: InputPin> SoftwareDecoder<A, B> {
+    /// Create decoder from phase A and B pads at current phase.
+    #[inline]
+    pub fn new(mut a: A, mut b: B) -> Self {
+        let state = phase(&mut a, &mut b);
+        Self {
+            a,
+            b,
+            state,
+            position: 0,
+            last_position: 0,
+            errors: 0,
+        }
+    }
+    /// Sample both phases and count a step.
+    #[inline]
+    pub fn on_edge(&mut self) {
+        let state = phase(&mut self.a, &mut self.b);
+        self.step(state);
+    }
+    #[inline]
+    fn step(&mut self, state: u8) {
+        match STEP[(self.state << 2 | state) as usize] {
+            Some(delta) => self.position = self.position.wrapping_add(delta as i32),
+            None => self.errors = self.errors.wrapping_add(1),
+        }
+        self.state = state;
+    }
+    /// Get position in steps.
+    #[inline]
+    pub const fn position(&self) -> i32 {
+        self.position
+    }
+    /// Set current position.
+    #[inline]
+    pub fn set_position(&mut self, position: i32) {
+        self.position = position;
+        self.last_position = position;
+    }
+    /// Get velocity in steps per second, given microseconds since last call.
+    #[inline]
+    pub fn velocity(&mut self, elapsed_micros: u32) -> i32 {
+        let steps = self.position.wrapping_sub(self.last_position);
+        self.last_position = self.position;
+        (steps as i64 * 1_000_000 / elapsed_micros.max(1) as i64) as i32
+    }
+    /// Get number of missed steps, where both phases changed between two edges.
+    #[inline]
+    pub const fn errors(&self) -> u32 {
+        self.errors
+    }
+    /// Release phase pads.
+    #[inline]
+    pub fn free(self) -> (A, B) {
+        (self.a, self.b)
+    }
+}
+
+#[inline]
+fn phase<A: InputPin, B: InputPin>(a: &mut A, b: &mut B) -> u8 {
+    let a = a.is_high().unwrap_or(false) as u8;
+    let b = b.is_high().unwrap_or(false) as u8;
+    (a << 1) | b
+}
+
+// Step of each previous and current phase pair, `None` if both phases changed.
+// Phases go 00, 01, 11, 10 when turning forward.
+const STEP: [Option<i8>; 16] = [
+    Some(0),
+    Some(1),
+    Some(-1),
+    None,
+    Some(-1),
+    Some(0),
+    None,
+    Some(1),
+    Some(1),
+    None,
+    Some(0),
+    Some(-1),
+    None,
+    Some(-1),
+    Some(1),
+    Some(0),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{Control, DecodeConfig, Direction, RegisterBlock, SoftwareDecoder, Value};
+    use core::{convert::Infallible, mem::offset_of};
+    use embedded_hal::digital::{ErrorType, InputPin};
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, control), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, config), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, value), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_enable), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_state), 0x14);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_clear), 0x18);
+    }
+
+    #[test]
+    fn struct_control_functions() {
+        let mut val = Control(0x0);
+        val = val.enable_decoder();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_decoder_enabled());
+        val = val.disable_decoder();
+        assert!(!val.is_decoder_enabled());
+        val = val.enable_swap();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_swap_enabled());
+        val = val.disable_swap();
+        assert!(!val.is_swap_enabled());
+    }
+
+    #[test]
+    fn struct_decode_config_functions() {
+        let mut val = DecodeConfig(0x0);
+        val = val.set_sample_period(0xf);
+        assert_eq!(val.0, 0x0000_000f);
+        assert_eq!(val.sample_period(), 0xf);
+        val = val.set_sample_period(0);
+
+        val = val.enable_deglitch();
+        assert_eq!(val.0, 0x0000_0010);
+        assert!(val.is_deglitch_enabled());
+        val = val.disable_deglitch();
+        assert!(!val.is_deglitch_enabled());
+
+        val = val.set_deglitch_count(0xf);
+        assert_eq!(val.0, 0x0000_0f00);
+        assert_eq!(val.deglitch_count(), 0xf);
+        val = val.set_deglitch_count(0);
+
+        val = val.set_report_period(0xffff);
+        assert_eq!(val.0, 0xffff_0000);
+        assert_eq!(val.report_period(), 0xffff);
+    }
+
+    #[test]
+    fn struct_value_functions() {
+        let val = Value(0x0001_fffe);
+        assert_eq!(val.accumulated(), -2);
+        assert_eq!(val.sample(), Direction::Forward);
+        let val = Value(0x0003_0005);
+        assert_eq!(val.accumulated(), 5);
+        assert_eq!(val.sample(), Direction::Error);
+    }
+
+    struct Phase<'a>(&'a core::cell::Cell<bool>);
+
+    impl ErrorType for Phase<'_> {
+        type Error = Infallible;
+    }
+
+    impl InputPin for Phase<'_> {
+        fn is_high(&mut self) -> Result<bool, Infallible> {
+            Ok(self.0.get())
+        }
+        fn is_low(&mut self) -> Result<bool, Infallible> {
+            Ok(!self.0.get())
+        }
+    }
+
+    #[test]
+    fn struct_software_decoder_functions() {
+        let a = core::cell::Cell::new(false);
+        let b = core::cell::Cell::new(false);
+        let mut decoder = SoftwareDecoder::new(Phase(&a), Phase(&b));
+        // Forward: 00, 01, 11, 10, 00.
+        for (pa, pb) in [(false, true), (true, true), (true, false), (false, false)] {
+            a.set(pa);
+            b.set(pb);
+            decoder.on_edge();
+        }
+        assert_eq!(decoder.position(), 4);
+        assert_eq!(decoder.velocity(1000), 4000);
+        // Backward: 00, 10, 11.
+        for (pa, pb) in [(true, false), (true, true)] {
+            a.set(pa);
+            b.set(pb);
+            decoder.on_edge();
+        }
+        assert_eq!(decoder.position(), 2);
+        assert_eq!(decoder.velocity(2000), -1000);
+        // Missed step: 11 to 00.
+        a.set(false);
+        b.set(false);
+        decoder.on_edge();
+        assert_eq!(decoder.position(), 2);
+        assert_eq!(decoder.errors(), 1);
+    }
+}
@@ -0,0 +1,210 @@
+//! Mask ROM driver function bindings.
+//!
+//! Boot ROM exports a table of driver function addresses. ROM drivers execute from ROM,
+//! so serial flash routines among them can erase and program flash while the rest of
+//! the firmware executes in place; callers must still keep interrupt handlers that
+//! run from flash disabled during such calls.
+//!
+//! Table indices of each function differ between chips and ROM revisions. Bind a
+//! function with [`RomFunction::new`] using the index from vendor ROM driver headers
+//! matching [`RomTable::version`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! // Index of `SFlash_Erase` in the ROM driver header of this chip.
+//! const SFLASH_ERASE: RomFunction<SflashEraseFn> =
+//!     unsafe { RomFunction::new(RomTable::BL808, INDEX) };
+//! unsafe { sflash_erase(&SFLASH_ERASE, flash_config, 0x10_0000, 0x10_0fff) }?;
+//! ```
+use core::{ffi::c_void, marker::PhantomData};
+
+/// Number of entries in ROM driver function table.
+pub const TABLE_LEN: usize = 0x800 / 4;
+
+/// ROM driver function table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RomTable {
+    base: usize,
+}
+
+impl RomTable {
+    /// Function table of BL808 ROM.
+    pub const BL808: Self = Self { base: 0x9001_5800 };
+    /// Function table of BL602 ROM.
+    pub const BL602: Self = Self { base: 0x2101_0800 };
+    /// Function table of BL702 ROM.
+    pub const BL702: Self = Self { base: 0x2101_0800 };
+
+    /// Read ROM driver table version.
+    #[inline]
+    pub fn version(&self) -> u32 {
+        unsafe { core::ptr::read_volatile(self.base as *const u32) }
+    }
+}
+
+/// Typed binding of a ROM driver function at a table index.
+pub struct RomFunction<F> {
+    table: RomTable,
+    index: usize,
+    _function: PhantomData<F>,
+}
+
+impl<F: Copy> RomFunction<F> {
+    /// Bind ROM driver function at `index` of function `table`.
+    ///
+    /// # Safety
+    ///
+    /// The table entry at `index` of the ROM on chip must be a function with signature `F`,
+    /// and `F` must be a function pointer type.
+    #[inline]
+    pub const unsafe fn new(table: RomTable, index: usize) -> Self {
+        assert!(
+            index > 0 && index < TABLE_LEN,
+            "ROM function index out of range"
+        );
+        Self {
+            table,
+            index,
+            _function: PhantomData,
+        }
+    }
+    /// Table index of this function.
+    #[inline]
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+    /// Address of this function read from function table.
+    #[inline]
+    pub fn address(&self) -> usize {
+        unsafe {
+            core::ptr::read_volatile((self.table.base as *const u32).add(self.index)) as usize
+        }
+    }
+    /// Function pointer read from function table.
+    #[inline]
+    pub fn get(&self) -> F {
+        let address = self.address();
+        unsafe { core::mem::transmute_copy(&address) }
+    }
+}
+
+/// Error returned by ROM driver functions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RomError {
+    /// Operation failed.
+    Error,
+    /// Operation timed out.
+    Timeout,
+    /// Other non-success return code.
+    Other(u32),
+}
+
+impl RomError {
+    #[inline]
+    const fn check(ret: u32) -> Result<(), RomError> {
+        match ret {
+            0 => Ok(()),
+            1 => Err(RomError::Error),
+            2 => Err(RomError::Timeout),
+            other => Err(RomError::Other(other)),
+        }
+    }
+}
+
+/// Serial flash erase, `(flash_config, start_address, end_address)` with inclusive end.
+pub type SflashEraseFn = unsafe extern "C" fn(*const c_void, u32, u32) -> u32;
+/// Serial flash program, `(flash_config, io_mode, address, data, len)`.
+pub type SflashProgramFn = unsafe extern "C" fn(*const c_void, u32, u32, *const u8, u32) -> u32;
+/// Serial flash read, `(flash_config, io_mode, continuous_read, address, data, len)`.
+pub type SflashReadFn = unsafe extern "C" fn(*const c_void, u32, u8, u32, *mut u8, u32) -> u32;
+/// Read eFuse words directly, `(word_offset, data, word_count)`.
+pub type EfuseReadFn = unsafe extern "C" fn(u32, *mut u32, u32);
+
+/// Erase flash from `start` to `end` inclusive with ROM driver.
+///
+/// # Safety
+///
+/// `flash_config` must point to the serial flash configuration of boot header, and
+/// no code may execute from flash during this call.
+#[inline]
+pub unsafe fn sflash_erase(
+    f: &RomFunction<SflashEraseFn>,
+    flash_config: *const c_void,
+    start: u32,
+    end: u32,
+) -> Result<(), RomError> {
+    RomError::check(unsafe { (f.get())(flash_config, start, end) })
+}
+
+/// Program `data` into flash at `address` with ROM driver in single line mode.
+///
+/// # Safety
+///
+/// `flash_config` must point to the serial flash configuration of boot header, and
+/// no code may execute from flash during this call.
+#[inline]
+pub unsafe fn sflash_program(
+    f: &RomFunction<SflashProgramFn>,
+    flash_config: *const c_void,
+    address: u32,
+    data: &[u8],
+) -> Result<(), RomError> {
+    RomError::check(unsafe {
+        (f.get())(flash_config, 0, address, data.as_ptr(), data.len() as u32)
+    })
+}
+
+/// Read flash at `address` into `buf` with ROM driver in single line mode.
+///
+/// # Safety
+///
+/// `flash_config` must point to the serial flash configuration of boot header, and
+/// no code may execute from flash during this call.
+#[inline]
+pub unsafe fn sflash_read(
+    f: &RomFunction<SflashReadFn>,
+    flash_config: *const c_void,
+    address: u32,
+    buf: &mut [u8],
+) -> Result<(), RomError> {
+    RomError::check(unsafe {
+        (f.get())(
+            flash_config,
+            0,
+            0,
+            address,
+            buf.as_mut_ptr(),
+            buf.len() as u32,
+        )
+    })
+}
+
+/// Read eFuse words from `word_offset` into `buf` with ROM driver.
+///
+/// # Safety
+///
+/// eFuse controller must not be in use by other code during this call.
+#[inline]
+pub unsafe fn efuse_read(f: &RomFunction<EfuseReadFn>, word_offset: u32, buf: &mut [u32]) {
+    unsafe { (f.get())(word_offset, buf.as_mut_ptr(), buf.len() as u32) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RomError, RomFunction, RomTable, SflashEraseFn};
+
+    #[test]
+    fn rom_error_check() {
+        assert_eq!(RomError::check(0), Ok(()));
+        assert_eq!(RomError::check(1), Err(RomError::Error));
+        assert_eq!(RomError::check(2), Err(RomError::Timeout));
+        assert_eq!(RomError::check(4), Err(RomError::Other(4)));
+    }
+
+    #[test]
+    fn rom_function_index() {
+        const F: RomFunction<SflashEraseFn> = unsafe { RomFunction::new(RomTable::BL808, 0x20) };
+        assert_eq!(F.index(), 0x20);
+    }
+}
@@ -1,6 +1,7 @@
 //! Secure Digital Input/Output peripheral.
 
 mod config;
+mod detect;
 mod dma_sdh;
 mod nodma_sdh;
 mod ops;
@@ -8,7 +9,9 @@ mod pad;
 mod register;
 pub mod sdcard;
 pub use config::*;
+pub use detect::*;
 pub use dma_sdh::*;
+pub use ops::CardType;
 pub use pad::*;
 pub use register::*;
 
@@ -0,0 +1,110 @@
+use super::register::{CardSignal, RegisterBlock};
+use embedded_hal::digital::InputPin;
+
+/// Card insertion or removal reported by card detect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CardEvent {
+    /// A card was inserted and has been initialized.
+    Inserted,
+    /// The card was removed; it must be initialized again after next insertion.
+    Removed,
+}
+
+/// Card detect switch of an SD slot.
+pub trait CardDetect {
+    /// Check if a card is in the slot.
+    fn is_inserted(&mut self, sdh: &RegisterBlock) -> bool;
+}
+
+/// Card detect on the SDCD signal of the host controller.
+///
+/// A card counts as inserted only after the controller has debounced the signal.
+#[derive(Debug)]
+pub struct SdcdCardDetect;
+
+impl CardDetect for SdcdCardDetect {
+    #[inline]
+    fn is_inserted(&mut self, sdh: &RegisterBlock) -> bool {
+        let state = sdh.present_state.read();
+        state.is_card_detect_stable() && state.is_card_inserted()
+    }
+}
+
+/// Card detect switch on an arbitrary input pin, low while a card is inserted.
+///
+/// Most slots close the switch to ground, so the pin should be a pull-up input. The pin
+/// is not debounced; poll it periodically rather than on every edge.
+#[derive(Debug)]
+pub struct GpioCardDetect<P>(pub P);
+
+impl<P: InputPin> CardDetect for GpioCardDetect<P> {
+    #[inline]
+    fn is_inserted(&mut self, _sdh: &RegisterBlock) -> bool {
+        self.0.is_low().unwrap_or(false)
+    }
+}
+
+/// Enable or disable card insertion and removal interrupts of the host controller.
+///
+/// The controller only sees the SDCD signal, so these interrupts are raised for
+/// [`SdcdCardDetect`] slots.
+#[inline]
+pub(crate) fn set_card_interrupts(sdh: &RegisterBlock, enable: bool) {
+    unsafe {
+        sdh.host_control_1
+            .modify(|val| val.set_card_detect_signal(CardSignal::SDCD));
+        if enable {
+            sdh.normal_interrupt_status_enable
+                .modify(|val| val.enable_card_insertion().enable_card_removal());
+            sdh.normal_interrupt_signal_enable
+                .modify(|val| val.enable_card_insertion().enable_card_removal());
+        } else {
+            sdh.normal_interrupt_signal_enable
+                .modify(|val| val.disable_card_insertion().disable_card_removal());
+            sdh.normal_interrupt_status_enable
+                .modify(|val| val.disable_card_insertion().disable_card_removal());
+        }
+    }
+}
+
+/// Check card detect against whether a card is initialized, clearing card interrupts.
+///
+/// Returns the event to handle, [`CardEvent::Inserted`] meaning the card still needs
+/// initialization.
+#[inline]
+pub(crate) fn card_event(
+    sdh: &RegisterBlock,
+    detect: &mut impl CardDetect,
+    initialized: bool,
+) -> Option<CardEvent> {
+    let status = sdh.normal_interrupt_status.read();
+    if status.is_card_inserted() || status.is_card_removed() {
+        unsafe {
+            sdh.normal_interrupt_status
+                .modify(|val| val.clear_card_inserted().clear_card_removed())
+        };
+    }
+    next_event(detect.is_inserted(sdh), initialized)
+}
+
+#[inline]
+const fn next_event(inserted: bool, initialized: bool) -> Option<CardEvent> {
+    match (inserted, initialized) {
+        (true, false) => Some(CardEvent::Inserted),
+        (false, true) => Some(CardEvent::Removed),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CardEvent, next_event};
+
+    #[test]
+    fn function_next_event() {
+        assert_eq!(next_event(true, false), Some(CardEvent::Inserted));
+        assert_eq!(next_event(false, true), Some(CardEvent::Removed));
+        assert_eq!(next_event(true, true), None);
+        assert_eq!(next_event(false, false), None);
+    }
+}
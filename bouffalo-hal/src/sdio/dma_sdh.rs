@@ -1,10 +1,13 @@
 use super::config::Config;
-use super::ops::{SdhResp, card_init, send_command};
+use super::detect::{CardDetect, CardEvent, card_event, set_card_interrupts};
+use super::ops::{
+    CardType, SdhResp, card_init, prepare_transfer, send_command, wait_transfer_completed,
+};
 use super::pad::Pads;
-use super::register::{
-    AutoCMDMode, BusVoltage, ClkGenMode, CmdType, DataTransferMode, DmaMode, RegisterBlock,
+use super::register::{BusVoltage, ClkGenMode, CmdType, DataTransferMode, DmaMode, RegisterBlock};
+use crate::dma::{
+    LliPool, LliTransfer, UntypedChannel, clean_dcache, flush_dcache, invalidate_dcache,
 };
-use crate::dma::{LliPool, LliTransfer, UntypedChannel};
 use crate::glb;
 use core::ops::Deref;
 use core::sync::atomic::{Ordering, fence};
@@ -17,6 +20,7 @@ pub struct Sdh<SDH, PADS, CH> {
     pads: PADS,
     dma_channel: CH,
     block_count: u32,
+    card_type: CardType,
 }
 
 impl<'a, SDH: Deref<Target = RegisterBlock>, PADS, CH: Deref<Target = UntypedChannel<'a>>>
@@ -90,6 +94,7 @@ impl<'a, SDH: Deref<Target = RegisterBlock>, PADS, CH: Deref<Target = UntypedCha
             pads,
             dma_channel,
             block_count: 0,
+            card_type: CardType::High,
         }
     }
 
@@ -97,159 +102,168 @@ impl<'a, SDH: Deref<Target = RegisterBlock>, PADS, CH: Deref<Target = UntypedCha
     // TODO a more proper abstraction
     #[inline]
     pub fn init<W: Write>(&mut self, w: &mut W, debug: bool) {
-        self.block_count = card_init(&self.sdh, w, debug)
+        (self.block_count, self.card_type) = card_init(&self.sdh, w, debug)
     }
 
-    /// Read block from sdcard using system dma controller.
+    /// Get capacity class of the initialized card.
     #[inline]
-    pub(crate) fn read_block_sys_dma(&self, block: &mut Block, block_idx: u32) {
-        unsafe {
-            // SDH_SD_TRANSFER_MODE.
-            self.sdh.transfer_mode.modify(|val| {
-                val.set_data_transfer_mode(DataTransferMode::MISO) // SDH_TO_HOST_DIR.
-                    .set_auto_cmd_mode(AutoCMDMode::None) // SDH_AUTO_CMD_EN.
-            });
-
-            // Block_size.
-            self.sdh
-                .block_size
-                .modify(|val| val.set_transfer_block(512));
-
-            // Block_count.
-            self.sdh.block_count.modify(|val| val.set_blocks_count(1));
-
-            // SDH_ClearIntStatus (SDH_INT_BUFFER_READ_READY).
-            self.sdh
-                .normal_interrupt_status
-                .modify(|val| val.clear_buffer_read_ready());
-        }
-        send_command(&self.sdh, SdhResp::R1, CmdType::Normal, 17, block_idx, true);
-        while !self
-            .sdh
-            .normal_interrupt_status
-            .read()
-            .is_buffer_read_ready()
-        {
-            // SDH_INT_BUFFER_READ_READY.
-            // Wait for buffer read ready.
-            core::hint::spin_loop()
-        }
-
-        for j in 0..Block::LEN / 4 {
-            let rx_lli_pool = &mut [LliPool::new(); 1];
-            let val = &mut [0u8; 4];
-            let rx_transfer = &mut [LliTransfer {
-                src_addr: 0x20060020,
-                dst_addr: val.as_mut_ptr() as u32,
-                nbytes: 4,
-            }];
-
-            self.dma_channel.lli_reload(rx_lli_pool, 1, rx_transfer, 1);
-            self.dma_channel.start();
+    pub fn card_type(&self) -> CardType {
+        self.card_type
+    }
 
-            while self.dma_channel.is_busy() {
-                core::hint::spin_loop();
-            }
+    /// Enable card insertion and removal interrupts on the SDCD signal.
+    #[inline]
+    pub fn enable_card_interrupts(&mut self) {
+        set_card_interrupts(&self.sdh, true);
+    }
 
-            self.dma_channel.stop();
+    /// Disable card insertion and removal interrupts.
+    #[inline]
+    pub fn disable_card_interrupts(&mut self) {
+        set_card_interrupts(&self.sdh, false);
+    }
 
-            // FIXME modify to a proper fence
-            fence(Ordering::SeqCst);
+    /// Check if a card is initialized and not removed since.
+    #[inline]
+    pub fn is_card_ready(&self) -> bool {
+        self.block_count != 0
+    }
 
-            block[j * 4 + 0] = val[0];
-            block[j * 4 + 1] = val[1];
-            block[j * 4 + 2] = val[2];
-            block[j * 4 + 3] = val[3];
+    /// Check `detect` for card insertion or removal since the last call.
+    ///
+    /// An inserted card is initialized before [`CardEvent::Inserted`] is returned; after
+    /// [`CardEvent::Removed`] the card holds no blocks until it is inserted again. Call it
+    /// periodically or from the card interrupt handler, whose status it clears.
+    #[inline]
+    pub fn poll_card<W: Write>(
+        &mut self,
+        detect: &mut impl CardDetect,
+        w: &mut W,
+    ) -> Option<CardEvent> {
+        let event = card_event(&self.sdh, detect, self.is_card_ready())?;
+        match event {
+            CardEvent::Inserted => self.init(w, false),
+            CardEvent::Removed => self.block_count = 0,
         }
+        Some(event)
     }
 
-    /// Write block from sdcard using system dma controller.
+    /// Read blocks from sdcard using system dma controller.
     #[inline]
-    pub(crate) fn write_block_sys_dma(&self, block: &Block, block_idx: u32) {
-        unsafe {
-            // SDH_SD_TRANSFER_MODE.
-            self.sdh.transfer_mode.modify(|val| {
-                val.set_data_transfer_mode(DataTransferMode::MOSI) // SDH_TO_HOST_DIR.
-                    .set_auto_cmd_mode(AutoCMDMode::None) // SDH_AUTO_CMD_EN.
-            });
+    pub(crate) fn read_blocks_sys_dma(&self, blocks: &mut [Block], block_idx: u32) {
+        let count = blocks.len() as u16;
+        prepare_transfer(&self.sdh, DataTransferMode::MISO, count);
+        let cmd_idx = if count > 1 { 18 } else { 17 };
+        let addr = self.card_type.address(block_idx);
+        send_command(&self.sdh, SdhResp::R1, CmdType::Normal, cmd_idx, addr, true);
 
-            // Block_size.
-            self.sdh
-                .block_size
-                .modify(|val| val.set_transfer_block(512));
+        for block in blocks {
+            while !self
+                .sdh
+                .normal_interrupt_status
+                .read()
+                .is_buffer_read_ready()
+            {
+                // SDH_INT_BUFFER_READ_READY.
+                // Wait for buffer read ready.
+                core::hint::spin_loop()
+            }
+            unsafe {
+                self.sdh
+                    .normal_interrupt_status
+                    .modify(|val| val.clear_buffer_read_ready());
+            }
 
-            // Block_count.
-            self.sdh.block_count.modify(|val| val.set_blocks_count(1));
+            for j in 0..Block::LEN / 4 {
+                let rx_lli_pool = &mut [LliPool::new(); 1];
+                let val = &mut [0u8; 4];
+                let rx_transfer = &mut [LliTransfer {
+                    src_addr: 0x20060020,
+                    dst_addr: val.as_mut_ptr() as u32,
+                    nbytes: 4,
+                }];
 
-            // SDH_ClearIntStatus(SDH_INT_BUFFER_WRITE_READY).
-            self.sdh
-                .normal_interrupt_status
-                .modify(|val| val.clear_buffer_write_ready());
-        }
-        send_command(&self.sdh, SdhResp::R1, CmdType::Normal, 24, block_idx, true);
+                flush_dcache(val.as_ptr() as usize, val.len());
+                self.dma_channel.lli_reload(rx_lli_pool, 1, rx_transfer, 1);
+                self.dma_channel.start();
 
-        while !self
-            .sdh
-            .normal_interrupt_status
-            .read()
-            .is_buffer_write_ready()
-        {
-            // SDH_INT_BUFFER_WRITE_READY.
-            // Wait for buffer write ready.
-            core::hint::spin_loop()
-        }
+                while self.dma_channel.is_busy() {
+                    core::hint::spin_loop();
+                }
 
-        for j in 0..Block::LEN / 4 {
-            let tx_lli_pool = &mut [LliPool::new(); 1];
-            let val = [
-                block[j * 4 + 0],
-                block[j * 4 + 1],
-                block[j * 4 + 2],
-                block[j * 4 + 3],
-            ];
-            let tx_transfer = &mut [LliTransfer {
-                src_addr: val.as_ptr() as u32,
-                dst_addr: 0x20060020,
-                nbytes: 4,
-            }];
+                self.dma_channel.stop();
 
-            self.dma_channel.lli_reload(tx_lli_pool, 1, tx_transfer, 1);
-            self.dma_channel.start();
+                fence(Ordering::SeqCst);
+                invalidate_dcache(val.as_ptr() as usize, val.len());
 
-            while self.dma_channel.is_busy() {
-                core::hint::spin_loop();
+                block[j * 4 + 0] = val[0];
+                block[j * 4 + 1] = val[1];
+                block[j * 4 + 2] = val[2];
+                block[j * 4 + 3] = val[3];
             }
+        }
 
-            self.dma_channel.stop();
+        wait_transfer_completed(&self.sdh);
+    }
 
-            // FIXME modify to a proper fence
-            fence(Ordering::SeqCst);
+    /// Write blocks to sdcard using system dma controller.
+    #[inline]
+    pub(crate) fn write_blocks_sys_dma(&self, blocks: &[Block], block_idx: u32) {
+        let count = blocks.len() as u16;
+        prepare_transfer(&self.sdh, DataTransferMode::MOSI, count);
+        let cmd_idx = if count > 1 { 25 } else { 24 };
+        let addr = self.card_type.address(block_idx);
+        send_command(&self.sdh, SdhResp::R1, CmdType::Normal, cmd_idx, addr, true);
 
+        for block in blocks {
+            while !self
+                .sdh
+                .normal_interrupt_status
+                .read()
+                .is_buffer_write_ready()
+            {
+                // SDH_INT_BUFFER_WRITE_READY.
+                // Wait for buffer write ready.
+                core::hint::spin_loop()
+            }
             unsafe {
                 self.sdh
                     .normal_interrupt_status
                     .modify(|val| val.clear_buffer_write_ready());
             }
-        }
 
-        // Wait for transfer completed.
-        while !self
-            .sdh
-            .normal_interrupt_status
-            .read()
-            .is_transfer_completed()
-        {
-            core::hint::spin_loop();
-        }
+            for j in 0..Block::LEN / 4 {
+                let tx_lli_pool = &mut [LliPool::new(); 1];
+                let val = [
+                    block[j * 4 + 0],
+                    block[j * 4 + 1],
+                    block[j * 4 + 2],
+                    block[j * 4 + 3],
+                ];
+                let tx_transfer = &mut [LliTransfer {
+                    src_addr: val.as_ptr() as u32,
+                    dst_addr: 0x20060020,
+                    nbytes: 4,
+                }];
 
-        unsafe {
-            self.sdh
-                .normal_interrupt_status
-                .modify(|val| val.clear_transfer_completed());
+                clean_dcache(val.as_ptr() as usize, val.len());
+                self.dma_channel.lli_reload(tx_lli_pool, 1, tx_transfer, 1);
+                self.dma_channel.start();
+
+                while self.dma_channel.is_busy() {
+                    core::hint::spin_loop();
+                }
+
+                self.dma_channel.stop();
+
+                fence(Ordering::SeqCst);
+            }
         }
+
+        wait_transfer_completed(&self.sdh);
     }
 
-    /// Read block from sdcard using system dma controller.
+    /// Read the block count from the SDH peripheral.
     #[inline]
     pub(crate) fn num_blocks(&self) -> embedded_sdmmc::BlockCount {
         embedded_sdmmc::BlockCount(self.block_count)
@@ -1,5 +1,6 @@
 use super::config::Config;
-use super::ops::{card_init, read_block, write_block};
+use super::detect::{CardDetect, CardEvent, card_event, set_card_interrupts};
+use super::ops::{CardType, card_init, read_blocks, write_blocks};
 use super::pad::Pads;
 use super::register::{BusVoltage, ClkGenMode, DmaMode, RegisterBlock};
 use crate::glb;
@@ -12,6 +13,7 @@ pub struct Sdh<SDH, PADS> {
     sdh: SDH,
     pads: PADS,
     block_count: u32,
+    card_type: CardType,
 }
 
 impl<SDH: Deref<Target = RegisterBlock>, PADS> Sdh<SDH, PADS> {
@@ -81,6 +83,7 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS> Sdh<SDH, PADS> {
             sdh,
             pads,
             block_count: 0,
+            card_type: CardType::High,
         }
     }
 
@@ -88,19 +91,62 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS> Sdh<SDH, PADS> {
     // TODO a more proper abstraction
     #[inline]
     pub fn init<W: Write>(&mut self, w: &mut W, debug: bool) {
-        self.block_count = card_init(&self.sdh, w, debug)
+        (self.block_count, self.card_type) = card_init(&self.sdh, w, debug)
     }
 
-    /// Read a block from the SDH peripheral.
+    /// Get capacity class of the initialized card.
     #[inline]
-    pub(crate) fn read_block(&self, block: &mut Block, block_idx: u32) {
-        read_block(&self.sdh, block, block_idx);
+    pub fn card_type(&self) -> CardType {
+        self.card_type
     }
 
-    /// Write a block to the SDH peripheral.
+    /// Enable card insertion and removal interrupts on the SDCD signal.
     #[inline]
-    pub(crate) fn write_block(&self, block: &Block, block_idx: u32) {
-        write_block(&self.sdh, block, block_idx);
+    pub fn enable_card_interrupts(&mut self) {
+        set_card_interrupts(&self.sdh, true);
+    }
+
+    /// Disable card insertion and removal interrupts.
+    #[inline]
+    pub fn disable_card_interrupts(&mut self) {
+        set_card_interrupts(&self.sdh, false);
+    }
+
+    /// Check if a card is initialized and not removed since.
+    #[inline]
+    pub fn is_card_ready(&self) -> bool {
+        self.block_count != 0
+    }
+
+    /// Check `detect` for card insertion or removal since the last call.
+    ///
+    /// An inserted card is initialized before [`CardEvent::Inserted`] is returned; after
+    /// [`CardEvent::Removed`] the card holds no blocks until it is inserted again. Call it
+    /// periodically or from the card interrupt handler, whose status it clears.
+    #[inline]
+    pub fn poll_card<W: Write>(
+        &mut self,
+        detect: &mut impl CardDetect,
+        w: &mut W,
+    ) -> Option<CardEvent> {
+        let event = card_event(&self.sdh, detect, self.is_card_ready())?;
+        match event {
+            CardEvent::Inserted => self.init(w, false),
+            CardEvent::Removed => self.block_count = 0,
+        }
+        Some(event)
+    }
+
+    /// Read blocks from the SDH peripheral.
+    #[inline]
+    pub(crate) fn read_blocks(&self, blocks: &mut [Block], block_idx: u32) {
+        read_blocks(&self.sdh, blocks, self.card_type.address(block_idx));
+    }
+
+    /// Write blocks to the SDH peripheral.
+    #[inline]
+    pub(crate) fn write_blocks(&self, blocks: &[Block], block_idx: u32) {
+        write_blocks(&self.sdh, blocks, self.card_type.address(block_idx));
     }
 
     /// Read the block count from the SDH peripheral.
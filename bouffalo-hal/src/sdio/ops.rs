@@ -1,4 +1,6 @@
-use super::register::{Argument, AutoCMDMode, CmdType, Command, DataTransferMode, RegisterBlock};
+use super::register::{
+    Argument, AutoCMDMode, BlockMode, CmdType, Command, DataTransferMode, RegisterBlock,
+};
 use embedded_io::Write;
 use embedded_sdmmc::Block;
 
@@ -43,92 +45,127 @@ enum SdhTransFlag {
     Abort = 0x00C00000,              // Abort command.
 }
 
+/// Capacity class of an initialized SD card.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CardType {
+    /// Standard capacity card (SDSC), addressed in bytes.
+    Standard,
+    /// High or extended capacity card (SDHC/SDXC), addressed in blocks.
+    High,
+}
+
+impl CardType {
+    /// Convert block index into data command argument.
+    #[inline]
+    pub(crate) const fn address(self, block_idx: u32) -> u32 {
+        match self {
+            CardType::Standard => block_idx * Block::LEN as u32,
+            CardType::High => block_idx,
+        }
+    }
+}
+
+/// Prepare transfer mode and block registers for a data command of `count` blocks.
 #[inline]
-pub(crate) fn read_block(sdh: &RegisterBlock, block: &mut Block, block_idx: u32) {
+pub(crate) fn prepare_transfer(sdh: &RegisterBlock, direction: DataTransferMode, count: u16) {
     unsafe {
         // SDH_SD_TRANSFER_MODE.
         sdh.transfer_mode.modify(|val| {
-            val.set_data_transfer_mode(DataTransferMode::MISO) // SDH_TO_HOST_DIR.
-                .set_auto_cmd_mode(AutoCMDMode::None) // SDH_AUTO_CMD_EN.
+            let val = val.set_data_transfer_mode(direction); // SDH_TO_HOST_DIR.
+            if count > 1 {
+                val.set_block_mode(BlockMode::MultiBlock) // SDH_MULTI_BLK_SEL.
+                    .enable_block_count() // SDH_BLK_CNT_EN.
+                    .set_auto_cmd_mode(AutoCMDMode::CMD12) // SDH_AUTO_CMD_EN.
+            } else {
+                val.set_block_mode(BlockMode::Other)
+                    .disable_block_count()
+                    .set_auto_cmd_mode(AutoCMDMode::None)
+            }
         });
 
         // Block_size.
         sdh.block_size.modify(|val| val.set_transfer_block(512));
 
         // Block_count.
-        sdh.block_count.modify(|val| val.set_blocks_count(1));
+        sdh.block_count.modify(|val| val.set_blocks_count(count));
 
-        // SDH_ClearIntStatus(SDH_INT_BUFFER_READ_READY).
+        // SDH_ClearIntStatus(SDH_INT_BUFFER_READ_READY | SDH_INT_BUFFER_WRITE_READY).
         sdh.normal_interrupt_status
-            .modify(|val| val.clear_buffer_read_ready());
-    }
-    send_command(sdh, SdhResp::R1, CmdType::Normal, 17, block_idx, true);
-    while !sdh.normal_interrupt_status.read().is_buffer_read_ready() {
-        // SDH_INT_BUFFER_READ_READY.
-        // Wait for buffer read ready.
-        core::hint::spin_loop()
-    }
-    for j in 0..Block::LEN / 4 {
-        let val = sdh.buffer_data_port.read().buffer_data();
-        block[j * 4 + 0] = (val >> 0) as u8;
-        block[j * 4 + 1] = (val >> 8) as u8;
-        block[j * 4 + 2] = (val >> 16) as u8;
-        block[j * 4 + 3] = (val >> 24) as u8;
+            .modify(|val| val.clear_buffer_read_ready().clear_buffer_write_ready());
     }
 }
 
+/// Wait for data transfer completed and clear the completion flag.
 #[inline]
-pub(crate) fn write_block(sdh: &RegisterBlock, block: &Block, block_idx: u32) {
+pub(crate) fn wait_transfer_completed(sdh: &RegisterBlock) {
+    while !sdh.normal_interrupt_status.read().is_transfer_completed() {
+        core::hint::spin_loop();
+    }
     unsafe {
-        // SDH_SD_TRANSFER_MODE.
-        sdh.transfer_mode.modify(|val| {
-            val.set_data_transfer_mode(DataTransferMode::MOSI) // SDH_TO_HOST_DIR.
-                .set_auto_cmd_mode(AutoCMDMode::None) // SDH_AUTO_CMD_EN.
-        });
-
-        // Block_size.
-        sdh.block_size.modify(|val| val.set_transfer_block(512));
-
-        // Block_count.
-        sdh.block_count.modify(|val| val.set_blocks_count(1));
-
-        // SDH_ClearIntStatus(SDH_INT_BUFFER_WRITE_READY).
         sdh.normal_interrupt_status
-            .modify(|val| val.clear_buffer_write_ready());
+            .modify(|val| val.clear_transfer_completed());
     }
-    send_command(sdh, SdhResp::R1, CmdType::Normal, 24, block_idx, true);
+}
 
-    while !sdh.normal_interrupt_status.read().is_buffer_write_ready() {
-        // SDH_INT_BUFFER_WRITE_READY.
-        // Wait for buffer write ready.
-        core::hint::spin_loop()
+/// Read blocks starting from card address `addr`, at most 65535 blocks at once.
+#[inline]
+pub(crate) fn read_blocks(sdh: &RegisterBlock, blocks: &mut [Block], addr: u32) {
+    let count = blocks.len() as u16;
+    prepare_transfer(sdh, DataTransferMode::MISO, count);
+    let cmd_idx = if count > 1 { 18 } else { 17 };
+    send_command(sdh, SdhResp::R1, CmdType::Normal, cmd_idx, addr, true);
+    for block in blocks {
+        while !sdh.normal_interrupt_status.read().is_buffer_read_ready() {
+            // SDH_INT_BUFFER_READ_READY.
+            // Wait for buffer read ready.
+            core::hint::spin_loop()
+        }
+        unsafe {
+            sdh.normal_interrupt_status
+                .modify(|val| val.clear_buffer_read_ready());
+        }
+        for j in 0..Block::LEN / 4 {
+            let val = sdh.buffer_data_port.read().buffer_data();
+            block[j * 4 + 0] = (val >> 0) as u8;
+            block[j * 4 + 1] = (val >> 8) as u8;
+            block[j * 4 + 2] = (val >> 16) as u8;
+            block[j * 4 + 3] = (val >> 24) as u8;
+        }
     }
+    wait_transfer_completed(sdh);
+}
 
-    for j in 0..Block::LEN / 4 {
-        let data_val = u32::from_le_bytes([
-            block[j * 4 + 0],
-            block[j * 4 + 1],
-            block[j * 4 + 2],
-            block[j * 4 + 3],
-        ]);
-
+/// Write blocks starting from card address `addr`, at most 65535 blocks at once.
+#[inline]
+pub(crate) fn write_blocks(sdh: &RegisterBlock, blocks: &[Block], addr: u32) {
+    let count = blocks.len() as u16;
+    prepare_transfer(sdh, DataTransferMode::MOSI, count);
+    let cmd_idx = if count > 1 { 25 } else { 24 };
+    send_command(sdh, SdhResp::R1, CmdType::Normal, cmd_idx, addr, true);
+    for block in blocks {
+        while !sdh.normal_interrupt_status.read().is_buffer_write_ready() {
+            // SDH_INT_BUFFER_WRITE_READY.
+            // Wait for buffer write ready.
+            core::hint::spin_loop()
+        }
         unsafe {
-            sdh.buffer_data_port
-                .modify(|val| val.set_buffer_data(data_val));
             sdh.normal_interrupt_status
                 .modify(|val| val.clear_buffer_write_ready());
         }
+        for j in 0..Block::LEN / 4 {
+            let data_val = u32::from_le_bytes([
+                block[j * 4 + 0],
+                block[j * 4 + 1],
+                block[j * 4 + 2],
+                block[j * 4 + 3],
+            ]);
+            unsafe {
+                sdh.buffer_data_port
+                    .modify(|val| val.set_buffer_data(data_val));
+            }
+        }
     }
-
-    // Wait for transfer completed.
-    while !sdh.normal_interrupt_status.read().is_transfer_completed() {
-        core::hint::spin_loop();
-    }
-
-    unsafe {
-        sdh.normal_interrupt_status
-            .modify(|val| val.clear_transfer_completed());
-    }
+    wait_transfer_completed(sdh);
 }
 
 /// Send command to sdcard.
@@ -175,9 +212,9 @@ pub(crate) fn send_command(
     }
 }
 
-/// Returns the block_count of SD card.
+/// Returns the block_count and capacity class of SD card.
 #[inline]
-pub(crate) fn card_init<W: Write>(sdh: &RegisterBlock, w: &mut W, debug: bool) -> u32 {
+pub(crate) fn card_init<W: Write>(sdh: &RegisterBlock, w: &mut W, debug: bool) -> (u32, CardType) {
     // Sdcard idle.
     loop {
         send_command(sdh, SdhResp::None, CmdType::Normal, 0, 0, false);
@@ -200,10 +237,10 @@ pub(crate) fn card_init<W: Write>(sdh: &RegisterBlock, w: &mut W, debug: bool) -
         sleep_ms(1000);
     }
 
-    loop {
-        const OCR_NBUSY: u32 = 0x80000000;
-        const OCR_VOLTAGE_MASK: u32 = 0x007FFF80;
-        const OCR_HCS: u32 = 0x40000000;
+    const OCR_NBUSY: u32 = 0x80000000;
+    const OCR_VOLTAGE_MASK: u32 = 0x007FFF80;
+    const OCR_HCS: u32 = 0x40000000;
+    let ocr = loop {
         send_command(sdh, SdhResp::R1, CmdType::Normal, 55, 0, false);
         sleep_ms(100);
         send_command(
@@ -215,11 +252,20 @@ pub(crate) fn card_init<W: Write>(sdh: &RegisterBlock, w: &mut W, debug: bool) -
             false,
         );
         sleep_ms(100);
-        let ocr = get_resp(sdh);
-        if (ocr as u32 & OCR_NBUSY) == OCR_NBUSY {
-            break;
+        let ocr = get_resp(sdh) as u32;
+        if (ocr & OCR_NBUSY) == OCR_NBUSY {
+            break ocr;
         }
         sleep_ms(100);
+    };
+    // Card capacity status shares the bit position with host capacity support.
+    let card_type = if ocr & OCR_HCS != 0 {
+        CardType::High
+    } else {
+        CardType::Standard
+    };
+    if debug {
+        writeln!(*w, "ocr: {:#010X}, card type: {:?}", ocr, card_type).ok();
     }
 
     // Send CMD2 to get CID.
@@ -242,22 +288,31 @@ pub(crate) fn card_init<W: Write>(sdh: &RegisterBlock, w: &mut W, debug: bool) -
     send_command(sdh, SdhResp::R2, CmdType::Normal, 9, rca << 16, false);
     sleep_ms(100);
     let csd_raw = get_resp(sdh);
-    let (csd_structure, c_size) = parse_csd_v2(csd_raw);
-    if csd_structure != 1 {
+    let block_count = match parse_csd_structure(csd_raw) {
+        0 => parse_csd_v1(csd_raw),
+        1 => Some(parse_csd_v2(csd_raw)),
+        _ => None,
+    };
+    let Some(block_count) = block_count else {
         writeln!(*w, "unexpected CSD: {:#034X}", csd_raw).ok();
         loop {}
-    }
+    };
     if debug {
-        writeln!(*w, "csd: {:#034X}, c_size: {}", csd_raw, c_size).ok();
+        writeln!(*w, "csd: {:#034X}, block count: {}", csd_raw, block_count).ok();
     }
 
     let block_size = 512;
-    let block_count = (c_size + 1) * 1024;
 
     // Send CMD7 to select card.
     send_command(sdh, SdhResp::R1B, CmdType::Normal, 7, rca << 16, false);
     sleep_ms(100);
 
+    // Standard capacity cards may default to a larger block length, send CMD16 to fix it.
+    if card_type == CardType::Standard {
+        send_command(sdh, SdhResp::R1, CmdType::Normal, 16, block_size, false);
+        sleep_ms(100);
+    }
+
     // Set 1 data len, CMD55 -> ACMD6.
     send_command(sdh, SdhResp::R1, CmdType::Normal, 55, rca << 16, false);
     sleep_ms(100);
@@ -286,7 +341,7 @@ pub(crate) fn card_init<W: Write>(sdh: &RegisterBlock, w: &mut W, debug: bool) -
         }
     }
 
-    block_count
+    (block_count, card_type)
 }
 
 #[inline]
@@ -301,10 +356,58 @@ fn sleep_ms(n: u32) {
     }
 }
 
-/// Parse CSD version 2.0.
+// The response register holds CSD bits [127:8], so CSD bit `n` is found at bit `n - 8`.
+
+/// Parse CSD structure version.
+#[inline]
+fn parse_csd_structure(csd: u128) -> u32 {
+    (((csd >> (32 * 3)) & 0xC00000) >> 22) as u32
+}
+
+/// Parse CSD version 1.0, returns count of 512-byte blocks.
+///
+/// Returns `None` if READ_BL_LEN is not one of the 512, 1024 or 2048-byte lengths
+/// allowed by the specification.
 #[inline]
-fn parse_csd_v2(csd: u128) -> (u32, u32) {
-    let csd_structure = (((csd >> (32 * 3)) & 0xC00000) >> 22) as u32;
+fn parse_csd_v1(csd: u128) -> Option<u32> {
+    let read_bl_len = ((csd >> 72) & 0xF) as u32;
+    if !(9..=11).contains(&read_bl_len) {
+        return None;
+    }
+    let c_size = ((csd >> 54) & 0xFFF) as u32;
+    let c_size_mult = ((csd >> 39) & 0x7) as u32;
+    Some((c_size + 1) << (c_size_mult + 2 + read_bl_len - 9))
+}
+
+/// Parse CSD version 2.0, returns count of 512-byte blocks.
+#[inline]
+fn parse_csd_v2(csd: u128) -> u32 {
     let c_size = (((csd >> 32) & 0x3FFFFF00) >> 8) as u32;
-    (csd_structure, c_size)
+    (c_size + 1) * 1024
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CardType, parse_csd_structure, parse_csd_v1, parse_csd_v2};
+
+    #[test]
+    fn parse_csd_functions() {
+        // CSD 1.0 card, READ_BL_LEN = 10, C_SIZE = 0xF17, C_SIZE_MULT = 7 (2 GB).
+        let csd: u128 = (0xA << 72) | (0xF17 << 54) | (0x7 << 39);
+        assert_eq!(parse_csd_structure(csd), 0);
+        assert_eq!(parse_csd_v1(csd), Some(0x3C6000));
+        // Reserved READ_BL_LEN values.
+        assert_eq!(parse_csd_v1((0x8 << 72) | (0xF17 << 54)), None);
+        assert_eq!(parse_csd_v1((0xC << 72) | (0xF17 << 54)), None);
+        // CSD 2.0 card, C_SIZE = 0x3B37 (8 GB).
+        let csd: u128 = (0x40 << 112) | (0x3B37 << 40);
+        assert_eq!(parse_csd_structure(csd), 1);
+        assert_eq!(parse_csd_v2(csd), 0xECE000);
+    }
+
+    #[test]
+    fn card_type_address() {
+        assert_eq!(CardType::Standard.address(3), 1536);
+        assert_eq!(CardType::High.address(3), 3);
+    }
 }
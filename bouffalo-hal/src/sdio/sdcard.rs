@@ -2,12 +2,15 @@ use crate::{dma::UntypedChannel, sdio::NonSysDmaSdh, sdio::RegisterBlock, sdio::
 use core::ops::Deref;
 use embedded_sdmmc::{Block, BlockDevice, BlockIdx};
 
+/// Maximum block count of a single transfer, limited by the block count register.
+const MAX_BLOCKS_PER_TRANSFER: usize = u16::MAX as usize;
+
 /// A block device that uses the SDIO interface.
 pub trait InnerSdh<'a> {
-    /// Read one block at the given block index.
-    fn sdh_read_block(&self, block: &mut Block, block_idx: u32);
-    /// Write one block at the given block index.
-    fn sdh_write_block(&self, block: &Block, block_idx: u32);
+    /// Read consecutive blocks starting at the given block index.
+    fn sdh_read_blocks(&self, blocks: &mut [Block], block_idx: u32);
+    /// Write consecutive blocks starting at the given block index.
+    fn sdh_write_blocks(&self, blocks: &[Block], block_idx: u32);
     /// Determine how many blocks this device can hold.
     fn sdh_num_blocks(&self) -> embedded_sdmmc::BlockCount;
 }
@@ -18,12 +21,12 @@ where
     CH: Deref<Target = UntypedChannel<'a>>,
 {
     #[inline]
-    fn sdh_read_block(&self, block: &mut Block, block_idx: u32) {
-        self.read_block_sys_dma(block, block_idx);
+    fn sdh_read_blocks(&self, blocks: &mut [Block], block_idx: u32) {
+        self.read_blocks_sys_dma(blocks, block_idx);
     }
     #[inline]
-    fn sdh_write_block(&self, block: &Block, block_idx: u32) {
-        self.write_block_sys_dma(block, block_idx);
+    fn sdh_write_blocks(&self, blocks: &[Block], block_idx: u32) {
+        self.write_blocks_sys_dma(blocks, block_idx);
     }
     #[inline]
     fn sdh_num_blocks(&self) -> embedded_sdmmc::BlockCount {
@@ -36,12 +39,12 @@ where
     SDH: Deref<Target = RegisterBlock>,
 {
     #[inline]
-    fn sdh_read_block(&self, block: &mut Block, block_idx: u32) {
-        self.read_block(block, block_idx);
+    fn sdh_read_blocks(&self, blocks: &mut [Block], block_idx: u32) {
+        self.read_blocks(blocks, block_idx);
     }
     #[inline]
-    fn sdh_write_block(&self, block: &Block, block_idx: u32) {
-        self.write_block(block, block_idx);
+    fn sdh_write_blocks(&self, blocks: &[Block], block_idx: u32) {
+        self.write_blocks(blocks, block_idx);
     }
     #[inline]
     fn sdh_num_blocks(&self) -> embedded_sdmmc::BlockCount {
@@ -71,17 +74,20 @@ impl<'a, T: InnerSdh<'a>> BlockDevice for Sdcard<'a, T> {
         start_block_idx: BlockIdx,
         _reason: &str,
     ) -> Result<(), Self::Error> {
-        for (i, block) in blocks.iter_mut().enumerate() {
-            self.sdh.sdh_read_block(block, start_block_idx.0 + i as u32);
+        let mut block_idx = start_block_idx.0;
+        for chunk in blocks.chunks_mut(MAX_BLOCKS_PER_TRANSFER) {
+            self.sdh.sdh_read_blocks(chunk, block_idx);
+            block_idx += chunk.len() as u32;
         }
         Ok(())
     }
 
     #[inline]
     fn write(&self, blocks: &[Block], start_block_idx: BlockIdx) -> Result<(), Self::Error> {
-        for (i, block) in blocks.iter().enumerate() {
-            self.sdh
-                .sdh_write_block(block, start_block_idx.0 + i as u32);
+        let mut block_idx = start_block_idx.0;
+        for chunk in blocks.chunks(MAX_BLOCKS_PER_TRANSFER) {
+            self.sdh.sdh_write_blocks(chunk, block_idx);
+            block_idx += chunk.len() as u32;
         }
         Ok(())
     }
@@ -1,12 +1,21 @@
 //! Serial Peripheral Interface peripheral.
 
+use crate::dma::{
+    BurstSize, DmaAddr, DmaChannelConfig, DmaMode, LliPool, LliTransfer, Periph4Dma01, Periph4Dma2,
+    PeripheralId, TransferWidth, TypedChannel, Word, clean_dcache, flush_dcache, invalidate_dcache,
+};
 use crate::glb::{self, v2::SpiMode};
 use crate::gpio::{self, Alternate};
+use core::cell::RefCell;
 use core::cmp::max;
 use core::ops::Deref;
+use core::sync::atomic::{Ordering, fence};
 use embedded_hal::spi::Mode;
 use volatile_register::{RO, RW, WO};
 
+mod nor_flash;
+pub use nor_flash::*;
+
 /// Serial Peripheral Interface registers.
 #[repr(C)]
 pub struct RegisterBlock {
@@ -687,12 +696,279 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> Spi<SPI, PADS, I>
     pub fn free(self) -> (SPI, PADS) {
         (self.spi, self.pads)
     }
+
+    /// Assert hardware chip select until [`deselect`](Self::deselect).
+    ///
+    /// The CS signal of this SPI must be multiplexed to a pad connected to the slave;
+    /// otherwise it is asserted only during each transfer.
+    #[inline]
+    pub fn select(&mut self) {
+        unsafe { self.spi.config.modify(|config| config.enable_master()) };
+    }
+
+    /// Release hardware chip select asserted by [`select`](Self::select).
+    #[inline]
+    pub fn deselect(&mut self) {
+        unsafe { self.spi.config.modify(|config| config.disable_master()) };
+    }
+
+    /// Enable master for a transfer, returns `true` if chip select already holds it.
+    #[inline]
+    fn start_master(&self) -> bool {
+        let config = self.spi.config.read();
+        if config.is_master_enabled() {
+            return true;
+        }
+        unsafe { self.spi.config.write(config.enable_master()) };
+        false
+    }
+
+    /// Disable master after a transfer, unless chip select holds it.
+    #[inline]
+    fn stop_master(&self, held: bool) {
+        if !held {
+            unsafe { self.spi.config.modify(|config| config.disable_master()) };
+        }
+    }
+
+    /// Set data frame size.
+    ///
+    /// Blocking `embedded-hal` transfers always use 8-bit frames; other frame sizes are
+    /// only used by DMA transfers.
+    #[inline]
+    pub fn set_frame_size(&mut self, val: FrameSize) {
+        unsafe { self.spi.config.modify(|config| config.set_frame_size(val)) };
+    }
+
+    /// Get data frame size.
+    #[inline]
+    pub fn frame_size(&self) -> FrameSize {
+        self.spi.config.read().frame_size()
+    }
+
+    /// Write `write` and read into `read` at the same time using DMA.
+    ///
+    /// Buffers of any length are split into chunks the DMA controller accepts. When
+    /// lengths differ, zero words are written after `write` ends and received words are
+    /// discarded after `read` ends. Words must match the frame size: `u8` for 8-bit,
+    /// `u16` for 16-bit, and `u32` for 24-bit or 32-bit frames.
+    #[inline]
+    pub fn transfer_dma<P, W>(
+        &mut self,
+        tx: &mut TypedChannel<'_, P>,
+        rx: &mut TypedChannel<'_, P>,
+        read: &mut [W],
+        write: &[W],
+    ) -> Result<(), Error>
+    where
+        P: DmaPeripheral<I>,
+        W: FrameWord,
+    {
+        if !frame_size_matches::<W>(self.frame_size()) {
+            return Err(Error::FrameSize);
+        }
+        // Any bit pattern is a valid DMA word.
+        let filler: W = unsafe { core::mem::zeroed() };
+        let mut sink: W = unsafe { core::mem::zeroed() };
+        let common = read.len().min(write.len());
+        let (read_common, read_rest) = read.split_at_mut(common);
+        let (write_common, write_rest) = write.split_at(common);
+
+        let held = self.start_master();
+        for (r, w) in read_common
+            .chunks_mut(DMA_CHUNK_WORDS)
+            .zip(write_common.chunks(DMA_CHUNK_WORDS))
+        {
+            self.dma_chunk(tx, rx, (w.as_ptr(), true), (r.as_mut_ptr(), true), r.len());
+        }
+        for w in write_rest.chunks(DMA_CHUNK_WORDS) {
+            self.dma_chunk(tx, rx, (w.as_ptr(), true), (&mut sink, false), w.len());
+        }
+        for r in read_rest.chunks_mut(DMA_CHUNK_WORDS) {
+            self.dma_chunk(tx, rx, (&filler, false), (r.as_mut_ptr(), true), r.len());
+        }
+        self.stop_master(held);
+        Ok(())
+    }
+
+    /// Write `words` and replace them with received words using DMA.
+    ///
+    /// Buffers of any length are split into chunks the DMA controller accepts. Words must
+    /// match the frame size as in [`transfer_dma`](Self::transfer_dma).
+    #[inline]
+    pub fn transfer_in_place_dma<P, W>(
+        &mut self,
+        tx: &mut TypedChannel<'_, P>,
+        rx: &mut TypedChannel<'_, P>,
+        words: &mut [W],
+    ) -> Result<(), Error>
+    where
+        P: DmaPeripheral<I>,
+        W: FrameWord,
+    {
+        if !frame_size_matches::<W>(self.frame_size()) {
+            return Err(Error::FrameSize);
+        }
+        let held = self.start_master();
+        for chunk in words.chunks_mut(DMA_CHUNK_WORDS) {
+            // Receive channel writes each word only after transmit channel has read it.
+            let ptr = chunk.as_mut_ptr();
+            self.dma_chunk(tx, rx, (ptr, true), (ptr, true), chunk.len());
+        }
+        self.stop_master(held);
+        Ok(())
+    }
+
+    /// Transfer one chunk of at most `DMA_CHUNK_WORDS` words, address pairs tell if the
+    /// buffer advances or stays at a single word.
+    #[inline]
+    fn dma_chunk<P: DmaPeripheral<I>, W: FrameWord>(
+        &self,
+        tx: &mut TypedChannel<'_, P>,
+        rx: &mut TypedChannel<'_, P>,
+        (src, src_inc): (*const W, bool),
+        (dst, dst_inc): (*mut W, bool),
+        len: usize,
+    ) {
+        let src_bytes = size_of::<W>() * if src_inc { len } else { 1 };
+        let dst_bytes = size_of::<W>() * if dst_inc { len } else { 1 };
+        let nbytes = (len * size_of::<W>()) as u32;
+        clean_dcache(src as usize, src_bytes);
+        flush_dcache(dst as usize, dst_bytes);
+        unsafe {
+            self.spi.fifo_config_0.modify(|config| {
+                config
+                    .disable_dma_transmit()
+                    .disable_dma_receive()
+                    .clear_transmit_fifo()
+                    .clear_receive_fifo()
+            })
+        };
+        rx.configure(DmaChannelConfig {
+            direction: DmaMode::Periph2Mem,
+            src_req: Some(P::RECEIVE),
+            dst_req: None,
+            src_addr_inc: false,
+            dst_addr_inc: dst_inc,
+            src_burst_size: BurstSize::INCR1,
+            dst_burst_size: BurstSize::INCR1,
+            src_transfer_width: W::TRANSFER_WIDTH,
+            dst_transfer_width: W::TRANSFER_WIDTH,
+        });
+        tx.configure(DmaChannelConfig {
+            direction: DmaMode::Mem2Periph,
+            src_req: None,
+            dst_req: Some(P::TRANSMIT),
+            src_addr_inc: src_inc,
+            dst_addr_inc: false,
+            src_burst_size: BurstSize::INCR1,
+            dst_burst_size: BurstSize::INCR1,
+            src_transfer_width: W::TRANSFER_WIDTH,
+            dst_transfer_width: W::TRANSFER_WIDTH,
+        });
+        let rx_transfer = &mut [LliTransfer {
+            src_addr: P::RECEIVE_ADDR as u32,
+            dst_addr: dst as u32,
+            nbytes,
+        }];
+        let tx_transfer = &mut [LliTransfer {
+            src_addr: src as u32,
+            dst_addr: P::TRANSMIT_ADDR as u32,
+            nbytes,
+        }];
+        // A chunk fits in one linked list item, which is loaded into channel registers.
+        rx.lli_reload(&mut [LliPool::new(); 1], 1, rx_transfer, 1);
+        tx.lli_reload(&mut [LliPool::new(); 1], 1, tx_transfer, 1);
+        rx.start();
+        tx.start();
+        unsafe {
+            self.spi
+                .fifo_config_0
+                .modify(|config| config.enable_dma_transmit().enable_dma_receive())
+        };
+        while tx.is_busy() || rx.is_busy() {
+            core::hint::spin_loop();
+        }
+        tx.stop();
+        rx.stop();
+        unsafe {
+            self.spi
+                .fifo_config_0
+                .modify(|config| config.disable_dma_transmit().disable_dma_receive())
+        };
+        fence(Ordering::SeqCst);
+        // Discarded words are never read, and their cache line may hold live stack data.
+        if dst_inc {
+            invalidate_dcache(dst as usize, dst_bytes);
+        }
+    }
+}
+
+// Words per DMA chunk, the largest transfer size used by DMA linked list items.
+const DMA_CHUNK_WORDS: usize = 4064;
+
+/// Word type of SPI frames transferred by DMA.
+pub trait FrameWord: Word {
+    /// DMA transfer width of the word.
+    const TRANSFER_WIDTH: TransferWidth;
+}
+
+impl FrameWord for u8 {
+    const TRANSFER_WIDTH: TransferWidth = TransferWidth::Byte;
+}
+impl FrameWord for u16 {
+    const TRANSFER_WIDTH: TransferWidth = TransferWidth::HalfWord;
+}
+impl FrameWord for u32 {
+    const TRANSFER_WIDTH: TransferWidth = TransferWidth::Word;
+}
+
+/// Check if words of type `W` carry frames of size `frame`.
+#[inline]
+const fn frame_size_matches<W>(frame: FrameSize) -> bool {
+    matches!(
+        (frame, size_of::<W>()),
+        (FrameSize::Eight, 1)
+            | (FrameSize::Sixteen, 2)
+            | (FrameSize::TwentyFour, 4)
+            | (FrameSize::ThirtyTwo, 4)
+    )
+}
+
+/// DMA request lines and data registers of SPI peripheral `I`.
+pub trait DmaPeripheral<const I: usize>: PeripheralId + Copy {
+    /// Transmit request line.
+    const TRANSMIT: Self;
+    /// Receive request line.
+    const RECEIVE: Self;
+    /// Transmit data register address.
+    const TRANSMIT_ADDR: DmaAddr;
+    /// Receive data register address.
+    const RECEIVE_ADDR: DmaAddr;
+}
+
+impl DmaPeripheral<0> for Periph4Dma01 {
+    const TRANSMIT: Self = Periph4Dma01::Spi0Tx;
+    const RECEIVE: Self = Periph4Dma01::Spi0Rx;
+    const TRANSMIT_ADDR: DmaAddr = DmaAddr::Spi0Tx;
+    const RECEIVE_ADDR: DmaAddr = DmaAddr::Spi0Rx;
+}
+
+impl DmaPeripheral<1> for Periph4Dma2 {
+    const TRANSMIT: Self = Periph4Dma2::Spi1Tx;
+    const RECEIVE: Self = Periph4Dma2::Spi1Rx;
+    const TRANSMIT_ADDR: DmaAddr = DmaAddr::Spi1Tx;
+    const RECEIVE_ADDR: DmaAddr = DmaAddr::Spi1Rx;
 }
 
 /// SPI error.
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum Error {
+    /// Word type does not match data frame size.
+    FrameSize,
+    /// Chip select pin could not be driven.
+    ChipSelect,
     Other,
 }
 
@@ -701,6 +977,8 @@ impl embedded_hal::spi::Error for Error {
     fn kind(&self) -> embedded_hal::spi::ErrorKind {
         use embedded_hal::spi::ErrorKind;
         match self {
+            Error::FrameSize => ErrorKind::Other,
+            Error::ChipSelect => ErrorKind::ChipSelectFault,
             Error::Other => ErrorKind::Other,
         }
     }
@@ -717,7 +995,7 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi
 {
     #[inline]
     fn read(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
-        unsafe { self.spi.config.modify(|config| config.enable_master()) };
+        let held = self.start_master();
 
         buf.iter_mut().for_each(|slot| {
             while self.spi.fifo_config_1.read().receive_available_bytes() == 0 {
@@ -726,12 +1004,12 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi
             *slot = self.spi.fifo_read.read()
         });
 
-        unsafe { self.spi.config.modify(|config| config.disable_master()) };
+        self.stop_master(held);
         Ok(())
     }
     #[inline]
     fn write(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
-        unsafe { self.spi.config.modify(|config| config.enable_master()) };
+        let held = self.start_master();
 
         buf.iter().for_each(|&word| {
             while self.spi.fifo_config_1.read().transmit_available_bytes() == 0 {
@@ -741,13 +1019,13 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi
             _ = self.spi.fifo_read.read();
         });
 
-        unsafe { self.spi.config.modify(|config| config.disable_master()) };
+        self.stop_master(held);
         Ok(())
     }
     #[inline]
     fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
         const MAX_RETRY: usize = 1000;
-        unsafe { self.spi.config.modify(|config| config.enable_master()) };
+        let held = self.start_master();
 
         let (mut tx, mut rx) = (0, 0);
         let mut fifo_config = self.spi.fifo_config_1.read();
@@ -772,13 +1050,13 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi
             }
         }
 
-        unsafe { self.spi.config.modify(|config| config.disable_master()) };
+        self.stop_master(held);
         Ok(())
     }
     #[inline]
     fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
         const MAX_RETRY: usize = 1000;
-        unsafe { self.spi.config.modify(|config| config.enable_master()) };
+        let held = self.start_master();
 
         let (mut tx, mut rx) = (0, 0);
         let mut fifo_config = self.spi.fifo_config_1.read();
@@ -803,7 +1081,7 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi
             }
         }
 
-        unsafe { self.spi.config.modify(|config| config.disable_master()) };
+        self.stop_master(held);
         Ok(())
     }
     #[inline]
@@ -821,36 +1099,139 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi
 impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi::SpiDevice
     for Spi<SPI, PADS, I>
 {
+    #[inline]
     fn transaction(
         &mut self,
         operations: &mut [embedded_hal::spi::Operation<'_, u8>],
     ) -> Result<(), Self::Error> {
-        for op in operations {
-            match op {
-                embedded_hal::spi::Operation::Read(buf) => {
-                    embedded_hal::spi::SpiBus::read(self, buf)?
-                }
-                embedded_hal::spi::Operation::Write(buf) => {
-                    embedded_hal::spi::SpiBus::write(self, buf)?
-                }
-                embedded_hal::spi::Operation::Transfer(read, write) => {
-                    embedded_hal::spi::SpiBus::transfer(self, read, write)?
-                }
-                embedded_hal::spi::Operation::TransferInPlace(buf) => {
-                    embedded_hal::spi::SpiBus::transfer_in_place(self, buf)?
-                }
-                embedded_hal::spi::Operation::DelayNs(_delay) => {
-                    for _ in 0..*_delay {
-                        // TODO: more accurate delay
-                        core::hint::spin_loop();
-                    }
+        run_operations(self, operations)
+    }
+}
+
+#[inline]
+fn run_operations<BUS: embedded_hal::spi::SpiBus<u8, Error = Error>>(
+    bus: &mut BUS,
+    operations: &mut [embedded_hal::spi::Operation<'_, u8>],
+) -> Result<(), Error> {
+    for op in operations {
+        match op {
+            embedded_hal::spi::Operation::Read(buf) => bus.read(buf)?,
+            embedded_hal::spi::Operation::Write(buf) => bus.write(buf)?,
+            embedded_hal::spi::Operation::Transfer(read, write) => bus.transfer(read, write)?,
+            embedded_hal::spi::Operation::TransferInPlace(buf) => bus.transfer_in_place(buf)?,
+            embedded_hal::spi::Operation::DelayNs(_delay) => {
+                for _ in 0..*_delay {
+                    // TODO: more accurate delay
+                    core::hint::spin_loop();
                 }
             }
         }
+    }
+    Ok(())
+}
+
+/// Chip select signal of a [`Device`] on a shared bus.
+pub trait ChipSelect {
+    /// Assert chip select to address the slave.
+    fn select(&mut self, spi: &RegisterBlock) -> Result<(), Error>;
+    /// Release chip select after the transaction.
+    fn deselect(&mut self, spi: &RegisterBlock) -> Result<(), Error>;
+}
+
+/// Chip select on the CS signal of the SPI peripheral, held for the whole transaction.
+///
+/// The CS signal must be multiplexed to a pad connected to the slave. A bus has only
+/// one such signal, so at most one device uses it; the pad also pulses during
+/// transactions of other devices, which ignore it as they are not selected.
+#[derive(Debug)]
+pub struct HardwareChipSelect;
+
+impl ChipSelect for HardwareChipSelect {
+    #[inline]
+    fn select(&mut self, spi: &RegisterBlock) -> Result<(), Error> {
+        unsafe { spi.config.modify(|config| config.enable_master()) };
+        Ok(())
+    }
+    #[inline]
+    fn deselect(&mut self, spi: &RegisterBlock) -> Result<(), Error> {
+        unsafe { spi.config.modify(|config| config.disable_master()) };
         Ok(())
     }
 }
 
+/// Chip select on an arbitrary output pin, driven low while the slave is selected.
+#[derive(Debug)]
+pub struct GpioChipSelect<P>(pub P);
+
+impl<P: embedded_hal::digital::OutputPin> ChipSelect for GpioChipSelect<P> {
+    #[inline]
+    fn select(&mut self, _spi: &RegisterBlock) -> Result<(), Error> {
+        self.0.set_low().map_err(|_| Error::ChipSelect)
+    }
+    #[inline]
+    fn deselect(&mut self, _spi: &RegisterBlock) -> Result<(), Error> {
+        self.0.set_high().map_err(|_| Error::ChipSelect)
+    }
+}
+
+/// SPI slave device on a bus shared with other devices.
+///
+/// Each device owns its chip select and borrows the bus for the duration of a
+/// transaction, so several drivers (e.g. flash, display and sensor) can use one SPI
+/// peripheral. The bus is shared through a [`RefCell`] and must not be used from
+/// interrupt handlers while a transaction is running.
+///
+/// # Example
+///
+/// ```ignore
+/// let bus = RefCell::new(Spi::new(p.spi1, pads, MODE_0, &p.glb));
+/// let mut flash = Device::new(&bus, HardwareChipSelect)?;
+/// let mut display = Device::new(&bus, GpioChipSelect(display_cs.into_pull_up_output()))?;
+/// ```
+pub struct Device<'a, BUS, CS> {
+    bus: &'a RefCell<BUS>,
+    cs: CS,
+}
+
+impl<'a, SPI: Deref<Target = RegisterBlock>, PADS, const I: usize, CS: ChipSelect>
+    Device<'a, Spi<SPI, PADS, I>, CS>
+{
+    /// Create a device on the shared bus, releasing its chip select.
+    #[inline]
+    pub fn new(bus: &'a RefCell<Spi<SPI, PADS, I>>, mut cs: CS) -> Result<Self, Error> {
+        cs.deselect(&bus.borrow().spi)?;
+        Ok(Self { bus, cs })
+    }
+
+    /// Release the device and return its chip select.
+    #[inline]
+    pub fn free(self) -> CS {
+        self.cs
+    }
+}
+
+impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize, CS: ChipSelect>
+    embedded_hal::spi::ErrorType for Device<'_, Spi<SPI, PADS, I>, CS>
+{
+    type Error = Error;
+}
+
+impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize, CS: ChipSelect>
+    embedded_hal::spi::SpiDevice for Device<'_, Spi<SPI, PADS, I>, CS>
+{
+    #[inline]
+    fn transaction(
+        &mut self,
+        operations: &mut [embedded_hal::spi::Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        let bus = &mut *self.bus.borrow_mut();
+        self.cs.select(&bus.spi)?;
+        let result = run_operations(bus, operations);
+        let deselect = self.cs.deselect(&bus.spi);
+        result.and(deselect)
+    }
+}
+
 // This part of implementation using `embedded_hal_027` is designed for backward compatibility of
 // ecosystem crates, as some of them depends on embedded-hal v0.2.7 traits.
 // We encourage ecosystem developers to use embedded-hal v1.0.0 traits; after that, this part of code
@@ -973,9 +1354,12 @@ impl<'a> HasCsSignal for Alternate<'a, 44, gpio::Spi<1>> {}
 #[cfg(test)]
 mod tests {
     use super::{
-        BusBusy, Config, FifoConfig0, FifoConfig1, FrameSize, Interrupt, InterruptConfig,
-        PeriodInterval, PeriodSignal, Phase, Polarity, ReceiveIgnore, RegisterBlock, SlaveTimeout,
+        BusBusy, ChipSelect, Config, DmaPeripheral, FifoConfig0, FifoConfig1, FrameSize,
+        GpioChipSelect, HardwareChipSelect, Interrupt, InterruptConfig, PeriodInterval,
+        PeriodSignal, Phase, Polarity, ReceiveIgnore, RegisterBlock, SlaveTimeout,
+        frame_size_matches,
     };
+    use crate::dma::{DmaAddr, Periph4Dma01, Periph4Dma2, PeripheralId};
     use core::mem::offset_of;
 
     #[test]
@@ -1261,4 +1645,61 @@ mod tests {
         assert_eq!(config.0, 0x1f000000);
         assert_eq!(config.receive_threshold(), 0x1f);
     }
+
+    #[test]
+    fn function_frame_size_matches() {
+        assert!(frame_size_matches::<u8>(FrameSize::Eight));
+        assert!(frame_size_matches::<u16>(FrameSize::Sixteen));
+        assert!(frame_size_matches::<u32>(FrameSize::TwentyFour));
+        assert!(frame_size_matches::<u32>(FrameSize::ThirtyTwo));
+        assert!(!frame_size_matches::<u8>(FrameSize::Sixteen));
+        assert!(!frame_size_matches::<u16>(FrameSize::ThirtyTwo));
+        assert!(!frame_size_matches::<u32>(FrameSize::Eight));
+    }
+
+    #[test]
+    fn trait_dma_peripheral() {
+        assert_eq!(<Periph4Dma01 as DmaPeripheral<0>>::TRANSMIT.id(), 11);
+        assert_eq!(<Periph4Dma01 as DmaPeripheral<0>>::RECEIVE.id(), 10);
+        assert_eq!(<Periph4Dma2 as DmaPeripheral<1>>::TRANSMIT.id(), 3);
+        assert_eq!(<Periph4Dma2 as DmaPeripheral<1>>::RECEIVE.id(), 2);
+        assert_eq!(
+            <Periph4Dma2 as DmaPeripheral<1>>::TRANSMIT_ADDR,
+            DmaAddr::Spi1Tx
+        );
+        assert_eq!(
+            <Periph4Dma01 as DmaPeripheral<0>>::RECEIVE_ADDR as u32,
+            0x2000A28C
+        );
+    }
+
+    #[test]
+    fn struct_chip_select_functions() {
+        let spi: RegisterBlock = unsafe { core::mem::zeroed() };
+        let mut cs = HardwareChipSelect;
+        cs.select(&spi).unwrap();
+        assert!(spi.config.read().is_master_enabled());
+        cs.deselect(&spi).unwrap();
+        assert!(!spi.config.read().is_master_enabled());
+
+        struct Pin(bool);
+        impl embedded_hal::digital::ErrorType for Pin {
+            type Error = core::convert::Infallible;
+        }
+        impl embedded_hal::digital::OutputPin for Pin {
+            fn set_low(&mut self) -> Result<(), Self::Error> {
+                self.0 = false;
+                Ok(())
+            }
+            fn set_high(&mut self) -> Result<(), Self::Error> {
+                self.0 = true;
+                Ok(())
+            }
+        }
+        let mut cs = GpioChipSelect(Pin(true));
+        cs.select(&spi).unwrap();
+        assert!(!cs.0.0);
+        cs.deselect(&spi).unwrap();
+        assert!(cs.0.0);
+    }
 }
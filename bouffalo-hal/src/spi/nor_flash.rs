@@ -0,0 +1,457 @@
+use crate::flash::{
+    CMD_PAGE_PROGRAM, CMD_READ_DATA, CMD_READ_JEDEC_ID, CMD_READ_STATUS, CMD_SECTOR_ERASE,
+    CMD_WRITE_ENABLE, PAGE_SIZE, SECTOR_SIZE, STATUS_BUSY,
+};
+use core::cell::RefCell;
+use embedded_hal::spi::{Operation, SpiDevice};
+use embedded_sdmmc::{Block, BlockCount, BlockDevice, BlockIdx};
+use embedded_storage::nor_flash::{self, NorFlash, NorFlashErrorKind, ReadNorFlash};
+
+// Largest capacity addressed by 3-byte addresses.
+const MAX_CAPACITY: usize = 1 << 24;
+
+/// External SPI NOR flash error.
+#[derive(Debug)]
+pub enum SpiFlashError<E> {
+    /// Error from SPI device.
+    Spi(E),
+    /// Address or length is not aligned to erase or program unit.
+    NotAligned,
+    /// Address range is outside of flash.
+    OutOfBounds,
+    /// JEDEC ID reports no capacity up to 16 MiB.
+    UnknownCapacity,
+}
+
+/// External NOR flash on a SPI device.
+///
+/// Unlike [`Flash`](crate::flash::Flash), the chip is not behind the flash controller, so
+/// it may be erased and programmed without stopping execute-in-place. Single line commands
+/// with 3-byte addresses are used, which all common chips up to 16 MiB support.
+///
+/// Implements `embedded-storage` NOR flash traits for file systems such as littlefs; wrap
+/// it in a [`NorBlockDevice`] to mount a FAT volume with `embedded-sdmmc`.
+///
+/// # Example
+///
+/// ```ignore
+/// let bus = RefCell::new(Spi::new(p.spi1, pads, MODE_0, &p.glb));
+/// let mut flash = SpiNorFlash::probe(Device::new(&bus, HardwareChipSelect)?)?;
+/// flash.erase(0, SECTOR_SIZE as u32)?;
+/// flash.write(0, b"boot count: 1")?;
+/// ```
+pub struct SpiNorFlash<D> {
+    device: D,
+    capacity: usize,
+}
+
+impl<D: SpiDevice> SpiNorFlash<D> {
+    /// Create flash over `device` with given capacity in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` exceeds 16 MiB.
+    #[inline]
+    pub fn new(device: D, capacity: usize) -> Self {
+        assert!(
+            capacity <= MAX_CAPACITY,
+            "capacity exceeds 3-byte address range"
+        );
+        Self { device, capacity }
+    }
+    /// Create flash over `device` with capacity from its JEDEC ID.
+    #[inline]
+    pub fn probe(device: D) -> Result<Self, SpiFlashError<D::Error>> {
+        let mut flash = Self {
+            device,
+            capacity: 0,
+        };
+        flash.capacity = capacity_from_jedec_id(flash.jedec_id()?)?;
+        Ok(flash)
+    }
+    /// Read manufacturer and device identifier.
+    #[inline]
+    pub fn jedec_id(&mut self) -> Result<[u8; 3], SpiFlashError<D::Error>> {
+        let mut id = [0u8; 3];
+        self.device
+            .transaction(&mut [
+                Operation::Write(&[CMD_READ_JEDEC_ID]),
+                Operation::Read(&mut id),
+            ])
+            .map_err(SpiFlashError::Spi)?;
+        Ok(id)
+    }
+    /// Read data from flash at `address`.
+    #[inline]
+    pub fn read(&mut self, address: u32, buf: &mut [u8]) -> Result<(), SpiFlashError<D::Error>> {
+        self.check_bounds(address, buf.len())?;
+        if buf.is_empty() {
+            return Ok(());
+        }
+        self.device
+            .transaction(&mut [
+                Operation::Write(&command(CMD_READ_DATA, address)),
+                Operation::Read(buf),
+            ])
+            .map_err(SpiFlashError::Spi)
+    }
+    /// Erase the 4-KiB sector at `address`.
+    #[inline]
+    pub fn erase_sector(&mut self, address: u32) -> Result<(), SpiFlashError<D::Error>> {
+        if !(address as usize).is_multiple_of(SECTOR_SIZE) {
+            return Err(SpiFlashError::NotAligned);
+        }
+        self.check_bounds(address, SECTOR_SIZE)?;
+        self.write_enable()?;
+        self.device
+            .write(&command(CMD_SECTOR_ERASE, address))
+            .map_err(SpiFlashError::Spi)?;
+        self.wait_ready()
+    }
+    /// Program data into flash; data must not cross a page boundary.
+    #[inline]
+    pub fn program_page(
+        &mut self,
+        address: u32,
+        data: &[u8],
+    ) -> Result<(), SpiFlashError<D::Error>> {
+        self.check_bounds(address, data.len())?;
+        if address as usize % PAGE_SIZE + data.len() > PAGE_SIZE {
+            return Err(SpiFlashError::NotAligned);
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+        self.write_enable()?;
+        self.device
+            .transaction(&mut [
+                Operation::Write(&command(CMD_PAGE_PROGRAM, address)),
+                Operation::Write(data),
+            ])
+            .map_err(SpiFlashError::Spi)?;
+        self.wait_ready()
+    }
+    /// Release flash and return its SPI device.
+    #[inline]
+    pub fn free(self) -> D {
+        self.device
+    }
+    #[inline]
+    fn write_enable(&mut self) -> Result<(), SpiFlashError<D::Error>> {
+        self.device
+            .write(&[CMD_WRITE_ENABLE])
+            .map_err(SpiFlashError::Spi)
+    }
+    #[inline]
+    fn wait_ready(&mut self) -> Result<(), SpiFlashError<D::Error>> {
+        loop {
+            let mut status = [0u8];
+            self.device
+                .transaction(&mut [
+                    Operation::Write(&[CMD_READ_STATUS]),
+                    Operation::Read(&mut status),
+                ])
+                .map_err(SpiFlashError::Spi)?;
+            if status[0] & STATUS_BUSY == 0 {
+                return Ok(());
+            }
+        }
+    }
+    #[inline]
+    fn check_bounds(&self, address: u32, len: usize) -> Result<(), SpiFlashError<D::Error>> {
+        match (address as usize).checked_add(len) {
+            Some(end) if end <= self.capacity => Ok(()),
+            _ => Err(SpiFlashError::OutOfBounds),
+        }
+    }
+}
+
+#[inline]
+fn command(opcode: u8, address: u32) -> [u8; 4] {
+    let [_, a2, a1, a0] = address.to_be_bytes();
+    [opcode, a2, a1, a0]
+}
+
+// Capacity code of a JEDEC ID is log2 of size in bytes.
+#[inline]
+fn capacity_from_jedec_id<E>(id: [u8; 3]) -> Result<usize, SpiFlashError<E>> {
+    match id[2] {
+        code @ 0x10..=0x18 => Ok(1 << code),
+        _ => Err(SpiFlashError::UnknownCapacity),
+    }
+}
+
+impl<E: core::fmt::Debug> nor_flash::NorFlashError for SpiFlashError<E> {
+    #[inline]
+    fn kind(&self) -> NorFlashErrorKind {
+        match self {
+            SpiFlashError::NotAligned => NorFlashErrorKind::NotAligned,
+            SpiFlashError::OutOfBounds => NorFlashErrorKind::OutOfBounds,
+            SpiFlashError::Spi(_) | SpiFlashError::UnknownCapacity => NorFlashErrorKind::Other,
+        }
+    }
+}
+
+impl<D: SpiDevice> nor_flash::ErrorType for SpiNorFlash<D> {
+    type Error = SpiFlashError<D::Error>;
+}
+
+impl<D: SpiDevice> ReadNorFlash for SpiNorFlash<D> {
+    const READ_SIZE: usize = 1;
+
+    #[inline]
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        SpiNorFlash::read(self, offset, bytes)
+    }
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl<D: SpiDevice> NorFlash for SpiNorFlash<D> {
+    const WRITE_SIZE: usize = 1;
+    const ERASE_SIZE: usize = SECTOR_SIZE;
+
+    #[inline]
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        if !(from as usize).is_multiple_of(SECTOR_SIZE)
+            || !(to as usize).is_multiple_of(SECTOR_SIZE)
+            || from > to
+        {
+            return Err(SpiFlashError::NotAligned);
+        }
+        for address in (from..to).step_by(SECTOR_SIZE) {
+            self.erase_sector(address)?;
+        }
+        Ok(())
+    }
+    #[inline]
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.check_bounds(offset, bytes.len())?;
+        let mut address = offset;
+        let mut bytes = bytes;
+        while !bytes.is_empty() {
+            let len = core::cmp::min(PAGE_SIZE - address as usize % PAGE_SIZE, bytes.len());
+            self.program_page(address, &bytes[..len])?;
+            address += len as u32;
+            bytes = &bytes[len..];
+        }
+        Ok(())
+    }
+}
+
+impl<D: SpiDevice> nor_flash::MultiwriteNorFlash for SpiNorFlash<D> {}
+
+/// Block device of 512-byte blocks over NOR flash, for FAT volumes of `embedded-sdmmc`.
+///
+/// Writing a block reads its erase sector into a buffer, erases the sector and programs
+/// it back, so partial sector writes survive; a power loss during a write loses the whole
+/// sector. Flash erase size must be a multiple of 512 bytes up to 4 KiB.
+pub struct NorBlockDevice<F> {
+    flash: RefCell<F>,
+    sector: RefCell<[u8; SECTOR_SIZE]>,
+}
+
+impl<F: NorFlash> NorBlockDevice<F> {
+    /// Create block device over `flash`.
+    #[inline]
+    pub const fn new(flash: F) -> Self {
+        const {
+            assert!(
+                F::ERASE_SIZE <= SECTOR_SIZE && F::ERASE_SIZE.is_multiple_of(Block::LEN),
+                "erase size must be a multiple of block size up to 4 KiB"
+            );
+            assert!(
+                Block::LEN.is_multiple_of(F::READ_SIZE) && Block::LEN.is_multiple_of(F::WRITE_SIZE),
+                "block size must be a multiple of read and write size"
+            )
+        };
+        Self {
+            flash: RefCell::new(flash),
+            sector: RefCell::new([0; SECTOR_SIZE]),
+        }
+    }
+    /// Release block device and return its flash.
+    #[inline]
+    pub fn free(self) -> F {
+        self.flash.into_inner()
+    }
+}
+
+impl<F: NorFlash> BlockDevice for NorBlockDevice<F> {
+    type Error = F::Error;
+
+    #[inline]
+    fn read(
+        &self,
+        blocks: &mut [Block],
+        start_block_idx: BlockIdx,
+        _reason: &str,
+    ) -> Result<(), Self::Error> {
+        let flash = &mut *self.flash.borrow_mut();
+        let mut address = start_block_idx.0 * Block::LEN_U32;
+        for block in blocks {
+            flash.read(address, &mut block.contents)?;
+            address += Block::LEN_U32;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn write(&self, blocks: &[Block], start_block_idx: BlockIdx) -> Result<(), Self::Error> {
+        let flash = &mut *self.flash.borrow_mut();
+        let sector = &mut self.sector.borrow_mut()[..F::ERASE_SIZE];
+        let per_sector = F::ERASE_SIZE / Block::LEN;
+        let mut index = start_block_idx.0 as usize;
+        let mut blocks = blocks;
+        while !blocks.is_empty() {
+            let first = index % per_sector;
+            let count = core::cmp::min(per_sector - first, blocks.len());
+            let base = ((index - first) * Block::LEN) as u32;
+            // Keep blocks of this sector which are not overwritten.
+            if count < per_sector {
+                flash.read(base, sector)?;
+            }
+            for (i, block) in blocks[..count].iter().enumerate() {
+                sector[(first + i) * Block::LEN..][..Block::LEN].copy_from_slice(&block.contents);
+            }
+            flash.erase(base, base + F::ERASE_SIZE as u32)?;
+            flash.write(base, sector)?;
+            index += count;
+            blocks = &blocks[count..];
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn num_blocks(&self) -> Result<BlockCount, Self::Error> {
+        Ok(BlockCount(
+            (self.flash.borrow().capacity() / Block::LEN) as u32,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::{NorBlockDevice, SpiFlashError, SpiNorFlash, capacity_from_jedec_id, command};
+    use embedded_hal::spi::{ErrorKind, ErrorType, Operation, SpiDevice};
+    use embedded_sdmmc::{Block, BlockDevice, BlockIdx};
+    use embedded_storage::nor_flash::{NorFlash, ReadNorFlash};
+    use std::{vec, vec::Vec};
+
+    // NOR flash chip of 64 KiB answering single line commands.
+    struct Chip {
+        memory: Vec<u8>,
+        write_enabled: bool,
+        erases: usize,
+    }
+
+    impl Chip {
+        fn new() -> Self {
+            Self {
+                memory: vec![0xff; 0x10000],
+                write_enabled: false,
+                erases: 0,
+            }
+        }
+    }
+
+    impl ErrorType for Chip {
+        type Error = ErrorKind;
+    }
+
+    impl SpiDevice for Chip {
+        fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), ErrorKind> {
+            let (header, data) = match operations {
+                [Operation::Write(header), data @ ..] => (*header, data),
+                _ => return Err(ErrorKind::Other),
+            };
+            let address = || u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+            match (header[0], data) {
+                (0x9f, [Operation::Read(id)]) => id.copy_from_slice(&[0xef, 0x40, 0x10]),
+                (0x05, [Operation::Read(status)]) => status[0] = 0,
+                (0x06, []) => self.write_enabled = true,
+                (0x03, [Operation::Read(buf)]) => {
+                    let address = address();
+                    buf.copy_from_slice(&self.memory[address..address + buf.len()]);
+                }
+                (0x20, []) if self.write_enabled => {
+                    let address = address();
+                    self.memory[address..address + 4096].fill(0xff);
+                    self.erases += 1;
+                    self.write_enabled = false;
+                }
+                (0x02, [Operation::Write(bytes)]) if self.write_enabled => {
+                    let address = address();
+                    assert!(address % 256 + bytes.len() <= 256, "page program wraps");
+                    for (cell, byte) in self.memory[address..].iter_mut().zip(bytes.iter()) {
+                        *cell &= byte;
+                    }
+                    self.write_enabled = false;
+                }
+                _ => return Err(ErrorKind::Other),
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn function_command() {
+        assert_eq!(command(0x03, 0x0012_3456), [0x03, 0x12, 0x34, 0x56]);
+    }
+
+    #[test]
+    fn function_capacity_from_jedec_id() {
+        assert_eq!(
+            capacity_from_jedec_id::<()>([0xef, 0x40, 0x18]).unwrap(),
+            16 * 1024 * 1024
+        );
+        assert!(matches!(
+            capacity_from_jedec_id::<()>([0xef, 0x40, 0x19]),
+            Err(SpiFlashError::UnknownCapacity)
+        ));
+    }
+
+    #[test]
+    fn struct_spi_nor_flash() {
+        let mut flash = SpiNorFlash::probe(Chip::new()).unwrap();
+        assert_eq!(flash.capacity(), 0x10000);
+        flash.erase(0x1000, 0x3000).unwrap();
+        let data: Vec<u8> = (0..300).map(|i| i as u8).collect();
+        NorFlash::write(&mut flash, 0x10f0, &data).unwrap();
+        let mut buf = [0u8; 300];
+        ReadNorFlash::read(&mut flash, 0x10f0, &mut buf).unwrap();
+        assert_eq!(buf[..], data[..]);
+        assert!(matches!(
+            flash.erase(0x1000, 0x1800),
+            Err(SpiFlashError::NotAligned)
+        ));
+        assert!(matches!(
+            ReadNorFlash::read(&mut flash, 0xffff, &mut buf),
+            Err(SpiFlashError::OutOfBounds)
+        ));
+        assert_eq!(flash.free().erases, 2);
+    }
+
+    #[test]
+    fn struct_nor_block_device() {
+        let device = NorBlockDevice::new(SpiNorFlash::new(Chip::new(), 0x10000));
+        assert_eq!(device.num_blocks().unwrap().0, 128);
+        let mut blocks = [Block::new(), Block::new(), Block::new()];
+        blocks[0].contents.fill(0x11);
+        blocks[1].contents.fill(0x22);
+        blocks[2].contents.fill(0x33);
+        // Blocks 7 to 9 span two sectors.
+        device.write(&blocks, BlockIdx(7)).unwrap();
+        device.write(&blocks[..1], BlockIdx(6)).unwrap();
+        let mut read = [Block::new(), Block::new(), Block::new(), Block::new()];
+        device.read(&mut read, BlockIdx(6), "test").unwrap();
+        assert!(read[0].contents.iter().all(|&b| b == 0x11));
+        assert!(read[1].contents.iter().all(|&b| b == 0x11));
+        assert!(read[2].contents.iter().all(|&b| b == 0x22));
+        assert!(read[3].contents.iter().all(|&b| b == 0x33));
+        assert_eq!(device.free().free().erases, 3);
+    }
+}
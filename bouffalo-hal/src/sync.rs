@@ -0,0 +1,209 @@
+//! Data shared between cores.
+//!
+//! BL808 cores do not keep their data caches coherent, and M0 (RV32) and D0 (RV64)
+//! disagree on the size of `usize`. A value written by one core is only seen by another
+//! after the writer cleans the cache lines and the reader invalidates them, with fences
+//! ordering these steps against surrounding memory accesses. [`SharedCell`] and
+//! [`SharedWord`] bundle these steps into explicit publish and consume operations.
+
+use crate::arch::xtheadcsr;
+use core::{
+    cell::UnsafeCell,
+    sync::atomic::{AtomicU32, Ordering, fence},
+};
+
+/// Plain data type that can be copied between cores byte by byte.
+///
+/// # Safety
+///
+/// The type must have no padding, no pointers or references, and accept any bit
+/// pattern. Its layout must be the same on 32-bit and 64-bit cores, which rules out
+/// `usize` and `isize`.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($t:ty),+) => {
+        $(unsafe impl Pod for $t {})+
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+/// Value shared between cores with explicit publish and consume semantics.
+///
+/// The cell occupies whole cache lines, so that maintenance on it never touches
+/// neighbouring data. The value is copied with plain accesses, which is why
+/// [`publish`](Self::publish) is unsafe; use [`SharedWord`] for a 32-bit value written
+/// from several contexts.
+#[repr(C, align(64))]
+pub struct SharedCell<T> {
+    value: UnsafeCell<T>,
+}
+
+unsafe impl<T: Pod> Sync for SharedCell<T> {}
+
+impl<T: Pod> SharedCell<T> {
+    /// Create a cell holding `val`.
+    #[inline]
+    pub const fn new(val: T) -> Self {
+        Self {
+            value: UnsafeCell::new(val),
+        }
+    }
+    /// Get the cell placed at a shared memory address.
+    ///
+    /// # Safety
+    ///
+    /// `address` must be aligned to 64 bytes, reserved for this cell on all cores,
+    /// and hold the same `T` on every side.
+    #[inline]
+    pub unsafe fn at(address: usize) -> &'static Self {
+        unsafe { &*(address as *const Self) }
+    }
+    /// Write `val` and make it visible to other cores.
+    ///
+    /// Earlier writes of this core are ordered before the value; shared data they
+    /// touch must already be cleaned from cache, or be placed in uncached memory.
+    ///
+    /// # Safety
+    ///
+    /// No other publish or consume of this cell may run at the same time, on any core
+    /// and in any context of this core, e.g. an interrupt handler. Pair the cell with a
+    /// [`SharedWord`] flag or an IPC doorbell to hand it over.
+    #[inline]
+    pub unsafe fn publish(&self, val: T) {
+        fence(Ordering::Release);
+        unsafe { self.value.get().write_volatile(val) };
+        clean(self);
+        fence(Ordering::SeqCst);
+    }
+    /// Read the latest value published by any core.
+    ///
+    /// Later reads of this core are ordered after the value; shared data they touch
+    /// must be invalidated from cache before reading, or be placed in uncached memory.
+    #[inline]
+    pub fn consume(&self) -> T {
+        invalidate(self);
+        let val = unsafe { self.value.get().read_volatile() };
+        fence(Ordering::Acquire);
+        val
+    }
+}
+
+impl<T: Pod + Default> Default for SharedCell<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// 32-bit word shared between cores, accessed atomically.
+///
+/// Unlike [`SharedCell`], the word is never observed half-written, so it may be
+/// published and consumed from any core and context, e.g. as a flag or a queue index.
+#[repr(C, align(64))]
+pub struct SharedWord {
+    value: AtomicU32,
+}
+
+impl SharedWord {
+    /// Create a word holding `val`.
+    #[inline]
+    pub const fn new(val: u32) -> Self {
+        Self {
+            value: AtomicU32::new(val),
+        }
+    }
+    /// Get the word placed at a shared memory address.
+    ///
+    /// # Safety
+    ///
+    /// `address` must be aligned to 64 bytes and reserved for this word on all cores.
+    #[inline]
+    pub unsafe fn at(address: usize) -> &'static Self {
+        unsafe { &*(address as *const Self) }
+    }
+    /// Write `val` and make it visible to other cores.
+    ///
+    /// Earlier writes of this core are ordered before the value; shared data they
+    /// touch must already be cleaned from cache, or be placed in uncached memory.
+    #[inline]
+    pub fn publish(&self, val: u32) {
+        self.value.store(val, Ordering::Release);
+        clean(self);
+        fence(Ordering::SeqCst);
+    }
+    /// Read the latest value published by any core.
+    ///
+    /// Later reads of this core are ordered after the value; shared data they touch
+    /// must be invalidated from cache before reading, or be placed in uncached memory.
+    ///
+    /// The cache line is written back before it is discarded, so a value stored by an
+    /// interrupted [`publish`](Self::publish) on this core is not lost.
+    #[inline]
+    pub fn consume(&self) -> u32 {
+        flush(self);
+        self.value.load(Ordering::Acquire)
+    }
+}
+
+impl Default for SharedWord {
+    #[inline]
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// Write back data cache lines covering `val` to memory.
+#[inline]
+pub(crate) fn clean<T>(val: &T) {
+    xtheadcsr::clean_dcache(val as *const T as usize, size_of::<T>());
+}
+
+/// Write back and discard data cache lines covering `val`.
+#[inline]
+pub(crate) fn flush<T>(val: &T) {
+    xtheadcsr::flush_dcache(val as *const T as usize, size_of::<T>());
+}
+
+/// Discard data cache lines covering `val` so next read comes from memory.
+#[inline]
+pub(crate) fn invalidate<T>(val: &T) {
+    xtheadcsr::invalidate_dcache(val as *const T as usize, size_of::<T>());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SharedCell, SharedWord};
+    use core::mem::{align_of, size_of};
+
+    #[test]
+    fn struct_shared_cell_layout() {
+        assert_eq!(align_of::<SharedCell<u8>>(), 64);
+        assert_eq!(size_of::<SharedCell<u32>>(), 64);
+        assert_eq!(size_of::<SharedCell<[u32; 20]>>(), 128);
+        assert_eq!(align_of::<SharedWord>(), 64);
+        assert_eq!(size_of::<SharedWord>(), 64);
+    }
+
+    #[test]
+    fn function_shared_cell_publish_consume() {
+        let cell = SharedCell::new(0u32);
+        assert_eq!(cell.consume(), 0);
+        unsafe { cell.publish(0x1234_5678) };
+        assert_eq!(cell.consume(), 0x1234_5678);
+        let array = SharedCell::<[u16; 3]>::default();
+        unsafe { array.publish([1, 2, 3]) };
+        assert_eq!(array.consume(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn function_shared_word_publish_consume() {
+        let word = SharedWord::default();
+        assert_eq!(word.consume(), 0);
+        word.publish(0x1234_5678);
+        assert_eq!(word.consume(), 0x1234_5678);
+    }
+}
@@ -1,7 +1,597 @@
 //! Timer and watchdog peripheral.
+//!
+//! Two 32-bit counters count ticks of a selectable and divided clock. Besides match
+//! interrupts, the peripheral latches counter 0 on edges of a GPIO pad routed to it,
+//! which measures pulse widths and periods of external signals for tachometers or
+//! radio control receivers. Capture completion raises the `timer_ch0` interrupt when
+//! enabled. Measuring a high and a low pulse in turn gives period and duty cycle of
+//! an external PWM signal.
+
+use crate::{clocks::Clocks, gpio::Input};
+use core::ops::Deref;
+use embedded_time::rate::Hertz;
+use volatile_register::{RO, RW, WO};
 
 /// Timer and watchdog peripheral registers.
 #[repr(C)]
 pub struct RegisterBlock {
-    // todo fields
+    /// Counter clock source register.
+    pub clock_config: RW<ClockConfig>,
+    _reserved0: [u8; 0xc],
+    /// Match values of each counter.
+    pub match_value: [[RW<u32>; 3]; 2],
+    _reserved1: [u8; 0x4],
+    /// Current value of each counter.
+    pub counter_value: [RO<u32>; 2],
+    _reserved2: [u8; 0x4],
+    /// Match state of each counter.
+    pub match_state: [RO<u32>; 2],
+    _reserved3: [u8; 0x4],
+    /// Match interrupt enable of each counter.
+    pub match_interrupt_enable: [RW<u32>; 2],
+    _reserved4: [u8; 0x4],
+    /// Preload value of each counter.
+    pub preload_value: [RW<u32>; 2],
+    _reserved5: [u8; 0x4],
+    /// Preload trigger of each counter.
+    pub preload_control: [RW<u32>; 2],
+    _reserved6: [u8; 0x14],
+    /// Match interrupt clear of each counter.
+    pub match_interrupt_clear: [WO<u32>; 2],
+    _reserved7: [u8; 0x4],
+    /// Counter enable register.
+    pub counter_enable: RW<CounterEnable>,
+    /// Counter mode register.
+    pub counter_mode: RW<CounterMode>,
+    _reserved8: [u8; 0x30],
+    /// Counter clock division register.
+    pub clock_division: RW<ClockDivision>,
+    /// GPIO capture configuration register.
+    pub capture_config: RW<CaptureConfig>,
+    /// Counter 0 value latched on first capture edge.
+    pub capture_first: RO<u32>,
+    /// Counter 0 value latched on second capture edge.
+    pub capture_second: RO<u32>,
+}
+
+/// Counter clock source register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct ClockConfig(u32);
+
+impl ClockConfig {
+    const SOURCE_SHIFT: [u32; 2] = [2, 5];
+
+    /// Set clock source of counter `idx`.
+    #[inline]
+    pub const fn set_clock_source(self, idx: usize, val: ClockSource) -> Self {
+        let shift = Self::SOURCE_SHIFT[idx];
+        Self((self.0 & !(0x3 << shift)) | ((val as u32) << shift))
+    }
+    /// Get clock source of counter `idx`.
+    #[inline]
+    pub const fn clock_source(self, idx: usize) -> ClockSource {
+        ClockSource::from_bits(((self.0 >> Self::SOURCE_SHIFT[idx]) & 0x3) as u8)
+    }
+}
+
+/// Counter clock source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ClockSource {
+    /// Fast clock of the bus.
+    Fclk = 0,
+    /// 32-kHz clock.
+    F32k = 1,
+    /// 1-kHz clock.
+    Khz1 = 2,
+    /// Crystal oscillator clock.
+    Xclk = 3,
+}
+
+impl ClockSource {
+    #[inline]
+    const fn from_bits(bits: u8) -> Self {
+        match bits {
+            0 => ClockSource::Fclk,
+            1 => ClockSource::F32k,
+            2 => ClockSource::Khz1,
+            _ => ClockSource::Xclk,
+        }
+    }
+    /// Frequency of this clock source, `None` if it is not known from `clocks`.
+    #[inline]
+    pub const fn frequency(self, clocks: &Clocks) -> Option<Hertz> {
+        match self {
+            ClockSource::Fclk => None,
+            ClockSource::F32k => Some(Hertz(32_768)),
+            ClockSource::Khz1 => Some(Hertz(1_000)),
+            ClockSource::Xclk => Some(clocks.xclk()),
+        }
+    }
+}
+
+/// Counter enable register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct CounterEnable(u32);
+
+impl CounterEnable {
+    /// Enable counter `idx`.
+    #[inline]
+    pub const fn enable_counter(self, idx: usize) -> Self {
+        Self(self.0 | (1 << (idx + 1)))
+    }
+    /// Disable counter `idx`.
+    #[inline]
+    pub const fn disable_counter(self, idx: usize) -> Self {
+        Self(self.0 & !(1 << (idx + 1)))
+    }
+    /// Check if counter `idx` is enabled.
+    #[inline]
+    pub const fn is_counter_enabled(self, idx: usize) -> bool {
+        self.0 & (1 << (idx + 1)) != 0
+    }
+}
+
+/// Counter mode register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct CounterMode(u32);
+
+impl CounterMode {
+    /// Let counter `idx` run freely and wrap around.
+    #[inline]
+    pub const fn enable_free_run(self, idx: usize) -> Self {
+        Self(self.0 | (1 << (idx + 1)))
+    }
+    /// Restart counter `idx` from its preload value on preload trigger.
+    #[inline]
+    pub const fn disable_free_run(self, idx: usize) -> Self {
+        Self(self.0 & !(1 << (idx + 1)))
+    }
+    /// Check if counter `idx` runs freely.
+    #[inline]
+    pub const fn is_free_run_enabled(self, idx: usize) -> bool {
+        self.0 & (1 << (idx + 1)) != 0
+    }
+}
+
+/// Counter clock division register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct ClockDivision(u32);
+
+impl ClockDivision {
+    /// Set clock divider of counter `idx`, clock is divided by `val + 1`.
+    #[inline]
+    pub const fn set_divider(self, idx: usize, val: u8) -> Self {
+        let shift = 8 * (idx as u32 + 1);
+        Self((self.0 & !(0xff << shift)) | ((val as u32) << shift))
+    }
+    /// Get clock divider of counter `idx`.
+    #[inline]
+    pub const fn divider(self, idx: usize) -> u8 {
+        (self.0 >> (8 * (idx as u32 + 1))) as u8
+    }
+}
+
+/// GPIO capture configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct CaptureConfig(u32);
+
+impl CaptureConfig {
+    const ENABLE: u32 = 1 << 1;
+    const INTERRUPT: u32 = 1 << 2;
+    const INVERT: u32 = 1 << 5;
+    const PAD: u32 = 0x3f << 8;
+    const DONE: u32 = 1 << 31;
+
+    /// Enable capture, restarting it from the first edge.
+    #[inline]
+    pub const fn enable_capture(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable capture.
+    #[inline]
+    pub const fn disable_capture(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if capture is enabled.
+    #[inline]
+    pub const fn is_capture_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Enable interrupt on capture completion.
+    #[inline]
+    pub const fn enable_interrupt(self) -> Self {
+        Self(self.0 | Self::INTERRUPT)
+    }
+    /// Disable interrupt on capture completion.
+    #[inline]
+    pub const fn disable_interrupt(self) -> Self {
+        Self(self.0 & !Self::INTERRUPT)
+    }
+    /// Check if interrupt on capture completion is enabled.
+    #[inline]
+    pub const fn is_interrupt_enabled(self) -> bool {
+        self.0 & Self::INTERRUPT != 0
+    }
+    /// Set pulse level to be measured.
+    #[inline]
+    pub const fn set_level(self, val: Level) -> Self {
+        match val {
+            Level::High => Self(self.0 & !Self::INVERT),
+            Level::Low => Self(self.0 | Self::INVERT),
+        }
+    }
+    /// Get pulse level to be measured.
+    #[inline]
+    pub const fn level(self) -> Level {
+        if self.0 & Self::INVERT != 0 {
+            Level::Low
+        } else {
+            Level::High
+        }
+    }
+    /// Set number of GPIO pad routed to capture.
+    #[inline]
+    pub const fn set_pad(self, val: u8) -> Self {
+        Self((self.0 & !Self::PAD) | (((val as u32) << 8) & Self::PAD))
+    }
+    /// Get number of GPIO pad routed to capture.
+    #[inline]
+    pub const fn pad(self) -> u8 {
+        ((self.0 & Self::PAD) >> 8) as u8
+    }
+    /// Check if both edges are latched.
+    #[inline]
+    pub const fn is_done(self) -> bool {
+        self.0 & Self::DONE != 0
+    }
+}
+
+/// Level of pulses measured by capture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Level {
+    /// High pulses, from rising edge to falling edge.
+    High,
+    /// Low pulses, from falling edge to rising edge.
+    Low,
+}
+
+/// Period and duty cycle of a measured signal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Measurement {
+    high: u32,
+    low: u32,
+    tick: Hertz,
+}
+
+impl Measurement {
+    /// Get high pulse width in ticks.
+    #[inline]
+    pub const fn high_ticks(&self) -> u32 {
+        self.high
+    }
+    /// Get period in ticks, `None` if it overflows counter range.
+    #[inline]
+    pub const fn period_ticks(&self) -> Option<u32> {
+        self.high.checked_add(self.low)
+    }
+    /// Get signal frequency, `None` if period overflows counter range or is zero.
+    #[inline]
+    pub const fn frequency(&self) -> Option<Hertz> {
+        match self.period_ticks() {
+            Some(period) if period != 0 => Some(Hertz(self.tick.0 / period)),
+            _ => None,
+        }
+    }
+    /// Get duty cycle scaled to `max`, as used by `SetDutyCycle` of embedded-hal.
+    #[inline]
+    pub const fn duty_cycle(&self, max: u16) -> u16 {
+        let period = self.high as u64 + self.low as u64;
+        if period == 0 {
+            return 0;
+        }
+        (self.high as u64 * max as u64 / period) as u16
+    }
+}
+
+/// Capture counter configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Config {
+    /// Counter clock source.
+    pub source: ClockSource,
+    /// Counter clock is divided by `divider + 1`.
+    pub divider: u8,
+}
+
+impl Default for Config {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            source: ClockSource::Xclk,
+            divider: 39,
+        }
+    }
+}
+
+impl Config {
+    /// Set counter clock source and divider.
+    #[inline]
+    pub const fn set_clock(mut self, source: ClockSource, divider: u8) -> Self {
+        self.source = source;
+        self.divider = divider;
+        self
+    }
+}
+
+/// Errors on capture configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConfigError {
+    /// Frequency of clock source is unknown.
+    ClockSource,
+}
+
+/// Managed input capture on counter 0 with routed GPIO pad.
+pub struct Capture<TIMER, PAD> {
+    timer: TIMER,
+    pad: PAD,
+    tick: Hertz,
+}
+
+impl<'a, TIMER: Deref<Target = RegisterBlock>, const N: usize, M> Capture<TIMER, Input<'a, N, M>> {
+    /// Create input capture on `pad`, measuring high pulses.
+    #[inline]
+    pub fn new(
+        timer: TIMER,
+        pad: Input<'a, N, M>,
+        config: Config,
+        clocks: &Clocks,
+    ) -> Result<Self, ConfigError> {
+        let Some(source) = config.source.frequency(clocks) else {
+            return Err(ConfigError::ClockSource);
+        };
+        let tick = Hertz(source.0 / (config.divider as u32 + 1));
+        unsafe {
+            timer.counter_enable.modify(|v| v.disable_counter(0));
+            timer
+                .clock_config
+                .modify(|v| v.set_clock_source(0, config.source));
+            timer
+                .clock_division
+                .modify(|v| v.set_divider(0, config.divider));
+            timer.counter_mode.modify(|v| v.enable_free_run(0));
+            timer
+                .capture_config
+                .write(CaptureConfig(0).set_pad(N as u8).enable_capture());
+            timer.counter_enable.modify(|v| v.enable_counter(0));
+        }
+        Ok(Self { timer, pad, tick })
+    }
+}
+
+impl<TIMER: Deref<Target = RegisterBlock>, PAD> Capture<TIMER, PAD> {
+    /// Get counter tick frequency.
+    #[inline]
+    pub const fn tick(&self) -> Hertz {
+        self.tick
+    }
+    /// Select pulse level to measure and restart capture.
+    #[inline]
+    pub fn set_level(&mut self, level: Level) {
+        unsafe {
+            self.timer
+                .capture_config
+                .modify(|v| v.disable_capture().set_level(level));
+            self.timer.capture_config.modify(|v| v.enable_capture());
+        }
+    }
+    /// Restart capture from the first edge.
+    #[inline]
+    pub fn restart(&mut self) {
+        unsafe {
+            self.timer.capture_config.modify(|v| v.disable_capture());
+            self.timer.capture_config.modify(|v| v.enable_capture());
+        }
+    }
+    /// Get width of latest pulse in ticks and restart capture, if captured.
+    #[inline]
+    pub fn try_pulse_width(&mut self) -> Option<u32> {
+        if !self.timer.capture_config.read().is_done() {
+            return None;
+        }
+        let first = self.timer.capture_first.read();
+        let second = self.timer.capture_second.read();
+        self.restart();
+        Some(second.wrapping_sub(first))
+    }
+    /// Wait for a pulse and get its width in ticks.
+    #[inline]
+    pub fn pulse_width(&mut self) -> u32 {
+        loop {
+            if let Some(ticks) = self.try_pulse_width() {
+                return ticks;
+            }
+            core::hint::spin_loop();
+        }
+    }
+    /// Measure period and duty cycle from one high and one low pulse.
+    ///
+    /// Waits for both pulses; pulse level is restored afterwards.
+    #[inline]
+    pub fn measure(&mut self) -> Measurement {
+        let level = self.timer.capture_config.read().level();
+        self.set_level(Level::High);
+        let high = self.pulse_width();
+        self.set_level(Level::Low);
+        let low = self.pulse_width();
+        self.set_level(level);
+        Measurement {
+            high,
+            low,
+            tick: self.tick,
+        }
+    }
+    /// Measure signal frequency from one high and one low pulse.
+    ///
+    /// Returns `None` if no full period fits in counter range.
+    #[inline]
+    pub fn frequency(&mut self) -> Option<Hertz> {
+        self.measure().frequency()
+    }
+    /// Convert ticks to microseconds.
+    #[inline]
+    pub const fn ticks_to_micros(&self, ticks: u32) -> u64 {
+        ticks as u64 * 1_000_000 / self.tick.0 as u64
+    }
+    /// Enable interrupt on capture completion.
+    ///
+    /// The interrupt is raised on the `timer_ch0` interrupt line; handlers read the
+    /// width with [`try_pulse_width`](Self::try_pulse_width), which also clears it.
+    #[inline]
+    pub fn enable_interrupt(&mut self) {
+        unsafe { self.timer.capture_config.modify(|v| v.enable_interrupt()) };
+    }
+    /// Disable interrupt on capture completion.
+    #[inline]
+    pub fn disable_interrupt(&mut self) {
+        unsafe { self.timer.capture_config.modify(|v| v.disable_interrupt()) };
+    }
+    /// Check if capture is complete.
+    #[inline]
+    pub fn has_interrupt(&self) -> bool {
+        self.timer.capture_config.read().is_done()
+    }
+    /// Stop capture and counter, and release the peripheral and pad.
+    #[inline]
+    pub fn free(self) -> (TIMER, PAD) {
+        unsafe {
+            self.timer.capture_config.write(CaptureConfig(0));
+            self.timer.counter_enable.modify(|v| v.disable_counter(0));
+        }
+        (self.timer, self.pad)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CaptureConfig, ClockConfig, ClockDivision, ClockSource, CounterEnable, CounterMode, Level,
+        Measurement, RegisterBlock,
+    };
+    use core::mem::offset_of;
+    use embedded_time::rate::Hertz;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, clock_config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, match_value), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, counter_value), 0x2c);
+        assert_eq!(offset_of!(RegisterBlock, match_state), 0x38);
+        assert_eq!(offset_of!(RegisterBlock, match_interrupt_enable), 0x44);
+        assert_eq!(offset_of!(RegisterBlock, preload_value), 0x50);
+        assert_eq!(offset_of!(RegisterBlock, preload_control), 0x5c);
+        assert_eq!(offset_of!(RegisterBlock, match_interrupt_clear), 0x78);
+        assert_eq!(offset_of!(RegisterBlock, counter_enable), 0x84);
+        assert_eq!(offset_of!(RegisterBlock, counter_mode), 0x88);
+        assert_eq!(offset_of!(RegisterBlock, clock_division), 0xbc);
+        assert_eq!(offset_of!(RegisterBlock, capture_config), 0xc0);
+        assert_eq!(offset_of!(RegisterBlock, capture_first), 0xc4);
+        assert_eq!(offset_of!(RegisterBlock, capture_second), 0xc8);
+    }
+
+    #[test]
+    fn struct_clock_config_functions() {
+        let mut val = ClockConfig(0x0);
+        val = val.set_clock_source(0, ClockSource::Xclk);
+        assert_eq!(val.0, 0x0000_000c);
+        assert_eq!(val.clock_source(0), ClockSource::Xclk);
+        val = val.set_clock_source(1, ClockSource::F32k);
+        assert_eq!(val.0, 0x0000_002c);
+        assert_eq!(val.clock_source(1), ClockSource::F32k);
+        val = val.set_clock_source(0, ClockSource::Fclk);
+        assert_eq!(val.0, 0x0000_0020);
+    }
+
+    #[test]
+    fn struct_counter_functions() {
+        let mut val = CounterEnable(0x0);
+        val = val.enable_counter(1);
+        assert_eq!(val.0, 0x0000_0004);
+        assert!(val.is_counter_enabled(1));
+        assert!(!val.is_counter_enabled(0));
+        val = val.disable_counter(1);
+        assert_eq!(val.0, 0x0000_0000);
+
+        let mut val = CounterMode(0x0);
+        val = val.enable_free_run(0);
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_free_run_enabled(0));
+        val = val.disable_free_run(0);
+        assert!(!val.is_free_run_enabled(0));
+
+        let mut val = ClockDivision(0x0);
+        val = val.set_divider(0, 39);
+        assert_eq!(val.0, 0x0000_2700);
+        assert_eq!(val.divider(0), 39);
+        val = val.set_divider(1, 0xff);
+        assert_eq!(val.0, 0x00ff_2700);
+        assert_eq!(val.divider(1), 0xff);
+    }
+
+    #[test]
+    fn struct_capture_config_functions() {
+        let mut val = CaptureConfig(0x0);
+
+        val = val.enable_capture();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_capture_enabled());
+        val = val.disable_capture();
+        assert!(!val.is_capture_enabled());
+
+        val = val.enable_interrupt();
+        assert_eq!(val.0, 0x0000_0004);
+        assert!(val.is_interrupt_enabled());
+        val = val.disable_interrupt();
+        assert!(!val.is_interrupt_enabled());
+
+        val = val.set_level(Level::Low);
+        assert_eq!(val.0, 0x0000_0020);
+        assert_eq!(val.level(), Level::Low);
+        val = val.set_level(Level::High);
+        assert_eq!(val.level(), Level::High);
+
+        val = val.set_pad(45);
+        assert_eq!(val.0, 0x0000_2d00);
+        assert_eq!(val.pad(), 45);
+
+        assert!(CaptureConfig(0x8000_0000).is_done());
+        assert!(!val.is_done());
+    }
+
+    #[test]
+    fn struct_measurement_functions() {
+        let val = Measurement {
+            high: 250,
+            low: 750,
+            tick: Hertz(1_000_000),
+        };
+        assert_eq!(val.high_ticks(), 250);
+        assert_eq!(val.period_ticks(), Some(1000));
+        assert_eq!(val.frequency(), Some(Hertz(1000)));
+        assert_eq!(val.duty_cycle(100), 25);
+        assert_eq!(val.duty_cycle(u16::MAX), 16383);
+
+        let val = Measurement {
+            high: u32::MAX,
+            low: 1,
+            tick: Hertz(1_000_000),
+        };
+        assert_eq!(val.period_ticks(), None);
+        assert_eq!(val.frequency(), None);
+        assert_eq!(val.duty_cycle(100), 99);
+    }
 }
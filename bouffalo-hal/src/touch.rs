@@ -0,0 +1,436 @@
+//! Capacitive touch sensing peripheral.
+//!
+//! The touch controller charges and discharges each enabled channel pad and counts
+//! cycles of its sensing oscillator; a finger on the pad adds capacitance and raises
+//! the count. Counts above a channel threshold set its interrupt flag. Channel pads
+//! are selected by their analog function in GLB before the controller is started.
+//!
+//! In low-power scan mode the controller runs from the 32-kHz clock in the always-on
+//! domain, sleeps between scans and raises a hibernate interrupt on touch, so a device
+//! in HBN mode wakes up when a channel is touched.
+
+use crate::hbn;
+use core::ops::Deref;
+use volatile_register::{RO, RW, WO};
+
+/// Number of touch channels.
+pub const CHANNELS: usize = 12;
+
+/// Touch sensing registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Controller configuration register.
+    pub config: RW<TouchConfig>,
+    /// Channel enable register.
+    pub channel_enable: RW<u32>,
+    /// Low-power scan configuration register.
+    pub scan: RW<ScanConfig>,
+    _reserved0: [u8; 0x4],
+    /// Channel threshold registers.
+    pub threshold: [RW<u32>; CHANNELS],
+    /// Channel count registers, updated after each scan.
+    pub count: [RO<u32>; CHANNELS],
+    /// Interrupt state register, one bit per channel.
+    pub interrupt_state: RO<u32>,
+    /// Interrupt enable register, one bit per channel.
+    pub interrupt_enable: RW<u32>,
+    /// Interrupt clear register, one bit per channel.
+    pub interrupt_clear: WO<u32>,
+}
+
+/// Controller configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct TouchConfig(u32);
+
+impl TouchConfig {
+    const ENABLE: u32 = 1 << 0;
+    const CONTINUOUS: u32 = 1 << 1;
+    const LOW_POWER: u32 = 1 << 2;
+    const CURRENT: u32 = 0x7 << 4;
+    const CLOCK_DIVIDER: u32 = 0x7 << 8;
+    const CYCLES: u32 = 0xff << 16;
+
+    /// Enable touch controller.
+    #[inline]
+    pub const fn enable_touch(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable touch controller.
+    #[inline]
+    pub const fn disable_touch(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if touch controller is enabled.
+    #[inline]
+    pub const fn is_touch_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Scan channels continuously.
+    #[inline]
+    pub const fn enable_continuous(self) -> Self {
+        Self(self.0 | Self::CONTINUOUS)
+    }
+    /// Scan channels once.
+    #[inline]
+    pub const fn disable_continuous(self) -> Self {
+        Self(self.0 & !Self::CONTINUOUS)
+    }
+    /// Check if channels are scanned continuously.
+    #[inline]
+    pub const fn is_continuous_enabled(self) -> bool {
+        self.0 & Self::CONTINUOUS != 0
+    }
+    /// Enable low-power scan from 32-kHz clock.
+    #[inline]
+    pub const fn enable_low_power(self) -> Self {
+        Self(self.0 | Self::LOW_POWER)
+    }
+    /// Disable low-power scan.
+    #[inline]
+    pub const fn disable_low_power(self) -> Self {
+        Self(self.0 & !Self::LOW_POWER)
+    }
+    /// Check if low-power scan is enabled.
+    #[inline]
+    pub const fn is_low_power_enabled(self) -> bool {
+        self.0 & Self::LOW_POWER != 0
+    }
+    /// Set charge current.
+    #[inline]
+    pub const fn set_current(self, val: Current) -> Self {
+        Self((self.0 & !Self::CURRENT) | ((val as u32) << 4))
+    }
+    /// Get charge current.
+    #[inline]
+    pub const fn current(self) -> Current {
+        Current::from_bits(((self.0 & Self::CURRENT) >> 4) as u8)
+    }
+    /// Set sensing clock divider, the clock is divided by `2^val`.
+    #[inline]
+    pub const fn set_clock_divider(self, val: u8) -> Self {
+        Self((self.0 & !Self::CLOCK_DIVIDER) | (((val as u32) << 8) & Self::CLOCK_DIVIDER))
+    }
+    /// Get sensing clock divider.
+    #[inline]
+    pub const fn clock_divider(self) -> u8 {
+        ((self.0 & Self::CLOCK_DIVIDER) >> 8) as u8
+    }
+    /// Set charge and discharge cycles measured per channel.
+    #[inline]
+    pub const fn set_cycles(self, val: u8) -> Self {
+        Self((self.0 & !Self::CYCLES) | ((val as u32) << 16))
+    }
+    /// Get charge and discharge cycles measured per channel.
+    #[inline]
+    pub const fn cycles(self) -> u8 {
+        ((self.0 & Self::CYCLES) >> 16) as u8
+    }
+}
+
+/// Charge current of touch channels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Current {
+    /// 0.5 µA.
+    Ua0p5 = 0,
+    /// 1 µA.
+    Ua1 = 1,
+    /// 2 µA.
+    Ua2 = 2,
+    /// 4 µA.
+    Ua4 = 3,
+    /// 8 µA.
+    Ua8 = 4,
+    /// 16 µA.
+    Ua16 = 5,
+}
+
+impl Current {
+    #[inline]
+    const fn from_bits(bits: u8) -> Self {
+        match bits {
+            0 => Current::Ua0p5,
+            1 => Current::Ua1,
+            2 => Current::Ua2,
+            3 => Current::Ua4,
+            4 => Current::Ua8,
+            _ => Current::Ua16,
+        }
+    }
+}
+
+/// Low-power scan configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct ScanConfig(u32);
+
+impl ScanConfig {
+    const INTERVAL: u32 = 0x7f_ffff;
+
+    /// Set 32-kHz clock cycles slept between two scans.
+    #[inline]
+    pub const fn set_interval(self, val: u32) -> Self {
+        Self((self.0 & !Self::INTERVAL) | (val & Self::INTERVAL))
+    }
+    /// Get 32-kHz clock cycles slept between two scans.
+    #[inline]
+    pub const fn interval(self) -> u32 {
+        self.0 & Self::INTERVAL
+    }
+}
+
+/// Touch controller configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Config {
+    /// Charge current.
+    pub current: Current,
+    /// Sensing clock divider as power of two.
+    pub clock_divider: u8,
+    /// Charge and discharge cycles measured per channel.
+    pub cycles: u8,
+}
+
+impl Default for Config {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            current: Current::Ua4,
+            clock_divider: 2,
+            cycles: 32,
+        }
+    }
+}
+
+impl Config {
+    /// Set charge current.
+    #[inline]
+    pub const fn set_current(mut self, current: Current) -> Self {
+        self.current = current;
+        self
+    }
+    /// Set charge and discharge cycles measured per channel.
+    ///
+    /// More cycles give finer counts at the cost of longer scans.
+    #[inline]
+    pub const fn set_cycles(mut self, cycles: u8) -> Self {
+        self.cycles = cycles;
+        self
+    }
+}
+
+/// Managed touch controller with a set of enabled channels.
+pub struct Touch<TOUCH> {
+    touch: TOUCH,
+    baseline: [u16; CHANNELS],
+}
+
+impl<TOUCH: Deref<Target = RegisterBlock>> Touch<TOUCH> {
+    /// Create touch controller and scan channels of `mask` continuously.
+    ///
+    /// Bit `n` of `mask` enables channel `n`.
+    #[inline]
+    pub fn new(touch: TOUCH, mask: u16, config: Config) -> Self {
+        let val = TouchConfig(0)
+            .set_current(config.current)
+            .set_clock_divider(config.clock_divider)
+            .set_cycles(config.cycles)
+            .enable_continuous();
+        unsafe {
+            touch.config.write(val);
+            touch.channel_enable.write(mask as u32 & CHANNEL_MASK);
+            touch.interrupt_clear.write(CHANNEL_MASK);
+            touch.config.modify(|v| v.enable_touch());
+        }
+        Self {
+            touch,
+            baseline: [0; CHANNELS],
+        }
+    }
+    /// Read latest count of a channel.
+    #[inline]
+    pub fn count(&self, channel: usize) -> u16 {
+        self.touch.count[channel].read() as u16
+    }
+    /// Measure untouched counts of enabled channels as their baseline.
+    ///
+    /// Averages `samples` readings per channel, waiting `delay` between readings so
+    /// that the controller finishes a scan. Pads must not be touched meanwhile.
+    #[inline]
+    pub fn calibrate(&mut self, samples: u16, mut delay: impl FnMut()) -> [u16; CHANNELS] {
+        let mask = self.touch.channel_enable.read();
+        let mut sum = [0u32; CHANNELS];
+        for _ in 0..samples {
+            delay();
+            for (channel, sum) in sum.iter_mut().enumerate() {
+                if mask & (1 << channel) != 0 {
+                    *sum += self.count(channel) as u32;
+                }
+            }
+        }
+        for (baseline, sum) in self.baseline.iter_mut().zip(sum) {
+            *baseline = sum.checked_div(samples as u32).unwrap_or(0) as u16;
+        }
+        self.baseline
+    }
+    /// Get calibrated baseline of a channel.
+    #[inline]
+    pub fn baseline(&self, channel: usize) -> u16 {
+        self.baseline[channel]
+    }
+    /// Detect touch when count of a channel rises `delta` above its baseline.
+    #[inline]
+    pub fn set_threshold(&mut self, channel: usize, delta: u16) {
+        let val = self.baseline[channel].saturating_add(delta);
+        unsafe { self.touch.threshold[channel].write(val as u32) };
+    }
+    /// Check if a channel is touched in latest scan.
+    #[inline]
+    pub fn is_touched(&self, channel: usize) -> bool {
+        self.touch.interrupt_state.read() & (1 << channel) != 0
+    }
+    /// Enable threshold interrupt of a channel.
+    ///
+    /// Touch interrupts are raised on the `hbn_out0` interrupt line.
+    #[inline]
+    pub fn enable_interrupt(&mut self, channel: usize) {
+        unsafe { self.touch.interrupt_enable.modify(|v| v | (1 << channel)) };
+    }
+    /// Disable threshold interrupt of a channel.
+    #[inline]
+    pub fn disable_interrupt(&mut self, channel: usize) {
+        unsafe { self.touch.interrupt_enable.modify(|v| v & !(1 << channel)) };
+    }
+    /// Clear threshold interrupt flag of a channel.
+    #[inline]
+    pub fn clear_interrupt(&mut self, channel: usize) {
+        unsafe { self.touch.interrupt_clear.write(1 << channel) };
+    }
+    /// Switch to low-power scan which wakes the chip from HBN mode on touch.
+    ///
+    /// Channels with threshold interrupt enabled are wake sources. The controller
+    /// sleeps `interval` cycles of 32-kHz clock between scans.
+    #[inline]
+    pub fn into_low_power(self, interval: u32, hbn: &hbn::RegisterBlock) -> LowPowerScan<TOUCH> {
+        unsafe {
+            self.touch.config.modify(|v| v.disable_touch());
+            self.touch.scan.write(ScanConfig(0).set_interval(interval));
+            self.touch
+                .config
+                .modify(|v| v.enable_low_power().enable_touch());
+            hbn.interrupt_mode.modify(|v| v.enable_touch_wakeup());
+        }
+        LowPowerScan { inner: self }
+    }
+    /// Stop touch controller and release its peripheral.
+    #[inline]
+    pub fn free(self) -> TOUCH {
+        unsafe {
+            self.touch.config.modify(|v| v.disable_touch());
+            self.touch.interrupt_enable.write(0);
+        }
+        self.touch
+    }
+}
+
+/// Touch controller in low-power scan mode.
+pub struct LowPowerScan<TOUCH> {
+    inner: Touch<TOUCH>,
+}
+
+impl<TOUCH: Deref<Target = RegisterBlock>> LowPowerScan<TOUCH> {
+    /// Check if touch woke the chip or raised the hibernate interrupt.
+    #[inline]
+    pub fn has_wakeup(&self, hbn: &hbn::RegisterBlock) -> bool {
+        hbn.interrupt_state.read() & HBN_TOUCH != 0
+    }
+    /// Clear touch hibernate interrupt.
+    #[inline]
+    pub fn clear_wakeup(&mut self, hbn: &hbn::RegisterBlock) {
+        unsafe { hbn.interrupt_clear.write(HBN_TOUCH) };
+    }
+    /// Return to continuous scan, keeping calibration and thresholds.
+    #[inline]
+    pub fn into_active(self, hbn: &hbn::RegisterBlock) -> Touch<TOUCH> {
+        let touch = &self.inner.touch;
+        unsafe {
+            hbn.interrupt_mode.modify(|v| v.disable_touch_wakeup());
+            touch.config.modify(|v| v.disable_touch());
+            touch
+                .config
+                .modify(|v| v.disable_low_power().enable_touch());
+        }
+        self.inner
+    }
+}
+
+// Bits of all channels in channel enable and interrupt registers.
+const CHANNEL_MASK: u32 = (1 << CHANNELS) - 1;
+
+// Touch bit in hibernate interrupt state and clear registers.
+const HBN_TOUCH: u32 = 1 << 24;
+
+#[cfg(test)]
+mod tests {
+    use super::{Current, RegisterBlock, ScanConfig, TouchConfig};
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, channel_enable), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, scan), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, threshold), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, count), 0x40);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_state), 0x70);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_enable), 0x74);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_clear), 0x78);
+    }
+
+    #[test]
+    fn struct_touch_config_functions() {
+        let mut val = TouchConfig(0x0);
+
+        val = val.enable_touch();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_touch_enabled());
+        val = val.disable_touch();
+        assert!(!val.is_touch_enabled());
+
+        val = val.enable_continuous();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_continuous_enabled());
+        val = val.disable_continuous();
+        assert!(!val.is_continuous_enabled());
+
+        val = val.enable_low_power();
+        assert_eq!(val.0, 0x0000_0004);
+        assert!(val.is_low_power_enabled());
+        val = val.disable_low_power();
+        assert!(!val.is_low_power_enabled());
+
+        val = val.set_current(Current::Ua16);
+        assert_eq!(val.0, 0x0000_0050);
+        assert_eq!(val.current(), Current::Ua16);
+        val = val.set_current(Current::Ua0p5);
+
+        val = val.set_clock_divider(7);
+        assert_eq!(val.0, 0x0000_0700);
+        assert_eq!(val.clock_divider(), 7);
+        val = val.set_clock_divider(0);
+
+        val = val.set_cycles(0xff);
+        assert_eq!(val.0, 0x00ff_0000);
+        assert_eq!(val.cycles(), 0xff);
+    }
+
+    #[test]
+    fn struct_scan_config_functions() {
+        let mut val = ScanConfig(0x0);
+        val = val.set_interval(0x7f_ffff);
+        assert_eq!(val.0, 0x007f_ffff);
+        assert_eq!(val.interval(), 0x7f_ffff);
+        val = val.set_interval(0xffff_ffff);
+        assert_eq!(val.0, 0x007f_ffff);
+    }
+}
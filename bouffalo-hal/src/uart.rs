@@ -16,6 +16,16 @@ mod blocking;
 pub use blocking::*;
 mod asynch;
 pub use asynch::*;
+mod rs485;
+pub use rs485::*;
+mod dmx512;
+pub use dmx512::*;
+mod sbus;
+pub use sbus::*;
+mod line;
+pub use line::*;
+mod bridge;
+pub use bridge::*;
 
 /// Extend constructor to owned UART register blocks.
 pub trait UartExt<PADS>: Sized {
@@ -1,5 +1,6 @@
 use super::{
     Config, ConfigError, Error, Interrupt, InterruptClear, Pads, RegisterBlock, uart_config,
+    uart_fifo_config,
 };
 use crate::clocks::Clocks;
 use core::{
@@ -35,11 +36,15 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> AsyncSerial<UART, PADS> {
         // Calculate transmit interval and register values from configuration.
         let (bit_period, data_config, transmit_config, receive_config) =
             uart_config::<I, PADS>(config, &clocks)?;
+        let (receive_timeout, fifo_config) = uart_fifo_config(config)?;
 
         // Write bit period.
         unsafe { uart.bit_period.write(bit_period) };
         // Write the bit-order.
         unsafe { uart.data_config.write(data_config) };
+        // Write receive timeout and FIFO thresholds.
+        unsafe { uart.receive_timeout.write(receive_timeout) };
+        unsafe { uart.fifo_config_1.write(fifo_config) };
         // Configure transmit feature with freerun.
         unsafe { uart.transmit_config.write(transmit_config.enable_freerun()) };
         // Configure receive feature.
@@ -52,6 +57,39 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> AsyncSerial<UART, PADS> {
         Ok(AsyncSerial { uart, pads, state })
     }
 
+    /// Set idle time in bit periods before receive timeout interrupt is raised.
+    #[inline]
+    pub fn set_receive_timeout(&mut self, bits: u8) {
+        unsafe {
+            self.uart
+                .receive_timeout
+                .modify(|val| val.set_timeout(bits))
+        };
+    }
+
+    /// Wait until receive line has been idle for the configured receive timeout.
+    ///
+    /// Useful for packetized protocols to detect the gap between frames.
+    #[inline]
+    pub async fn wait_receive_timeout(&mut self) {
+        unsafe {
+            self.uart
+                .interrupt_enable
+                .modify(|val| val.enable_interrupt(Interrupt::ReceiveTimeout))
+        };
+        WaitForInterrupt::new(
+            &self.uart,
+            Interrupt::ReceiveTimeout,
+            &self.state.receive_timeout,
+        )
+        .await;
+        unsafe {
+            self.uart
+                .interrupt_clear
+                .write(InterruptClear::default().clear_interrupt(Interrupt::ReceiveTimeout))
+        };
+    }
+
     /// Release serial instance and return its peripheral and pads.
     #[inline]
     pub fn free(self) -> (UART, PADS) {
@@ -64,6 +102,7 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> AsyncSerial<UART, PADS> {
 pub struct SerialState {
     transmit_ready: atomic_waker::AtomicWaker,
     receive_ready: atomic_waker::AtomicWaker,
+    receive_timeout: atomic_waker::AtomicWaker,
     ref_to_serial: AtomicUsize,
 }
 
@@ -74,6 +113,7 @@ impl SerialState {
         SerialState {
             transmit_ready: atomic_waker::AtomicWaker::new(),
             receive_ready: atomic_waker::AtomicWaker::new(),
+            receive_timeout: atomic_waker::AtomicWaker::new(),
             ref_to_serial: AtomicUsize::new(0),
         }
     }
@@ -95,6 +135,15 @@ impl SerialState {
                 };
             }
         }
+        // Receive timeout flag is kept for the waiting future to observe and clear;
+        // disable the interrupt so it does not fire again in the meantime.
+        if state.has_interrupt(Interrupt::ReceiveTimeout) {
+            unsafe {
+                uart.interrupt_enable
+                    .modify(|val| val.disable_interrupt(Interrupt::ReceiveTimeout))
+            };
+            self.receive_timeout.wake();
+        }
     }
 }
 
@@ -1,11 +1,19 @@
-use super::{Config, ConfigError, Error, Pads, RegisterBlock, uart_config};
+use super::{
+    Config, ConfigError, Error, Interrupt, InterruptClear, MultiDropWord, Pads, Parity,
+    RegisterBlock, SelfTestError, StopBits, TimeoutError, WordLength,
+    assert_baudrate_within_tolerance, uart_config, uart_fifo_config,
+};
 use crate::clocks::Clocks;
+use crate::dma::{UntypedChannel, flush_dcache, invalidate_dcache};
 use core::ops::Deref;
+use embedded_time::{
+    Clock, Instant, duration::Duration, fixed_point::FixedPoint, rate::Extensions,
+};
 
 /// Managed blocking serial peripheral.
 pub struct BlockingSerial<UART, PADS> {
-    uart: UART,
-    pads: PADS,
+    pub(crate) uart: UART,
+    pub(crate) pads: PADS,
 }
 
 impl<UART: Deref<Target = RegisterBlock>, PADS> BlockingSerial<UART, PADS> {
@@ -23,11 +31,15 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> BlockingSerial<UART, PADS> {
         // Calculate transmit interval and register values from configuration.
         let (bit_period, data_config, transmit_config, receive_config) =
             uart_config::<I, PADS>(config, &clocks)?;
+        let (receive_timeout, fifo_config) = uart_fifo_config(config)?;
 
         // Write bit period.
         unsafe { uart.bit_period.write(bit_period) };
         // Write the bit-order.
         unsafe { uart.data_config.write(data_config) };
+        // Write receive timeout and FIFO thresholds.
+        unsafe { uart.receive_timeout.write(receive_timeout) };
+        unsafe { uart.fifo_config_1.write(fifo_config) };
 
         // Configure freerun transmit feature.
         let mut val = transmit_config;
@@ -39,6 +51,30 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> BlockingSerial<UART, PADS> {
         Ok(Self { uart, pads })
     }
 
+    /// Creates a polling serial instance with baudrate checked at compile time.
+    ///
+    /// Compilation fails if `BAUDRATE` cannot be achieved within 2% error on a
+    /// `UART_CLOCK` Hz clock; the clock is then verified against `clocks` at runtime.
+    #[inline]
+    pub fn freerun_checked<const I: usize, const UART_CLOCK: u32, const BAUDRATE: u32>(
+        uart: UART,
+        config: Config,
+        pads: PADS,
+        clocks: &Clocks,
+    ) -> Result<Self, ConfigError>
+    where
+        PADS: Pads<I>,
+    {
+        const { assert_baudrate_within_tolerance(UART_CLOCK, BAUDRATE) };
+        match clocks.uart_clock::<I>() {
+            Some(freq) if freq.0 == UART_CLOCK => {}
+            Some(_) => return Err(ConfigError::ClockMismatch),
+            None => return Err(ConfigError::ClockSource),
+        }
+        let config = config.set_baudrate(BAUDRATE.Bd());
+        Self::freerun(uart, config, pads, clocks)
+    }
+
     /// Enable transmit DMA.
     #[inline]
     pub fn enable_tx_dma(self) -> Self {
@@ -67,6 +103,280 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> BlockingSerial<UART, PADS> {
         self
     }
 
+    /// Set idle time in bit periods before receive timeout interrupt is raised.
+    #[inline]
+    pub fn set_receive_timeout(&mut self, bits: u8) {
+        unsafe {
+            self.uart
+                .receive_timeout
+                .modify(|val| val.set_timeout(bits))
+        };
+    }
+
+    /// Set transmit and receive FIFO thresholds in bytes.
+    #[inline]
+    pub fn set_fifo_thresholds(&mut self, transmit: u8, receive: u8) -> Result<(), ConfigError> {
+        if transmit > 31 {
+            return Err(ConfigError::TransmitThresholdTooHigh);
+        }
+        if receive > 31 {
+            return Err(ConfigError::ReceiveThresholdTooHigh);
+        }
+        unsafe {
+            self.uart.fifo_config_1.modify(|val| {
+                val.set_transmit_threshold(transmit)
+                    .set_receive_threshold(receive)
+            })
+        };
+        Ok(())
+    }
+
+    /// Check if receive line has been idle for the configured receive timeout.
+    #[inline]
+    pub fn is_receive_timeout(&self) -> bool {
+        self.uart
+            .interrupt_state
+            .read()
+            .has_interrupt(Interrupt::ReceiveTimeout)
+    }
+
+    /// Clear receive timeout flag.
+    #[inline]
+    pub fn clear_receive_timeout(&mut self) {
+        unsafe {
+            self.uart
+                .interrupt_clear
+                .write(InterruptClear::default().clear_interrupt(Interrupt::ReceiveTimeout))
+        };
+    }
+
+    /// Receive into `buf` over DMA until receive line goes idle or buffer is full.
+    ///
+    /// Returns however many bytes arrived, which suits frames of unknown length such
+    /// as Modbus or NMEA sentences. Receive DMA must be enabled with [`enable_rx_dma`],
+    /// and `channel` configured for byte wide peripheral-to-memory transfers on this
+    /// UART's receive request. The line is idle after the receive timeout set by
+    /// [`set_receive_timeout`]; bytes below DMA threshold left in receive FIFO are
+    /// copied by the processor. At most 4095 bytes are received per call.
+    ///
+    /// [`enable_rx_dma`]: Self::enable_rx_dma
+    /// [`set_receive_timeout`]: Self::set_receive_timeout
+    #[inline]
+    pub fn read_until_idle(
+        &mut self,
+        channel: &UntypedChannel,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        uart_read_until_idle(&self.uart, channel, buf)
+    }
+
+    /// Send a break condition lasting at least `bits` bit periods.
+    ///
+    /// Transmit signal is held low by software while dummy frames are shifted out to time the
+    /// break, thus the duration is rounded up to whole frames.
+    #[inline]
+    pub fn send_break(&mut self, bits: u16) {
+        let frames = bits.div_ceil(uart_frame_bits(&self.uart));
+        let _ = uart_flush(&self.uart);
+        unsafe {
+            self.uart
+                .software_mode
+                .modify(|val| val.set_txd_value(false).enable_txd_software())
+        };
+        for _ in 0..frames {
+            while uart_write_nb(&self.uart, 0x00).is_err() {
+                core::hint::spin_loop();
+            }
+        }
+        let _ = uart_flush(&self.uart);
+        uart_wait_transmit_idle(&self.uart);
+        unsafe {
+            self.uart
+                .software_mode
+                .modify(|val| val.disable_txd_software())
+        };
+    }
+
+    /// Enable break detection on receive line.
+    ///
+    /// A break is reported through the LIN sync error flag, see [`is_break_detected`].
+    ///
+    /// [`is_break_detected`]: Self::is_break_detected
+    #[inline]
+    pub fn enable_break_detect(&mut self) {
+        unsafe {
+            self.uart
+                .receive_config
+                .modify(|val| val.enable_lin_receive())
+        };
+    }
+
+    /// Disable break detection on receive line.
+    #[inline]
+    pub fn disable_break_detect(&mut self) {
+        unsafe {
+            self.uart
+                .receive_config
+                .modify(|val| val.disable_lin_receive())
+        };
+    }
+
+    /// Check if a break has been detected on receive line.
+    #[inline]
+    pub fn is_break_detected(&self) -> bool {
+        self.uart
+            .interrupt_state
+            .read()
+            .has_interrupt(Interrupt::ReceiveSyncError)
+    }
+
+    /// Clear break detected flag.
+    #[inline]
+    pub fn clear_break_detected(&mut self) {
+        unsafe {
+            self.uart
+                .interrupt_clear
+                .write(InterruptClear::default().clear_interrupt(Interrupt::ReceiveSyncError))
+        };
+    }
+
+    /// Enable multi-drop mode for nine-bit addressing on RS-485 buses.
+    ///
+    /// The UART has no address matching logic; the ninth bit is carried in the parity bit,
+    /// which is switched between even and odd for each word. Both halves are set to 8-bit words.
+    #[inline]
+    pub fn enable_multidrop(&mut self) {
+        unsafe {
+            self.uart.receive_config.modify(|val| {
+                val.set_word_length(WordLength::Eight)
+                    .set_parity(Parity::Even)
+            });
+            self.uart.transmit_config.modify(|val| {
+                val.set_word_length(WordLength::Eight)
+                    .set_parity(Parity::Even)
+            });
+            self.uart
+                .interrupt_clear
+                .write(InterruptClear::default().clear_interrupt(Interrupt::ReceiveParityError));
+        }
+    }
+
+    /// Write a nine-bit word in multi-drop mode.
+    ///
+    /// Blocks until the previous word has left the transmitter if the parity has to be switched.
+    #[inline]
+    pub fn write_multidrop(&mut self, word: MultiDropWord) -> Result<(), Error> {
+        let (byte, ninth) = match word {
+            MultiDropWord::Address(byte) => (byte, true),
+            MultiDropWord::Data(byte) => (byte, false),
+        };
+        // Even parity sets parity bit when the byte has odd number of ones.
+        let parity = if (byte.count_ones() % 2 == 1) == ninth {
+            Parity::Even
+        } else {
+            Parity::Odd
+        };
+        if self.uart.transmit_config.read().parity() != parity {
+            uart_flush(&self.uart)?;
+            uart_wait_transmit_idle(&self.uart);
+            unsafe {
+                self.uart
+                    .transmit_config
+                    .modify(|val| val.set_parity(parity))
+            };
+        }
+        nb::block!(uart_write_nb(&self.uart, byte))
+    }
+
+    /// Read a nine-bit word in multi-drop mode.
+    ///
+    /// The ninth bit is recovered from the parity error flag, so words must be read
+    /// one by one as they arrive; use a receive FIFO threshold of 0.
+    #[inline]
+    pub fn read_multidrop(&mut self) -> nb::Result<MultiDropWord, Error> {
+        let byte = uart_read_nb(&self.uart)?;
+        let parity_error = self
+            .uart
+            .interrupt_state
+            .read()
+            .has_interrupt(Interrupt::ReceiveParityError);
+        if parity_error {
+            unsafe {
+                self.uart
+                    .interrupt_clear
+                    .write(InterruptClear::default().clear_interrupt(Interrupt::ReceiveParityError))
+            };
+        }
+        // Receiver checks for even parity; a mismatch means the ninth bit differs from it.
+        let ninth = (byte.count_ones() % 2 == 1) != parity_error;
+        Ok(match ninth {
+            true => MultiDropWord::Address(byte),
+            false => MultiDropWord::Data(byte),
+        })
+    }
+
+    /// Write all bytes in buffer, or fail if `timeout` elapses on `clock` before it's done.
+    #[inline]
+    pub fn write_all_timeout<C: Clock, D: Duration + FixedPoint>(
+        &mut self,
+        buf: &[u8],
+        clock: &C,
+        timeout: D,
+    ) -> Result<(), TimeoutError>
+    where
+        C::T: TryFrom<D::T>,
+    {
+        uart_write_all_timeout(&self.uart, buf, clock, timeout)
+    }
+
+    /// Fill the whole buffer, or fail if `timeout` elapses on `clock` before it's done.
+    #[inline]
+    pub fn read_exact_timeout<C: Clock, D: Duration + FixedPoint>(
+        &mut self,
+        buf: &mut [u8],
+        clock: &C,
+        timeout: D,
+    ) -> Result<(), TimeoutError>
+    where
+        C::T: TryFrom<D::T>,
+    {
+        uart_read_exact_timeout(&self.uart, buf, clock, timeout)
+    }
+
+    /// Transmit a test pattern and verify it byte by byte through the receive FIFO.
+    ///
+    /// This UART has no internal loopback path; transmit and receive pads must be connected
+    /// on the board, e.g. by a test fixture. Each byte must return within `timeout`.
+    #[inline]
+    pub fn self_test<C: Clock, D: Duration + FixedPoint + Copy>(
+        &mut self,
+        pattern: &[u8],
+        clock: &C,
+        timeout: D,
+    ) -> Result<(), SelfTestError>
+    where
+        C::T: TryFrom<D::T>,
+    {
+        unsafe {
+            self.uart
+                .fifo_config_0
+                .modify(|val| val.clear_receive_fifo())
+        };
+        for (index, &expected) in pattern.iter().enumerate() {
+            let mut received = [0u8];
+            uart_write_all_timeout(&self.uart, &[expected], clock, timeout)?;
+            uart_read_exact_timeout(&self.uart, &mut received, clock, timeout)?;
+            if received[0] != expected {
+                return Err(SelfTestError::Mismatch {
+                    index,
+                    expected,
+                    received: received[0],
+                });
+            }
+        }
+        Ok(())
+    }
+
     /// Release serial instance and return its peripheral and pads.
     #[inline]
     pub fn free(self) -> (UART, PADS) {
@@ -95,8 +405,62 @@ pub struct BlockingReceiveHalf<UART, PADS> {
     pub(crate) _pads: PADS,
 }
 
+impl<UART: Deref<Target = RegisterBlock>, PADS> BlockingTransmitHalf<UART, PADS> {
+    /// Write all bytes in buffer, or fail if `timeout` elapses on `clock` before it's done.
+    #[inline]
+    pub fn write_all_timeout<C: Clock, D: Duration + FixedPoint>(
+        &mut self,
+        buf: &[u8],
+        clock: &C,
+        timeout: D,
+    ) -> Result<(), TimeoutError>
+    where
+        C::T: TryFrom<D::T>,
+    {
+        uart_write_all_timeout(&self.uart, buf, clock, timeout)
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> BlockingReceiveHalf<UART, PADS> {
+    /// Fill the whole buffer, or fail if `timeout` elapses on `clock` before it's done.
+    #[inline]
+    pub fn read_exact_timeout<C: Clock, D: Duration + FixedPoint>(
+        &mut self,
+        buf: &mut [u8],
+        clock: &C,
+        timeout: D,
+    ) -> Result<(), TimeoutError>
+    where
+        C::T: TryFrom<D::T>,
+    {
+        uart_read_exact_timeout(&self.uart, buf, clock, timeout)
+    }
+}
+
 #[inline]
-fn uart_write(uart: &RegisterBlock, buf: &[u8]) -> Result<usize, Error> {
+fn uart_frame_bits(uart: &RegisterBlock) -> u16 {
+    let config = uart.transmit_config.read();
+    let word = match config.word_length() {
+        WordLength::Five => 5,
+        WordLength::Six => 6,
+        WordLength::Seven => 7,
+        WordLength::Eight => 8,
+    };
+    let parity = match config.parity() {
+        Parity::None => 0,
+        Parity::Even | Parity::Odd => 1,
+    };
+    // Fractional stop bits are rounded down, so that frames never overestimate elapsed time.
+    let stop = match config.stop_bits() {
+        StopBits::ZeroPointFive => 0,
+        StopBits::One | StopBits::OnePointFive => 1,
+        StopBits::Two => 2,
+    };
+    1 + word + parity + stop
+}
+
+#[inline]
+pub(crate) fn uart_write(uart: &RegisterBlock, buf: &[u8]) -> Result<usize, Error> {
     while uart.fifo_config_1.read().transmit_available_bytes() == 0 {
         core::hint::spin_loop();
     }
@@ -111,7 +475,7 @@ fn uart_write(uart: &RegisterBlock, buf: &[u8]) -> Result<usize, Error> {
 }
 
 #[inline]
-fn uart_write_nb(uart: &RegisterBlock, word: u8) -> nb::Result<(), Error> {
+pub(crate) fn uart_write_nb(uart: &RegisterBlock, word: u8) -> nb::Result<(), Error> {
     if uart.fifo_config_1.read().transmit_available_bytes() == 0 {
         return Err(nb::Error::WouldBlock);
     }
@@ -120,7 +484,7 @@ fn uart_write_nb(uart: &RegisterBlock, word: u8) -> nb::Result<(), Error> {
 }
 
 #[inline]
-fn uart_flush(uart: &RegisterBlock) -> Result<(), Error> {
+pub(crate) fn uart_flush(uart: &RegisterBlock) -> Result<(), Error> {
     // There are maximum 32 bytes in transmit FIFO queue, wait until all bytes are available,
     // meaning that all data in queue has been sent into UART bus.
     while uart.fifo_config_1.read().transmit_available_bytes() != 32 {
@@ -130,7 +494,7 @@ fn uart_flush(uart: &RegisterBlock) -> Result<(), Error> {
 }
 
 #[inline]
-fn uart_flush_nb(uart: &RegisterBlock) -> nb::Result<(), Error> {
+pub(crate) fn uart_flush_nb(uart: &RegisterBlock) -> nb::Result<(), Error> {
     if uart.fifo_config_1.read().transmit_available_bytes() != 32 {
         return Err(nb::Error::WouldBlock);
     }
@@ -138,7 +502,202 @@ fn uart_flush_nb(uart: &RegisterBlock) -> nb::Result<(), Error> {
 }
 
 #[inline]
-fn uart_read(uart: &RegisterBlock, buf: &mut [u8]) -> Result<usize, Error> {
+pub(crate) fn uart_wait_transmit_idle(uart: &RegisterBlock) {
+    while uart.bus_state.read().transmit_busy() {
+        core::hint::spin_loop();
+    }
+}
+
+#[inline]
+fn uart_deadline<C: Clock, D: Duration + FixedPoint>(
+    clock: &C,
+    timeout: D,
+) -> Result<Instant<C>, TimeoutError>
+where
+    C::T: TryFrom<D::T>,
+{
+    let now = clock.try_now().map_err(|_| TimeoutError::Clock)?;
+    now.checked_add(timeout).ok_or(TimeoutError::Clock)
+}
+
+#[inline]
+fn uart_write_all_timeout<C: Clock, D: Duration + FixedPoint>(
+    uart: &RegisterBlock,
+    buf: &[u8],
+    clock: &C,
+    timeout: D,
+) -> Result<(), TimeoutError>
+where
+    C::T: TryFrom<D::T>,
+{
+    let deadline = uart_deadline(clock, timeout)?;
+    for (written, &word) in buf.iter().enumerate() {
+        loop {
+            match uart_write_nb(uart, word) {
+                Ok(()) => break,
+                Err(nb::Error::Other(e)) => return Err(e.into()),
+                Err(nb::Error::WouldBlock) => {}
+            }
+            if clock.try_now().map_err(|_| TimeoutError::Clock)? >= deadline {
+                return Err(TimeoutError::Timeout(written));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[inline]
+fn uart_read_exact_timeout<C: Clock, D: Duration + FixedPoint>(
+    uart: &RegisterBlock,
+    buf: &mut [u8],
+    clock: &C,
+    timeout: D,
+) -> Result<(), TimeoutError>
+where
+    C::T: TryFrom<D::T>,
+{
+    let deadline = uart_deadline(clock, timeout)?;
+    for (read, slot) in buf.iter_mut().enumerate() {
+        loop {
+            match uart_read_nb(uart) {
+                Ok(word) => {
+                    *slot = word;
+                    break;
+                }
+                Err(nb::Error::Other(e)) => return Err(e.into()),
+                Err(nb::Error::WouldBlock) => {}
+            }
+            if clock.try_now().map_err(|_| TimeoutError::Clock)? >= deadline {
+                return Err(TimeoutError::Timeout(read));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Largest transfer size of a DMA channel without linked list items.
+pub(crate) const DMA_MAX_TRANSFER: usize = 4095;
+
+#[inline]
+fn uart_read_until_idle(
+    uart: &RegisterBlock,
+    channel: &UntypedChannel,
+    buf: &mut [u8],
+) -> Result<usize, Error> {
+    let len = uart_start_receive_dma(uart, channel, buf);
+    if len == 0 {
+        return Ok(0);
+    }
+    let idle = loop {
+        if !channel.is_busy() {
+            break false;
+        }
+        if uart_receive_idle(uart) {
+            break true;
+        }
+        core::hint::spin_loop();
+    };
+    uart_finish_receive_dma(uart, channel, &mut buf[..len], idle)
+}
+
+// Start receive DMA into `buf`, returns number of bytes the transfer is set up for.
+#[inline]
+pub(crate) fn uart_start_receive_dma(
+    uart: &RegisterBlock,
+    channel: &UntypedChannel,
+    buf: &mut [u8],
+) -> usize {
+    let len = core::cmp::min(buf.len(), DMA_MAX_TRANSFER);
+    if len == 0 {
+        return 0;
+    }
+    let registers = &channel.dma.channels[channel.channel_id];
+    // No dirty line of the buffer may be written back over incoming data.
+    flush_dcache(buf.as_ptr() as usize, len);
+    unsafe {
+        uart.interrupt_clear
+            .write(InterruptClear::default().clear_interrupt(Interrupt::ReceiveTimeout));
+        registers
+            .source_address
+            .write(&uart.fifo_read as *const _ as u32);
+        registers.destination_address.write(buf.as_mut_ptr() as u32);
+        registers.linked_list_item.write(0);
+        registers
+            .control
+            .modify(|val| val.set_transfer_size(len as u16));
+    }
+    channel.start();
+    len
+}
+
+#[inline]
+pub(crate) fn uart_receive_idle(uart: &RegisterBlock) -> bool {
+    uart.interrupt_state
+        .read()
+        .has_interrupt(Interrupt::ReceiveTimeout)
+}
+
+// Stop receive DMA started on `buf` and return number of bytes received. If the line
+// went `idle`, bytes below DMA threshold left in receive FIFO are copied by the processor.
+#[inline]
+pub(crate) fn uart_finish_receive_dma(
+    uart: &RegisterBlock,
+    channel: &UntypedChannel,
+    buf: &mut [u8],
+    idle: bool,
+) -> Result<usize, Error> {
+    let len = buf.len();
+    let registers = &channel.dma.channels[channel.channel_id];
+    // Let in-flight beats land before reading remaining transfer size.
+    unsafe { registers.config.modify(|val| val.stop_dma()) };
+    while !registers.config.read().is_fifo_empty() {
+        core::hint::spin_loop();
+    }
+    channel.stop();
+    unsafe { registers.config.modify(|val| val.resume_dma()) };
+    let mut received = len - registers.control.read().transfer_size() as usize;
+    invalidate_dcache(buf.as_ptr() as usize, len);
+    if idle {
+        while received < len {
+            match uart_read_nb(uart) {
+                Ok(word) => buf[received] = word,
+                Err(_) => break,
+            }
+            received += 1;
+        }
+        unsafe {
+            uart.interrupt_clear
+                .write(InterruptClear::default().clear_interrupt(Interrupt::ReceiveTimeout))
+        };
+    }
+    if uart.fifo_config_0.read().receive_fifo_overflow() {
+        unsafe { uart.fifo_config_0.modify(|val| val.clear_receive_fifo()) };
+        return Err(Error::Overrun);
+    }
+    Ok(received)
+}
+
+// Start transmit DMA of `data`, which must stay untouched until the channel is idle.
+#[inline]
+pub(crate) fn uart_start_transmit_dma(uart: &RegisterBlock, channel: &UntypedChannel, data: &[u8]) {
+    let registers = &channel.dma.channels[channel.channel_id];
+    // Data may still be in data cache, write it back before DMA reads memory.
+    flush_dcache(data.as_ptr() as usize, data.len());
+    unsafe {
+        registers.source_address.write(data.as_ptr() as u32);
+        registers
+            .destination_address
+            .write(&uart.fifo_write as *const _ as u32);
+        registers.linked_list_item.write(0);
+        registers
+            .control
+            .modify(|val| val.set_transfer_size(data.len() as u16));
+    }
+    channel.start();
+}
+
+#[inline]
+pub(crate) fn uart_read(uart: &RegisterBlock, buf: &mut [u8]) -> Result<usize, Error> {
     while uart.fifo_config_1.read().receive_available_bytes() == 0 {
         core::hint::spin_loop();
     }
@@ -153,7 +712,12 @@ fn uart_read(uart: &RegisterBlock, buf: &mut [u8]) -> Result<usize, Error> {
 }
 
 #[inline]
-fn uart_read_nb(uart: &RegisterBlock) -> nb::Result<u8, Error> {
+fn uart_read_ready(uart: &RegisterBlock) -> bool {
+    uart.fifo_config_1.read().receive_available_bytes() != 0
+}
+
+#[inline]
+pub(crate) fn uart_read_nb(uart: &RegisterBlock) -> nb::Result<u8, Error> {
     if uart.fifo_config_1.read().receive_available_bytes() == 0 {
         return Err(nb::Error::WouldBlock);
     }
@@ -216,6 +780,15 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_io::Read for BlockingSe
     }
 }
 
+impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_io::ReadReady
+    for BlockingSerial<UART, PADS>
+{
+    #[inline]
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(uart_read_ready(&self.uart))
+    }
+}
+
 impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_hal_nb::serial::Read
     for BlockingSerial<UART, PADS>
 {
@@ -260,6 +833,15 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_io::Read
     }
 }
 
+impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_io::ReadReady
+    for BlockingReceiveHalf<UART, PADS>
+{
+    #[inline]
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(uart_read_ready(&self.uart))
+    }
+}
+
 impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_hal_nb::serial::Read
     for BlockingReceiveHalf<UART, PADS>
 {
@@ -268,3 +850,77 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_hal_nb::serial::Read
         uart_read_nb(&self.uart)
     }
 }
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_hal_027::serial::Write<u8>
+    for BlockingSerial<UART, PADS>
+{
+    type Error = Error;
+
+    #[inline]
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        uart_write_nb(&self.uart, word)
+    }
+    #[inline]
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        uart_flush_nb(&self.uart)
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> core::fmt::Write for BlockingSerial<UART, PADS> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for &word in s.as_bytes() {
+            nb::block!(uart_write_nb(&self.uart, word)).map_err(|_| core::fmt::Error)?;
+        }
+        Ok(())
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_hal_027::serial::Read<u8>
+    for BlockingSerial<UART, PADS>
+{
+    type Error = Error;
+
+    #[inline]
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        uart_read_nb(&self.uart)
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_hal_027::serial::Write<u8>
+    for BlockingTransmitHalf<UART, PADS>
+{
+    type Error = Error;
+
+    #[inline]
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        uart_write_nb(&self.uart, word)
+    }
+    #[inline]
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        uart_flush_nb(&self.uart)
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> core::fmt::Write
+    for BlockingTransmitHalf<UART, PADS>
+{
+    #[inline]
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for &word in s.as_bytes() {
+            nb::block!(uart_write_nb(&self.uart, word)).map_err(|_| core::fmt::Error)?;
+        }
+        Ok(())
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_hal_027::serial::Read<u8>
+    for BlockingReceiveHalf<UART, PADS>
+{
+    type Error = Error;
+
+    #[inline]
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        uart_read_nb(&self.uart)
+    }
+}
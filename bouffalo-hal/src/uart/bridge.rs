@@ -0,0 +1,177 @@
+use super::{
+    BlockingSerial, Error, RegisterBlock, uart_finish_receive_dma, uart_receive_idle,
+    uart_start_receive_dma, uart_start_transmit_dma,
+};
+use crate::dma::UntypedChannel;
+use core::ops::Deref;
+
+/// DMA channels and ping-pong buffers forwarding one direction of a [`Bridge`].
+///
+/// Receive DMA fills one buffer until the receive line goes idle or the buffer is full,
+/// then transmit DMA sends it while receive DMA fills the other buffer. At most 4095
+/// bytes of each buffer are used.
+pub struct BridgePath<'a> {
+    receive: &'a UntypedChannel<'a>,
+    transmit: &'a UntypedChannel<'a>,
+    buffers: [&'a mut [u8]; 2],
+    // Buffer filled by receive DMA.
+    current: usize,
+    // Length of running receive transfer, zero if stopped.
+    receiving: usize,
+    // Bytes received into current buffer waiting for transmit channel.
+    pending: usize,
+}
+
+impl<'a> BridgePath<'a> {
+    /// Create a forwarding path from `receive` channel to `transmit` channel.
+    ///
+    /// `receive` must be configured for byte wide peripheral-to-memory transfers on the
+    /// receive request of source UART, and `transmit` for byte wide memory-to-peripheral
+    /// transfers on the transmit request of destination UART.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ping` or `pong` is empty.
+    #[inline]
+    pub fn new(
+        receive: &'a UntypedChannel<'a>,
+        transmit: &'a UntypedChannel<'a>,
+        ping: &'a mut [u8],
+        pong: &'a mut [u8],
+    ) -> Self {
+        assert!(
+            !ping.is_empty() && !pong.is_empty(),
+            "bridge buffers must not be empty"
+        );
+        Self {
+            receive,
+            transmit,
+            buffers: [ping, pong],
+            current: 0,
+            receiving: 0,
+            pending: 0,
+        }
+    }
+    // Advance forwarding from `src` to `dst`, returns number of bytes handed to transmit DMA.
+    #[inline]
+    fn poll(&mut self, src: &RegisterBlock, dst: &RegisterBlock) -> Result<usize, Error> {
+        if self.pending == 0 {
+            if self.receiving == 0 {
+                self.start_receive(src);
+                return Ok(0);
+            }
+            let idle = uart_receive_idle(src);
+            if self.receive.is_busy() && !idle {
+                return Ok(0);
+            }
+            let len = core::mem::take(&mut self.receiving);
+            let buf = &mut self.buffers[self.current][..len];
+            self.pending = uart_finish_receive_dma(src, self.receive, buf, idle)?;
+            if self.pending == 0 {
+                self.start_receive(src);
+                return Ok(0);
+            }
+        }
+        // The other buffer is read by transmit DMA until it stops.
+        if self.transmit.is_busy() {
+            return Ok(0);
+        }
+        self.transmit.stop();
+        let sent = core::mem::take(&mut self.pending);
+        uart_start_transmit_dma(dst, self.transmit, &self.buffers[self.current][..sent]);
+        self.current ^= 1;
+        self.start_receive(src);
+        Ok(sent)
+    }
+    #[inline]
+    fn start_receive(&mut self, src: &RegisterBlock) {
+        self.receiving = uart_start_receive_dma(src, self.receive, self.buffers[self.current]);
+    }
+    #[inline]
+    fn stop(&mut self) {
+        self.receive.stop();
+        self.transmit.stop();
+        self.receiving = 0;
+        self.pending = 0;
+    }
+}
+
+/// Transparent bridge forwarding data between two serials over DMA.
+///
+/// Data received on either serial is sent on the other once its receive line goes idle
+/// or a buffer is full, so line speeds and framings may differ; the bridge does not look
+/// into the data. While a buffer waits for the transmit channel, incoming data is kept in
+/// the receive FIFO only, so the destination should not be much slower than the source.
+///
+/// # Example
+///
+/// ```ignore
+/// // AT command passthrough between host console on UART0 and modem on UART1.
+/// let console = p.uart0.freerun(config, console_pads, &c).unwrap();
+/// let modem = p.uart1.freerun(config, modem_pads, &c).unwrap();
+/// let (mut buf0, mut buf1, mut buf2, mut buf3) = ([0; 256], [0; 256], [0; 256], [0; 256]);
+/// let to_modem = BridgePath::new(&dma0.ch0, &dma0.ch1, &mut buf0, &mut buf1);
+/// let to_console = BridgePath::new(&dma0.ch2, &dma0.ch3, &mut buf2, &mut buf3);
+/// let mut bridge = Bridge::new(console, modem, to_modem, to_console);
+/// let mut overruns = 0;
+/// loop {
+///     if let Err(Error::Overrun) = bridge.poll() {
+///         overruns += 1;
+///     }
+/// }
+/// ```
+pub struct Bridge<'a, A, PA, B, PB> {
+    a: BlockingSerial<A, PA>,
+    b: BlockingSerial<B, PB>,
+    a_to_b: BridgePath<'a>,
+    b_to_a: BridgePath<'a>,
+}
+
+impl<'a, A, PA, B, PB> Bridge<'a, A, PA, B, PB>
+where
+    A: Deref<Target = RegisterBlock>,
+    B: Deref<Target = RegisterBlock>,
+{
+    /// Creates a bridge forwarding `a` to `b` over `a_to_b`, and `b` to `a` over `b_to_a`.
+    ///
+    /// Receive and transmit DMA are enabled on both serials. The receive timeout set on
+    /// each serial decides how long its line stays silent before received data is sent.
+    #[inline]
+    pub fn new(
+        a: BlockingSerial<A, PA>,
+        b: BlockingSerial<B, PB>,
+        a_to_b: BridgePath<'a>,
+        b_to_a: BridgePath<'a>,
+    ) -> Self {
+        let a = a.enable_rx_dma().enable_tx_dma();
+        let b = b.enable_rx_dma().enable_tx_dma();
+        Self {
+            a,
+            b,
+            a_to_b,
+            b_to_a,
+        }
+    }
+
+    /// Forward data received in both directions without waiting.
+    ///
+    /// Call it in a loop. Returns number of bytes handed to transmit DMA. On receive FIFO
+    /// overflow of either serial the data it held is dropped and [`Error::Overrun`] is
+    /// returned; forwarding goes on with next call.
+    #[inline]
+    pub fn poll(&mut self) -> Result<usize, Error> {
+        let a_to_b = self.a_to_b.poll(&self.a.uart, &self.b.uart);
+        let b_to_a = self.b_to_a.poll(&self.b.uart, &self.a.uart);
+        Ok(a_to_b? + b_to_a?)
+    }
+
+    /// Release bridge and return both serials.
+    ///
+    /// All four DMA channels are stopped; data not yet sent is dropped.
+    #[inline]
+    pub fn free(mut self) -> (BlockingSerial<A, PA>, BlockingSerial<B, PB>) {
+        self.a_to_b.stop();
+        self.b_to_a.stop();
+        (self.a, self.b)
+    }
+}
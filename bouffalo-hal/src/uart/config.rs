@@ -1,4 +1,6 @@
-use super::{BitPeriod, DataConfig, Pads, ReceiveConfig, TransmitConfig};
+use super::{
+    BitPeriod, DataConfig, FifoConfig1, Pads, ReceiveConfig, ReceiveTimeout, TransmitConfig,
+};
 use crate::clocks::Clocks;
 use embedded_time::rate::{Baud, Extensions};
 
@@ -21,6 +23,12 @@ pub struct Config {
     pub transmit_word_length: WordLength,
     /// Data word length on the receive half.
     pub receive_word_length: WordLength,
+    /// Idle time in bit periods before a receive timeout interrupt is raised.
+    pub receive_timeout: u8,
+    /// Transmit FIFO threshold in bytes, from 0 to 31.
+    pub transmit_fifo_threshold: u8,
+    /// Receive FIFO threshold in bytes, from 0 to 31.
+    pub receive_fifo_threshold: u8,
 }
 
 impl Config {
@@ -53,6 +61,11 @@ impl Config {
             ..self
         }
     }
+    /// Set stop bits for both the transmit and receive halves.
+    #[inline]
+    pub const fn set_stop_bits(self, stop_bits: StopBits) -> Self {
+        Self { stop_bits, ..self }
+    }
     #[inline]
     fn into_registers(self) -> (DataConfig, TransmitConfig, ReceiveConfig) {
         let data_config = DataConfig::default().set_bit_order(self.bit_order);
@@ -68,7 +81,8 @@ impl Config {
 }
 
 impl Default for Config {
-    /// Serial configuration defaults to 8-bit word, no parity check, 1 stop bit, LSB first.
+    /// Serial configuration defaults to 8-bit word, no parity check, 1 stop bit, LSB first,
+    /// with receive timeout after 15 idle bits.
     #[inline]
     fn default() -> Self {
         Config {
@@ -80,10 +94,54 @@ impl Default for Config {
             stop_bits: StopBits::One,
             transmit_word_length: WordLength::Eight,
             receive_word_length: WordLength::Eight,
+            receive_timeout: 15,
+            transmit_fifo_threshold: 0,
+            receive_fifo_threshold: 0,
         }
     }
 }
 
+/// Bit period divisor and achieved baudrate for a requested baudrate.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BaudrateDivisor {
+    /// Bit period in UART clock cycles.
+    pub divisor: u16,
+    /// Baudrate actually achieved with this divisor.
+    pub actual: u32,
+    /// Deviation of achieved baudrate from requested one, in parts per million.
+    pub error_ppm: u32,
+}
+
+/// Calculate bit period divisor from UART clock frequency and requested baudrate.
+///
+/// Returns `None` if the baudrate cannot be represented by the bit period register.
+#[inline]
+pub const fn baudrate_divisor(uart_clock: u32, baudrate: u32) -> Option<BaudrateDivisor> {
+    if baudrate == 0 {
+        return None;
+    }
+    let divisor = uart_clock / baudrate;
+    if divisor < 1 || divisor > 65535 {
+        return None;
+    }
+    let actual = uart_clock / divisor;
+    let error_ppm = (actual.abs_diff(baudrate) as u64 * 1_000_000 / baudrate as u64) as u32;
+    Some(BaudrateDivisor {
+        divisor: divisor as u16,
+        actual,
+        error_ppm,
+    })
+}
+
+/// Check at compile time that the baudrate is achievable within 2% error.
+pub(crate) const fn assert_baudrate_within_tolerance(uart_clock: u32, baudrate: u32) {
+    match baudrate_divisor(uart_clock, baudrate) {
+        Some(divisor) if divisor.error_ppm <= 20_000 => {}
+        Some(_) => panic!("baudrate error exceeds 2% on this UART clock"),
+        None => panic!("baudrate out of range on this UART clock"),
+    }
+}
+
 #[inline]
 pub(crate) fn uart_config<const I: usize, PADS: Pads<I>>(
     config: Config,
@@ -121,6 +179,23 @@ pub(crate) fn uart_config<const I: usize, PADS: Pads<I>>(
     Ok((bit_period, data_config, transmit_config, receive_config))
 }
 
+#[inline]
+pub(crate) fn uart_fifo_config(
+    config: Config,
+) -> Result<(ReceiveTimeout, FifoConfig1), ConfigError> {
+    if config.transmit_fifo_threshold > 31 {
+        return Err(ConfigError::TransmitThresholdTooHigh);
+    }
+    if config.receive_fifo_threshold > 31 {
+        return Err(ConfigError::ReceiveThresholdTooHigh);
+    }
+    let receive_timeout = ReceiveTimeout::default().set_timeout(config.receive_timeout);
+    let fifo_config = FifoConfig1::default()
+        .set_transmit_threshold(config.transmit_fifo_threshold)
+        .set_receive_threshold(config.receive_fifo_threshold);
+    Ok((receive_timeout, fifo_config))
+}
+
 /// Errors on serial configuration.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ConfigError {
@@ -134,6 +209,12 @@ pub enum ConfigError {
     ReceiveBaudrateTooLow,
     /// Clock source unavailable.
     ClockSource,
+    /// Transmit FIFO threshold exceeds FIFO depth.
+    TransmitThresholdTooHigh,
+    /// Receive FIFO threshold exceeds FIFO depth.
+    ReceiveThresholdTooHigh,
+    /// UART clock differs from the one baudrate was checked against.
+    ClockMismatch,
 }
 
 /// Order of the bits transmitted and received on the wire.
@@ -156,6 +237,15 @@ pub enum Parity {
     Odd,
 }
 
+/// Nine-bit word on a multi-drop bus.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MultiDropWord {
+    /// Address byte, with the ninth bit set.
+    Address(u8),
+    /// Data byte, with the ninth bit cleared.
+    Data(u8),
+}
+
 /// Stop bits.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum StopBits {
@@ -181,3 +271,31 @@ pub enum WordLength {
     /// Eight bits per word.
     Eight,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{BaudrateDivisor, baudrate_divisor};
+
+    #[test]
+    fn function_baudrate_divisor() {
+        assert_eq!(
+            baudrate_divisor(80_000_000, 2_000_000),
+            Some(BaudrateDivisor {
+                divisor: 40,
+                actual: 2_000_000,
+                error_ppm: 0,
+            })
+        );
+        assert_eq!(
+            baudrate_divisor(80_000_000, 115_200),
+            Some(BaudrateDivisor {
+                divisor: 694,
+                actual: 115_273,
+                error_ppm: 633,
+            })
+        );
+        assert_eq!(baudrate_divisor(80_000_000, 0), None);
+        assert_eq!(baudrate_divisor(80_000_000, 1_000), None);
+        assert_eq!(baudrate_divisor(80_000_000, 100_000_000), None);
+    }
+}
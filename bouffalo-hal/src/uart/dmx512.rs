@@ -0,0 +1,184 @@
+use super::{
+    BlockingSerial, Config, ConfigError, Pads, RegisterBlock, StopBits, uart_flush,
+    uart_start_transmit_dma, uart_wait_transmit_idle, uart_write_nb,
+};
+use crate::clocks::Clocks;
+use crate::dma::UntypedChannel;
+use core::ops::Deref;
+use embedded_time::rate::Extensions;
+
+/// Number of slots in a DMX512 universe, not counting the start code.
+pub const DMX512_SLOTS: usize = 512;
+
+/// Start code of packets carrying dimmer levels.
+pub const NULL_START_CODE: u8 = 0x00;
+
+// Bit period at 250 kBd is 4 µs, so an 8N2 frame lasts 44 µs. Transmitters must send
+// breaks of at least 92 µs and marks-after-break of at least 12 µs.
+const FRAME_MICROS: u32 = 44;
+const BREAK_MICROS: u32 = 92;
+const MARK_AFTER_BREAK_MICROS: u32 = 12;
+const BREAK_FRAMES: u32 = BREAK_MICROS.div_ceil(FRAME_MICROS);
+const MARK_AFTER_BREAK_FRAMES: u32 = MARK_AFTER_BREAK_MICROS.div_ceil(FRAME_MICROS);
+
+/// DMX512 packet of a start code followed by 512 slots.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Universe([u8; DMX512_SLOTS + 1]);
+
+impl Universe {
+    /// Create a universe with null start code and all slots at zero.
+    #[inline]
+    pub const fn new() -> Self {
+        Self([0; DMX512_SLOTS + 1])
+    }
+    /// Set start code of the packet.
+    #[inline]
+    pub const fn set_start_code(&mut self, code: u8) {
+        self.0[0] = code;
+    }
+    /// Get start code of the packet.
+    #[inline]
+    pub const fn start_code(&self) -> u8 {
+        self.0[0]
+    }
+    /// Slot values, where DMX512 slot 1 is at index 0.
+    #[inline]
+    pub fn slots(&self) -> &[u8] {
+        &self.0[1..]
+    }
+    /// Mutable slot values, where DMX512 slot 1 is at index 0.
+    #[inline]
+    pub fn slots_mut(&mut self) -> &mut [u8] {
+        &mut self.0[1..]
+    }
+    /// Packet bytes as sent on the line, starting with the start code.
+    #[inline]
+    pub const fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Default for Universe {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// DMX512 transmitter over a blocking serial peripheral.
+///
+/// Each packet is preceded by a break of 132 µs and a mark-after-break of 44 µs. The LIN
+/// header generator of this UART cannot produce breaks this long and always appends a sync
+/// field, so both are timed by shifting dummy frames with the transmit line held by software.
+pub struct Dmx512<UART, PADS> {
+    serial: BlockingSerial<UART, PADS>,
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> Dmx512<UART, PADS> {
+    /// Creates a DMX512 transmitter at 250 kBd, 8 data bits, no parity and 2 stop bits.
+    ///
+    /// Transmit DMA is enabled on the serial.
+    #[inline]
+    pub fn freerun<const I: usize>(
+        uart: UART,
+        pads: PADS,
+        clocks: &Clocks,
+    ) -> Result<Self, ConfigError>
+    where
+        PADS: Pads<I>,
+    {
+        let config = Config::default()
+            .set_baudrate(250_000.Bd())
+            .set_stop_bits(StopBits::Two);
+        let serial = BlockingSerial::freerun(uart, config, pads, clocks)?.enable_tx_dma();
+        Ok(Self { serial })
+    }
+
+    /// Send break, mark-after-break and `packet` over DMA, waiting until the last slot is sent.
+    ///
+    /// `packet` starts with the start code, e.g. [`Universe::as_bytes`]; bytes beyond a full
+    /// universe are not sent. `channel` must be configured for byte wide memory-to-peripheral
+    /// transfers on this UART's transmit request.
+    #[inline]
+    pub fn transmit(&mut self, channel: &UntypedChannel, packet: &[u8]) {
+        let packet = &packet[..core::cmp::min(packet.len(), DMX512_SLOTS + 1)];
+        dmx_hold_line(&self.serial.uart, false, BREAK_FRAMES);
+        dmx_hold_line(&self.serial.uart, true, MARK_AFTER_BREAK_FRAMES);
+        if !packet.is_empty() {
+            dmx_write_dma(&self.serial.uart, channel, packet);
+        }
+        let _ = uart_flush(&self.serial.uart);
+        uart_wait_transmit_idle(&self.serial.uart);
+    }
+
+    /// Send `universe`, see [`transmit`](Self::transmit).
+    #[inline]
+    pub fn transmit_universe(&mut self, channel: &UntypedChannel, universe: &Universe) {
+        self.transmit(channel, universe.as_bytes())
+    }
+
+    /// Release DMX512 transmitter and return its blocking serial.
+    #[inline]
+    pub fn free(self) -> BlockingSerial<UART, PADS> {
+        self.serial
+    }
+}
+
+// Hold transmit line at `level` while `frames` dummy frames are shifted out.
+#[inline]
+fn dmx_hold_line(uart: &RegisterBlock, level: bool, frames: u32) {
+    let _ = uart_flush(uart);
+    uart_wait_transmit_idle(uart);
+    unsafe {
+        uart.software_mode
+            .modify(|val| val.set_txd_value(level).enable_txd_software())
+    };
+    for _ in 0..frames {
+        while uart_write_nb(uart, 0x00).is_err() {
+            core::hint::spin_loop();
+        }
+    }
+    let _ = uart_flush(uart);
+    uart_wait_transmit_idle(uart);
+    unsafe { uart.software_mode.modify(|val| val.disable_txd_software()) };
+}
+
+#[inline]
+fn dmx_write_dma(uart: &RegisterBlock, channel: &UntypedChannel, packet: &[u8]) {
+    uart_start_transmit_dma(uart, channel, packet);
+    while channel.is_busy() {
+        core::hint::spin_loop();
+    }
+    channel.stop();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BREAK_FRAMES, DMX512_SLOTS, FRAME_MICROS, MARK_AFTER_BREAK_FRAMES, NULL_START_CODE,
+        Universe,
+    };
+
+    #[test]
+    fn struct_universe() {
+        let mut universe = Universe::default();
+        assert_eq!(universe.start_code(), NULL_START_CODE);
+        assert_eq!(universe.slots().len(), DMX512_SLOTS);
+        assert_eq!(universe.as_bytes().len(), DMX512_SLOTS + 1);
+        universe.set_start_code(0xcc);
+        universe.slots_mut()[0] = 0x12;
+        universe.slots_mut()[511] = 0xff;
+        assert_eq!(universe.as_bytes()[0], 0xcc);
+        assert_eq!(universe.as_bytes()[1], 0x12);
+        assert_eq!(universe.as_bytes()[512], 0xff);
+    }
+
+    #[test]
+    fn dmx512_timing() {
+        assert_eq!(BREAK_FRAMES, 3);
+        assert_eq!(MARK_AFTER_BREAK_FRAMES, 1);
+        assert_eq!(BREAK_FRAMES * FRAME_MICROS, 132);
+        assert_eq!(MARK_AFTER_BREAK_FRAMES * FRAME_MICROS, 44);
+    }
+}
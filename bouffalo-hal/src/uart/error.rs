@@ -30,3 +30,97 @@ impl embedded_hal_nb::serial::Error for Error {
         }
     }
 }
+
+/// Serial error on transfers with a deadline.
+#[derive(Debug)]
+pub enum TimeoutError {
+    /// Deadline elapsed after transferring given number of bytes.
+    Timeout(usize),
+    /// Error from serial peripheral.
+    Serial(Error),
+    /// Timer source unavailable or deadline out of its range.
+    Clock,
+}
+
+impl From<Error> for TimeoutError {
+    #[inline]
+    fn from(value: Error) -> Self {
+        TimeoutError::Serial(value)
+    }
+}
+
+/// RS-485 serial error.
+#[derive(Debug)]
+pub enum Rs485Error<E> {
+    /// Error from serial peripheral.
+    Serial(Error),
+    /// Error from driver-enable signal.
+    DriverEnable(E),
+}
+
+impl<E> From<Error> for Rs485Error<E> {
+    #[inline]
+    fn from(value: Error) -> Self {
+        Rs485Error::Serial(value)
+    }
+}
+
+impl<E: core::fmt::Debug> embedded_io::Error for Rs485Error<E> {
+    #[inline]
+    fn kind(&self) -> embedded_io::ErrorKind {
+        embedded_io::ErrorKind::Other
+    }
+}
+
+impl<E: core::fmt::Debug> embedded_hal_nb::serial::Error for Rs485Error<E> {
+    #[inline]
+    fn kind(&self) -> embedded_hal_nb::serial::ErrorKind {
+        match self {
+            Rs485Error::Serial(e) => embedded_hal_nb::serial::Error::kind(e),
+            Rs485Error::DriverEnable(_) => embedded_hal_nb::serial::ErrorKind::Other,
+        }
+    }
+}
+
+/// Serial self test error.
+#[derive(Debug)]
+pub enum SelfTestError {
+    /// Received byte differs from the transmitted one.
+    Mismatch {
+        /// Index of the byte in test pattern.
+        index: usize,
+        /// Transmitted byte.
+        expected: u8,
+        /// Received byte.
+        received: u8,
+    },
+    /// Transfer failed or timed out.
+    Transfer(TimeoutError),
+}
+
+impl From<TimeoutError> for SelfTestError {
+    #[inline]
+    fn from(value: TimeoutError) -> Self {
+        SelfTestError::Transfer(value)
+    }
+}
+
+/// SBUS frame error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SbusError {
+    /// Frame does not start with header byte, contains the received byte.
+    Header(u8),
+    /// Frame does not end with a known footer byte, contains the received byte.
+    Footer(u8),
+}
+
+/// Line reader error.
+#[derive(Debug)]
+pub enum LineError<E> {
+    /// Error from underlying reader.
+    Read(E),
+    /// Line does not fit in buffer; its remaining bytes are skipped.
+    Overflow,
+    /// Reader reached end of stream.
+    EndOfStream,
+}
@@ -0,0 +1,213 @@
+use super::LineError;
+use embedded_io::{Read, ReadReady};
+
+/// Line reader over any byte reader with a fixed `N` byte buffer.
+///
+/// Lines end with `\n`, and a trailing `\r` is removed, which suits NMEA sentences and AT
+/// command responses. Bytes read after a line ending are kept for the next line.
+///
+/// A device which stops sending in the middle of a line leaves it in the buffer; when the
+/// serial reports receive timeout, [`take_partial`](Self::take_partial) returns it.
+///
+/// # Example
+///
+/// ```ignore
+/// let serial = p.uart1.freerun(config, pads, &c).unwrap();
+/// let mut gps = LineReader::<_, 128>::new(serial);
+/// loop {
+///     match gps.read_line() {
+///         Ok(line) if line.starts_with(b"$GPRMC") => handle_rmc(line),
+///         Ok(_) | Err(LineError::Overflow) => continue,
+///         Err(e) => panic!("{:?}", e),
+///     }
+/// }
+/// ```
+pub struct LineReader<R, const N: usize> {
+    reader: R,
+    buf: [u8; N],
+    // Bytes buffered in `buf[..len]`.
+    len: usize,
+    // Bytes of `buf` returned as the last line, removed on next call.
+    consumed: usize,
+    // Skipping the rest of an overflowed line.
+    discard: bool,
+}
+
+impl<R: Read, const N: usize> LineReader<R, N> {
+    /// Create line reader over `reader`.
+    #[inline]
+    pub const fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: [0; N],
+            len: 0,
+            consumed: 0,
+            discard: false,
+        }
+    }
+    /// Wait for next line and return it without line ending.
+    #[inline]
+    pub fn read_line(&mut self) -> Result<&[u8], LineError<R::Error>> {
+        let end = loop {
+            if let Some(end) = self.next_line()? {
+                break end;
+            }
+            self.fill()?;
+        };
+        Ok(trim_line(&self.buf[..end]))
+    }
+    /// Return next line if a whole line is available without blocking.
+    #[inline]
+    pub fn poll_line(&mut self) -> nb::Result<&[u8], LineError<R::Error>>
+    where
+        R: ReadReady,
+    {
+        let end = loop {
+            if let Some(end) = self.next_line()? {
+                break end;
+            }
+            if !self.reader.read_ready().map_err(LineError::Read)? {
+                return Err(nb::Error::WouldBlock);
+            }
+            self.fill()?;
+        };
+        Ok(trim_line(&self.buf[..end]))
+    }
+    /// Return and remove buffered bytes of an unterminated line.
+    #[inline]
+    pub fn take_partial(&mut self) -> &[u8] {
+        self.compact();
+        let len = self.len;
+        self.consumed = len;
+        if core::mem::take(&mut self.discard) {
+            return &[];
+        }
+        &self.buf[..len]
+    }
+    /// Get a reference to underlying reader.
+    #[inline]
+    pub const fn inner(&self) -> &R {
+        &self.reader
+    }
+    /// Get a mutable reference to underlying reader.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+    /// Release line reader and return underlying reader; buffered bytes are dropped.
+    #[inline]
+    pub fn free(self) -> R {
+        self.reader
+    }
+    // Remove last returned line, then find end of next line in buffer.
+    #[inline]
+    fn next_line(&mut self) -> Result<Option<usize>, LineError<R::Error>> {
+        self.compact();
+        while let Some(pos) = self.buf[..self.len].iter().position(|&b| b == b'\n') {
+            if self.discard {
+                self.discard = false;
+                self.consumed = pos + 1;
+                self.compact();
+                continue;
+            }
+            self.consumed = pos + 1;
+            return Ok(Some(pos));
+        }
+        if self.len == N {
+            // Drop the partial line, report it once and skip until its end.
+            self.len = 0;
+            if !core::mem::replace(&mut self.discard, true) {
+                return Err(LineError::Overflow);
+            }
+        }
+        Ok(None)
+    }
+    #[inline]
+    fn compact(&mut self) {
+        if self.consumed != 0 {
+            self.buf.copy_within(self.consumed..self.len, 0);
+            self.len -= self.consumed;
+            self.consumed = 0;
+        }
+    }
+    #[inline]
+    fn fill(&mut self) -> Result<(), LineError<R::Error>> {
+        match self.reader.read(&mut self.buf[self.len..]) {
+            Ok(0) => Err(LineError::EndOfStream),
+            Ok(n) => {
+                self.len += n;
+                Ok(())
+            }
+            Err(e) => Err(LineError::Read(e)),
+        }
+    }
+}
+
+#[inline]
+fn trim_line(line: &[u8]) -> &[u8] {
+    match line {
+        [rest @ .., b'\r'] => rest,
+        _ => line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LineError, LineReader};
+
+    // Reader returning at most `chunk` bytes per call.
+    struct Chunks<'a> {
+        data: &'a [u8],
+        chunk: usize,
+    }
+
+    impl embedded_io::ErrorType for Chunks<'_> {
+        type Error = embedded_io::ErrorKind;
+    }
+
+    impl embedded_io::Read for Chunks<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            let n = self.chunk.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    impl embedded_io::ReadReady for Chunks<'_> {
+        fn read_ready(&mut self) -> Result<bool, Self::Error> {
+            Ok(!self.data.is_empty())
+        }
+    }
+
+    #[test]
+    fn struct_line_reader() {
+        let data = b"$GPGGA,1*47\r\n$GPRMC,2*4A\r\n\nAT\n";
+        for chunk in [1, 5, 64] {
+            let mut reader = LineReader::<_, 16>::new(Chunks { data, chunk });
+            assert_eq!(reader.read_line().unwrap(), b"$GPGGA,1*47");
+            assert_eq!(reader.read_line().unwrap(), b"$GPRMC,2*4A");
+            assert_eq!(reader.read_line().unwrap(), b"");
+            assert_eq!(reader.read_line().unwrap(), b"AT");
+            assert!(matches!(reader.read_line(), Err(LineError::EndOfStream)));
+        }
+    }
+
+    #[test]
+    fn struct_line_reader_overflow() {
+        let data = b"0123456789abcdefghij\r\nOK\r\n";
+        let mut reader = LineReader::<_, 8>::new(Chunks { data, chunk: 3 });
+        assert!(matches!(reader.read_line(), Err(LineError::Overflow)));
+        assert_eq!(reader.read_line().unwrap(), b"OK");
+    }
+
+    #[test]
+    fn struct_line_reader_poll() {
+        let data = b"OK\r\nERR";
+        let mut reader = LineReader::<_, 8>::new(Chunks { data, chunk: 2 });
+        assert_eq!(reader.poll_line().unwrap(), b"OK");
+        assert!(matches!(reader.poll_line(), Err(nb::Error::WouldBlock)));
+        assert_eq!(reader.take_partial(), b"ERR");
+        assert!(matches!(reader.poll_line(), Err(nb::Error::WouldBlock)));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::glb::{self, v2::UartSignal};
+use crate::glb::{self, modify_shared, v2::UartSignal};
 use core::marker::PhantomData;
 
 /// Multiplex to Request-to-Send (type state).
@@ -73,10 +73,9 @@ impl<'a, const N: usize, M> UartMux<'a, N, M> {
     /// Configure the internal UART signal to Request-to-Send (RTS).
     #[inline]
     pub fn into_request_to_send<const U: usize>(self) -> UartMux<'a, N, MuxRts<U>> {
-        let config = self.base.uart_mux_group[N >> 3]
-            .read()
-            .set_signal(N & 0x7, MuxRts::<U>::signal());
-        unsafe { self.base.uart_mux_group[N >> 3].write(config) };
+        modify_shared(&self.base.uart_mux_group[N >> 3], |config| {
+            config.set_signal(N & 0x7, MuxRts::<U>::signal())
+        });
         UartMux {
             base: self.base,
             _mode: PhantomData,
@@ -85,10 +84,9 @@ impl<'a, const N: usize, M> UartMux<'a, N, M> {
     /// Configure the internal UART signal to Transmit (TXD).
     #[inline]
     pub fn into_transmit<const U: usize>(self) -> UartMux<'a, N, MuxTxd<U>> {
-        let config = self.base.uart_mux_group[N >> 3]
-            .read()
-            .set_signal(N & 0x7, MuxTxd::<U>::signal());
-        unsafe { self.base.uart_mux_group[N >> 3].write(config) };
+        modify_shared(&self.base.uart_mux_group[N >> 3], |config| {
+            config.set_signal(N & 0x7, MuxTxd::<U>::signal())
+        });
         UartMux {
             base: self.base,
             _mode: PhantomData,
@@ -97,10 +95,9 @@ impl<'a, const N: usize, M> UartMux<'a, N, M> {
     /// Configure the internal UART signal to Receive (RXD).
     #[inline]
     pub fn into_receive<const U: usize>(self) -> UartMux<'a, N, MuxRxd<U>> {
-        let config = self.base.uart_mux_group[N >> 3]
-            .read()
-            .set_signal(N & 0x7, MuxRxd::<U>::signal());
-        unsafe { self.base.uart_mux_group[N >> 3].write(config) };
+        modify_shared(&self.base.uart_mux_group[N >> 3], |config| {
+            config.set_signal(N & 0x7, MuxRxd::<U>::signal())
+        });
         UartMux {
             base: self.base,
             _mode: PhantomData,
@@ -109,15 +106,21 @@ impl<'a, const N: usize, M> UartMux<'a, N, M> {
     /// Configure the internal UART signal to Clear-to-Send (CTS).
     #[inline]
     pub fn into_clear_to_send<const U: usize>(self) -> UartMux<'a, N, MuxCts<U>> {
-        let config = self.base.uart_mux_group[N >> 3]
-            .read()
-            .set_signal(N & 0x7, MuxCts::<U>::signal());
-        unsafe { self.base.uart_mux_group[N >> 3].write(config) };
+        modify_shared(&self.base.uart_mux_group[N >> 3], |config| {
+            config.set_signal(N & 0x7, MuxCts::<U>::signal())
+        });
         UartMux {
             base: self.base,
             _mode: PhantomData,
         }
     }
+    /// Read back the internal UART signal this multiplexer is currently routed to.
+    ///
+    /// Returns `None` if the hardware field holds a reserved value, e.g. after reset.
+    #[inline]
+    pub fn current_signal(&self) -> Option<UartSignal> {
+        self.base.uart_mux_group[N >> 3].read().try_signal(N & 0x7)
+    }
 }
 
 /// Available UART signal multiplexers.
@@ -148,6 +151,29 @@ pub struct UartMuxes<'a> {
     pub sig11: UartMux<'a, 11, MuxRts<0>>,
 }
 
+impl UartMuxes<'_> {
+    /// Read back internal UART signals of all multiplexers, indexed by signal number.
+    ///
+    /// Useful to check pin routing when a serial console prints nothing.
+    #[inline]
+    pub fn dump(&self) -> [Option<UartSignal>; 12] {
+        [
+            self.sig0.current_signal(),
+            self.sig1.current_signal(),
+            self.sig2.current_signal(),
+            self.sig3.current_signal(),
+            self.sig4.current_signal(),
+            self.sig5.current_signal(),
+            self.sig6.current_signal(),
+            self.sig7.current_signal(),
+            self.sig8.current_signal(),
+            self.sig9.current_signal(),
+            self.sig10.current_signal(),
+            self.sig11.current_signal(),
+        ]
+    }
+}
+
 // Macro internal functions, do not use.
 
 impl<'a, const N: usize, M> UartMux<'a, N, M> {
@@ -180,3 +206,29 @@ impl<'a> UartMuxes<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::UartMuxes;
+    use crate::glb::v2::{RegisterBlock, UartSignal};
+
+    #[test]
+    fn function_uart_muxes_dump() {
+        let glb: RegisterBlock = unsafe { core::mem::zeroed() };
+        let muxes = UartMuxes::__uart_muxes_from_glb(&glb);
+        let sig2 = muxes.sig2.into_transmit::<0>();
+        let sig3 = muxes.sig3.into_receive::<0>();
+        assert_eq!(sig2.current_signal(), Some(UartSignal::Txd0));
+        assert_eq!(sig3.current_signal(), Some(UartSignal::Rxd0));
+        let muxes = UartMuxes::__uart_muxes_from_glb(&glb);
+        unsafe {
+            (&glb.uart_mux_group[1] as *const _ as *mut u32).write_volatile(0xffff_f6ff);
+        }
+        let signals = muxes.dump();
+        assert_eq!(signals[0], Some(UartSignal::Rts0));
+        assert_eq!(signals[2], Some(UartSignal::Txd0));
+        assert_eq!(signals[3], Some(UartSignal::Rxd0));
+        assert_eq!(signals[8], None);
+        assert_eq!(signals[10], Some(UartSignal::Txd1));
+    }
+}
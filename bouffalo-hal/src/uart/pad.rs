@@ -1,8 +1,17 @@
 use super::{BlockingReceiveHalf, BlockingTransmitHalf, MuxCts, MuxRts, MuxRxd, MuxTxd, UartMux};
 use crate::gpio::{Alternate, MmUart, Uart};
+use crate::sealed::Sealed;
 
 /// Check if target gpio `Pin` is internally connected to UART signal index `I`.
-pub trait HasUartSignal<const I: usize> {}
+///
+/// This trait is sealed; the connections are fixed on hardware and listed in this crate.
+///
+/// ```compile_fail
+/// use bouffalo_hal::uart::HasUartSignal;
+/// struct MyPin;
+/// impl HasUartSignal<0> for MyPin {}
+/// ```
+pub trait HasUartSignal<const I: usize>: Sealed {}
 
 impl<'a> HasUartSignal<0> for Alternate<'a, 0, Uart> {}
 impl<'a> HasUartSignal<1> for Alternate<'a, 1, Uart> {}
@@ -52,15 +61,33 @@ impl<'a> HasUartSignal<8> for Alternate<'a, 44, Uart> {}
 impl<'a> HasUartSignal<9> for Alternate<'a, 45, Uart> {}
 
 /// Check if an internal multi-media UART signal is connected to target gpio `Pin`.
-pub trait HasMmUartSignal {}
+///
+/// This trait is sealed; the connections are fixed on hardware and listed in this crate.
+pub trait HasMmUartSignal: Sealed {}
 
 impl<'a, const N: usize> HasMmUartSignal for Alternate<'a, N, MmUart> {}
 
 /// Valid UART pads.
+///
+/// This trait is sealed and implemented for pads with matching alternate modes and signal
+/// multiplexers, so that a peripheral never runs on pads the GLB does not connect to it.
+///
+/// ```compile_fail
+/// use bouffalo_hal::uart::Pads;
+/// struct MyPads;
+/// impl Pads<0> for MyPads {
+///     const RTS: bool = false;
+///     const CTS: bool = false;
+///     const TXD: bool = true;
+///     const RXD: bool = true;
+///     type Split<T> = ();
+///     fn split<T>(self, _: T) {}
+/// }
+/// ```
 #[diagnostic::on_unimplemented(
     message = "the I/O pad and signal multiplexer group {Self} is not connected to any UART peripherals on hardware"
 )]
-pub trait Pads<const U: usize> {
+pub trait Pads<const U: usize>: Sealed {
     /// Checks if this pin configuration includes Request-to-Send feature.
     const RTS: bool;
     /// Checks if this pin configuration includes Clear-to-Send feature.
@@ -109,6 +136,25 @@ where
     }
 }
 
+impl<'a, 'b, const I: usize, const U: usize, const N: usize> Pads<U>
+    for (Alternate<'a, N, Uart>, UartMux<'b, I, MuxRxd<U>>)
+where
+    Alternate<'a, N, Uart>: HasUartSignal<I>,
+{
+    const RTS: bool = false;
+    const CTS: bool = false;
+    const TXD: bool = false;
+    const RXD: bool = true;
+    type Split<T> = (
+        BlockingTransmitHalf<T, ()>,
+        BlockingReceiveHalf<T, (Alternate<'a, N, Uart>, UartMux<'b, I, MuxRxd<U>>)>,
+    );
+    #[inline]
+    fn split<T>(self, uart: T) -> Self::Split<T> {
+        from_pads(uart, (), self)
+    }
+}
+
 impl<
     'a,
     'b,
@@ -159,7 +205,7 @@ impl<
     )
 where
     Alternate<'a, N1, Uart>: HasUartSignal<I1>,
-    Alternate<'d, N2, Uart>: HasUartSignal<I2>,
+    Alternate<'c, N2, Uart>: HasUartSignal<I2>,
 {
     const RTS: bool = false;
     const CTS: bool = true;
@@ -178,6 +224,42 @@ where
     }
 }
 
+impl<
+    'a,
+    'b,
+    'c,
+    'd,
+    const I1: usize,
+    const I2: usize,
+    const U: usize,
+    const N1: usize,
+    const N2: usize,
+> Pads<U>
+    for (
+        (Alternate<'a, N1, Uart>, UartMux<'b, I1, MuxRxd<U>>),
+        (Alternate<'c, N2, Uart>, UartMux<'d, I2, MuxRts<U>>),
+    )
+where
+    Alternate<'a, N1, Uart>: HasUartSignal<I1>,
+    Alternate<'c, N2, Uart>: HasUartSignal<I2>,
+{
+    const RTS: bool = true;
+    const CTS: bool = false;
+    const TXD: bool = false;
+    const RXD: bool = true;
+    type Split<T> = BlockingReceiveHalf<
+        T,
+        (
+            (Alternate<'a, N1, Uart>, UartMux<'b, I1, MuxRxd<U>>),
+            (Alternate<'c, N2, Uart>, UartMux<'d, I2, MuxRts<U>>),
+        ),
+    >;
+    #[inline]
+    fn split<T>(self, uart: T) -> Self::Split<T> {
+        BlockingReceiveHalf { uart, _pads: self }
+    }
+}
+
 impl<
     'a,
     'b,
@@ -209,10 +291,10 @@ where
     Alternate<'e, N3, Uart>: HasUartSignal<I3>,
     Alternate<'g, N4, Uart>: HasUartSignal<I4>,
 {
-    const RTS: bool = false;
+    const RTS: bool = true;
     const CTS: bool = true;
     const TXD: bool = true;
-    const RXD: bool = false;
+    const RXD: bool = true;
     type Split<T> = (
         BlockingTransmitHalf<
             T,
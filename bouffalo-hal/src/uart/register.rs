@@ -12,7 +12,11 @@ pub struct RegisterBlock {
     pub bit_period: RW<BitPeriod>,
     /// Data format configuration.
     pub data_config: RW<DataConfig>,
-    _reserved1: [u8; 0x10],
+    _reserved1: [u8; 0x8],
+    /// Receive timeout configuration.
+    pub receive_timeout: RW<ReceiveTimeout>,
+    /// Software override of transmit and request-to-send signals.
+    pub software_mode: RW<SoftwareMode>,
     /// Interrupt state register.
     pub interrupt_state: RO<InterruptState>,
     /// Interrupt mask register.
@@ -23,14 +27,14 @@ pub struct RegisterBlock {
     pub interrupt_enable: RW<InterruptEnable>,
     /// Bus state.
     pub bus_state: RO<BusState>,
-    _reserved2: [u8; 0x4c],
+    _reserved3: [u8; 0x4c],
     /// First-in first-out queue configuration 0.
     pub fifo_config_0: RW<FifoConfig0>,
     /// First-in first-out queue configuration 1.
     pub fifo_config_1: RW<FifoConfig1>,
     /// Write data into first-in first-out queue.
     pub fifo_write: WO<u8>,
-    _reserved3: [u8; 0x3],
+    _reserved4: [u8; 0x3],
     /// Read data from first-in first-out queue.
     pub fifo_read: RO<u8>,
 }
@@ -46,81 +50,48 @@ mod transmit_config {
     use crate::BitField;
 
     pub(crate) type Enable = BitField<1, 0, u32>;
+    pub(crate) type Cts = BitField<1, 1, u32>;
+    pub(crate) type Freerun = BitField<1, 2, u32>;
+    pub(crate) type LinTransmit = BitField<1, 3, u32>;
     pub(crate) type ParityEnable = BitField<1, 4, u32>;
     pub(crate) type ParityMode = BitField<1, 5, u32>;
+    pub(crate) type IrTransmit = BitField<1, 6, u32>;
+    pub(crate) type IrInverse = BitField<1, 7, u32>;
     pub(crate) type WordLength = BitField<3, 8, u32>;
+    pub(crate) type StopBits = BitField<2, 11, u32>;
+    pub(crate) type LinBreakBits = BitField<3, 13, u32>;
+    pub(crate) type TransferLength = BitField<16, 16, u32>;
 }
 
 impl TransmitConfig {
-    const CTS: u32 = 1 << 1;
-    const FREERUN: u32 = 1 << 2;
-    const LIN_TRANSMIT: u32 = 1 << 3;
-    const IR_TRANSMIT: u32 = 1 << 6;
-    const IR_INVERSE: u32 = 1 << 7;
-    const STOP_BITS: u32 = 0b11 << 11;
-    const LIN_BREAK_BITS: u32 = 0b111 << 13;
-    const TRANSFER_LENGTH: u32 = 0xffff << 16;
-
-    /// Enable transmit.
-    #[inline]
-    pub const fn enable_txd(self) -> Self {
-        Self(transmit_config::Enable::from(self.0).enable())
-    }
-    /// Disable transmit.
-    #[inline]
-    pub const fn disable_txd(self) -> Self {
-        Self(transmit_config::Enable::from(self.0).disable())
-    }
-    /// Check if transmit is enabled.
-    #[inline]
-    pub const fn is_txd_enabled(self) -> bool {
-        transmit_config::Enable::from(self.0).is_enabled()
-    }
-    /// Enable Clear-to-Send signal.
-    #[inline]
-    pub const fn enable_cts(self) -> Self {
-        Self(self.0 | Self::CTS)
-    }
-    /// Disable Clear-to-Send signal.
-    #[inline]
-    pub const fn disable_cts(self) -> Self {
-        Self(self.0 & !Self::CTS)
-    }
-    /// Check if Clear-to-Send signal is enabled.
-    #[inline]
-    pub const fn is_cts_enabled(self) -> bool {
-        self.0 & Self::CTS != 0
-    }
-    /// Enable free-run mode.
-    #[inline]
-    pub const fn enable_freerun(self) -> Self {
-        Self(self.0 | Self::FREERUN)
-    }
-    /// Disable free-run mode.
-    #[inline]
-    pub const fn disable_freerun(self) -> Self {
-        Self(self.0 & !Self::FREERUN)
-    }
-    /// Check if free-run mode is enabled.
-    #[inline]
-    pub const fn is_freerun_enabled(self) -> bool {
-        self.0 & Self::FREERUN != 0
-    }
-    /// Enable LIN protocol transmission.
-    #[inline]
-    pub const fn enable_lin_transmit(self) -> Self {
-        Self(self.0 | Self::LIN_TRANSMIT)
-    }
-    /// Disable LIN protocol transmission.
-    #[inline]
-    pub const fn disable_lin_transmit(self) -> Self {
-        Self(self.0 & !Self::LIN_TRANSMIT)
-    }
-    /// Check if LIN protocol transmission is enabled.
-    #[inline]
-    pub const fn is_lin_transmit_enabled(self) -> bool {
-        self.0 & Self::LIN_TRANSMIT != 0
-    }
+    register_flag!(
+        enable_txd,
+        disable_txd,
+        is_txd_enabled,
+        transmit_config::Enable,
+        "transmit"
+    );
+    register_flag!(
+        enable_cts,
+        disable_cts,
+        is_cts_enabled,
+        transmit_config::Cts,
+        "Clear-to-Send signal"
+    );
+    register_flag!(
+        enable_freerun,
+        disable_freerun,
+        is_freerun_enabled,
+        transmit_config::Freerun,
+        "free-run mode"
+    );
+    register_flag!(
+        enable_lin_transmit,
+        disable_lin_transmit,
+        is_lin_transmit_enabled,
+        transmit_config::LinTransmit,
+        "LIN protocol transmission"
+    );
     /// Set parity check mode.
     #[inline]
     pub const fn set_parity(self, parity: Parity) -> Self {
@@ -152,35 +123,21 @@ impl TransmitConfig {
             Parity::Odd
         }
     }
-    /// Enable IR transmission.
-    #[inline]
-    pub const fn enable_ir_transmit(self) -> Self {
-        Self(self.0 | Self::IR_TRANSMIT)
-    }
-    /// Disable IR transmission.
-    #[inline]
-    pub const fn disable_ir_transmit(self) -> Self {
-        Self(self.0 & !Self::IR_TRANSMIT)
-    }
-    /// Check if IR transmission is enabled.
-    #[inline]
-    pub const fn is_ir_transmit_enabled(self) -> bool {
-        self.0 & Self::IR_TRANSMIT != 0
-    }
-    /// Invert transmit signal output in IR mode.
-    #[inline]
-    pub const fn enable_ir_inverse(self) -> Self {
-        Self(self.0 | Self::IR_INVERSE)
-    }
-    /// Don't invert transmit signal output in IR mode.
-    #[inline]
-    pub const fn disable_ir_inverse(self) -> Self {
-        Self(self.0 & !Self::IR_INVERSE)
-    }
-    /// Check if transmit signal output in IR mode is inverted.
-    #[inline]
-    pub const fn is_ir_inverse_enabled(self) -> bool {
-        self.0 & Self::IR_INVERSE != 0
+    register_flag!(
+        enable_ir_transmit,
+        disable_ir_transmit,
+        is_ir_transmit_enabled,
+        transmit_config::IrTransmit,
+        "IR transmission"
+    );
+    register_flag! {
+        /// Invert transmit signal output in IR mode.
+        enable_ir_inverse,
+        /// Don't invert transmit signal output in IR mode.
+        disable_ir_inverse,
+        /// Check if transmit signal output in IR mode is inverted.
+        is_ir_inverse_enabled,
+        transmit_config::IrInverse
     }
     /// Set word length.
     #[inline]
@@ -215,13 +172,12 @@ impl TransmitConfig {
             StopBits::OnePointFive => 2,
             StopBits::Two => 3,
         };
-        Self(self.0 & !Self::STOP_BITS | val << 11)
+        Self(transmit_config::StopBits::from(self.0).set(val))
     }
     /// Get stop-bit configuration.
     #[inline]
     pub const fn stop_bits(self) -> StopBits {
-        let val = (self.0 & Self::STOP_BITS) >> 11;
-        match val {
+        match transmit_config::StopBits::from(self.0).get() {
             0 => StopBits::ZeroPointFive,
             1 => StopBits::One,
             2 => StopBits::OnePointFive,
@@ -229,33 +185,25 @@ impl TransmitConfig {
             _ => unreachable!(),
         }
     }
-    /// Set synchronize interval under LIN mode.
-    ///
-    /// # Parameters
-    ///
-    /// * `bits` - Interval in bits, the value should be 0 ~ 7.
-    #[inline]
-    pub const fn set_lin_break_bits(self, bits: u8) -> Self {
-        Self(self.0 & !Self::LIN_BREAK_BITS | (bits as u32) << 13)
-    }
-    /// Get synchronize interval under LIN mode.
-    ///
-    /// Return value is 0 ~ 7, represent in bits.
-    #[inline]
-    pub const fn lin_break_bits(self) -> u8 {
-        ((self.0 & Self::LIN_BREAK_BITS) >> 13) as u8
-    }
-    /// Trigger interrupt when specified length of data is sent.
-    ///
-    /// NOTE: This bit is not valid when it is running under free-run mode.
-    #[inline]
-    pub const fn set_transfer_length(self, length: u16) -> Self {
-        Self(self.0 & !Self::TRANSFER_LENGTH | (length as u32) << 16)
-    }
-    /// Get the length of data that triggers the interrupt.
-    #[inline]
-    pub const fn transfer_length(self) -> u16 {
-        ((self.0 & Self::TRANSFER_LENGTH) >> 16) as u16
+    register_field! {
+        /// Set synchronize interval under LIN mode.
+        ///
+        /// # Parameters
+        ///
+        /// * `val` - Interval in bits, the value should be 0 ~ 7.
+        set_lin_break_bits,
+        /// Get synchronize interval under LIN mode.
+        ///
+        /// Return value is 0 ~ 7, represent in bits.
+        lin_break_bits: u8 = transmit_config::LinBreakBits
+    }
+    register_field! {
+        /// Trigger interrupt when specified length of data is sent.
+        ///
+        /// NOTE: This bit is not valid when it is running under free-run mode.
+        set_transfer_length,
+        /// Get the length of data that triggers the interrupt.
+        transfer_length: u16 = transmit_config::TransferLength
     }
 }
 
@@ -275,65 +223,40 @@ mod receive_config {
     use crate::BitField;
 
     pub(crate) type Enable = BitField<1, 0, u32>;
+    pub(crate) type AutoBaudrate = BitField<1, 1, u32>;
+    pub(crate) type LinReceive = BitField<1, 3, u32>;
     pub(crate) type ParityEnable = BitField<1, 4, u32>;
     pub(crate) type ParityMode = BitField<1, 5, u32>;
+    pub(crate) type IrReceive = BitField<1, 6, u32>;
+    pub(crate) type IrInverse = BitField<1, 7, u32>;
     pub(crate) type WordLength = BitField<3, 8, u32>;
+    pub(crate) type Deglitch = BitField<1, 11, u32>;
+    pub(crate) type DeglitchCycles = BitField<4, 12, u32>;
+    pub(crate) type TransferLength = BitField<16, 16, u32>;
 }
 
 impl ReceiveConfig {
-    const ABR: u32 = 1 << 1;
-    const LIN_RECEIVE: u32 = 1 << 3;
-    const IR_RECEIVE: u32 = 1 << 6;
-    const IR_INVERSE: u32 = 1 << 7;
-    const DEGLICH: u32 = 1 << 11;
-    const DEGLICH_CYCLE: u32 = 0xf << 12;
-    const TRANSFER_LENGTH: u32 = 0xffff << 16;
-
-    /// Enable receive.
-    #[inline]
-    pub const fn enable_rxd(self) -> Self {
-        Self(receive_config::Enable::from(self.0).enable())
-    }
-    /// Disable receive.
-    #[inline]
-    pub const fn disable_rxd(self) -> Self {
-        Self(receive_config::Enable::from(self.0).disable())
-    }
-    /// Check if receive is enabled.
-    #[inline]
-    pub const fn is_rxd_enabled(self) -> bool {
-        receive_config::Enable::from(self.0).is_enabled()
-    }
-    /// Enable auto baud rate detection.
-    #[inline]
-    pub const fn enable_auto_baudrate(self) -> Self {
-        Self(self.0 | Self::ABR)
-    }
-    /// Disable auto baud rate detection.
-    #[inline]
-    pub const fn disable_auto_baudrate(self) -> Self {
-        Self(self.0 & !Self::ABR)
-    }
-    /// Check if auto baud rate detection is enabled.
-    #[inline]
-    pub const fn is_auto_baudrate_enabled(self) -> bool {
-        self.0 & Self::ABR != 0
-    }
-    /// Enable LIN protocol receive.
-    #[inline]
-    pub const fn enable_lin_receive(self) -> Self {
-        Self(self.0 | Self::LIN_RECEIVE)
-    }
-    /// Disable LIN protocol receive.
-    #[inline]
-    pub const fn disable_lin_receive(self) -> Self {
-        Self(self.0 & !Self::LIN_RECEIVE)
-    }
-    /// Check if LIN protocol receive is enabled.
-    #[inline]
-    pub const fn is_lin_receive_enabled(self) -> bool {
-        self.0 & Self::LIN_RECEIVE != 0
-    }
+    register_flag!(
+        enable_rxd,
+        disable_rxd,
+        is_rxd_enabled,
+        receive_config::Enable,
+        "receive"
+    );
+    register_flag!(
+        enable_auto_baudrate,
+        disable_auto_baudrate,
+        is_auto_baudrate_enabled,
+        receive_config::AutoBaudrate,
+        "auto baud rate detection"
+    );
+    register_flag!(
+        enable_lin_receive,
+        disable_lin_receive,
+        is_lin_receive_enabled,
+        receive_config::LinReceive,
+        "LIN protocol receive"
+    );
     /// Set parity check mode.
     #[inline]
     pub const fn set_parity(self, parity: Parity) -> Self {
@@ -365,35 +288,21 @@ impl ReceiveConfig {
             Parity::Odd
         }
     }
-    /// Enable IR receive.
-    #[inline]
-    pub const fn enable_ir_receive(self) -> Self {
-        Self(self.0 | Self::IR_RECEIVE)
-    }
-    /// Disable IR receive.
-    #[inline]
-    pub const fn disable_ir_receive(self) -> Self {
-        Self(self.0 & !Self::IR_RECEIVE)
-    }
-    /// Check if IR receive is enabled.
-    #[inline]
-    pub const fn is_ir_receive_enabled(self) -> bool {
-        self.0 & Self::IR_RECEIVE != 0
-    }
-    /// Invert receive signal output in IR mode.
-    #[inline]
-    pub const fn enable_ir_inverse(self) -> Self {
-        Self(self.0 | Self::IR_INVERSE)
-    }
-    /// Don't invert receive signal output in IR mode.
-    #[inline]
-    pub const fn disable_ir_inverse(self) -> Self {
-        Self(self.0 & !Self::IR_INVERSE)
-    }
-    /// Check if receive signal output in IR mode is inverted.
-    #[inline]
-    pub const fn is_ir_inverse_enabled(self) -> bool {
-        self.0 & Self::IR_INVERSE != 0
+    register_flag!(
+        enable_ir_receive,
+        disable_ir_receive,
+        is_ir_receive_enabled,
+        receive_config::IrReceive,
+        "IR receive"
+    );
+    register_flag! {
+        /// Invert receive signal output in IR mode.
+        enable_ir_inverse,
+        /// Don't invert receive signal output in IR mode.
+        disable_ir_inverse,
+        /// Check if receive signal output in IR mode is inverted.
+        is_ir_inverse_enabled,
+        receive_config::IrInverse
     }
     /// Set word length.
     #[inline]
@@ -419,41 +328,31 @@ impl ReceiveConfig {
             _ => unreachable!(),
         }
     }
-    /// Enable de-glitch function.
-    #[inline]
-    pub const fn enable_deglitch(self) -> Self {
-        Self(self.0 | Self::DEGLICH)
-    }
-    /// Disable de-glitch function.
-    #[inline]
-    pub const fn disable_deglitch(self) -> Self {
-        Self(self.0 & !Self::DEGLICH)
-    }
-    /// Check if de-glitch function is enabled.
-    #[inline]
-    pub const fn is_deglitch_enabled(self) -> bool {
-        self.0 & Self::DEGLICH != 0
-    }
+    register_flag!(
+        enable_deglitch,
+        disable_deglitch,
+        is_deglitch_enabled,
+        receive_config::Deglitch,
+        "de-glitch function"
+    );
     /// Set de-glich function cycle count.
     #[inline]
     pub const fn set_deglitch_cycles(self, val: u8) -> Self {
-        Self(self.0 & !Self::DEGLICH_CYCLE | ((val as u32) << 12))
+        let field = receive_config::DeglitchCycles::from(self.0);
+        Self(self.0 & !(field.get_mask() as u32) | ((val as u32) << field.get_shift()))
     }
     /// Get de-glich function cycle count.
     #[inline]
     pub const fn deglitch_cycles(self) -> u8 {
-        ((self.0 & Self::DEGLICH_CYCLE) >> 12) as u8
-    }
-    /// Set the length of data that triggers the interrupt.
-    #[inline]
-    pub const fn set_transfer_length(self, length: u16) -> Self {
-        Self(self.0 & !Self::TRANSFER_LENGTH | (length as u32) << 16)
-    }
-    /// Get the length of data that triggers the interrupt.
-    #[inline]
-    pub const fn transfer_length(self) -> u16 {
-        ((self.0 & Self::TRANSFER_LENGTH) >> 16) as u16
-    }
+        receive_config::DeglitchCycles::from(self.0).get() as u8
+    }
+    register_field!(
+        set_transfer_length,
+        transfer_length,
+        u16,
+        receive_config::TransferLength,
+        "the length of data that triggers the interrupt"
+    );
 }
 
 impl Default for ReceiveConfig {
@@ -468,30 +367,28 @@ impl Default for ReceiveConfig {
 #[repr(transparent)]
 pub struct BitPeriod(u32);
 
-impl BitPeriod {
-    const TRANSMIT: u32 = 0xffff;
-    const RECEIVE: u32 = 0xffff << 16;
+mod bit_period {
+    use crate::BitField;
 
-    /// Set transmit time interval.
-    #[inline]
-    pub const fn set_transmit_time_interval(self, val: u16) -> Self {
-        Self(self.0 & !Self::TRANSMIT | val as u32)
-    }
-    /// Get transmit time interval.
-    #[inline]
-    pub const fn transmit_time_interval(self) -> u16 {
-        (self.0 & Self::TRANSMIT) as u16
-    }
-    /// Set receive time interval.
-    #[inline]
-    pub const fn set_receive_time_interval(self, val: u16) -> Self {
-        Self(self.0 & !Self::RECEIVE | ((val as u32) << 16))
-    }
-    /// Get receive time interval.
-    #[inline]
-    pub const fn receive_time_interval(self) -> u16 {
-        ((self.0 & Self::RECEIVE) >> 16) as u16
-    }
+    pub(crate) type Transmit = BitField<16, 0, u32>;
+    pub(crate) type Receive = BitField<16, 16, u32>;
+}
+
+impl BitPeriod {
+    register_field!(
+        set_transmit_time_interval,
+        transmit_time_interval,
+        u16,
+        bit_period::Transmit,
+        "transmit time interval"
+    );
+    register_field!(
+        set_receive_time_interval,
+        receive_time_interval,
+        u16,
+        bit_period::Receive,
+        "receive time interval"
+    );
 }
 
 impl Default for BitPeriod {
@@ -506,21 +403,26 @@ impl Default for BitPeriod {
 #[repr(transparent)]
 pub struct DataConfig(u32);
 
-impl DataConfig {
-    const BIT_ORDER: u32 = 1 << 0;
+mod data_config {
+    use crate::BitField;
 
+    pub(crate) type BitOrder = BitField<1, 0, u32>;
+}
+
+impl DataConfig {
     /// Set the bit order in each data word.
     #[inline]
     pub const fn set_bit_order(self, val: BitOrder) -> Self {
+        let field = data_config::BitOrder::from(self.0);
         match val {
-            BitOrder::LsbFirst => Self(self.0 & !Self::BIT_ORDER),
-            BitOrder::MsbFirst => Self(self.0 | Self::BIT_ORDER),
+            BitOrder::LsbFirst => Self(field.disable()),
+            BitOrder::MsbFirst => Self(field.enable()),
         }
     }
     /// Get the bit order in each data word.
     #[inline]
     pub const fn bit_order(self) -> BitOrder {
-        if self.0 & Self::BIT_ORDER == 0 {
+        if !data_config::BitOrder::from(self.0).is_enabled() {
             BitOrder::LsbFirst
         } else {
             BitOrder::MsbFirst
@@ -535,6 +437,99 @@ impl Default for DataConfig {
     }
 }
 
+/// Receive timeout configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct ReceiveTimeout(u32);
+
+mod receive_timeout {
+    use crate::BitField;
+
+    pub(crate) type Value = BitField<8, 0, u32>;
+}
+
+impl ReceiveTimeout {
+    register_field!(
+        set_timeout,
+        timeout,
+        u8,
+        receive_timeout::Value,
+        "idle time in bit periods before receive timeout interrupt is raised"
+    );
+}
+
+impl Default for ReceiveTimeout {
+    #[inline]
+    fn default() -> Self {
+        Self(0x0000_000f)
+    }
+}
+
+/// Software override register for transmit and request-to-send signals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct SoftwareMode(u32);
+
+mod software_mode {
+    use crate::BitField;
+
+    pub(crate) type TxdSoftware = BitField<1, 0, u32>;
+    pub(crate) type TxdValue = BitField<1, 1, u32>;
+    pub(crate) type RtsSoftware = BitField<1, 2, u32>;
+    pub(crate) type RtsValue = BitField<1, 3, u32>;
+}
+
+impl SoftwareMode {
+    register_flag! {
+        /// Drive transmit signal from software value instead of the transmitter.
+        enable_txd_software,
+        /// Drive transmit signal from the transmitter.
+        disable_txd_software,
+        /// Check if transmit signal is driven by software.
+        is_txd_software_enabled,
+        software_mode::TxdSoftware
+    }
+    /// Set software value of transmit signal.
+    #[inline]
+    pub const fn set_txd_value(self, high: bool) -> Self {
+        let field = software_mode::TxdValue::from(self.0);
+        if high {
+            Self(field.enable())
+        } else {
+            Self(field.disable())
+        }
+    }
+    /// Get software value of transmit signal.
+    #[inline]
+    pub const fn txd_value(self) -> bool {
+        software_mode::TxdValue::from(self.0).is_enabled()
+    }
+    register_flag! {
+        /// Drive request-to-send signal from software value instead of the receiver.
+        enable_rts_software,
+        /// Drive request-to-send signal from the receiver.
+        disable_rts_software,
+        /// Check if request-to-send signal is driven by software.
+        is_rts_software_enabled,
+        software_mode::RtsSoftware
+    }
+    /// Set software value of request-to-send signal.
+    #[inline]
+    pub const fn set_rts_value(self, high: bool) -> Self {
+        let field = software_mode::RtsValue::from(self.0);
+        if high {
+            Self(field.enable())
+        } else {
+            Self(field.disable())
+        }
+    }
+    /// Get software value of request-to-send signal.
+    #[inline]
+    pub const fn rts_value(self) -> bool {
+        software_mode::RtsValue::from(self.0).is_enabled()
+    }
+}
+
 /// Interrupt event.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
@@ -630,19 +625,23 @@ impl InterruptEnable {
 #[repr(transparent)]
 pub struct BusState(u32);
 
-impl BusState {
-    const TRANSMIT_BUSY: u32 = 1 << 0;
-    const RECEIVE_BUSY: u32 = 1 << 1;
+mod bus_state {
+    use crate::BitField;
 
+    pub(crate) type TransmitBusy = BitField<1, 0, u32>;
+    pub(crate) type ReceiveBusy = BitField<1, 1, u32>;
+}
+
+impl BusState {
     /// Get if UART transmit bus is busy.
     #[inline]
     pub const fn transmit_busy(self) -> bool {
-        self.0 & Self::TRANSMIT_BUSY != 0
+        bus_state::TransmitBusy::from(self.0).is_enabled()
     }
     /// Get if UART receive bus is busy.
     #[inline]
     pub const fn receive_busy(self) -> bool {
-        self.0 & Self::RECEIVE_BUSY != 0
+        bus_state::ReceiveBusy::from(self.0).is_enabled()
     }
 }
 
@@ -651,75 +650,63 @@ impl BusState {
 #[repr(transparent)]
 pub struct FifoConfig0(u32);
 
+mod fifo_config_0 {
+    use crate::BitField;
+
+    pub(crate) type TransmitDma = BitField<1, 0, u32>;
+    pub(crate) type ReceiveDma = BitField<1, 1, u32>;
+    pub(crate) type TransmitFifoClear = BitField<1, 2, u32>;
+    pub(crate) type ReceiveFifoClear = BitField<1, 3, u32>;
+    pub(crate) type TransmitFifoOverflow = BitField<1, 4, u32>;
+    pub(crate) type TransmitFifoUnderflow = BitField<1, 5, u32>;
+    pub(crate) type ReceiveFifoOverflow = BitField<1, 6, u32>;
+    pub(crate) type ReceiveFifoUnderflow = BitField<1, 7, u32>;
+}
+
 impl FifoConfig0 {
-    const TRANSMIT_DMA_ENABLE: u32 = 1 << 0;
-    const RECEIVE_DMA_ENABLE: u32 = 1 << 1;
-    const TRANSMIT_FIFO_CLEAR: u32 = 1 << 2;
-    const RECEIVE_FIFO_CLEAR: u32 = 1 << 3;
-    const TRANSMIT_FIFO_OVERFLOW: u32 = 1 << 4;
-    const TRANSMIT_FIFO_UNDERFLOW: u32 = 1 << 5;
-    const RECEIVE_FIFO_OVERFLOW: u32 = 1 << 6;
-    const RECEIVE_FIFO_UNDERFLOW: u32 = 1 << 7;
-
-    /// Enable transmit DMA.
-    #[inline]
-    pub const fn enable_transmit_dma(self) -> Self {
-        Self(self.0 | Self::TRANSMIT_DMA_ENABLE)
-    }
-    /// Disable transmit DMA.
-    #[inline]
-    pub const fn disable_transmit_dma(self) -> Self {
-        Self(self.0 & !Self::TRANSMIT_DMA_ENABLE)
-    }
-    /// Check if transmit DMA is enabled.
-    #[inline]
-    pub const fn is_transmit_dma_enabled(self) -> bool {
-        self.0 & Self::TRANSMIT_DMA_ENABLE != 0
-    }
-    /// Enable receive DMA.
-    #[inline]
-    pub const fn enable_receive_dma(self) -> Self {
-        Self(self.0 | Self::RECEIVE_DMA_ENABLE)
-    }
-    /// Disable receive DMA.
-    #[inline]
-    pub const fn disable_receive_dma(self) -> Self {
-        Self(self.0 & !Self::RECEIVE_DMA_ENABLE)
-    }
-    /// Check if receive DMA is enabled.
-    #[inline]
-    pub const fn is_receive_dma_enabled(self) -> bool {
-        self.0 & Self::RECEIVE_DMA_ENABLE != 0
-    }
+    register_flag!(
+        enable_transmit_dma,
+        disable_transmit_dma,
+        is_transmit_dma_enabled,
+        fifo_config_0::TransmitDma,
+        "transmit DMA"
+    );
+    register_flag!(
+        enable_receive_dma,
+        disable_receive_dma,
+        is_receive_dma_enabled,
+        fifo_config_0::ReceiveDma,
+        "receive DMA"
+    );
     /// Clear transmit FIFO.
     #[inline]
     pub const fn clear_transmit_fifo(self) -> Self {
-        Self(self.0 | Self::TRANSMIT_FIFO_CLEAR)
+        Self(fifo_config_0::TransmitFifoClear::from(self.0).enable())
     }
     /// Clear receive FIFO.
     #[inline]
     pub const fn clear_receive_fifo(self) -> Self {
-        Self(self.0 | Self::RECEIVE_FIFO_CLEAR)
+        Self(fifo_config_0::ReceiveFifoClear::from(self.0).enable())
     }
     /// Check if transmit FIFO is overflow.
     #[inline]
     pub const fn transmit_fifo_overflow(self) -> bool {
-        self.0 & Self::TRANSMIT_FIFO_OVERFLOW != 0
+        fifo_config_0::TransmitFifoOverflow::from(self.0).is_enabled()
     }
     /// Check if transmit FIFO is underflow.
     #[inline]
     pub const fn transmit_fifo_underflow(self) -> bool {
-        self.0 & Self::TRANSMIT_FIFO_UNDERFLOW != 0
+        fifo_config_0::TransmitFifoUnderflow::from(self.0).is_enabled()
     }
     /// Check if receive FIFO is overflow.
     #[inline]
     pub const fn receive_fifo_overflow(self) -> bool {
-        self.0 & Self::RECEIVE_FIFO_OVERFLOW != 0
+        fifo_config_0::ReceiveFifoOverflow::from(self.0).is_enabled()
     }
     /// Check if receive FIFO is underflow.
     #[inline]
     pub const fn receive_fifo_underflow(self) -> bool {
-        self.0 & Self::RECEIVE_FIFO_UNDERFLOW != 0
+        fifo_config_0::ReceiveFifoUnderflow::from(self.0).is_enabled()
     }
 }
 
@@ -728,41 +715,47 @@ impl FifoConfig0 {
 #[repr(transparent)]
 pub struct FifoConfig1(u32);
 
-impl FifoConfig1 {
-    const TRANSMIT_COUNT: u32 = 0x3f;
-    const RECEIVE_COUNT: u32 = 0x3f << 8;
-    const TRANSMIT_THRESHOLD: u32 = 0x1f << 16;
-    const RECEIVE_THRESHOLD: u32 = 0x1f << 24;
+mod fifo_config_1 {
+    use crate::BitField;
 
+    pub(crate) type TransmitCount = BitField<6, 0, u32>;
+    pub(crate) type ReceiveCount = BitField<6, 8, u32>;
+    pub(crate) type TransmitThreshold = BitField<5, 16, u32>;
+    pub(crate) type ReceiveThreshold = BitField<5, 24, u32>;
+}
+
+impl FifoConfig1 {
     /// Get number of empty spaces remained in transmit FIFO queue.
     #[inline]
     pub const fn transmit_available_bytes(self) -> u8 {
-        (self.0 & Self::TRANSMIT_COUNT) as u8
+        fifo_config_1::TransmitCount::from(self.0).get() as u8
     }
     /// Get number of available bytes received in receive FIFO queue.
     #[inline]
     pub const fn receive_available_bytes(self) -> u8 {
-        ((self.0 & Self::RECEIVE_COUNT) >> 8) as u8
+        fifo_config_1::ReceiveCount::from(self.0).get() as u8
     }
     /// Set transmit FIFO threshold.
     #[inline]
     pub const fn set_transmit_threshold(self, val: u8) -> Self {
-        Self(self.0 & !Self::TRANSMIT_THRESHOLD | ((val as u32) << 16))
+        let field = fifo_config_1::TransmitThreshold::from(self.0);
+        Self(self.0 & !(field.get_mask() as u32) | ((val as u32) << field.get_shift()))
     }
     /// Get transmit FIFO threshold.
     #[inline]
     pub const fn transmit_threshold(self) -> u8 {
-        ((self.0 & Self::TRANSMIT_THRESHOLD) >> 16) as u8
+        fifo_config_1::TransmitThreshold::from(self.0).get() as u8
     }
     /// Set receive FIFO threshold.
     #[inline]
     pub const fn set_receive_threshold(self, val: u8) -> Self {
-        Self(self.0 & !Self::RECEIVE_THRESHOLD | ((val as u32) << 24))
+        let field = fifo_config_1::ReceiveThreshold::from(self.0);
+        Self(self.0 & !(field.get_mask() as u32) | ((val as u32) << field.get_shift()))
     }
     /// Get receive FIFO threshold.
     #[inline]
     pub const fn receive_threshold(self) -> u8 {
-        ((self.0 & Self::RECEIVE_THRESHOLD) >> 24) as u8
+        fifo_config_1::ReceiveThreshold::from(self.0).get() as u8
     }
 }
 
@@ -779,6 +772,8 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, receive_config), 0x4);
         assert_eq!(offset_of!(RegisterBlock, bit_period), 0x08);
         assert_eq!(offset_of!(RegisterBlock, data_config), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, receive_timeout), 0x18);
+        assert_eq!(offset_of!(RegisterBlock, software_mode), 0x1c);
         assert_eq!(offset_of!(RegisterBlock, interrupt_state), 0x20);
         assert_eq!(offset_of!(RegisterBlock, interrupt_mask), 0x24);
         assert_eq!(offset_of!(RegisterBlock, interrupt_clear), 0x28);
@@ -1042,6 +1037,44 @@ mod tests {
         assert_eq!(default.bit_order(), super::BitOrder::LsbFirst);
     }
 
+    #[test]
+    fn struct_receive_timeout_functions() {
+        let mut val = super::ReceiveTimeout(0x0);
+        val = val.set_timeout(0x50);
+        assert_eq!(val.0, 0x0000_0050);
+        assert_eq!(val.timeout(), 0x50);
+
+        let default = super::ReceiveTimeout::default();
+        assert_eq!(default.timeout(), 15);
+    }
+
+    #[test]
+    fn struct_software_mode_functions() {
+        let mut val = super::SoftwareMode(0x0);
+
+        val = val.enable_txd_software();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_txd_software_enabled());
+        val = val.set_txd_value(true);
+        assert_eq!(val.0, 0x0000_0003);
+        assert!(val.txd_value());
+        val = val.set_txd_value(false).disable_txd_software();
+        assert_eq!(val.0, 0x0000_0000);
+
+        val = val.enable_rts_software();
+        assert_eq!(val.0, 0x0000_0004);
+        assert!(val.is_rts_software_enabled());
+        val = val.set_rts_value(true);
+        assert_eq!(val.0, 0x0000_000c);
+        assert!(val.rts_value());
+        val = val.set_rts_value(false).disable_rts_software();
+        assert_eq!(val.0, 0x0000_0000);
+
+        let default = super::SoftwareMode::default();
+        assert!(!default.is_txd_software_enabled());
+        assert!(!default.is_rts_software_enabled());
+    }
+
     #[test]
     fn struct_interrupt_state_functions() {
         let val: super::InterruptState = super::InterruptState(0x0);
@@ -0,0 +1,176 @@
+use super::{
+    BlockingSerial, RegisterBlock, Rs485Error, uart_flush, uart_flush_nb, uart_read, uart_read_nb,
+    uart_wait_transmit_idle, uart_write, uart_write_nb,
+};
+use core::{convert::Infallible, fmt::Debug, ops::Deref};
+use embedded_hal::digital::OutputPin;
+
+/// Driver-enable signal of an RS-485 transceiver.
+pub trait DriverEnable {
+    /// Error type of driver-enable signal.
+    type Error: Debug;
+    /// Assert driver-enable signal to take over the bus.
+    fn assert(&mut self, uart: &RegisterBlock) -> Result<(), Self::Error>;
+    /// Release driver-enable signal to give back the bus.
+    fn release(&mut self, uart: &RegisterBlock) -> Result<(), Self::Error>;
+}
+
+/// Driver-enable on Request-to-Send signal, driven high by software during transmission.
+///
+/// The Request-to-Send signal of this UART must be multiplexed to a pad connected to the transceiver.
+#[derive(Debug)]
+pub struct RtsDriverEnable;
+
+impl DriverEnable for RtsDriverEnable {
+    type Error = Infallible;
+    #[inline]
+    fn assert(&mut self, uart: &RegisterBlock) -> Result<(), Self::Error> {
+        unsafe {
+            uart.software_mode
+                .modify(|val| val.set_rts_value(true).enable_rts_software())
+        };
+        Ok(())
+    }
+    #[inline]
+    fn release(&mut self, uart: &RegisterBlock) -> Result<(), Self::Error> {
+        unsafe {
+            uart.software_mode
+                .modify(|val| val.set_rts_value(false).enable_rts_software())
+        };
+        Ok(())
+    }
+}
+
+/// Driver-enable on an arbitrary output pin, driven high during transmission.
+#[derive(Debug)]
+pub struct GpioDriverEnable<P>(pub P);
+
+impl<P: OutputPin> DriverEnable for GpioDriverEnable<P> {
+    type Error = P::Error;
+    #[inline]
+    fn assert(&mut self, _uart: &RegisterBlock) -> Result<(), Self::Error> {
+        self.0.set_high()
+    }
+    #[inline]
+    fn release(&mut self, _uart: &RegisterBlock) -> Result<(), Self::Error> {
+        self.0.set_low()
+    }
+}
+
+/// Half-duplex RS-485 serial over a blocking serial peripheral.
+///
+/// Driver-enable is asserted on the first write, and released after the last word has
+/// completely left the transmitter, either on flush or before the next read.
+pub struct Rs485<UART, PADS, DE> {
+    serial: BlockingSerial<UART, PADS>,
+    de: DE,
+    transmitting: bool,
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS, DE: DriverEnable> Rs485<UART, PADS, DE> {
+    /// Creates an RS-485 serial from blocking serial and driver-enable signal.
+    #[inline]
+    pub fn new(
+        serial: BlockingSerial<UART, PADS>,
+        mut de: DE,
+    ) -> Result<Self, Rs485Error<DE::Error>> {
+        de.release(&serial.uart).map_err(Rs485Error::DriverEnable)?;
+        Ok(Self {
+            serial,
+            de,
+            transmitting: false,
+        })
+    }
+
+    /// Release RS-485 serial and return its blocking serial and driver-enable signal.
+    #[inline]
+    pub fn free(self) -> (BlockingSerial<UART, PADS>, DE) {
+        (self.serial, self.de)
+    }
+
+    #[inline]
+    fn start_transmit(&mut self) -> Result<(), Rs485Error<DE::Error>> {
+        if !self.transmitting {
+            self.de
+                .assert(&self.serial.uart)
+                .map_err(Rs485Error::DriverEnable)?;
+            self.transmitting = true;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn end_transmit(&mut self) -> Result<(), Rs485Error<DE::Error>> {
+        self.de
+            .release(&self.serial.uart)
+            .map_err(Rs485Error::DriverEnable)?;
+        self.transmitting = false;
+        Ok(())
+    }
+}
+
+impl<UART, PADS, DE: DriverEnable> embedded_io::ErrorType for Rs485<UART, PADS, DE> {
+    type Error = Rs485Error<DE::Error>;
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS, DE: DriverEnable> embedded_io::Write
+    for Rs485<UART, PADS, DE>
+{
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.start_transmit()?;
+        Ok(uart_write(&self.serial.uart, buf)?)
+    }
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        uart_flush(&self.serial.uart)?;
+        uart_wait_transmit_idle(&self.serial.uart);
+        self.end_transmit()
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS, DE: DriverEnable> embedded_io::Read
+    for Rs485<UART, PADS, DE>
+{
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if self.transmitting {
+            embedded_io::Write::flush(self)?;
+        }
+        Ok(uart_read(&self.serial.uart, buf)?)
+    }
+}
+
+impl<UART, PADS, DE: DriverEnable> embedded_hal_nb::serial::ErrorType for Rs485<UART, PADS, DE> {
+    type Error = Rs485Error<DE::Error>;
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS, DE: DriverEnable> embedded_hal_nb::serial::Write
+    for Rs485<UART, PADS, DE>
+{
+    #[inline]
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        self.start_transmit()?;
+        uart_write_nb(&self.serial.uart, word).map_err(|e| e.map(Rs485Error::Serial))
+    }
+    #[inline]
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        uart_flush_nb(&self.serial.uart).map_err(|e| e.map(Rs485Error::Serial))?;
+        if self.serial.uart.bus_state.read().transmit_busy() {
+            return Err(nb::Error::WouldBlock);
+        }
+        Ok(self.end_transmit()?)
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS, DE: DriverEnable> embedded_hal_nb::serial::Read
+    for Rs485<UART, PADS, DE>
+{
+    #[inline]
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        if self.transmitting {
+            embedded_hal_nb::serial::Write::flush(self)?;
+        }
+        uart_read_nb(&self.serial.uart).map_err(|e| e.map(Rs485Error::Serial))
+    }
+}
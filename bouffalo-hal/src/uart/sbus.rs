@@ -0,0 +1,213 @@
+use super::{
+    BlockingSerial, Config, ConfigError, Interrupt, InterruptClear, Pads, Parity, RegisterBlock,
+    SbusError, StopBits, uart_read_nb,
+};
+use crate::clocks::Clocks;
+use core::ops::Deref;
+use embedded_time::rate::Extensions;
+
+/// Length of an SBUS frame in bytes.
+pub const SBUS_FRAME_LEN: usize = 25;
+
+const HEADER: u8 = 0x0f;
+// SBUS2 receivers cycle telemetry slots in the upper footer bits.
+const FOOTER: u8 = 0x00;
+const FOOTER_SBUS2: u8 = 0x04;
+
+const CHANNEL_17: u8 = 1 << 0;
+const CHANNEL_18: u8 = 1 << 1;
+const FRAME_LOST: u8 = 1 << 2;
+const FAILSAFE: u8 = 1 << 3;
+
+/// Serial configuration of SBUS, 100000 Bd with 8 data bits, even parity and 2 stop bits.
+///
+/// Frames are separated by at least 3 ms of idle line, which receive timeout detects.
+#[inline]
+pub fn sbus_config() -> Config {
+    Config {
+        receive_timeout: 40,
+        ..Config::default()
+            .set_baudrate(100_000.Bd())
+            .set_parity(Parity::Even)
+            .set_stop_bits(StopBits::Two)
+    }
+}
+
+/// Decoded SBUS frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct SbusFrame {
+    /// Proportional channels 1 to 16, 11-bit values.
+    pub channels: [u16; 16],
+    /// Digital channel 17.
+    pub channel_17: bool,
+    /// Digital channel 18.
+    pub channel_18: bool,
+    /// Receiver missed a frame from the transmitter.
+    pub frame_lost: bool,
+    /// Receiver lost the transmitter and reports failsafe values.
+    pub failsafe: bool,
+}
+
+impl SbusFrame {
+    /// Decode frame from raw bytes.
+    #[inline]
+    pub const fn parse(raw: &[u8; SBUS_FRAME_LEN]) -> Result<Self, SbusError> {
+        if raw[0] != HEADER {
+            return Err(SbusError::Header(raw[0]));
+        }
+        let footer = raw[SBUS_FRAME_LEN - 1];
+        if footer != FOOTER && footer & 0x0f != FOOTER_SBUS2 {
+            return Err(SbusError::Footer(footer));
+        }
+        // Channels are packed LSB first, 11 bits each, in the 22 bytes after header.
+        let mut channels = [0u16; 16];
+        let mut i = 0;
+        while i < 16 {
+            let bit = i * 11;
+            let byte = 1 + bit / 8;
+            let word =
+                raw[byte] as u32 | (raw[byte + 1] as u32) << 8 | (raw[byte + 2] as u32) << 16;
+            channels[i] = ((word >> (bit % 8)) & 0x7ff) as u16;
+            i += 1;
+        }
+        let flags = raw[23];
+        Ok(Self {
+            channels,
+            channel_17: flags & CHANNEL_17 != 0,
+            channel_18: flags & CHANNEL_18 != 0,
+            frame_lost: flags & FRAME_LOST != 0,
+            failsafe: flags & FAILSAFE != 0,
+        })
+    }
+}
+
+/// SBUS receiver over a blocking serial peripheral.
+///
+/// SBUS is transmitted with inverted polarity, idle low. This UART only inverts its signals
+/// in IR mode, which also changes line coding, so receive pad must be driven through an
+/// external inverter, as on most flight controller boards.
+pub struct Sbus<UART, PADS> {
+    serial: BlockingSerial<UART, PADS>,
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> Sbus<UART, PADS> {
+    /// Creates an SBUS receiver with [`sbus_config`].
+    #[inline]
+    pub fn freerun<const I: usize>(
+        uart: UART,
+        pads: PADS,
+        clocks: &Clocks,
+    ) -> Result<Self, ConfigError>
+    where
+        PADS: Pads<I>,
+    {
+        let serial = BlockingSerial::freerun(uart, sbus_config(), pads, clocks)?;
+        Ok(Self { serial })
+    }
+
+    /// Wait for next complete frame and decode it.
+    ///
+    /// Bursts between idle gaps which are not exactly one frame long, or which contain
+    /// parity errors, are skipped; frames with wrong header or footer are returned as errors.
+    #[inline]
+    pub fn read_frame(&mut self) -> Result<SbusFrame, SbusError> {
+        let raw = sbus_read_raw(&self.serial.uart);
+        SbusFrame::parse(&raw)
+    }
+
+    /// Release SBUS receiver and return its blocking serial.
+    #[inline]
+    pub fn free(self) -> BlockingSerial<UART, PADS> {
+        self.serial
+    }
+}
+
+#[inline]
+fn sbus_read_raw(uart: &RegisterBlock) -> [u8; SBUS_FRAME_LEN] {
+    let mut raw = [0u8; SBUS_FRAME_LEN];
+    let mut len = 0;
+    sbus_clear_flags(uart);
+    loop {
+        if let Ok(word) = uart_read_nb(uart) {
+            if len < SBUS_FRAME_LEN {
+                raw[len] = word;
+            }
+            len += 1;
+            continue;
+        }
+        let state = uart.interrupt_state.read();
+        if !state.has_interrupt(Interrupt::ReceiveTimeout) {
+            core::hint::spin_loop();
+            continue;
+        }
+        let parity_error = state.has_interrupt(Interrupt::ReceiveParityError);
+        sbus_clear_flags(uart);
+        if len == SBUS_FRAME_LEN && !parity_error {
+            return raw;
+        }
+        len = 0;
+    }
+}
+
+#[inline]
+fn sbus_clear_flags(uart: &RegisterBlock) {
+    unsafe {
+        uart.interrupt_clear.write(
+            InterruptClear::default()
+                .clear_interrupt(Interrupt::ReceiveTimeout)
+                .clear_interrupt(Interrupt::ReceiveParityError),
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SBUS_FRAME_LEN, SbusFrame};
+    use crate::uart::SbusError;
+
+    // Pack 11-bit channels LSB first like an SBUS transmitter.
+    fn encode(channels: &[u16; 16], flags: u8, footer: u8) -> [u8; SBUS_FRAME_LEN] {
+        let mut raw = [0u8; SBUS_FRAME_LEN];
+        raw[0] = 0x0f;
+        for (i, &value) in channels.iter().enumerate() {
+            for b in 0..11 {
+                if value & (1 << b) != 0 {
+                    let bit = i * 11 + b;
+                    raw[1 + bit / 8] |= 1 << (bit % 8);
+                }
+            }
+        }
+        raw[23] = flags;
+        raw[24] = footer;
+        raw
+    }
+
+    #[test]
+    fn struct_sbus_frame() {
+        let mut channels = [0u16; 16];
+        for (i, value) in channels.iter_mut().enumerate() {
+            *value = 172 + i as u16 * 109;
+        }
+        channels[15] = 0x7ff;
+        let frame = SbusFrame::parse(&encode(&channels, 0x0d, 0x00)).unwrap();
+        assert_eq!(frame.channels, channels);
+        assert!(frame.channel_17);
+        assert!(!frame.channel_18);
+        assert!(frame.frame_lost);
+        assert!(frame.failsafe);
+
+        let frame = SbusFrame::parse(&encode(&[992; 16], 0x02, 0x14)).unwrap();
+        assert_eq!(frame.channels, [992; 16]);
+        assert!(!frame.channel_17);
+        assert!(frame.channel_18);
+        assert!(!frame.frame_lost);
+        assert!(!frame.failsafe);
+
+        let mut raw = encode(&[992; 16], 0, 0);
+        raw[0] = 0x00;
+        assert_eq!(SbusFrame::parse(&raw), Err(SbusError::Header(0x00)));
+        raw[0] = 0x0f;
+        raw[24] = 0x55;
+        assert_eq!(SbusFrame::parse(&raw), Err(SbusError::Footer(0x55)));
+    }
+}
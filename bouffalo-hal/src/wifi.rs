@@ -0,0 +1,462 @@
+//! Wi-Fi Media Access Control low-level registers.
+//!
+//! This module covers what an open Wi-Fi driver needs before exchanging frames: powering
+//! up the radio frequency front end in order, programming the station MAC address read
+//! from eFuse, moving the MAC between idle and active states, and servicing the `wl_all`
+//! interrupt. Frame exchange and PHY calibration are left to higher level drivers.
+
+pub mod rf;
+
+use core::ops::Deref;
+use volatile_register::{RO, RW, WO};
+
+/// Wi-Fi Media Access Control registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Hardware signature register.
+    pub signature: RO<u32>,
+    /// Hardware version register.
+    pub version: RO<u32>,
+    _reserved0: [u8; 0x8],
+    /// Station address registers, low and high words.
+    pub mac_address: [RW<u32>; 2],
+    /// Basic service set identifier registers, low and high words.
+    pub bssid: [RW<u32>; 2],
+    _reserved1: [u8; 0x18],
+    /// MAC state control register.
+    pub state_control: RW<StateControl>,
+    _reserved2: [u8; 0x30],
+    /// General interrupt state register.
+    pub interrupt_state: RO<InterruptState>,
+    /// General interrupt acknowledge register.
+    pub interrupt_clear: WO<InterruptState>,
+    /// General interrupt enable register.
+    pub interrupt_enable: RW<InterruptEnable>,
+}
+
+/// MAC state control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct StateControl(u32);
+
+impl StateControl {
+    const CURRENT_STATE: u32 = 0xf << 0;
+    const NEXT_STATE: u32 = 0xf << 4;
+
+    /// Get state the MAC is in.
+    #[inline]
+    pub const fn current_state(self) -> Option<State> {
+        State::from_bits(self.0 & Self::CURRENT_STATE)
+    }
+    /// Request the MAC to move into state.
+    #[inline]
+    pub const fn set_next_state(self, val: State) -> Self {
+        Self((self.0 & !Self::NEXT_STATE) | ((val as u32) << 4))
+    }
+    /// Get requested state.
+    #[inline]
+    pub const fn next_state(self) -> Option<State> {
+        State::from_bits((self.0 & Self::NEXT_STATE) >> 4)
+    }
+}
+
+/// MAC operating state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum State {
+    /// Frame exchange stopped, registers may be reprogrammed.
+    Idle = 0,
+    /// Low power state between beacons.
+    Doze = 2,
+    /// Frame exchange running.
+    Active = 3,
+}
+
+impl State {
+    #[inline]
+    const fn from_bits(bits: u32) -> Option<Self> {
+        match bits {
+            0 => Some(State::Idle),
+            2 => Some(State::Doze),
+            3 => Some(State::Active),
+            _ => None,
+        }
+    }
+}
+
+/// General interrupt state register.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct InterruptState(u32);
+
+impl InterruptState {
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub const fn has_interrupt(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32)) != 0
+    }
+    /// Acknowledge interrupt flag.
+    #[inline]
+    pub const fn clear_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32)))
+    }
+}
+
+/// General interrupt enable register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct InterruptEnable(u32);
+
+impl InterruptEnable {
+    const MASTER: u32 = 1 << 31;
+
+    /// Enable interrupt.
+    #[inline]
+    pub const fn enable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32)))
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub const fn disable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 & !(1 << (val as u32)))
+    }
+    /// Check if interrupt is enabled.
+    #[inline]
+    pub const fn is_interrupt_enabled(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32)) != 0
+    }
+    /// Route enabled interrupts to the `wl_all` interrupt line.
+    #[inline]
+    pub const fn enable_master(self) -> Self {
+        Self(self.0 | Self::MASTER)
+    }
+    /// Mask all interrupts.
+    #[inline]
+    pub const fn disable_master(self) -> Self {
+        Self(self.0 & !Self::MASTER)
+    }
+    /// Check if interrupts are routed to the interrupt line.
+    #[inline]
+    pub const fn is_master_enabled(self) -> bool {
+        self.0 & Self::MASTER != 0
+    }
+}
+
+/// General MAC interrupt event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Interrupt {
+    /// MAC reached idle state.
+    Idle = 0,
+    /// Target beacon transmission time is near.
+    Beacon = 1,
+    /// Frame received.
+    Receive = 2,
+    /// Frame transmission completed.
+    Transmit = 3,
+    /// Hardware timer expired.
+    Timer = 4,
+    /// Internal hardware error, MAC must be reset.
+    HardwareError = 5,
+}
+
+impl Interrupt {
+    const ALL: [Interrupt; 6] = [
+        Interrupt::HardwareError,
+        Interrupt::Idle,
+        Interrupt::Beacon,
+        Interrupt::Receive,
+        Interrupt::Transmit,
+        Interrupt::Timer,
+    ];
+}
+
+/// Station MAC address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MacAddress(pub [u8; 6]);
+
+impl MacAddress {
+    /// eFuse word offset of the factory programmed address.
+    pub const EFUSE_WORD_OFFSET: u32 = 0x14 / 4;
+
+    /// Decode factory programmed address from its two eFuse words.
+    ///
+    /// Returns `None` on blank eFuse, or when the address is not a valid station address.
+    #[inline]
+    pub const fn from_efuse(words: [u32; 2]) -> Option<Self> {
+        let low = words[0].to_le_bytes();
+        let high = words[1].to_le_bytes();
+        let address = Self([low[0], low[1], low[2], low[3], high[0], high[1]]);
+        if address.is_valid() {
+            Some(address)
+        } else {
+            None
+        }
+    }
+    /// Check if address is neither blank nor multicast.
+    #[inline]
+    pub const fn is_valid(&self) -> bool {
+        let blank = matches!(
+            self.0,
+            [0, 0, 0, 0, 0, 0] | [0xff, 0xff, 0xff, 0xff, 0xff, 0xff]
+        );
+        !blank && self.0[0] & 0x01 == 0
+    }
+    #[inline]
+    const fn to_words(self) -> [u32; 2] {
+        let a = self.0;
+        [
+            u32::from_le_bytes([a[0], a[1], a[2], a[3]]),
+            u16::from_le_bytes([a[4], a[5]]) as u32,
+        ]
+    }
+    #[inline]
+    const fn from_words(words: [u32; 2]) -> Self {
+        let low = words[0].to_le_bytes();
+        let high = words[1].to_le_bytes();
+        Self([low[0], low[1], low[2], low[3], high[0], high[1]])
+    }
+}
+
+/// Read factory programmed MAC address from eFuse with ROM driver.
+///
+/// # Safety
+///
+/// eFuse controller must not be in use by other code during this call.
+#[cfg(feature = "rom-api")]
+#[inline]
+pub unsafe fn read_efuse_mac_address(
+    f: &crate::rom_api::RomFunction<crate::rom_api::EfuseReadFn>,
+) -> Option<MacAddress> {
+    let mut words = [0u32; 2];
+    unsafe { crate::rom_api::efuse_read(f, MacAddress::EFUSE_WORD_OFFSET, &mut words) };
+    MacAddress::from_efuse(words)
+}
+
+/// Errors on Wi-Fi power up and state changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// Front end regulator did not become ready.
+    LdoTimeout,
+    /// Radio frequency synthesizer did not lock.
+    PllTimeout,
+    /// MAC did not reach requested state.
+    StateTimeout,
+    /// MAC must be idle for this operation.
+    NotIdle,
+    /// Address is blank or multicast.
+    InvalidAddress,
+}
+
+/// Polling rounds before a power up or state change step times out.
+const MAX_RETRY: usize = 100_000;
+
+/// Managed Wi-Fi MAC with its radio frequency front end.
+pub struct WifiMac<MAC, RF> {
+    mac: MAC,
+    rf: RF,
+}
+
+impl<MAC: Deref<Target = RegisterBlock>, RF: Deref<Target = rf::RegisterBlock>> WifiMac<MAC, RF> {
+    /// Power up front end and bring the MAC to idle state.
+    ///
+    /// Regulator, synthesizer, then receive and transmit chains are powered in order,
+    /// waiting for each stage to settle. MAC interrupts stay masked.
+    #[inline]
+    pub fn new(mac: MAC, rf: RF) -> Result<Self, Error> {
+        unsafe {
+            mac.interrupt_enable.modify(|v| v.disable_master());
+            rf.power.modify(|v| v.enable_ldo());
+        }
+        wait(|| rf.status.read().is_ldo_ready(), Error::LdoTimeout)?;
+        unsafe { rf.power.modify(|v| v.enable_pll()) };
+        wait(|| rf.status.read().is_pll_locked(), Error::PllTimeout)?;
+        unsafe { rf.power.modify(|v| v.enable_receive().enable_transmit()) };
+        let mut ans = Self { mac, rf };
+        ans.set_state(State::Idle)?;
+        Ok(ans)
+    }
+    /// Move the MAC into state, waiting until it is reached.
+    #[inline]
+    pub fn set_state(&mut self, state: State) -> Result<(), Error> {
+        unsafe { self.mac.state_control.modify(|v| v.set_next_state(state)) };
+        wait(
+            || self.mac.state_control.read().current_state() == Some(state),
+            Error::StateTimeout,
+        )
+    }
+    /// Get current MAC state, `None` while it is changing.
+    #[inline]
+    pub fn state(&self) -> Option<State> {
+        self.mac.state_control.read().current_state()
+    }
+    /// Program station address, the MAC must be idle.
+    #[inline]
+    pub fn set_mac_address(&mut self, address: MacAddress) -> Result<(), Error> {
+        if !address.is_valid() {
+            return Err(Error::InvalidAddress);
+        }
+        if self.state() != Some(State::Idle) {
+            return Err(Error::NotIdle);
+        }
+        let [low, high] = address.to_words();
+        unsafe {
+            self.mac.mac_address[0].write(low);
+            self.mac.mac_address[1].write(high);
+        }
+        Ok(())
+    }
+    /// Get programmed station address.
+    #[inline]
+    pub fn mac_address(&self) -> MacAddress {
+        MacAddress::from_words([
+            self.mac.mac_address[0].read(),
+            self.mac.mac_address[1].read(),
+        ])
+    }
+    /// Enable interrupt on the `wl_all` interrupt line.
+    #[inline]
+    pub fn enable_interrupt(&mut self, val: Interrupt) {
+        unsafe {
+            self.mac
+                .interrupt_enable
+                .modify(|v| v.enable_interrupt(val).enable_master())
+        };
+    }
+    /// Disable interrupt.
+    #[inline]
+    pub fn disable_interrupt(&mut self, val: Interrupt) {
+        unsafe {
+            self.mac
+                .interrupt_enable
+                .modify(|v| v.disable_interrupt(val))
+        };
+    }
+    /// Check if interrupt flag is set.
+    #[inline]
+    pub fn has_interrupt(&self, val: Interrupt) -> bool {
+        self.mac.interrupt_state.read().has_interrupt(val)
+    }
+    /// Clear interrupt flag.
+    #[inline]
+    pub fn clear_interrupt(&mut self, val: Interrupt) {
+        unsafe {
+            self.mac
+                .interrupt_clear
+                .write(InterruptState::default().clear_interrupt(val))
+        };
+    }
+    /// Claim a pending enabled interrupt, acknowledging it.
+    ///
+    /// Call this function in the `wl_all` interrupt handler until it returns `None`;
+    /// hardware errors are claimed first.
+    #[inline]
+    pub fn claim_interrupt(&mut self) -> Option<Interrupt> {
+        let state = self.mac.interrupt_state.read();
+        let enable = self.mac.interrupt_enable.read();
+        let irq = Interrupt::ALL
+            .into_iter()
+            .find(|&irq| state.has_interrupt(irq) && enable.is_interrupt_enabled(irq))?;
+        self.clear_interrupt(irq);
+        Some(irq)
+    }
+    /// Stop the MAC, power down front end and release peripherals.
+    #[inline]
+    pub fn free(mut self) -> (MAC, RF) {
+        unsafe { self.mac.interrupt_enable.modify(|v| v.disable_master()) };
+        let _ = self.set_state(State::Idle);
+        unsafe {
+            self.rf.power.modify(|v| {
+                v.disable_transmit()
+                    .disable_receive()
+                    .disable_pll()
+                    .disable_ldo()
+            })
+        };
+        (self.mac, self.rf)
+    }
+}
+
+#[inline]
+fn wait(mut f: impl FnMut() -> bool, error: Error) -> Result<(), Error> {
+    for _ in 0..MAX_RETRY {
+        if f() {
+            return Ok(());
+        }
+        core::hint::spin_loop();
+    }
+    Err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Interrupt, InterruptEnable, InterruptState, MacAddress, RegisterBlock, State, StateControl,
+    };
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, signature), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, version), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, mac_address), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, bssid), 0x18);
+        assert_eq!(offset_of!(RegisterBlock, state_control), 0x38);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_state), 0x6c);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_clear), 0x70);
+        assert_eq!(offset_of!(RegisterBlock, interrupt_enable), 0x74);
+    }
+
+    #[test]
+    fn struct_state_control_functions() {
+        let mut val = StateControl(0x0);
+        assert_eq!(val.current_state(), Some(State::Idle));
+
+        val = val.set_next_state(State::Active);
+        assert_eq!(val.0, 0x0000_0030);
+        assert_eq!(val.next_state(), Some(State::Active));
+        val = val.set_next_state(State::Doze);
+        assert_eq!(val.0, 0x0000_0020);
+        assert_eq!(val.next_state(), Some(State::Doze));
+
+        assert_eq!(StateControl(0x1).current_state(), None);
+        assert_eq!(StateControl(0x3).current_state(), Some(State::Active));
+    }
+
+    #[test]
+    fn struct_interrupt_functions() {
+        let mut val = InterruptEnable(0x0);
+
+        val = val.enable_interrupt(Interrupt::HardwareError);
+        assert_eq!(val.0, 0x0000_0020);
+        assert!(val.is_interrupt_enabled(Interrupt::HardwareError));
+        val = val.disable_interrupt(Interrupt::HardwareError);
+        assert!(!val.is_interrupt_enabled(Interrupt::HardwareError));
+
+        val = val.enable_master();
+        assert_eq!(val.0, 0x8000_0000);
+        assert!(val.is_master_enabled());
+        val = val.disable_master();
+        assert!(!val.is_master_enabled());
+
+        let val = InterruptState(0x0000_0004);
+        assert!(val.has_interrupt(Interrupt::Receive));
+        assert!(!val.has_interrupt(Interrupt::Transmit));
+        let val = InterruptState::default().clear_interrupt(Interrupt::Timer);
+        assert_eq!(val.0, 0x0000_0010);
+    }
+
+    #[test]
+    fn struct_mac_address_functions() {
+        let address = MacAddress([0x18, 0xb9, 0x05, 0x12, 0x34, 0x56]);
+        assert_eq!(address.to_words(), [0x1205_b918, 0x0000_5634]);
+        assert_eq!(MacAddress::from_words(address.to_words()), address);
+        assert_eq!(
+            MacAddress::from_efuse([0x1205_b918, 0xa5a5_5634]),
+            Some(address)
+        );
+        assert_eq!(MacAddress::from_efuse([0, 0]), None);
+        assert_eq!(MacAddress::from_efuse([0xffff_ffff, 0xffff]), None);
+        assert_eq!(MacAddress::from_efuse([0x0000_0001, 0]), None);
+    }
+}
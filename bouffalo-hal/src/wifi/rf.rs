@@ -0,0 +1,158 @@
+//! Radio frequency front end power control.
+
+use volatile_register::{RO, RW};
+
+/// Radio frequency front end registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Block power control register.
+    pub power: RW<Power>,
+    /// Front end state register.
+    pub status: RO<Status>,
+}
+
+/// Block power control register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Power(u32);
+
+impl Power {
+    const LDO: u32 = 1 << 0;
+    const PLL: u32 = 1 << 1;
+    const RECEIVE: u32 = 1 << 2;
+    const TRANSMIT: u32 = 1 << 3;
+
+    /// Power up front end regulator.
+    #[inline]
+    pub const fn enable_ldo(self) -> Self {
+        Self(self.0 | Self::LDO)
+    }
+    /// Power down front end regulator.
+    #[inline]
+    pub const fn disable_ldo(self) -> Self {
+        Self(self.0 & !Self::LDO)
+    }
+    /// Check if front end regulator is powered.
+    #[inline]
+    pub const fn is_ldo_enabled(self) -> bool {
+        self.0 & Self::LDO != 0
+    }
+    /// Power up radio frequency synthesizer.
+    #[inline]
+    pub const fn enable_pll(self) -> Self {
+        Self(self.0 | Self::PLL)
+    }
+    /// Power down radio frequency synthesizer.
+    #[inline]
+    pub const fn disable_pll(self) -> Self {
+        Self(self.0 & !Self::PLL)
+    }
+    /// Check if radio frequency synthesizer is powered.
+    #[inline]
+    pub const fn is_pll_enabled(self) -> bool {
+        self.0 & Self::PLL != 0
+    }
+    /// Power up receive chain.
+    #[inline]
+    pub const fn enable_receive(self) -> Self {
+        Self(self.0 | Self::RECEIVE)
+    }
+    /// Power down receive chain.
+    #[inline]
+    pub const fn disable_receive(self) -> Self {
+        Self(self.0 & !Self::RECEIVE)
+    }
+    /// Check if receive chain is powered.
+    #[inline]
+    pub const fn is_receive_enabled(self) -> bool {
+        self.0 & Self::RECEIVE != 0
+    }
+    /// Power up transmit chain.
+    #[inline]
+    pub const fn enable_transmit(self) -> Self {
+        Self(self.0 | Self::TRANSMIT)
+    }
+    /// Power down transmit chain.
+    #[inline]
+    pub const fn disable_transmit(self) -> Self {
+        Self(self.0 & !Self::TRANSMIT)
+    }
+    /// Check if transmit chain is powered.
+    #[inline]
+    pub const fn is_transmit_enabled(self) -> bool {
+        self.0 & Self::TRANSMIT != 0
+    }
+}
+
+/// Front end state register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Status(u32);
+
+impl Status {
+    const LDO_READY: u32 = 1 << 0;
+    const PLL_LOCKED: u32 = 1 << 1;
+
+    /// Check if front end regulator output is stable.
+    #[inline]
+    pub const fn is_ldo_ready(self) -> bool {
+        self.0 & Self::LDO_READY != 0
+    }
+    /// Check if radio frequency synthesizer is locked.
+    #[inline]
+    pub const fn is_pll_locked(self) -> bool {
+        self.0 & Self::PLL_LOCKED != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Power, RegisterBlock, Status};
+    use core::mem::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, power), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, status), 0x04);
+    }
+
+    #[test]
+    fn struct_power_functions() {
+        let mut val = Power(0x0);
+
+        val = val.enable_ldo();
+        assert_eq!(val.0, 0x0000_0001);
+        assert!(val.is_ldo_enabled());
+        val = val.disable_ldo();
+        assert!(!val.is_ldo_enabled());
+
+        val = val.enable_pll();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_pll_enabled());
+        val = val.disable_pll();
+        assert!(!val.is_pll_enabled());
+
+        val = val.enable_receive();
+        assert_eq!(val.0, 0x0000_0004);
+        assert!(val.is_receive_enabled());
+        val = val.disable_receive();
+        assert!(!val.is_receive_enabled());
+
+        val = val.enable_transmit();
+        assert_eq!(val.0, 0x0000_0008);
+        assert!(val.is_transmit_enabled());
+        val = val.disable_transmit();
+        assert_eq!(val.0, 0x0000_0000);
+        assert!(!val.is_transmit_enabled());
+    }
+
+    #[test]
+    fn struct_status_functions() {
+        let val = Status(0x0000_0003);
+        assert!(val.is_ldo_ready());
+        assert!(val.is_pll_locked());
+        let val = Status(0x0);
+        assert!(!val.is_ldo_ready());
+        assert!(!val.is_pll_locked());
+    }
+}
@@ -0,0 +1,8 @@
+use bouffalo_hal::gpio::PinMode;
+
+struct MyMode;
+
+// Pin modes are sealed in `bouffalo-hal`.
+impl PinMode for MyMode {}
+
+fn main() {}
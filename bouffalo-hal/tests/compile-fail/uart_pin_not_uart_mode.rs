@@ -0,0 +1,9 @@
+use bouffalo_hal::gpio::{Alternate, Spi};
+use bouffalo_hal::uart::HasUartSignal;
+
+fn check_signal<const I: usize, P: HasUartSignal<I>>() {}
+
+fn main() {
+    // GPIO14 carries UART signal 2 only after it is converted into UART mode.
+    check_signal::<2, Alternate<'static, 14, Spi<0>>>();
+}
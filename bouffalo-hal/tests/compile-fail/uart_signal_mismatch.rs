@@ -0,0 +1,9 @@
+use bouffalo_hal::gpio::{Alternate, Uart};
+use bouffalo_hal::uart::{MuxTxd, Pads, UartMux};
+
+fn check_pads<const U: usize, P: Pads<U>>() {}
+
+fn main() {
+    // GPIO5 is connected to UART signal 5, not UART signal 0.
+    check_pads::<0, (Alternate<'static, 5, Uart>, UartMux<'static, 0, MuxTxd<0>>)>();
+}
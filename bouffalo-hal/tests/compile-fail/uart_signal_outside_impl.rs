@@ -0,0 +1,8 @@
+use bouffalo_hal::uart::HasUartSignal;
+
+struct MyPin;
+
+// Pad and signal connections are sealed in `bouffalo-hal`.
+impl HasUartSignal<0> for MyPin {}
+
+fn main() {}
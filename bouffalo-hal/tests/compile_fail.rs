@@ -0,0 +1,16 @@
+//! Type-state guarantees: invalid pad and peripheral combinations must not compile.
+//!
+//! Expected compiler output is stored beside each case in `tests/compile-fail`; run with
+//! `TRYBUILD=overwrite` to update it after changing diagnostics or pin tables.
+//! Diagnostics differ between release channels, so the snapshots follow the nightly
+//! toolchain used in CI and the test is skipped on other channels.
+
+#[rustversion::attr(
+    not(nightly),
+    ignore = "compiler output snapshots are taken on nightly"
+)]
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/compile-fail/*.rs");
+}
@@ -82,6 +82,8 @@ SECTIONS {
         *(.sbss .sbss.*)
         ebss = .;
     } > OCRAM
+    sheap = ALIGN(ebss, 8);
+    eheap = ORIGIN(OCRAM) + LENGTH(OCRAM);
     /DISCARD/ : {
         *(.eh_frame)
     }
@@ -95,6 +97,7 @@ MEMORY {
     PSEUDO_HEADER : ORIGIN = 0x58000000 - 0x1000, LENGTH = 4K
     FLASH : ORIGIN = 0x58000000, LENGTH = 32M - 4K
     WRAM : ORIGIN = 0x62030000, LENGTH = 160K
+    SHARED_LOCK : ORIGIN = 0x62020000 + 64K - 64, LENGTH = 64
 }
 SECTIONS {
     .head : ALIGN(4) {
@@ -126,6 +129,8 @@ SECTIONS {
     .text : ALIGN(4) {
         stext = .;
         KEEP(*(.text.entry))
+        . = ALIGN(64);
+        *(.trap.trap-entry)
         *(.text .text.*)
         . = ALIGN(4);
         etext = .;
@@ -152,10 +157,93 @@ SECTIONS {
         *(.sbss .sbss.*)
         ebss = .;
     } > WRAM
+    .shared_lock (NOLOAD) : ALIGN(64) {
+        . += 64;
+    } > SHARED_LOCK
+    sstack = _stack_start - _stack_size;
+    sstack_guard = (sstack - 64) & ~63;
+    sheap = ALIGN(ebss, 8);
+    eheap = (_stack_start > ORIGIN(WRAM) && _stack_start <= ORIGIN(WRAM) + LENGTH(WRAM))
+        ? sstack_guard : ORIGIN(WRAM) + LENGTH(WRAM);
+    ASSERT(_stack_start % 16 == 0, \"_stack_start must be 16-byte aligned\")
+    ASSERT(eheap >= sheap, \"stack overlaps .bss, reduce _stack_size or move _stack_start\")
     /DISCARD/ : {
         *(.eh_frame)
     }
-}";
+}
+/* stack placement, override with `-C link-arg=--defsym=<symbol>=<value>` */
+PROVIDE(_stack_start = ORIGIN(WRAM) + LENGTH(WRAM));
+PROVIDE(_stack_size = 4K);
+/* spinlock word shared with the other core, see `critical-section-multi-core` */
+PROVIDE(_critical_section_lock = ORIGIN(SHARED_LOCK) + LENGTH(SHARED_LOCK) - 4);
+/* exceptions */
+PROVIDE(exceptions = default_exception_handler);
+/* interrupts */
+PROVIDE(bmx_mcu_bus_err = default_handler);
+PROVIDE(bmx_mcu_to = default_handler);
+PROVIDE(mcu_reserved2 = default_handler);
+PROVIDE(ipc_m0 = default_handler);
+PROVIDE(audio = default_handler);
+PROVIDE(rf_top_int0 = default_handler);
+PROVIDE(rf_top_int1 = default_handler);
+PROVIDE(lz4 = default_handler);
+PROVIDE(mcu_reserved8 = default_handler);
+PROVIDE(sec_eng_id0_gmac = default_handler);
+PROVIDE(sec_eng_id0_cdet = default_handler);
+PROVIDE(sec_eng_id0_pka = default_handler);
+PROVIDE(sec_eng_id0_trng = default_handler);
+PROVIDE(sec_eng_id0_aes = default_handler);
+PROVIDE(sec_eng_id0_sha = default_handler);
+PROVIDE(dma0_all = default_handler);
+PROVIDE(dma1_all = default_handler);
+PROVIDE(sdh = default_handler);
+PROVIDE(mm_all = default_handler);
+PROVIDE(ir_tx = default_handler);
+PROVIDE(ir_rx = default_handler);
+PROVIDE(usb = default_handler);
+PROVIDE(aupdm = default_handler);
+PROVIDE(mcu_reserved23 = default_handler);
+PROVIDE(emac = default_handler);
+PROVIDE(gpadc_dma = default_handler);
+PROVIDE(efuse = default_handler);
+PROVIDE(spi0 = default_handler);
+PROVIDE(uart0 = default_handler);
+PROVIDE(uart1 = default_handler);
+PROVIDE(uart2 = default_handler);
+PROVIDE(gpio_dma = default_handler);
+PROVIDE(i2c0 = default_handler);
+PROVIDE(pwm = default_handler);
+PROVIDE(ipc_reserved = default_handler);
+PROVIDE(ipc_lp = default_handler);
+PROVIDE(timer0_ch0 = default_handler);
+PROVIDE(timer0_ch1 = default_handler);
+PROVIDE(timer0_wdt = default_handler);
+PROVIDE(i2c1 = default_handler);
+PROVIDE(i2s = default_handler);
+PROVIDE(ana_ocp_out0 = default_handler);
+PROVIDE(ana_ocp_out1 = default_handler);
+PROVIDE(ana_ocp_out2 = default_handler);
+PROVIDE(gpio = default_handler);
+PROVIDE(dm = default_handler);
+PROVIDE(bt = default_handler);
+PROVIDE(m154_req_ack = default_handler);
+PROVIDE(m154 = default_handler);
+PROVIDE(m154_aes = default_handler);
+PROVIDE(pds_wakeup = default_handler);
+PROVIDE(hbn_out0 = default_handler);
+PROVIDE(hbn_out1 = default_handler);
+PROVIDE(bor = default_handler);
+PROVIDE(wifi = default_handler);
+PROVIDE(bz_phy = default_handler);
+PROVIDE(ble = default_handler);
+PROVIDE(mac_txrx_timer = default_handler);
+PROVIDE(mac_txrx_misc = default_handler);
+PROVIDE(mac_rx_trg = default_handler);
+PROVIDE(mac_tx_trg = default_handler);
+PROVIDE(mac_gen = default_handler);
+PROVIDE(mac_port_trg = default_handler);
+PROVIDE(wifi_ipc_public = default_handler);
+";
 
 #[cfg(feature = "bl808-dsp")]
 const LINKER_SCRIPT_BL808_DSP: &[u8] = b"
@@ -166,6 +254,7 @@ MEMORY {
     FLASH : ORIGIN = 0x58000000, LENGTH = 32M - 4K
     DRAM : ORIGIN = 0x3EFF7000, LENGTH = 4K
     VRAM : ORIGIN = 0x3F000000, LENGTH = 32K
+    SHARED_LOCK : ORIGIN = 0x62020000 + 64K - 64, LENGTH = 64
 }
 SECTIONS {
     .head : ALIGN(8) {
@@ -225,12 +314,26 @@ SECTIONS {
         *(.sbss .sbss.*)
         ebss = .;
     } > VRAM
+    .shared_lock (NOLOAD) : ALIGN(64) {
+        . += 64;
+    } > SHARED_LOCK
+    sstack = _stack_start - _stack_size;
+    sheap = ALIGN(ebss, 8);
+    eheap = (_stack_start > ORIGIN(VRAM) && _stack_start <= ORIGIN(VRAM) + LENGTH(VRAM))
+        ? sstack : ORIGIN(VRAM) + LENGTH(VRAM);
+    ASSERT(_stack_start % 16 == 0, \"_stack_start must be 16-byte aligned\")
+    ASSERT(eheap >= sheap, \"stack overlaps .bss, reduce _stack_size or move _stack_start\")
     /DISCARD/ : {
         *(.eh_frame)
     }
 }
+/* stack placement, override with `-C link-arg=--defsym=<symbol>=<value>` */
+PROVIDE(_stack_start = ORIGIN(VRAM) + LENGTH(VRAM));
+PROVIDE(_stack_size = 8K);
+/* spinlock word shared with the other core, see `critical-section-multi-core` */
+PROVIDE(_critical_section_lock = ORIGIN(SHARED_LOCK) + LENGTH(SHARED_LOCK) - 4);
 /* exceptions */
-PROVIDE(exceptions = default_handler);
+PROVIDE(exceptions = default_exception_handler);
 /* interrupts */
 PROVIDE(bmx_dsp_bus_err = default_handler);
 PROVIDE(dsp_reserved1 = default_handler);
@@ -340,6 +443,8 @@ SECTIONS {
     .text : ALIGN(4) {
         stext = .;
         KEEP(*(.text.entry))
+        . = ALIGN(64);
+        *(.trap.trap-entry)
         *(.text .text.*)
         . = ALIGN(4);
         etext = .;
@@ -366,10 +471,87 @@ SECTIONS {
         *(.sbss .sbss.*)
         ebss = .;
     } > RAM
+    sstack = _stack_start - _stack_size;
+    sheap = ALIGN(ebss, 8);
+    eheap = (_stack_start > ORIGIN(RAM) && _stack_start <= ORIGIN(RAM) + LENGTH(RAM))
+        ? sstack : ORIGIN(RAM) + LENGTH(RAM);
+    ASSERT(_stack_start % 16 == 0, \"_stack_start must be 16-byte aligned\")
+    ASSERT(eheap >= sheap, \"stack overlaps .bss, reduce _stack_size or move _stack_start\")
     /DISCARD/ : {
         *(.eh_frame)
     }
-}";
+}
+/* stack placement, override with `-C link-arg=--defsym=<symbol>=<value>` */
+PROVIDE(_stack_start = ORIGIN(RAM) + LENGTH(RAM));
+PROVIDE(_stack_size = 2K);
+/* exceptions */
+PROVIDE(exceptions = default_exception_handler);
+/* interrupts */
+PROVIDE(bmx_mcu_bus_err = default_handler);
+PROVIDE(bmx_mcu_to = default_handler);
+PROVIDE(mcu_reserved2 = default_handler);
+PROVIDE(ipc_m0 = default_handler);
+PROVIDE(audio = default_handler);
+PROVIDE(rf_top_int0 = default_handler);
+PROVIDE(rf_top_int1 = default_handler);
+PROVIDE(lz4 = default_handler);
+PROVIDE(mcu_reserved8 = default_handler);
+PROVIDE(sec_eng_id0_gmac = default_handler);
+PROVIDE(sec_eng_id0_cdet = default_handler);
+PROVIDE(sec_eng_id0_pka = default_handler);
+PROVIDE(sec_eng_id0_trng = default_handler);
+PROVIDE(sec_eng_id0_aes = default_handler);
+PROVIDE(sec_eng_id0_sha = default_handler);
+PROVIDE(dma0_all = default_handler);
+PROVIDE(dma1_all = default_handler);
+PROVIDE(sdh = default_handler);
+PROVIDE(mm_all = default_handler);
+PROVIDE(ir_tx = default_handler);
+PROVIDE(ir_rx = default_handler);
+PROVIDE(usb = default_handler);
+PROVIDE(aupdm = default_handler);
+PROVIDE(mcu_reserved23 = default_handler);
+PROVIDE(emac = default_handler);
+PROVIDE(gpadc_dma = default_handler);
+PROVIDE(efuse = default_handler);
+PROVIDE(spi0 = default_handler);
+PROVIDE(uart0 = default_handler);
+PROVIDE(uart1 = default_handler);
+PROVIDE(uart2 = default_handler);
+PROVIDE(gpio_dma = default_handler);
+PROVIDE(i2c0 = default_handler);
+PROVIDE(pwm = default_handler);
+PROVIDE(ipc_reserved = default_handler);
+PROVIDE(ipc_lp = default_handler);
+PROVIDE(timer0_ch0 = default_handler);
+PROVIDE(timer0_ch1 = default_handler);
+PROVIDE(timer0_wdt = default_handler);
+PROVIDE(i2c1 = default_handler);
+PROVIDE(i2s = default_handler);
+PROVIDE(ana_ocp_out0 = default_handler);
+PROVIDE(ana_ocp_out1 = default_handler);
+PROVIDE(ana_ocp_out2 = default_handler);
+PROVIDE(gpio = default_handler);
+PROVIDE(dm = default_handler);
+PROVIDE(bt = default_handler);
+PROVIDE(m154_req_ack = default_handler);
+PROVIDE(m154 = default_handler);
+PROVIDE(m154_aes = default_handler);
+PROVIDE(pds_wakeup = default_handler);
+PROVIDE(hbn_out0 = default_handler);
+PROVIDE(hbn_out1 = default_handler);
+PROVIDE(bor = default_handler);
+PROVIDE(wifi = default_handler);
+PROVIDE(bz_phy = default_handler);
+PROVIDE(ble = default_handler);
+PROVIDE(mac_txrx_timer = default_handler);
+PROVIDE(mac_txrx_misc = default_handler);
+PROVIDE(mac_rx_trg = default_handler);
+PROVIDE(mac_tx_trg = default_handler);
+PROVIDE(mac_gen = default_handler);
+PROVIDE(mac_port_trg = default_handler);
+PROVIDE(wifi_ipc_public = default_handler);
+";
 
 #[cfg(feature = "bl702")]
 const LINKER_SCRIPT_BL702: &[u8] = b"
@@ -400,6 +582,8 @@ SECTIONS {
     .text : ALIGN(4) {
         stext = .;
         KEEP(*(.text.entry))
+        . = ALIGN(64);
+        *(.trap.trap-entry)
         *(.text .text.*)
         . = ALIGN(4);
         etext = .;
@@ -426,7 +610,77 @@ SECTIONS {
         *(.sbss .sbss.*)
         ebss = .;
     } > OCRAM
+    sheap = ALIGN(ebss, 8);
+    eheap = ORIGIN(OCRAM) + LENGTH(OCRAM);
     /DISCARD/ : {
         *(.eh_frame)
     }
-}";
+}
+/* exceptions */
+PROVIDE(exceptions = default_exception_handler);
+/* interrupts */
+PROVIDE(bmx_err = default_handler);
+PROVIDE(bmx_to = default_handler);
+PROVIDE(l1c_bmx_err = default_handler);
+PROVIDE(l1c_bmx_to = default_handler);
+PROVIDE(sec_bmx_err = default_handler);
+PROVIDE(rf_top_int0 = default_handler);
+PROVIDE(rf_top_int1 = default_handler);
+PROVIDE(sdio = default_handler);
+PROVIDE(dma_bmx_err = default_handler);
+PROVIDE(sec_gmac = default_handler);
+PROVIDE(sec_cdet = default_handler);
+PROVIDE(sec_pka = default_handler);
+PROVIDE(sec_trng = default_handler);
+PROVIDE(sec_aes = default_handler);
+PROVIDE(sec_sha = default_handler);
+PROVIDE(dma_all = default_handler);
+PROVIDE(mjpeg = default_handler);
+PROVIDE(cam = default_handler);
+PROVIDE(i2s = default_handler);
+PROVIDE(ir_tx = default_handler);
+PROVIDE(ir_rx = default_handler);
+PROVIDE(usb = default_handler);
+PROVIDE(qdec = default_handler);
+PROVIDE(kys = default_handler);
+PROVIDE(emac = default_handler);
+PROVIDE(gpadc_dma = default_handler);
+PROVIDE(efuse = default_handler);
+PROVIDE(spi = default_handler);
+PROVIDE(reserved28 = default_handler);
+PROVIDE(uart0 = default_handler);
+PROVIDE(uart1 = default_handler);
+PROVIDE(reserved31 = default_handler);
+PROVIDE(i2c = default_handler);
+PROVIDE(reserved33 = default_handler);
+PROVIDE(pwm = default_handler);
+PROVIDE(reserved35 = default_handler);
+PROVIDE(timer_ch0 = default_handler);
+PROVIDE(timer_ch1 = default_handler);
+PROVIDE(timer_wdt = default_handler);
+PROVIDE(reserved39 = default_handler);
+PROVIDE(reserved40 = default_handler);
+PROVIDE(reserved41 = default_handler);
+PROVIDE(reserved42 = default_handler);
+PROVIDE(reserved43 = default_handler);
+PROVIDE(gpio = default_handler);
+PROVIDE(reserved45 = default_handler);
+PROVIDE(reserved46 = default_handler);
+PROVIDE(m154_req_enh_ack = default_handler);
+PROVIDE(m154 = default_handler);
+PROVIDE(m154_aes = default_handler);
+PROVIDE(pds_wakeup = default_handler);
+PROVIDE(hbn_out0 = default_handler);
+PROVIDE(hbn_out1 = default_handler);
+PROVIDE(bor = default_handler);
+PROVIDE(wifi = default_handler);
+PROVIDE(bz_phy = default_handler);
+PROVIDE(ble = default_handler);
+PROVIDE(mac_txrx_timer = default_handler);
+PROVIDE(mac_txrx_misc = default_handler);
+PROVIDE(mac_rx_trg = default_handler);
+PROVIDE(mac_tx_trg = default_handler);
+PROVIDE(mac_gen = default_handler);
+PROVIDE(mac_port_trg = default_handler);
+PROVIDE(wifi_ipc_public = default_handler);
+";
@@ -4,9 +4,20 @@ use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
 use syn::spanned::Spanned;
-use syn::{ItemFn, ReturnType, Type, Visibility, parse, parse_macro_input};
+use syn::{FnArg, ItemFn, ReturnType, Type, Visibility, parse, parse_macro_input};
 
 /// ROM runtime function entry.
+///
+/// The function receives peripherals and clocks prepared by the runtime and must never
+/// return. It is exported as the `main` symbol that startup code of each chip calls once
+/// stack, `.bss` and `.data` are set up.
+///
+/// ```ignore
+/// #[entry]
+/// fn main(p: Peripherals, c: Clocks) -> ! {
+///     loop {}
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn entry(args: TokenStream, input: TokenStream) -> TokenStream {
     if !args.is_empty() {
@@ -66,6 +77,17 @@ pub fn entry(args: TokenStream, input: TokenStream) -> TokenStream {
 }
 
 /// Interrupt handler function.
+///
+/// The function name selects the interrupt source, for example `uart3` or `ipc_d0` on
+/// BL808 DSP core, and is checked against sources of the target chip. Sources without
+/// a handler fall back to an empty default handler.
+///
+/// ```ignore
+/// #[interrupt]
+/// fn ipc_d0() {
+///     // handle doorbell
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn interrupt(args: TokenStream, input: TokenStream) -> TokenStream {
     if !args.is_empty() {
@@ -134,6 +156,17 @@ pub fn interrupt(args: TokenStream, input: TokenStream) -> TokenStream {
 }
 
 /// Exception handler function.
+///
+/// Handles all synchronous exceptions of the current core. The trap frame holds
+/// all general registers and trap CSRs; caller-saved registers, `mcause`, `mepc` and
+/// `mstatus` are written back, and `mepc` is where execution resumes.
+///
+/// ```ignore
+/// #[exception]
+/// fn exceptions(tf: &mut TrapFrame) {
+///     tf.mepc += 4;
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
     if !args.is_empty() {
@@ -180,6 +213,27 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
         .into();
     }
 
+    let valid_input = match f.sig.inputs.first() {
+        Some(FnArg::Typed(arg)) => match *arg.ty {
+            Type::Reference(ref r) => {
+                r.mutability.is_some()
+                    && matches!(*r.elem, Type::Path(ref p)
+                        if p.path.segments.last().is_some_and(|s| s.ident == "TrapFrame"))
+            }
+            _ => false,
+        },
+        _ => false,
+    };
+
+    if !valid_input {
+        return parse::Error::new(
+            f.sig.inputs.span(),
+            "`#[exception]` handler parameter must be of type `&mut TrapFrame`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
     let attrs = f.attrs;
     let unsafety = f.sig.unsafety;
     let stmts = f.block.stmts;
@@ -187,8 +241,6 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
     let output = f.sig.output;
     let inputs = f.sig.inputs;
 
-    // FIXME: check input type of arguments
-
     quote!(
         #(#attrs)*
         #[unsafe(export_name = "exceptions")]
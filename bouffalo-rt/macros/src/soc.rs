@@ -1,3 +1,4 @@
+mod bl702;
 mod bl808;
 
 use proc_macro2::Ident;
@@ -14,8 +15,34 @@ pub fn check_interrupt_name(ident: &Ident) -> Option<Error> {
             ),
         ));
     }
+    // M0 and LP cores share the same interrupt sources.
+    #[cfg(any(feature = "bl808-mcu", feature = "bl808-lp"))]
+    if !bl808::BL808_MCU_INTERRUPTS.contains(&format!("{}", ident).as_str()) {
+        return Some(Error::new(
+            ident.span(),
+            format!(
+                "invalid `#[interrupt]` source. Must be one of: {}.",
+                bl808::BL808_MCU_INTERRUPTS.join(", ")
+            ),
+        ));
+    }
+    #[cfg(feature = "bl702")]
+    if !bl702::BL702_INTERRUPTS.contains(&format!("{}", ident).as_str()) {
+        return Some(Error::new(
+            ident.span(),
+            format!(
+                "invalid `#[interrupt]` source. Must be one of: {}.",
+                bl702::BL702_INTERRUPTS.join(", ")
+            ),
+        ));
+    }
     // TODO: support for other chips and contexts
-    #[cfg(not(feature = "bl808-dsp"))]
+    #[cfg(not(any(
+        feature = "bl808-dsp",
+        feature = "bl808-mcu",
+        feature = "bl808-lp",
+        feature = "bl702"
+    )))]
     let _ = ident;
     None
 }
@@ -0,0 +1,17 @@
+#[rustfmt::skip]
+#[cfg(feature = "bl702")]
+pub(crate) const BL702_INTERRUPTS: [&'static str; 64] = [
+    "bmx_err",          "bmx_to",           "l1c_bmx_err",      "l1c_bmx_to",       "sec_bmx_err",
+    "rf_top_int0",      "rf_top_int1",      "sdio",             "dma_bmx_err",      "sec_gmac",
+    "sec_cdet",         "sec_pka",          "sec_trng",         "sec_aes",          "sec_sha",
+    "dma_all",          "mjpeg",            "cam",              "i2s",              "ir_tx",
+    "ir_rx",            "usb",              "qdec",             "kys",              "emac",
+    "gpadc_dma",        "efuse",            "spi",              "reserved28",       "uart0",
+    "uart1",            "reserved31",       "i2c",              "reserved33",       "pwm",
+    "reserved35",       "timer_ch0",        "timer_ch1",        "timer_wdt",        "reserved39",
+    "reserved40",       "reserved41",       "reserved42",       "reserved43",       "gpio",
+    "reserved45",       "reserved46",       "m154_req_enh_ack", "m154",             "m154_aes",
+    "pds_wakeup",       "hbn_out0",         "hbn_out1",         "bor",              "wifi",
+    "bz_phy",           "ble",              "mac_txrx_timer",   "mac_txrx_misc",    "mac_rx_trg",
+    "mac_tx_trg",       "mac_gen",          "mac_port_trg",     "wifi_ipc_public",
+];
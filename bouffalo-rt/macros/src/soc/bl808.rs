@@ -17,6 +17,20 @@ pub(crate) const BL808_DSP_INTERRUPTS: [&'static str; 67] = [
     "wl_all",	        "pds",
 ];
 
-// TODO const BL808_MCU_INTERRUPTS
-
-// TODO const BL808_LP_INTERRUPTS
+#[rustfmt::skip]
+#[cfg(any(feature = "bl808-mcu", feature = "bl808-lp"))]
+pub(crate) const BL808_MCU_INTERRUPTS: [&'static str; 64] = [
+    "bmx_mcu_bus_err",	"bmx_mcu_to",	    "mcu_reserved2",	"ipc_m0",	        "audio",
+    "rf_top_int0",	    "rf_top_int1",	    "lz4",	            "mcu_reserved8",	"sec_eng_id0_gmac",
+    "sec_eng_id0_cdet",	"sec_eng_id0_pka",	"sec_eng_id0_trng",	"sec_eng_id0_aes",	"sec_eng_id0_sha",
+    "dma0_all",	        "dma1_all",	        "sdh",	            "mm_all",	        "ir_tx",
+    "ir_rx",	        "usb",	            "aupdm",	        "mcu_reserved23",	"emac",
+    "gpadc_dma",	    "efuse",	        "spi0",	            "uart0",	        "uart1",
+    "uart2",	        "gpio_dma",	        "i2c0",	            "pwm",	            "ipc_reserved",
+    "ipc_lp",	        "timer0_ch0",	    "timer0_ch1",	    "timer0_wdt",	    "i2c1",
+    "i2s",	            "ana_ocp_out0",	    "ana_ocp_out1",	    "ana_ocp_out2",	    "gpio",
+    "dm",	            "bt",	            "m154_req_ack",	    "m154",	            "m154_aes",
+    "pds_wakeup",	    "hbn_out0",	        "hbn_out1",	        "bor",	            "wifi",
+    "bz_phy",	        "ble",	            "mac_txrx_timer",	"mac_txrx_misc",	"mac_rx_trg",
+    "mac_tx_trg",	    "mac_gen",	        "mac_port_trg",	    "wifi_ipc_public",
+];
@@ -8,6 +8,7 @@ pub struct Stack<const N: usize>(pub(crate) [u8; N]);
 
 /// RISC-V 'E' instruction base Trap stack frame declaration.
 #[repr(C)]
+#[derive(Debug)]
 pub struct TrapFrame {
     /// Return address register.
     pub ra: usize,
@@ -35,4 +36,14 @@ pub struct TrapFrame {
     pub mepc: usize,
     /// Machine status register.
     pub mstatus: usize,
+    /// Stack pointer before the trap; saved for inspection only.
+    pub sp: usize,
+    /// Global pointer; saved for inspection only.
+    pub gp: usize,
+    /// Thread pointer; saved for inspection only.
+    pub tp: usize,
+    /// Saved register 0; preserved by the handler, not restored from the frame.
+    pub s0: usize,
+    /// Saved register 1; preserved by the handler, not restored from the frame.
+    pub s1: usize,
 }
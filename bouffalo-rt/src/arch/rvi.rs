@@ -9,6 +9,7 @@ pub struct Stack<const N: usize>(pub(crate) [u8; N]);
 
 /// RISC-V 'I' instruction base Trap stack frame declaration.
 #[repr(C)]
+#[derive(Debug)]
 pub struct TrapFrame {
     /// Return address register.
     pub ra: usize,
@@ -48,4 +49,34 @@ pub struct TrapFrame {
     pub mepc: usize,
     /// Machine status register.
     pub mstatus: usize,
+    /// Stack pointer before the trap; saved for inspection only.
+    pub sp: usize,
+    /// Global pointer; saved for inspection only.
+    pub gp: usize,
+    /// Thread pointer; saved for inspection only.
+    pub tp: usize,
+    /// Saved register 0; preserved by the handler, not restored from the frame.
+    pub s0: usize,
+    /// Saved register 1; preserved by the handler, not restored from the frame.
+    pub s1: usize,
+    /// Saved register 2; preserved by the handler, not restored from the frame.
+    pub s2: usize,
+    /// Saved register 3; preserved by the handler, not restored from the frame.
+    pub s3: usize,
+    /// Saved register 4; preserved by the handler, not restored from the frame.
+    pub s4: usize,
+    /// Saved register 5; preserved by the handler, not restored from the frame.
+    pub s5: usize,
+    /// Saved register 6; preserved by the handler, not restored from the frame.
+    pub s6: usize,
+    /// Saved register 7; preserved by the handler, not restored from the frame.
+    pub s7: usize,
+    /// Saved register 8; preserved by the handler, not restored from the frame.
+    pub s8: usize,
+    /// Saved register 9; preserved by the handler, not restored from the frame.
+    pub s9: usize,
+    /// Saved register 10; preserved by the handler, not restored from the frame.
+    pub s10: usize,
+    /// Saved register 11; preserved by the handler, not restored from the frame.
+    pub s11: usize,
 }
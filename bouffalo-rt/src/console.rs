@@ -0,0 +1,169 @@
+//! Global console for `print!`, `println!` and `dbg!` macros.
+//!
+//! The console is a serial or any other [`fmt::Write`] set once by [`init`]; output
+//! written before that is dropped. Every macro invocation formats with machine
+//! interrupts of the current core masked, so lines from interrupt handlers do not
+//! interleave with lines from thread mode. Output of a macro invoked while formatting
+//! another one, e.g. from a `Display` implementation, is dropped.
+//!
+//! The panic handler of `panic-uart` feature and the default exception handler also
+//! print to this console.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let serial = p.uart0.freerun(config, pads, &c).unwrap();
+//! // The console lives forever, e.g. leak it with the `alloc` feature.
+//! bouffalo_rt::console::init(Box::leak(Box::new(serial)));
+//! bouffalo_rt::println!("Hello world!");
+//! ```
+
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+struct Console {
+    taken: AtomicBool,
+    // Only accessed with interrupts of the current core masked.
+    console: UnsafeCell<Option<&'static mut dyn fmt::Write>>,
+}
+
+unsafe impl Sync for Console {}
+
+static CONSOLE: Console = Console {
+    taken: AtomicBool::new(false),
+    console: UnsafeCell::new(None),
+};
+
+/// Set the global console written by `print!`, `println!` and `dbg!`.
+///
+/// Replaces any previous console.
+#[inline]
+pub fn init(console: &'static mut dyn fmt::Write) {
+    crate::interrupt_free(|| unsafe { *CONSOLE.console.get() = Some(console) });
+}
+
+/// Take the console for good before halting the current core.
+///
+/// Called with machine interrupts masked. Returns `None` if there is no console, or if
+/// the halt comes from inside a console write, which still holds the console.
+#[cfg(any(
+    all(feature = "panic-uart", not(test)),
+    feature = "bl616",
+    feature = "bl808-mcu",
+    feature = "bl808-dsp",
+    feature = "bl808-lp",
+    feature = "bl702"
+))]
+#[inline]
+pub(crate) fn take_for_halt() -> Option<&'static mut dyn fmt::Write> {
+    if CONSOLE.taken.load(Ordering::Relaxed) {
+        return None;
+    }
+    unsafe { (*CONSOLE.console.get()).take() }
+}
+
+#[doc(hidden)]
+#[inline]
+pub fn _print(args: fmt::Arguments) {
+    crate::interrupt_free(|| {
+        // Interrupts are masked, no atomic read-modify-write needed.
+        if CONSOLE.taken.load(Ordering::Relaxed) {
+            return;
+        }
+        CONSOLE.taken.store(true, Ordering::Relaxed);
+        if let Some(console) = unsafe { &mut *CONSOLE.console.get() } {
+            let _ = console.write_fmt(args);
+        }
+        CONSOLE.taken.store(false, Ordering::Relaxed);
+    });
+}
+
+/// Print to the global console.
+#[macro_export]
+macro_rules! print {
+    ($($arg:tt)*) => {
+        $crate::console::_print(core::format_args!($($arg)*))
+    };
+}
+
+/// Print to the global console, with a newline.
+#[macro_export]
+macro_rules! println {
+    () => {
+        $crate::console::_print(core::format_args!("\n"))
+    };
+    ($($arg:tt)*) => {
+        $crate::console::_print(core::format_args!("{}\n", core::format_args!($($arg)*)))
+    };
+}
+
+/// Print and return the value of an expression with its source location.
+///
+/// Works like `std::dbg!` but writes to the global console.
+#[macro_export]
+macro_rules! dbg {
+    () => {
+        $crate::println!("[{}:{}:{}]", core::file!(), core::line!(), core::column!())
+    };
+    ($val:expr $(,)?) => {
+        match $val {
+            tmp => {
+                $crate::println!(
+                    "[{}:{}:{}] {} = {:#?}",
+                    core::file!(),
+                    core::line!(),
+                    core::column!(),
+                    core::stringify!($val),
+                    &tmp
+                );
+                tmp
+            }
+        }
+    };
+    ($($val:expr),+ $(,)?) => {
+        ($($crate::dbg!($val)),+,)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use core::fmt;
+    use std::{boxed::Box, string::String, sync::Mutex};
+
+    static OUTPUT: Mutex<String> = Mutex::new(String::new());
+
+    struct Buffer;
+
+    impl fmt::Write for Buffer {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            OUTPUT.lock().unwrap().push_str(s);
+            Ok(())
+        }
+    }
+
+    struct Nested;
+
+    impl fmt::Display for Nested {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            crate::print!("dropped");
+            f.write_str("nested")
+        }
+    }
+
+    #[test]
+    fn print_to_console() {
+        super::init(Box::leak(Box::new(Buffer)));
+        crate::print!("a = ");
+        crate::println!("{}", 1);
+        let value = crate::dbg!(2 + 3);
+        assert_eq!(value, 5);
+        crate::println!("{}", Nested);
+        let output = OUTPUT.lock().unwrap();
+        assert!(output.starts_with("a = 1\n["));
+        assert!(output.ends_with("] 2 + 3 = 5\nnested\n"));
+    }
+}
@@ -0,0 +1,132 @@
+//! `critical-section` implementation for single-core and multi-core use.
+//!
+//! With `critical-section-single-core`, a critical section masks machine interrupts of
+//! the current core. This is enough when only one core runs Rust code, or when cores do
+//! not share data.
+//!
+//! With `critical-section-multi-core`, a critical section additionally takes a spinlock
+//! shared by BL808 M0 and D0 cores, so `Mutex<RefCell<...>>` data placed in shared memory
+//! is protected across cores. The lock word is at linker symbol `_critical_section_lock`,
+//! by default the last word of OCRAM on its non-cacheable alias `0x6202_0000`, reserved
+//! by both linker scripts; both images must use the same address, override it with
+//! `-C link-arg=--defsym=_critical_section_lock=<addr>`. M0 clears the word before its
+//! entry function, so D0 must be started from M0 firmware after that; D0 drops a lock
+//! left held by its own previous run when it starts.
+//! The BL808 LP core has no atomic instructions and cannot take part in the spinlock.
+//!
+//! When both features are enabled, the multi-core implementation is used.
+
+#[cfg(all(feature = "critical-section-multi-core", feature = "bl808-lp"))]
+compile_error!("feature `critical-section-multi-core` is not supported on BL808 LP core");
+
+#[cfg(all(
+    feature = "critical-section-multi-core",
+    not(any(feature = "bl808-mcu", feature = "bl808-dsp"))
+))]
+compile_error!("feature `critical-section-multi-core` is only supported on BL808 M0 and D0 cores");
+
+struct MachineCriticalSection;
+
+critical_section::set_impl!(MachineCriticalSection);
+
+// Restore state bits.
+const MIE_WAS_ENABLED: u8 = 1 << 0;
+#[cfg(feature = "critical-section-multi-core")]
+const LOCK_TAKEN: u8 = 1 << 1;
+
+unsafe impl critical_section::Impl for MachineCriticalSection {
+    #[inline]
+    unsafe fn acquire() -> critical_section::RawRestoreState {
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        let mstatus: usize;
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        unsafe {
+            core::arch::asm!("csrrci {}, mstatus, 8", out(reg) mstatus)
+        };
+        #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+        let mstatus = 0;
+        let state = if mstatus & 8 != 0 { MIE_WAS_ENABLED } else { 0 };
+        #[cfg(feature = "critical-section-multi-core")]
+        let state = if spinlock::lock() {
+            state | LOCK_TAKEN
+        } else {
+            state
+        };
+        state
+    }
+    #[inline]
+    unsafe fn release(state: critical_section::RawRestoreState) {
+        #[cfg(feature = "critical-section-multi-core")]
+        if state & LOCK_TAKEN != 0 {
+            spinlock::unlock();
+        }
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        if state & MIE_WAS_ENABLED != 0 {
+            unsafe { core::arch::asm!("csrsi mstatus, 8") };
+        }
+        #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+        let _ = state;
+    }
+}
+
+/// Prepare the spinlock shared between cores, called before the entry function.
+#[cfg(feature = "critical-section-multi-core")]
+#[inline]
+pub(crate) fn init() {
+    spinlock::init();
+}
+
+#[cfg(feature = "critical-section-multi-core")]
+mod spinlock {
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    // Lock owner, zero if the lock is free.
+    #[cfg(feature = "bl808-mcu")]
+    const OWNER: u32 = 1;
+    #[cfg(feature = "bl808-dsp")]
+    const OWNER: u32 = 2;
+
+    unsafe extern "C" {
+        static _critical_section_lock: AtomicU32;
+    }
+
+    #[inline]
+    fn lock_word() -> &'static AtomicU32 {
+        unsafe { &_critical_section_lock }
+    }
+
+    /// Prepare the lock word when this core starts.
+    #[inline]
+    pub fn init() {
+        let word = lock_word();
+        // M0 starts before D0, so it clears power-on content of the lock word.
+        #[cfg(feature = "bl808-mcu")]
+        word.store(0, Ordering::Release);
+        // A lock held by this core before a reset would never be released otherwise.
+        #[cfg(feature = "bl808-dsp")]
+        let _ = word.compare_exchange(OWNER, 0, Ordering::Release, Ordering::Relaxed);
+    }
+
+    /// Take the lock, returns `false` if it is already held by the current core.
+    #[inline]
+    pub fn lock() -> bool {
+        let word = lock_word();
+        // Nested critical sections on the same core keep the outer lock.
+        if word.load(Ordering::Relaxed) == OWNER {
+            return false;
+        }
+        while word
+            .compare_exchange_weak(0, OWNER, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+        true
+    }
+
+    /// Release the lock held by the current core.
+    #[inline]
+    pub fn unlock() {
+        lock_word().store(0, Ordering::Release);
+    }
+}
@@ -0,0 +1,213 @@
+//! `defmt` global logger over a serial console or RTT.
+//!
+//! Log frames are written to the transport set by [`set_transport`], typically a
+//! blocking serial; frames logged before a transport is set are dropped. With the
+//! `defmt-rtt` feature, frames are also written to an RTT up channel which debug probes
+//! read from RAM through the T-Head debug module, so logging works without a serial.
+//!
+//! Timestamps are machine timer ticks of the current core in microseconds. As with any
+//! `defmt` logger, applications link with `-C link-arg=-Tdefmt.x`.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let serial = p.uart0.freerun(config, pads, &c).unwrap();
+//! // The transport lives forever, e.g. leak it with the `alloc` feature.
+//! bouffalo_rt::defmt::set_transport(Box::leak(Box::new(serial)));
+//! defmt::info!("hello from {=str}", "bouffalo-rt");
+//! ```
+
+use bouffalo_hal::clint::{MachineTimer, RegisterBlock};
+use core::{
+    cell::UnsafeCell,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+cfg_if::cfg_if! {
+    if #[cfg(any(feature = "bl808-mcu", feature = "bl808-dsp", feature = "bl808-lp"))] {
+        use crate::soc::bl808::{CLINT, TIME_SOURCE};
+    } else if #[cfg(feature = "bl702")] {
+        use crate::soc::bl702::{CLINT, TIME_SOURCE};
+    } else {
+        compile_error!("defmt support requires a chip with machine timer");
+    }
+}
+
+/// Byte sink for encoded `defmt` frames.
+///
+/// Implemented for every [`embedded_io::Write`] type; write errors are ignored.
+pub trait Transport {
+    /// Write all bytes to the transport.
+    fn write(&mut self, bytes: &[u8]);
+    /// Wait until all written bytes are sent.
+    fn flush(&mut self);
+}
+
+impl<T: embedded_io::Write> Transport for T {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let _ = self.write_all(bytes);
+    }
+    #[inline]
+    fn flush(&mut self) {
+        let _ = embedded_io::Write::flush(self);
+    }
+}
+
+/// Set the transport `defmt` frames are written to.
+///
+/// Replaces any previous transport.
+#[inline]
+pub fn set_transport(transport: &'static mut dyn Transport) {
+    crate::interrupt_free(|| unsafe { *LOGGER.transport.get() = Some(transport) });
+}
+
+struct LoggerState {
+    taken: AtomicBool,
+    // Fields below are only accessed with interrupts of the current core masked.
+    mstatus_mie: UnsafeCell<usize>,
+    encoder: UnsafeCell<defmt::Encoder>,
+    transport: UnsafeCell<Option<&'static mut dyn Transport>>,
+}
+
+unsafe impl Sync for LoggerState {}
+
+static LOGGER: LoggerState = LoggerState {
+    taken: AtomicBool::new(false),
+    mstatus_mie: UnsafeCell::new(0),
+    encoder: UnsafeCell::new(defmt::Encoder::new()),
+    transport: UnsafeCell::new(None),
+};
+
+#[inline]
+fn write_frame(bytes: &[u8]) {
+    if let Some(transport) = unsafe { &mut *LOGGER.transport.get() } {
+        transport.write(bytes);
+    }
+    #[cfg(feature = "defmt-rtt")]
+    rtt::write(bytes);
+}
+
+#[defmt::global_logger]
+struct Logger;
+
+unsafe impl defmt::Logger for Logger {
+    #[inline]
+    fn acquire() {
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        let mstatus: usize;
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        unsafe {
+            core::arch::asm!("csrrci {}, mstatus, 8", out(reg) mstatus)
+        };
+        #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+        let mstatus = 0;
+        // Interrupts are masked, no atomic read-modify-write needed.
+        if LOGGER.taken.load(Ordering::Relaxed) {
+            panic!("defmt logger taken reentrantly");
+        }
+        LOGGER.taken.store(true, Ordering::Relaxed);
+        unsafe {
+            *LOGGER.mstatus_mie.get() = mstatus & 8;
+            (*LOGGER.encoder.get()).start_frame(write_frame);
+        }
+    }
+    #[inline]
+    unsafe fn flush() {
+        if let Some(transport) = unsafe { &mut *LOGGER.transport.get() } {
+            transport.flush();
+        }
+    }
+    #[inline]
+    unsafe fn release() {
+        unsafe { (*LOGGER.encoder.get()).end_frame(write_frame) };
+        LOGGER.taken.store(false, Ordering::Relaxed);
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        unsafe {
+            core::arch::asm!("csrs mstatus, {}", in(reg) *LOGGER.mstatus_mie.get())
+        };
+    }
+    #[inline]
+    unsafe fn write(bytes: &[u8]) {
+        unsafe { (*LOGGER.encoder.get()).write(bytes, write_frame) };
+    }
+}
+
+defmt::timestamp!(
+    "{=u64:us}",
+    MachineTimer::<&RegisterBlock>::new(unsafe { &*CLINT::ptr() }, TIME_SOURCE).current_time()
+);
+
+#[cfg(feature = "defmt-rtt")]
+mod rtt {
+    //! Minimal SEGGER RTT control block with one up channel.
+
+    use core::{
+        cell::UnsafeCell,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    const BUFFER_SIZE: usize = 1024;
+
+    // Most fields are only read by debug probes.
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct ControlBlock {
+        id: [u8; 16],
+        max_up_channels: usize,
+        max_down_channels: usize,
+        up: Channel,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct Channel {
+        name: *const u8,
+        buffer: *mut u8,
+        size: usize,
+        write: AtomicUsize,
+        read: AtomicUsize,
+        flags: AtomicUsize,
+    }
+
+    struct Buffer(UnsafeCell<[u8; BUFFER_SIZE]>);
+
+    unsafe impl Sync for Buffer {}
+    unsafe impl Sync for ControlBlock {}
+
+    static BUFFER: Buffer = Buffer(UnsafeCell::new([0; BUFFER_SIZE]));
+
+    // Debug probes scan RAM for the id, the name is fixed by RTT host tools.
+    #[unsafe(no_mangle)]
+    static _SEGGER_RTT: ControlBlock = ControlBlock {
+        id: *b"SEGGER RTT\0\0\0\0\0\0",
+        max_up_channels: 1,
+        max_down_channels: 0,
+        up: Channel {
+            name: c"defmt".as_ptr().cast(),
+            buffer: BUFFER.0.get().cast(),
+            size: BUFFER_SIZE,
+            write: AtomicUsize::new(0),
+            read: AtomicUsize::new(0),
+            // Skip bytes which do not fit when no probe drains the buffer.
+            flags: AtomicUsize::new(0),
+        },
+    };
+
+    /// Write bytes into the up channel, dropping what does not fit.
+    #[inline]
+    pub fn write(bytes: &[u8]) {
+        let channel = &_SEGGER_RTT.up;
+        let read = channel.read.load(Ordering::Acquire);
+        let mut write = channel.write.load(Ordering::Relaxed);
+        for &byte in bytes {
+            let next = (write + 1) % BUFFER_SIZE;
+            if next == read {
+                break;
+            }
+            unsafe { channel.buffer.add(write).write_volatile(byte) };
+            write = next;
+        }
+        channel.write.store(write, Ordering::Release);
+    }
+}
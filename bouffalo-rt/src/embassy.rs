@@ -0,0 +1,222 @@
+//! Embassy time driver and executors.
+//!
+//! The time driver counts ticks of the machine timer of the current core, with a
+//! tick rate of 1 MHz. Timer interrupt wakes expired timers in the queue and programs
+//! `mtimecmp` for the next one.
+//!
+//! [`Executor`] runs tasks in thread mode and sleeps with `wfi` when there is no work.
+//! [`InterruptExecutor`] polls tasks in the machine software interrupt, so they preempt
+//! thread mode tasks; there is one such executor on each core.
+//!
+//! # Example
+//!
+//! ```ignore
+//! static EXECUTOR: StaticCell<Executor> = StaticCell::new();
+//!
+//! #[entry]
+//! fn main(p: Peripherals, c: Clocks) -> ! {
+//!     bouffalo_rt::embassy::init();
+//!     EXECUTOR.init(Executor::new()).run(|spawner| {
+//!         spawner.must_spawn(blink(p.gpio.io8.into_floating_output()));
+//!     })
+//! }
+//! ```
+
+use bouffalo_hal::clint::{MachineTimer, RegisterBlock};
+use core::{
+    cell::UnsafeCell,
+    marker::PhantomData,
+    sync::atomic::{AtomicBool, AtomicPtr, Ordering},
+    task::Waker,
+};
+use embassy_executor::{SendSpawner, Spawner, raw};
+use embassy_time_driver::Driver;
+use embassy_time_queue_utils::Queue;
+
+cfg_if::cfg_if! {
+    if #[cfg(any(feature = "bl808-mcu", feature = "bl808-dsp", feature = "bl808-lp"))] {
+        use crate::soc::bl808::{CLINT, TIME_SOURCE};
+        #[cfg(not(feature = "bl808-dsp"))]
+        use crate::soc::bl808::CLIC_INTIE_BASE;
+    } else if #[cfg(feature = "bl702")] {
+        use crate::soc::bl702::{CLINT, TIME_SOURCE, CLIC_INTIE_BASE};
+    } else {
+        compile_error!("embassy support requires a chip with machine timer dispatch");
+    }
+}
+
+const MACHINE_SOFTWARE: usize = 3;
+const MACHINE_TIMER: usize = 7;
+
+/// Initialize time driver and enable machine timer interrupt of the current core.
+///
+/// [`Executor::run`] sets global machine interrupt enable in `mstatus`.
+#[inline]
+pub fn init() {
+    timer().set_timecmp(u64::MAX);
+    enable_interrupt(MACHINE_TIMER);
+}
+
+#[inline]
+fn timer() -> MachineTimer<&'static RegisterBlock> {
+    MachineTimer::new(unsafe { &*CLINT::ptr() }, TIME_SOURCE)
+}
+
+#[inline]
+fn enable_interrupt(id: usize) {
+    #[cfg(feature = "bl808-dsp")]
+    unsafe {
+        core::arch::asm!("csrs mie, {}", in(reg) 1usize << id)
+    };
+    #[cfg(not(feature = "bl808-dsp"))]
+    unsafe {
+        ((CLIC_INTIE_BASE + id) as *mut u8).write_volatile(1)
+    };
+}
+
+struct MachineTimerDriver {
+    // Only accessed with interrupts of the current core masked.
+    queue: UnsafeCell<Queue>,
+}
+
+unsafe impl Sync for MachineTimerDriver {}
+
+impl Driver for MachineTimerDriver {
+    #[inline]
+    fn now(&self) -> u64 {
+        timer().current_time()
+    }
+    #[inline]
+    fn schedule_wake(&self, at: u64, waker: &Waker) {
+        crate::interrupt_free(|| {
+            let queue = unsafe { &mut *self.queue.get() };
+            if queue.schedule_wake(at, waker) {
+                // A deadline already passed makes the timer interrupt pending at once.
+                let timer = timer();
+                timer.set_timecmp(queue.next_expiration(timer.current_time()));
+            }
+        })
+    }
+}
+
+embassy_time_driver::time_driver_impl!(static DRIVER: MachineTimerDriver = MachineTimerDriver {
+    queue: UnsafeCell::new(Queue::new()),
+});
+
+/// Wake expired timers and program the next deadline.
+pub(crate) fn on_timer_interrupt() {
+    crate::interrupt_free(|| {
+        let queue = unsafe { &mut *DRIVER.queue.get() };
+        let timer = timer();
+        timer.set_timecmp(queue.next_expiration(timer.current_time()));
+    })
+}
+
+/// Poll the interrupt executor, if any is started on this core.
+pub(crate) fn on_software_interrupt() {
+    timer().clear_software_interrupt();
+    let executor = INTERRUPT_EXECUTOR.load(Ordering::Acquire);
+    if !executor.is_null() {
+        unsafe { (*executor).poll() };
+    }
+}
+
+// Pender contexts telling which executor has work to do.
+const THREAD_PENDER: usize = 0;
+const INTERRUPT_PENDER: usize = 1;
+
+static THREAD_SIGNALED: AtomicBool = AtomicBool::new(false);
+static INTERRUPT_EXECUTOR: AtomicPtr<raw::Executor> = AtomicPtr::new(core::ptr::null_mut());
+
+#[unsafe(export_name = "__pender")]
+fn __pender(context: *mut ()) {
+    match context as usize {
+        THREAD_PENDER => THREAD_SIGNALED.store(true, Ordering::Release),
+        INTERRUPT_PENDER => timer().set_software_interrupt(),
+        _ => unreachable!(),
+    }
+}
+
+/// Thread mode executor, sleeps until an interrupt when no task is ready.
+pub struct Executor {
+    inner: raw::Executor,
+    not_send: PhantomData<*mut ()>,
+}
+
+impl Executor {
+    /// Create a thread mode executor.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            inner: raw::Executor::new(THREAD_PENDER as *mut ()),
+            not_send: PhantomData,
+        }
+    }
+    /// Spawn initial tasks with `init` and run the executor forever.
+    pub fn run(&'static mut self, init: impl FnOnce(Spawner)) -> ! {
+        init(self.inner.spawner());
+        #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+        unsafe {
+            core::arch::asm!("csrsi mstatus, 8")
+        };
+        loop {
+            unsafe { self.inner.poll() };
+            // Masking interrupts closes the race between checking the flag and `wfi`;
+            // a pending interrupt still wakes the core and is taken after unmasking.
+            crate::interrupt_free(|| {
+                if !THREAD_SIGNALED.load(Ordering::Acquire) {
+                    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+                    unsafe {
+                        core::arch::asm!("wfi")
+                    };
+                }
+                THREAD_SIGNALED.store(false, Ordering::Relaxed);
+            });
+        }
+    }
+}
+
+impl Default for Executor {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Executor polled in machine software interrupt of the current core.
+pub struct InterruptExecutor {
+    inner: raw::Executor,
+}
+
+unsafe impl Sync for InterruptExecutor {}
+
+impl InterruptExecutor {
+    /// Create an interrupt mode executor.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            inner: raw::Executor::new(INTERRUPT_PENDER as *mut ()),
+        }
+    }
+    /// Start the executor and enable machine software interrupt.
+    ///
+    /// Returns a spawner that thread mode code may use to spawn tasks on this executor.
+    /// Panics if another interrupt executor is already started on this core.
+    pub fn start(&'static self) -> SendSpawner {
+        crate::interrupt_free(|| {
+            if !INTERRUPT_EXECUTOR.load(Ordering::Relaxed).is_null() {
+                panic!("interrupt executor is already started on this core");
+            }
+            INTERRUPT_EXECUTOR.store(&self.inner as *const _ as *mut _, Ordering::Release);
+        });
+        enable_interrupt(MACHINE_SOFTWARE);
+        self.inner.spawner().make_send()
+    }
+}
+
+impl Default for InterruptExecutor {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,233 @@
+//! Synchronous exception decoding and default exception handler.
+//!
+//! When no `#[exception]` handler is defined, the runtime links in a default handler
+//! which decodes `mcause` and `mtval`, prints the exception and the trap frame to the
+//! global console set by [`console::init`](crate::console::init), and halts the current
+//! core.
+
+use core::fmt;
+
+/// Synchronous exception decoded from `mcause` and `mtval` registers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Exception {
+    /// Instruction address misaligned.
+    InstructionMisaligned {
+        /// Faulting instruction address.
+        address: usize,
+    },
+    /// Instruction access fault.
+    InstructionFault {
+        /// Faulting instruction address.
+        address: usize,
+    },
+    /// Illegal instruction.
+    IllegalInstruction {
+        /// Faulting instruction bits, or zero if not provided by the core.
+        instruction: usize,
+    },
+    /// Breakpoint, e.g. by an `ebreak` instruction.
+    Breakpoint,
+    /// Load address misaligned.
+    LoadMisaligned {
+        /// Faulting data address.
+        address: usize,
+    },
+    /// Load access fault.
+    LoadFault {
+        /// Faulting data address.
+        address: usize,
+    },
+    /// Store or AMO address misaligned.
+    StoreMisaligned {
+        /// Faulting data address.
+        address: usize,
+    },
+    /// Store or AMO access fault, which includes stack overflow into a PMP guard.
+    StoreFault {
+        /// Faulting data address.
+        address: usize,
+    },
+    /// Environment call from U-mode.
+    UserEnvCall,
+    /// Environment call from S-mode.
+    SupervisorEnvCall,
+    /// Environment call from M-mode.
+    MachineEnvCall,
+    /// Instruction page fault.
+    InstructionPageFault {
+        /// Faulting virtual address.
+        address: usize,
+    },
+    /// Load page fault.
+    LoadPageFault {
+        /// Faulting virtual address.
+        address: usize,
+    },
+    /// Store or AMO page fault.
+    StorePageFault {
+        /// Faulting virtual address.
+        address: usize,
+    },
+    /// Reserved or platform specific exception code.
+    Unknown {
+        /// Exception code from `mcause`.
+        code: usize,
+        /// Value of `mtval` register.
+        value: usize,
+    },
+}
+
+impl Exception {
+    /// Decode exception from `mcause` and `mtval` register values.
+    ///
+    /// The interrupt flag and CLIC specific fields in `mcause` are ignored;
+    /// only the low 12-bit exception code is used.
+    #[inline]
+    pub const fn new(mcause: usize, mtval: usize) -> Self {
+        match mcause & 0xfff {
+            0 => Exception::InstructionMisaligned { address: mtval },
+            1 => Exception::InstructionFault { address: mtval },
+            2 => Exception::IllegalInstruction { instruction: mtval },
+            3 => Exception::Breakpoint,
+            4 => Exception::LoadMisaligned { address: mtval },
+            5 => Exception::LoadFault { address: mtval },
+            6 => Exception::StoreMisaligned { address: mtval },
+            7 => Exception::StoreFault { address: mtval },
+            8 => Exception::UserEnvCall,
+            9 => Exception::SupervisorEnvCall,
+            11 => Exception::MachineEnvCall,
+            12 => Exception::InstructionPageFault { address: mtval },
+            13 => Exception::LoadPageFault { address: mtval },
+            15 => Exception::StorePageFault { address: mtval },
+            code => Exception::Unknown { code, value: mtval },
+        }
+    }
+}
+
+impl fmt::Display for Exception {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Exception::InstructionMisaligned { address } => {
+                write!(f, "instruction address misaligned at {address:#x}")
+            }
+            Exception::InstructionFault { address } => {
+                write!(f, "instruction access fault at {address:#x}")
+            }
+            Exception::IllegalInstruction { instruction } => {
+                write!(f, "illegal instruction {instruction:#x}")
+            }
+            Exception::Breakpoint => f.write_str("breakpoint"),
+            Exception::LoadMisaligned { address } => {
+                write!(f, "load address misaligned at {address:#x}")
+            }
+            Exception::LoadFault { address } => write!(f, "load access fault at {address:#x}"),
+            Exception::StoreMisaligned { address } => {
+                write!(f, "store address misaligned at {address:#x}")
+            }
+            Exception::StoreFault { address } => write!(f, "store access fault at {address:#x}"),
+            Exception::UserEnvCall => f.write_str("environment call from U-mode"),
+            Exception::SupervisorEnvCall => f.write_str("environment call from S-mode"),
+            Exception::MachineEnvCall => f.write_str("environment call from M-mode"),
+            Exception::InstructionPageFault { address } => {
+                write!(f, "instruction page fault at {address:#x}")
+            }
+            Exception::LoadPageFault { address } => write!(f, "load page fault at {address:#x}"),
+            Exception::StorePageFault { address } => {
+                write!(f, "store page fault at {address:#x}")
+            }
+            Exception::Unknown { code, value } => {
+                write!(f, "unknown exception {code} (mtval {value:#x})")
+            }
+        }
+    }
+}
+
+/// Read `mtval` register of the current trap.
+///
+/// Only meaningful inside an exception handler before any nested trap occurs.
+#[inline]
+pub fn mtval() -> usize {
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    {
+        let value: usize;
+        unsafe { core::arch::asm!("csrr {}, mtval", out(reg) value) };
+        value
+    }
+    #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+    0
+}
+
+#[cfg(any(
+    feature = "bl616",
+    feature = "bl808-mcu",
+    feature = "bl808-dsp",
+    feature = "bl808-lp",
+    feature = "bl702"
+))]
+#[doc(hidden)]
+#[unsafe(no_mangle)]
+pub extern "C" fn default_exception_handler(tf: &mut crate::TrapFrame) -> ! {
+    let mtval = mtval();
+    let exception = Exception::new(tf.mcause, mtval);
+    // Take the console so that a nested exception while printing does not reuse it.
+    if let Some(console) = crate::console::take_for_halt() {
+        let _ = writeln!(
+            console,
+            "unhandled exception: {exception}, mepc {:#x}, mtval {mtval:#x}, sp {:#x}",
+            tf.mepc, tf.sp
+        );
+        let _ = writeln!(console, "{tf:#x?}");
+    }
+    loop {
+        core::hint::spin_loop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Exception;
+
+    #[test]
+    fn decode_exception() {
+        assert_eq!(
+            Exception::new(7, 0x6203_0040),
+            Exception::StoreFault {
+                address: 0x6203_0040
+            }
+        );
+        assert_eq!(
+            Exception::new(2, 0x0000_0073),
+            Exception::IllegalInstruction {
+                instruction: 0x0000_0073
+            }
+        );
+        assert_eq!(Exception::new(3, 0x1234), Exception::Breakpoint);
+        assert_eq!(Exception::new(11, 0), Exception::MachineEnvCall);
+        // CLIC keeps extra fields above the exception code.
+        assert_eq!(
+            Exception::new(0x3800_0005, 0x10),
+            Exception::LoadFault { address: 0x10 }
+        );
+        assert_eq!(
+            Exception::new(24, 0x55),
+            Exception::Unknown {
+                code: 24,
+                value: 0x55
+            }
+        );
+    }
+
+    #[test]
+    fn display_exception() {
+        extern crate std;
+        use std::string::ToString;
+        assert_eq!(
+            Exception::StoreFault { address: 0x40 }.to_string(),
+            "store access fault at 0x40"
+        );
+        assert_eq!(
+            Exception::Unknown { code: 24, value: 1 }.to_string(),
+            "unknown exception 24 (mtval 0x1)"
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! Global heap allocator.
+//!
+//! [`init`] places the heap on on-chip RAM left free after static data of the current
+//! core: OCRAM on BL616 and BL702, WRAM on BL808 M0, VRAM on BL808 D0 and RAM on BL808
+//! LP. Larger heaps can be placed on PSRAM with [`init_region`], using the range
+//! returned by PSRAM initialization.
+//!
+//! The allocator locks through `critical-section`; this feature enables the
+//! `critical-section-single-core` implementation, which masks machine interrupts of
+//! the current core.
+//!
+//! # Example
+//!
+//! ```ignore
+//! extern crate alloc;
+//!
+//! bouffalo_rt::heap::init();
+//! let buf = alloc::vec![0u8; 1024];
+//! ```
+
+use core::{
+    ops::Range,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use embedded_alloc::LlffHeap;
+
+use crate::interrupt_free;
+
+#[cfg_attr(target_os = "none", global_allocator)]
+static HEAP: LlffHeap = LlffHeap::empty();
+
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+unsafe extern "C" {
+    static sheap: u8;
+    static eheap: u8;
+}
+
+/// Initialize global heap on free on-chip RAM of the current core.
+///
+/// # Panics
+///
+/// Panics if the heap is already initialized.
+#[inline]
+pub fn init() {
+    let start = &raw const sheap as usize;
+    let end = &raw const eheap as usize;
+    unsafe { init_region(start..end) }
+}
+
+/// Initialize global heap on given memory range, e.g. initialized PSRAM.
+///
+/// # Safety
+///
+/// The range must be valid read-write memory not used for any other purpose.
+///
+/// # Panics
+///
+/// Panics if the heap is already initialized.
+#[inline]
+pub unsafe fn init_region(range: Range<usize>) {
+    let initialized = interrupt_free(|| {
+        let initialized = INITIALIZED.load(Ordering::Relaxed);
+        INITIALIZED.store(true, Ordering::Relaxed);
+        initialized
+    });
+    if initialized {
+        panic!("heap is already initialized");
+    }
+    unsafe { HEAP.init(range.start, range.len()) }
+}
+
+/// Number of bytes currently allocated on heap.
+#[inline]
+pub fn used() -> usize {
+    HEAP.used()
+}
+
+/// Number of bytes currently free on heap.
+#[inline]
+pub fn free() -> usize {
+    HEAP.free()
+}
@@ -7,9 +7,44 @@ mod macros;
 
 pub use bouffalo_rt_macros::{entry, exception, interrupt};
 
+#[doc(hidden)]
+pub use bouffalo_hal as __hal;
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
 pub mod arch;
+pub mod console;
+#[cfg(any(
+    feature = "critical-section-single-core",
+    feature = "critical-section-multi-core"
+))]
+mod critical_section;
+#[cfg(feature = "defmt")]
+pub mod defmt;
+#[cfg(feature = "embassy")]
+pub mod embassy;
+pub mod exception;
+#[cfg(feature = "alloc")]
+pub mod heap;
+#[cfg(feature = "console-mux")]
+pub mod mux;
+pub mod ota;
+#[cfg(feature = "panic-uart")]
+pub mod panic;
+pub mod partition;
+#[cfg(feature = "rtic")]
+pub mod rtic;
 pub mod soc;
 
+#[cfg(all(feature = "embassy", feature = "rtic"))]
+compile_error!("features `embassy` and `rtic` both drive the machine timer, enable only one");
+
+#[cfg(all(
+    feature = "custom-boot-header",
+    not(any(feature = "bl808-mcu", feature = "bl808-dsp"))
+))]
+compile_error!("feature `custom-boot-header` is only supported on BL808 M0 and D0 cores");
+
 pub mod prelude {
     pub use bouffalo_hal::prelude::*;
 }
@@ -42,6 +77,38 @@ cfg_if::cfg_if! {
 #[unsafe(no_mangle)]
 pub extern "C" fn default_handler() {}
 
+static PERIPHERALS_TAKEN: AtomicBool = AtomicBool::new(false);
+
+/// Mark peripherals as taken, returns `false` if they were taken before.
+///
+/// Every core runs its own image with its own flag, so masking interrupts of the current
+/// core is enough; BL808 LP core has no atomic read-modify-write instructions.
+#[inline]
+pub(crate) fn take_peripherals_once() -> bool {
+    interrupt_free(|| {
+        let taken = PERIPHERALS_TAKEN.load(Ordering::Relaxed);
+        PERIPHERALS_TAKEN.store(true, Ordering::Relaxed);
+        !taken
+    })
+}
+
+/// Run `f` with machine interrupts of the current core masked.
+#[inline]
+pub(crate) fn interrupt_free<R>(f: impl FnOnce() -> R) -> R {
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    let mstatus: usize;
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    unsafe {
+        core::arch::asm!("csrrci {}, mstatus, 8", out(reg) mstatus)
+    };
+    let ans = f();
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    unsafe {
+        core::arch::asm!("csrs mstatus, {}", in(reg) mstatus & 8)
+    };
+    ans
+}
+
 /// Flash configuration in ROM header.
 #[repr(C)]
 pub struct HalFlashConfig {
@@ -138,6 +205,193 @@ impl HalFlashConfig {
     }
 }
 
+impl HalFlashConfig {
+    /// Start building a flash configuration from the runtime default.
+    #[inline]
+    pub const fn builder() -> FlashConfigBuilder {
+        FlashConfigBuilder {
+            cfg: SpiFlashCfgType::DEFAULT,
+        }
+    }
+}
+
+/// Serial flash interface mode used to fetch code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum FlashIoMode {
+    /// Single line command, address and data.
+    Normal = 0,
+    /// Single line address, dual line data.
+    DualOutput = 1,
+    /// Single line address, quad line data.
+    QuadOutput = 2,
+    /// Dual line address and data.
+    DualIo = 3,
+    /// Quad line address and data.
+    QuadIo = 4,
+}
+
+/// Compile-time builder of [`HalFlashConfig`] with presets for common flash parts.
+///
+/// ```ignore
+/// const FLASH: HalFlashConfig = FlashConfigBuilder::gd25q64()
+///     .io_mode(FlashIoMode::QuadIo)
+///     .build();
+/// ```
+pub struct FlashConfigBuilder {
+    cfg: SpiFlashCfgType,
+}
+
+impl FlashConfigBuilder {
+    /// Winbond W25Q64 series, 8 MiB.
+    #[inline]
+    pub const fn w25q64() -> Self {
+        Self::winbond().erase_times(400, 1600, 2000)
+    }
+    /// Winbond W25Q128 series, 16 MiB.
+    #[inline]
+    pub const fn w25q128() -> Self {
+        Self::winbond().erase_times(400, 1600, 2000)
+    }
+    /// GigaDevice GD25Q64 series, 8 MiB.
+    #[inline]
+    pub const fn gd25q64() -> Self {
+        Self::gigadevice().erase_times(300, 1000, 1200)
+    }
+    /// GigaDevice GD25Q128 series, 16 MiB.
+    #[inline]
+    pub const fn gd25q128() -> Self {
+        Self::gigadevice().erase_times(400, 1200, 1600)
+    }
+    #[inline]
+    const fn winbond() -> Self {
+        let mut cfg = SpiFlashCfgType::DEFAULT;
+        cfg.mid = 0xef;
+        // Quad enable bit lives in status register 2, written by its own command.
+        cfg.qe_write_reg_len = 0x01;
+        cfg.write_reg_cmd = [0x01, 0x31, 0x00, 0x00];
+        cfg.time_page_pgm = 3;
+        Self { cfg }
+    }
+    #[inline]
+    const fn gigadevice() -> Self {
+        let mut cfg = SpiFlashCfgType::DEFAULT;
+        cfg.mid = 0xc8;
+        cfg.time_page_pgm = 3;
+        Self { cfg }
+    }
+    /// Set interface mode used to fetch code.
+    #[inline]
+    pub const fn io_mode(mut self, mode: FlashIoMode) -> Self {
+        self.cfg.io_mode = (self.cfg.io_mode & !0x0f) | mode as u8;
+        self
+    }
+    /// Set manufacturer ID expected from JEDEC ID command.
+    #[inline]
+    pub const fn manufacturer_id(mut self, mid: u8) -> Self {
+        self.cfg.mid = mid;
+        self
+    }
+    /// Set clock delay and clock invert settings of flash interface.
+    #[inline]
+    pub const fn clock_delay(mut self, delay: u8, invert: u8) -> Self {
+        self.cfg.clk_delay = delay;
+        self.cfg.clk_invert = invert;
+        self
+    }
+    /// Set dummy clocks of fast read commands in dual output, dual I/O, quad output
+    /// and quad I/O modes.
+    #[inline]
+    pub const fn dummy_clocks(
+        mut self,
+        dual_output: u8,
+        dual_io: u8,
+        quad_output: u8,
+        quad_io: u8,
+    ) -> Self {
+        self.cfg.fr_do_dmy_clk = dual_output;
+        self.cfg.fr_dio_dmy_clk = dual_io;
+        self.cfg.fr_qo_dmy_clk = quad_output;
+        self.cfg.fr_qio_dmy_clk = quad_io;
+        self.cfg.qpi_fr_qio_dmy_clk = quad_io;
+        self
+    }
+    /// Set location of quad enable bit and register lengths to access it.
+    #[inline]
+    pub const fn quad_enable(mut self, index: u8, bit: u8, write_len: u8, read_len: u8) -> Self {
+        self.cfg.qe_index = index;
+        self.cfg.qe_bit = bit;
+        self.cfg.qe_write_reg_len = write_len;
+        self.cfg.qe_read_reg_len = read_len;
+        self
+    }
+    /// Set read and write status register commands.
+    #[inline]
+    pub const fn status_commands(mut self, read: [u8; 4], write: [u8; 4]) -> Self {
+        self.cfg.read_reg_cmd = read;
+        self.cfg.write_reg_cmd = write;
+        self
+    }
+    /// Set 4-KiB sector, 32-KiB block and 64-KiB block erase times in milliseconds.
+    #[inline]
+    pub const fn erase_times(mut self, sector: u16, block_32k: u16, block_64k: u16) -> Self {
+        self.cfg.time_e_sector = sector;
+        self.cfg.time_e_32k = block_32k;
+        self.cfg.time_e_64k = block_64k;
+        self
+    }
+    /// Set page program time in milliseconds.
+    #[inline]
+    pub const fn page_program_time(mut self, time: u16) -> Self {
+        self.cfg.time_page_pgm = time;
+        self
+    }
+    /// Set chip erase time in milliseconds.
+    #[inline]
+    pub const fn chip_erase_time(mut self, time: u16) -> Self {
+        self.cfg.time_ce = time;
+        self
+    }
+    /// Validate and build the flash configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics, or fails compilation in const context, if the configuration is invalid.
+    #[inline]
+    pub const fn build(self) -> HalFlashConfig {
+        let cfg = &self.cfg;
+        assert!(cfg.io_mode & 0x0f <= 4, "invalid flash interface mode");
+        assert!(
+            cfg.qe_index < 4 && cfg.busy_index < 4 && cfg.wr_enable_index < 4,
+            "status register index out of range"
+        );
+        assert!(
+            cfg.qe_bit < 8 && cfg.busy_bit < 8 && cfg.wr_enable_bit < 8,
+            "status register bit out of range"
+        );
+        assert!(
+            cfg.qe_write_reg_len >= 1
+                && cfg.qe_write_reg_len <= 4
+                && cfg.qe_read_reg_len >= 1
+                && cfg.qe_read_reg_len <= 4,
+            "invalid quad enable register length"
+        );
+        assert!(
+            cfg.fr_do_dmy_clk < 16
+                && cfg.fr_dio_dmy_clk < 16
+                && cfg.fr_qo_dmy_clk < 16
+                && cfg.fr_qio_dmy_clk < 16,
+            "too many dummy clocks"
+        );
+        assert!(cfg.clk_delay < 0x80, "invalid clock delay");
+        assert!(
+            cfg.page_size.is_power_of_two() && cfg.sector_size != 0,
+            "invalid flash geometry"
+        );
+        HalFlashConfig::new(self.cfg)
+    }
+}
+
 #[repr(C)]
 struct SpiFlashCfgType {
     /// Serail flash uint32erface mode,bit0-3:IF mode,bit4:unwrap,bit5:32-bits addr mode support.
@@ -286,6 +540,84 @@ struct SpiFlashCfgType {
     qe_data: u8,
 }
 
+impl SpiFlashCfgType {
+    /// Default configuration for common serial flash parts.
+    const DEFAULT: Self = SpiFlashCfgType {
+        io_mode: 0x11,
+        c_read_support: 0x00,
+        clk_delay: 0x01,
+        clk_invert: 0x01,
+        reset_en_cmd: 0x66,
+        reset_cmd: 0x99,
+        reset_cread_cmd: 0xff,
+        reset_cread_cmd_size: 0x03,
+        jedec_id_cmd: 0x9f,
+        jedec_id_cmd_dmy_clk: 0x00,
+        enter_32_bits_addr_cmd: 0xb7,
+        exit_32_bits_addr_cmd: 0xe9,
+        sector_size: 0x04,
+        mid: 0x00,
+        page_size: 0x100,
+        chip_erase_cmd: 0xc7,
+        sector_erase_cmd: 0x20,
+        blk32_erase_cmd: 0x52,
+        blk64_erase_cmd: 0xd8,
+        write_enable_cmd: 0x06,
+        page_program_cmd: 0x02,
+        qpage_program_cmd: 0x32,
+        qpp_addr_mode: 0x00,
+        fast_read_cmd: 0x0b,
+        fr_dmy_clk: 0x01,
+        qpi_fast_read_cmd: 0x0b,
+        qpi_fr_dmy_clk: 0x01,
+        fast_read_do_cmd: 0x3b,
+        fr_do_dmy_clk: 0x01,
+        fast_read_dio_cmd: 0xbb,
+        fr_dio_dmy_clk: 0x00,
+        fast_read_qo_cmd: 0x6b,
+        fr_qo_dmy_clk: 0x01,
+        fast_read_qio_cmd: 0xeb,
+        fr_qio_dmy_clk: 0x02,
+        qpi_fast_read_qio_cmd: 0xeb,
+        qpi_fr_qio_dmy_clk: 0x02,
+        qpi_page_program_cmd: 0x02,
+        writev_reg_enable_cmd: 0x50,
+        wr_enable_index: 0x00,
+        qe_index: 0x01,
+        busy_index: 0x00,
+        wr_enable_bit: 0x01,
+        qe_bit: 0x01,
+        busy_bit: 0x00,
+        wr_enable_read_reg_len: 0x01,
+        wr_enable_write_reg_len: 0x02,
+        qe_write_reg_len: 0x02,
+        qe_read_reg_len: 0x01,
+        release_power_down: 0xab,
+        busy_read_reg_len: 0x01,
+        read_reg_cmd: [0x05, 0x35, 0x00, 0x00],
+        write_reg_cmd: [0x01, 0x01, 0x00, 0x00],
+        enter_qpi: 0x38,
+        exit_qpi: 0xff,
+        c_read_mode: 0x20,
+        cr_exit: 0xf0,
+        burst_wrap_cmd: 0x77,
+        burst_wrap_cmd_dmy_clk: 0x03,
+        burst_wrap_data_mode: 0x02,
+        burst_wrap_data: 0x40,
+        de_burst_wrap_cmd: 0x77,
+        de_burst_wrap_cmd_dmy_clk: 0x03,
+        de_burst_wrap_data_mode: 0x02,
+        de_burst_wrap_data: 0xf0,
+        time_e_sector: 300,
+        time_e_32k: 1200,
+        time_e_64k: 1200,
+        time_ce: 33000,
+        time_page_pgm: 50,
+        pd_delay: 20,
+        qe_data: 0,
+    };
+}
+
 #[repr(C)]
 struct HalBasicConfig {
     /// Flags 4bytes
@@ -328,83 +660,93 @@ pub struct HalPatchCfg {
     value: u32,
 }
 
+/// Boot header verification error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeaderError {
+    /// Buffer is shorter than boot header.
+    Length(usize),
+    /// Wrong boot header magic number.
+    Magic(u32),
+    /// Wrong flash configuration magic number.
+    FlashConfigMagic(u32),
+    /// Flash configuration checksum mismatch.
+    FlashConfigCrc {
+        /// Checksum stored in header.
+        stored: u32,
+        /// Checksum computed from header content.
+        computed: u32,
+    },
+    /// Wrong clock configuration magic number.
+    ClockConfigMagic(u32),
+    /// Clock configuration checksum mismatch.
+    ClockConfigCrc {
+        /// Checksum stored in header.
+        stored: u32,
+        /// Checksum computed from header content.
+        computed: u32,
+    },
+    /// Whole header checksum mismatch.
+    HeaderCrc {
+        /// Checksum stored in header.
+        stored: u32,
+        /// Checksum computed from header content.
+        computed: u32,
+    },
+}
+
+/// Verify magic numbers and checksums of a raw boot header of `len` bytes, with clock
+/// configuration of `clock_len` bytes following flash configuration.
+fn verify_header(bytes: &[u8], len: usize, clock_len: usize) -> Result<(), HeaderError> {
+    const FLASH_OFFSET: usize = 0x08;
+    const CLOCK_OFFSET: usize = FLASH_OFFSET + size_of::<HalFlashConfig>();
+    let word = |offset: usize| {
+        u32::from_le_bytes([
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ])
+    };
+    let crc = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+    if bytes.len() < len {
+        return Err(HeaderError::Length(bytes.len()));
+    }
+    if word(0) != 0x504E4642 {
+        return Err(HeaderError::Magic(word(0)));
+    }
+    if word(FLASH_OFFSET) != 0x47464346 {
+        return Err(HeaderError::FlashConfigMagic(word(FLASH_OFFSET)));
+    }
+    let (stored, computed) = (
+        word(CLOCK_OFFSET - 4),
+        crc.checksum(&bytes[FLASH_OFFSET + 4..CLOCK_OFFSET - 4]),
+    );
+    if stored != computed {
+        return Err(HeaderError::FlashConfigCrc { stored, computed });
+    }
+    if word(CLOCK_OFFSET) != 0x47464350 {
+        return Err(HeaderError::ClockConfigMagic(word(CLOCK_OFFSET)));
+    }
+    let clock_end = CLOCK_OFFSET + clock_len;
+    let (stored, computed) = (
+        word(clock_end - 4),
+        crc.checksum(&bytes[CLOCK_OFFSET + 4..clock_end - 4]),
+    );
+    if stored != computed {
+        return Err(HeaderError::ClockConfigCrc { stored, computed });
+    }
+    let (stored, computed) = (word(len - 4), crc.checksum(&bytes[..len - 4]));
+    if stored != computed {
+        return Err(HeaderError::HeaderCrc { stored, computed });
+    }
+    Ok(())
+}
+
 /// Flash configuration at boot-time.
+#[cfg(not(feature = "custom-boot-header"))]
 #[cfg_attr(target_os = "none", unsafe(link_section = ".head.flash"))]
 #[used]
-pub static FLASH_CONFIG: HalFlashConfig = HalFlashConfig::new(SpiFlashCfgType {
-    io_mode: 0x11,
-    c_read_support: 0x00,
-    clk_delay: 0x01,
-    clk_invert: 0x01,
-    reset_en_cmd: 0x66,
-    reset_cmd: 0x99,
-    reset_cread_cmd: 0xff,
-    reset_cread_cmd_size: 0x03,
-    jedec_id_cmd: 0x9f,
-    jedec_id_cmd_dmy_clk: 0x00,
-    enter_32_bits_addr_cmd: 0xb7,
-    exit_32_bits_addr_cmd: 0xe9,
-    sector_size: 0x04,
-    mid: 0x00,
-    page_size: 0x100,
-    chip_erase_cmd: 0xc7,
-    sector_erase_cmd: 0x20,
-    blk32_erase_cmd: 0x52,
-    blk64_erase_cmd: 0xd8,
-    write_enable_cmd: 0x06,
-    page_program_cmd: 0x02,
-    qpage_program_cmd: 0x32,
-    qpp_addr_mode: 0x00,
-    fast_read_cmd: 0x0b,
-    fr_dmy_clk: 0x01,
-    qpi_fast_read_cmd: 0x0b,
-    qpi_fr_dmy_clk: 0x01,
-    fast_read_do_cmd: 0x3b,
-    fr_do_dmy_clk: 0x01,
-    fast_read_dio_cmd: 0xbb,
-    fr_dio_dmy_clk: 0x00,
-    fast_read_qo_cmd: 0x6b,
-    fr_qo_dmy_clk: 0x01,
-    fast_read_qio_cmd: 0xeb,
-    fr_qio_dmy_clk: 0x02,
-    qpi_fast_read_qio_cmd: 0xeb,
-    qpi_fr_qio_dmy_clk: 0x02,
-    qpi_page_program_cmd: 0x02,
-    writev_reg_enable_cmd: 0x50,
-    wr_enable_index: 0x00,
-    qe_index: 0x01,
-    busy_index: 0x00,
-    wr_enable_bit: 0x01,
-    qe_bit: 0x01,
-    busy_bit: 0x00,
-    wr_enable_read_reg_len: 0x01,
-    wr_enable_write_reg_len: 0x02,
-    qe_write_reg_len: 0x02,
-    qe_read_reg_len: 0x01,
-    release_power_down: 0xab,
-    busy_read_reg_len: 0x01,
-    read_reg_cmd: [0x05, 0x35, 0x00, 0x00],
-    write_reg_cmd: [0x01, 0x01, 0x00, 0x00],
-    enter_qpi: 0x38,
-    exit_qpi: 0xff,
-    c_read_mode: 0x20,
-    cr_exit: 0xf0,
-    burst_wrap_cmd: 0x77,
-    burst_wrap_cmd_dmy_clk: 0x03,
-    burst_wrap_data_mode: 0x02,
-    burst_wrap_data: 0x40,
-    de_burst_wrap_cmd: 0x77,
-    de_burst_wrap_cmd_dmy_clk: 0x03,
-    de_burst_wrap_data_mode: 0x02,
-    de_burst_wrap_data: 0xf0,
-    time_e_sector: 300,
-    time_e_32k: 1200,
-    time_e_64k: 1200,
-    time_ce: 33000,
-    time_page_pgm: 50,
-    pd_delay: 20,
-    qe_data: 0,
-});
+pub static FLASH_CONFIG: HalFlashConfig = HalFlashConfig::new(SpiFlashCfgType::DEFAULT);
 
 /// Decrypt-on-fly region length.
 ///
@@ -428,9 +770,37 @@ pub static CRC32: u32 = 0xdeadbeef;
 
 #[cfg(test)]
 mod tests {
-    use crate::{HalBasicConfig, HalFlashConfig, HalPatchCfg, SpiFlashCfgType};
+    use crate::{
+        FlashConfigBuilder, FlashIoMode, HalBasicConfig, HalFlashConfig, HalPatchCfg,
+        SpiFlashCfgType,
+    };
     use core::mem::offset_of;
 
+    #[test]
+    fn flash_config_builder() {
+        const FLASH: HalFlashConfig = FlashConfigBuilder::w25q128()
+            .io_mode(FlashIoMode::QuadIo)
+            .build();
+        assert_eq!(FLASH.magic, 0x47464346);
+        assert_eq!(FLASH.cfg.mid, 0xef);
+        assert_eq!(FLASH.cfg.io_mode, 0x14);
+        assert_eq!(FLASH.cfg.write_reg_cmd, [0x01, 0x31, 0x00, 0x00]);
+        let default = HalFlashConfig::builder().build();
+        assert_eq!(
+            default.crc32,
+            HalFlashConfig::new(SpiFlashCfgType::DEFAULT).crc32
+        );
+        assert_eq!(FlashConfigBuilder::gd25q64().build().cfg.mid, 0xc8);
+    }
+
+    #[test]
+    #[should_panic(expected = "too many dummy clocks")]
+    fn flash_config_builder_invalid() {
+        FlashConfigBuilder::gd25q64()
+            .dummy_clocks(1, 0, 1, 16)
+            .build();
+    }
+
     #[test]
     fn struct_lengths() {
         use core::mem::size_of;
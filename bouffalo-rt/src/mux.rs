@@ -0,0 +1,207 @@
+//! Multiplexed console sharing one serial between log frames and an interactive channel.
+//!
+//! Every write on a [`Channel`] is sent as a packet: a [`SYNC`] byte, the channel number,
+//! then the payload with [`SYNC`] and [`ESCAPE`] bytes escaped as in SLIP. A host tool
+//! switches to the channel given after each [`SYNC`], so `defmt` frames on
+//! [`LOG_CHANNEL`] and text on [`CONSOLE_CHANNEL`] can be told apart on one wire.
+//! Packets are written with machine interrupts of the current core masked, so a log frame
+//! from an interrupt handler never splits a console write.
+//!
+//! Bytes received from the host are not multiplexed; every channel reads them unchanged.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let serial = p.uart0.freerun(config, pads, &c).unwrap();
+//! // The multiplexer lives forever, e.g. leak it with the `alloc` feature.
+//! let mux: &'static Multiplexer<_> = Box::leak(Box::new(Multiplexer::new(serial)));
+//! bouffalo_rt::defmt::set_transport(Box::leak(Box::new(mux.channel(LOG_CHANNEL))));
+//! let mut console = mux.channel(CONSOLE_CHANNEL);
+//! writeln!(console, "> ").ok();
+//! ```
+
+use core::{cell::UnsafeCell, fmt};
+use embedded_io::{ErrorType, Read, ReadReady, Write};
+
+/// Byte starting a packet, followed by the channel number.
+pub const SYNC: u8 = 0xc0;
+/// Byte starting an escape sequence in payload.
+pub const ESCAPE: u8 = 0xdb;
+/// Escaped [`SYNC`] byte following [`ESCAPE`].
+pub const ESCAPED_SYNC: u8 = 0xdc;
+/// Escaped [`ESCAPE`] byte following [`ESCAPE`].
+pub const ESCAPED_ESCAPE: u8 = 0xdd;
+
+/// Channel carrying log frames, e.g. `defmt`.
+pub const LOG_CHANNEL: u8 = 0;
+/// Channel carrying interactive console text.
+pub const CONSOLE_CHANNEL: u8 = 1;
+
+/// Serial shared by multiplexed channels.
+pub struct Multiplexer<S> {
+    // Only accessed with interrupts of the current core masked.
+    serial: UnsafeCell<S>,
+}
+
+unsafe impl<S: Send> Sync for Multiplexer<S> {}
+
+impl<S> Multiplexer<S> {
+    /// Create multiplexer over `serial`.
+    #[inline]
+    pub const fn new(serial: S) -> Self {
+        Self {
+            serial: UnsafeCell::new(serial),
+        }
+    }
+    /// Get a writer on channel `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is [`SYNC`] or [`ESCAPE`].
+    #[inline]
+    pub fn channel(&self, id: u8) -> Channel<'_, S> {
+        assert!(id != SYNC && id != ESCAPE, "channel id is a framing byte");
+        Channel { mux: self, id }
+    }
+    /// Release multiplexer and return its serial.
+    #[inline]
+    pub fn free(self) -> S {
+        self.serial.into_inner()
+    }
+    #[inline]
+    fn with_serial<R>(&self, f: impl FnOnce(&mut S) -> R) -> R {
+        crate::interrupt_free(|| f(unsafe { &mut *self.serial.get() }))
+    }
+}
+
+/// One channel of a [`Multiplexer`].
+pub struct Channel<'a, S> {
+    mux: &'a Multiplexer<S>,
+    id: u8,
+}
+
+impl<S> Channel<'_, S> {
+    /// Channel number.
+    #[inline]
+    pub const fn id(&self) -> u8 {
+        self.id
+    }
+}
+
+impl<S: ErrorType> ErrorType for Channel<'_, S> {
+    type Error = S::Error;
+}
+
+impl<S: Write> Write for Channel<'_, S> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let id = self.id;
+        self.mux
+            .with_serial(|serial| write_packet(serial, id, buf))?;
+        Ok(buf.len())
+    }
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.mux.with_serial(|serial| serial.flush())
+    }
+}
+
+impl<S: Read + ReadReady> Read for Channel<'_, S> {
+    /// Wait for bytes from the host; interrupts are only masked while bytes are available.
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let ans = self.mux.with_serial(|serial| match serial.read_ready() {
+                Ok(true) => Some(serial.read(buf)),
+                Ok(false) => None,
+                Err(e) => Some(Err(e)),
+            });
+            if let Some(ans) = ans {
+                return ans;
+            }
+            core::hint::spin_loop();
+        }
+    }
+}
+
+impl<S: Read + ReadReady> ReadReady for Channel<'_, S> {
+    #[inline]
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        self.mux.with_serial(|serial| serial.read_ready())
+    }
+}
+
+impl<S: Write> fmt::Write for Channel<'_, S> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_all(s.as_bytes()).map_err(|_| fmt::Error)
+    }
+}
+
+#[inline]
+fn write_packet<S: Write>(serial: &mut S, id: u8, payload: &[u8]) -> Result<(), S::Error> {
+    serial.write_all(&[SYNC, id])?;
+    let mut rest = payload;
+    while let Some(pos) = rest.iter().position(|&b| b == SYNC || b == ESCAPE) {
+        serial.write_all(&rest[..pos])?;
+        let escaped = if rest[pos] == SYNC {
+            ESCAPED_SYNC
+        } else {
+            ESCAPED_ESCAPE
+        };
+        serial.write_all(&[ESCAPE, escaped])?;
+        rest = &rest[pos + 1..];
+    }
+    serial.write_all(rest)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::{CONSOLE_CHANNEL, LOG_CHANNEL, Multiplexer};
+    use core::fmt::Write as _;
+    use embedded_io::Write;
+    use std::vec::Vec;
+
+    struct Buffer(Vec<u8>);
+
+    impl embedded_io::ErrorType for Buffer {
+        type Error = embedded_io::ErrorKind;
+    }
+
+    impl embedded_io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            self.0.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn struct_multiplexer() {
+        let mux = Multiplexer::new(Buffer(Vec::new()));
+        let mut log = mux.channel(LOG_CHANNEL);
+        let mut console = mux.channel(CONSOLE_CHANNEL);
+        log.write_all(&[0x01, 0xc0, 0x02, 0xdb]).unwrap();
+        console.write_str("ok").unwrap();
+        log.flush().unwrap();
+        assert_eq!(
+            mux.free().0,
+            [
+                0xc0, 0x00, 0x01, 0xdb, 0xdc, 0x02, 0xdb, 0xdd, 0xc0, 0x01, b'o', b'k'
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn struct_multiplexer_framing_channel() {
+        let mux = Multiplexer::new(Buffer(Vec::new()));
+        let _ = mux.channel(0xc0);
+    }
+}
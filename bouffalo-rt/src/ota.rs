@@ -0,0 +1,445 @@
+//! Over-the-air firmware update on A/B firmware slots.
+//!
+//! The firmware partition entry holds two slots; boot2 boots the active one. An update
+//! writes the new image into the inactive slot, checks its CRC32, and writes a partition
+//! table with the slots switched and a larger age into the older of the two table
+//! copies. The update is then staged until reset. Once boot2 has booted the new image,
+//! it stays pending until it calls [`Ota::mark_boot_successful`], and the table copies
+//! differ; [`Ota::rollback`] erases the newer copy so boot2 falls back to the previous
+//! image. No new update can be started while one is staged or pending.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut ota = Ota::new(flash, [0xE000, 0xF000])?;
+//! if ota.is_pending() {
+//!     ota.mark_boot_successful()?;
+//! }
+//! ota.begin_update()?;
+//! ota.write_image(0, &chunk)?;
+//! ota.finish_update(image_len, image_crc32)?;
+//! ```
+
+use crate::partition::{
+    MAX_ENTRIES, PartitionEntry, PartitionTableHeader, PartitionType, crc32_update,
+};
+use embedded_storage::nor_flash::NorFlash;
+
+/// Over-the-air update error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OtaError<E> {
+    /// Underlying flash error.
+    Flash(E),
+    /// No valid partition table copy found.
+    NoPartitionTable,
+    /// Partition table has no firmware entry with two slots.
+    NoFirmwareSlots,
+    /// Image does not fit into the inactive slot.
+    ImageTooLarge,
+    /// Image checksum mismatch after writing.
+    Crc {
+        /// Checksum expected by caller.
+        expected: u32,
+        /// Checksum computed from flash content.
+        computed: u32,
+    },
+    /// No pending update to roll back.
+    NotPending,
+    /// An update is staged or the booted image is not yet confirmed.
+    UpdatePending,
+    /// Staged update has not been booted yet and cannot be confirmed.
+    NotBooted,
+}
+
+/// Over-the-air firmware updater.
+pub struct Ota<F> {
+    flash: F,
+    table_addresses: [u32; 2],
+    newest: usize,
+    state: State,
+    age: u32,
+    entries: [PartitionEntry; MAX_ENTRIES],
+    count: usize,
+    firmware: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Both table copies select the running image.
+    Confirmed,
+    /// A new table copy is written in this boot, but not yet booted.
+    Staged,
+    /// Booted image comes from the newer table copy and is not yet confirmed.
+    Pending,
+}
+
+struct Table {
+    age: u32,
+    entries: [PartitionEntry; MAX_ENTRIES],
+    count: usize,
+}
+
+impl Table {
+    /// Index of the firmware entry with two slots.
+    fn firmware(&self) -> Option<usize> {
+        self.entries[..self.count]
+            .iter()
+            .position(|e| e.kind() == PartitionType::Firmware as u8 && e.has_backup())
+    }
+    /// Check that the firmware entry, if any, selects one of its two slots.
+    fn has_valid_active_slot(&self) -> bool {
+        self.firmware()
+            .is_none_or(|index| self.entries[index].active() <= 1)
+    }
+}
+
+impl<F: NorFlash> Ota<F> {
+    /// Load partition tables from the two flash addresses recorded in boot header.
+    pub fn new(flash: F, table_addresses: [u32; 2]) -> Result<Self, OtaError<F::Error>> {
+        let mut ans = Self {
+            flash,
+            table_addresses,
+            newest: 0,
+            state: State::Confirmed,
+            age: 0,
+            entries: [PartitionEntry::new(PartitionType::Firmware, "", 0, 0); MAX_ENTRIES],
+            count: 0,
+            firmware: 0,
+        };
+        ans.reload()?;
+        Ok(ans)
+    }
+    /// Index of the slot current firmware boots from.
+    #[inline]
+    pub fn active_slot(&self) -> u8 {
+        self.entries[self.firmware].active()
+    }
+    /// Flash address and maximum size of the slot an update is written into.
+    #[inline]
+    pub fn inactive_slot(&self) -> (u32, u32) {
+        let entry = &self.entries[self.firmware];
+        let index = 1 - entry.active() as usize;
+        (entry.address(index), entry.max_len(index))
+    }
+    /// Check if the booted image comes from an update not yet marked successful.
+    #[inline]
+    pub fn is_pending(&self) -> bool {
+        self.state == State::Pending
+    }
+    /// Check if an update is written in this boot and takes effect on next reset.
+    #[inline]
+    pub fn is_staged(&self) -> bool {
+        self.state == State::Staged
+    }
+    /// Erase the inactive slot before writing a new image.
+    pub fn begin_update(&mut self) -> Result<(), OtaError<F::Error>> {
+        self.check_confirmed()?;
+        let (address, size) = self.inactive_slot();
+        let size = size.next_multiple_of(F::ERASE_SIZE as u32);
+        self.flash
+            .erase(address, address + size)
+            .map_err(OtaError::Flash)
+    }
+    /// Write a chunk of new image at `offset` into the inactive slot.
+    pub fn write_image(&mut self, offset: u32, data: &[u8]) -> Result<(), OtaError<F::Error>> {
+        self.check_confirmed()?;
+        let (address, size) = self.inactive_slot();
+        match offset.checked_add(data.len() as u32) {
+            Some(end) if end <= size => {}
+            _ => return Err(OtaError::ImageTooLarge),
+        }
+        self.flash
+            .write(address + offset, data)
+            .map_err(OtaError::Flash)
+    }
+    /// Check the written image against `crc32`, and switch boot2 to the inactive slot.
+    ///
+    /// The update stays staged until reset, and pending after it until the new image
+    /// marks boot as successful.
+    pub fn finish_update(&mut self, len: u32, crc32: u32) -> Result<(), OtaError<F::Error>> {
+        self.check_confirmed()?;
+        let (address, size) = self.inactive_slot();
+        if len > size {
+            return Err(OtaError::ImageTooLarge);
+        }
+        let mut crc = !0;
+        let mut buf = [0u8; 64];
+        let mut offset = 0;
+        while offset < len {
+            let chunk = &mut buf[..(len - offset).min(64) as usize];
+            self.flash
+                .read(address + offset, chunk)
+                .map_err(OtaError::Flash)?;
+            crc = crc32_update(crc, chunk);
+            offset += chunk.len() as u32;
+        }
+        if !crc != crc32 {
+            return Err(OtaError::Crc {
+                expected: crc32,
+                computed: !crc,
+            });
+        }
+        let entry = self.entries[self.firmware];
+        self.entries[self.firmware] = entry
+            .active_index(1 - entry.active())
+            .image_len(len)
+            .age(entry.entry_age().wrapping_add(1));
+        self.age = self.age.wrapping_add(1);
+        let target = 1 - self.newest;
+        self.write_table(target)?;
+        self.newest = target;
+        self.state = State::Staged;
+        Ok(())
+    }
+    /// Confirm the running image, so both partition table copies select it.
+    pub fn mark_boot_successful(&mut self) -> Result<(), OtaError<F::Error>> {
+        match self.state {
+            State::Confirmed => return Ok(()),
+            State::Staged => return Err(OtaError::NotBooted),
+            State::Pending => {}
+        }
+        self.write_table(1 - self.newest)?;
+        self.state = State::Confirmed;
+        Ok(())
+    }
+    /// Drop a staged or pending update, so boot2 boots the previous image on next reset.
+    pub fn rollback(&mut self) -> Result<(), OtaError<F::Error>> {
+        if self.state == State::Confirmed {
+            return Err(OtaError::NotPending);
+        }
+        let address = self.table_addresses[self.newest];
+        self.flash
+            .erase(address, address + F::ERASE_SIZE as u32)
+            .map_err(OtaError::Flash)?;
+        self.reload()
+    }
+    /// Release updater and return its flash.
+    #[inline]
+    pub fn free(self) -> F {
+        self.flash
+    }
+
+    fn check_confirmed(&self) -> Result<(), OtaError<F::Error>> {
+        match self.state {
+            State::Confirmed => Ok(()),
+            State::Staged | State::Pending => Err(OtaError::UpdatePending),
+        }
+    }
+    fn reload(&mut self) -> Result<(), OtaError<F::Error>> {
+        // A copy with valid checksum may still select a slot that does not exist.
+        let tables = [self.read_table(0)?, self.read_table(1)?]
+            .map(|table| table.filter(Table::has_valid_active_slot));
+        let (newest, table) = match &tables {
+            [Some(a), Some(b)] if b.age.wrapping_sub(a.age) as i32 > 0 => (1, b),
+            [Some(a), _] => (0, a),
+            [None, Some(b)] => (1, b),
+            [None, None] => return Err(OtaError::NoPartitionTable),
+        };
+        let firmware = table.firmware().ok_or(OtaError::NoFirmwareSlots)?;
+        let other = &tables[1 - newest];
+        self.newest = newest;
+        self.state = match other {
+            Some(other) if other.age != table.age => State::Pending,
+            _ => State::Confirmed,
+        };
+        self.age = table.age;
+        self.entries = table.entries;
+        self.count = table.count;
+        self.firmware = firmware;
+        Ok(())
+    }
+    fn read_table(&mut self, index: usize) -> Result<Option<Table>, OtaError<F::Error>> {
+        let mut address = self.table_addresses[index];
+        let mut buf = [0u8; 16];
+        self.flash
+            .read(address, &mut buf)
+            .map_err(OtaError::Flash)?;
+        let header = PartitionTableHeader::from_bytes(buf);
+        if !header.is_valid() {
+            return Ok(None);
+        }
+        address += 16;
+        let mut table = Table {
+            age: header.age(),
+            entries: [PartitionEntry::new(PartitionType::Firmware, "", 0, 0); MAX_ENTRIES],
+            count: header.entry_count(),
+        };
+        let mut crc = !0;
+        for entry in &mut table.entries[..table.count] {
+            let mut buf = [0u8; 36];
+            self.flash
+                .read(address, &mut buf)
+                .map_err(OtaError::Flash)?;
+            crc = crc32_update(crc, &buf);
+            *entry = PartitionEntry::from_bytes(buf);
+            address += 36;
+        }
+        let mut buf = [0u8; 4];
+        self.flash
+            .read(address, &mut buf)
+            .map_err(OtaError::Flash)?;
+        if u32::from_le_bytes(buf) != !crc {
+            return Ok(None);
+        }
+        Ok(Some(table))
+    }
+    fn write_table(&mut self, index: usize) -> Result<(), OtaError<F::Error>> {
+        let mut address = self.table_addresses[index];
+        self.flash
+            .erase(address, address + F::ERASE_SIZE as u32)
+            .map_err(OtaError::Flash)?;
+        let header = PartitionTableHeader::new(self.count as u16, self.age);
+        self.flash
+            .write(address, &header.to_bytes())
+            .map_err(OtaError::Flash)?;
+        address += 16;
+        let mut crc = !0;
+        for entry in &self.entries[..self.count] {
+            let buf = entry.to_bytes();
+            crc = crc32_update(crc, &buf);
+            self.flash.write(address, &buf).map_err(OtaError::Flash)?;
+            address += 36;
+        }
+        self.flash
+            .write(address, &(!crc).to_le_bytes())
+            .map_err(OtaError::Flash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ota, OtaError};
+    use crate::partition::{PartitionEntry, PartitionTable};
+    use embedded_storage::nor_flash::{ErrorType, NorFlash, NorFlashErrorKind, ReadNorFlash};
+
+    struct MockFlash([u8; 0x400]);
+
+    impl ErrorType for MockFlash {
+        type Error = NorFlashErrorKind;
+    }
+
+    impl ReadNorFlash for MockFlash {
+        const READ_SIZE: usize = 1;
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            bytes.copy_from_slice(&self.0[offset..offset + bytes.len()]);
+            Ok(())
+        }
+        fn capacity(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    impl NorFlash for MockFlash {
+        const WRITE_SIZE: usize = 1;
+        const ERASE_SIZE: usize = 0x100;
+        fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+            self.0[from as usize..to as usize].fill(0xff);
+            Ok(())
+        }
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            for (dst, src) in self.0[offset..offset + bytes.len()].iter_mut().zip(bytes) {
+                *dst &= *src;
+            }
+            Ok(())
+        }
+    }
+
+    fn flash_with_tables() -> MockFlash {
+        const TABLE: PartitionTable<1> = PartitionTable::new(
+            0,
+            [PartitionEntry::firmware(0x200, 0x100).backup(0x300, 0x100)],
+        );
+        let mut flash = MockFlash([0xff; 0x400]);
+        let bytes = TABLE.as_bytes();
+        flash.0[..bytes.len()].copy_from_slice(bytes);
+        flash.0[0x100..0x100 + bytes.len()].copy_from_slice(bytes);
+        flash
+    }
+
+    #[test]
+    fn ota_update_and_rollback() {
+        let image = [0x5a; 0x80];
+        let crc32 = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC).checksum(&image);
+
+        let mut ota = Ota::new(flash_with_tables(), [0x000, 0x100]).unwrap();
+        assert_eq!(ota.active_slot(), 0);
+        assert!(!ota.is_pending());
+        assert_eq!(ota.inactive_slot(), (0x300, 0x100));
+        ota.begin_update().unwrap();
+        ota.write_image(0, &image).unwrap();
+        assert_eq!(ota.write_image(0xc0, &image), Err(OtaError::ImageTooLarge));
+        assert!(matches!(
+            ota.finish_update(0x80, !crc32),
+            Err(OtaError::Crc { .. })
+        ));
+        ota.finish_update(0x80, crc32).unwrap();
+        assert!(ota.is_staged());
+        assert_eq!(ota.begin_update(), Err(OtaError::UpdatePending));
+        assert_eq!(ota.write_image(0, &image), Err(OtaError::UpdatePending));
+        assert_eq!(ota.mark_boot_successful(), Err(OtaError::NotBooted));
+
+        let mut ota = Ota::new(ota.free(), [0x000, 0x100]).unwrap();
+        assert_eq!(ota.active_slot(), 1);
+        assert!(ota.is_pending());
+        assert_eq!(ota.begin_update(), Err(OtaError::UpdatePending));
+        ota.rollback().unwrap();
+        assert_eq!(ota.active_slot(), 0);
+        assert!(!ota.is_pending());
+        assert_eq!(ota.rollback(), Err(OtaError::NotPending));
+    }
+
+    // Select slot `active` in the first entry of table copy at `address`, keeping its
+    // checksum valid.
+    fn set_active_slot(flash: &mut MockFlash, address: usize, active: u8) {
+        let entry = address + 16;
+        flash.0[entry + 2] = active;
+        let crc32 =
+            crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC).checksum(&flash.0[entry..entry + 36]);
+        flash.0[entry + 36..entry + 40].copy_from_slice(&crc32.to_le_bytes());
+    }
+
+    #[test]
+    fn ota_reject_invalid_active_slot() {
+        const TABLE: PartitionTable<1> = PartitionTable::new(
+            1,
+            [PartitionEntry::firmware(0x200, 0x100).backup(0x300, 0x100)],
+        );
+        let mut flash = flash_with_tables();
+        let bytes = TABLE.as_bytes();
+        flash.0[0x100..0x100 + bytes.len()].copy_from_slice(bytes);
+        set_active_slot(&mut flash, 0x100, 2);
+
+        // Newer copy is rejected, older copy is used instead.
+        let ota = Ota::new(flash, [0x000, 0x100]).unwrap();
+        assert_eq!(ota.active_slot(), 0);
+        assert!(!ota.is_pending());
+        assert_eq!(ota.inactive_slot(), (0x300, 0x100));
+
+        let mut flash = ota.free();
+        set_active_slot(&mut flash, 0x000, 0xff);
+        assert!(matches!(
+            Ota::new(flash, [0x000, 0x100]),
+            Err(OtaError::NoPartitionTable)
+        ));
+    }
+
+    #[test]
+    fn ota_mark_boot_successful() {
+        let image = [0xa5; 0x40];
+        let crc32 = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC).checksum(&image);
+
+        let mut ota = Ota::new(flash_with_tables(), [0x000, 0x100]).unwrap();
+        ota.begin_update().unwrap();
+        ota.write_image(0, &image).unwrap();
+        ota.finish_update(0x40, crc32).unwrap();
+
+        let mut ota = Ota::new(ota.free(), [0x000, 0x100]).unwrap();
+        ota.mark_boot_successful().unwrap();
+        let ota = Ota::new(ota.free(), [0x000, 0x100]).unwrap();
+        assert_eq!(ota.active_slot(), 1);
+        assert!(!ota.is_pending());
+        assert_eq!(ota.inactive_slot(), (0x200, 0x100));
+    }
+}
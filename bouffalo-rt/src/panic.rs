@@ -0,0 +1,66 @@
+//! Panic handler printing over a serial console.
+//!
+//! With this feature the runtime provides the `#[panic_handler]`; applications must not
+//! link another panic handler crate such as `panic-halt`. The handler prints panic
+//! location, message and a short register dump (`ra`, `sp`, `mepc` and `mcause`) to the
+//! global console set by [`console::init`](crate::console::init), then halts the current
+//! core. Without a console it only halts.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let serial = p.uart0.freerun(config, pads, &c).unwrap();
+//! // The console lives forever, e.g. leak it with the `alloc` feature.
+//! bouffalo_rt::console::init(Box::leak(Box::new(serial)));
+//! ```
+
+#[cfg(not(test))]
+#[panic_handler]
+fn panic(info: &core::panic::PanicInfo) -> ! {
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    let (ra, sp, mepc, mcause) = {
+        let (ra, sp, mepc, mcause): (usize, usize, usize, usize);
+        unsafe {
+            core::arch::asm!(
+                "mv {ra}, ra",
+                "mv {sp}, sp",
+                "csrr {mepc}, mepc",
+                "csrr {mcause}, mcause",
+                ra = out(reg) ra,
+                sp = out(reg) sp,
+                mepc = out(reg) mepc,
+                mcause = out(reg) mcause,
+            )
+        };
+        (ra, sp, mepc, mcause)
+    };
+    #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+    let (ra, sp, mepc, mcause) = (0usize, 0usize, 0usize, 0usize);
+    // Mask machine interrupts for good, the core halts below.
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    unsafe {
+        core::arch::asm!("csrci mstatus, 8")
+    };
+    // Take the console so that a nested panic while printing does not reuse it.
+    if let Some(console) = crate::console::take_for_halt() {
+        if let Some(location) = info.location() {
+            let _ = writeln!(
+                console,
+                "panicked at {}:{}:{}",
+                location.file(),
+                location.line(),
+                location.column()
+            );
+        }
+        let _ = writeln!(console, "{}", info.message());
+        // `mepc` and `mcause` hold the last trap taken, which is useful when the
+        // panic was raised from an exception or interrupt handler.
+        let _ = writeln!(
+            console,
+            "ra {ra:#x}, sp {sp:#x}, mepc {mepc:#x}, mcause {mcause:#x}"
+        );
+    }
+    loop {
+        core::hint::spin_loop();
+    }
+}
@@ -0,0 +1,453 @@
+//! Bouffalo partition table.
+//!
+//! Boot2 bootloader reads the partition table from the flash addresses recorded as
+//! `boot2_pt_table_0` and `boot2_pt_table_1` in boot header, and picks the valid
+//! copy with the larger age. Each entry describes up to two slots of a partition, so
+//! that firmware can be updated in the inactive slot.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use bouffalo_rt::partition::{PartitionEntry, PartitionTable};
+//!
+//! const TABLE: PartitionTable<4> = PartitionTable::new(
+//!     0,
+//!     [
+//!         PartitionEntry::boot2(0x0, 0xE000),
+//!         PartitionEntry::firmware(0x10000, 0x1D0000).backup(0x1E0000, 0x1D0000),
+//!         PartitionEntry::mfg(0x3B0000, 0x32000),
+//!         PartitionEntry::media(0x3E2000, 0x47000),
+//!     ],
+//! );
+//! let bytes = TABLE.as_bytes();
+//! ```
+
+/// Magic number of partition table, `BFPT` in little endian.
+pub const PARTITION_MAGIC: u32 = 0x54504642;
+/// Maximum number of entries in a partition table.
+pub const MAX_ENTRIES: usize = 16;
+
+/// Partition type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PartitionType {
+    /// Application firmware.
+    Firmware = 0,
+    /// Manufacturing test firmware.
+    Mfg = 2,
+    /// Media file system.
+    Media = 3,
+    /// Persistent storage.
+    Psm = 4,
+    /// Security keys.
+    Key = 5,
+    /// Calibration and user data.
+    Data = 6,
+    /// Factory settings.
+    Factory = 7,
+    /// Second stage bootloader.
+    Boot2 = 16,
+}
+
+/// Partition table header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct PartitionTableHeader {
+    magic: u32,
+    version: u16,
+    entry_count: u16,
+    age: u32,
+    crc32: u32,
+}
+
+/// Partition table entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct PartitionEntry {
+    kind: u8,
+    device: u8,
+    active_index: u8,
+    name: [u8; 9],
+    address: [u32; 2],
+    max_len: [u32; 2],
+    len: u32,
+    age: u32,
+}
+
+impl PartitionEntry {
+    /// Create a partition entry with one slot at `address` of at most `size` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics, or fails compilation in const context, if `name` is longer than 8 bytes.
+    #[inline]
+    pub const fn new(kind: PartitionType, name: &str, address: u32, size: u32) -> Self {
+        let bytes = name.as_bytes();
+        assert!(bytes.len() < 9, "partition name is longer than 8 bytes");
+        let mut name = [0u8; 9];
+        let mut i = 0;
+        while i < bytes.len() {
+            name[i] = bytes[i];
+            i += 1;
+        }
+        Self {
+            kind: kind as u8,
+            device: 0,
+            active_index: 0,
+            name,
+            address: [address, 0],
+            max_len: [size, 0],
+            len: 0,
+            age: 0,
+        }
+    }
+    /// Second stage bootloader partition.
+    #[inline]
+    pub const fn boot2(address: u32, size: u32) -> Self {
+        Self::new(PartitionType::Boot2, "Boot2", address, size)
+    }
+    /// Application firmware partition.
+    #[inline]
+    pub const fn firmware(address: u32, size: u32) -> Self {
+        Self::new(PartitionType::Firmware, "FW", address, size)
+    }
+    /// Manufacturing test firmware partition.
+    #[inline]
+    pub const fn mfg(address: u32, size: u32) -> Self {
+        Self::new(PartitionType::Mfg, "mfg", address, size)
+    }
+    /// Media file system partition.
+    #[inline]
+    pub const fn media(address: u32, size: u32) -> Self {
+        Self::new(PartitionType::Media, "media", address, size)
+    }
+    /// Add backup slot at `address` of at most `size` bytes.
+    #[inline]
+    pub const fn backup(mut self, address: u32, size: u32) -> Self {
+        self.address[1] = address;
+        self.max_len[1] = size;
+        self
+    }
+    /// Set length of image stored in the active slot.
+    #[inline]
+    pub const fn image_len(mut self, len: u32) -> Self {
+        self.len = len;
+        self
+    }
+    /// Set active slot index, 0 or 1.
+    #[inline]
+    pub const fn active_index(mut self, index: u8) -> Self {
+        self.active_index = index;
+        self
+    }
+    /// Set update counter of this entry.
+    #[inline]
+    pub const fn age(mut self, age: u32) -> Self {
+        self.age = age;
+        self
+    }
+    /// Flash address of slot `index`.
+    #[inline]
+    pub const fn address(&self, index: usize) -> u32 {
+        self.address[index]
+    }
+    /// Maximum size of slot `index`.
+    #[inline]
+    pub const fn max_len(&self, index: usize) -> u32 {
+        self.max_len[index]
+    }
+    /// Length of image stored in the active slot.
+    #[inline]
+    pub const fn data_len(&self) -> u32 {
+        self.len
+    }
+    /// Partition type byte.
+    #[inline]
+    pub const fn kind(&self) -> u8 {
+        self.kind
+    }
+    /// Index of the active slot.
+    #[inline]
+    pub const fn active(&self) -> u8 {
+        self.active_index
+    }
+    /// Update counter of this entry.
+    #[inline]
+    pub const fn entry_age(&self) -> u32 {
+        self.age
+    }
+    /// Check if this entry has a backup slot.
+    #[inline]
+    pub const fn has_backup(&self) -> bool {
+        self.max_len[1] != 0
+    }
+}
+
+/// Partition table with `N` entries as stored on flash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct PartitionTable<const N: usize> {
+    header: PartitionTableHeader,
+    entries: [PartitionEntry; N],
+    crc32: u32,
+}
+
+impl<const N: usize> PartitionTable<N> {
+    /// Create a partition table with checksums filled in compile time.
+    ///
+    /// # Panics
+    ///
+    /// Panics, or fails compilation in const context, if there are more than
+    /// [`MAX_ENTRIES`] entries, an entry has an invalid active index, or slots overlap.
+    #[inline]
+    pub const fn new(age: u32, entries: [PartitionEntry; N]) -> Self {
+        assert!(N <= MAX_ENTRIES, "too many partition entries");
+        let mut i = 0;
+        while i < N {
+            let entry = &entries[i];
+            assert!(
+                entry.active_index == 0 || (entry.active_index == 1 && entry.has_backup()),
+                "invalid active slot index"
+            );
+            i += 1;
+        }
+        // Slot `k` is slot `k % 2` of entry `k / 2`; empty slots are skipped.
+        let mut a = 0;
+        while a < N * 2 {
+            let mut b = a + 1;
+            while b < N * 2 {
+                let (x, y) = (&entries[a / 2], &entries[b / 2]);
+                let (x_len, y_len) = (x.max_len[a % 2], y.max_len[b % 2]);
+                if x_len != 0 && y_len != 0 {
+                    let (x_addr, y_addr) = (x.address[a % 2], y.address[b % 2]);
+                    assert!(
+                        x_addr + x_len <= y_addr || y_addr + y_len <= x_addr,
+                        "partition slots overlap"
+                    );
+                }
+                b += 1;
+            }
+            a += 1;
+        }
+        let header = PartitionTableHeader::new(N as u16, age);
+        let mut crc = !0;
+        let mut i = 0;
+        while i < N {
+            crc = crc32_update(crc, &entries[i].to_bytes());
+            i += 1;
+        }
+        Self {
+            header,
+            entries,
+            crc32: !crc,
+        }
+    }
+    /// Update counter of this table; boot2 uses the valid copy with the larger age.
+    #[inline]
+    pub const fn age(&self) -> u32 {
+        self.header.age
+    }
+    /// Entries of this partition table.
+    #[inline]
+    pub const fn entries(&self) -> &[PartitionEntry; N] {
+        &self.entries
+    }
+    /// Check magic number and checksums of this table.
+    #[inline]
+    pub const fn is_valid(&self) -> bool {
+        if self.header.magic != PARTITION_MAGIC
+            || self.header.entry_count as usize != N
+            || self.header.crc32 != self.header.checksum()
+        {
+            return false;
+        }
+        let mut crc = !0;
+        let mut i = 0;
+        while i < N {
+            crc = crc32_update(crc, &self.entries[i].to_bytes());
+            i += 1;
+        }
+        !crc == self.crc32
+    }
+    /// Raw bytes of this table to be written into flash.
+    #[inline]
+    pub const fn as_bytes(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self as *const _ as *const u8, size_of::<Self>()) }
+    }
+}
+
+impl PartitionTableHeader {
+    #[inline]
+    pub(crate) const fn new(entry_count: u16, age: u32) -> Self {
+        let mut header = PartitionTableHeader {
+            magic: PARTITION_MAGIC,
+            version: 0,
+            entry_count,
+            age,
+            crc32: 0,
+        };
+        header.crc32 = header.checksum();
+        header
+    }
+    #[inline]
+    pub(crate) const fn from_bytes(buf: [u8; 16]) -> Self {
+        Self {
+            magic: u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]),
+            version: u16::from_le_bytes([buf[4], buf[5]]),
+            entry_count: u16::from_le_bytes([buf[6], buf[7]]),
+            age: u32::from_le_bytes([buf[8], buf[9], buf[10], buf[11]]),
+            crc32: u32::from_le_bytes([buf[12], buf[13], buf[14], buf[15]]),
+        }
+    }
+    #[inline]
+    pub(crate) const fn to_bytes(self) -> [u8; 16] {
+        let mut buf = [0u8; 16];
+        [buf[0], buf[1], buf[2], buf[3]] = self.magic.to_le_bytes();
+        [buf[4], buf[5]] = self.version.to_le_bytes();
+        [buf[6], buf[7]] = self.entry_count.to_le_bytes();
+        [buf[8], buf[9], buf[10], buf[11]] = self.age.to_le_bytes();
+        [buf[12], buf[13], buf[14], buf[15]] = self.crc32.to_le_bytes();
+        buf
+    }
+    /// Check magic number, entry count and checksum of this header.
+    #[inline]
+    pub(crate) const fn is_valid(&self) -> bool {
+        self.magic == PARTITION_MAGIC
+            && self.entry_count as usize <= MAX_ENTRIES
+            && self.crc32 == self.checksum()
+    }
+    #[inline]
+    pub(crate) const fn entry_count(&self) -> usize {
+        self.entry_count as usize
+    }
+    #[inline]
+    pub(crate) const fn age(&self) -> u32 {
+        self.age
+    }
+    #[inline]
+    const fn checksum(&self) -> u32 {
+        let mut buf = [0u8; 12];
+        [buf[0], buf[1], buf[2], buf[3]] = self.magic.to_le_bytes();
+        [buf[4], buf[5]] = self.version.to_le_bytes();
+        [buf[6], buf[7]] = self.entry_count.to_le_bytes();
+        [buf[8], buf[9], buf[10], buf[11]] = self.age.to_le_bytes();
+        crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC).checksum(&buf)
+    }
+}
+
+impl PartitionEntry {
+    #[inline]
+    pub(crate) const fn from_bytes(buf: [u8; 36]) -> Self {
+        let mut name = [0u8; 9];
+        let mut i = 0;
+        while i < 9 {
+            name[i] = buf[3 + i];
+            i += 1;
+        }
+        Self {
+            kind: buf[0],
+            device: buf[1],
+            active_index: buf[2],
+            name,
+            address: [
+                u32::from_le_bytes([buf[12], buf[13], buf[14], buf[15]]),
+                u32::from_le_bytes([buf[16], buf[17], buf[18], buf[19]]),
+            ],
+            max_len: [
+                u32::from_le_bytes([buf[20], buf[21], buf[22], buf[23]]),
+                u32::from_le_bytes([buf[24], buf[25], buf[26], buf[27]]),
+            ],
+            len: u32::from_le_bytes([buf[28], buf[29], buf[30], buf[31]]),
+            age: u32::from_le_bytes([buf[32], buf[33], buf[34], buf[35]]),
+        }
+    }
+    #[inline]
+    pub(crate) const fn to_bytes(self) -> [u8; 36] {
+        let mut buf = [0u8; 36];
+        buf[0] = self.kind;
+        buf[1] = self.device;
+        buf[2] = self.active_index;
+        let mut i = 0;
+        while i < 9 {
+            buf[3 + i] = self.name[i];
+            i += 1;
+        }
+        [buf[12], buf[13], buf[14], buf[15]] = self.address[0].to_le_bytes();
+        [buf[16], buf[17], buf[18], buf[19]] = self.address[1].to_le_bytes();
+        [buf[20], buf[21], buf[22], buf[23]] = self.max_len[0].to_le_bytes();
+        [buf[24], buf[25], buf[26], buf[27]] = self.max_len[1].to_le_bytes();
+        [buf[28], buf[29], buf[30], buf[31]] = self.len.to_le_bytes();
+        [buf[32], buf[33], buf[34], buf[35]] = self.age.to_le_bytes();
+        buf
+    }
+}
+
+/// Feed `bytes` into a running CRC-32/ISO-HDLC register.
+#[inline]
+pub(crate) const fn crc32_update(mut crc: u32, bytes: &[u8]) -> u32 {
+    let mut i = 0;
+    while i < bytes.len() {
+        crc ^= bytes[i] as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB88320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        i += 1;
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PartitionEntry, PartitionTable, PartitionTableHeader, crc32_update};
+    use core::mem::{offset_of, size_of};
+
+    #[test]
+    fn struct_partition_table_offset() {
+        assert_eq!(size_of::<PartitionTableHeader>(), 16);
+        assert_eq!(size_of::<PartitionEntry>(), 36);
+        assert_eq!(offset_of!(PartitionEntry, name), 0x03);
+        assert_eq!(offset_of!(PartitionEntry, address), 0x0c);
+        assert_eq!(offset_of!(PartitionEntry, max_len), 0x14);
+        assert_eq!(offset_of!(PartitionEntry, len), 0x1c);
+        assert_eq!(offset_of!(PartitionEntry, age), 0x20);
+        assert_eq!(offset_of!(PartitionTable<2>, entries), 0x10);
+        assert_eq!(offset_of!(PartitionTable<2>, crc32), 0x58);
+    }
+
+    #[test]
+    fn partition_table_checksum() {
+        const TABLE: PartitionTable<2> = PartitionTable::new(
+            1,
+            [
+                PartitionEntry::boot2(0x0, 0xE000),
+                PartitionEntry::firmware(0x10000, 0x1D0000).backup(0x1E0000, 0x1D0000),
+            ],
+        );
+        assert!(TABLE.is_valid());
+        let bytes = TABLE.as_bytes();
+        assert_eq!(&bytes[..4], b"BFPT");
+        let crc = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+        assert_eq!(crc.checksum(&bytes[0x10..0x58]), TABLE.crc32);
+        assert_eq!(!crc32_update(!0, &bytes[..12]), crc.checksum(&bytes[..12]));
+        assert_eq!(&bytes[0x13..0x19], b"Boot2\0");
+        assert_eq!(&bytes[0x37..0x3a], b"FW\0");
+    }
+
+    #[test]
+    #[should_panic(expected = "partition slots overlap")]
+    fn partition_table_overlap() {
+        PartitionTable::new(
+            0,
+            [
+                PartitionEntry::boot2(0x0, 0xE000),
+                PartitionEntry::firmware(0xD000, 0x1000),
+            ],
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! RTIC v2 support.
+//!
+//! [`Mono`] is a monotonic on the machine timer of the current core with a tick rate of
+//! 1 MHz; the runtime forwards machine timer interrupts to its timer queue.
+//!
+//! On the D0 core, [`Interrupt`] re-exports PLIC interrupt sources the way device crates
+//! do. RTIC task priority `n` maps onto PLIC priority `n` of the source it is bound to;
+//! the D0 dispatcher raises the PLIC threshold to the running source priority, so only
+//! sources with higher priority preempt a running handler.
+//!
+//! # Example
+//!
+//! ```ignore
+//! bouffalo_rt::rtic::Mono::start();
+//! loop {
+//!     led.toggle().ok();
+//!     Mono::delay(500.millis()).await;
+//! }
+//! ```
+
+use bouffalo_hal::clint::{MachineTimer, RegisterBlock};
+use rtic_time::{
+    monotonic::TimerQueueBasedMonotonic,
+    timer_queue::{TimerQueue, TimerQueueBackend},
+};
+
+cfg_if::cfg_if! {
+    if #[cfg(any(feature = "bl808-mcu", feature = "bl808-dsp", feature = "bl808-lp"))] {
+        use crate::soc::bl808::{CLINT, TIME_SOURCE};
+        #[cfg(not(feature = "bl808-dsp"))]
+        use crate::soc::bl808::CLIC_INTIE_BASE;
+    } else if #[cfg(feature = "bl702")] {
+        use crate::soc::bl702::{CLINT, TIME_SOURCE, CLIC_INTIE_BASE};
+    } else {
+        compile_error!("rtic support requires a chip with machine timer dispatch");
+    }
+}
+
+/// PLIC interrupt sources of D0 core.
+#[cfg(feature = "bl808-dsp")]
+pub use crate::soc::bl808::DspInterrupt as Interrupt;
+
+/// Number of PLIC priority bits on D0 core, priorities range from 1 to 31.
+#[cfg(feature = "bl808-dsp")]
+pub const PLIC_PRIORITY_BITS: u8 = 5;
+
+const MACHINE_TIMER: usize = 7;
+
+static TIMER_QUEUE: TimerQueue<MachineTimerBackend> = TimerQueue::new();
+
+#[inline]
+fn timer() -> MachineTimer<&'static RegisterBlock> {
+    MachineTimer::new(unsafe { &*CLINT::ptr() }, TIME_SOURCE)
+}
+
+/// Timer queue backend on machine timer.
+pub struct MachineTimerBackend;
+
+impl TimerQueueBackend for MachineTimerBackend {
+    type Ticks = u64;
+
+    #[inline]
+    fn now() -> u64 {
+        timer().current_time()
+    }
+    #[inline]
+    fn set_compare(instant: u64) {
+        timer().set_timecmp(instant);
+    }
+    #[inline]
+    fn clear_compare_flag() {
+        // Timer interrupt stays pending while counter is past compare value.
+        timer().set_timecmp(u64::MAX);
+    }
+    #[inline]
+    fn pend_interrupt() {
+        timer().set_timecmp(0);
+    }
+    #[inline]
+    fn timer_queue() -> &'static TimerQueue<Self> {
+        &TIMER_QUEUE
+    }
+}
+
+/// Monotonic on machine timer of the current core.
+pub struct Mono;
+
+impl Mono {
+    /// Initialize timer queue and enable machine timer interrupt.
+    pub fn start() {
+        timer().set_timecmp(u64::MAX);
+        TIMER_QUEUE.initialize(MachineTimerBackend);
+        #[cfg(feature = "bl808-dsp")]
+        unsafe {
+            core::arch::asm!("csrs mie, {}", in(reg) 1usize << MACHINE_TIMER)
+        };
+        #[cfg(not(feature = "bl808-dsp"))]
+        unsafe {
+            ((CLIC_INTIE_BASE + MACHINE_TIMER) as *mut u8).write_volatile(1)
+        };
+    }
+}
+
+impl TimerQueueBasedMonotonic for Mono {
+    type Backend = MachineTimerBackend;
+    type Instant = fugit::Instant<u64, 1, 1_000_000>;
+    type Duration = fugit::Duration<u64, 1, 1_000_000>;
+}
+
+/// Advance the timer queue on machine timer interrupt.
+pub(crate) fn on_timer_interrupt() {
+    unsafe { TIMER_QUEUE.on_monotonic_interrupt() };
+}
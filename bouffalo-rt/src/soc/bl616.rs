@@ -40,8 +40,8 @@ unsafe extern "C" fn start() -> ! {
 }
 
 #[cfg(all(feature = "bl616", target_arch = "riscv32"))]
-extern "Rust" {
-    // This symbol is generated by `#[entry]` macro
+unsafe extern "C" {
+    // This symbol is generated by `#[entry]` macro, see `bouffalo_rt::entry`.
     fn main() -> !;
 }
 
@@ -130,6 +130,26 @@ pub struct HalBootheader {
     crc32: u32,
 }
 
+impl HalBootheader {
+    /// Read boot header from the start of a raw image.
+    #[inline]
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < size_of::<Self>() {
+            return None;
+        }
+        Some(unsafe { core::ptr::read_unaligned(bytes.as_ptr() as *const Self) })
+    }
+    /// Verify magic numbers, flash and clock configuration checksums and whole header
+    /// checksum of this header.
+    #[inline]
+    pub fn verify(&self) -> Result<(), crate::HeaderError> {
+        let bytes = unsafe {
+            core::slice::from_raw_parts(self as *const _ as *const u8, size_of::<Self>())
+        };
+        crate::verify_header(bytes, size_of::<Self>(), size_of::<HalPllConfig>())
+    }
+}
+
 /// Hardware system clock configuration.
 #[repr(C)]
 pub struct HalSysClkConfig {
@@ -235,6 +255,8 @@ pub struct Peripherals<'a> {
     pub hbn: HBN,
     /// Ethernet Media Access Control peripheral.
     pub emac: EMAC,
+    /// Core-local interruptor with machine timer.
+    pub clint: CLINT,
 }
 
 soc! {
@@ -256,34 +278,66 @@ soc! {
     pub struct HBN => 0x2000F000, bouffalo_hal::hbn::RegisterBlock;
     /// Ethernet Media Access Control peripheral.
     pub struct EMAC => 0x20070000, bouffalo_hal::emac::RegisterBlock;
+    /// Core-local interruptor with machine timer.
+    pub struct CLINT => 0xE0000000, bouffalo_hal::clint::RegisterBlock;
+}
+
+impl CLINT {
+    /// Get machine timer driver of the core.
+    #[inline]
+    pub fn machine_timer(self) -> bouffalo_hal::clint::MachineTimer<Self> {
+        bouffalo_hal::clint::MachineTimer::new(self, bouffalo_hal::clint::TimeSource::Register)
+    }
+}
+
+#[cfg(feature = "bl616")]
+impl Peripherals<'static> {
+    /// Take peripherals of the current core, returns `None` if they are already taken.
+    ///
+    /// The `#[entry]` function receives peripherals this way, so later calls return `None`.
+    #[inline]
+    pub fn take() -> Option<Self> {
+        if crate::take_peripherals_once() {
+            Some(unsafe { Self::steal() })
+        } else {
+            None
+        }
+    }
+    /// Get peripherals regardless of whether they are taken.
+    ///
+    /// # Safety
+    ///
+    /// Drivers created from both copies may alias the same registers.
+    #[inline]
+    pub unsafe fn steal() -> Self {
+        Peripherals {
+            glb: GLBv2 { _private: () },
+            gpio: bouffalo_hal::gpio::Pads::__pads_from_glb(&GLBv2 { _private: () }),
+            uart_muxes: bouffalo_hal::uart::UartMuxes::__uart_muxes_from_glb(&GLBv2 {
+                _private: (),
+            }),
+            uart0: UART0 { _private: () },
+            uart1: UART1 { _private: () },
+            spi: SPI { _private: () },
+            i2c0: I2C0 { _private: () },
+            pwm: PWM { _private: () },
+            i2c1: I2C1 { _private: () },
+            hbn: HBN { _private: () },
+            emac: EMAC { _private: () },
+            clint: CLINT { _private: () },
+        }
+    }
 }
 
 pub use bouffalo_hal::clocks::Clocks;
 
 // Used by macros only.
-#[allow(unused)]
+#[cfg(feature = "bl616")]
 #[doc(hidden)]
 #[inline(always)]
 pub fn __rom_init_params(xtal_hz: u32) -> (Peripherals<'static>, Clocks) {
     use embedded_time::rate::Hertz;
-    let peripherals = Peripherals {
-        glb: GLBv2 { _private: () },
-        gpio: match () {
-            #[cfg(feature = "bl616")]
-            () => bouffalo_hal::gpio::Pads::__pads_from_glb(&GLBv2 { _private: () }),
-            #[cfg(not(feature = "bl616"))]
-            () => unimplemented!(),
-        },
-        uart_muxes: bouffalo_hal::uart::UartMuxes::__uart_muxes_from_glb(&GLBv2 { _private: () }),
-        uart0: UART0 { _private: () },
-        uart1: UART1 { _private: () },
-        spi: SPI { _private: () },
-        i2c0: I2C0 { _private: () },
-        pwm: PWM { _private: () },
-        i2c1: I2C1 { _private: () },
-        hbn: HBN { _private: () },
-        emac: EMAC { _private: () },
-    };
+    let peripherals = Peripherals::take().expect("peripherals are taken before entry");
     let clocks = Clocks {
         xtal: Hertz(xtal_hz),
     };
@@ -293,8 +347,81 @@ pub fn __rom_init_params(xtal_hz: u32) -> (Peripherals<'static>, Clocks) {
 #[cfg(test)]
 mod tests {
     use super::{HalBootheader, HalPllConfig, HalSysClkConfig};
+    use crate::HeaderError;
     use core::mem::offset_of;
 
+    /// Boot header of a BL616 image as laid out by the vendor flash tool. The flash
+    /// configuration is taken from a vendor-processed image; the clock configuration
+    /// matches `CLOCK_CONFIG` and checksums are computed with an independent CRC-32.
+    const VENDOR_HEADER: [u8; 0x100] = [
+        0x42, 0x46, 0x4e, 0x50, 0x01, 0x00, 0x00, 0x00, 0x46, 0x43, 0x46, 0x47, 0x11, 0x00, 0x01,
+        0x01, 0x66, 0x99, 0xff, 0x03, 0x9f, 0x00, 0xb7, 0xe9, 0x04, 0xff, 0x00, 0x01, 0xc7, 0x20,
+        0x52, 0xd8, 0x06, 0x02, 0x32, 0x00, 0x0b, 0x01, 0x0b, 0x01, 0x3b, 0x01, 0xbb, 0x00, 0x6b,
+        0x01, 0xeb, 0x02, 0xeb, 0x02, 0x02, 0x50, 0x00, 0x01, 0x00, 0x01, 0x01, 0x00, 0x02, 0x01,
+        0x02, 0x01, 0xab, 0x01, 0x05, 0x35, 0x00, 0x00, 0x01, 0x01, 0x00, 0x00, 0x38, 0xff, 0xff,
+        0xf0, 0x77, 0x03, 0x02, 0x40, 0x77, 0x03, 0x02, 0xf0, 0x2c, 0x01, 0xb0, 0x04, 0xb0, 0x04,
+        0x32, 0x00, 0xe8, 0x80, 0x14, 0x00, 0x4f, 0xb1, 0xfe, 0x70, 0x50, 0x43, 0x46, 0x47, 0x07,
+        0x05, 0x00, 0x00, 0x03, 0x02, 0x01, 0x01, 0x00, 0x01, 0x01, 0x00, 0x0b, 0x34, 0xef, 0x89,
+        0x00, 0x01, 0x4c, 0x65, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x30, 0x7a, 0x00,
+        0x00, 0xef, 0xbe, 0xad, 0xde, 0xef, 0xbe, 0xad, 0xde, 0xef, 0xbe, 0xad, 0xde, 0xef, 0xbe,
+        0xad, 0xde, 0xef, 0xbe, 0xad, 0xde, 0xef, 0xbe, 0xad, 0xde, 0xef, 0xbe, 0xad, 0xde, 0xef,
+        0xbe, 0xad, 0xde, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xa0,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x48, 0x05, 0x00, 0x20, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1c, 0xa7, 0x51,
+        0x60,
+    ];
+
+    #[test]
+    fn hal_bootheader_verify() {
+        let header = HalBootheader::from_bytes(&VENDOR_HEADER).unwrap();
+        assert_eq!(header.verify(), Ok(()));
+        assert!(HalBootheader::from_bytes(&VENDOR_HEADER[..0x80]).is_none());
+
+        let mut bytes = VENDOR_HEADER;
+        bytes[0x10] ^= 0x01;
+        let header = HalBootheader::from_bytes(&bytes).unwrap();
+        assert!(matches!(
+            header.verify(),
+            Err(HeaderError::FlashConfigCrc {
+                stored: 0x70feb14f,
+                ..
+            })
+        ));
+
+        let mut bytes = VENDOR_HEADER;
+        bytes[0x68] = 0x04;
+        let header = HalBootheader::from_bytes(&bytes).unwrap();
+        assert!(matches!(
+            header.verify(),
+            Err(HeaderError::ClockConfigCrc {
+                stored: 0x89ef340b,
+                ..
+            })
+        ));
+
+        let mut bytes = VENDOR_HEADER;
+        bytes[0x7d] = 0x20;
+        let header = HalBootheader::from_bytes(&bytes).unwrap();
+        assert!(matches!(
+            header.verify(),
+            Err(HeaderError::HeaderCrc {
+                stored: 0x6051a71c,
+                ..
+            })
+        ));
+
+        let mut bytes = VENDOR_HEADER;
+        bytes[0x64] = 0x00;
+        let header = HalBootheader::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            header.verify(),
+            Err(HeaderError::ClockConfigMagic(0x47464300))
+        );
+    }
+
     #[test]
     fn struct_lengths() {
         use core::mem::size_of;
@@ -42,9 +42,14 @@ unsafe extern "C" fn start() -> ! {
             addi    t4, t4, 4
             j       1b
         1:",
+            "   la      t0, {trap_entry}
+            ori     t0, t0, {trap_mode}
+            csrw    mtvec, t0",
             "   call  {main}",
             stack = sym STACK,
             hart_stack_size = const LEN_STACK,
+            trap_entry = sym trap_entry,
+            trap_mode = const 3, // CLIC mode, all traps enter base address unless hardware vectored
             main = sym main,
         )
     }
@@ -52,11 +57,245 @@ unsafe extern "C" fn start() -> ! {
 
 #[cfg(feature = "bl702")]
 #[rustfmt::skip]
-unsafe extern "Rust" {
-    // This symbol is generated by `#[entry]` macro
+unsafe extern "C" {
+    // This symbol is generated by `#[entry]` macro, see `bouffalo_rt::entry`.
     fn main() -> !;
 }
 
+// Alignment of this function is ensured by `build.rs` script.
+#[cfg(feature = "bl702")]
+#[unsafe(link_section = ".trap.trap-entry")]
+#[naked]
+unsafe extern "C" fn trap_entry() -> ! {
+    unsafe {
+        naked_asm!(
+            ".p2align 6",
+            "addi   sp, sp, -34*4",
+            "sw     ra, 0*4(sp)",
+            "sw     t0, 1*4(sp)",
+            "sw     t1, 2*4(sp)",
+            "sw     t2, 3*4(sp)",
+            "sw     a0, 4*4(sp)",
+            "sw     a1, 5*4(sp)",
+            "sw     a2, 6*4(sp)",
+            "sw     a3, 7*4(sp)",
+            "sw     a4, 8*4(sp)",
+            "sw     a5, 9*4(sp)",
+            "sw     a6, 10*4(sp)",
+            "sw     a7, 11*4(sp)",
+            "sw     t3, 12*4(sp)",
+            "sw     t4, 13*4(sp)",
+            "sw     t5, 14*4(sp)",
+            "sw     t6, 15*4(sp)",
+            "csrr   t0, mcause",
+            "sw     t0, 16*4(sp)",
+            "csrr   t1, mepc",
+            "sw     t1, 17*4(sp)",
+            "csrr   t2, mstatus",
+            "sw     t2, 18*4(sp)",
+            "addi   t0, sp, 34*4",
+            "sw     t0, 19*4(sp)",
+            "sw     gp, 20*4(sp)",
+            "sw     tp, 21*4(sp)",
+            "sw     s0, 22*4(sp)",
+            "sw     s1, 23*4(sp)",
+            "sw     s2, 24*4(sp)",
+            "sw     s3, 25*4(sp)",
+            "sw     s4, 26*4(sp)",
+            "sw     s5, 27*4(sp)",
+            "sw     s6, 28*4(sp)",
+            "sw     s7, 29*4(sp)",
+            "sw     s8, 30*4(sp)",
+            "sw     s9, 31*4(sp)",
+            "sw     s10, 32*4(sp)",
+            "sw     s11, 33*4(sp)",
+            "mv     a0, sp",
+            "call   {rust_all_traps}",
+            "lw     t0, 16*4(sp)",
+            "csrw   mcause, t0",
+            "lw     t1, 17*4(sp)",
+            "csrw   mepc, t1",
+            "lw     t2, 18*4(sp)",
+            "csrw   mstatus, t2",
+            "lw     ra, 0*4(sp)",
+            "lw     t0, 1*4(sp)",
+            "lw     t1, 2*4(sp)",
+            "lw     t2, 3*4(sp)",
+            "lw     a0, 4*4(sp)",
+            "lw     a1, 5*4(sp)",
+            "lw     a2, 6*4(sp)",
+            "lw     a3, 7*4(sp)",
+            "lw     a4, 8*4(sp)",
+            "lw     a5, 9*4(sp)",
+            "lw     a6, 10*4(sp)",
+            "lw     a7, 11*4(sp)",
+            "lw     t3, 12*4(sp)",
+            "lw     t4, 13*4(sp)",
+            "lw     t5, 14*4(sp)",
+            "lw     t6, 15*4(sp)",
+            "addi   sp, sp, 34*4",
+            "mret",
+            rust_all_traps = sym rust_bl702_all_traps,
+        )
+    }
+}
+
+#[cfg(feature = "bl702")]
+fn rust_bl702_all_traps(tf: &mut crate::arch::rvi::TrapFrame) {
+    if tf.mcause & (1 << 31) == 0 {
+        unsafe { exceptions(tf) };
+        return;
+    }
+    let idx = tf.mcause & 0xfff;
+    match idx {
+        #[cfg(feature = "embassy")]
+        3 => crate::embassy::on_software_interrupt(),
+        #[cfg(feature = "embassy")]
+        7 => crate::embassy::on_timer_interrupt(),
+        #[cfg(feature = "rtic")]
+        7 => crate::rtic::on_timer_interrupt(),
+        16..80 => unsafe { (INTERRUPT_HANDLERS[idx - 16])() },
+        _ => {}
+    }
+}
+
+#[cfg(feature = "bl702")]
+static INTERRUPT_HANDLERS: [unsafe extern "C" fn(); 64] = [
+    bmx_err,
+    bmx_to,
+    l1c_bmx_err,
+    l1c_bmx_to,
+    sec_bmx_err,
+    rf_top_int0,
+    rf_top_int1,
+    sdio,
+    dma_bmx_err,
+    sec_gmac,
+    sec_cdet,
+    sec_pka,
+    sec_trng,
+    sec_aes,
+    sec_sha,
+    dma_all,
+    mjpeg,
+    cam,
+    i2s,
+    ir_tx,
+    ir_rx,
+    usb,
+    qdec,
+    kys,
+    emac,
+    gpadc_dma,
+    efuse,
+    spi,
+    reserved28,
+    uart0,
+    uart1,
+    reserved31,
+    i2c,
+    reserved33,
+    pwm,
+    reserved35,
+    timer_ch0,
+    timer_ch1,
+    timer_wdt,
+    reserved39,
+    reserved40,
+    reserved41,
+    reserved42,
+    reserved43,
+    gpio,
+    reserved45,
+    reserved46,
+    m154_req_enh_ack,
+    m154,
+    m154_aes,
+    pds_wakeup,
+    hbn_out0,
+    hbn_out1,
+    bor,
+    wifi,
+    bz_phy,
+    ble,
+    mac_txrx_timer,
+    mac_txrx_misc,
+    mac_rx_trg,
+    mac_tx_trg,
+    mac_gen,
+    mac_port_trg,
+    wifi_ipc_public,
+];
+
+#[cfg(feature = "bl702")]
+unsafe extern "C" {
+    fn exceptions(tf: &mut crate::arch::rvi::TrapFrame);
+    fn bmx_err();
+    fn bmx_to();
+    fn l1c_bmx_err();
+    fn l1c_bmx_to();
+    fn sec_bmx_err();
+    fn rf_top_int0();
+    fn rf_top_int1();
+    fn sdio();
+    fn dma_bmx_err();
+    fn sec_gmac();
+    fn sec_cdet();
+    fn sec_pka();
+    fn sec_trng();
+    fn sec_aes();
+    fn sec_sha();
+    fn dma_all();
+    fn mjpeg();
+    fn cam();
+    fn i2s();
+    fn ir_tx();
+    fn ir_rx();
+    fn usb();
+    fn qdec();
+    fn kys();
+    fn emac();
+    fn gpadc_dma();
+    fn efuse();
+    fn spi();
+    fn reserved28();
+    fn uart0();
+    fn uart1();
+    fn reserved31();
+    fn i2c();
+    fn reserved33();
+    fn pwm();
+    fn reserved35();
+    fn timer_ch0();
+    fn timer_ch1();
+    fn timer_wdt();
+    fn reserved39();
+    fn reserved40();
+    fn reserved41();
+    fn reserved42();
+    fn reserved43();
+    fn gpio();
+    fn reserved45();
+    fn reserved46();
+    fn m154_req_enh_ack();
+    fn m154();
+    fn m154_aes();
+    fn pds_wakeup();
+    fn hbn_out0();
+    fn hbn_out1();
+    fn bor();
+    fn wifi();
+    fn bz_phy();
+    fn ble();
+    fn mac_txrx_timer();
+    fn mac_txrx_misc();
+    fn mac_rx_trg();
+    fn mac_tx_trg();
+    fn mac_gen();
+    fn mac_port_trg();
+    fn wifi_ipc_public();
+}
+
 #[cfg(any(doc, feature = "bl702"))]
 #[unsafe(link_section = ".head.clock")]
 #[used]
@@ -88,6 +327,26 @@ pub struct HalBootheader {
     crc32: u32,
 }
 
+impl HalBootheader {
+    /// Read boot header from the start of a raw image.
+    #[inline]
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < size_of::<Self>() {
+            return None;
+        }
+        Some(unsafe { core::ptr::read_unaligned(bytes.as_ptr() as *const Self) })
+    }
+    /// Verify magic numbers, flash and clock configuration checksums and whole header
+    /// checksum of this header.
+    #[inline]
+    pub fn verify(&self) -> Result<(), crate::HeaderError> {
+        let bytes = unsafe {
+            core::slice::from_raw_parts(self as *const _ as *const u8, size_of::<Self>())
+        };
+        crate::verify_header(bytes, size_of::<Self>(), size_of::<HalPllConfig>())
+    }
+}
+
 /// Hardware system clock configuration.
 #[repr(C)]
 pub struct HalSysClkConfig {
@@ -189,6 +448,28 @@ pub struct Peripherals {
     pub hbn: HBN,
     /// Universal Serial Bus peripheral.
     pub usb: USBv1,
+    /// Timer peripheral.
+    pub timer: TIMER,
+    /// Infrared remote peripheral.
+    pub ir: IR,
+    /// Keyboard matrix scan peripheral.
+    pub kys: KYS,
+    /// Quadrature decoder peripheral 0.
+    pub qdec0: QDEC0,
+    /// Quadrature decoder peripheral 1.
+    pub qdec1: QDEC1,
+    /// Quadrature decoder peripheral 2.
+    pub qdec2: QDEC2,
+    /// Capacitive touch sensing peripheral.
+    pub touch: TOUCH,
+    /// Inter-IC Sound peripheral.
+    pub i2s: I2S,
+    /// Security engine peripheral.
+    pub sec: SEC,
+    /// Generic DAC, ADC and ACOMP interface control peripheral.
+    pub gpip: GPIP,
+    /// Core-local interruptor with machine timer.
+    pub clint: CLINT,
 }
 
 soc! {
@@ -210,6 +491,87 @@ soc! {
     pub struct HBN => 0x4000F000, bouffalo_hal::hbn::RegisterBlock;
     /// Universal Serial Bus peripheral.
     pub struct USBv1 => 0x4000D800, bouffalo_hal::usb::v1::RegisterBlock;
+    /// Timer peripheral.
+    pub struct TIMER => 0x4000A500, bouffalo_hal::timer::RegisterBlock;
+    /// Infrared remote peripheral.
+    pub struct IR => 0x4000A600, bouffalo_hal::ir::RegisterBlock;
+    /// Keyboard matrix scan peripheral.
+    pub struct KYS => 0x4000A900, bouffalo_hal::kys::RegisterBlock;
+    /// Quadrature decoder peripheral 0.
+    pub struct QDEC0 => 0x4000A800, bouffalo_hal::qdec::RegisterBlock;
+    /// Quadrature decoder peripheral 1.
+    pub struct QDEC1 => 0x4000A840, bouffalo_hal::qdec::RegisterBlock;
+    /// Quadrature decoder peripheral 2.
+    pub struct QDEC2 => 0x4000A880, bouffalo_hal::qdec::RegisterBlock;
+    /// Capacitive touch sensing peripheral.
+    pub struct TOUCH => 0x4000FB00, bouffalo_hal::touch::RegisterBlock;
+    /// Inter-IC Sound peripheral.
+    pub struct I2S => 0x4000AA00, bouffalo_hal::i2s::RegisterBlock;
+    /// Security engine peripheral.
+    pub struct SEC => 0x40004000, bouffalo_hal::sec::RegisterBlock;
+    /// Generic DAC, ADC and ACOMP interface control peripheral.
+    pub struct GPIP => 0x40002000, bouffalo_hal::gpip::RegisterBlock;
+    /// Core-local interruptor with machine timer.
+    pub struct CLINT => 0x02000000, bouffalo_hal::clint::RegisterBlock;
+}
+
+pub(crate) const TIME_SOURCE: bouffalo_hal::clint::TimeSource =
+    bouffalo_hal::clint::TimeSource::Register;
+
+// Interrupt enable bytes of CLIC, one byte for each interrupt ID.
+#[cfg(any(feature = "embassy", feature = "rtic"))]
+pub(crate) const CLIC_INTIE_BASE: usize = 0x02800400;
+
+impl CLINT {
+    /// Get machine timer driver of the core.
+    #[inline]
+    pub fn machine_timer(self) -> bouffalo_hal::clint::MachineTimer<Self> {
+        bouffalo_hal::clint::MachineTimer::new(self, TIME_SOURCE)
+    }
+}
+
+impl Peripherals {
+    /// Take peripherals of the current core, returns `None` if they are already taken.
+    ///
+    /// The `#[entry]` function receives peripherals this way, so later calls return `None`.
+    #[inline]
+    pub fn take() -> Option<Self> {
+        if crate::take_peripherals_once() {
+            Some(unsafe { Self::steal() })
+        } else {
+            None
+        }
+    }
+    /// Get peripherals regardless of whether they are taken.
+    ///
+    /// # Safety
+    ///
+    /// Drivers created from both copies may alias the same registers.
+    #[inline]
+    pub unsafe fn steal() -> Self {
+        Peripherals {
+            glb: GLBv1 { _private: () },
+            uart0: UART0 { _private: () },
+            uart1: UART1 { _private: () },
+            spi: SPI { _private: () },
+            i2c: I2C { _private: () },
+            pwm: PWM { _private: () },
+            emac: EMAC { _private: () },
+            hbn: HBN { _private: () },
+            usb: USBv1 { _private: () },
+            timer: TIMER { _private: () },
+            ir: IR { _private: () },
+            kys: KYS { _private: () },
+            qdec0: QDEC0 { _private: () },
+            qdec1: QDEC1 { _private: () },
+            qdec2: QDEC2 { _private: () },
+            touch: TOUCH { _private: () },
+            i2s: I2S { _private: () },
+            sec: SEC { _private: () },
+            gpip: GPIP { _private: () },
+            clint: CLINT { _private: () },
+        }
+    }
 }
 
 pub use bouffalo_hal::clocks::Clocks;
@@ -221,17 +583,7 @@ pub use bouffalo_hal::clocks::Clocks;
 #[inline(always)]
 pub fn __rom_init_params(xtal_hz: u32) -> (Peripherals, Clocks) {
     use embedded_time::rate::Hertz;
-    let peripherals = Peripherals {
-        glb: GLBv1 { _private: () },
-        uart0: UART0 { _private: () },
-        uart1: UART1 { _private: () },
-        spi: SPI { _private: () },
-        i2c: I2C { _private: () },
-        pwm: PWM { _private: () },
-        emac: EMAC { _private: () },
-        hbn: HBN { _private: () },
-        usb: USBv1 { _private: () },
-    };
+    let peripherals = Peripherals::take().expect("peripherals are taken before entry");
     let clocks = Clocks {
         xtal: Hertz(xtal_hz),
     };
@@ -241,8 +593,75 @@ pub fn __rom_init_params(xtal_hz: u32) -> (Peripherals, Clocks) {
 #[cfg(test)]
 mod tests {
     use super::{HalBasicConfig, HalBootheader, HalPllConfig, HalSysClkConfig};
+    use crate::HeaderError;
     use core::mem::offset_of;
 
+    /// Boot header of a BL702 image as laid out by the vendor flash tool. The flash
+    /// configuration is taken from a vendor-processed image; the clock configuration
+    /// matches `CLOCK_CONFIG` and checksums are computed with an independent CRC-32.
+    const VENDOR_HEADER: [u8; 0xb0] = [
+        0x42, 0x46, 0x4e, 0x50, 0x01, 0x00, 0x00, 0x00, 0x46, 0x43, 0x46, 0x47, 0x11, 0x00, 0x01,
+        0x01, 0x66, 0x99, 0xff, 0x03, 0x9f, 0x00, 0xb7, 0xe9, 0x04, 0xff, 0x00, 0x01, 0xc7, 0x20,
+        0x52, 0xd8, 0x06, 0x02, 0x32, 0x00, 0x0b, 0x01, 0x0b, 0x01, 0x3b, 0x01, 0xbb, 0x00, 0x6b,
+        0x01, 0xeb, 0x02, 0xeb, 0x02, 0x02, 0x50, 0x00, 0x01, 0x00, 0x01, 0x01, 0x00, 0x02, 0x01,
+        0x02, 0x01, 0xab, 0x01, 0x05, 0x35, 0x00, 0x00, 0x01, 0x01, 0x00, 0x00, 0x38, 0xff, 0xff,
+        0xf0, 0x77, 0x03, 0x02, 0x40, 0x77, 0x03, 0x02, 0xf0, 0x2c, 0x01, 0xb0, 0x04, 0xb0, 0x04,
+        0x32, 0x00, 0xe8, 0x80, 0x14, 0x00, 0x4f, 0xb1, 0xfe, 0x70, 0x50, 0x43, 0x46, 0x47, 0x01,
+        0x04, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x31, 0xb5, 0x1b, 0xd8, 0x10, 0x03, 0x00, 0x00,
+        0x20, 0x5c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x21, 0x00, 0x20, 0x00, 0x00, 0xef, 0xbe, 0xad,
+        0xde, 0xef, 0xbe, 0xad, 0xde, 0xef, 0xbe, 0xad, 0xde, 0xef, 0xbe, 0xad, 0xde, 0xef, 0xbe,
+        0xad, 0xde, 0xef, 0xbe, 0xad, 0xde, 0xef, 0xbe, 0xad, 0xde, 0xef, 0xbe, 0xad, 0xde, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x6e, 0x04, 0x15, 0x26,
+    ];
+
+    #[test]
+    fn hal_bootheader_verify() {
+        let header = HalBootheader::from_bytes(&VENDOR_HEADER).unwrap();
+        assert_eq!(header.verify(), Ok(()));
+        assert!(HalBootheader::from_bytes(&VENDOR_HEADER[..0x80]).is_none());
+
+        let mut bytes = VENDOR_HEADER;
+        bytes[0x10] ^= 0x01;
+        let header = HalBootheader::from_bytes(&bytes).unwrap();
+        assert!(matches!(
+            header.verify(),
+            Err(HeaderError::FlashConfigCrc {
+                stored: 0x70feb14f,
+                ..
+            })
+        ));
+
+        let mut bytes = VENDOR_HEADER;
+        bytes[0x68] = 0x04;
+        let header = HalBootheader::from_bytes(&bytes).unwrap();
+        assert!(matches!(
+            header.verify(),
+            Err(HeaderError::ClockConfigCrc {
+                stored: 0xd81bb531,
+                ..
+            })
+        ));
+
+        let mut bytes = VENDOR_HEADER;
+        bytes[0x78] = 0x00;
+        let header = HalBootheader::from_bytes(&bytes).unwrap();
+        assert!(matches!(
+            header.verify(),
+            Err(HeaderError::HeaderCrc {
+                stored: 0x2615046e,
+                ..
+            })
+        ));
+
+        let mut bytes = VENDOR_HEADER;
+        bytes[0x64] = 0x00;
+        let header = HalBootheader::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            header.verify(),
+            Err(HeaderError::ClockConfigMagic(0x47464300))
+        );
+    }
+
     #[test]
     fn struct_lengths() {
         use core::mem::size_of;
@@ -1,6 +1,8 @@
 //! BL808 tri-core heterogeneous Wi-Fi 802.11b/g/n, Bluetooth 5, Zigbee AIoT system-on-chip.
 
 use crate::{HalBasicConfig, HalFlashConfig, HalPatchCfg};
+#[cfg(any(doc, feature = "bl808-dsp"))]
+use core::sync::atomic::{AtomicPtr, Ordering};
 
 #[cfg(all(feature = "bl808-mcu", target_arch = "riscv32"))]
 #[naked]
@@ -8,14 +10,8 @@ use crate::{HalBasicConfig, HalFlashConfig, HalPatchCfg};
 #[unsafe(export_name = "_start")]
 unsafe extern "C" fn start() -> ! {
     unsafe {
-        use crate::arch::rvi::Stack;
-        const LEN_STACK_MCU: usize = 1 * 1024;
-        #[unsafe(link_section = ".bss.uninit")]
-        static mut STACK: Stack<LEN_STACK_MCU> = Stack([0; LEN_STACK_MCU]);
         core::arch::naked_asm!(
-            "   la      sp, {stack}
-            li      t0, {hart_stack_size}
-            add     sp, sp, t0",
+            "   la      sp, _stack_start",
             "   la      t1, sbss
             la      t2, ebss
         1:  bgeu    t1, t2, 1f
@@ -36,40 +32,45 @@ unsafe extern "C" fn start() -> ! {
             "   la      t0, {trap_entry}
             ori     t0, t0, {trap_mode}
             csrw    mtvec, t0",
-            "   li      t1, {stack_protect_pmp_address_begin}
+            "   la      t1, sstack_guard
+            srli    t1, t1, 2
+            ori     t1, t1, {stack_guard_napot}
             csrw    pmpaddr0, t1
-            li      t1, {stack_protect_pmp_address_end}
+            li      t1, {stack_protect_pmp_address_begin}
             csrw    pmpaddr1, t1
+            li      t1, {stack_protect_pmp_address_end}
+            csrw    pmpaddr2, t1
             li      t2, {stack_protect_pmp_flags}
             csrw    pmpcfg0, t2",
             "   call  {main}",
-            stack = sym STACK,
-            hart_stack_size = const LEN_STACK_MCU,
-            trap_entry = sym trap_vectored,
-            trap_mode = const 1, // RISC-V standard vectored trap
-            // Set PMP entry to block U/S-mode stack access (TOR, no R/W/X permissions)
+            trap_entry = sym trap_entry,
+            trap_mode = const 3, // CLIC mode, all traps enter base address unless hardware vectored
+            // Entry 0 is a locked NAPOT guard below the stack (no R/W/X permissions in any mode),
+            // so that a stack overflow raises a store access fault instead of corrupting `.bss`.
+            stack_guard_napot = const (STACK_GUARD_SIZE / 8 - 1),
+            // Entries 1 and 2 block U/S-mode stack access (TOR, no R/W/X permissions)
             stack_protect_pmp_address_begin = const {0x62030000 >> 2},
             stack_protect_pmp_address_end = const {(0x62030000 + 160 * 1024) >> 2},
-            stack_protect_pmp_flags = const 0b00001000 << 8,
+            stack_protect_pmp_flags = const (0b10011000 | 0b00001000 << 16),
             main = sym main,
         )
     }
 }
 
+/// Size of the PMP guard region placed below the MCU stack.
+///
+/// Must match the guard size reserved in the `bl808-mcu` linker script.
+#[cfg(all(feature = "bl808-mcu", target_arch = "riscv32"))]
+const STACK_GUARD_SIZE: usize = 64;
+
 #[cfg(all(feature = "bl808-dsp", target_arch = "riscv64"))]
 #[naked]
 #[unsafe(link_section = ".text.entry")]
 #[unsafe(export_name = "_start")]
 unsafe extern "C" fn start() -> ! {
     unsafe {
-        use crate::arch::rvi::Stack;
-        const LEN_STACK_DSP: usize = 4 * 1024;
-        #[unsafe(link_section = ".bss.uninit")]
-        static mut STACK: Stack<LEN_STACK_DSP> = Stack([0; LEN_STACK_DSP]);
         core::arch::naked_asm!(
-            "   la      sp, {stack}
-            li      t0, {hart_stack_size}
-            add     sp, sp, t0",
+            "   la      sp, _stack_start",
             "   la      t1, sbss
             la      t2, ebss
         1:  bgeu    t1, t2, 1f
@@ -97,8 +98,6 @@ unsafe extern "C" fn start() -> ! {
             li      t2, {stack_protect_pmp_flags}
             csrw    pmpcfg0, t2",
             "   call    {main}",
-            stack = sym STACK,
-            hart_stack_size = const LEN_STACK_DSP,
             trap_entry = sym trap_vectored,
             trap_mode = const 1, // RISC-V standard vectored trap
             // Set PMP entry to block U/S-mode stack access (TOR, no R/W/X permissions)
@@ -116,14 +115,8 @@ unsafe extern "C" fn start() -> ! {
 #[unsafe(export_name = "_start")]
 unsafe extern "C" fn start() -> ! {
     unsafe {
-        use crate::arch::rve::Stack;
-        const LEN_STACK_LP: usize = 1 * 1024;
-        #[unsafe(link_section = ".bss.uninit")]
-        static mut STACK: Stack<LEN_STACK_LP> = Stack([0; LEN_STACK_LP]);
         core::arch::naked_asm!(
-            "   la      sp, {stack}
-            li      t0, {hart_stack_size}
-            add     sp, sp, t0",
+            "   la      sp, _stack_start",
             "   la      t1, sbss
             la      t2, ebss
         1:  bgeu    t1, t2, 1f
@@ -131,21 +124,24 @@ unsafe extern "C" fn start() -> ! {
             addi    t1, t1, 4
             j       1b
         1:",
-            "   la      t3, sidata
-            la      t4, sdata
-            la      t5, edata
-        1:  bgeu    t4, t5, 1f
-            lw      t6, 0(t3)
-            sw      t6, 0(t4)
-            addi    t3, t3, 4
-            addi    t4, t4, 4
+            // RV32E has no t3 to t6, use argument registers instead
+            "   la      a3, sidata
+            la      a4, sdata
+            la      a5, edata
+        1:  bgeu    a4, a5, 1f
+            lw      t0, 0(a3)
+            sw      t0, 0(a4)
+            addi    a3, a3, 4
+            addi    a4, a4, 4
             j       1b
         1:",
-            // TODO trap support
+            "   la      t0, {trap_entry}
+            ori     t0, t0, {trap_mode}
+            csrw    mtvec, t0",
             // TODO pmp support
             "   call  {main}",
-            stack = sym STACK,
-            hart_stack_size = const LEN_STACK_LP,
+            trap_entry = sym trap_entry,
+            trap_mode = const 3, // CLIC mode, same as M0 core
             main = sym main,
         )
     }
@@ -156,16 +152,13 @@ unsafe extern "C" fn start() -> ! {
     all(feature = "bl808-lp", target_arch = "riscv32"),
     all(feature = "bl808-dsp", target_arch = "riscv64")
 ))]
-unsafe extern "Rust" {
-    // This symbol is generated by `#[entry]` macro
+unsafe extern "C" {
+    // This symbol is generated by `#[entry]` macro, see `bouffalo_rt::entry`.
     fn main() -> !;
 }
 
 // Alignment of this function is ensured by `build.rs` script.
-#[cfg(any(
-    all(feature = "bl808-mcu", target_arch = "riscv32"),
-    all(feature = "bl808-dsp", target_arch = "riscv64")
-))]
+#[cfg(all(feature = "bl808-dsp", target_arch = "riscv64"))]
 #[unsafe(link_section = ".trap.trap-entry")]
 #[naked]
 unsafe extern "C" fn trap_vectored() -> ! {
@@ -192,10 +185,10 @@ unsafe extern "C" fn trap_vectored() -> ! {
             "j {thead_hpm_overflow}",
             exceptions = sym exceptions_trampoline,
             supervisor_software = sym reserved,
-            machine_software = sym reserved,
+            machine_software = sym machine_interrupt_trampoline,
             supervisor_timer = sym reserved,
-            machine_timer = sym reserved,
-            machine_external = sym machine_external_trampoline,
+            machine_timer = sym machine_interrupt_trampoline,
+            machine_external = sym machine_interrupt_trampoline,
             supervisor_external = sym reserved,
             thead_hpm_overflow = sym reserved,
             reserved = sym reserved,
@@ -203,25 +196,319 @@ unsafe extern "C" fn trap_vectored() -> ! {
     }
 }
 
-#[cfg(any(
-    all(feature = "bl808-mcu", target_arch = "riscv32"),
-    all(feature = "bl808-dsp", target_arch = "riscv64")
-))]
+#[cfg(all(feature = "bl808-dsp", target_arch = "riscv64"))]
 #[naked]
 unsafe extern "C" fn reserved() -> ! {
     unsafe { core::arch::naked_asm!("1: j   1b") }
 }
 
-#[cfg(any(all(feature = "bl808-dsp", target_arch = "riscv64")))]
+#[cfg(any(
+    all(feature = "bl808-mcu", target_arch = "riscv32"),
+    all(feature = "bl808-lp", target_arch = "riscv32"),
+    all(feature = "bl808-dsp", target_arch = "riscv64")
+))]
 unsafe extern "C" {
-    fn exceptions(tf: &mut crate::arch::rvi::TrapFrame);
+    fn exceptions(tf: &mut crate::TrapFrame);
 }
 
-// TODO exceptions_trampoline for bl808-mcu
+// Alignment of this function is ensured by `build.rs` script.
 #[cfg(all(feature = "bl808-mcu", target_arch = "riscv32"))]
+#[unsafe(link_section = ".trap.trap-entry")]
 #[naked]
-unsafe extern "C" fn exceptions_trampoline() -> ! {
-    unsafe { core::arch::naked_asm!("") }
+unsafe extern "C" fn trap_entry() -> ! {
+    unsafe {
+        core::arch::naked_asm!(
+            ".p2align 6",
+            "addi   sp, sp, -34*4",
+            "sw     ra, 0*4(sp)",
+            "sw     t0, 1*4(sp)",
+            "sw     t1, 2*4(sp)",
+            "sw     t2, 3*4(sp)",
+            "sw     a0, 4*4(sp)",
+            "sw     a1, 5*4(sp)",
+            "sw     a2, 6*4(sp)",
+            "sw     a3, 7*4(sp)",
+            "sw     a4, 8*4(sp)",
+            "sw     a5, 9*4(sp)",
+            "sw     a6, 10*4(sp)",
+            "sw     a7, 11*4(sp)",
+            "sw     t3, 12*4(sp)",
+            "sw     t4, 13*4(sp)",
+            "sw     t5, 14*4(sp)",
+            "sw     t6, 15*4(sp)",
+            "csrr   t0, mcause",
+            "sw     t0, 16*4(sp)",
+            "csrr   t1, mepc",
+            "sw     t1, 17*4(sp)",
+            "csrr   t2, mstatus",
+            "sw     t2, 18*4(sp)",
+            "addi   t0, sp, 34*4",
+            "sw     t0, 19*4(sp)",
+            "sw     gp, 20*4(sp)",
+            "sw     tp, 21*4(sp)",
+            "sw     s0, 22*4(sp)",
+            "sw     s1, 23*4(sp)",
+            "sw     s2, 24*4(sp)",
+            "sw     s3, 25*4(sp)",
+            "sw     s4, 26*4(sp)",
+            "sw     s5, 27*4(sp)",
+            "sw     s6, 28*4(sp)",
+            "sw     s7, 29*4(sp)",
+            "sw     s8, 30*4(sp)",
+            "sw     s9, 31*4(sp)",
+            "sw     s10, 32*4(sp)",
+            "sw     s11, 33*4(sp)",
+            "mv     a0, sp",
+            "call   {rust_all_traps}",
+            "lw     t0, 16*4(sp)",
+            "csrw   mcause, t0",
+            "lw     t1, 17*4(sp)",
+            "csrw   mepc, t1",
+            "lw     t2, 18*4(sp)",
+            "csrw   mstatus, t2",
+            "lw     ra, 0*4(sp)",
+            "lw     t0, 1*4(sp)",
+            "lw     t1, 2*4(sp)",
+            "lw     t2, 3*4(sp)",
+            "lw     a0, 4*4(sp)",
+            "lw     a1, 5*4(sp)",
+            "lw     a2, 6*4(sp)",
+            "lw     a3, 7*4(sp)",
+            "lw     a4, 8*4(sp)",
+            "lw     a5, 9*4(sp)",
+            "lw     a6, 10*4(sp)",
+            "lw     a7, 11*4(sp)",
+            "lw     t3, 12*4(sp)",
+            "lw     t4, 13*4(sp)",
+            "lw     t5, 14*4(sp)",
+            "lw     t6, 15*4(sp)",
+            "addi   sp, sp, 34*4",
+            "mret",
+            rust_all_traps = sym rust_bl808_mcu_lp_all_traps,
+        )
+    }
+}
+
+// Alignment of this function is ensured by `build.rs` script.
+#[cfg(all(feature = "bl808-lp", target_arch = "riscv32"))]
+#[unsafe(link_section = ".trap.trap-entry")]
+#[naked]
+unsafe extern "C" fn trap_entry() -> ! {
+    unsafe {
+        core::arch::naked_asm!(
+            ".p2align 6",
+            "addi   sp, sp, -18*4",
+            "sw     ra, 0*4(sp)",
+            "sw     t0, 1*4(sp)",
+            "sw     t1, 2*4(sp)",
+            "sw     t2, 3*4(sp)",
+            "sw     a0, 4*4(sp)",
+            "sw     a1, 5*4(sp)",
+            "sw     a2, 6*4(sp)",
+            "sw     a3, 7*4(sp)",
+            "sw     a4, 8*4(sp)",
+            "sw     a5, 9*4(sp)",
+            "csrr   t0, mcause",
+            "sw     t0, 10*4(sp)",
+            "csrr   t1, mepc",
+            "sw     t1, 11*4(sp)",
+            "csrr   t2, mstatus",
+            "sw     t2, 12*4(sp)",
+            "addi   t0, sp, 18*4",
+            "sw     t0, 13*4(sp)",
+            "sw     gp, 14*4(sp)",
+            "sw     tp, 15*4(sp)",
+            "sw     s0, 16*4(sp)",
+            "sw     s1, 17*4(sp)",
+            "mv     a0, sp",
+            "call   {rust_all_traps}",
+            "lw     t0, 10*4(sp)",
+            "csrw   mcause, t0",
+            "lw     t1, 11*4(sp)",
+            "csrw   mepc, t1",
+            "lw     t2, 12*4(sp)",
+            "csrw   mstatus, t2",
+            "lw     ra, 0*4(sp)",
+            "lw     t0, 1*4(sp)",
+            "lw     t1, 2*4(sp)",
+            "lw     t2, 3*4(sp)",
+            "lw     a0, 4*4(sp)",
+            "lw     a1, 5*4(sp)",
+            "lw     a2, 6*4(sp)",
+            "lw     a3, 7*4(sp)",
+            "lw     a4, 8*4(sp)",
+            "lw     a5, 9*4(sp)",
+            "addi   sp, sp, 18*4",
+            "mret",
+            rust_all_traps = sym rust_bl808_mcu_lp_all_traps,
+        )
+    }
+}
+
+#[cfg(any(
+    all(feature = "bl808-mcu", target_arch = "riscv32"),
+    all(feature = "bl808-lp", target_arch = "riscv32")
+))]
+fn rust_bl808_mcu_lp_all_traps(tf: &mut crate::TrapFrame) {
+    // In CLIC mode the interrupt flag stays on bit 31, and interrupt ID is the low 12 bits.
+    if tf.mcause & (1 << 31) == 0 {
+        unsafe { exceptions(tf) };
+        return;
+    }
+    let idx = tf.mcause & 0xfff;
+    match idx {
+        #[cfg(feature = "embassy")]
+        3 => crate::embassy::on_software_interrupt(),
+        #[cfg(feature = "embassy")]
+        7 => crate::embassy::on_timer_interrupt(),
+        #[cfg(feature = "rtic")]
+        7 => crate::rtic::on_timer_interrupt(),
+        16..80 => unsafe { (MCU_LP_INTERRUPT_HANDLERS[idx - 16])() },
+        _ => {}
+    }
+}
+
+// M0 and LP cores share the same interrupt source numbers.
+#[cfg(any(
+    all(feature = "bl808-mcu", target_arch = "riscv32"),
+    all(feature = "bl808-lp", target_arch = "riscv32")
+))]
+static MCU_LP_INTERRUPT_HANDLERS: [unsafe extern "C" fn(); 64] = [
+    bmx_mcu_bus_err,
+    bmx_mcu_to,
+    mcu_reserved2,
+    ipc_m0,
+    audio,
+    rf_top_int0,
+    rf_top_int1,
+    lz4,
+    mcu_reserved8,
+    sec_eng_id0_gmac,
+    sec_eng_id0_cdet,
+    sec_eng_id0_pka,
+    sec_eng_id0_trng,
+    sec_eng_id0_aes,
+    sec_eng_id0_sha,
+    dma0_all,
+    dma1_all,
+    sdh,
+    mm_all,
+    ir_tx,
+    ir_rx,
+    usb,
+    aupdm,
+    mcu_reserved23,
+    emac,
+    gpadc_dma,
+    efuse,
+    spi0,
+    uart0,
+    uart1,
+    uart2,
+    gpio_dma,
+    i2c0,
+    pwm,
+    ipc_reserved,
+    ipc_lp,
+    timer0_ch0,
+    timer0_ch1,
+    timer0_wdt,
+    i2c1,
+    i2s,
+    ana_ocp_out0,
+    ana_ocp_out1,
+    ana_ocp_out2,
+    gpio,
+    dm,
+    bt,
+    m154_req_ack,
+    m154,
+    m154_aes,
+    pds_wakeup,
+    hbn_out0,
+    hbn_out1,
+    bor,
+    wifi,
+    bz_phy,
+    ble,
+    mac_txrx_timer,
+    mac_txrx_misc,
+    mac_rx_trg,
+    mac_tx_trg,
+    mac_gen,
+    mac_port_trg,
+    wifi_ipc_public,
+];
+
+#[cfg(any(
+    all(feature = "bl808-mcu", target_arch = "riscv32"),
+    all(feature = "bl808-lp", target_arch = "riscv32")
+))]
+unsafe extern "C" {
+    fn bmx_mcu_bus_err();
+    fn bmx_mcu_to();
+    fn mcu_reserved2();
+    fn ipc_m0();
+    fn audio();
+    fn rf_top_int0();
+    fn rf_top_int1();
+    fn lz4();
+    fn mcu_reserved8();
+    fn sec_eng_id0_gmac();
+    fn sec_eng_id0_cdet();
+    fn sec_eng_id0_pka();
+    fn sec_eng_id0_trng();
+    fn sec_eng_id0_aes();
+    fn sec_eng_id0_sha();
+    fn dma0_all();
+    fn dma1_all();
+    fn sdh();
+    fn mm_all();
+    fn ir_tx();
+    fn ir_rx();
+    fn usb();
+    fn aupdm();
+    fn mcu_reserved23();
+    fn emac();
+    fn gpadc_dma();
+    fn efuse();
+    fn spi0();
+    fn uart0();
+    fn uart1();
+    fn uart2();
+    fn gpio_dma();
+    fn i2c0();
+    fn pwm();
+    fn ipc_reserved();
+    fn ipc_lp();
+    fn timer0_ch0();
+    fn timer0_ch1();
+    fn timer0_wdt();
+    fn i2c1();
+    fn i2s();
+    fn ana_ocp_out0();
+    fn ana_ocp_out1();
+    fn ana_ocp_out2();
+    fn gpio();
+    fn dm();
+    fn bt();
+    fn m154_req_ack();
+    fn m154();
+    fn m154_aes();
+    fn pds_wakeup();
+    fn hbn_out0();
+    fn hbn_out1();
+    fn bor();
+    fn wifi();
+    fn bz_phy();
+    fn ble();
+    fn mac_txrx_timer();
+    fn mac_txrx_misc();
+    fn mac_rx_trg();
+    fn mac_tx_trg();
+    fn mac_gen();
+    fn mac_port_trg();
+    fn wifi_ipc_public();
 }
 
 #[cfg(all(feature = "bl808-dsp", target_arch = "riscv64"))]
@@ -229,7 +516,7 @@ unsafe extern "C" fn exceptions_trampoline() -> ! {
 unsafe extern "C" fn exceptions_trampoline() -> ! {
     unsafe {
         core::arch::naked_asm!(
-            "addi   sp, sp, -19*8",
+            "addi   sp, sp, -34*8",
             "sd     ra, 0*8(sp)",
             "sd     t0, 1*8(sp)",
             "sd     t1, 2*8(sp)",
@@ -252,6 +539,22 @@ unsafe extern "C" fn exceptions_trampoline() -> ! {
             "sd     t1, 17*8(sp)",
             "csrr   t2, mstatus",
             "sd     t2, 18*8(sp)",
+            "addi   t0, sp, 34*8",
+            "sd     t0, 19*8(sp)",
+            "sd     gp, 20*8(sp)",
+            "sd     tp, 21*8(sp)",
+            "sd     s0, 22*8(sp)",
+            "sd     s1, 23*8(sp)",
+            "sd     s2, 24*8(sp)",
+            "sd     s3, 25*8(sp)",
+            "sd     s4, 26*8(sp)",
+            "sd     s5, 27*8(sp)",
+            "sd     s6, 28*8(sp)",
+            "sd     s7, 29*8(sp)",
+            "sd     s8, 30*8(sp)",
+            "sd     s9, 31*8(sp)",
+            "sd     s10, 32*8(sp)",
+            "sd     s11, 33*8(sp)",
             // "csrs   mstatus, 8", // TODO: disallow nested interrupt by now
             "mv     a0, sp",
             "call   {rust_exceptions}",
@@ -277,26 +580,19 @@ unsafe extern "C" fn exceptions_trampoline() -> ! {
             "ld     t4, 13*8(sp)",
             "ld     t5, 14*8(sp)",
             "ld     t6, 15*8(sp)",
-            "addi   sp, sp, 19*8",
+            "addi   sp, sp, 34*8",
             "mret",
             rust_exceptions = sym exceptions,
         )
     }
 }
 
-// TODO machine_external_trampoline for bl808-mcu
-#[cfg(all(feature = "bl808-mcu", target_arch = "riscv32"))]
-#[naked]
-unsafe extern "C" fn machine_external_trampoline() -> ! {
-    unsafe { core::arch::naked_asm!("") }
-}
-
 #[cfg(all(feature = "bl808-dsp", target_arch = "riscv64"))]
 #[naked]
-unsafe extern "C" fn machine_external_trampoline() -> ! {
+unsafe extern "C" fn machine_interrupt_trampoline() -> ! {
     unsafe {
         core::arch::naked_asm!(
-            "addi   sp, sp, -19*8",
+            "addi   sp, sp, -34*8",
             "sd     ra, 0*8(sp)",
             "sd     t0, 1*8(sp)",
             "sd     t1, 2*8(sp)",
@@ -319,7 +615,23 @@ unsafe extern "C" fn machine_external_trampoline() -> ! {
             "sd     t1, 17*8(sp)",
             "csrr   t2, mstatus",
             "sd     t2, 18*8(sp)",
-            // "csrs   mstatus, 8", // TODO: disallow nested interrupt by now
+            "addi   t0, sp, 34*8",
+            "sd     t0, 19*8(sp)",
+            "sd     gp, 20*8(sp)",
+            "sd     tp, 21*8(sp)",
+            "sd     s0, 22*8(sp)",
+            "sd     s1, 23*8(sp)",
+            "sd     s2, 24*8(sp)",
+            "sd     s3, 25*8(sp)",
+            "sd     s4, 26*8(sp)",
+            "sd     s5, 27*8(sp)",
+            "sd     s6, 28*8(sp)",
+            "sd     s7, 29*8(sp)",
+            "sd     s8, 30*8(sp)",
+            "sd     s9, 31*8(sp)",
+            "sd     s10, 32*8(sp)",
+            "sd     s11, 33*8(sp)",
+            // Interrupts are re-enabled by the handler below once PLIC threshold is raised.
             "mv     a0, sp",
             "call   {rust_all_traps}",
             "ld     t0, 16*8(sp)",
@@ -344,22 +656,81 @@ unsafe extern "C" fn machine_external_trampoline() -> ! {
             "ld     t4, 13*8(sp)",
             "ld     t5, 14*8(sp)",
             "ld     t6, 15*8(sp)",
-            "addi   sp, sp, 19*8",
+            "addi   sp, sp, 34*8",
             "mret",
-            rust_all_traps = sym rust_bl808_dsp_machine_external,
+            rust_all_traps = sym rust_bl808_dsp_machine_interrupts,
         )
     }
 }
 
 #[cfg(all(feature = "bl808-dsp", target_arch = "riscv64"))]
-fn rust_bl808_dsp_machine_external(_tf: &mut crate::arch::rvi::TrapFrame) {
+fn rust_bl808_dsp_machine_interrupts(tf: &mut crate::arch::rvi::TrapFrame) {
+    #[cfg(any(feature = "embassy", feature = "rtic"))]
+    match tf.mcause & 0xff {
+        #[cfg(feature = "embassy")]
+        3 => return crate::embassy::on_software_interrupt(),
+        #[cfg(feature = "embassy")]
+        7 => return crate::embassy::on_timer_interrupt(),
+        #[cfg(feature = "rtic")]
+        7 => return crate::rtic::on_timer_interrupt(),
+        _ => {}
+    }
+    #[cfg(not(any(feature = "embassy", feature = "rtic")))]
+    let _ = tf;
     let plic: PLIC = unsafe { core::mem::transmute(()) };
-    if let Some(source) = plic.claim(D0Machine) {
+    if let Some(source) = (*plic).claim(D0Machine) {
         let idx = source.get() as usize;
+        // Only sources with higher priority than the current one may preempt its handler.
+        let threshold = (*plic).get_threshold(D0Machine);
+        let priority = (*plic).get_priority(RawPlicSource(source));
+        (*plic).set_threshold(D0Machine, priority.max(threshold));
+        unsafe { core::arch::asm!("csrsi mstatus, 8") };
         if idx >= 16 && idx < 16 + 67 {
-            unsafe { (D0_INTERRUPT_HANDLERS[idx - 16])() };
+            let handler = D0_RUNTIME_HANDLERS[idx - 16].load(Ordering::Acquire);
+            match unsafe { handler_from_ptr(handler) } {
+                Some(f) => f(),
+                None => unsafe { (D0_INTERRUPT_HANDLERS[idx - 16])() },
+            }
         }
-        plic.complete(D0Machine, RawPlicSource(source));
+        unsafe { core::arch::asm!("csrci mstatus, 8") };
+        (*plic).set_threshold(D0Machine, threshold);
+        (*plic).complete(D0Machine, RawPlicSource(source));
+    }
+}
+
+#[cfg(any(doc, feature = "bl808-dsp"))]
+static D0_RUNTIME_HANDLERS: [AtomicPtr<()>; 67] =
+    [const { AtomicPtr::new(core::ptr::null_mut()) }; 67];
+
+/// Install handler of D0 core interrupt source at runtime, returns the previous one.
+///
+/// Runtime handlers take precedence over `#[interrupt]` functions of the same source,
+/// so libraries can service interrupts without defining handler symbols.
+#[cfg(any(doc, feature = "bl808-dsp"))]
+#[inline]
+pub fn register_handler(irq: DspInterrupt, handler: fn()) -> Option<fn()> {
+    let prev = D0_RUNTIME_HANDLERS[irq as usize - 16].swap(handler as *mut (), Ordering::AcqRel);
+    unsafe { handler_from_ptr(prev) }
+}
+
+/// Remove runtime handler of D0 core interrupt source, returns the removed one.
+///
+/// The source falls back to its `#[interrupt]` function, if any.
+#[cfg(any(doc, feature = "bl808-dsp"))]
+#[inline]
+pub fn unregister_handler(irq: DspInterrupt) -> Option<fn()> {
+    let prev = D0_RUNTIME_HANDLERS[irq as usize - 16].swap(core::ptr::null_mut(), Ordering::AcqRel);
+    unsafe { handler_from_ptr(prev) }
+}
+
+/// Safety: `ptr` must be null or come from a `fn()`.
+#[cfg(any(doc, feature = "bl808-dsp"))]
+#[inline]
+unsafe fn handler_from_ptr(ptr: *mut ()) -> Option<fn()> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(unsafe { core::mem::transmute::<*mut (), fn()>(ptr) })
     }
 }
 
@@ -515,10 +886,8 @@ impl plic::HartContext for D0Machine {
     }
 }
 
-#[cfg(all(feature = "bl808-dsp", target_arch = "riscv64"))]
 struct RawPlicSource(core::num::NonZeroU32);
 
-#[cfg(all(feature = "bl808-dsp", target_arch = "riscv64"))]
 impl plic::InterruptSource for RawPlicSource {
     #[inline]
     fn id(self) -> core::num::NonZeroU32 {
@@ -529,6 +898,8 @@ impl plic::InterruptSource for RawPlicSource {
 /// DSP core PLIC interrupt source.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub enum DspInterrupt {
+    /// DSP bus error interrupt.
+    BmxDspBusErr = 16 + 0,
     /// UART3 interrupt.
     Uart3 = 16 + 4,
     /// I2C2 interrupt.
@@ -537,6 +908,34 @@ pub enum DspInterrupt {
     I2c3 = 16 + 6,
     /// SPI1 interrupt.
     Spi1 = 16 + 7,
+    /// Scaler end-of-frame interrupt 0.
+    SeofInt0 = 16 + 10,
+    /// Scaler end-of-frame interrupt 1.
+    SeofInt1 = 16 + 11,
+    /// Scaler end-of-frame interrupt 2.
+    SeofInt2 = 16 + 12,
+    /// DVP capture unit 0 interrupt.
+    Dvp2BusInt0 = 16 + 13,
+    /// DVP capture unit 1 interrupt.
+    Dvp2BusInt1 = 16 + 14,
+    /// DVP capture unit 2 interrupt.
+    Dvp2BusInt2 = 16 + 15,
+    /// DVP capture unit 3 interrupt.
+    Dvp2BusInt3 = 16 + 16,
+    /// H.264 bitstream interrupt.
+    H264Bs = 16 + 17,
+    /// H.264 frame done interrupt.
+    H264Frame = 16 + 18,
+    /// H.264 sequence done interrupt.
+    H264SeqDone = 16 + 19,
+    /// MJPEG encoder interrupt.
+    Mjpeg = 16 + 20,
+    /// H.264 sub-stream bitstream interrupt.
+    H264SBs = 16 + 21,
+    /// H.264 sub-stream frame done interrupt.
+    H264SFrame = 16 + 22,
+    /// H.264 sub-stream sequence done interrupt.
+    H264SSeqDone = 16 + 23,
     /// DMA2 interrupt 0.
     Dma2Int0 = 16 + 24,
     /// DMA2 interrupt 1.
@@ -555,12 +954,42 @@ pub enum DspInterrupt {
     Dma2Int7 = 16 + 31,
     /// EMAC2 interrupt.
     Emac2 = 16 + 36,
+    /// MIPI CSI receiver interrupt.
+    MipiCsi = 16 + 37,
+    /// IPC interrupt of D0 core.
+    IpcD0 = 16 + 38,
+    /// MJPEG decoder interrupt.
+    Mjdec = 16 + 40,
+    /// DVP capture unit 4 interrupt.
+    Dvp2BusInt4 = 16 + 41,
+    /// DVP capture unit 5 interrupt.
+    Dvp2BusInt5 = 16 + 42,
+    /// DVP capture unit 6 interrupt.
+    Dvp2BusInt6 = 16 + 43,
+    /// DVP capture unit 7 interrupt.
+    Dvp2BusInt7 = 16 + 44,
     /// DMA2D interrupt 0.
     Dma2dInt0 = 16 + 45,
     /// DMA2D interrupt 1.
     Dma2dInt1 = 16 + 46,
+    /// Display controller interrupt.
+    Display = 16 + 47,
     /// PWM interrupt.
     Pwm = 16 + 48,
+    /// Scaler end-of-frame interrupt 3.
+    SeofInt3 = 16 + 49,
+    /// On-screen display interrupt.
+    Osd = 16 + 52,
+    /// DBI interrupt.
+    Dbi = 16 + 53,
+    /// On-screen display layer A bus drain interrupt.
+    OsdaBusDrain = 16 + 55,
+    /// On-screen display layer B bus drain interrupt.
+    OsdbBusDrain = 16 + 56,
+    /// On-screen display palette interrupt.
+    OsdPb = 16 + 57,
+    /// MIPI DSI host interrupt.
+    MipiDsi = 16 + 59,
     /// TIM1 channel 0 interrupt.
     Tim1Ch0 = 16 + 61,
     /// TIM1 interrupt.
@@ -569,10 +998,74 @@ pub enum DspInterrupt {
     Tim1Wdt = 16 + 63,
     /// AUDIO interrupt.
     Audio = 16 + 64,
+    /// Wi-Fi interrupt.
+    WlAll = 16 + 65,
     /// PDS interrupt.
     Pds = 16 + 66,
 }
 
+impl DspInterrupt {
+    /// Get interrupt source from PLIC source number.
+    #[inline]
+    pub const fn from_id(id: u32) -> Option<Self> {
+        Some(match id {
+            16 => Self::BmxDspBusErr,
+            20 => Self::Uart3,
+            21 => Self::I2c2,
+            22 => Self::I2c3,
+            23 => Self::Spi1,
+            26 => Self::SeofInt0,
+            27 => Self::SeofInt1,
+            28 => Self::SeofInt2,
+            29 => Self::Dvp2BusInt0,
+            30 => Self::Dvp2BusInt1,
+            31 => Self::Dvp2BusInt2,
+            32 => Self::Dvp2BusInt3,
+            33 => Self::H264Bs,
+            34 => Self::H264Frame,
+            35 => Self::H264SeqDone,
+            36 => Self::Mjpeg,
+            37 => Self::H264SBs,
+            38 => Self::H264SFrame,
+            39 => Self::H264SSeqDone,
+            40 => Self::Dma2Int0,
+            41 => Self::Dma2Int1,
+            42 => Self::Dma2Int2,
+            43 => Self::Dma2Int3,
+            44 => Self::Dma2Int4,
+            45 => Self::Dma2Int5,
+            46 => Self::Dma2Int6,
+            47 => Self::Dma2Int7,
+            52 => Self::Emac2,
+            53 => Self::MipiCsi,
+            54 => Self::IpcD0,
+            56 => Self::Mjdec,
+            57 => Self::Dvp2BusInt4,
+            58 => Self::Dvp2BusInt5,
+            59 => Self::Dvp2BusInt6,
+            60 => Self::Dvp2BusInt7,
+            61 => Self::Dma2dInt0,
+            62 => Self::Dma2dInt1,
+            63 => Self::Display,
+            64 => Self::Pwm,
+            65 => Self::SeofInt3,
+            68 => Self::Osd,
+            69 => Self::Dbi,
+            71 => Self::OsdaBusDrain,
+            72 => Self::OsdbBusDrain,
+            73 => Self::OsdPb,
+            75 => Self::MipiDsi,
+            77 => Self::Tim1Ch0,
+            78 => Self::Tim1Ch1,
+            79 => Self::Tim1Wdt,
+            80 => Self::Audio,
+            81 => Self::WlAll,
+            82 => Self::Pds,
+            _ => return None,
+        })
+    }
+}
+
 impl plic::InterruptSource for DspInterrupt {
     #[inline]
     fn id(self) -> core::num::NonZeroU32 {
@@ -630,124 +1123,370 @@ pub enum McuLpInterrupt {
     HbnOut1 = 16 + 52,
 }
 
+/// Boot header parts placed by the runtime unless feature `custom-boot-header` is enabled.
+#[cfg(all(
+    any(doc, feature = "bl808-mcu", feature = "bl808-dsp"),
+    not(feature = "custom-boot-header")
+))]
+const DEFAULT_BOOT_HEADER: BootHeader = BootHeader::builder().build();
+
 /// Clock configuration at boot-time.
-#[cfg(any(doc, feature = "bl808-mcu", feature = "bl808-dsp"))]
+#[cfg(all(
+    any(doc, feature = "bl808-mcu", feature = "bl808-dsp"),
+    not(feature = "custom-boot-header")
+))]
 #[unsafe(link_section = ".head.clock")]
-pub static CLOCK_CONFIG: HalPllConfig = HalPllConfig::new(HalSysClkConfig {
-    xtal_type: 0x07,
-    mcu_clk: 0x04,
-    mcu_clk_div: 0x00,
-    mcu_bclk_div: 0x00,
-
-    mcu_pbclk_div: 0x03,
-    lp_div: 0x01,
-    dsp_clk: 0x03,
-    dsp_clk_div: 0x00,
-
-    dsp_bclk_div: 0x01,
-    dsp_pbclk: 0x02,
-    dsp_pbclk_div: 0x00,
-    emi_clk: 0x02,
-
-    emi_clk_div: 0x01,
-    flash_clk_type: 0x01,
-    flash_clk_div: 0x00,
-    wifipll_pu: 0x01,
-
-    aupll_pu: 0x01,
-    cpupll_pu: 0x01,
-    mipipll_pu: 0x01,
-    uhspll_pu: 0x01,
-});
+pub static CLOCK_CONFIG: HalPllConfig = DEFAULT_BOOT_HEADER.clock;
 
 /// Miscellaneous image flags.
-#[cfg(any(doc, feature = "bl808-mcu", feature = "bl808-dsp"))]
+#[cfg(all(
+    any(doc, feature = "bl808-mcu", feature = "bl808-dsp"),
+    not(feature = "custom-boot-header")
+))]
 #[unsafe(link_section = ".head.base.flag")]
-pub static BASIC_CONFIG_FLAGS: u32 = 0x654c0100;
+pub static BASIC_CONFIG_FLAGS: u32 = DEFAULT_BOOT_HEADER.flags;
 
 /// Processor core configuration.
-#[cfg(any(doc, feature = "bl808-mcu", feature = "bl808-dsp"))]
+#[cfg(all(
+    any(doc, feature = "bl808-mcu", feature = "bl808-dsp"),
+    not(feature = "custom-boot-header")
+))]
 #[unsafe(link_section = ".head.cpu")]
-pub static CPU_CONFIG: [HalCpuCfg; 3] = [
-    #[cfg(feature = "bl808-mcu")]
-    HalCpuCfg {
-        config_enable: 1,
-        halt_cpu: 0,
-        cache_flags: 0,
-        _rsvd: 0,
-        cache_range_h: 0,
-        cache_range_l: 0,
-        image_address_offset: 0,
-        boot_entry: 0x58000000,
-        msp_val: 0,
-    },
-    #[cfg(not(feature = "bl808-mcu"))]
-    HalCpuCfg::disabled(),
-    #[cfg(feature = "bl808-dsp")]
-    HalCpuCfg {
-        config_enable: 1,
-        halt_cpu: 0,
-        cache_flags: 0,
-        _rsvd: 0,
-        cache_range_h: 0,
-        cache_range_l: 0,
-        image_address_offset: 0,
-        boot_entry: 0x58000000,
-        msp_val: 0,
-    },
-    #[cfg(not(feature = "bl808-dsp"))]
-    HalCpuCfg::disabled(),
-    #[cfg(feature = "bl808-lp")]
-    HalCpuCfg {
-        config_enable: 1,
-        halt_cpu: 0,
-        cache_flags: 0,
-        _rsvd: 0,
-        cache_range_h: 0,
-        cache_range_l: 0,
-        image_address_offset: 0,
-        boot_entry: 0,
-        msp_val: 0,
-    },
-    #[cfg(not(feature = "bl808-lp"))]
-    HalCpuCfg {
-        config_enable: 0,
-        halt_cpu: 0,
-        cache_flags: 0,
-        _rsvd: 0,
-        cache_range_h: 1476722688,
-        cache_range_l: 1476657152,
-        image_address_offset: 0x42000,
-        boot_entry: 0x58040000,
-        msp_val: 0,
-    },
-];
+pub static CPU_CONFIG: [HalCpuCfg; 3] = DEFAULT_BOOT_HEADER.cpu;
 
 /// Code patches on flash reading.
-#[cfg(any(doc, feature = "bl808-mcu", feature = "bl808-dsp"))]
+#[cfg(all(
+    any(doc, feature = "bl808-mcu", feature = "bl808-dsp"),
+    not(feature = "custom-boot-header")
+))]
 #[unsafe(link_section = ".head.patch.on-read")]
-pub static PATCH_ON_READ: [HalPatchCfg; 4] = [
-    HalPatchCfg { addr: 0, value: 0 },
-    HalPatchCfg { addr: 0, value: 0 },
-    HalPatchCfg { addr: 0, value: 0 },
-    HalPatchCfg { addr: 0, value: 0 },
-];
+pub static PATCH_ON_READ: [HalPatchCfg; 4] = DEFAULT_BOOT_HEADER.patch_on_read;
 
 /// Code patches on jump and run stage.
-#[cfg(any(doc, feature = "bl808-mcu", feature = "bl808-dsp"))]
+#[cfg(all(
+    any(doc, feature = "bl808-mcu", feature = "bl808-dsp"),
+    not(feature = "custom-boot-header")
+))]
 #[unsafe(link_section = ".head.patch.on-jump")]
-pub static PATCH_ON_JUMP: [HalPatchCfg; 4] = [
-    HalPatchCfg {
-        addr: 0x20000320,
-        value: 0x0,
-    },
-    HalPatchCfg {
-        addr: 0x2000F038,
-        value: 0x18000000,
-    },
-    HalPatchCfg { addr: 0, value: 0 },
-    HalPatchCfg { addr: 0, value: 0 },
-];
+pub static PATCH_ON_JUMP: [HalPatchCfg; 4] = DEFAULT_BOOT_HEADER.patch_on_jump;
+
+/// Customizable parts of ROM bootloading header, with checksums computed in compile time.
+///
+/// Enable feature `custom-boot-header` and place the header with [`boot_header!`]
+/// to replace the default one:
+///
+/// ```ignore
+/// bouffalo_rt::boot_header!(
+///     bouffalo_rt::soc::bl808::BootHeader::builder()
+///         .xtal_40m()
+///         .mcu_entry(0x58000000)
+///         .build()
+/// );
+/// ```
+///
+/// Image length, payload hash and header checksum depend on the linked image; they are
+/// filled by the linker script and image processing programs.
+///
+/// [`boot_header!`]: crate::boot_header
+pub struct BootHeader {
+    /// Flash configuration.
+    pub flash: HalFlashConfig,
+    /// Clock configuration.
+    pub clock: HalPllConfig,
+    /// Miscellaneous image flags.
+    pub flags: u32,
+    /// Processor core configuration of M0, D0 and LP cores.
+    pub cpu: [HalCpuCfg; 3],
+    /// Code patches on flash reading.
+    pub patch_on_read: [HalPatchCfg; 4],
+    /// Code patches on jump and run stage.
+    pub patch_on_jump: [HalPatchCfg; 4],
+}
+
+impl BootHeader {
+    /// Start building a boot header from the runtime defaults.
+    #[inline]
+    pub const fn builder() -> BootHeaderBuilder {
+        BootHeaderBuilder::new()
+    }
+}
+
+/// Compile-time builder of [`BootHeader`].
+pub struct BootHeaderBuilder {
+    flash: HalFlashConfig,
+    clock: HalSysClkConfig,
+    flags: u32,
+    cpu: [HalCpuCfg; 3],
+    patch_on_read: [HalPatchCfg; 4],
+    patch_on_jump: [HalPatchCfg; 4],
+}
+
+impl BootHeaderBuilder {
+    #[inline]
+    const fn new() -> Self {
+        Self {
+            flash: HalFlashConfig::builder().build(),
+            clock: HalSysClkConfig {
+                xtal_type: 0x07,
+                mcu_clk: 0x04,
+                mcu_clk_div: 0x00,
+                mcu_bclk_div: 0x00,
+
+                mcu_pbclk_div: 0x03,
+                lp_div: 0x01,
+                dsp_clk: 0x03,
+                dsp_clk_div: 0x00,
+
+                dsp_bclk_div: 0x01,
+                dsp_pbclk: 0x02,
+                dsp_pbclk_div: 0x00,
+                emi_clk: 0x02,
+
+                emi_clk_div: 0x01,
+                flash_clk_type: 0x01,
+                flash_clk_div: 0x00,
+                wifipll_pu: 0x01,
+
+                aupll_pu: 0x01,
+                cpupll_pu: 0x01,
+                mipipll_pu: 0x01,
+                uhspll_pu: 0x01,
+            },
+            flags: 0x654c0100,
+            cpu: [
+                #[cfg(feature = "bl808-mcu")]
+                HalCpuCfg::enabled(0x58000000),
+                #[cfg(not(feature = "bl808-mcu"))]
+                HalCpuCfg::disabled(),
+                #[cfg(feature = "bl808-dsp")]
+                HalCpuCfg::enabled(0x58000000),
+                #[cfg(not(feature = "bl808-dsp"))]
+                HalCpuCfg::disabled(),
+                #[cfg(feature = "bl808-lp")]
+                HalCpuCfg::enabled(0),
+                #[cfg(not(feature = "bl808-lp"))]
+                HalCpuCfg {
+                    config_enable: 0,
+                    halt_cpu: 0,
+                    cache_flags: 0,
+                    _rsvd: 0,
+                    cache_range_h: 0x5802_0000,
+                    cache_range_l: 0x5801_0000,
+                    image_address_offset: 0x42000,
+                    boot_entry: 0x58040000,
+                    msp_val: 0,
+                },
+            ],
+            patch_on_read: [
+                HalPatchCfg { addr: 0, value: 0 },
+                HalPatchCfg { addr: 0, value: 0 },
+                HalPatchCfg { addr: 0, value: 0 },
+                HalPatchCfg { addr: 0, value: 0 },
+            ],
+            patch_on_jump: [
+                HalPatchCfg {
+                    addr: 0x20000320,
+                    value: 0x0,
+                },
+                HalPatchCfg {
+                    addr: 0x2000F038,
+                    value: 0x18000000,
+                },
+                HalPatchCfg { addr: 0, value: 0 },
+                HalPatchCfg { addr: 0, value: 0 },
+            ],
+        }
+    }
+    /// Use 24-MHz crystal oscillator.
+    #[inline]
+    pub const fn xtal_24m(self) -> Self {
+        self.xtal_type(1)
+    }
+    /// Use 32-MHz crystal oscillator.
+    #[inline]
+    pub const fn xtal_32m(self) -> Self {
+        self.xtal_type(2)
+    }
+    /// Use 38.4-MHz crystal oscillator.
+    #[inline]
+    pub const fn xtal_38p4m(self) -> Self {
+        self.xtal_type(3)
+    }
+    /// Use 40-MHz crystal oscillator.
+    #[inline]
+    pub const fn xtal_40m(self) -> Self {
+        self.xtal_type(4)
+    }
+    /// Use 26-MHz crystal oscillator.
+    #[inline]
+    pub const fn xtal_26m(self) -> Self {
+        self.xtal_type(5)
+    }
+    /// Let boot ROM detect crystal oscillator frequency.
+    #[inline]
+    pub const fn xtal_auto(self) -> Self {
+        self.xtal_type(7)
+    }
+    #[inline]
+    const fn xtal_type(mut self, xtal_type: u8) -> Self {
+        self.clock.xtal_type = xtal_type;
+        self
+    }
+    /// Set flash clock source and divider.
+    #[inline]
+    pub const fn flash_clock(mut self, clock_type: u8, divider: u8) -> Self {
+        self.clock.flash_clk_type = clock_type;
+        self.clock.flash_clk_div = divider;
+        self
+    }
+    /// Use flash configuration for the serial flash part on board.
+    ///
+    /// Built with [`FlashConfigBuilder`](crate::FlashConfigBuilder).
+    #[inline]
+    pub const fn flash(mut self, flash: HalFlashConfig) -> Self {
+        self.flash = flash;
+        self
+    }
+    /// Set miscellaneous image flags.
+    #[inline]
+    pub const fn flags(mut self, flags: u32) -> Self {
+        self.flags = flags;
+        self
+    }
+    /// Boot M0 core from `entry`.
+    #[inline]
+    pub const fn mcu_entry(mut self, entry: u32) -> Self {
+        self.cpu[0] = HalCpuCfg::enabled(entry);
+        self
+    }
+    /// Boot D0 core from `entry`.
+    #[inline]
+    pub const fn dsp_entry(mut self, entry: u32) -> Self {
+        self.cpu[1] = HalCpuCfg::enabled(entry);
+        self
+    }
+    /// Boot LP core from `entry`.
+    #[inline]
+    pub const fn lp_entry(mut self, entry: u32) -> Self {
+        self.cpu[2] = HalCpuCfg::enabled(entry);
+        self
+    }
+    /// Set cache configuration boot ROM applies to M0 core.
+    #[inline]
+    pub const fn mcu_cache(mut self, cache: CacheConfig) -> Self {
+        self.cpu[0] = self.cpu[0].with_cache(cache);
+        self
+    }
+    /// Set cache configuration boot ROM applies to D0 core.
+    #[inline]
+    pub const fn dsp_cache(mut self, cache: CacheConfig) -> Self {
+        self.cpu[1] = self.cpu[1].with_cache(cache);
+        self
+    }
+    /// Set cache configuration boot ROM applies to LP core.
+    #[inline]
+    pub const fn lp_cache(mut self, cache: CacheConfig) -> Self {
+        self.cpu[2] = self.cpu[2].with_cache(cache);
+        self
+    }
+    /// Write `value` to `addr` in patch slot `idx` when reading flash.
+    #[inline]
+    pub const fn patch_on_read(mut self, idx: usize, addr: u32, value: u32) -> Self {
+        self.patch_on_read[idx] = HalPatchCfg { addr, value };
+        self
+    }
+    /// Write `value` to `addr` in patch slot `idx` before jumping to image.
+    #[inline]
+    pub const fn patch_on_jump(mut self, idx: usize, addr: u32, value: u32) -> Self {
+        self.patch_on_jump[idx] = HalPatchCfg { addr, value };
+        self
+    }
+    /// Build the boot header, computing checksums of flash and clock configurations.
+    #[inline]
+    pub const fn build(self) -> BootHeader {
+        BootHeader {
+            flash: self.flash,
+            clock: HalPllConfig::new(self.clock),
+            flags: self.flags,
+            cpu: self.cpu,
+            patch_on_read: self.patch_on_read,
+            patch_on_jump: self.patch_on_jump,
+        }
+    }
+}
+
+/// Place a custom [`BootHeader`] in ROM bootloading header sections.
+///
+/// Requires feature `custom-boot-header`.
+#[cfg(feature = "custom-boot-header")]
+#[macro_export]
+macro_rules! boot_header {
+    ($header:expr) => {
+        const __BOUFFALO_RT_BOOT_HEADER: $crate::soc::bl808::BootHeader = $header;
+        #[cfg_attr(target_os = "none", unsafe(link_section = ".head.flash"))]
+        #[used]
+        static __BOUFFALO_RT_FLASH_CONFIG: $crate::HalFlashConfig = __BOUFFALO_RT_BOOT_HEADER.flash;
+        #[cfg_attr(target_os = "none", unsafe(link_section = ".head.clock"))]
+        #[used]
+        static __BOUFFALO_RT_CLOCK_CONFIG: $crate::soc::bl808::HalPllConfig =
+            __BOUFFALO_RT_BOOT_HEADER.clock;
+        #[cfg_attr(target_os = "none", unsafe(link_section = ".head.base.flag"))]
+        #[used]
+        static __BOUFFALO_RT_BASIC_CONFIG_FLAGS: u32 = __BOUFFALO_RT_BOOT_HEADER.flags;
+        #[cfg_attr(target_os = "none", unsafe(link_section = ".head.cpu"))]
+        #[used]
+        static __BOUFFALO_RT_CPU_CONFIG: [$crate::soc::bl808::HalCpuCfg; 3] =
+            __BOUFFALO_RT_BOOT_HEADER.cpu;
+        #[cfg_attr(target_os = "none", unsafe(link_section = ".head.patch.on-read"))]
+        #[used]
+        static __BOUFFALO_RT_PATCH_ON_READ: [$crate::HalPatchCfg; 4] =
+            __BOUFFALO_RT_BOOT_HEADER.patch_on_read;
+        #[cfg_attr(target_os = "none", unsafe(link_section = ".head.patch.on-jump"))]
+        #[used]
+        static __BOUFFALO_RT_PATCH_ON_JUMP: [$crate::HalPatchCfg; 4] =
+            __BOUFFALO_RT_BOOT_HEADER.patch_on_jump;
+    };
+}
+
+/// Configure UART0 as a blocking serial console on BL808 boards.
+///
+/// Takes GPIO pins and UART signal multiplexers out of peripherals `p`, routes them to
+/// UART0 and returns the result of `BlockingSerial::freerun` with given baudrate. The
+/// short form uses GPIO14 (TXD) and GPIO15 (RXD) on signals 2 and 3, which are wired
+/// to the USB serial bridge on Sipeed M1s Dock and most BL808 boards. Pins other than
+/// default are given with their signal multiplexers, signal number being pin number
+/// modulo 12.
+///
+/// # Examples
+///
+/// ```ignore
+/// let mut serial = uart_console!(p, c, 2000000.Bd()).unwrap();
+/// writeln!(serial, "Hello world!").ok();
+/// ```
+///
+/// ```ignore
+/// let mut serial = uart_console!(p, c, 115200.Bd(), (io16, sig4), (io17, sig5)).unwrap();
+/// ```
+#[macro_export]
+macro_rules! uart_console {
+    ($p:expr, $c:expr, $baudrate:expr) => {
+        $crate::uart_console!($p, $c, $baudrate, (io14, sig2), (io15, sig3))
+    };
+    ($p:expr, $c:expr, $baudrate:expr, ($tx:ident, $tx_sig:ident), ($rx:ident, $rx_sig:ident)) => {{
+        use $crate::__hal::prelude::*;
+        let tx = $p.gpio.$tx.into_uart();
+        let rx = $p.gpio.$rx.into_uart();
+        let tx_sig = $p.uart_muxes.$tx_sig.into_transmit::<0>();
+        let rx_sig = $p.uart_muxes.$rx_sig.into_receive::<0>();
+        let config = $crate::__hal::uart::Config::default().set_baudrate($baudrate);
+        $crate::__hal::uart::BlockingSerial::freerun(
+            $p.uart0,
+            config,
+            ((tx, tx_sig), (rx, rx_sig)),
+            &$c,
+        )
+    }};
+}
 
 /// Full ROM bootloading header.
 #[repr(C)]
@@ -774,6 +1513,26 @@ pub struct HalBootheader {
     crc32: u32,
 }
 
+impl HalBootheader {
+    /// Read boot header from the start of a raw image.
+    #[inline]
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < size_of::<Self>() {
+            return None;
+        }
+        Some(unsafe { core::ptr::read_unaligned(bytes.as_ptr() as *const Self) })
+    }
+    /// Verify magic numbers, flash and clock configuration checksums and whole header
+    /// checksum of this header.
+    #[inline]
+    pub fn verify(&self) -> Result<(), crate::HeaderError> {
+        let bytes = unsafe {
+            core::slice::from_raw_parts(self as *const _ as *const u8, size_of::<Self>())
+        };
+        crate::verify_header(bytes, size_of::<Self>(), size_of::<HalPllConfig>())
+    }
+}
+
 /// Hardware system clock configuration.
 #[repr(C)]
 pub struct HalSysClkConfig {
@@ -858,7 +1617,163 @@ impl HalPllConfig {
     }
 }
 
+/// Cache configuration of a processor core.
+///
+/// Written into the boot header with [`BootHeaderBuilder::mcu_cache`] and friends, or
+/// applied at runtime with [`apply_cache_config`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CacheConfig {
+    flags: u8,
+    start: u32,
+    end: u32,
+}
+
+impl CacheConfig {
+    const ENABLE: u8 = 1 << 0;
+    const WRITE_ALLOCATE: u8 = 1 << 1;
+    const WRITE_BACK: u8 = 1 << 2;
+    const WRITE_THROUGH: u8 = 1 << 3;
+    const WAY_DISABLE: u8 = 0xf << 4;
+
+    /// Cache disabled, with an empty cacheable range.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            flags: 0,
+            start: 0,
+            end: 0,
+        }
+    }
+    #[inline]
+    const fn from_flags(flags: u8) -> Self {
+        Self {
+            flags,
+            start: 0,
+            end: 0,
+        }
+    }
+    #[inline]
+    const fn flags(&self) -> u8 {
+        self.flags
+    }
+    /// Enable cache.
+    #[inline]
+    pub const fn enable(mut self) -> Self {
+        self.flags |= Self::ENABLE;
+        self
+    }
+    /// Check if cache is enabled.
+    #[inline]
+    pub const fn is_enabled(&self) -> bool {
+        self.flags & Self::ENABLE != 0
+    }
+    /// Write back dirty lines on eviction, allocating lines on write miss.
+    #[inline]
+    pub const fn write_back(mut self) -> Self {
+        self.flags = self.flags & !Self::WRITE_THROUGH | Self::WRITE_BACK | Self::WRITE_ALLOCATE;
+        self
+    }
+    /// Check if cache writes back dirty lines.
+    #[inline]
+    pub const fn is_write_back(&self) -> bool {
+        self.flags & Self::WRITE_BACK != 0
+    }
+    /// Write through to memory on every write, without allocating lines.
+    #[inline]
+    pub const fn write_through(mut self) -> Self {
+        self.flags = self.flags & !(Self::WRITE_BACK | Self::WRITE_ALLOCATE) | Self::WRITE_THROUGH;
+        self
+    }
+    /// Check if cache writes through to memory.
+    #[inline]
+    pub const fn is_write_through(&self) -> bool {
+        self.flags & Self::WRITE_THROUGH != 0
+    }
+    /// Check if lines are allocated on write miss.
+    #[inline]
+    pub const fn is_write_allocate(&self) -> bool {
+        self.flags & Self::WRITE_ALLOCATE != 0
+    }
+    /// Disable cache ways in `mask`, one bit per way.
+    #[inline]
+    pub const fn disable_ways(mut self, mask: u8) -> Self {
+        self.flags = self.flags & !Self::WAY_DISABLE | ((mask << 4) & Self::WAY_DISABLE);
+        self
+    }
+    /// Get mask of disabled cache ways.
+    #[inline]
+    pub const fn disabled_ways(&self) -> u8 {
+        (self.flags & Self::WAY_DISABLE) >> 4
+    }
+    /// Set cacheable address range, from `start` inclusive to `end` exclusive.
+    #[inline]
+    pub const fn range(mut self, start: u32, end: u32) -> Self {
+        self.start = start;
+        self.end = end;
+        self
+    }
+    /// Get cacheable address range start.
+    #[inline]
+    pub const fn start(&self) -> u32 {
+        self.start
+    }
+    /// Get cacheable address range end.
+    #[inline]
+    pub const fn end(&self) -> u32 {
+        self.end
+    }
+}
+
+impl Default for CacheConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Apply cache configuration to the current core at runtime.
+///
+/// Sets enable and write policy in `mhcr` as boot ROM does for a boot header with the
+/// same configuration. The cacheable range is fixed by boot ROM before entering
+/// firmware and is not changed here. Dirty lines are written back before the data
+/// cache is disabled, and caches are invalidated before they are enabled.
+///
+/// # Safety
+///
+/// Memory shared with other cores or bus masters must be maintained explicitly under
+/// the new policy, see [`bouffalo_hal::sync`].
+#[inline]
+pub unsafe fn apply_cache_config(cache: CacheConfig) {
+    use bouffalo_hal::arch::xtheadcsr::{self, Mhcr};
+    let mhcr = Mhcr::read();
+    if !cache.is_enabled() {
+        xtheadcsr::dcache_flush_all();
+        xtheadcsr::sync();
+        unsafe { mhcr.disable_dcache().disable_icache().write() };
+        return;
+    }
+    if mhcr.is_dcache_enabled() {
+        xtheadcsr::dcache_flush_all();
+    } else {
+        xtheadcsr::dcache_invalidate_all();
+    }
+    xtheadcsr::icache_invalidate_all();
+    xtheadcsr::sync();
+    let mhcr = if cache.is_write_back() {
+        mhcr.enable_write_back()
+    } else {
+        mhcr.disable_write_back()
+    };
+    let mhcr = if cache.is_write_allocate() {
+        mhcr.enable_write_allocate()
+    } else {
+        mhcr.disable_write_allocate()
+    };
+    unsafe { mhcr.enable_icache().enable_dcache().write() };
+}
+
 /// Processor core configuration in ROM header.
+#[derive(Clone, Copy)]
 #[repr(C)]
 pub struct HalCpuCfg {
     /// Config this cpu.
@@ -881,6 +1796,43 @@ pub struct HalCpuCfg {
 }
 
 impl HalCpuCfg {
+    /// Execute-in-place window of the image booted by this configuration.
+    ///
+    /// Code follows the 4-KiB boot header, as placed by the runtime linker script.
+    #[inline]
+    pub const fn xip_window(&self) -> bouffalo_hal::flash::XipWindow {
+        bouffalo_hal::flash::XipWindow::new(
+            0x5800_0000,
+            self.image_address_offset + 0x1000,
+            32 * 1024 * 1024 - 0x1000,
+        )
+    }
+    /// Cache configuration of this processor core.
+    #[inline]
+    pub const fn cache(&self) -> CacheConfig {
+        CacheConfig::from_flags(self.cache_flags).range(self.cache_range_l, self.cache_range_h)
+    }
+    #[inline]
+    const fn with_cache(mut self, cache: CacheConfig) -> Self {
+        self.cache_flags = cache.flags();
+        self.cache_range_l = cache.start;
+        self.cache_range_h = cache.end;
+        self
+    }
+    #[inline]
+    const fn enabled(boot_entry: u32) -> HalCpuCfg {
+        HalCpuCfg {
+            config_enable: 1,
+            halt_cpu: 0,
+            cache_flags: 0,
+            _rsvd: 0,
+            cache_range_h: 0,
+            cache_range_l: 0,
+            image_address_offset: 0,
+            boot_entry,
+            msp_val: 0,
+        }
+    }
     #[allow(dead_code)]
     #[inline]
     const fn disabled() -> HalCpuCfg {
@@ -916,14 +1868,26 @@ pub struct Peripherals<'a> {
     pub i2c0: I2C0,
     /// Pulse Width Modulation peripheral.
     pub pwm: PWM,
+    /// Timer peripheral.
+    pub timer: TIMER,
+    /// Infrared remote peripheral.
+    pub ir: IR,
     /// Inter-Integrated Circuit bus peripheral 1.
     pub i2c1: I2C1,
     /// Universal Asynchronous Receiver/Transmitter peripheral 2.
     pub uart2: UART2,
+    /// Inter-IC Sound peripheral.
+    pub i2s: I2S,
+    /// Audio Analog-Digital Converter.
+    pub auadc: AUADC,
+    /// Audio Digital-Analog Converter.
+    pub audac: AUDAC,
     /// Hardware LZ4 Decompressor.
     pub lz4d: LZ4D,
     /// Hibernation control peripheral.
     pub hbn: HBN,
+    /// Analog comparators.
+    pub acomp: ACOMP,
     /// Ethernet Media Access Control peripheral.
     pub emac: EMAC,
     /// Universal Asynchronous Receiver/Transmitter peripheral 3.
@@ -936,10 +1900,48 @@ pub struct Peripherals<'a> {
     pub spi1: SPI1,
     /// Platform-local Interrupt Controller.
     pub plic: PLIC,
+    /// Core-local interruptor with machine timer of the current core.
+    pub clint: CLINT,
     /// Multi-media subsystem global peripheral.
     pub mmglb: MMGLB,
+    /// Multi-media subsystem miscellaneous registers.
+    pub mmmisc: MMMISC,
     /// Pseudo Static Random Access Memory controller.
     pub psram: PSRAM,
+    /// Serial flash controller.
+    pub flash: FLASH,
+    /// Digital Video Port capture unit 0.
+    pub dvp0: DVP0,
+    /// Digital Video Port capture unit 1.
+    pub dvp1: DVP1,
+    /// Digital Video Port capture unit 2.
+    pub dvp2: DVP2,
+    /// Digital Video Port capture unit 3.
+    pub dvp3: DVP3,
+    /// Digital Video Port capture unit 4.
+    pub dvp4: DVP4,
+    /// Digital Video Port capture unit 5.
+    pub dvp5: DVP5,
+    /// Digital Video Port capture unit 6.
+    pub dvp6: DVP6,
+    /// Digital Video Port capture unit 7.
+    pub dvp7: DVP7,
+    /// MIPI Camera Serial Interface receiver.
+    pub csi: CSI,
+    /// Display controller with on-screen display layers.
+    pub display: DISPLAY,
+    /// MIPI Display Serial Interface host.
+    pub dsi: DSI,
+    /// Motion JPEG hardware encoder.
+    pub mjpeg: MJPEG,
+    /// H.264 hardware video encoder.
+    pub h264: H264,
+    /// Inter-processor communication mailbox of M0 core.
+    pub ipc0: IPC0,
+    /// Inter-processor communication mailbox of LP core.
+    pub ipc1: IPC1,
+    /// Inter-processor communication mailbox of D0 core.
+    pub ipc2: IPC2,
     /// Secure Digital High Capacity peripheral.
     pub sdh: SDH,
     /// Direct Memory Access peripheral 0.
@@ -948,6 +1950,12 @@ pub struct Peripherals<'a> {
     pub dma1: DMA1,
     /// Direct Memory Access peripheral 2.
     pub dma2: DMA2,
+    /// Wi-Fi Media Access Control.
+    #[cfg(feature = "wifi")]
+    pub wifi_mac: WIFI_MAC,
+    /// Wi-Fi radio frequency front end.
+    #[cfg(feature = "wifi")]
+    pub wifi_rf: WIFI_RF,
 }
 
 soc! {
@@ -963,16 +1971,28 @@ soc! {
     pub struct I2C0 => 0x2000A300, bouffalo_hal::i2c::RegisterBlock;
     /// Pulse Width Modulation peripheral.
     pub struct PWM => 0x2000A400, bouffalo_hal::pwm::RegisterBlock;
+    /// Timer peripheral.
+    pub struct TIMER => 0x2000A500, bouffalo_hal::timer::RegisterBlock;
+    /// Infrared remote peripheral.
+    pub struct IR => 0x2000A600, bouffalo_hal::ir::RegisterBlock;
     /// Inter-Integrated Circuit bus 1 with fixed base address.
     pub struct I2C1 => 0x2000A900, bouffalo_hal::i2c::RegisterBlock;
     /// Universal Asynchronous Receiver/Transmitter 2 with fixed base address.
     pub struct UART2 => 0x2000AA00, bouffalo_hal::uart::RegisterBlock;
+    /// Inter-IC Sound peripheral.
+    pub struct I2S => 0x2000AB00, bouffalo_hal::i2s::RegisterBlock;
+    /// Audio Analog-Digital Converter.
+    pub struct AUADC => 0x2000AC00, bouffalo_hal::audio::auadc::RegisterBlock;
+    /// Audio Digital-Analog Converter.
+    pub struct AUDAC => 0x20055000, bouffalo_hal::audio::audac::RegisterBlock;
     /// Hardware LZ4 Decompressor.
     pub struct LZ4D => 0x2000AD00, bouffalo_hal::lz4d::RegisterBlock;
     /// Direct Memory Access peripheral 0.
     pub struct DMA0 => 0x2000C000, bouffalo_hal::dma::RegisterBlock;
     /// Hibernation control peripheral.
     pub struct HBN => 0x2000F000, bouffalo_hal::hbn::RegisterBlock;
+    /// Analog comparators.
+    pub struct ACOMP => 0x2000F900, bouffalo_hal::acomp::RegisterBlock;
     /// Secure Digital High Capacity peripheral.
     pub struct SDH => 0x20060000, bouffalo_hal::sdio::RegisterBlock;
     /// Ethernet Media Access Control peripheral.
@@ -989,15 +2009,228 @@ soc! {
     pub struct I2C3 => 0x30004000, bouffalo_hal::i2c::RegisterBlock;
     /// Multi-media subsystem global peripheral.
     pub struct MMGLB => 0x30007000, bouffalo_hal::glb::mm::RegisterBlock;
+    /// Multi-media subsystem miscellaneous registers.
+    pub struct MMMISC => 0x30000000, bouffalo_hal::glb::mm::MiscRegisterBlock;
     /// Serial Peripheral Interface peripheral 1.
     pub struct SPI1 => 0x30008000, bouffalo_hal::spi::RegisterBlock;
+    /// Digital Video Port capture unit 0.
+    pub struct DVP0 => 0x30010000, bouffalo_hal::dvp::RegisterBlock;
+    /// Digital Video Port capture unit 1.
+    pub struct DVP1 => 0x30010100, bouffalo_hal::dvp::RegisterBlock;
+    /// Digital Video Port capture unit 2.
+    pub struct DVP2 => 0x30010200, bouffalo_hal::dvp::RegisterBlock;
+    /// Digital Video Port capture unit 3.
+    pub struct DVP3 => 0x30010300, bouffalo_hal::dvp::RegisterBlock;
+    /// Digital Video Port capture unit 4.
+    pub struct DVP4 => 0x30010400, bouffalo_hal::dvp::RegisterBlock;
+    /// Digital Video Port capture unit 5.
+    pub struct DVP5 => 0x30010500, bouffalo_hal::dvp::RegisterBlock;
+    /// Digital Video Port capture unit 6.
+    pub struct DVP6 => 0x30010600, bouffalo_hal::dvp::RegisterBlock;
+    /// Digital Video Port capture unit 7.
+    pub struct DVP7 => 0x30010700, bouffalo_hal::dvp::RegisterBlock;
+    /// MIPI Camera Serial Interface receiver.
+    pub struct CSI => 0x3001A000, bouffalo_hal::mipi_csi::RegisterBlock;
+    /// Display controller with on-screen display layers.
+    pub struct DISPLAY => 0x30013000, bouffalo_hal::display::RegisterBlock;
+    /// MIPI Display Serial Interface host.
+    pub struct DSI => 0x3001A100, bouffalo_hal::display::dsi::RegisterBlock;
+    /// Motion JPEG hardware encoder.
+    pub struct MJPEG => 0x30021000, bouffalo_hal::mjpeg::RegisterBlock;
+    /// H.264 hardware video encoder.
+    pub struct H264 => 0x30020000, bouffalo_hal::h264::RegisterBlock;
+    /// Inter-processor communication mailbox of M0 core.
+    pub struct IPC0 => 0x2000A800, bouffalo_hal::ipc::RegisterBlock;
+    /// Inter-processor communication mailbox of LP core.
+    pub struct IPC1 => 0x2000A840, bouffalo_hal::ipc::RegisterBlock;
+    /// Inter-processor communication mailbox of D0 core.
+    pub struct IPC2 => 0x30005000, bouffalo_hal::ipc::RegisterBlock;
     /// Pseudo Static Random Access Memory controller.
     pub struct PSRAM => 0x3000F000, bouffalo_hal::psram::RegisterBlock;
+    /// Serial flash controller.
+    pub struct FLASH => 0x2000B000, bouffalo_hal::flash::RegisterBlock;
     /// Platform-local Interrupt Controller.
     pub struct PLIC => 0xE0000000, xuantie_riscv::peripheral::plic::Plic;
+    /// Core-local interruptor with machine timer of the current core.
+    pub struct CLINT => CLINT_BASE, bouffalo_hal::clint::RegisterBlock;
+}
+
+#[cfg(feature = "wifi")]
+soc! {
+    /// Wi-Fi Media Access Control.
+    pub struct WIFI_MAC => 0x24B00000, bouffalo_hal::wifi::RegisterBlock;
+    /// Wi-Fi radio frequency front end.
+    pub struct WIFI_RF => 0x20001000, bouffalo_hal::wifi::rf::RegisterBlock;
+}
+
+// C906 places its core-local interruptor after the PLIC, E907 cores map it at the
+// start of the CLIC region.
+#[cfg(feature = "bl808-dsp")]
+const CLINT_BASE: usize = 0xE4000000;
+#[cfg(not(feature = "bl808-dsp"))]
+const CLINT_BASE: usize = 0xE0000000;
+
+#[cfg(feature = "bl808-dsp")]
+pub(crate) const TIME_SOURCE: bouffalo_hal::clint::TimeSource =
+    bouffalo_hal::clint::TimeSource::Csr;
+#[cfg(not(feature = "bl808-dsp"))]
+pub(crate) const TIME_SOURCE: bouffalo_hal::clint::TimeSource =
+    bouffalo_hal::clint::TimeSource::Register;
+
+// Interrupt enable bytes of M0 and LP core CLIC, one byte for each interrupt ID.
+#[cfg(all(not(feature = "bl808-dsp"), any(feature = "embassy", feature = "rtic")))]
+pub(crate) const CLIC_INTIE_BASE: usize = 0xE0800400;
+
+impl CLINT {
+    /// Get machine timer driver of the current core.
+    #[inline]
+    pub fn machine_timer(self) -> bouffalo_hal::clint::MachineTimer<Self> {
+        bouffalo_hal::clint::MachineTimer::new(self, TIME_SOURCE)
+    }
+}
+
+#[cfg(any(feature = "bl808-dsp", feature = "bl808-mcu", feature = "bl808-lp"))]
+impl Peripherals<'static> {
+    /// Take peripherals of the current core, returns `None` if they are already taken.
+    ///
+    /// The `#[entry]` function receives peripherals this way, so later calls return `None`.
+    #[inline]
+    pub fn take() -> Option<Self> {
+        if crate::take_peripherals_once() {
+            Some(unsafe { Self::steal() })
+        } else {
+            None
+        }
+    }
+    /// Get peripherals regardless of whether they are taken.
+    ///
+    /// # Safety
+    ///
+    /// Drivers created from both copies may alias the same registers.
+    #[inline]
+    pub unsafe fn steal() -> Self {
+        Peripherals {
+            glb: GLBv2 { _private: () },
+            gpio: bouffalo_hal::gpio::Pads::__pads_from_glb(&GLBv2 { _private: () }),
+            uart_muxes: bouffalo_hal::uart::UartMuxes::__uart_muxes_from_glb(&GLBv2 {
+                _private: (),
+            }),
+            uart0: UART0 { _private: () },
+            uart1: UART1 { _private: () },
+            spi0: SPI0 { _private: () },
+            i2c0: I2C0 { _private: () },
+            pwm: PWM { _private: () },
+            timer: TIMER { _private: () },
+            ir: IR { _private: () },
+            i2c1: I2C1 { _private: () },
+            uart2: UART2 { _private: () },
+            i2s: I2S { _private: () },
+            auadc: AUADC { _private: () },
+            audac: AUDAC { _private: () },
+            lz4d: LZ4D { _private: () },
+            hbn: HBN { _private: () },
+            acomp: ACOMP { _private: () },
+            emac: EMAC { _private: () },
+            uart3: UART3 { _private: () },
+            i2c2: I2C2 { _private: () },
+            i2c3: I2C3 { _private: () },
+            spi1: SPI1 { _private: () },
+            plic: PLIC { _private: () },
+            clint: CLINT { _private: () },
+            mmglb: MMGLB { _private: () },
+            mmmisc: MMMISC { _private: () },
+            psram: PSRAM { _private: () },
+            flash: FLASH { _private: () },
+            dvp0: DVP0 { _private: () },
+            dvp1: DVP1 { _private: () },
+            dvp2: DVP2 { _private: () },
+            dvp3: DVP3 { _private: () },
+            dvp4: DVP4 { _private: () },
+            dvp5: DVP5 { _private: () },
+            dvp6: DVP6 { _private: () },
+            dvp7: DVP7 { _private: () },
+            csi: CSI { _private: () },
+            display: DISPLAY { _private: () },
+            dsi: DSI { _private: () },
+            mjpeg: MJPEG { _private: () },
+            h264: H264 { _private: () },
+            ipc0: IPC0 { _private: () },
+            ipc1: IPC1 { _private: () },
+            ipc2: IPC2 { _private: () },
+            sdh: SDH { _private: () },
+            dma0: DMA0 { _private: () },
+            dma1: DMA1 { _private: () },
+            dma2: DMA2 { _private: () },
+            #[cfg(feature = "wifi")]
+            wifi_mac: WIFI_MAC { _private: () },
+            #[cfg(feature = "wifi")]
+            wifi_rf: WIFI_RF { _private: () },
+        }
+    }
 }
 
 pub use bouffalo_hal::clocks::Clocks;
+
+impl PLIC {
+    /// Enable interrupt source on D0 core.
+    #[inline]
+    pub fn enable(&self, irq: DspInterrupt) {
+        (**self).enable(irq, D0Machine)
+    }
+    /// Disable interrupt source on D0 core.
+    #[inline]
+    pub fn disable(&self, irq: DspInterrupt) {
+        (**self).disable(irq, D0Machine)
+    }
+    /// Check if interrupt source is enabled on D0 core.
+    #[inline]
+    pub fn is_enabled(&self, irq: DspInterrupt) -> bool {
+        (**self).is_enabled(irq, D0Machine)
+    }
+    /// Check if interrupt source is pending.
+    #[inline]
+    pub fn is_pending(&self, irq: DspInterrupt) -> bool {
+        (**self).is_pending(irq)
+    }
+    /// Set priority of interrupt source, 0 disables the source.
+    #[inline]
+    pub fn set_priority(&self, irq: DspInterrupt, priority: u32) {
+        (**self).set_priority(irq, priority)
+    }
+    /// Get priority of interrupt source.
+    #[inline]
+    pub fn get_priority(&self, irq: DspInterrupt) -> u32 {
+        (**self).get_priority(irq)
+    }
+    /// Set priority threshold of D0 core, sources at or below it are masked.
+    #[inline]
+    pub fn set_threshold(&self, threshold: u32) {
+        (**self).set_threshold(D0Machine, threshold)
+    }
+    /// Get priority threshold of D0 core.
+    #[inline]
+    pub fn get_threshold(&self) -> u32 {
+        (**self).get_threshold(D0Machine)
+    }
+    /// Claim the highest priority pending interrupt of D0 core.
+    ///
+    /// Sources without a [`DspInterrupt`] variant are completed and skipped.
+    #[inline]
+    pub fn claim(&self) -> Option<DspInterrupt> {
+        loop {
+            let id = (**self).claim(D0Machine)?;
+            match DspInterrupt::from_id(id.get()) {
+                Some(irq) => return Some(irq),
+                None => (**self).complete(D0Machine, RawPlicSource(id)),
+            }
+        }
+    }
+    /// Signal completion of a claimed interrupt on D0 core.
+    #[inline]
+    pub fn complete(&self, irq: DspInterrupt) {
+        (**self).complete(D0Machine, irq)
+    }
+}
 use bouffalo_hal::dma::{EightChannels, FourChannels, Periph4Dma01, Periph4Dma2};
 
 dma! {
@@ -1007,42 +2240,14 @@ dma! {
 }
 
 // Used by macros only.
-#[allow(unused)]
+#[cfg(any(feature = "bl808-dsp", feature = "bl808-mcu", feature = "bl808-lp"))]
 #[doc(hidden)]
 #[inline(always)]
 pub fn __rom_init_params(xtal_hz: u32) -> (Peripherals<'static>, Clocks) {
     use embedded_time::rate::Hertz;
-    let peripherals = Peripherals {
-        glb: GLBv2 { _private: () },
-        gpio: match () {
-            #[cfg(any(feature = "bl808-dsp", feature = "bl808-mcu", feature = "bl808-lp"))]
-            () => bouffalo_hal::gpio::Pads::__pads_from_glb(&GLBv2 { _private: () }),
-            #[cfg(not(any(feature = "bl808-dsp", feature = "bl808-mcu", feature = "bl808-lp")))]
-            () => unimplemented!(),
-        },
-        uart_muxes: bouffalo_hal::uart::UartMuxes::__uart_muxes_from_glb(&GLBv2 { _private: () }),
-        uart0: UART0 { _private: () },
-        uart1: UART1 { _private: () },
-        spi0: SPI0 { _private: () },
-        i2c0: I2C0 { _private: () },
-        pwm: PWM { _private: () },
-        i2c1: I2C1 { _private: () },
-        uart2: UART2 { _private: () },
-        lz4d: LZ4D { _private: () },
-        hbn: HBN { _private: () },
-        emac: EMAC { _private: () },
-        uart3: UART3 { _private: () },
-        i2c2: I2C2 { _private: () },
-        i2c3: I2C3 { _private: () },
-        spi1: SPI1 { _private: () },
-        plic: PLIC { _private: () },
-        mmglb: MMGLB { _private: () },
-        psram: PSRAM { _private: () },
-        sdh: SDH { _private: () },
-        dma0: DMA0 { _private: () },
-        dma1: DMA1 { _private: () },
-        dma2: DMA2 { _private: () },
-    };
+    #[cfg(feature = "critical-section-multi-core")]
+    crate::critical_section::init();
+    let peripherals = Peripherals::take().expect("peripherals are taken before entry");
     let clocks = Clocks {
         xtal: Hertz(xtal_hz),
     };
@@ -1051,9 +2256,145 @@ pub fn __rom_init_params(xtal_hz: u32) -> (Peripherals<'static>, Clocks) {
 
 #[cfg(test)]
 mod tests {
-    use super::{HalBootheader, HalCpuCfg, HalPllConfig, HalSysClkConfig};
+    use super::{BootHeader, CacheConfig, HalBootheader, HalCpuCfg, HalPllConfig, HalSysClkConfig};
+    use crate::HeaderError;
     use core::mem::offset_of;
 
+    /// Boot header of a vendor-tool processed BL808 image.
+    const VENDOR_HEADER: [u8; 0x160] = [
+        0x42, 0x46, 0x4e, 0x50, 0x01, 0x00, 0x00, 0x00, 0x46, 0x43, 0x46, 0x47, 0x11, 0x00, 0x01,
+        0x01, 0x66, 0x99, 0xff, 0x03, 0x9f, 0x00, 0xb7, 0xe9, 0x04, 0xff, 0x00, 0x01, 0xc7, 0x20,
+        0x52, 0xd8, 0x06, 0x02, 0x32, 0x00, 0x0b, 0x01, 0x0b, 0x01, 0x3b, 0x01, 0xbb, 0x00, 0x6b,
+        0x01, 0xeb, 0x02, 0xeb, 0x02, 0x02, 0x50, 0x00, 0x01, 0x00, 0x01, 0x01, 0x00, 0x02, 0x01,
+        0x02, 0x01, 0xab, 0x01, 0x05, 0x35, 0x00, 0x00, 0x01, 0x01, 0x00, 0x00, 0x38, 0xff, 0xff,
+        0xf0, 0x77, 0x03, 0x02, 0x40, 0x77, 0x03, 0x02, 0xf0, 0x2c, 0x01, 0xb0, 0x04, 0xb0, 0x04,
+        0x32, 0x00, 0xe8, 0x80, 0x14, 0x00, 0x4f, 0xb1, 0xfe, 0x70, 0x50, 0x43, 0x46, 0x47, 0x07,
+        0x04, 0x00, 0x00, 0x03, 0x01, 0x03, 0x00, 0x01, 0x02, 0x00, 0x02, 0x01, 0x01, 0x00, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x0a, 0x89, 0x4b, 0x86, 0x00, 0x01, 0xcc, 0x72, 0x00, 0x10, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0xa0, 0x00, 0x00, 0x00, 0x20, 0x67, 0xe8, 0xf7, 0xe2, 0x48,
+        0x6c, 0xd7, 0x53, 0xd8, 0xc1, 0x11, 0x69, 0x60, 0xbf, 0x57, 0x04, 0x44, 0x75, 0xd6, 0xba,
+        0xe9, 0xd2, 0x20, 0x21, 0x43, 0x9f, 0x00, 0xf5, 0xcb, 0xb7, 0x4c, 0x01, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x58, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x60, 0x01, 0x00, 0x00, 0xc8, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20, 0x03, 0x00, 0x20,
+        0x00, 0x00, 0x00, 0x00, 0x38, 0xf0, 0x00, 0x20, 0x00, 0x00, 0x00, 0x18, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0xba, 0x13, 0x6e, 0xcb,
+    ];
+
+    #[test]
+    fn hal_bootheader_verify() {
+        let header = HalBootheader::from_bytes(&VENDOR_HEADER).unwrap();
+        assert_eq!(header.verify(), Ok(()));
+        assert!(HalBootheader::from_bytes(&VENDOR_HEADER[..0x100]).is_none());
+
+        let mut bytes = VENDOR_HEADER;
+        bytes[0x10] ^= 0x01;
+        let header = HalBootheader::from_bytes(&bytes).unwrap();
+        assert!(matches!(
+            header.verify(),
+            Err(HeaderError::FlashConfigCrc {
+                stored: 0x70feb14f,
+                ..
+            })
+        ));
+
+        let mut bytes = VENDOR_HEADER;
+        bytes[0x68] = 0x04;
+        let header = HalBootheader::from_bytes(&bytes).unwrap();
+        assert!(matches!(
+            header.verify(),
+            Err(HeaderError::ClockConfigCrc {
+                stored: 0x864b890a,
+                ..
+            })
+        ));
+
+        let mut bytes = VENDOR_HEADER;
+        bytes[0xc3] = 0x59;
+        let header = HalBootheader::from_bytes(&bytes).unwrap();
+        assert!(matches!(
+            header.verify(),
+            Err(HeaderError::HeaderCrc {
+                stored: 0xcb6e13ba,
+                ..
+            })
+        ));
+
+        let mut bytes = VENDOR_HEADER;
+        bytes[0x64] = 0x00;
+        let header = HalBootheader::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            header.verify(),
+            Err(HeaderError::ClockConfigMagic(0x47464300))
+        );
+    }
+
+    #[test]
+    fn boot_header_builder() {
+        let header = BootHeader::builder()
+            .xtal_40m()
+            .mcu_entry(0x58010000)
+            .build();
+        assert_eq!(header.flash.magic, 0x47464346);
+        assert_eq!(header.clock.magic, 0x47464350);
+        assert_eq!(header.clock.cfg.xtal_type, 4);
+        assert_eq!(header.clock.crc32, header.clock.cfg.crc32());
+        assert_eq!(header.cpu[0].config_enable, 1);
+        assert_eq!(header.cpu[0].boot_entry, 0x58010000);
+        assert_eq!(header.flags, 0x654c0100);
+        let window = header.cpu[0].xip_window();
+        assert_eq!(window.to_virtual(0x1000), Some(0x5800_0000));
+        assert_eq!(window.to_physical(0x5800_2000), Some(0x3000));
+    }
+
+    #[test]
+    fn struct_cache_config() {
+        let cache = CacheConfig::new()
+            .enable()
+            .write_back()
+            .disable_ways(0x3)
+            .range(0x5800_0000, 0x5900_0000);
+        assert_eq!(cache.flags(), 0x37);
+        assert!(cache.is_enabled());
+        assert!(cache.is_write_back());
+        assert!(cache.is_write_allocate());
+        assert!(!cache.is_write_through());
+        assert_eq!(cache.disabled_ways(), 0x3);
+        let cache = cache.write_through();
+        assert_eq!(cache.flags(), 0x39);
+        assert!(!cache.is_write_back());
+        assert!(!cache.is_write_allocate());
+
+        let header = BootHeader::builder()
+            .mcu_entry(0x58000000)
+            .mcu_cache(cache)
+            .build();
+        assert_eq!(header.cpu[0].cache_flags, 0x39);
+        assert_eq!(header.cpu[0].cache_range_l, 0x5800_0000);
+        assert_eq!(header.cpu[0].cache_range_h, 0x5900_0000);
+        assert_eq!(header.cpu[0].cache(), cache);
+        assert_eq!(header.cpu[0].config_enable, 1);
+
+        let header = BootHeader::builder()
+            .dsp_entry(0x58000000)
+            .dsp_cache(CacheConfig::new().enable())
+            .lp_cache(cache.range(0x2202_0000, 0x2204_0000))
+            .build();
+        assert_eq!(header.cpu[0].cache_flags, 0);
+        assert_eq!(header.cpu[1].cache_flags, 0x01);
+        assert_eq!(header.cpu[1].boot_entry, 0x58000000);
+        assert_eq!(header.cpu[2].cache_flags, 0x39);
+        assert_eq!(header.cpu[2].cache_range_l, 0x2202_0000);
+        assert_eq!(header.cpu[2].cache_range_h, 0x2204_0000);
+    }
+
     #[test]
     fn struct_lengths() {
         use core::mem::size_of;
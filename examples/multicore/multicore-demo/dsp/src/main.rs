@@ -1,11 +1,24 @@
 #![no_std]
 #![no_main]
 
-use bouffalo_hal::{prelude::*, uart::Config};
-use bouffalo_rt::{Clocks, Peripherals, entry};
+use bouffalo_hal::{
+    ipc::{Channel, Ipc},
+    prelude::*,
+    uart::Config,
+};
+use bouffalo_rt::{
+    Clocks, Peripherals, entry, interrupt,
+    soc::bl808::{DspInterrupt, IPC2},
+};
+use core::sync::atomic::{AtomicU32, Ordering};
 use embedded_time::rate::*;
 use panic_halt as _;
 
+/// Doorbell channel rung by MCU core on every LED toggle.
+const LED_TOGGLED: Channel = Channel::new(0);
+
+static TOGGLES: AtomicU32 = AtomicU32::new(0);
+
 #[entry]
 fn main(p: Peripherals, c: Clocks) -> ! {
     let tx = p.gpio.io14.into_uart();
@@ -17,12 +30,40 @@ fn main(p: Peripherals, c: Clocks) -> ! {
     let config = Config::default().set_baudrate(2000000.Bd());
     let mut serial = p.uart0.freerun(config, pads, &c).unwrap();
 
+    writeln!(
+        serial,
+        "Welcome to bouffalo-hal multicore demo from DSP core🦀!"
+    )
+    .ok();
+
+    let mut ipc = Ipc::new(p.ipc2);
+    ipc.clear(LED_TOGGLED);
+    ipc.enable_channel(LED_TOGGLED);
+    p.plic.set_threshold(0);
+    p.plic.set_priority(DspInterrupt::IpcD0, 1);
+    p.plic.enable(DspInterrupt::IpcD0);
+    unsafe {
+        riscv::register::mie::set_mext();
+        riscv::register::mstatus::set_mie();
+    }
+
+    let mut last = 0;
     loop {
-        writeln!(
-            serial,
-            "Welcome to bouffalo-hal multicore demo from DSP core🦀!"
-        )
-        .ok();
-        riscv::asm::delay(100_000);
+        let toggles = TOGGLES.load(Ordering::Relaxed);
+        if toggles != last {
+            writeln!(serial, "MCU core toggled LED {} times", toggles).ok();
+            last = toggles;
+        }
+        riscv::asm::wfi();
     }
 }
+
+#[interrupt]
+fn ipc_d0() {
+    let mut ipc = Ipc::new(unsafe { &*IPC2::ptr() });
+    ipc.on_interrupt(|channel| {
+        if channel == LED_TOGGLED {
+            TOGGLES.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+}
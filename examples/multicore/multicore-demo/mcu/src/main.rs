@@ -1,17 +1,26 @@
 #![no_std]
 #![no_main]
 
-use bouffalo_hal::prelude::*;
+use bouffalo_hal::{
+    ipc::{Channel, Ipc},
+    prelude::*,
+};
 use bouffalo_rt::{Clocks, Peripherals, entry};
 use panic_halt as _;
 
+/// Doorbell channel rung on every LED toggle.
+const LED_TOGGLED: Channel = Channel::new(0);
+
 #[entry]
 fn main(p: Peripherals, _c: Clocks) -> ! {
     let mut led = p.gpio.io8.into_floating_output();
     let mut led_state = PinState::High;
+    // Mailbox of D0 core, used to signal the DSP.
+    let dsp = Ipc::new(p.ipc2);
     loop {
         led.set_state(led_state).ok();
         led_state = !led_state;
+        dsp.notify(LED_TOGGLED);
         riscv::asm::delay(100_000);
     }
 }
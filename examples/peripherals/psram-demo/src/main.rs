@@ -3,7 +3,7 @@
 
 use core::{arch::asm, ptr};
 
-use bouffalo_hal::{prelude::*, psram::init_psram, uart::Config};
+use bouffalo_hal::{prelude::*, psram, uart::Config};
 use bouffalo_rt::{Clocks, Peripherals, entry};
 use embedded_time::rate::*;
 use panic_halt as _;
@@ -27,40 +27,41 @@ fn main(p: Peripherals, c: Clocks) -> ! {
 
     writeln!(serial, "Welcome to psram-demo🦀!").ok();
 
-    init_psram(&p.psram, &p.glb);
+    let range = psram::init(&p.psram, &p.glb, &c).unwrap();
+    writeln!(serial, "psram range: {:#010X?}", range).ok();
 
-    const MEMORY_SIZE: usize = 64 * 1024 * 1024;
-    const START_ADDRESS: u32 = 0x50000000;
-    const PROGRESS_INTERVAL: usize = MEMORY_SIZE / 4 / 10;
+    let memory_size = range.len();
+    let start_address = range.start as u32;
+    let progress_interval = memory_size / 4 / 10;
     writeln!(serial, "start memory test...").ok();
 
     writeln!(serial, "  write start...").ok();
-    for i in 0..MEMORY_SIZE / 4 {
-        if (i + 1) % PROGRESS_INTERVAL == 0 {
+    for i in 0..memory_size / 4 {
+        if (i + 1) % progress_interval == 0 {
             writeln!(
                 serial,
                 "  write progress: {}%",
-                ((i + 1) * 100) / (MEMORY_SIZE / 4) + 1
+                ((i + 1) * 100) / (memory_size / 4) + 1
             )
             .ok();
         }
-        let addr = START_ADDRESS + (i as u32 * 4);
+        let addr = start_address + (i as u32 * 4);
         write_memory(addr, i as u32);
     }
     writeln!(serial, "  write finish").ok();
 
     writeln!(serial, "  read start...").ok();
     let mut error_cnt = 0;
-    for i in 0..MEMORY_SIZE / 4 {
-        if (i + 1) % PROGRESS_INTERVAL == 0 {
+    for i in 0..memory_size / 4 {
+        if (i + 1) % progress_interval == 0 {
             writeln!(
                 serial,
                 "  read progress: {}%",
-                ((i + 1) * 100) / (MEMORY_SIZE / 4) + 1
+                ((i + 1) * 100) / (memory_size / 4) + 1
             )
             .ok();
         }
-        let addr = START_ADDRESS + (i as u32 * 4);
+        let addr = start_address + (i as u32 * 4);
         let val = read_memory(addr);
         if val != i as u32 {
             error_cnt = error_cnt + 1;
@@ -83,7 +84,7 @@ fn main(p: Peripherals, c: Clocks) -> ! {
             serial,
             "memory test failed, error_cnt: {} ({:.5}%). The first 10 errors are shown above.",
             error_cnt,
-            error_cnt as f64 / (MEMORY_SIZE / 4) as f64
+            error_cnt as f64 / (memory_size / 4) as f64
         )
         .ok();
     }
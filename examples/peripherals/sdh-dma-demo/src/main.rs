@@ -43,10 +43,10 @@ fn main(p: Peripherals, c: Clocks) -> ! {
     writeln!(serial, "Welcome to sdh-dma-demo!").ok();
 
     // Sdh gpio init.
-    let sdh_clk = p.gpio.io0.into_sdh();
-    let sdh_cmd = p.gpio.io1.into_sdh();
-    let sdh_d0 = p.gpio.io2.into_sdh();
-    let sdh_d1 = p.gpio.io3.into_sdh();
+    let sdh_clk = p.gpio.io0.release().into_sdh();
+    let sdh_cmd = p.gpio.io1.release().into_sdh();
+    let sdh_d0 = p.gpio.io2.release().into_sdh();
+    let sdh_d1 = p.gpio.io3.release().into_sdh();
     let sdh_d2 = p.gpio.io4.into_sdh();
     let sdh_d3 = p.gpio.io5.into_sdh();
     let pads = (sdh_clk, sdh_cmd, sdh_d0, sdh_d1, sdh_d2, sdh_d3);
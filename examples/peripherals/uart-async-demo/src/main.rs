@@ -5,10 +5,7 @@ use bouffalo_hal::{
     prelude::*,
     uart::{Config, SerialState},
 };
-use bouffalo_rt::{
-    Clocks, Peripherals, entry, interrupt,
-    soc::bl808::{D0Machine, DspInterrupt},
-};
+use bouffalo_rt::{Clocks, Peripherals, entry, interrupt, soc::bl808::DspInterrupt};
 use embedded_time::rate::*;
 use panic_halt as _;
 
@@ -28,7 +25,7 @@ async fn async_main(p: Peripherals<'_>, c: Clocks) {
         .with_interrupt(config, (tx, rx), &c, &UART3_STATE)
         .unwrap();
     p.plic.set_priority(DspInterrupt::Uart3, 1);
-    p.plic.enable(DspInterrupt::Uart3, D0Machine);
+    p.plic.enable(DspInterrupt::Uart3);
 
     serial.write_all(b"Hello async/await world!\n").await.ok();
 
@@ -57,7 +54,7 @@ fn main(p: Peripherals, c: Clocks) -> ! {
         future::Future,
         task::{Context, Poll, Waker},
     };
-    p.plic.set_threshold(D0Machine, 0);
+    p.plic.set_threshold(0);
     let mut fut = core::pin::pin!(async_main(p, c));
     let waker = Waker::noop();
     let mut ctx = Context::from_waker(waker);
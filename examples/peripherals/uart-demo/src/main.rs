@@ -1,21 +1,14 @@
 #![no_std]
 #![no_main]
 
-use bouffalo_hal::{prelude::*, uart::Config};
-use bouffalo_rt::{Clocks, Peripherals, entry};
+use bouffalo_hal::prelude::*;
+use bouffalo_rt::{Clocks, Peripherals, entry, uart_console};
 use embedded_time::rate::*;
 use panic_halt as _;
 
 #[entry]
 fn main(p: Peripherals, c: Clocks) -> ! {
-    let tx = p.gpio.io14.into_uart();
-    let rx = p.gpio.io15.into_uart();
-    let sig2 = p.uart_muxes.sig2.into_transmit::<0>();
-    let sig3 = p.uart_muxes.sig3.into_receive::<0>();
-    let pads = ((tx, sig2), (rx, sig3));
-
-    let config = Config::default().set_baudrate(2000000.Bd());
-    let mut serial = p.uart0.freerun(config, pads, &c).unwrap();
+    let mut serial = uart_console!(p, c, 2000000.Bd()).unwrap();
 
     let mut led = p.gpio.io8.into_floating_output();
     let mut led_state = PinState::Low;